@@ -0,0 +1,58 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::mime_types::BCS;
+
+use std::convert::Infallible;
+use warp::{http::header::ACCEPT, Filter};
+
+/// `AcceptType` is the response content type requested by the client through
+/// the `Accept` header. JSON is the default when the header is missing or does
+/// not ask for BCS.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AcceptType {
+    Json,
+    Bcs,
+}
+
+impl From<Option<String>> for AcceptType {
+    fn from(accept: Option<String>) -> Self {
+        match accept {
+            Some(accept) if accept.split(',').any(|t| t.trim().starts_with(BCS)) => Self::Bcs,
+            _ => Self::Json,
+        }
+    }
+}
+
+pub fn accept_type() -> impl Filter<Extract = (AcceptType,), Error = Infallible> + Clone {
+    warp::header::headers_cloned().map(|headers: warp::http::HeaderMap| {
+        AcceptType::from(
+            headers
+                .get(ACCEPT)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_owned()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AcceptType;
+
+    #[test]
+    fn test_parse_accept_type() {
+        assert_eq!(AcceptType::from(None), AcceptType::Json);
+        assert_eq!(
+            AcceptType::from(Some("application/json".to_owned())),
+            AcceptType::Json
+        );
+        assert_eq!(
+            AcceptType::from(Some("application/x-bcs".to_owned())),
+            AcceptType::Bcs
+        );
+        assert_eq!(
+            AcceptType::from(Some("text/html, application/x-bcs;q=0.9".to_owned())),
+            AcceptType::Bcs
+        );
+    }
+}
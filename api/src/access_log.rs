@@ -0,0 +1,303 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::Context;
+
+use aptos_config::config::ApiAccessLogConfig;
+use aptos_logger::{info, Schema};
+use bytes::{Buf, Bytes};
+use futures::{Stream, TryStreamExt};
+use hyper::{body::HttpBody, service::Service, Body};
+use rand::Rng;
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+use warp::{
+    filters::path::FullPath,
+    http::{
+        header::{CONTENT_LENGTH, CONTENT_TYPE, UPGRADE, USER_AGENT},
+        HeaderMap, HeaderValue, Method, Request,
+    },
+    reply::Response,
+    Filter, Rejection, Reply,
+};
+
+// The values of these fields are replaced in the logged JSON bodies.
+const REDACTED_FIELDS: &[&str] = &["signature", "signatures"];
+const REDACTED: &str = "<redacted>";
+
+/// Logs a sample of the requests served by `routes` when the access log is configured.
+///
+/// The body of a request can only be read once, so a sampled request is read out and
+/// dispatched again to `routes`, with the remote address of the client in an extension read by
+/// `remote_addr`, as it's lost by the dispatch. The other requests are served by `routes` as
+/// usual.
+pub(crate) fn with_access_log<F>(
+    routes: F,
+    context: Context,
+) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone
+where
+    F: Filter<Error = Infallible> + Clone + Send + Sync + 'static,
+    F::Extract: Reply,
+{
+    let service = warp::service(routes.clone());
+    sampled(context)
+        .and(warp::addr::remote())
+        .and(warp::method())
+        .and(warp::path::full())
+        .and(raw_query())
+        .and(warp::header::headers_cloned())
+        .and(warp::body::stream())
+        .and_then(
+            move |config: ApiAccessLogConfig,
+                  remote_addr: Option<SocketAddr>,
+                  method: Method,
+                  path: FullPath,
+                  query: String,
+                  headers: HeaderMap,
+                  body| {
+                let mut service = service.clone();
+                async move {
+                    let start = Instant::now();
+                    let path_and_query = if query.is_empty() {
+                        path.as_str().to_owned()
+                    } else {
+                        format!("{}?{}", path.as_str(), query)
+                    };
+                    let (body, request_body) =
+                        read_body(body_from_stream(body), &headers, config.max_body_bytes).await;
+
+                    let mut request = Request::builder()
+                        .method(method.clone())
+                        .uri(path_and_query.as_str())
+                        .body(body)
+                        .expect("the request was valid when received");
+                    *request.headers_mut() = headers.clone();
+                    if let Some(remote_addr) = remote_addr {
+                        request.extensions_mut().insert(DispatchedFrom(remote_addr));
+                    }
+                    let response = match service.call(request).await {
+                        Ok(response) => response,
+                        Err(infallible) => match infallible {},
+                    };
+
+                    let (parts, body) = response.into_parts();
+                    let (body, response_body) =
+                        read_body(body, &parts.headers, config.max_body_bytes).await;
+                    info!(AccessLog {
+                        remote_addr,
+                        method: method.as_str(),
+                        path: &path_and_query,
+                        status: parts.status.as_u16(),
+                        user_agent: headers.get(USER_AGENT).and_then(|v| v.to_str().ok()),
+                        elapsed: start.elapsed(),
+                        request_body,
+                        response_body,
+                    });
+                    Ok::<_, Rejection>(Response::from_parts(parts, body))
+                }
+            },
+        )
+        .or(routes)
+}
+
+// The remote address of a request dispatched again by the access log
+#[derive(Clone, Copy)]
+struct DispatchedFrom(SocketAddr);
+
+/// Extracts the remote address of the client, also of the requests dispatched again by the
+/// access log.
+pub(crate) fn remote_addr(
+) -> impl Filter<Extract = (Option<SocketAddr>,), Error = Infallible> + Clone {
+    warp::addr::remote()
+        .and(warp::ext::optional::<DispatchedFrom>())
+        .map(
+            |remote: Option<SocketAddr>, dispatched: Option<DispatchedFrom>| {
+                remote.or_else(|| dispatched.map(|DispatchedFrom(addr)| addr))
+            },
+        )
+}
+
+/// Passes the requests sampled by the access log config, rejects the others.
+/// Upgrade requests (e.g. WebSocket handshakes) are never sampled, because the upgrade
+/// can't be dispatched again.
+fn sampled(
+    context: Context,
+) -> impl Filter<Extract = (ApiAccessLogConfig,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(UPGRADE.as_str())
+        .and(context.filter())
+        .and_then(|upgrade: Option<String>, context: Context| async move {
+            match context.access_log_config() {
+                Some(config) if upgrade.is_none() && sample(config.sample_rate) => {
+                    Ok(config.clone())
+                }
+                _ => Err(warp::reject()),
+            }
+        })
+}
+
+fn sample(rate: f64) -> bool {
+    rand::thread_rng().gen_bool(rate.clamp(0.0, 1.0))
+}
+
+/// Extracts the raw query string, which is empty when the request has no query.
+fn raw_query() -> impl Filter<Extract = (String,), Error = Infallible> + Clone {
+    warp::query::raw().or(warp::any().map(String::new)).unify()
+}
+
+fn body_from_stream<S, B>(stream: S) -> Body
+where
+    S: Stream<Item = Result<B, warp::Error>> + Send + 'static,
+    B: Buf,
+{
+    Body::wrap_stream(stream.map_ok(|mut buf| buf.copy_to_bytes(buf.remaining())))
+}
+
+/// Reads out the body for logging when it is a JSON document no larger than `max_bytes`,
+/// returns the body to send on. The other bodies, including streamed ones, are sent on
+/// untouched.
+async fn read_body(
+    body: Body,
+    headers: &HeaderMap,
+    max_bytes: usize,
+) -> (Body, Option<serde_json::Value>) {
+    let size = body
+        .size_hint()
+        .exact()
+        .or_else(|| content_length(headers.get(CONTENT_LENGTH)));
+    match size {
+        Some(size) if size as usize <= max_bytes && is_json(headers.get(CONTENT_TYPE)) => {}
+        _ => return (body, None),
+    }
+    match hyper::body::to_bytes(body).await {
+        Ok(bytes) => {
+            let logged = serde_json::from_slice(&bytes).ok().map(redact);
+            (Body::from(bytes), logged)
+        }
+        // the body is broken, e.g. the client is disconnected, the error is given to the
+        // routes or the client by the body sent on
+        Err(e) => (
+            Body::wrap_stream(futures::stream::once(async move {
+                Err::<Bytes, hyper::Error>(e)
+            })),
+            None,
+        ),
+    }
+}
+
+fn content_length(value: Option<&HeaderValue>) -> Option<u64> {
+    value?.to_str().ok()?.parse().ok()
+}
+
+fn is_json(value: Option<&HeaderValue>) -> bool {
+    value
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.starts_with("application/json"))
+        .unwrap_or(false)
+}
+
+/// Replaces the values of the signature fields, wherever they are in the document.
+fn redact(mut value: serde_json::Value) -> serde_json::Value {
+    match &mut value {
+        serde_json::Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                *field = if REDACTED_FIELDS.contains(&key.as_str()) {
+                    serde_json::Value::String(REDACTED.to_owned())
+                } else {
+                    redact(field.take())
+                };
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for v in values.iter_mut() {
+                *v = redact(v.take());
+            }
+        }
+        _ => {}
+    }
+    value
+}
+
+#[derive(Schema)]
+struct AccessLog<'a> {
+    #[schema(display)]
+    remote_addr: Option<SocketAddr>,
+    method: &'a str,
+    path: &'a str,
+    status: u16,
+    user_agent: Option<&'a str>,
+    #[schema(debug)]
+    elapsed: Duration,
+    request_body: Option<serde_json::Value>,
+    response_body: Option<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_json, read_body, redact};
+    use hyper::Body;
+    use serde_json::json;
+    use warp::http::{header::CONTENT_TYPE, HeaderMap, HeaderValue};
+
+    #[test]
+    fn test_redact_signatures() {
+        let body = json!([{
+            "sender": "0x1",
+            "signature": {
+                "type": "multi_agent_signature",
+                "sender": {
+                    "type": "ed25519_signature",
+                    "public_key": "0x12",
+                    "signature": "0x34",
+                },
+                "secondary_signers": [{
+                    "type": "multi_ed25519_signature",
+                    "public_keys": ["0x56"],
+                    "signatures": ["0x78"],
+                }],
+            },
+        }]);
+        assert_eq!(
+            redact(body),
+            json!([{"sender": "0x1", "signature": "<redacted>"}])
+        );
+
+        let body = json!({
+            "secondary_signers": [{"public_keys": ["0x56"], "signatures": ["0x78"]}],
+        });
+        assert_eq!(
+            redact(body),
+            json!({
+                "secondary_signers": [{"public_keys": ["0x56"], "signatures": "<redacted>"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_json() {
+        assert!(is_json(Some(&HeaderValue::from_static("application/json"))));
+        assert!(is_json(Some(&HeaderValue::from_static(
+            "application/json; charset=utf-8"
+        ))));
+        assert!(!is_json(Some(&HeaderValue::from_static(
+            "application/x.aptos.signed_transaction+bcs"
+        ))));
+        assert!(!is_json(None));
+    }
+
+    #[tokio::test]
+    async fn test_read_body() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let (body, logged) = read_body(Body::from(r#"{"a":1}"#), &headers, 1024).await;
+        assert_eq!(logged, Some(json!({"a": 1})));
+        assert_eq!(hyper::body::to_bytes(body).await.unwrap(), r#"{"a":1}"#);
+
+        let (body, logged) = read_body(Body::from(r#"{"a":1}"#), &headers, 4).await;
+        assert_eq!(logged, None);
+        assert_eq!(hyper::body::to_bytes(body).await.unwrap(), r#"{"a":1}"#);
+    }
+}
@@ -2,35 +2,47 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    accept_type::{accept_type, AcceptType},
+    api_key::api_key,
+    bytes_encoding::BytesEncodingQuery,
     context::Context,
     failpoint::fail_point,
+    head::get_or_head,
     metrics::metrics,
+    page::ResourcePage,
     param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam},
+    resource_filter::{ResourceFilter, ResourceFilterQuery},
     version::Version,
 };
 
 use aptos_api_types::{
-    AccountData, Address, AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response,
-    TransactionId,
+    AccountBalance, AccountData, Address, AptosErrorCode, AsConverter, BytesEncoding, Error,
+    LedgerInfo, MoveModuleBytecode, MoveResource, Response, TransactionId,
 };
 use aptos_types::{
-    account_config::AccountResource,
+    access_path::AccessPath,
+    account_config::{AccountResource, BalanceResource},
     account_state::AccountState,
+    account_state_blob::AccountStateBlob,
     event::{EventHandle, EventKey},
 };
 
 use anyhow::Result;
+use aptos_crypto::{hash::CryptoHash, HashValue};
 use move_core_types::{
-    identifier::Identifier, language_storage::StructTag, move_resource::MoveStructType,
+    ident_str, identifier::Identifier, language_storage::StructTag, move_resource::MoveStructType,
     value::MoveValue,
 };
-use std::convert::TryInto;
-use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+use std::convert::TryFrom;
+use warp::{filters::BoxedFilter, http::header::IF_NONE_MATCH, Filter, Rejection, Reply};
 
 // GET /accounts/<address>
 pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam)
-        .and(warp::get())
+        .and(get_or_head())
+        .and(accept_type())
+        .and(if_none_match())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_get_account)
         .with(metrics("get_account"))
@@ -40,30 +52,73 @@ pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
 // GET /accounts/<address>
 pub fn get_account_state_blob(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "blob")
-        .and(warp::get())
+        .and(get_or_head())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_get_account_state_blob)
         .with(metrics("get_account_state_blob"))
         .boxed()
 }
 
-// GET /accounts/<address>/resources
+// GET /accounts/<address>/resources?start={struct_tag}&limit={u16}&bytes_encoding={encoding}
+//     &filter={struct_tag_prefix}
 pub fn get_account_resources(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "resources")
-        .and(warp::get())
+        .and(get_or_head())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .and(warp::query::<ResourcePage>())
+        .and(warp::query::<ResourceFilterQuery>())
+        .and(warp::query::<BytesEncodingQuery>())
+        .and(accept_type())
+        .and(if_none_match())
+        .map(
+            |address,
+             ctx,
+             version: Version,
+             page,
+             filter,
+             bytes_encoding,
+             accept_type,
+             if_none_match| {
+                (
+                    version.version,
+                    address,
+                    page,
+                    filter,
+                    bytes_encoding,
+                    accept_type,
+                    if_none_match,
+                    ctx,
+                )
+            },
+        )
         .untuple_one()
         .and_then(handle_get_account_resources)
         .with(metrics("get_account_resources"))
         .boxed()
 }
 
+// GET /accounts/<address>/balance/<coin_type>
+pub fn get_account_balance(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "balance" / MoveStructTagParam)
+        .and(get_or_head())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|address, coin_type, ctx, version: Version| (version.version, address, coin_type, ctx))
+        .untuple_one()
+        .and_then(handle_get_account_balance)
+        .with(metrics("get_account_balance"))
+        .boxed()
+}
+
 // GET /accounts/<address>/modules
 pub fn get_account_modules(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "modules")
-        .and(warp::get())
+        .and(get_or_head())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and(warp::query::<Version>())
         .map(|address, ctx, version: Version| (version.version, address, ctx))
@@ -73,12 +128,18 @@ pub fn get_account_modules(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+fn if_none_match() -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(IF_NONE_MATCH.as_str())
+}
+
 async fn handle_get_account(
     address: AddressParam,
+    accept_type: AcceptType,
+    if_none_match: Option<String>,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account")?;
-    Ok(Account::new(None, address, context)?.account()?)
+    Ok(Account::new(None, address, context)?.account(accept_type, if_none_match)?)
 }
 
 async fn handle_get_account_state_blob(
@@ -92,10 +153,33 @@ async fn handle_get_account_state_blob(
 async fn handle_get_account_resources(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
+    page: ResourcePage,
+    filter: ResourceFilterQuery,
+    bytes_encoding: BytesEncodingQuery,
+    accept_type: AcceptType,
+    if_none_match: Option<String>,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_resources")?;
-    Ok(Account::new(ledger_version, address, context)?.resources()?)
+    let filter = filter.parse()?;
+    let bytes_encoding = bytes_encoding.parse()?;
+    Ok(Account::new(ledger_version, address, context)?.resources(
+        page,
+        filter,
+        bytes_encoding,
+        accept_type,
+        if_none_match,
+    )?)
+}
+
+async fn handle_get_account_balance(
+    ledger_version: Option<LedgerVersionParam>,
+    address: AddressParam,
+    coin_type: MoveStructTagParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_balance")?;
+    Ok(Account::new(ledger_version, address, context)?.balance(coin_type)?)
 }
 
 async fn handle_get_account_modules(
@@ -127,46 +211,161 @@ impl Account {
 
         if ledger_version > latest_ledger_info.version() {
             return Err(Error::not_found(
+                AptosErrorCode::VersionNotFound,
                 "ledger",
                 TransactionId::Version(ledger_version),
                 latest_ledger_info.version(),
             ));
         }
+        context.ensure_state_not_pruned(ledger_version, &latest_ledger_info)?;
 
         Ok(Self {
             ledger_version,
-            address: address.parse("account address")?,
+            address: context.resolve_address(address)?,
             latest_ledger_info,
             context,
         })
     }
 
-    pub fn account(self) -> Result<impl Reply, Error> {
-        let account_state = self.account_state()?;
-        let account: AccountData = account_state
-            .get_account_resource()?
-            .ok_or_else(|| self.resource_not_found(&AccountResource::struct_tag()))?
-            .into();
+    /// Renders the account resource; responds `304 Not Modified` when `if_none_match`
+    /// matches the ETag of the account state and `accept_type`.
+    pub fn account(
+        self,
+        accept_type: AcceptType,
+        if_none_match: Option<String>,
+    ) -> Result<Response, Error> {
+        let blob = self.account_state_blob_at_version()?;
+        let etag = account_state_etag(&blob, &format!("{:?}", accept_type));
+        if matches_etag(if_none_match.as_deref(), &etag) {
+            return Ok(Response::not_modified(self.latest_ledger_info, etag));
+        }
 
-        Response::new(self.latest_ledger_info, &account)
+        let account = self.account_data(&AccountState::try_from(&blob)?)?;
+        Ok(Response::new(self.latest_ledger_info, &account)?.with_etag(etag))
     }
 
-    pub fn account_state_blob(self) -> Result<impl Reply, Error> {
-        let blob: Vec<u8> = self
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub fn ledger_version(&self) -> u64 {
+        self.ledger_version
+    }
+
+    /// Returns the account resource at the ledger version.
+    pub fn data(&self) -> Result<AccountData, Error> {
+        self.account_data(&self.account_state()?)
+    }
+
+    /// Returns all the resources of the account matching `filter` when it is given.
+    pub fn move_resources(
+        &self,
+        filter: Option<&ResourceFilter>,
+        bytes_encoding: BytesEncoding,
+    ) -> Result<Vec<MoveResource>, Error> {
+        let account_state = self.account_state()?;
+        let resources = account_state
+            .get_resources()
+            .filter(|(tag, _)| filter.map(|f| f.matches(tag)).unwrap_or(true));
+        Ok(self
             .context
-            .get_account_state_blob(self.address.into(), self.ledger_version)?
-            .ok_or_else(|| self.account_not_found())?
-            .into();
+            .move_resolver()?
+            .as_converter()
+            .with_bytes_encoding(bytes_encoding)
+            .try_into_resources(resources)?)
+    }
+
+    pub fn account_state_blob(self) -> Result<impl Reply, Error> {
+        let blob: Vec<u8> = self.account_state_blob_at_version()?.into();
         Response::new(self.latest_ledger_info, &blob)
     }
 
-    pub fn resources(self) -> Result<impl Reply, Error> {
+    /// Renders a page of the account resources, only the resources matching `filter` when
+    /// it is given; responds `304 Not Modified` when `if_none_match` matches the ETag of the
+    /// account state and the parsed query parameters and `accept_type`.
+    pub fn resources(
+        self,
+        page: ResourcePage,
+        filter: Option<ResourceFilter>,
+        bytes_encoding: BytesEncoding,
+        accept_type: AcceptType,
+        if_none_match: Option<String>,
+    ) -> Result<Response, Error> {
+        let start = page.start()?;
+        let limit = page.limit()?;
+        let blob = self.account_state_blob_at_version()?;
+        let etag = account_state_etag(
+            &blob,
+            &format!(
+                "{:?}&{:?}&{:?}&{:?}&{:?}",
+                start.as_ref().map(|tag| tag.to_string()),
+                limit,
+                filter,
+                bytes_encoding,
+                accept_type,
+            ),
+        );
+        if matches_etag(if_none_match.as_deref(), &etag) {
+            return Ok(Response::not_modified(self.latest_ledger_info, etag));
+        }
+
+        let account_state = AccountState::try_from(&blob)?;
+        // resources are ordered by their access path in the account state, so the cursor
+        // is compared by access path too.
+        let start = start.map(AccessPath::resource_access_vec);
+        let mut resources = account_state
+            .get_resources()
+            .filter(|(tag, _)| filter.as_ref().map(|f| f.matches(tag)).unwrap_or(true))
+            .skip_while(|(tag, _)| {
+                start
+                    .as_ref()
+                    .map(|s| &AccessPath::resource_access_vec(tag.clone()) < s)
+                    .unwrap_or(false)
+            });
+
+        let (page_resources, cursor) = match limit {
+            None => (resources.collect::<Vec<_>>(), None),
+            Some(limit) => {
+                let page_resources = resources.by_ref().take(limit as usize).collect::<Vec<_>>();
+                let cursor = resources.next().map(|(tag, _)| tag.to_string());
+                (page_resources, cursor)
+            }
+        };
+
         let resources = self
             .context
             .move_resolver()?
             .as_converter()
-            .try_into_resources(self.account_state()?.get_resources())?;
-        Response::new(self.latest_ledger_info, &resources)
+            .with_bytes_encoding(bytes_encoding)
+            .try_into_resources(page_resources.into_iter())?;
+        Ok(Response::new(self.latest_ledger_info, &resources)?
+            .with_cursor(cursor)
+            .with_etag(etag))
+    }
+
+    /// Renders the balance of the coin held by the account. `0x1::TestCoin::Coin`, whose
+    /// balance is held in the `0x1::TestCoin::Balance` resource, is the only coin of the
+    /// framework.
+    pub fn balance(self, coin_type: MoveStructTagParam) -> Result<impl Reply, Error> {
+        let coin_type = coin_type.parse_struct_tag("coin_type")?;
+        let balance_type = BalanceResource::struct_tag();
+        let test_coin_type = StructTag {
+            name: ident_str!("Coin").to_owned(),
+            ..balance_type.clone()
+        };
+        if coin_type != test_coin_type {
+            return Err(Error::invalid_param("coin_type", coin_type));
+        }
+
+        let balance = self
+            .account_state()?
+            .get_balance_resources()?
+            .ok_or_else(|| self.resource_not_found(&balance_type))?;
+        let balance = AccountBalance {
+            coin_type: coin_type.into(),
+            value: balance.coin().into(),
+        };
+        Response::new(self.latest_ledger_info, &balance)
     }
 
     pub fn modules(self) -> Result<impl Reply, Error> {
@@ -184,7 +383,7 @@ impl Account {
         struct_tag_param: MoveStructTagParam,
         field_name_param: MoveIdentifierParam,
     ) -> Result<EventKey, Error> {
-        let struct_tag: StructTag = struct_tag_param.parse("event handle struct")?.try_into()?;
+        let struct_tag = struct_tag_param.parse_struct_tag("event_handle_struct")?;
         let field_name = field_name_param.parse("event handle field name")?;
 
         let resource = self.find_resource(&struct_tag)?;
@@ -198,10 +397,13 @@ impl Account {
         let event_handle_bytes = bcs::to_bytes(&value).map_err(anyhow::Error::from)?;
         // deserialization may fail because the bytes are not EventHandle struct type.
         let event_handle: EventHandle = bcs::from_bytes(&event_handle_bytes).map_err(|e| {
-            Error::bad_request(format!(
-                "field({}) type is not EventHandle struct, deserialize error: {}",
-                field_name, e
-            ))
+            Error::bad_request(
+                AptosErrorCode::InvalidParameter,
+                format!(
+                    "field({}) type is not EventHandle struct, deserialize error: {}",
+                    field_name, e
+                ),
+            )
         })?;
         Ok(*event_handle.key())
     }
@@ -222,6 +424,28 @@ impl Account {
             .move_struct_fields(&typ, data)?)
     }
 
+    fn account_state_blob_at_version(&self) -> Result<AccountStateBlob, Error> {
+        let blob = self
+            .context
+            .get_account_state_blob(self.address.into(), self.ledger_version)?
+            .ok_or_else(|| self.account_not_found())?;
+        Ok(blob)
+    }
+
+    fn account_data(&self, account_state: &AccountState) -> Result<AccountData, Error> {
+        let mut data: AccountData = account_state
+            .get_account_resource()?
+            .ok_or_else(|| self.resource_not_found(&AccountResource::struct_tag()))?
+            .into();
+        let history = self
+            .context
+            .get_account_history(self.address.into(), self.ledger_version)?;
+        data.creation_version = history.creation_version.map(Into::into);
+        data.authentication_key_rotations = history.authentication_key_rotations.into();
+        data.last_transaction_version = history.last_transaction_version.map(Into::into);
+        Ok(data)
+    }
+
     fn account_state(&self) -> Result<AccountState, Error> {
         let state = self
             .context
@@ -232,6 +456,7 @@ impl Account {
 
     fn account_not_found(&self) -> Error {
         Error::not_found(
+            AptosErrorCode::AccountNotFound,
             "account",
             format!(
                 "address({}) and ledger version({})",
@@ -243,6 +468,7 @@ impl Account {
 
     fn resource_not_found(&self, struct_tag: &StructTag) -> Error {
         Error::not_found(
+            AptosErrorCode::ResourceNotFound,
             "resource",
             format!(
                 "address({}), struct tag({}) and ledger version({})",
@@ -254,6 +480,7 @@ impl Account {
 
     fn field_not_found(&self, struct_tag: &StructTag, field_name: &Identifier) -> Error {
         Error::not_found(
+            AptosErrorCode::ResourceNotFound,
             "resource",
             format!(
                 "address({}), struct tag({}), field name({}) and ledger version({})",
@@ -263,3 +490,27 @@ impl Account {
         )
     }
 }
+
+/// The account state blob changes only when the account state changes, so its hash, together
+/// with the `params` the response is rendered by, is used as a weak ETag of the responses
+/// rendered from the account state. The params are parsed before, so that the equivalent
+/// queries, e.g. `0x1::` and `0x00000000000000000000000000000001::`, share the ETag.
+fn account_state_etag(blob: &AccountStateBlob, params: &str) -> String {
+    let mut bytes = blob.hash().to_vec();
+    bytes.extend_from_slice(params.as_bytes());
+    format!("W/\"{}\"", HashValue::sha3_256_of(&bytes))
+}
+
+/// Returns true if any entity tag in the `If-None-Match` header value matches the `etag`
+/// by the weak comparison, i.e. the weak indicators `W/` are ignored.
+fn matches_etag(if_none_match: Option<&str>, etag: &str) -> bool {
+    let strip_weak = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+    let etag = strip_weak(etag);
+    if_none_match
+        .map(|header| {
+            header
+                .split(',')
+                .any(|tag| tag.trim() == "*" || strip_weak(tag) == etag)
+        })
+        .unwrap_or(false)
+}
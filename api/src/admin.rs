@@ -0,0 +1,426 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{api_key, context::Context, metrics::metrics};
+
+use aptos_api_types::{AptosErrorCode, Error, Response};
+use aptos_types::{download_stats::DownloadStats, liveness::LivenessSnapshot};
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path},
+};
+use warp::{filters::BoxedFilter, http::StatusCode, reject, Filter, Rejection, Reply};
+
+/// Request header carrying the shared secret of the admin routes, it must match the
+/// `admin_token` of the API config.
+pub const X_APTOS_ADMIN_TOKEN: &str = "X-Aptos-Admin-Token";
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct Failpoint {
+    name: String,
+    // the actions in the syntax of the `fail` crate, e.g. `return` or `50%return`; `off`
+    // disables the failpoint.
+    actions: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct CheckpointRequest {
+    // the name of the dir to create the checkpoint in, in the `checkpoint_dir` of the API
+    // config; it must not exist.
+    name: String,
+}
+
+// The downloads of state sync since the node started, read from its download throttle. The
+// throughput is measured each time the streams are checked for progress.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+struct StateSyncDownloads {
+    throughput_bytes_per_sec: u64,
+    // `None` if the downloads are not throttled.
+    rate_limit_bytes_per_sec: Option<u64>,
+    downloaded_bytes: u64,
+    throttled_data_requests: u64,
+}
+
+impl From<DownloadStats> for StateSyncDownloads {
+    fn from(stats: DownloadStats) -> Self {
+        Self {
+            throughput_bytes_per_sec: stats.throughput_bytes_per_sec,
+            rate_limit_bytes_per_sec: stats.rate_limit_bytes_per_sec,
+            downloaded_bytes: stats.downloaded_bytes,
+            throttled_data_requests: stats.throttled_data_requests,
+        }
+    }
+}
+
+// The liveness of consensus, read from its diagnostics, to tell why the rounds stall. The rounds
+// are zero till consensus has started its first round.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+struct ConsensusDiagnostics {
+    current_round: u64,
+    last_committed_round: u64,
+    // the count of the validators whose votes for the current round are pending a QC or a TC.
+    pending_votes: u64,
+    // the count of the timeout votes received from each validator since the node started.
+    timeout_votes: BTreeMap<String, u64>,
+    // the count of the ordered blocks waiting to be executed, signed or committed.
+    block_queue_depth: u64,
+}
+
+impl From<LivenessSnapshot> for ConsensusDiagnostics {
+    fn from(snapshot: LivenessSnapshot) -> Self {
+        Self {
+            current_round: snapshot.current_round,
+            last_committed_round: snapshot.last_committed_round,
+            pending_votes: snapshot.pending_votes as u64,
+            timeout_votes: snapshot
+                .timeout_votes
+                .into_iter()
+                .map(|(author, count)| (author.to_string(), count))
+                .collect(),
+            block_queue_depth: snapshot.pending_blocks as u64,
+        }
+    }
+}
+
+// GET /admin/failpoints
+pub fn list_failpoints(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("admin" / "failpoints")
+        .and(warp::get())
+        .and(admin_auth(context.clone()))
+        .and(context.filter())
+        .and_then(handle_list_failpoints)
+        .with(metrics("list_failpoints"))
+        .boxed()
+}
+
+// POST /admin/failpoints
+pub fn set_failpoint(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("admin" / "failpoints")
+        .and(warp::post())
+        .and(admin_auth(context.clone()))
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<Failpoint>())
+        .and(context.filter())
+        .and_then(handle_set_failpoint)
+        .with(metrics("set_failpoint"))
+        .boxed()
+}
+
+// GET /admin/usage
+pub fn get_usage(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("admin" / "usage")
+        .and(warp::get())
+        .and(admin_auth(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_usage)
+        .with(metrics("get_usage"))
+        .boxed()
+}
+
+// GET /admin/storage
+pub fn get_storage(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("admin" / "storage")
+        .and(warp::get())
+        .and(admin_auth(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_storage)
+        .with(metrics("get_storage"))
+        .boxed()
+}
+
+// GET /admin/state_sync
+pub fn get_state_sync(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("admin" / "state_sync")
+        .and(warp::get())
+        .and(admin_auth(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_state_sync)
+        .with(metrics("get_state_sync"))
+        .boxed()
+}
+
+// GET /admin/consensus
+pub fn get_consensus(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("admin" / "consensus")
+        .and(warp::get())
+        .and(admin_auth(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_consensus)
+        .with(metrics("get_consensus"))
+        .boxed()
+}
+
+// GET /admin/execution_profile
+pub fn get_execution_profile(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("admin" / "execution_profile")
+        .and(warp::get())
+        .and(admin_auth(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_execution_profile)
+        .with(metrics("get_execution_profile"))
+        .boxed()
+}
+
+// GET /admin/native_trace
+pub fn get_native_trace(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("admin" / "native_trace")
+        .and(warp::get())
+        .and(admin_auth(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_native_trace)
+        .with(metrics("get_native_trace"))
+        .boxed()
+}
+
+// GET /admin/gas_schedule
+pub fn get_gas_schedule(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("admin" / "gas_schedule")
+        .and(warp::get())
+        .and(admin_auth(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_gas_schedule)
+        .with(metrics("get_gas_schedule"))
+        .boxed()
+}
+
+// POST /admin/checkpoint
+pub fn create_checkpoint(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("admin" / "checkpoint")
+        .and(warp::post())
+        .and(admin_auth(context.clone()))
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<CheckpointRequest>())
+        .and(context.filter())
+        .and_then(handle_create_checkpoint)
+        .with(metrics("create_checkpoint"))
+        .boxed()
+}
+
+async fn handle_list_failpoints(context: Context) -> Result<impl Reply, Rejection> {
+    ensure_failpoints_enabled()?;
+    let failpoints: Vec<Failpoint> = fail::list()
+        .into_iter()
+        .map(|(name, actions)| Failpoint { name, actions })
+        .collect();
+    Ok(Response::new(
+        context.get_latest_ledger_info()?,
+        &failpoints,
+    )?)
+}
+
+async fn handle_set_failpoint(
+    failpoint: Failpoint,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    ensure_failpoints_enabled()?;
+    fail::cfg(failpoint.name.as_str(), &failpoint.actions).map_err(|e| {
+        Error::invalid_request_body(format!(
+            "invalid actions of failpoint {}: {}",
+            failpoint.name, e
+        ))
+    })?;
+    Ok(Response::new(
+        context.get_latest_ledger_info()?,
+        &failpoint,
+    )?)
+}
+
+// The usage of the API keys, empty when API keys are not configured.
+async fn handle_get_usage(context: Context) -> Result<impl Reply, Rejection> {
+    let usage = context
+        .api_keys()
+        .map(|api_keys| api_keys.usage(api_key::unix_secs()))
+        .unwrap_or_default();
+    Ok(Response::new(context.get_latest_ledger_info()?, &usage)?)
+}
+
+// The disk usage of the column families of the DB, the least readable version of each pruner
+// and the latest version.
+async fn handle_get_storage(context: Context) -> Result<impl Reply, Rejection> {
+    let usage = context.get_storage_usage().map_err(Error::from)?;
+    Ok(Response::new(context.get_latest_ledger_info()?, &usage)?)
+}
+
+async fn handle_get_state_sync(context: Context) -> Result<impl Reply, Rejection> {
+    let downloads = match context.download_throttle() {
+        Some(download_throttle) => StateSyncDownloads::from(download_throttle.download_stats()),
+        None => return Err(reject::not_found()),
+    };
+    Ok(Response::new(
+        context.get_latest_ledger_info()?,
+        &downloads,
+    )?)
+}
+
+// Only the validators run consensus, the fullnodes have no diagnostics.
+async fn handle_get_consensus(context: Context) -> Result<impl Reply, Rejection> {
+    let diagnostics = match context.liveness_diagnostics() {
+        Some(liveness_diagnostics) => {
+            ConsensusDiagnostics::from(liveness_diagnostics.liveness_snapshot())
+        }
+        None => return Err(reject::not_found()),
+    };
+    Ok(Response::new(
+        context.get_latest_ledger_info()?,
+        &diagnostics,
+    )?)
+}
+
+// The profiles of the last user transactions executed, in the order they were executed.
+async fn handle_get_execution_profile(context: Context) -> Result<impl Reply, Rejection> {
+    ensure_execution_profiling_enabled()?;
+    let profiles = aptos_vm::profiling::transaction_profiles();
+    Ok(Response::new(context.get_latest_ledger_info()?, &profiles)?)
+}
+
+// The native calls of the last user transactions executed, in the order they were executed.
+async fn handle_get_native_trace(context: Context) -> Result<impl Reply, Rejection> {
+    ensure_native_tracing_enabled()?;
+    let traces = aptos_vm::native_tracing::native_traces();
+    Ok(Response::new(context.get_latest_ledger_info()?, &traces)?)
+}
+
+// The gas schedule the node executes the blocks with. Till the node executed a block, e.g. while
+// it's syncing, it's the one of the latest committed state, which the first block will load.
+async fn handle_get_gas_schedule(context: Context) -> Result<impl Reply, Rejection> {
+    let ledger_info = context.get_latest_ledger_info()?;
+    let gas_schedule = match aptos_vm::gas_schedule::active_gas_schedule() {
+        Some(gas_schedule) => Some(gas_schedule),
+        None => {
+            let state_view = context
+                .state_view_at_version(ledger_info.version())
+                .map_err(Error::internal)?;
+            aptos_vm::gas_schedule::gas_schedule_of(&state_view)
+        }
+    };
+    Ok(Response::new(ledger_info, &gas_schedule)?)
+}
+
+// The manifest of the checkpoint of the DB created at the latest committed version.
+async fn handle_create_checkpoint(
+    request: CheckpointRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    let (checkpoint_dir, db_checkpointer) =
+        match (context.checkpoint_dir(), context.db_checkpointer()) {
+            (Some(dir), Some(db_checkpointer)) => {
+                (dir.join(checkpoint_name(&request.name)?), db_checkpointer)
+            }
+            _ => return Err(reject::not_found()),
+        };
+    // the checkpoint waits for the pending commits and writes to disk
+    let manifest =
+        tokio::task::spawn_blocking(move || db_checkpointer.create_db_checkpoint(checkpoint_dir))
+            .await
+            .map_err(|e| Error::internal(e.into()))?
+            .map_err(|e| Error::internal(e.context("failed to create checkpoint")))?;
+    Ok(Response::new(context.get_latest_ledger_info()?, &manifest)?)
+}
+
+// The name of a checkpoint is a single component of a path, so that the checkpoints are only
+// created in the configured dir.
+fn checkpoint_name(name: &str) -> Result<&Path, Error> {
+    let path = Path::new(name);
+    match path.components().collect::<Vec<_>>().as_slice() {
+        [Component::Normal(_)] => Ok(path),
+        _ => Err(Error::invalid_request_body(format!(
+            "invalid checkpoint name {:?}, expected the name of a dir",
+            name
+        ))),
+    }
+}
+
+fn ensure_failpoints_enabled() -> Result<(), Error> {
+    if fail::has_failpoints() {
+        Ok(())
+    } else {
+        Err(Error::bad_request(
+            AptosErrorCode::InvalidRequestBody,
+            "failpoints are not compiled in the binary".to_owned(),
+        ))
+    }
+}
+
+fn ensure_execution_profiling_enabled() -> Result<(), Error> {
+    if aptos_vm::profiling::is_profiling_enabled() {
+        Ok(())
+    } else {
+        Err(Error::bad_request(
+            AptosErrorCode::InvalidRequestBody,
+            "execution profiling is not enabled in the execution config".to_owned(),
+        ))
+    }
+}
+
+fn ensure_native_tracing_enabled() -> Result<(), Error> {
+    if aptos_vm::native_tracing::is_native_tracing_enabled() {
+        Ok(())
+    } else {
+        Err(Error::bad_request(
+            AptosErrorCode::InvalidRequestBody,
+            "native tracing is not enabled in the execution config".to_owned(),
+        ))
+    }
+}
+
+/// Passes the requests carrying the configured admin token. When no admin token is
+/// configured, the admin routes are rejected as if they don't exist.
+pub(crate) fn admin_auth(context: Context) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>(X_APTOS_ADMIN_TOKEN)
+        .and(context.filter())
+        .and_then(|token: Option<String>, context: Context| async move {
+            authorize(context.admin_token(), token.as_deref())
+        })
+        .untuple_one()
+}
+
+fn authorize(expected: Option<&str>, given: Option<&str>) -> Result<(), Rejection> {
+    match (expected, given) {
+        (None, _) => Err(reject::not_found()),
+        (Some(expected), Some(given)) if constant_time_eq(expected, given) => Ok(()),
+        (Some(_), _) => Err(Error::new(
+            StatusCode::UNAUTHORIZED,
+            AptosErrorCode::Unauthorized,
+            format!("missing or invalid {} header", X_APTOS_ADMIN_TOKEN),
+        )
+        .into()),
+    }
+}
+
+// Compares the whole tokens regardless of where they differ, so the response time doesn't
+// tell how much of a guessed token is correct.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{authorize, constant_time_eq};
+
+    #[test]
+    fn test_authorize() {
+        assert!(authorize(None, None).unwrap_err().is_not_found());
+        assert!(authorize(None, Some("token")).unwrap_err().is_not_found());
+        assert!(authorize(Some("token"), Some("token")).is_ok());
+        assert!(authorize(Some("token"), Some("tokem")).is_err());
+        assert!(authorize(Some("token"), None).is_err());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("", ""));
+        assert!(constant_time_eq("abc", "abc"));
+        assert!(!constant_time_eq("abc", "abd"));
+        assert!(!constant_time_eq("abc", "abcd"));
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    rate_limit::{RateLimited, TokenBucket},
+};
+
+use aptos_api_types::{AptosErrorCode, Error};
+use aptos_config::config::{ApiKeyConfig, ApiKeysConfig};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use warp::{http::StatusCode, reject, Filter, Rejection};
+
+/// Request header carrying the API key of a client, required when API keys are configured
+/// unless anonymous requests are allowed.
+pub const X_APTOS_API_KEY: &str = "X-Aptos-Api-Key";
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// The usage of an API key since the node started, reported by `GET /admin/usage`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ApiKeyUsage {
+    pub name: String,
+    /// The number of requests accepted in the current UTC day.
+    pub requests_today: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daily_quota: Option<u64>,
+    pub total_requests: u64,
+    /// The number of requests rejected because the key ran out of its quota or rate.
+    pub rejected_requests: u64,
+}
+
+struct ApiKey {
+    config: ApiKeyConfig,
+    usage: Mutex<Usage>,
+}
+
+#[derive(Default)]
+struct Usage {
+    // days since the unix epoch, `requests_today` is of this day
+    day: u64,
+    requests_today: u64,
+    total_requests: u64,
+    rejected_requests: u64,
+    // the bucket of the per-minute rate, created by the first request
+    bucket: Option<TokenBucket>,
+}
+
+pub(crate) struct ApiKeys {
+    allow_anonymous: bool,
+    keys: HashMap<String, ApiKey>,
+}
+
+impl ApiKeys {
+    pub fn new(config: &ApiKeysConfig) -> Self {
+        Self {
+            allow_anonymous: config.allow_anonymous,
+            keys: config
+                .keys
+                .iter()
+                .map(|key| {
+                    let api_key = ApiKey {
+                        config: key.clone(),
+                        usage: Mutex::new(Usage::default()),
+                    };
+                    (key.key.clone(), api_key)
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns true iff the API key is configured
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.contains_key(key)
+    }
+
+    /// Accounts a request to the API key, rejects the request when the key is missing or
+    /// unknown, or when the key runs out of its daily quota or per-minute rate.
+    fn acquire(&self, key: Option<&str>, now: Instant, unix_secs: u64) -> Result<(), Rejection> {
+        let api_key = match key.and_then(|key| self.keys.get(key)) {
+            Some(api_key) => api_key,
+            None if key.is_none() && self.allow_anonymous => return Ok(()),
+            None => {
+                return Err(Error::new(
+                    StatusCode::UNAUTHORIZED,
+                    AptosErrorCode::Unauthorized,
+                    format!("missing or invalid {} header", X_APTOS_API_KEY),
+                )
+                .into())
+            }
+        };
+
+        let mut usage = api_key.usage.lock().unwrap();
+        let today = unix_secs / SECS_PER_DAY;
+        if usage.day != today {
+            usage.day = today;
+            usage.requests_today = 0;
+        }
+        if let Some(quota) = api_key.config.daily_quota {
+            if usage.requests_today >= quota {
+                usage.rejected_requests += 1;
+                let retry_after = Duration::from_secs(SECS_PER_DAY - unix_secs % SECS_PER_DAY);
+                return Err(reject::custom(RateLimited::new(
+                    "daily quota of the API key",
+                    retry_after,
+                )));
+            }
+        }
+        if let Some(requests_per_minute) = api_key.config.requests_per_minute {
+            let capacity = requests_per_minute.max(1) as f64;
+            let rate = capacity / 60.0;
+            let acquired = usage
+                .bucket
+                .get_or_insert_with(|| TokenBucket::full(capacity, now))
+                .acquire(now, rate, capacity);
+            if let Err(retry_after) = acquired {
+                usage.rejected_requests += 1;
+                return Err(reject::custom(RateLimited::new(
+                    "rate limit of the API key",
+                    retry_after,
+                )));
+            }
+        }
+        usage.requests_today += 1;
+        usage.total_requests += 1;
+        Ok(())
+    }
+
+    /// Returns the usage of all the API keys sorted by name.
+    pub fn usage(&self, unix_secs: u64) -> Vec<ApiKeyUsage> {
+        let today = unix_secs / SECS_PER_DAY;
+        let mut usage: Vec<_> = self
+            .keys
+            .values()
+            .map(|api_key| {
+                let usage = api_key.usage.lock().unwrap();
+                ApiKeyUsage {
+                    name: api_key.config.name.clone(),
+                    requests_today: if usage.day == today {
+                        usage.requests_today
+                    } else {
+                        0
+                    },
+                    daily_quota: api_key.config.daily_quota,
+                    total_requests: usage.total_requests,
+                    rejected_requests: usage.rejected_requests,
+                }
+            })
+            .collect();
+        usage.sort_by(|a, b| a.name.cmp(&b.name));
+        usage
+    }
+}
+
+pub(crate) fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Rejects the request without a valid API key, or whose key runs out of its quota, passes
+/// all requests when API keys are not configured. It's applied by each route once the request
+/// matched it, so that the requests of other routes aren't accounted to the key.
+pub(crate) fn api_key(context: Context) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>(X_APTOS_API_KEY)
+        .and(context.filter())
+        .and_then(|key: Option<String>, context: Context| async move {
+            match context.api_keys() {
+                Some(api_keys) => api_keys.acquire(key.as_deref(), Instant::now(), unix_secs()),
+                None => Ok(()),
+            }
+        })
+        .untuple_one()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiKeyUsage, ApiKeys, SECS_PER_DAY};
+    use crate::rate_limit::RateLimited;
+    use aptos_api_types::Error;
+    use aptos_config::config::{ApiKeyConfig, ApiKeysConfig};
+    use std::time::{Duration, Instant};
+
+    fn new_api_keys(allow_anonymous: bool) -> ApiKeys {
+        ApiKeys::new(&ApiKeysConfig {
+            keys: vec![
+                ApiKeyConfig {
+                    name: "quota".to_owned(),
+                    key: "quota-key".to_owned(),
+                    daily_quota: Some(2),
+                    requests_per_minute: None,
+                },
+                ApiKeyConfig {
+                    name: "rate".to_owned(),
+                    key: "rate-key".to_owned(),
+                    daily_quota: None,
+                    requests_per_minute: Some(60),
+                },
+            ],
+            allow_anonymous,
+        })
+    }
+
+    #[test]
+    fn test_reject_missing_or_unknown_key() {
+        let api_keys = new_api_keys(false);
+        let now = Instant::now();
+        for key in [None, Some("unknown")] {
+            let rejection = api_keys.acquire(key, now, 0).unwrap_err();
+            assert_eq!(rejection.find::<Error>().unwrap().code, 401);
+        }
+
+        let api_keys = new_api_keys(true);
+        assert!(api_keys.acquire(None, now, 0).is_ok());
+        assert!(api_keys.acquire(Some("unknown"), now, 0).is_err());
+    }
+
+    #[test]
+    fn test_daily_quota() {
+        let api_keys = new_api_keys(false);
+        let now = Instant::now();
+        let unix_secs = 10 * SECS_PER_DAY + 100;
+        assert!(api_keys.acquire(Some("quota-key"), now, unix_secs).is_ok());
+        assert!(api_keys.acquire(Some("quota-key"), now, unix_secs).is_ok());
+        let rejection = api_keys
+            .acquire(Some("quota-key"), now, unix_secs)
+            .unwrap_err();
+        assert_eq!(
+            rejection.find::<RateLimited>().unwrap().retry_after_secs(),
+            SECS_PER_DAY - 100
+        );
+
+        // the quota is of a UTC day
+        let tomorrow = 11 * SECS_PER_DAY;
+        assert!(api_keys.acquire(Some("quota-key"), now, tomorrow).is_ok());
+        assert_eq!(
+            api_keys.usage(tomorrow)[0],
+            ApiKeyUsage {
+                name: "quota".to_owned(),
+                requests_today: 1,
+                daily_quota: Some(2),
+                total_requests: 3,
+                rejected_requests: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_requests_per_minute() {
+        let api_keys = new_api_keys(false);
+        let now = Instant::now();
+        for _ in 0..60 {
+            assert!(api_keys.acquire(Some("rate-key"), now, 0).is_ok());
+        }
+        let rejection = api_keys.acquire(Some("rate-key"), now, 0).unwrap_err();
+        assert_eq!(
+            rejection.find::<RateLimited>().unwrap().retry_after_secs(),
+            1
+        );
+
+        let later = now + Duration::from_secs(1);
+        assert!(api_keys.acquire(Some("rate-key"), later, 0).is_ok());
+
+        let usage = api_keys.usage(0);
+        assert_eq!(usage[0].name, "quota");
+        assert_eq!(usage[0].total_requests, 0);
+        assert_eq!(usage[1].name, "rate");
+        assert_eq!(usage[1].requests_today, 61);
+        assert_eq!(usage[1].rejected_requests, 1);
+    }
+}
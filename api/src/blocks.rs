@@ -0,0 +1,133 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    api_key::api_key,
+    context::Context,
+    failpoint::fail_point,
+    head::get_or_head,
+    metrics::metrics,
+    param::{BlockHeightParam, Param},
+    response_cache::ResponseCacheKey,
+};
+
+use aptos_api_types::{AptosErrorCode, AsConverter, Block, Error, LedgerInfo, Response};
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::cmp::min;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+// Max number of transactions loaded from the database at once while rendering a block.
+const BLOCK_TRANSACTIONS_BATCH_SIZE: u64 = 1000;
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockQuery {
+    with_events: Option<Param<bool>>,
+}
+
+// GET /blocks/<height>?with_events={bool}
+pub fn get_block_by_height(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("blocks" / BlockHeightParam)
+        .and(get_or_head())
+        .and(warp::query::<BlockQuery>())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_block_by_height)
+        .with(metrics("get_block_by_height"))
+        .boxed()
+}
+
+async fn handle_get_block_by_height(
+    height: BlockHeightParam,
+    query: BlockQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_block_by_height")?;
+    let with_events = query
+        .with_events
+        .map(|param| param.parse("with_events"))
+        .transpose()?
+        .unwrap_or(false);
+    Ok(Blocks::new(context)?.get(height.parse("block height")?, with_events)?)
+}
+
+struct Blocks {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl Blocks {
+    fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info()?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    /// Renders the block at the given height with all of its transactions, the events
+    /// of the transactions are left out unless `with_events` is true.
+    pub fn get(self, height: u64, with_events: bool) -> Result<impl Reply, Error> {
+        let cache_key =
+            ResponseCacheKey::new("get_block_by_height", format!("{}:{}", height, with_events));
+        if let Some(cached) = self.context.response_cache().get(&cache_key) {
+            self.context
+                .ensure_transaction_not_pruned(cached.version, &self.ledger_info)?;
+            return Ok(cached.into_response(self.ledger_info));
+        }
+
+        let ledger_version = self.ledger_info.version();
+        let (first_version, last_version) = self
+            .context
+            .get_block_version_range(height, ledger_version)?
+            .ok_or_else(|| {
+                Error::not_found(
+                    AptosErrorCode::BlockNotFound,
+                    "block",
+                    format!("height({})", height),
+                    ledger_version,
+                )
+            })?;
+
+        self.context
+            .ensure_transaction_not_pruned(first_version, &self.ledger_info)?;
+
+        // All transactions of a block share the timestamp of its block metadata transaction.
+        let timestamp = self.context.get_block_timestamp(first_version)?;
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+
+        let mut transactions = vec![];
+        let mut version = first_version;
+        while version <= last_version {
+            let limit = min(last_version - version + 1, BLOCK_TRANSACTIONS_BATCH_SIZE);
+            for mut data in self
+                .context
+                .get_transactions(version, limit as u16, ledger_version)?
+            {
+                if !with_events {
+                    data.events.clear();
+                }
+                transactions.push(converter.try_into_onchain_transaction(timestamp, data)?);
+            }
+            version += limit;
+        }
+
+        let block = Block {
+            block_height: height.into(),
+            block_timestamp: timestamp.into(),
+            first_version: first_version.into(),
+            last_version: last_version.into(),
+            transactions,
+        };
+        let response = Response::new(self.ledger_info, &block)?;
+        // the latest block may still grow, all blocks before it are complete
+        if last_version < ledger_version {
+            self.context
+                .response_cache()
+                .insert(cache_key, first_version, &response);
+        }
+        Ok(response)
+    }
+}
@@ -0,0 +1,24 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::param::Param;
+
+use aptos_api_types::{BytesEncoding, Error};
+use serde::Deserialize;
+
+/// `BytesEncodingQuery` selects the encoding of the Move `vector<u8>` values in the JSON
+/// response: `hex` (default), `base64` or `utf8`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct BytesEncodingQuery {
+    bytes_encoding: Option<Param<BytesEncoding>>,
+}
+
+impl BytesEncodingQuery {
+    pub fn parse(self) -> Result<BytesEncoding, Error> {
+        Ok(self
+            .bytes_encoding
+            .map(|param| param.parse("bytes_encoding"))
+            .transpose()?
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use std::convert::Infallible;
+use warp::{
+    http::{
+        header::{ACCEPT_ENCODING, UPGRADE},
+        Method,
+    },
+    Filter, Rejection, Reply,
+};
+
+const BROTLI: &str = "br";
+const GZIP: &str = "gzip";
+
+/// Compresses the responses of `routes` with the encoding negotiated by the request
+/// `Accept-Encoding` header, brotli is preferred over gzip when both are accepted.
+/// The response body is compressed while it is streamed out, so memory usage is bounded
+/// regardless of the response size.
+pub(crate) fn with_compression<F>(
+    routes: F,
+    enabled: bool,
+) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone
+where
+    F: Filter<Error = Infallible> + Clone + Send + Sync + 'static,
+    F::Extract: Reply,
+{
+    accepts_encoding(BROTLI, enabled)
+        .and(routes.clone())
+        .with(warp::compression::brotli())
+        .or(accepts_encoding(GZIP, enabled)
+            .and(routes.clone())
+            .with(warp::compression::gzip()))
+        .or(routes)
+}
+
+/// Passes when compression is enabled and the request accepts the given encoding.
+/// Upgrade requests (e.g. WebSocket handshakes) are never compressed, because the
+/// connection is taken over by the upgraded protocol after the response headers. HEAD
+/// responses have no body to compress, and their `Content-Length` must be kept.
+fn accepts_encoding(
+    encoding: &'static str,
+    enabled: bool,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>(ACCEPT_ENCODING.as_str())
+        .and(warp::header::optional::<String>(UPGRADE.as_str()))
+        .and(warp::method())
+        .and_then(
+            move |accept_encoding: Option<String>, upgrade: Option<String>, method: Method| {
+                let compressible = enabled && upgrade.is_none() && method != Method::HEAD;
+                async move {
+                    match accept_encoding {
+                        Some(header) if compressible && accepts(&header, encoding) => Ok(()),
+                        _ => Err(warp::reject()),
+                    }
+                }
+            },
+        )
+        .untuple_one()
+}
+
+/// Returns true if the encoding is listed in the `Accept-Encoding` header value with a
+/// non-zero quality value.
+fn accepts(header: &str, encoding: &str) -> bool {
+    header.split(',').any(|item| {
+        let mut parts = item.split(';').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let quality = parts
+            .find_map(|param| param.strip_prefix("q="))
+            .map(|q| q.parse::<f32>().unwrap_or(0.0))
+            .unwrap_or(1.0);
+        name.eq_ignore_ascii_case(encoding) && quality > 0.0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{accepts, with_compression};
+    use warp::{http::header::CONTENT_ENCODING, Filter};
+
+    #[test]
+    fn test_accepts() {
+        assert!(accepts("gzip", "gzip"));
+        assert!(accepts("deflate, GZIP;q=0.5", "gzip"));
+        assert!(accepts("br;q=1.0, gzip", "br"));
+        assert!(!accepts("gzip;q=0", "gzip"));
+        assert!(!accepts("deflate", "gzip"));
+        assert!(!accepts("", "gzip"));
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_compression() {
+        let routes = with_compression(warp::any().map(|| "hello"), true);
+        for (accept_encoding, content_encoding) in [
+            ("br, gzip", Some("br")),
+            ("gzip", Some("gzip")),
+            ("deflate", None),
+        ] {
+            let resp = warp::test::request()
+                .header("accept-encoding", accept_encoding)
+                .reply(&routes)
+                .await;
+            assert_eq!(
+                resp.headers()
+                    .get(CONTENT_ENCODING)
+                    .map(|v| v.to_str().unwrap()),
+                content_encoding
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_no_compression_when_disabled() {
+        let routes = with_compression(warp::any().map(|| "hello"), false);
+        let resp = warp::test::request()
+            .header("accept-encoding", "gzip")
+            .reply(&routes)
+            .await;
+        assert!(resp.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(resp.body(), "hello");
+    }
+}
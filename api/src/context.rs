@@ -1,32 +1,72 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_api_types::{Error, LedgerInfo, TransactionOnChainData};
-use aptos_config::config::ApiConfig;
+use crate::{
+    api_key::ApiKeys,
+    gas_estimation::{self, GasPriceWindow},
+    idempotency::{IdempotencyCache, DEFAULT_IDEMPOTENCY_CACHE_CAPACITY},
+    param::AddressParam,
+    rate_limit::RateLimiter,
+    response_cache::ResponseCache,
+};
+
+use aptos_api_types::{Address, Error, GasEstimation, LedgerInfo, TransactionOnChainData};
+use aptos_config::config::{ApiAccessLogConfig, ApiConfig, CorsConfig, RoleType};
 use aptos_crypto::HashValue;
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use aptos_mempool::{MempoolClientRequest, MempoolClientSender, MempoolInfo, SubmissionStatus};
 use aptos_types::{
     account_address::AccountAddress,
     account_state::AccountState,
     account_state_blob::AccountStateBlob,
+    block_metadata::new_block_event_key,
     chain_id::ChainId,
     contract_event::ContractEvent,
+    download_stats::DownloadStatsReporter,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
-    transaction::{SignedTransaction, TransactionWithProof},
+    liveness::LivenessReporter,
+    on_chain_config::{OnChainConfig, VMConfig},
+    transaction::{ScriptFunction, SignedTransaction, TransactionOutput, TransactionWithProof},
+    vm_status::VMStatus,
 };
-use storage_interface::{DbReader, Order};
+use storage_interface::{AccountHistory, DbCheckpointer, DbReader, Order, StorageUsage};
 
 use anyhow::{ensure, format_err, Result};
+use aptos_logger::{
+    prelude::{sample, SampleRate},
+    warn,
+};
 use aptos_types::{state_store::state_key::StateKey, transaction::Version};
-use aptos_vm::data_cache::{IntoMoveResolver, RemoteStorageOwned};
+use aptos_vm::{
+    data_cache::{IntoMoveResolver, RemoteStorageOwned},
+    view_function, AptosVM,
+};
 use futures::{channel::oneshot, SinkExt};
+use move_core_types::{gas_schedule::GasAlgebra, value::MoveValue};
+use once_cell::sync::OnceCell;
 use std::{
+    cmp::min,
     convert::{Infallible, TryFrom},
-    sync::Arc,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
 };
 use storage_interface::state_view::{DbStateView, DbStateViewAtVersion, LatestDbStateView};
-use warp::{filters::BoxedFilter, Filter, Reply};
+use tokio::sync::{broadcast, watch};
+use warp::Filter;
+
+// Max number of committed events buffered for an event stream subscriber, the subscriber
+// is disconnected when it falls behind more than this number of events.
+const EVENT_STREAM_CHANNEL_SIZE: usize = 4096;
+const EVENT_STREAM_BATCH_SIZE: u64 = 100;
+
+/// Resolves a registered name, e.g. an ANS name, to the address of the account it is
+/// registered to. The API accepts such names wherever an account address is expected
+/// when a resolver is given to the `Context`.
+pub trait NameResolver: Send + Sync {
+    /// Returns `None` when the name is not registered.
+    fn resolve(&self, name: &str) -> Result<Option<AccountAddress>>;
+}
 
 // Context holds application scope context
 #[derive(Clone)]
@@ -35,6 +75,17 @@ pub struct Context {
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     api_config: ApiConfig,
+    event_subscriptions: Arc<OnceCell<broadcast::Sender<(Version, ContractEvent)>>>,
+    gas_price_window: Arc<Mutex<GasPriceWindow>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    api_keys: Option<Arc<ApiKeys>>,
+    name_resolver: Option<Arc<dyn NameResolver>>,
+    db_checkpointer: Option<Arc<dyn DbCheckpointer>>,
+    download_throttle: Option<Arc<dyn DownloadStatsReporter>>,
+    liveness_diagnostics: Option<Arc<dyn LivenessReporter>>,
+    role: Option<RoleType>,
+    idempotency_cache: Arc<IdempotencyCache>,
+    response_cache: Arc<ResponseCache>,
 }
 
 impl Context {
@@ -44,11 +95,84 @@ impl Context {
         mp_sender: MempoolClientSender,
         api_config: ApiConfig,
     ) -> Self {
+        let response_cache = Arc::new(ResponseCache::new(&api_config.response_cache));
+        let rate_limiter = api_config
+            .rate_limit
+            .as_ref()
+            .map(|config| Arc::new(RateLimiter::new(config)));
+        let api_keys = api_config
+            .api_keys
+            .as_ref()
+            .map(|config| Arc::new(ApiKeys::new(config)));
         Self {
             chain_id,
             db,
             mp_sender,
             api_config,
+            event_subscriptions: Arc::new(OnceCell::new()),
+            gas_price_window: Arc::new(Mutex::new(GasPriceWindow::default())),
+            rate_limiter,
+            api_keys,
+            name_resolver: None,
+            db_checkpointer: None,
+            download_throttle: None,
+            liveness_diagnostics: None,
+            role: None,
+            idempotency_cache: Arc::new(IdempotencyCache::new(DEFAULT_IDEMPOTENCY_CACHE_CAPACITY)),
+            response_cache,
+        }
+    }
+
+    pub fn with_db_checkpointer(mut self, db_checkpointer: Arc<dyn DbCheckpointer>) -> Self {
+        self.db_checkpointer = Some(db_checkpointer);
+        self
+    }
+
+    pub fn with_download_throttle(
+        mut self,
+        download_throttle: Arc<dyn DownloadStatsReporter>,
+    ) -> Self {
+        self.download_throttle = Some(download_throttle);
+        self
+    }
+
+    pub fn with_liveness_diagnostics(
+        mut self,
+        liveness_diagnostics: Arc<dyn LivenessReporter>,
+    ) -> Self {
+        self.liveness_diagnostics = Some(liveness_diagnostics);
+        self
+    }
+
+    pub fn with_name_resolver(mut self, name_resolver: Arc<dyn NameResolver>) -> Self {
+        self.name_resolver = Some(name_resolver);
+        self
+    }
+
+    pub fn with_role(mut self, role: RoleType) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    /// The role of the node serving the API, if any.
+    pub fn role(&self) -> Option<RoleType> {
+        self.role
+    }
+
+    /// Parses the account address parameter, the parameter that is not an address is
+    /// resolved as a registered name when there is a name resolver.
+    pub fn resolve_address(&self, address: AddressParam) -> Result<Address, Error> {
+        let resolver = match &self.name_resolver {
+            Some(resolver) => resolver,
+            None => return address.parse("account address"),
+        };
+        let name = address.decode("account address")?;
+        match address.parse("account address") {
+            Ok(address) => Ok(address),
+            Err(err) => match resolver.resolve(&name).map_err(Error::internal)? {
+                Some(address) => Ok(address.into()),
+                None => Err(err),
+            },
         }
     }
 
@@ -70,6 +194,67 @@ impl Context {
         self.api_config.content_length_limit()
     }
 
+    pub fn max_submit_transaction_batch_size(&self) -> usize {
+        self.api_config.max_submit_transaction_batch_size()
+    }
+
+    pub fn compression_enabled(&self) -> bool {
+        self.api_config.compression_enabled
+    }
+
+    pub fn cors_config(&self) -> &CorsConfig {
+        &self.api_config.cors
+    }
+
+    pub fn access_log_config(&self) -> Option<&ApiAccessLogConfig> {
+        self.api_config.access_log.as_ref()
+    }
+
+    pub(crate) fn idempotency_cache(&self) -> &IdempotencyCache {
+        &self.idempotency_cache
+    }
+
+    pub(crate) fn response_cache(&self) -> &ResponseCache {
+        &self.response_cache
+    }
+
+    pub fn graphql_enabled(&self) -> bool {
+        self.api_config.graphql_enabled
+    }
+
+    pub fn admin_token(&self) -> Option<&str> {
+        self.api_config.admin_token.as_deref()
+    }
+
+    /// The dir the checkpoints of the DB are created in, `None` if they can't be created, without
+    /// a configured dir or a DB to checkpoint.
+    pub fn checkpoint_dir(&self) -> Option<&Path> {
+        self.db_checkpointer.as_ref()?;
+        self.api_config.checkpoint_dir.as_deref()
+    }
+
+    pub fn db_checkpointer(&self) -> Option<Arc<dyn DbCheckpointer>> {
+        self.db_checkpointer.clone()
+    }
+
+    /// The throttle of the downloads of state sync, `None` if the node doesn't run state sync.
+    pub fn download_throttle(&self) -> Option<&dyn DownloadStatsReporter> {
+        self.download_throttle.as_deref()
+    }
+
+    /// The liveness of consensus, `None` if the node doesn't run consensus.
+    pub fn liveness_diagnostics(&self) -> Option<&dyn LivenessReporter> {
+        self.liveness_diagnostics.as_deref()
+    }
+
+    pub(crate) fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_deref()
+    }
+
+    pub(crate) fn api_keys(&self) -> Option<&ApiKeys> {
+        self.api_keys.as_deref()
+    }
+
     pub fn filter(self) -> impl Filter<Extract = (Context,), Error = Infallible> + Clone {
         warp::any().map(move || self.clone())
     }
@@ -84,6 +269,32 @@ impl Context {
         callback.await?
     }
 
+    /// Simulates the transaction against the latest state, the signature is not checked.
+    pub fn simulate_transaction(
+        &self,
+        txn: &SignedTransaction,
+    ) -> Result<(VMStatus, TransactionOutput)> {
+        let state_view = self.db.latest_state_view()?;
+        Ok(AptosVM::simulate_signed_transaction(txn, &state_view))
+    }
+
+    /// Executes the view function against the latest state, the VM status is returned
+    /// when the execution fails.
+    pub fn execute_view_function(
+        &self,
+        function: ScriptFunction,
+    ) -> Result<Result<Vec<MoveValue>, VMStatus>> {
+        let state_view = self.db.latest_state_view()?;
+        let (module, function, ty_args, args) = function.into_inner();
+        Ok(view_function::execute_view_function(
+            &state_view,
+            &module,
+            &function,
+            ty_args,
+            args,
+        ))
+    }
+
     pub fn get_latest_ledger_info(&self) -> Result<LedgerInfo, Error> {
         Ok(LedgerInfo::new(
             &self.chain_id(),
@@ -95,6 +306,47 @@ impl Context {
         self.db.get_latest_ledger_info()
     }
 
+    /// Returns the oldest transaction version not pruned.
+    pub fn get_oldest_version(&self) -> Result<Version, Error> {
+        Ok(self.db.get_least_readable_transaction_version()?)
+    }
+
+    /// Returns `410 Gone` when the transaction of the version is pruned.
+    pub fn ensure_transaction_not_pruned(
+        &self,
+        version: Version,
+        ledger_info: &LedgerInfo,
+    ) -> Result<(), Error> {
+        let oldest_version = self.get_oldest_version()?;
+        if version < oldest_version {
+            return Err(Error::version_pruned(
+                "transaction",
+                format!("version({})", version),
+                oldest_version,
+                ledger_info.version(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns `410 Gone` when the state at the version is pruned.
+    pub fn ensure_state_not_pruned(
+        &self,
+        version: Version,
+        ledger_info: &LedgerInfo,
+    ) -> Result<(), Error> {
+        let oldest_version = self.db.get_least_readable_state_version()?;
+        if version < oldest_version {
+            return Err(Error::version_pruned(
+                "state",
+                format!("ledger version({})", version),
+                oldest_version,
+                ledger_info.version(),
+            ));
+        }
+        Ok(())
+    }
+
     pub fn get_account_state(
         &self,
         address: AccountAddress,
@@ -107,6 +359,18 @@ impl Context {
         })
     }
 
+    pub fn get_account_history(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<AccountHistory> {
+        self.db.get_account_history(address, version)
+    }
+
+    pub fn get_storage_usage(&self) -> Result<StorageUsage> {
+        self.db.get_storage_usage()
+    }
+
     pub fn get_account_state_blob(
         &self,
         account: AccountAddress,
@@ -188,6 +452,26 @@ impl Context {
             .collect::<Result<Vec<_>>>()
     }
 
+    pub fn get_account_participated_transactions(
+        &self,
+        address: AccountAddress,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        let txns = self.db.get_account_participated_transactions(
+            address,
+            start_version,
+            limit as u64,
+            true,
+            ledger_version,
+        )?;
+        txns.into_inner()
+            .into_iter()
+            .map(|t| self.convert_into_transaction_on_chain_data(t))
+            .collect::<Result<Vec<_>>>()
+    }
+
     pub fn get_transaction_by_hash(
         &self,
         hash: HashValue,
@@ -214,6 +498,54 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    /// Returns the sequence number which the pending transaction of the hash waits for, when
+    /// the transaction can't be broadcast because a preceding sequence number of its sender is
+    /// missing in mempool.
+    pub async fn get_waiting_for_sequence_number(&self, hash: HashValue) -> Result<Option<u64>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetWaitingForSequenceNumber(
+                hash, req_sender,
+            ))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    /// Returns the transactions of the account waiting in mempool, ordered by sequence number.
+    pub async fn get_pending_account_transactions(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Vec<SignedTransaction>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetAccountTransactions(
+                address, req_sender,
+            ))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    /// Returns the occupancy of mempool.
+    pub async fn get_mempool_info(&self) -> Result<MempoolInfo> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetMempoolInfo(req_sender))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
@@ -260,7 +592,154 @@ impl Context {
             .collect::<Vec<_>>())
     }
 
-    pub fn health_check_route(&self) -> BoxedFilter<(impl Reply,)> {
-        super::health_check::health_check_route(self.db.clone())
+    /// Returns the first and last transaction versions of the block at the given height,
+    /// or `None` when the block is not committed at the `ledger_version`.
+    ///
+    /// Blocks are delimited by new block events: the block at height `h > 0` starts at the
+    /// `BlockMetadata` transaction emitting the new block event of sequence number `h - 1`,
+    /// and the genesis block (height 0) starts at version 0.
+    pub fn get_block_version_range(
+        &self,
+        height: u64,
+        ledger_version: Version,
+    ) -> Result<Option<(Version, Version)>> {
+        // For the genesis block we only need to find where the next block starts.
+        let (start, limit) = if height == 0 { (0, 1) } else { (height - 1, 2) };
+        let versions = self
+            .db
+            .get_events(&new_block_event_key(), start, Order::Ascending, limit)?
+            .into_iter()
+            .map(|(version, _event)| version)
+            .filter(|version| version <= &ledger_version)
+            .collect::<Vec<_>>();
+
+        let first_version = if height == 0 {
+            0
+        } else {
+            match versions.first() {
+                Some(version) => *version,
+                None => return Ok(None),
+            }
+        };
+        let last_version = match versions.get(limit as usize - 1) {
+            Some(next_block_version) => next_block_version - 1,
+            None => ledger_version,
+        };
+        Ok(Some((first_version, last_version)))
+    }
+
+    /// Returns the height of the latest block committed at the `ledger_version`, the
+    /// height is the number of new block events emitted up to the block.
+    pub fn get_latest_block_height(&self, ledger_version: Version) -> Result<u64> {
+        match self
+            .db
+            .get_latest_event_sequence_number(&new_block_event_key(), ledger_version)?
+        {
+            Some(sequence_number) => Ok(sequence_number + 1),
+            // only the genesis block is committed at the genesis version
+            None if ledger_version == 0 => Ok(0),
+            None => Err(format_err!(
+                "no new block event is found at ledger version {}",
+                ledger_version
+            )),
+        }
+    }
+
+    /// Subscribes the events of transactions committed after the subscription.
+    /// The events are `(transaction version, event)` pairs, in the order of commit.
+    ///
+    /// All subscribers share one background task tailing the ledger as it's committed, which is
+    /// spawned by the first subscriber, hence this must be called within the tokio runtime.
+    pub fn subscribe_events(&self) -> Result<broadcast::Receiver<(Version, ContractEvent)>> {
+        let sender = self.event_subscriptions.get_or_try_init(|| {
+            let commits = self.db.subscribe_committed_version()?;
+            let next_version = *commits.borrow() + 1;
+            let (sender, _) = broadcast::channel(EVENT_STREAM_CHANNEL_SIZE);
+            tokio::spawn(broadcast_committed_events(
+                self.db.clone(),
+                sender.clone(),
+                commits,
+                next_version,
+            ));
+            Ok::<_, anyhow::Error>(sender)
+        })?;
+        Ok(sender.subscribe())
+    }
+
+    /// Subscribes to the latest committed ledger version, the receiver is notified each
+    /// time new transactions are committed.
+    pub fn subscribe_commits(&self) -> Result<watch::Receiver<Version>> {
+        self.db.subscribe_committed_version()
+    }
+
+    /// Estimates the gas unit price from the recently committed user transactions, the
+    /// minimum gas unit price of the gas schedule is suggested when there is none.
+    pub fn estimate_gas_price(&self, ledger_version: Version) -> Result<GasEstimation> {
+        let start = self
+            .gas_price_window
+            .lock()
+            .unwrap()
+            .next_version(ledger_version);
+        // the DB is read without holding the lock, not to block the other requests by it
+        let prices = gas_estimation::read_prices(self.db.as_ref(), start, ledger_version)?;
+        let estimation = {
+            let mut window = self.gas_price_window.lock().unwrap();
+            window.extend(prices, ledger_version);
+            window.estimate()
+        };
+        match estimation {
+            Some(estimation) => Ok(estimation),
+            None => {
+                let min_price = VMConfig::fetch_config(&self.move_resolver()?)
+                    .ok_or_else(|| format_err!("failed to fetch on-chain VM config"))?
+                    .gas_schedule
+                    .gas_constants
+                    .min_price_per_gas_unit
+                    .get();
+                Ok(GasEstimation {
+                    low: min_price.into(),
+                    median: min_price.into(),
+                    high: min_price.into(),
+                })
+            }
+        }
+    }
+}
+
+async fn broadcast_committed_events(
+    db: Arc<dyn DbReader>,
+    sender: broadcast::Sender<(Version, ContractEvent)>,
+    mut commits: watch::Receiver<Version>,
+    mut next_version: Version,
+) {
+    // the events failed to be broadcast are broadcast again with the ones of the next commit.
+    while commits.changed().await.is_ok() {
+        let latest_version = *commits.borrow();
+        if let Err(err) = broadcast_new_events(&db, &sender, latest_version, &mut next_version) {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(10)),
+                warn!(error = ?err, "failed to broadcast committed events")
+            );
+        }
+    }
+}
+
+fn broadcast_new_events(
+    db: &Arc<dyn DbReader>,
+    sender: &broadcast::Sender<(Version, ContractEvent)>,
+    latest_version: Version,
+    next_version: &mut Version,
+) -> Result<()> {
+    while *next_version <= latest_version {
+        let limit = min(EVENT_STREAM_BATCH_SIZE, latest_version - *next_version + 1);
+        let txns = db.get_transactions(*next_version, limit, latest_version, true)?;
+        for (i, events) in txns.events.unwrap_or_default().into_iter().enumerate() {
+            for event in events {
+                // send fails only when there is no subscriber, it is fine to drop the event.
+                let _ = sender.send((*next_version + i as u64, event));
+            }
+        }
+        *next_version += limit;
     }
+    Ok(())
 }
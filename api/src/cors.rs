@@ -0,0 +1,86 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{api_key::X_APTOS_API_KEY, idempotency::IDEMPOTENCY_KEY};
+
+use aptos_config::config::CorsConfig;
+use warp::{cors::Builder, http::header};
+
+/// Builds the CORS filter of the API routes from the config, any origin is allowed when no
+/// origin is configured. `Content-Type`, `X-Aptos-Api-Key` and `Idempotency-Key` are always
+/// allowed headers, because the API does not work without them.
+///
+/// Panics when the config contains an invalid origin, method or header name, so that a bad
+/// config fails the node at startup instead of rejecting requests at runtime.
+pub(crate) fn cors(config: &CorsConfig) -> Builder {
+    let builder = if config.allowed_origins.is_empty() {
+        warp::cors().allow_any_origin()
+    } else {
+        warp::cors().allow_origins(config.allowed_origins.iter().map(String::as_str))
+    };
+    builder
+        .allow_methods(config.allowed_methods.iter().map(String::as_str))
+        .allow_headers(vec![
+            header::CONTENT_TYPE.as_str(),
+            X_APTOS_API_KEY,
+            IDEMPOTENCY_KEY,
+        ])
+        .allow_headers(config.allowed_headers.iter().map(String::as_str))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cors;
+    use aptos_config::config::CorsConfig;
+    use warp::{http::header, Filter};
+
+    fn preflight(origin: &str, method: &str) -> warp::test::RequestBuilder {
+        warp::test::request()
+            .method("OPTIONS")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, method)
+    }
+
+    #[tokio::test]
+    async fn test_allow_any_origin_by_default() {
+        let routes = warp::any()
+            .map(warp::reply)
+            .with(cors(&CorsConfig::default()));
+        let resp = preflight("https://example.com", "POST")
+            .reply(&routes)
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        let resp = preflight("https://example.com", "DELETE")
+            .reply(&routes)
+            .await;
+        assert_eq!(resp.status(), 403);
+    }
+
+    #[tokio::test]
+    async fn test_allow_configured_origins() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://dapp.example.com".to_owned()],
+            allowed_headers: vec!["x-custom-header".to_owned()],
+            ..CorsConfig::default()
+        };
+        let routes = warp::any().map(warp::reply).with(cors(&config));
+        let resp = preflight("https://dapp.example.com", "GET")
+            .header(
+                header::ACCESS_CONTROL_REQUEST_HEADERS,
+                "content-type, x-custom-header",
+            )
+            .reply(&routes)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://dapp.example.com"
+        );
+
+        let resp = preflight("https://other.example.com", "GET")
+            .reply(&routes)
+            .await;
+        assert_eq!(resp.status(), 403);
+    }
+}
@@ -3,35 +3,75 @@
 
 use crate::{
     accounts::Account,
+    api_key::api_key,
+    bytes_encoding::BytesEncodingQuery,
     context::Context,
     failpoint::fail_point,
+    head::get_or_head,
     metrics::metrics,
     page::Page,
-    param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam},
+    param::{
+        AddressParam, EventCreationNumberParam, EventKeyParam, MoveIdentifierParam,
+        MoveStructTagParam,
+    },
 };
 
-use aptos_api_types::{AsConverter, Error, LedgerInfo, Response};
+use aptos_api_types::{AsConverter, BytesEncoding, Error, Event, LedgerInfo, Response};
 
 use anyhow::Result;
-use aptos_types::event::EventKey;
+use aptos_types::{contract_event::ContractEvent, event::EventKey};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use serde::Deserialize;
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
+#[derive(Clone, Debug, Deserialize)]
+struct EventTypeFilter {
+    #[serde(rename = "type")]
+    typ: Option<MoveStructTagParam>,
+}
+
+impl EventTypeFilter {
+    fn struct_tag(self) -> Result<Option<StructTag>, Error> {
+        self.typ
+            .map(|param| param.parse_struct_tag("type"))
+            .transpose()
+    }
+}
+
 // GET /events/<event_key>
 pub fn get_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("events" / EventKeyParam)
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(warp::query::<BytesEncodingQuery>())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_get_events_by_event_key)
         .with(metrics("get_events_by_event_key"))
         .boxed()
 }
 
-// GET /accounts/<address>/events/<event_handle_struct>/<field_name>
+// GET /accounts/<address>/events/<creation_number>
+pub fn get_events_by_creation_number(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "events" / EventCreationNumberParam)
+        .and(get_or_head())
+        .and(warp::query::<Page>())
+        .and(warp::query::<BytesEncodingQuery>())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_events_by_creation_number)
+        .with(metrics("get_events_by_creation_number"))
+        .boxed()
+}
+
+// GET /accounts/<address>/events/<event_handle_struct>/<field_name>?type={struct_tag}
 pub fn get_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "events" / MoveStructTagParam / MoveIdentifierParam)
-        .and(warp::get())
+        .and(get_or_head())
         .and(warp::query::<Page>())
+        .and(warp::query::<EventTypeFilter>())
+        .and(warp::query::<BytesEncodingQuery>())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_get_events_by_event_handle)
         .with(metrics("get_events_by_event_handle"))
@@ -41,10 +81,34 @@ pub fn get_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)
 async fn handle_get_events_by_event_key(
     event_key: EventKeyParam,
     page: Page,
+    bytes_encoding: BytesEncodingQuery,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_key")?;
-    Ok(Events::new(event_key.parse("event key")?.into(), context)?.list(page)?)
+    Ok(
+        Events::new(event_key.parse("event key")?.into(), context)?.list(
+            page,
+            None,
+            bytes_encoding.parse()?,
+        )?,
+    )
+}
+
+async fn handle_get_events_by_creation_number(
+    address: AddressParam,
+    creation_number: EventCreationNumberParam,
+    page: Page,
+    bytes_encoding: BytesEncodingQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_events_by_creation_number")?;
+    // the event key is composed of the creation number and the address of the account
+    // that created the event handle.
+    let key = EventKey::new_from_address(
+        &context.resolve_address(address)?.into(),
+        creation_number.parse("creation number")?,
+    );
+    Ok(Events::new(key, context)?.list(page, None, bytes_encoding.parse()?)?)
 }
 
 async fn handle_get_events_by_event_handle(
@@ -52,22 +116,26 @@ async fn handle_get_events_by_event_handle(
     struct_tag: MoveStructTagParam,
     field_name: MoveIdentifierParam,
     page: Page,
+    type_filter: EventTypeFilter,
+    bytes_encoding: BytesEncodingQuery,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_handle")?;
+    let event_type = type_filter.struct_tag()?;
+    let bytes_encoding = bytes_encoding.parse()?;
     let key =
         Account::new(None, address, context.clone())?.find_event_key(struct_tag, field_name)?;
-    Ok(Events::new(key, context)?.list(page)?)
+    Ok(Events::new(key, context)?.list(page, event_type, bytes_encoding)?)
 }
 
-struct Events {
+pub(crate) struct Events {
     key: EventKey,
     ledger_info: LedgerInfo,
     context: Context,
 }
 
 impl Events {
-    fn new(key: EventKey, context: Context) -> Result<Self, Error> {
+    pub fn new(key: EventKey, context: Context) -> Result<Self, Error> {
         let ledger_info = context.get_latest_ledger_info()?;
         Ok(Self {
             key,
@@ -76,16 +144,124 @@ impl Events {
         })
     }
 
-    pub fn list(self, page: Page) -> Result<impl Reply, Error> {
-        let contract_events = self.context.get_events(
-            &self.key,
-            page.start(0, u64::MAX)?,
-            page.limit()?,
-            self.ledger_info.version(),
-        )?;
+    /// Lists a page of events, when `event_type` is given, the events of other types
+    /// are skipped, and the page holds the next `limit` events of the type.
+    pub fn list(
+        self,
+        page: Page,
+        event_type: Option<StructTag>,
+        bytes_encoding: BytesEncoding,
+    ) -> Result<impl Reply, Error> {
+        let events = self.list_events(&page, event_type, bytes_encoding)?;
+        Response::new(self.ledger_info, &events)
+    }
+
+    pub fn list_events(
+        &self,
+        page: &Page,
+        event_type: Option<StructTag>,
+        bytes_encoding: BytesEncoding,
+    ) -> Result<Vec<Event>, Error> {
+        let start = page.start(0, u64::MAX)?;
+        let limit = page.limit()?;
+        let ledger_version = self.ledger_info.version();
+        let fetch = |start, limit| {
+            self.context
+                .get_events(&self.key, start, limit, ledger_version)
+        };
+        let contract_events = match event_type {
+            Some(struct_tag) => {
+                collect_events_of_type(&TypeTag::Struct(struct_tag), start, limit, fetch)?
+            }
+            None => fetch(start, limit)?,
+        };
 
         let resolver = self.context.move_resolver()?;
-        let events = resolver.as_converter().try_into_events(&contract_events)?;
-        Response::new(self.ledger_info, &events)
+        Ok(resolver
+            .as_converter()
+            .with_bytes_encoding(bytes_encoding)
+            .try_into_events(&contract_events)?)
+    }
+}
+
+/// Collects the first `limit` events of `type_tag` from `start` in the event stream, which is
+/// read page by page by `fetch(start, limit)` until a page is shorter than `limit`, i.e. the
+/// end of the stream at the ledger version is reached.
+fn collect_events_of_type(
+    type_tag: &TypeTag,
+    mut start: u64,
+    limit: u16,
+    mut fetch: impl FnMut(u64, u16) -> Result<Vec<ContractEvent>>,
+) -> Result<Vec<ContractEvent>> {
+    let mut events = vec![];
+    loop {
+        let page = fetch(start, limit)?;
+        let end_of_stream = page.len() < limit as usize;
+        match page.last() {
+            Some(last) => start = last.sequence_number().saturating_add(1),
+            None => break,
+        }
+        events.extend(
+            page.into_iter()
+                .filter(|event| event.type_tag() == type_tag),
+        );
+        if events.len() >= limit as usize || end_of_stream {
+            break;
+        }
+    }
+    events.truncate(limit as usize);
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::collect_events_of_type;
+
+    use aptos_types::{
+        account_address::AccountAddress, contract_event::ContractEvent, event::EventKey,
+    };
+    use move_core_types::language_storage::TypeTag;
+
+    #[test]
+    fn test_collect_events_of_type_across_pages() {
+        // every third event of the stream is of the type
+        let key = EventKey::new_from_address(&AccountAddress::ONE, 0);
+        let stream: Vec<_> = (0..10)
+            .map(|seq| {
+                let type_tag = if seq % 3 == 2 {
+                    TypeTag::U64
+                } else {
+                    TypeTag::Bool
+                };
+                ContractEvent::new(key, seq, type_tag, vec![])
+            })
+            .collect();
+        let fetch = |start: u64, limit: u16| -> anyhow::Result<Vec<ContractEvent>> {
+            Ok(stream
+                .iter()
+                .skip(start as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        };
+        let sequence_numbers = |events: Vec<ContractEvent>| {
+            events
+                .iter()
+                .map(|event| event.sequence_number())
+                .collect::<Vec<_>>()
+        };
+
+        let events = collect_events_of_type(&TypeTag::U64, 0, 2, fetch).unwrap();
+        assert_eq!(sequence_numbers(events), vec![2, 5]);
+
+        let events = collect_events_of_type(&TypeTag::U64, 3, 2, fetch).unwrap();
+        assert_eq!(sequence_numbers(events), vec![5, 8]);
+
+        // the stream ends before the page is filled
+        let events = collect_events_of_type(&TypeTag::U64, 6, 3, fetch).unwrap();
+        assert_eq!(sequence_numbers(events), vec![8]);
+
+        let events = collect_events_of_type(&TypeTag::Address, 0, 2, fetch).unwrap();
+        assert!(events.is_empty());
     }
 }
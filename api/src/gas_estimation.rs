@@ -0,0 +1,160 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{api_key::api_key, context::Context, failpoint::fail_point, metrics::metrics};
+
+use aptos_api_types::{GasEstimation, Response};
+use aptos_types::transaction::{Transaction, Version};
+
+use anyhow::Result;
+use std::{
+    cmp::{max, min},
+    collections::VecDeque,
+};
+use storage_interface::DbReader;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+// Number of the most recent transactions the gas price estimation is computed from.
+const GAS_ESTIMATION_WINDOW_SIZE: u64 = 1000;
+const GAS_ESTIMATION_BATCH_SIZE: u64 = 100;
+
+const LOW_PERCENTILE: usize = 10;
+const MEDIAN_PERCENTILE: usize = 50;
+const HIGH_PERCENTILE: usize = 90;
+
+// GET /estimate_gas_price
+pub fn estimate_gas_price(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("estimate_gas_price")
+        .and(warp::get())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_estimate_gas_price)
+        .with(metrics("estimate_gas_price"))
+        .boxed()
+}
+
+async fn handle_estimate_gas_price(context: Context) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_estimate_gas_price")?;
+    let ledger_info = context.get_latest_ledger_info()?;
+    let estimation = context.estimate_gas_price(ledger_info.version())?;
+    Ok(Response::new(ledger_info, &estimation)?)
+}
+
+/// `GasPriceWindow` keeps the gas unit prices of the user transactions in the most recent
+/// `GAS_ESTIMATION_WINDOW_SIZE` transactions. It is updated incrementally, so only the
+/// transactions committed since the last update are read from the DB, by `read_prices`.
+#[derive(Debug, Default)]
+pub(crate) struct GasPriceWindow {
+    next_version: Version,
+    // (version, gas unit price) of user transactions, ordered by version
+    prices: VecDeque<(Version, u64)>,
+}
+
+impl GasPriceWindow {
+    /// Returns the first version whose gas unit price is missing from the window.
+    pub fn next_version(&self, ledger_version: Version) -> Version {
+        max(self.next_version, window_start(ledger_version))
+    }
+
+    /// Adds the prices read up to the ledger version, the ones already added by a concurrent
+    /// update are skipped.
+    pub fn extend(&mut self, prices: Vec<(Version, u64)>, ledger_version: Version) {
+        let next_version = self.next_version;
+        self.prices.extend(
+            prices
+                .into_iter()
+                .filter(|(version, _)| *version >= next_version),
+        );
+        self.next_version = max(self.next_version, ledger_version + 1);
+
+        let window_start = window_start(ledger_version);
+        while matches!(self.prices.front(), Some((version, _)) if *version < window_start) {
+            self.prices.pop_front();
+        }
+    }
+
+    /// Returns `None` when there is no user transaction in the window.
+    pub fn estimate(&self) -> Option<GasEstimation> {
+        if self.prices.is_empty() {
+            return None;
+        }
+        let mut prices: Vec<u64> = self.prices.iter().map(|(_, price)| *price).collect();
+        prices.sort_unstable();
+        Some(GasEstimation {
+            low: percentile(&prices, LOW_PERCENTILE).into(),
+            median: percentile(&prices, MEDIAN_PERCENTILE).into(),
+            high: percentile(&prices, HIGH_PERCENTILE).into(),
+        })
+    }
+}
+
+/// Reads the gas unit prices of the user transactions from the start version to the ledger
+/// version.
+pub(crate) fn read_prices(
+    db: &dyn DbReader,
+    mut start: Version,
+    ledger_version: Version,
+) -> Result<Vec<(Version, u64)>> {
+    let mut prices = vec![];
+    while start <= ledger_version {
+        let limit = min(GAS_ESTIMATION_BATCH_SIZE, ledger_version - start + 1);
+        let txns = db.get_transactions(start, limit, ledger_version, false)?;
+        for (i, txn) in txns.transactions.iter().enumerate() {
+            if let Transaction::UserTransaction(txn) = txn {
+                prices.push((start + i as u64, txn.gas_unit_price()));
+            }
+        }
+        start += limit;
+    }
+    Ok(prices)
+}
+
+fn window_start(ledger_version: Version) -> Version {
+    (ledger_version + 1).saturating_sub(GAS_ESTIMATION_WINDOW_SIZE)
+}
+
+fn percentile(sorted_prices: &[u64], percentile: usize) -> u64 {
+    sorted_prices[(sorted_prices.len() - 1) * percentile / 100]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GasPriceWindow;
+    use aptos_api_types::GasEstimation;
+
+    #[test]
+    fn test_estimate_empty_window() {
+        assert_eq!(GasPriceWindow::default().estimate(), None);
+    }
+
+    #[test]
+    fn test_estimate_by_percentiles() {
+        let window = GasPriceWindow {
+            next_version: 101,
+            prices: (1..=100).rev().map(|v| (v, v * 10)).collect(),
+        };
+        assert_eq!(
+            window.estimate(),
+            Some(GasEstimation {
+                low: 100u64.into(),
+                median: 500u64.into(),
+                high: 900u64.into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_extend_by_concurrent_updates() {
+        let mut window = GasPriceWindow::default();
+        let start = window.next_version(10);
+        window.extend(vec![(8, 80), (10, 100)], 10);
+        // read from the same start, before the first update is added
+        window.extend(vec![(8, 80), (10, 100), (11, 110)], 11);
+        assert_eq!(start, 0);
+        assert_eq!(window.next_version(11), 12);
+        assert_eq!(
+            window.prices.iter().copied().collect::<Vec<_>>(),
+            vec![(8, 80), (10, 100), (11, 110)]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    accounts::Account,
+    api_key::api_key,
+    context::Context,
+    events::Events,
+    metrics::metrics,
+    page::{Page, DEFAULT_PAGE_SIZE},
+    param::Param,
+    resource_filter::ResourceFilter,
+    transactions::Transactions,
+};
+
+use aptos_api_types::{
+    AccountData, BytesEncoding, Error, Event, EventKey, LedgerInfo, MoveResource, Transaction,
+};
+
+use async_graphql::{EmptyMutation, EmptySubscription, ErrorExtensions, Json, Object, Schema};
+use async_graphql_warp::GraphQLResponse;
+use std::{convert::Infallible, str::FromStr};
+use warp::{filters::BoxedFilter, reject, Filter, Rejection, Reply};
+
+// Bounds of a query, so that one query can't make the node load an unbounded amount of data.
+// The complexity of a page is the number of its items, so a query loads about as many items as
+// the largest page of the REST API.
+const MAX_QUERY_DEPTH: usize = 8;
+const MAX_QUERY_COMPLEXITY: usize = 1000;
+
+type ApiSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+// POST /graphql
+pub fn graphql(context: Context) -> BoxedFilter<(impl Reply,)> {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(context.clone())
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish();
+    warp::path!("graphql")
+        .and(warp::post())
+        .and(graphql_enabled(context.clone()))
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(api_key(context.clone()))
+        .and(async_graphql_warp::graphql(schema))
+        .and_then(handle_graphql)
+        .with(metrics("graphql"))
+        .boxed()
+}
+
+async fn handle_graphql(
+    (schema, request): (ApiSchema, async_graphql::Request),
+) -> Result<impl Reply, Infallible> {
+    Ok(GraphQLResponse::from(schema.execute(request).await))
+}
+
+/// Passes the requests when GraphQL is enabled by the API config, otherwise the route is
+/// rejected as if it doesn't exist.
+fn graphql_enabled(context: Context) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    context
+        .filter()
+        .and_then(|context: Context| async move {
+            if context.graphql_enabled() {
+                Ok(())
+            } else {
+                Err(reject::not_found())
+            }
+        })
+        .untuple_one()
+}
+
+/// The root of the GraphQL schema. Versions are given as strings like in the REST API; the
+/// Move values, transactions and events are rendered as their REST JSON documents.
+pub(crate) struct Query;
+
+#[Object]
+impl Query {
+    /// The latest ledger info of the node.
+    async fn ledger_info(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> async_graphql::Result<Json<LedgerInfo>> {
+        let ledger_info = api_context(ctx)
+            .get_latest_ledger_info()
+            .map_err(graphql_error)?;
+        Ok(Json(ledger_info))
+    }
+
+    /// The account at the ledger version, the latest ledger version by default.
+    async fn account(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        address: String,
+        ledger_version: Option<String>,
+    ) -> async_graphql::Result<AccountNode> {
+        let account = Account::new(
+            ledger_version.map(param),
+            param(address),
+            api_context(ctx).clone(),
+        )
+        .map_err(graphql_error)?;
+        Ok(AccountNode(account))
+    }
+
+    /// A page of the committed transactions, the last page by default.
+    #[graphql(complexity = "page_complexity(limit)")]
+    async fn transactions(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        start: Option<String>,
+        limit: Option<u16>,
+    ) -> async_graphql::Result<Vec<Json<Transaction>>> {
+        let txns = Transactions::new(api_context(ctx).clone())
+            .and_then(|txns| txns.list_committed(&page(start, limit)))
+            .map_err(graphql_error)?;
+        Ok(txns.into_iter().map(Json).collect())
+    }
+
+    async fn transaction(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        version: String,
+    ) -> async_graphql::Result<Json<Transaction>> {
+        let context = api_context(ctx).clone();
+        let txn = param(version)
+            .parse("version")
+            .and_then(|version| Transactions::new(context)?.get_committed(version))
+            .map_err(graphql_error)?;
+        Ok(Json(txn))
+    }
+
+    /// A page of the events of the event key, starting from the first event by default.
+    #[graphql(complexity = "page_complexity(limit)")]
+    async fn events(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        key: String,
+        start: Option<String>,
+        limit: Option<u16>,
+    ) -> async_graphql::Result<Vec<Json<Event>>> {
+        let page = page(start, limit);
+        let events = param::<EventKey>(key)
+            .parse("event key")
+            .and_then(|key| Events::new(key.into(), api_context(ctx).clone()))
+            .and_then(|events| events.list_events(&page, None, BytesEncoding::Hex))
+            .map_err(graphql_error)?;
+        Ok(events.into_iter().map(Json).collect())
+    }
+}
+
+pub(crate) struct AccountNode(Account);
+
+#[Object(name = "Account")]
+impl AccountNode {
+    async fn address(&self) -> String {
+        self.0.address().to_string()
+    }
+
+    async fn ledger_version(&self) -> String {
+        self.0.ledger_version().to_string()
+    }
+
+    async fn data(&self) -> async_graphql::Result<Json<AccountData>> {
+        Ok(Json(self.0.data().map_err(graphql_error)?))
+    }
+
+    /// The resources of the account, only those whose type starts with `filter` when it is
+    /// given, e.g. `0x1::Coin::`.
+    async fn resources(
+        &self,
+        filter: Option<String>,
+    ) -> async_graphql::Result<Vec<Json<MoveResource>>> {
+        let filter = filter
+            .map(|filter| param::<ResourceFilter>(filter).parse("filter"))
+            .transpose()
+            .map_err(graphql_error)?;
+        let resources = self
+            .0
+            .move_resources(filter.as_ref(), BytesEncoding::Hex)
+            .map_err(graphql_error)?;
+        Ok(resources.into_iter().map(Json).collect())
+    }
+
+    /// A page of the committed transactions sent by the account, starting from the sequence
+    /// number `start`, 0 by default.
+    #[graphql(complexity = "page_complexity(limit)")]
+    async fn transactions(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        start: Option<String>,
+        limit: Option<u16>,
+    ) -> async_graphql::Result<Vec<Json<Transaction>>> {
+        let context = api_context(ctx);
+        let page = page(start, limit);
+        let txns = Transactions::new(context.clone())
+            .and_then(|txns| {
+                let data = context.get_account_transactions(
+                    self.0.address().into(),
+                    page.start(0, u64::MAX)?,
+                    page.limit()?,
+                    self.0.ledger_version(),
+                )?;
+                Ok(txns.render_transactions(data)?)
+            })
+            .map_err(graphql_error)?;
+        Ok(txns.into_iter().map(Json).collect())
+    }
+}
+
+fn api_context<'a>(ctx: &async_graphql::Context<'a>) -> &'a Context {
+    ctx.data_unchecked::<Context>()
+}
+
+fn param<T: FromStr>(value: String) -> Param<T> {
+    match value.parse() {
+        Ok(param) => param,
+        Err(infallible) => match infallible {},
+    }
+}
+
+/// The complexity of a paged field, i.e. the number of the items of the page.
+fn page_complexity(limit: Option<u16>) -> usize {
+    limit.map_or(DEFAULT_PAGE_SIZE, |limit| limit.max(1)) as usize
+}
+
+fn page(start: Option<String>, limit: Option<u16>) -> Page {
+    Page::new(
+        start.map(param),
+        limit.map(|limit| param(limit.to_string())),
+    )
+}
+
+/// Converts the error into a GraphQL error, the status code, error code and ledger version
+/// of the REST error are given in the extensions of the GraphQL error.
+fn graphql_error(err: Error) -> async_graphql::Error {
+    async_graphql::Error::new(err.message.clone()).extend_with(|_, extensions| {
+        extensions.set("code", err.code);
+        if let Ok(error_code) = async_graphql::to_value(&err.error_code) {
+            extensions.set("error_code", error_code);
+        }
+        if let Some(version) = &err.aptos_ledger_version {
+            extensions.set("aptos_ledger_version", version.to_string());
+        }
+        if let Some(version) = &err.oldest_ledger_version {
+            extensions.set("oldest_ledger_version", version.to_string());
+        }
+    })
+}
@@ -0,0 +1,155 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{context::Context, transactions::Transactions};
+
+use aptos_api_types::Error;
+use aptos_logger::{debug, error, info};
+use aptos_transaction_stream::{
+    transaction_stream_server::{TransactionStream, TransactionStreamServer},
+    GetTransactionsRequest, Transaction, TransactionsResponse,
+};
+use aptos_types::transaction::Version;
+
+use std::{cmp::min, net::SocketAddr};
+use tokio::sync::{mpsc, watch};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{transport::Server, Request, Response, Status};
+
+// Max number of transactions in one response, used when the request doesn't set the batch
+// size.
+const DEFAULT_BATCH_SIZE: u16 = 100;
+const MAX_BATCH_SIZE: u16 = 1000;
+// Max number of responses buffered for a stream; once the buffer is full, the stream stops
+// reading the database until the client consumes the buffered responses.
+const STREAM_BUFFER_SIZE: usize = 4;
+
+/// Serves the gRPC transaction stream on the address, returns when the server fails.
+pub async fn serve(context: Context, address: SocketAddr) {
+    info!("gRPC transaction stream listening on {}", address);
+    let service = TransactionStreamServer::new(TransactionStreamService::new(context));
+    if let Err(err) = Server::builder().add_service(service).serve(address).await {
+        error!("gRPC transaction stream failed: {}", err);
+    }
+}
+
+pub struct TransactionStreamService {
+    context: Context,
+}
+
+impl TransactionStreamService {
+    pub fn new(context: Context) -> Self {
+        Self { context }
+    }
+}
+
+#[tonic::async_trait]
+impl TransactionStream for TransactionStreamService {
+    type GetTransactionsStream = ReceiverStream<Result<TransactionsResponse, Status>>;
+
+    async fn get_transactions(
+        &self,
+        request: Request<GetTransactionsRequest>,
+    ) -> Result<Response<Self::GetTransactionsStream>, Status> {
+        let request = request.into_inner();
+        let batch_size = match request.batch_size {
+            0 => DEFAULT_BATCH_SIZE,
+            size => min(size, MAX_BATCH_SIZE as u32) as u16,
+        };
+        let ledger_info = self.context.get_latest_ledger_info().map_err(status)?;
+        self.context
+            .ensure_transaction_not_pruned(request.starting_version, &ledger_info)
+            .map_err(status)?;
+        let commits = self
+            .context
+            .subscribe_commits()
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER_SIZE);
+        tokio::spawn(send_transactions(
+            self.context.clone(),
+            request.starting_version,
+            batch_size,
+            commits,
+            sender,
+        ));
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+async fn send_transactions(
+    context: Context,
+    mut version: Version,
+    batch_size: u16,
+    mut commits: watch::Receiver<Version>,
+    sender: mpsc::Sender<Result<TransactionsResponse, Status>>,
+) {
+    loop {
+        let latest_version = *commits.borrow();
+        if version > latest_version {
+            tokio::select! {
+                changed = commits.changed() => match changed {
+                    Ok(()) => continue,
+                    // the commit watcher is gone
+                    Err(_) => break,
+                },
+                _ = sender.closed() => break,
+            }
+        }
+        let limit = min(latest_version - version + 1, batch_size as u64) as u16;
+        match render_transactions(&context, version, limit, latest_version) {
+            Ok(response) => {
+                version += response.transactions.len() as u64;
+                // blocks while the buffer is full, fails when the client is gone
+                if sender.send(Ok(response)).await.is_err() {
+                    break;
+                }
+            }
+            Err(err) => {
+                let _ = sender.send(Err(err)).await;
+                break;
+            }
+        }
+    }
+    debug!("transaction stream is closed");
+}
+
+fn render_transactions(
+    context: &Context,
+    start_version: Version,
+    limit: u16,
+    ledger_version: Version,
+) -> Result<TransactionsResponse, Status> {
+    let data = context
+        .get_transactions(start_version, limit, ledger_version)
+        .map_err(|e| Status::internal(e.to_string()))?;
+    let transactions = Transactions::new(context.clone())
+        .map_err(status)?
+        .render_transactions(data)
+        .and_then(|txns| {
+            txns.into_iter()
+                .zip(start_version..)
+                .map(|(txn, version)| {
+                    Ok(Transaction {
+                        version,
+                        json: serde_json::to_string(&txn)?,
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .map_err(|e| Status::internal(e.to_string()))?;
+    Ok(TransactionsResponse {
+        chain_id: context.chain_id().id() as u32,
+        transactions,
+    })
+}
+
+fn status(err: Error) -> Status {
+    match err.code {
+        400 => Status::invalid_argument(err.message),
+        404 => Status::not_found(err.message),
+        // the requested version is pruned
+        410 => Status::failed_precondition(err.message),
+        _ => Status::internal(err.message),
+    }
+}
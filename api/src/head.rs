@@ -0,0 +1,58 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use hyper::{body::HttpBody, Body};
+use std::convert::Infallible;
+use warp::{
+    http::{header::CONTENT_LENGTH, HeaderValue},
+    Filter, Rejection, Reply,
+};
+
+/// Matches GET and HEAD requests, for the read endpoints whose response headers are useful
+/// without the body, e.g. the ledger version for freshness checks.
+pub(crate) fn get_or_head() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::get().or(warp::head()).unify()
+}
+
+/// Serves the HEAD requests of `routes` by dropping the body of the GET response, the
+/// `Content-Length` header tells the size of the dropped body when it is known up front,
+/// i.e. when the body is not streamed.
+pub(crate) fn with_head<F>(
+    routes: F,
+) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone
+where
+    F: Filter<Error = Infallible> + Clone + Send + Sync + 'static,
+    F::Extract: Reply,
+{
+    warp::head()
+        .and(routes.clone())
+        .map(|reply: F::Extract| {
+            let mut response = reply.into_response();
+            if let Some(length) = response.body().size_hint().exact() {
+                response
+                    .headers_mut()
+                    .insert(CONTENT_LENGTH, HeaderValue::from(length));
+            }
+            *response.body_mut() = Body::empty();
+            response
+        })
+        .or(routes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::with_head;
+    use warp::{http::header::CONTENT_LENGTH, Filter};
+
+    #[tokio::test]
+    async fn test_head_drops_body() {
+        let routes = with_head(warp::any().map(|| "hello"));
+        let resp = warp::test::request().method("HEAD").reply(&routes).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers()[CONTENT_LENGTH], "5");
+        assert!(resp.body().is_empty());
+
+        let resp = warp::test::request().method("GET").reply(&routes).await;
+        assert_eq!(resp.body(), "hello");
+    }
+}
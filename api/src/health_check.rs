@@ -1,14 +1,16 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{context::Context, index::handle_rejection, metrics::metrics, param::Param};
+
+use aptos_api_types::{AptosErrorCode, Error};
+
 use anyhow::{ensure, Result};
 use std::{
     ops::Sub,
-    sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use storage_interface::DbReader;
-use warp::{filters::BoxedFilter, reject, Filter, Reply};
+use warp::{filters::BoxedFilter, http::StatusCode, Filter, Rejection, Reply};
 
 // HealthCheckParams is optional params for different layer's health check.
 // If no param is provided, server return 200 by default to indicate HTTP server is running health.
@@ -16,38 +18,50 @@ use warp::{filters::BoxedFilter, reject, Filter, Reply};
 struct HealthCheckParams {
     // Health check returns 200 when this param is provided and meet the following condition:
     //   server latest ledger info timestamp >= server current time timestamp - duration_secs
-    pub duration_secs: Option<u64>,
+    pub duration_secs: Option<Param<u64>>,
 }
 
-#[derive(Debug)]
-struct HealthCheckError;
-impl reject::Reject for HealthCheckError {}
-
-pub fn health_check_route(health_aptos_db: Arc<dyn DbReader>) -> BoxedFilter<(impl Reply,)> {
+// GET /-/healthy?duration_secs={u64}
+//
+// The route isn't rate limited, its failures are replied once the path is matched: falling
+// through to the other routes would take a token from the client's bucket.
+pub fn health_check_route(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("-" / "healthy")
         .and(warp::path::end())
-        .and(warp::query().map(move |params: HealthCheckParams| params))
-        .and(warp::any().map(move || health_aptos_db.clone()))
-        .and(warp::any().map(SystemTime::now))
-        .and_then(health_check)
+        .and(
+            warp::query::<HealthCheckParams>()
+                .and(context.filter())
+                .and(warp::any().map(SystemTime::now))
+                .and_then(health_check)
+                .recover(handle_rejection),
+        )
+        .with(metrics("health_check"))
         .boxed()
 }
 
 async fn health_check(
     params: HealthCheckParams,
-    db: Arc<dyn DbReader>,
+    context: Context,
     now: SystemTime,
-) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+) -> Result<impl Reply, Rejection> {
     if let Some(duration) = params.duration_secs {
-        let ledger_info = db
-            .get_latest_ledger_info()
-            .map_err(|_| reject::custom(HealthCheckError))?;
-        let timestamp = ledger_info.ledger_info().timestamp_usecs();
-
-        check_latest_ledger_info_timestamp(duration, timestamp, now)
-            .map_err(|_| reject::custom(HealthCheckError))?;
+        let duration = duration.parse("duration_secs")?;
+        let ledger_info = context.get_latest_ledger_info()?;
+        check_latest_ledger_info_timestamp(duration, ledger_info.timestamp(), now).map_err(
+            |_| {
+                Error::new(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    AptosErrorCode::HealthCheckFailed,
+                    format!(
+                        "the latest ledger info timestamp {} is more than {} seconds behind",
+                        ledger_info.timestamp(),
+                        duration
+                    ),
+                )
+            },
+        )?;
     }
-    Ok(Box::new("aptos-node:ok"))
+    Ok("aptos-node:ok")
 }
 
 pub fn check_latest_ledger_info_timestamp(
@@ -0,0 +1,182 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::rate_limit::ClientKey;
+use aptos_crypto::HashValue;
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+    mem,
+    sync::Mutex,
+};
+
+/// Request header of the key identifying a transaction submission, the submissions retried
+/// with the same key get the result of the first submission.
+pub const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+// Number of the most recently used keys remembered by the API.
+pub(crate) const DEFAULT_IDEMPOTENCY_CACHE_CAPACITY: usize = 10_000;
+
+/// The key of a submission, scoped by the client submitting it: the clients don't share their
+/// keys, nor see the transactions submitted by the others.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct IdempotencyKey {
+    pub client: Option<ClientKey>,
+    pub key: String,
+}
+
+/// What to do with the transaction submitted with a key
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) enum Reservation {
+    /// The key is reserved for the transaction, which is to be submitted, and released if it's
+    /// rejected.
+    New,
+    /// The transaction was submitted with the key already.
+    Submitted,
+    /// The key was used to submit another transaction.
+    Conflict,
+}
+
+/// Remembers the hash of the transaction submitted with a key, the least recently used
+/// key is forgotten when the capacity is reached. Only the hash is kept, the retried request
+/// carries the transaction itself.
+pub(crate) struct IdempotencyCache {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    // key => (last use, transaction hash)
+    entries: HashMap<IdempotencyKey, (u64, HashValue)>,
+    // last use => key
+    uses: BTreeMap<u64, IdempotencyKey>,
+    clock: u64,
+}
+
+impl IdempotencyCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Reserves the key for the transaction unless the key is already used, so that the
+    /// concurrent submissions with the same key are submitted only once.
+    pub fn reserve(&self, key: IdempotencyKey, hash: HashValue) -> Reservation {
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+        inner.clock += 1;
+        let clock = inner.clock;
+        let (reservation, previous_use) = match inner.entries.entry(key.clone()) {
+            Entry::Occupied(mut entry) => {
+                let (last_use, submitted_hash) = entry.get_mut();
+                let reservation = if *submitted_hash == hash {
+                    Reservation::Submitted
+                } else {
+                    Reservation::Conflict
+                };
+                (reservation, Some(mem::replace(last_use, clock)))
+            }
+            Entry::Vacant(entry) => {
+                entry.insert((clock, hash));
+                (Reservation::New, None)
+            }
+        };
+        if let Some(previous_use) = previous_use {
+            inner.uses.remove(&previous_use);
+        }
+        inner.uses.insert(clock, key);
+
+        while inner.entries.len() > self.capacity {
+            let oldest = match inner.uses.keys().next() {
+                Some(oldest) => *oldest,
+                None => break,
+            };
+            if let Some(key) = inner.uses.remove(&oldest) {
+                inner.entries.remove(&key);
+            }
+        }
+        reservation
+    }
+
+    /// Releases the key reserved for the transaction, e.g. the transaction is rejected, so that
+    /// the key can be used again.
+    pub fn release(&self, key: &IdempotencyKey, hash: HashValue) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some((last_use, _)) = inner
+            .entries
+            .get(key)
+            .filter(|(_, reserved_hash)| *reserved_hash == hash)
+            .copied()
+        {
+            inner.entries.remove(key);
+            inner.uses.remove(&last_use);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdempotencyCache, IdempotencyKey, Reservation};
+    use crate::rate_limit::ClientKey;
+    use aptos_crypto::HashValue;
+
+    fn key(key: &str) -> IdempotencyKey {
+        IdempotencyKey {
+            client: Some(ClientKey::Ip("127.0.0.1".parse().unwrap())),
+            key: key.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_evict_least_recently_used_key() {
+        let cache = IdempotencyCache::new(2);
+        let (a, b, c) = (
+            HashValue::random(),
+            HashValue::random(),
+            HashValue::random(),
+        );
+        assert_eq!(cache.reserve(key("a"), a), Reservation::New);
+        assert_eq!(cache.reserve(key("b"), b), Reservation::New);
+        assert_eq!(cache.reserve(key("a"), a), Reservation::Submitted);
+
+        assert_eq!(cache.reserve(key("c"), c), Reservation::New);
+        assert_eq!(cache.reserve(key("a"), a), Reservation::Submitted);
+        assert_eq!(cache.reserve(key("c"), c), Reservation::Submitted);
+        assert_eq!(cache.reserve(key("b"), b), Reservation::New);
+    }
+
+    #[test]
+    fn test_reserve_used_key() {
+        let cache = IdempotencyCache::new(2);
+        let (a, b) = (HashValue::random(), HashValue::random());
+        assert_eq!(cache.reserve(key("a"), a), Reservation::New);
+        assert_eq!(cache.reserve(key("a"), b), Reservation::Conflict);
+        assert_eq!(cache.reserve(key("a"), a), Reservation::Submitted);
+        assert_eq!(cache.inner.lock().unwrap().uses.len(), 1);
+
+        // the key of another client is another key
+        let other_client = IdempotencyKey {
+            client: None,
+            key: "a".to_owned(),
+        };
+        assert_eq!(cache.reserve(other_client, b), Reservation::New);
+    }
+
+    #[test]
+    fn test_release_key() {
+        let cache = IdempotencyCache::new(2);
+        let (a, b) = (HashValue::random(), HashValue::random());
+        assert_eq!(cache.reserve(key("a"), a), Reservation::New);
+        // only the transaction the key is reserved for releases it
+        cache.release(&key("a"), b);
+        assert_eq!(cache.reserve(key("a"), b), Reservation::Conflict);
+
+        cache.release(&key("a"), a);
+        assert!(cache.inner.lock().unwrap().uses.is_empty());
+        assert_eq!(cache.reserve(key("a"), b), Reservation::New);
+    }
+}
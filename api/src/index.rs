@@ -2,16 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    accounts,
+    access_log::with_access_log,
+    accounts, admin,
+    api_key::api_key,
+    blocks,
+    compression::with_compression,
     context::Context,
+    cors::cors,
     events,
     failpoint::fail_point,
-    log,
+    gas_estimation, graphql,
+    head::with_head,
+    health_check, log, mempool,
     metrics::{metrics, status_metrics},
-    state, transactions,
+    rate_limit::{rate_limit, RateLimited},
+    state, stream, transactions, view_function,
 };
-use aptos_api_types::{Error, Response};
+use aptos_api_types::{ApiFeatures, AptosErrorCode, Error, NodeInfo, Response};
 
+use async_graphql_warp::GraphQLBadRequest;
+use once_cell::sync::Lazy;
 use std::convert::Infallible;
 use warp::{
     body::BodyDeserializeError,
@@ -23,104 +33,242 @@ use warp::{
 };
 
 const OPEN_API_HTML: &str = include_str!("../doc/spec.html");
-const OPEN_API_SPEC: &str = include_str!("../doc/openapi.yaml");
+pub(crate) const OPEN_API_SPEC: &str = include_str!("../doc/openapi.yaml");
+
+// The spec is converted to JSON once. The YAML document is checked by the tests, an invalid one
+// fails the requests of the JSON spec rather than the node.
+static OPEN_API_SPEC_JSON: Lazy<Result<serde_json::Value, String>> =
+    Lazy::new(|| serde_yaml::from_str(OPEN_API_SPEC).map_err(|err| err.to_string()));
 
 pub fn routes(context: Context) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone {
-    index(context.clone())
-        .or(openapi_spec())
+    let compression_enabled = context.compression_enabled();
+    let endpoints = index(context.clone())
+        .or(info(context.clone()))
+        .or(openapi_spec(context.clone()))
         .or(accounts::get_account(context.clone()))
         .or(accounts::get_account_resources(context.clone()))
+        .or(accounts::get_account_balance(context.clone()))
         .or(accounts::get_account_modules(context.clone()))
         .or(accounts::get_account_state_blob(context.clone()))
+        .or(transactions::get_transactions_range(context.clone()))
         .or(transactions::get_transaction(context.clone()))
+        .or(transactions::wait_transaction_by_hash(context.clone()))
         .or(transactions::get_transactions(context.clone()))
         .or(transactions::get_account_transactions(context.clone()))
+        .or(transactions::get_account_participated_transactions(
+            context.clone(),
+        ))
         .or(transactions::submit_bcs_transactions(context.clone()))
         .or(transactions::submit_json_transactions(context.clone()))
+        .or(transactions::submit_bcs_transactions_batch(context.clone()))
+        .or(transactions::simulate_bcs_transactions(context.clone()))
+        .or(transactions::simulate_json_transactions(context.clone()))
         .or(transactions::create_signing_message(context.clone()))
         .or(events::get_events_by_event_key(context.clone()))
         .or(events::get_events_by_event_handle(context.clone()))
+        .or(events::get_events_by_creation_number(context.clone()))
+        .or(blocks::get_block_by_height(context.clone()))
+        .or(gas_estimation::estimate_gas_price(context.clone()))
+        .or(view_function::view_function(context.clone()))
         .or(state::get_account_resource(context.clone()))
+        .or(state::get_account_resources_batch(context.clone()))
         .or(state::get_account_module(context.clone()))
-        .or(context.health_check_route().with(metrics("health_check")))
-        .with(
-            warp::cors()
-                .allow_any_origin()
-                .allow_methods(vec!["POST", "GET"])
-                .allow_headers(vec![header::CONTENT_TYPE]),
-        )
-        .recover(handle_rejection)
-        .with(log::logger())
-        .with(status_metrics())
+        .or(stream::stream_events(context.clone()))
+        .or(graphql::graphql(context.clone()))
+        // the admin-gated routes don't require an API key
+        .or(admin::list_failpoints(context.clone()))
+        .or(admin::set_failpoint(context.clone()))
+        .or(admin::get_usage(context.clone()))
+        .or(admin::get_storage(context.clone()))
+        .or(admin::get_state_sync(context.clone()))
+        .or(admin::get_consensus(context.clone()))
+        .or(admin::get_execution_profile(context.clone()))
+        .or(admin::get_native_trace(context.clone()))
+        .or(admin::get_gas_schedule(context.clone()))
+        .or(admin::create_checkpoint(context.clone()))
+        .or(mempool::get_mempool_info(context.clone()))
+        .or(mempool::get_mempool_transactions(context.clone()));
+    // the requests are rate limited once, whether they are sampled by the access log or not,
+    // but the health check, which requires no API key and is polled by the load balancers
+    let routes = health_check::health_check_route(context.clone())
+        .or(rate_limit(context.clone()).and(with_access_log(
+            endpoints.recover(handle_rejection),
+            context.clone(),
+        )))
+        .with(cors(context.cors_config()))
+        .recover(handle_rejection);
+    let routes = with_head(routes).with(log::logger()).with(status_metrics());
+    with_compression(routes, compression_enabled)
 }
 
 // GET /openapi.yaml
+// GET /spec.json
 // GET /spec.html
-pub fn openapi_spec() -> BoxedFilter<(impl Reply,)> {
+pub fn openapi_spec(context: Context) -> BoxedFilter<(impl Reply,)> {
     let spec = warp::path!("openapi.yaml")
         .and(warp::get())
+        .and(api_key(context.clone()))
         .map(|| OPEN_API_SPEC)
         .with(metrics("openapi_yaml"))
         .boxed();
+    let json = warp::path!("spec.json")
+        .and(warp::get())
+        .and(api_key(context.clone()))
+        .and_then(handle_spec_json)
+        .with(metrics("spec_json"))
+        .boxed();
     let html = warp::path!("spec.html")
         .and(warp::get())
+        .and(api_key(context))
         .map(|| reply::html(open_api_html()))
         .with(metrics("spec_html"))
         .boxed();
-    spec.or(html).boxed()
+    spec.or(json).or(html).boxed()
 }
 
 // GET /
 pub fn index(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path::end()
         .and(warp::get())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_index)
         .with(metrics("get_ledger_info"))
         .boxed()
 }
 
+async fn handle_spec_json() -> Result<impl Reply, Rejection> {
+    match &*OPEN_API_SPEC_JSON {
+        Ok(spec) => Ok(reply::json(spec)),
+        Err(err) => {
+            Err(Error::internal(anyhow::format_err!("invalid OpenAPI spec: {}", err)).into())
+        }
+    }
+}
+
 pub async fn handle_index(context: Context) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_index")?;
     let info = context.get_latest_ledger_info()?;
     Ok(Response::new(info.clone(), &info)?)
 }
 
-async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
+// GET /info
+pub fn info(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("info")
+        .and(warp::get())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_info)
+        .with(metrics("get_node_info"))
+        .boxed()
+}
+
+async fn handle_info(context: Context) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_info")?;
+    let ledger_info = context.get_latest_ledger_info()?;
+    let info = NodeInfo {
+        chain_id: ledger_info.chain_id,
+        epoch: ledger_info.epoch,
+        role: context.role().map(|role| role.as_str().to_owned()),
+        api_version: env!("CARGO_PKG_VERSION").to_owned(),
+        ledger_version: ledger_info.ledger_version,
+        oldest_ledger_version: context.get_oldest_version()?.into(),
+        features: ApiFeatures {
+            bcs_output: true,
+            transaction_simulation: true,
+            // tables are not supported by the framework yet
+            table_listing: false,
+            graphql: context.graphql_enabled(),
+        },
+    };
+    Ok(Response::new(ledger_info, &info)?)
+}
+
+pub(crate) async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
     let code;
     let body;
 
     if err.is_not_found() {
         code = StatusCode::NOT_FOUND;
-        body = reply::json(&Error::new(code, "Not Found".to_owned()));
+        body = reply::json(&Error::new(
+            code,
+            AptosErrorCode::NotFound,
+            "Not Found".to_owned(),
+        ));
     } else if let Some(error) = err.find::<Error>() {
         code = error.status_code();
         body = reply::json(error);
     } else if let Some(cause) = err.find::<CorsForbidden>() {
         code = StatusCode::FORBIDDEN;
-        body = reply::json(&Error::new(code, cause.to_string()));
+        body = reply::json(&Error::new(
+            code,
+            AptosErrorCode::CorsForbidden,
+            cause.to_string(),
+        ));
     } else if let Some(cause) = err.find::<BodyDeserializeError>() {
         code = StatusCode::BAD_REQUEST;
-        body = reply::json(&Error::new(code, cause.to_string()));
+        body = reply::json(&Error::new(
+            code,
+            AptosErrorCode::InvalidRequestBody,
+            cause.to_string(),
+        ));
+    } else if let Some(cause) = err.find::<GraphQLBadRequest>() {
+        code = StatusCode::BAD_REQUEST;
+        body = reply::json(&Error::new(
+            code,
+            AptosErrorCode::InvalidRequestBody,
+            cause.to_string(),
+        ));
     } else if let Some(cause) = err.find::<LengthRequired>() {
         code = StatusCode::LENGTH_REQUIRED;
-        body = reply::json(&Error::new(code, cause.to_string()));
+        body = reply::json(&Error::new(
+            code,
+            AptosErrorCode::ContentLengthRequired,
+            cause.to_string(),
+        ));
     } else if let Some(cause) = err.find::<PayloadTooLarge>() {
         code = StatusCode::PAYLOAD_TOO_LARGE;
-        body = reply::json(&Error::new(code, cause.to_string()));
+        body = reply::json(&Error::new(
+            code,
+            AptosErrorCode::PayloadTooLarge,
+            cause.to_string(),
+        ));
     } else if let Some(cause) = err.find::<UnsupportedMediaType>() {
         code = StatusCode::UNSUPPORTED_MEDIA_TYPE;
-        body = reply::json(&Error::new(code, cause.to_string()));
+        body = reply::json(&Error::new(
+            code,
+            AptosErrorCode::UnsupportedMediaType,
+            cause.to_string(),
+        ));
+    } else if let Some(cause) = err.find::<RateLimited>() {
+        code = StatusCode::TOO_MANY_REQUESTS;
+        body = reply::json(&Error::new(
+            code,
+            AptosErrorCode::RateLimited,
+            cause.to_string(),
+        ));
     } else if let Some(cause) = err.find::<MethodNotAllowed>() {
         code = StatusCode::METHOD_NOT_ALLOWED;
-        body = reply::json(&Error::new(code, cause.to_string()));
+        body = reply::json(&Error::new(
+            code,
+            AptosErrorCode::MethodNotAllowed,
+            cause.to_string(),
+        ));
     } else {
         code = StatusCode::INTERNAL_SERVER_ERROR;
-        body = reply::json(&Error::new(code, format!("unexpected error: {:?}", err)));
+        body = reply::json(&Error::new(
+            code,
+            AptosErrorCode::InternalError,
+            format!("unexpected error: {:?}", err),
+        ));
     }
     let mut rep = reply::with_status(body, code).into_response();
     rep.headers_mut()
         .insert("access-control-allow-origin", HeaderValue::from_static("*"));
+    if let Some(cause) = err.find::<RateLimited>() {
+        rep.headers_mut()
+            .insert(header::RETRY_AFTER, cause.retry_after_secs().into());
+    }
     Ok(rep)
 }
 
@@ -1,19 +1,40 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod accept_type;
+mod access_log;
 mod accounts;
+mod admin;
+mod api_key;
+mod blocks;
+mod bytes_encoding;
+mod compression;
 mod context;
+mod cors;
 mod events;
+mod gas_estimation;
+mod graphql;
+mod grpc;
+mod head;
 mod health_check;
+mod idempotency;
 mod index;
 pub(crate) mod log;
+mod mempool;
 mod metrics;
 mod page;
 pub(crate) mod param;
+mod rate_limit;
+mod resource_filter;
+mod response_cache;
 pub mod runtime;
 mod state;
+mod stream;
 mod transactions;
 pub(crate) mod version;
+mod view_function;
+
+pub use context::{Context, NameResolver};
 
 mod failpoint;
 #[cfg(any(test))]
@@ -0,0 +1,93 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{admin::admin_auth, context::Context, metrics::metrics, param::AddressParam};
+
+use aptos_api_types::{Address, AsConverter, Error, Response, Transaction};
+
+use serde::{Deserialize, Serialize};
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct MempoolInfo {
+    size: usize,
+    // the transactions ready to be pulled by consensus
+    priority_queue_size: usize,
+    // the transactions waiting for a preceding sequence number of their sender
+    parking_lot_size: usize,
+    // the senders with the most parked transactions, in descending order
+    parking_lot_accounts: Vec<ParkingLotAccount>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct ParkingLotAccount {
+    address: Address,
+    parked_transactions: usize,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct MempoolTransactionsQuery {
+    sender: AddressParam,
+}
+
+// GET /mempool/info
+pub fn get_mempool_info(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("mempool" / "info")
+        .and(warp::get())
+        .and(admin_auth(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_mempool_info)
+        .with(metrics("get_mempool_info"))
+        .boxed()
+}
+
+// GET /mempool/transactions?sender={address}
+pub fn get_mempool_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("mempool" / "transactions")
+        .and(warp::get())
+        .and(admin_auth(context.clone()))
+        .and(warp::query::<MempoolTransactionsQuery>())
+        .and(context.filter())
+        .and_then(handle_get_mempool_transactions)
+        .with(metrics("get_mempool_transactions"))
+        .boxed()
+}
+
+async fn handle_get_mempool_info(context: Context) -> Result<impl Reply, Rejection> {
+    let info = context.get_mempool_info().await.map_err(Error::from)?;
+    let info = MempoolInfo {
+        size: info.size,
+        priority_queue_size: info.priority_queue_size,
+        parking_lot_size: info.parking_lot_size,
+        parking_lot_accounts: info
+            .parking_lot_accounts
+            .into_iter()
+            .map(|(address, parked_transactions)| ParkingLotAccount {
+                address: address.into(),
+                parked_transactions,
+            })
+            .collect(),
+    };
+    Ok(Response::new(context.get_latest_ledger_info()?, &info)?)
+}
+
+// The transactions of the sender waiting in mempool, ordered by sequence number.
+async fn handle_get_mempool_transactions(
+    query: MempoolTransactionsQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    let ledger_info = context.get_latest_ledger_info()?;
+    let sender = context.resolve_address(query.sender)?;
+    let pending = context
+        .get_pending_account_transactions(sender.into())
+        .await
+        .map_err(Error::from)?;
+    let resolver = context.move_resolver().map_err(Error::from)?;
+    let converter = resolver.as_converter();
+    let txns = pending
+        .into_iter()
+        .map(|txn| converter.try_into_pending_transaction(txn))
+        .collect::<anyhow::Result<Vec<Transaction>>>()
+        .map_err(Error::from)?;
+    Ok(Response::new(ledger_info, &txns)?)
+}
@@ -4,7 +4,10 @@
 use aptos_metrics::{register_histogram_vec, HistogramVec};
 
 use once_cell::sync::Lazy;
-use warp::log::{custom, Info, Log};
+use warp::{
+    http::header::CONTENT_LENGTH,
+    log::{custom, Info, Log},
+};
 
 static HISTOGRAM: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -15,6 +18,16 @@ static HISTOGRAM: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+static REQUEST_PAYLOAD_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_api_request_payload_size_bytes",
+        "API request body size in bytes grouped by method, operation_id and status",
+        &["method", "operation_id", "status"],
+        vec![64.0, 256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0]
+    )
+    .unwrap()
+});
+
 static RESPONSE_STATUS: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         "aptos_api_response_status",
@@ -27,19 +40,33 @@ static RESPONSE_STATUS: Lazy<HistogramVec> = Lazy::new(|| {
 // Record metrics by method, operation_id and status.
 // The operation_id is the id for the request handler.
 // Should use same `operationId` defined in `openapi.yaml` whenever possible.
+// The request body size is taken from the `Content-Length` header, requests without
+// the header (e.g. GET requests) are recorded as empty payloads.
+// Requests rejected by the handler are only recorded by `status_metrics`, as the response
+// of a rejection is created after all routes are tried.
 pub fn metrics(operation_id: &'static str) -> Log<impl Fn(Info) + Copy> {
     let func = move |info: Info| {
+        let method = info.method().to_string();
+        let status = info.status().as_u16().to_string();
+        let labels = [method.as_str(), operation_id, status.as_str()];
         HISTOGRAM
-            .with_label_values(&[
-                info.method().to_string().as_str(),
-                operation_id,
-                info.status().as_u16().to_string().as_str(),
-            ])
+            .with_label_values(&labels)
             .observe(info.elapsed().as_secs_f64());
+        REQUEST_PAYLOAD_SIZE
+            .with_label_values(&labels)
+            .observe(payload_size(&info) as f64);
     };
     custom(func)
 }
 
+fn payload_size(info: &Info) -> u64 {
+    info.request_headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
 // Record metrics by response status.
 // This is for understanding the overview of responses in case server
 // is overloaded by unknown reason.
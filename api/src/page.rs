@@ -1,15 +1,16 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::param::{Param, TransactionVersionParam};
+use crate::param::{MoveStructTagParam, Param, TransactionVersionParam};
 
-use aptos_api_types::{Error, TransactionId};
+use aptos_api_types::{AptosErrorCode, Error, TransactionId};
 
 use anyhow::Result;
+use move_core_types::language_storage::StructTag;
 use serde::Deserialize;
 use std::num::NonZeroU16;
 
-const DEFAULT_PAGE_SIZE: u16 = 25;
+pub(crate) const DEFAULT_PAGE_SIZE: u16 = 25;
 const MAX_PAGE_SIZE: u16 = 1000;
 
 #[derive(Clone, Debug, Deserialize)]
@@ -19,6 +20,10 @@ pub(crate) struct Page {
 }
 
 impl Page {
+    pub fn new(start: Option<TransactionVersionParam>, limit: Option<Param<NonZeroU16>>) -> Self {
+        Self { start, limit }
+    }
+
     pub fn start(&self, default: u64, max: u64) -> Result<u64, Error> {
         let version = self
             .start
@@ -27,6 +32,7 @@ impl Page {
             .unwrap_or_else(|| Ok(default))?;
         if version > max {
             return Err(Error::not_found(
+                AptosErrorCode::TransactionNotFound,
                 "transaction",
                 TransactionId::Version(version),
                 max,
@@ -36,18 +42,44 @@ impl Page {
     }
 
     pub fn limit(&self) -> Result<u16, Error> {
-        let limit = self
-            .limit
+        parse_limit(self.limit.clone()).map(|limit| limit.unwrap_or(DEFAULT_PAGE_SIZE))
+    }
+}
+
+/// `ResourcePage` pages through the resources of an account. Resources have no version
+/// to start from, so the `start` cursor is the struct tag of the first resource to return,
+/// which is the value of the `X-Aptos-Cursor` header returned by the previous page.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ResourcePage {
+    start: Option<MoveStructTagParam>,
+    limit: Option<Param<NonZeroU16>>,
+}
+
+impl ResourcePage {
+    pub fn start(&self) -> Result<Option<StructTag>, Error> {
+        self.start
             .clone()
-            .map(|v| v.parse("limit"))
-            .unwrap_or_else(|| Ok(NonZeroU16::new(DEFAULT_PAGE_SIZE).unwrap()))?
-            .get();
-        if limit > MAX_PAGE_SIZE {
-            return Err(Error::invalid_param(
-                "limit",
-                format!("{}, exceed limit {}", limit, MAX_PAGE_SIZE),
-            ));
-        }
-        Ok(limit)
+            .map(|param| param.parse_struct_tag("start"))
+            .transpose()
+    }
+
+    /// Returns `None` when no limit is given, all the resources are returned in this case
+    /// for backward compatibility.
+    pub fn limit(&self) -> Result<Option<u16>, Error> {
+        parse_limit(self.limit.clone())
+    }
+}
+
+fn parse_limit(limit: Option<Param<NonZeroU16>>) -> Result<Option<u16>, Error> {
+    let limit = match limit {
+        Some(v) => v.parse("limit")?.get(),
+        None => return Ok(None),
+    };
+    if limit > MAX_PAGE_SIZE {
+        return Err(Error::invalid_param(
+            "limit",
+            format!("{}, exceed limit {}", limit, MAX_PAGE_SIZE),
+        ));
     }
+    Ok(Some(limit))
 }
@@ -1,18 +1,24 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_api_types::{Address, Error, EventKey, MoveStructTag, TransactionId};
-use move_core_types::identifier::Identifier;
+use aptos_api_types::{Address, Error, EventKey, HashValue, MoveStructTag, TransactionId};
+use move_core_types::{identifier::Identifier, language_storage::StructTag};
 use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Deserializer};
 
-use std::{convert::Infallible, str::FromStr};
+use std::{
+    convert::{Infallible, TryInto},
+    str::FromStr,
+};
 
 pub type AddressParam = Param<Address>;
 pub type TransactionIdParam = Param<TransactionId>;
+pub type TransactionHashParam = Param<HashValue>;
 pub type TransactionVersionParam = Param<u64>;
 pub type LedgerVersionParam = Param<u64>;
+pub type BlockHeightParam = Param<u64>;
 pub type EventKeyParam = Param<EventKey>;
+pub type EventCreationNumberParam = Param<u64>;
 pub type MoveStructTagParam = Param<MoveStructTag>;
 pub type MoveIdentifierParam = Param<Identifier>;
 
@@ -38,14 +44,33 @@ impl<T: FromStr> FromStr for Param<T> {
 
 impl<T: FromStr> Param<T> {
     pub fn parse(self, name: &str) -> Result<T, Error> {
-        let decoded = percent_decode_str(&self.data)
-            .decode_utf8()
-            .map_err(|_| Error::invalid_param(name, &self.data))?;
-
+        let decoded = self.decode(name)?;
         decoded
             .parse()
             .map_err(|_| Error::invalid_param(name, &decoded))
     }
+
+    /// Returns the percent-decoded string of the parameter.
+    pub fn decode(&self, name: &str) -> Result<String, Error> {
+        percent_decode_str(&self.data)
+            .decode_utf8()
+            .map(|decoded| decoded.into_owned())
+            .map_err(|_| Error::invalid_param(name, &self.data))
+    }
+}
+
+impl MoveStructTagParam {
+    /// Parses the struct tag, which is invalid when one of its type params isn't a valid type;
+    /// `name` is the name of the param in the route, e.g. `resource_type`.
+    pub fn parse_struct_tag(self, name: &str) -> Result<StructTag, Error> {
+        let struct_tag = self.parse(name)?;
+        try_into_struct_tag(struct_tag).map_err(|struct_tag| Error::invalid_param(name, struct_tag))
+    }
+}
+
+/// Converts the struct tag, giving it back when one of its type params isn't a valid type.
+pub fn try_into_struct_tag(struct_tag: MoveStructTag) -> Result<StructTag, MoveStructTag> {
+    struct_tag.clone().try_into().map_err(|_| struct_tag)
 }
 
 /// `Deserialize` is required for parsing `warp` query string parameter into `Param` type.
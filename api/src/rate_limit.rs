@@ -0,0 +1,300 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{access_log::remote_addr, api_key::X_APTOS_API_KEY, context::Context};
+
+use aptos_config::config::ApiRateLimitConfig;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    convert::Infallible,
+    fmt, mem,
+    net::{IpAddr, SocketAddr},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use warp::{
+    reject::{self, Reject},
+    Filter, Rejection,
+};
+
+// When the number of clients tracked since the last rotation of the buckets reaches this
+// number, the buckets are rotated early, which may refill the buckets of the clients of the
+// previous period.
+const MAX_TRACKED_CLIENTS: usize = 100_000;
+
+/// The clients are identified by their API key (`X-Aptos-Api-Key`) when it's a configured
+/// one, otherwise by their remote IP address: an unknown key could be made up for each request.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum ClientKey {
+    ApiKey(String),
+    Ip(IpAddr),
+}
+
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn full(capacity: f64, now: Instant) -> Self {
+        Self {
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant, rate: f64, capacity: f64) -> f64 {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * rate).min(capacity);
+        self.last_refill = now;
+        self.tokens
+    }
+
+    /// Takes a token from the bucket; when the bucket is empty, returns how long to wait for
+    /// the next token.
+    pub fn acquire(&mut self, now: Instant, rate: f64, capacity: f64) -> Result<(), Duration> {
+        if self.refill(now, rate, capacity) >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+}
+
+/// The buckets of the clients, in two generations: the buckets used since the last rotation,
+/// and the ones only used in the period before it. A bucket is moved to the current generation
+/// when it's used again, so the buckets dropped by a rotation were unused for at least a whole
+/// period, which is long enough for them to refill: a full bucket is no different from a new
+/// one.
+struct Buckets {
+    current: HashMap<ClientKey, TokenBucket>,
+    previous: HashMap<ClientKey, TokenBucket>,
+    rotated_at: Instant,
+}
+
+pub(crate) struct RateLimiter {
+    // tokens refilled per second
+    rate: f64,
+    capacity: f64,
+    // the time it takes for an empty bucket to refill
+    refill_period: Duration,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &ApiRateLimitConfig) -> Self {
+        let rate = config.requests_per_second.max(1) as f64;
+        let capacity = config.burst_size.max(1) as f64;
+        Self {
+            rate,
+            capacity,
+            refill_period: Duration::from_secs_f64(capacity / rate),
+            buckets: Mutex::new(Buckets {
+                current: HashMap::new(),
+                previous: HashMap::new(),
+                rotated_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token from the bucket of the client; when the bucket is empty, returns how
+    /// long the client should wait for the next token.
+    fn acquire(&self, key: ClientKey, now: Instant) -> Result<(), Duration> {
+        let (rate, capacity) = (self.rate, self.capacity);
+        let mut buckets = self.buckets.lock().unwrap();
+        let mut expired = None;
+        if now.saturating_duration_since(buckets.rotated_at) >= self.refill_period
+            || buckets.current.len() >= MAX_TRACKED_CLIENTS
+        {
+            let current = mem::take(&mut buckets.current);
+            expired = Some(mem::replace(&mut buckets.previous, current));
+            buckets.rotated_at = now;
+        }
+
+        let Buckets {
+            current, previous, ..
+        } = &mut *buckets;
+        let bucket = match current.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let bucket = previous
+                    .remove(entry.key())
+                    .unwrap_or_else(|| TokenBucket::full(capacity, now));
+                entry.insert(bucket)
+            }
+        };
+        let result = bucket.acquire(now, rate, capacity);
+        // the expired buckets are dropped without holding the lock
+        drop(buckets);
+        drop(expired);
+        result
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimited {
+    // the limit the client runs out of, e.g. "rate limit"
+    limit: &'static str,
+    retry_after: Duration,
+}
+
+impl RateLimited {
+    pub fn new(limit: &'static str, retry_after: Duration) -> Self {
+        Self { limit, retry_after }
+    }
+
+    /// The value of the `Retry-After` response header, in whole seconds.
+    pub fn retry_after_secs(&self) -> u64 {
+        self.retry_after.as_secs_f64().ceil().max(1.0) as u64
+    }
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} exceeded, retry after {} seconds",
+            self.limit,
+            self.retry_after_secs()
+        )
+    }
+}
+
+impl Reject for RateLimited {}
+
+/// Identifies the client of the request, the client is unknown when the server is not bound to
+/// a socket and the request has no configured API key.
+pub(crate) fn client_key(
+    context: Context,
+) -> impl Filter<Extract = (Option<ClientKey>,), Error = Infallible> + Clone {
+    remote_addr()
+        .and(warp::header::optional::<String>(X_APTOS_API_KEY))
+        .and(context.filter())
+        .map(
+            |remote: Option<SocketAddr>, api_key: Option<String>, context: Context| {
+                let api_key = api_key.filter(|api_key| {
+                    context
+                        .api_keys()
+                        .map_or(false, |api_keys| api_keys.contains(api_key))
+                });
+                match (api_key, remote) {
+                    (Some(api_key), _) => Some(ClientKey::ApiKey(api_key)),
+                    (None, Some(addr)) => Some(ClientKey::Ip(addr.ip())),
+                    (None, None) => None,
+                }
+            },
+        )
+}
+
+/// Rejects the request with `RateLimited` when the client runs out of its rate limit,
+/// passes all requests when rate limiting is not configured.
+pub(crate) fn rate_limit(context: Context) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    client_key(context.clone())
+        .and(context.filter())
+        .and_then(|key: Option<ClientKey>, context: Context| async move {
+            let (limiter, key) = match (context.rate_limiter(), key) {
+                (Some(limiter), Some(key)) => (limiter, key),
+                _ => return Ok(()),
+            };
+            limiter
+                .acquire(key, Instant::now())
+                .map_err(|retry_after| reject::custom(RateLimited::new("rate limit", retry_after)))
+        })
+        .untuple_one()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientKey, RateLimited, RateLimiter};
+    use aptos_config::config::ApiRateLimitConfig;
+    use std::time::{Duration, Instant};
+
+    fn new_limiter(requests_per_second: u32, burst_size: u32) -> RateLimiter {
+        RateLimiter::new(&ApiRateLimitConfig {
+            requests_per_second,
+            burst_size,
+        })
+    }
+
+    #[test]
+    fn test_acquire_up_to_burst_size() {
+        let limiter = new_limiter(1, 3);
+        let now = Instant::now();
+        let key = ClientKey::ApiKey("key".to_owned());
+        for _ in 0..3 {
+            assert!(limiter.acquire(key.clone(), now).is_ok());
+        }
+        assert_eq!(
+            limiter.acquire(key.clone(), now),
+            Err(Duration::from_secs(1))
+        );
+
+        // another client has its own bucket
+        assert!(limiter
+            .acquire(ClientKey::Ip("127.0.0.1".parse().unwrap()), now)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_acquire_after_refill() {
+        let limiter = new_limiter(2, 2);
+        let now = Instant::now();
+        let key = ClientKey::Ip("127.0.0.1".parse().unwrap());
+        assert!(limiter.acquire(key.clone(), now).is_ok());
+        assert!(limiter.acquire(key.clone(), now).is_ok());
+        assert_eq!(
+            limiter.acquire(key.clone(), now),
+            Err(Duration::from_millis(500))
+        );
+
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.acquire(key.clone(), later).is_ok());
+        assert!(limiter.acquire(key, later).is_err());
+    }
+
+    #[test]
+    fn test_evict_refilled_buckets() {
+        // the buckets refill in 2 seconds
+        let limiter = new_limiter(1, 2);
+        let now = limiter.buckets.lock().unwrap().rotated_at;
+        let (first, second) = (
+            ClientKey::Ip("127.0.0.1".parse().unwrap()),
+            ClientKey::Ip("127.0.0.2".parse().unwrap()),
+        );
+        assert!(limiter.acquire(first.clone(), now).is_ok());
+        assert!(limiter.acquire(first.clone(), now).is_ok());
+        assert!(limiter.acquire(second.clone(), now).is_ok());
+
+        // the buckets used again are kept by the rotation, even if they are refilled
+        let later = now + Duration::from_secs(2);
+        assert!(limiter.acquire(first.clone(), later).is_ok());
+        assert!(limiter.acquire(first.clone(), later).is_ok());
+        assert!(limiter.acquire(first.clone(), later).is_err());
+        {
+            let buckets = limiter.buckets.lock().unwrap();
+            assert_eq!(buckets.current.len(), 1);
+            assert!(buckets.previous.contains_key(&second));
+        }
+
+        // the buckets unused for a whole period are full again, so they are dropped
+        let even_later = later + Duration::from_secs(2);
+        assert!(limiter.acquire(first.clone(), even_later).is_ok());
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.current.len(), 1);
+        assert!(buckets.previous.is_empty());
+    }
+
+    #[test]
+    fn test_retry_after_secs() {
+        let rejection = RateLimited::new("rate limit", Duration::from_millis(10));
+        assert_eq!(rejection.retry_after_secs(), 1);
+        let rejection = RateLimited::new("rate limit", Duration::from_millis(1500));
+        assert_eq!(rejection.retry_after_secs(), 2);
+        assert_eq!(
+            rejection.to_string(),
+            "rate limit exceeded, retry after 2 seconds"
+        );
+    }
+}
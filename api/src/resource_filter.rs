@@ -0,0 +1,89 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::param::Param;
+
+use aptos_api_types::Error;
+use move_core_types::{account_address::AccountAddress, language_storage::StructTag};
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// `ResourceFilterQuery` keeps only the resources whose type starts with the `filter` prefix,
+/// e.g. `0x1::Coin::` for the resources defined by the `0x1::Coin` module.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ResourceFilterQuery {
+    filter: Option<Param<ResourceFilter>>,
+}
+
+impl ResourceFilterQuery {
+    pub fn parse(self) -> Result<Option<ResourceFilter>, Error> {
+        self.filter.map(|param| param.parse("filter")).transpose()
+    }
+}
+
+/// A prefix of resource types. The address is compared by value, so `0x1::` and
+/// `0x00000000000000000000000000000001::` are the same prefix; the rest of the prefix is
+/// compared by string to the module name, struct name and type parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ResourceFilter {
+    address: AccountAddress,
+    rest: String,
+}
+
+impl ResourceFilter {
+    pub fn matches(&self, struct_tag: &StructTag) -> bool {
+        if struct_tag.address != self.address {
+            return false;
+        }
+        let tag = struct_tag.to_string();
+        match tag.split_once("::") {
+            Some((_, rest)) => rest.starts_with(&self.rest),
+            None => false,
+        }
+    }
+}
+
+impl FromStr for ResourceFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, rest) = s.split_once("::").unwrap_or((s, ""));
+        Ok(Self {
+            address: AccountAddress::from_hex_literal(address)?,
+            rest: rest.to_owned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResourceFilter;
+    use move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::StructTag,
+    };
+
+    fn struct_tag(module: &str, name: &str) -> StructTag {
+        StructTag {
+            address: AccountAddress::ONE,
+            module: Identifier::new(module).unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_params: vec![],
+        }
+    }
+
+    #[test]
+    fn test_matches_prefix() {
+        let filter: ResourceFilter = "0x1::Coin::".parse().unwrap();
+        assert!(filter.matches(&struct_tag("Coin", "CoinStore")));
+        assert!(!filter.matches(&struct_tag("CoinInfo", "CoinStore")));
+        assert!(!filter.matches(&struct_tag("Account", "Account")));
+
+        let filter: ResourceFilter = "0x00000000000000000000000000000001".parse().unwrap();
+        assert!(filter.matches(&struct_tag("Account", "Account")));
+    }
+
+    #[test]
+    fn test_parse_invalid_address() {
+        assert!("Coin::CoinStore".parse::<ResourceFilter>().is_err());
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::{LedgerInfo, Response};
+use aptos_config::config::ApiResponseCacheConfig;
+use aptos_types::transaction::Version;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+};
+use warp::http::StatusCode;
+
+/// Identifies an immutable response by the endpoint and the id of the requested data, the
+/// id includes the request options changing the rendering, e.g. the accept type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct ResponseCacheKey {
+    endpoint: &'static str,
+    id: String,
+}
+
+impl ResponseCacheKey {
+    pub fn new(endpoint: &'static str, id: String) -> Self {
+        Self { endpoint, id }
+    }
+}
+
+/// The body of a response that never changes; the ledger info headers are not cached, they
+/// are of the ledger at the time of the request.
+#[derive(Clone, Debug)]
+pub(crate) struct CachedResponse {
+    /// The first version of the data rendered in the body, the cached response is not served
+    /// once the version is pruned.
+    pub version: Version,
+    body: Vec<u8>,
+    content_type: &'static str,
+}
+
+impl CachedResponse {
+    pub fn into_response(self, ledger_info: LedgerInfo) -> Response {
+        Response {
+            ledger_info,
+            body: self.body,
+            content_type: self.content_type,
+            cursor: None,
+            etag: None,
+            status: StatusCode::OK,
+        }
+    }
+}
+
+/// LRU cache of the responses of immutable data, e.g. committed transactions and the blocks
+/// before the latest block. The least recently used responses are evicted when either the
+/// number of responses or the total size of their bodies exceeds the limits of the config.
+pub(crate) struct ResponseCache {
+    max_entries: usize,
+    max_bytes: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    // key => (last use, response)
+    entries: HashMap<ResponseCacheKey, (u64, CachedResponse)>,
+    // last use => key
+    uses: BTreeMap<u64, ResponseCacheKey>,
+    bytes: usize,
+    clock: u64,
+}
+
+impl ResponseCache {
+    pub fn new(config: &ApiResponseCacheConfig) -> Self {
+        Self {
+            max_entries: config.max_entries,
+            max_bytes: config.max_bytes,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    pub fn get(&self, key: &ResponseCacheKey) -> Option<CachedResponse> {
+        if self.max_entries == 0 {
+            return None;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let clock = inner.clock;
+        let (last_use, response) = inner.entries.get_mut(key)?;
+        let previous_use = std::mem::replace(last_use, clock);
+        let response = response.clone();
+        inner.uses.remove(&previous_use);
+        inner.uses.insert(clock, key.clone());
+        Some(response)
+    }
+
+    /// Caches the successful response rendering data from `version`, the response larger
+    /// than the max total size is not cached.
+    pub fn insert(&self, key: ResponseCacheKey, version: Version, response: &Response) {
+        if self.max_entries == 0
+            || response.status != StatusCode::OK
+            || response.body.len() > self.max_bytes
+        {
+            return;
+        }
+        let response = CachedResponse {
+            version,
+            body: response.body.clone(),
+            content_type: response.content_type,
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.clock += 1;
+        let clock = inner.clock;
+        inner.bytes += response.body.len();
+        if let Some((previous_use, previous)) = inner.entries.insert(key.clone(), (clock, response))
+        {
+            inner.uses.remove(&previous_use);
+            inner.bytes -= previous.body.len();
+        }
+        inner.uses.insert(clock, key);
+
+        while inner.entries.len() > self.max_entries || inner.bytes > self.max_bytes {
+            let oldest = match inner.uses.keys().next() {
+                Some(oldest) => *oldest,
+                None => break,
+            };
+            if let Some(key) = inner.uses.remove(&oldest) {
+                if let Some((_, evicted)) = inner.entries.remove(&key) {
+                    inner.bytes -= evicted.body.len();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResponseCache, ResponseCacheKey};
+    use aptos_api_types::{LedgerInfo, Response};
+    use aptos_config::config::ApiResponseCacheConfig;
+    use aptos_types::chain_id::ChainId;
+
+    fn ledger_info() -> LedgerInfo {
+        LedgerInfo {
+            chain_id: ChainId::test().id(),
+            epoch: 1,
+            ledger_version: 10.into(),
+            ledger_timestamp: 100.into(),
+        }
+    }
+
+    fn response(body: &str) -> Response {
+        Response::new(ledger_info(), &body).unwrap()
+    }
+
+    fn key(id: &str) -> ResponseCacheKey {
+        ResponseCacheKey::new("test", id.to_owned())
+    }
+
+    #[test]
+    fn test_evict_least_recently_used_response() {
+        let cache = ResponseCache::new(&ApiResponseCacheConfig {
+            max_entries: 2,
+            max_bytes: 1024,
+        });
+        cache.insert(key("a"), 1, &response("a"));
+        cache.insert(key("b"), 2, &response("b"));
+        assert!(cache.get(&key("a")).is_some());
+
+        cache.insert(key("c"), 3, &response("c"));
+        assert_eq!(cache.get(&key("a")).unwrap().version, 1);
+        assert!(cache.get(&key("b")).is_none());
+        let cached = cache.get(&key("c")).unwrap().into_response(ledger_info());
+        assert_eq!(cached.body, response("c").body);
+    }
+
+    #[test]
+    fn test_evict_when_max_bytes_is_exceeded() {
+        // a response body is the 3 bytes of a JSON string of one character
+        let cache = ResponseCache::new(&ApiResponseCacheConfig {
+            max_entries: 10,
+            max_bytes: 7,
+        });
+        cache.insert(key("a"), 1, &response("a"));
+        cache.insert(key("b"), 2, &response("b"));
+        cache.insert(key("c"), 3, &response("c"));
+        assert!(cache.get(&key("a")).is_none());
+        assert!(cache.get(&key("b")).is_some());
+        assert!(cache.get(&key("c")).is_some());
+        assert_eq!(cache.inner.lock().unwrap().bytes, 6);
+
+        // too large to be cached
+        cache.insert(key("d"), 4, &response("dddddddd"));
+        assert!(cache.get(&key("d")).is_none());
+    }
+
+    #[test]
+    fn test_disabled_when_max_entries_is_zero() {
+        let cache = ResponseCache::new(&ApiResponseCacheConfig {
+            max_entries: 0,
+            max_bytes: 1024,
+        });
+        cache.insert(key("a"), 1, &response("a"));
+        assert!(cache.get(&key("a")).is_none());
+    }
+}
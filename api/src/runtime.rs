@@ -1,12 +1,19 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{context::Context, index};
+use crate::{
+    context::{Context, NameResolver},
+    grpc, index,
+};
 
-use aptos_config::config::{ApiConfig, JsonRpcConfig, NodeConfig};
+use anyhow::ensure;
+use aptos_config::config::{ApiListenAddress, JsonRpcConfig, NodeConfig};
 use aptos_mempool::MempoolClientSender;
-use aptos_types::chain_id::ChainId;
-use storage_interface::DbReader;
+use aptos_types::{
+    chain_id::ChainId, download_stats::DownloadStatsReporter, liveness::LivenessReporter,
+};
+use futures::future::join_all;
+use storage_interface::{DbCheckpointer, DbReader};
 use warp::{Filter, Reply};
 
 use std::{convert::Infallible, net::SocketAddr, sync::Arc};
@@ -23,19 +30,107 @@ pub fn bootstrap(
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
 ) -> anyhow::Result<Runtime> {
+    bootstrap_impl(
+        config,
+        chain_id,
+        db,
+        mp_sender,
+        None,
+        NodeComponents::default(),
+    )
+}
+
+/// Same as `bootstrap`, in addition the requests can address accounts by the names
+/// registered to the `name_resolver`.
+pub fn bootstrap_with_name_resolver(
+    config: &NodeConfig,
+    chain_id: ChainId,
+    db: Arc<dyn DbReader>,
+    mp_sender: MempoolClientSender,
+    name_resolver: Option<Arc<dyn NameResolver>>,
+) -> anyhow::Result<Runtime> {
+    bootstrap_impl(
+        config,
+        chain_id,
+        db,
+        mp_sender,
+        name_resolver,
+        NodeComponents::default(),
+    )
+}
+
+/// The components of the node the admin API operates on and reports about.
+#[derive(Clone, Default)]
+pub struct NodeComponents {
+    /// Creates the checkpoints of the DB, in the `checkpoint_dir` of the API config.
+    pub db_checkpointer: Option<Arc<dyn DbCheckpointer>>,
+    /// Throttles the downloads of state sync, whose stats are reported.
+    pub download_throttle: Option<Arc<dyn DownloadStatsReporter>>,
+    /// The liveness of consensus, on the validators, which is reported.
+    pub liveness_diagnostics: Option<Arc<dyn LivenessReporter>>,
+}
+
+/// Same as `bootstrap`, in addition the admin API operates on the `components` of the node.
+pub fn bootstrap_with_node_components(
+    config: &NodeConfig,
+    chain_id: ChainId,
+    db: Arc<dyn DbReader>,
+    mp_sender: MempoolClientSender,
+    components: NodeComponents,
+) -> anyhow::Result<Runtime> {
+    bootstrap_impl(config, chain_id, db, mp_sender, None, components)
+}
+
+fn bootstrap_impl(
+    config: &NodeConfig,
+    chain_id: ChainId,
+    db: Arc<dyn DbReader>,
+    mp_sender: MempoolClientSender,
+    name_resolver: Option<Arc<dyn NameResolver>>,
+    components: NodeComponents,
+) -> anyhow::Result<Runtime> {
+    let api_config = config.api.clone();
+    let role = config.base.role;
+    let servers = api_config
+        .listen_addresses()
+        .into_iter()
+        .map(WebServer::from)
+        .collect::<Vec<_>>();
+    for (i, server) in servers.iter().enumerate() {
+        server.validate()?;
+        ensure!(
+            servers[..i].iter().all(|s| s.address != server.address),
+            "API address {} is configured more than once",
+            server.address
+        );
+    }
+
     let runtime = Builder::new_multi_thread()
         .thread_name("api")
         .enable_all()
         .build()
         .expect("[api] failed to create runtime");
 
-    let api_config = config.api.clone();
-    let api = WebServer::from(api_config.clone());
-
     runtime.spawn(async move {
-        let context = Context::new(chain_id, db, mp_sender, api_config);
+        let grpc_address = api_config.grpc_address;
+        let mut context = Context::new(chain_id, db, mp_sender, api_config).with_role(role);
+        if let Some(name_resolver) = name_resolver {
+            context = context.with_name_resolver(name_resolver);
+        }
+        if let Some(db_checkpointer) = components.db_checkpointer {
+            context = context.with_db_checkpointer(db_checkpointer);
+        }
+        if let Some(download_throttle) = components.download_throttle {
+            context = context.with_download_throttle(download_throttle);
+        }
+        if let Some(liveness_diagnostics) = components.liveness_diagnostics {
+            context = context.with_liveness_diagnostics(liveness_diagnostics);
+        }
+        if let Some(address) = grpc_address {
+            tokio::spawn(grpc::serve(context.clone(), address));
+        }
         let routes = index::routes(context);
-        api.serve(routes).await;
+        join_all(servers.iter().map(|server| server.serve(routes.clone()))).await;
     });
     Ok(runtime)
 }
@@ -45,17 +140,23 @@ struct WebServer {
     pub address: SocketAddr,
     pub tls_cert_path: Option<String>,
     pub tls_key_path: Option<String>,
+    pub tls_client_ca_path: Option<String>,
 }
 
-impl From<ApiConfig> for WebServer {
-    fn from(cfg: ApiConfig) -> Self {
-        Self::new(cfg.address, cfg.tls_cert_path, cfg.tls_key_path)
+impl From<ApiListenAddress> for WebServer {
+    fn from(cfg: ApiListenAddress) -> Self {
+        Self::new(
+            cfg.address,
+            cfg.tls_cert_path,
+            cfg.tls_key_path,
+            cfg.tls_client_ca_path,
+        )
     }
 }
 
 impl From<JsonRpcConfig> for WebServer {
     fn from(cfg: JsonRpcConfig) -> Self {
-        Self::new(cfg.address, cfg.tls_cert_path, cfg.tls_key_path)
+        Self::new(cfg.address, cfg.tls_cert_path, cfg.tls_key_path, None)
     }
 }
 
@@ -64,29 +165,52 @@ impl WebServer {
         address: SocketAddr,
         tls_cert_path: Option<String>,
         tls_key_path: Option<String>,
+        tls_client_ca_path: Option<String>,
     ) -> Self {
         Self {
             address,
             tls_cert_path,
             tls_key_path,
+            tls_client_ca_path,
         }
     }
 
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.tls_cert_path.is_some() == self.tls_key_path.is_some(),
+            "API address {}: TLS requires both the certificate and key paths",
+            self.address
+        );
+        ensure!(
+            self.tls_client_ca_path.is_none() || self.tls_cert_path.is_some(),
+            "API address {}: client certificate authentication requires TLS",
+            self.address
+        );
+        Ok(())
+    }
+
     pub async fn serve<F>(&self, routes: F)
     where
         F: Filter<Error = Infallible> + Clone + Sync + Send + 'static,
         F::Extract: Reply,
     {
-        match &self.tls_cert_path {
-            None => warp::serve(routes).bind(self.address).await,
-            Some(cert_path) => {
-                warp::serve(routes)
+        match (&self.tls_cert_path, &self.tls_key_path) {
+            (Some(cert_path), Some(key_path)) => {
+                let server = warp::serve(routes)
                     .tls()
                     .cert_path(cert_path)
-                    .key_path(self.tls_key_path.as_ref().unwrap())
-                    .bind(self.address)
-                    .await
+                    .key_path(key_path);
+                match &self.tls_client_ca_path {
+                    Some(ca_path) => {
+                        server
+                            .client_auth_required_path(ca_path)
+                            .bind(self.address)
+                            .await
+                    }
+                    None => server.bind(self.address).await,
+                }
             }
+            _ => warp::serve(routes).bind(self.address).await,
         }
     }
 }
@@ -95,11 +219,11 @@ impl WebServer {
 mod tests {
     use std::time::Duration;
 
-    use aptos_config::config::NodeConfig;
+    use aptos_config::config::{ApiListenAddress, NodeConfig};
     use aptos_types::chain_id::ChainId;
 
     use crate::{
-        runtime::bootstrap,
+        runtime::{bootstrap, WebServer},
         tests::{new_test_context, TestContext},
     };
 
@@ -110,6 +234,63 @@ mod tests {
         bootstrap_with_config(cfg);
     }
 
+    #[test]
+    fn test_bootstrap_api_with_additional_addresses() {
+        let mut cfg = NodeConfig::default();
+        cfg.api.additional_addresses = vec![ApiListenAddress {
+            address: cfg.api.address,
+            tls_cert_path: None,
+            tls_key_path: None,
+            tls_client_ca_path: None,
+        }];
+        cfg.randomize_ports();
+        assert_ne!(cfg.api.address, cfg.api.additional_addresses[0].address);
+        bootstrap_with_config(cfg);
+    }
+
+    #[test]
+    fn test_validate_tls_config() {
+        let server = |cert: Option<&str>, key: Option<&str>, ca: Option<&str>| {
+            WebServer::new(
+                "127.0.0.1:8080".parse().unwrap(),
+                cert.map(str::to_owned),
+                key.map(str::to_owned),
+                ca.map(str::to_owned),
+            )
+        };
+        assert!(server(None, None, None).validate().is_ok());
+        assert!(server(Some("cert"), Some("key"), None).validate().is_ok());
+        assert!(server(Some("cert"), Some("key"), Some("ca"))
+            .validate()
+            .is_ok());
+        assert!(server(Some("cert"), None, None).validate().is_err());
+        assert!(server(None, Some("key"), None).validate().is_err());
+        assert!(server(None, None, Some("ca")).validate().is_err());
+    }
+
+    #[test]
+    fn test_bootstrap_api_with_duplicated_addresses() {
+        let mut cfg = NodeConfig::default();
+        cfg.randomize_ports();
+        cfg.api.additional_addresses = vec![ApiListenAddress {
+            address: cfg.api.address,
+            tls_cert_path: None,
+            tls_key_path: None,
+            tls_client_ca_path: None,
+        }];
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let context = runtime.block_on(new_test_context_async(
+            "test_bootstrap_api_with_duplicated_addresses",
+        ));
+        let ret = bootstrap(
+            &cfg,
+            ChainId::test(),
+            context.db.clone(),
+            context.mempool.ac_client.clone(),
+        );
+        assert!(ret.is_err());
+    }
+
     pub fn bootstrap_with_config(cfg: NodeConfig) {
         let runtime = tokio::runtime::Runtime::new().unwrap();
         let context = runtime.block_on(new_test_context_async(
@@ -123,7 +304,9 @@ mod tests {
         );
         assert!(ret.is_ok());
 
-        assert_web_server(cfg.api.address.port());
+        for listen_address in cfg.api.listen_addresses() {
+            assert_web_server(listen_address.address.port());
+        }
     }
 
     pub fn assert_web_server(port: u16) {
@@ -2,14 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    accept_type::{accept_type, AcceptType},
+    api_key::api_key,
+    bytes_encoding::BytesEncodingQuery,
     context::Context,
     failpoint::fail_point,
+    head::get_or_head,
     metrics::metrics,
-    param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam},
+    param::{
+        try_into_struct_tag, AddressParam, LedgerVersionParam, MoveIdentifierParam,
+        MoveStructTagParam,
+    },
     version::Version,
 };
 use aptos_api_types::{
-    AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response, TransactionId,
+    AptosErrorCode, AsConverter, BytesEncoding, Error, LedgerInfo, MoveModuleBytecode,
+    MoveResource, MoveStructTag, Response, TransactionId,
 };
 use aptos_state_view::StateView;
 use aptos_types::{access_path::AccessPath, state_store::state_key::StateKey};
@@ -19,29 +27,67 @@ use move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, ResourceKey, StructTag},
 };
-use std::convert::TryInto;
 use storage_interface::state_view::DbStateView;
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
-// GET /accounts/<address>/resource/<resource_type>
+// Max number of resources can be requested by one batch request.
+const MAX_RESOURCES_BATCH_SIZE: usize = 100;
+
+// GET /accounts/<address>/resource/<resource_type>?bytes_encoding={encoding}
 pub fn get_account_resource(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "resource" / MoveStructTagParam)
-        .and(warp::get())
+        .and(get_or_head())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, struct_tag, ctx, version: Version| {
-            (version.version, address, struct_tag, ctx)
-        })
+        .and(warp::query::<BytesEncodingQuery>())
+        .and(accept_type())
+        .map(
+            |address, struct_tag, ctx, version: Version, bytes_encoding, accept_type| {
+                (
+                    version.version,
+                    address,
+                    struct_tag,
+                    bytes_encoding,
+                    accept_type,
+                    ctx,
+                )
+            },
+        )
         .untuple_one()
         .and_then(handle_get_account_resource)
         .with(metrics("get_account_resource"))
         .boxed()
 }
 
+// POST /accounts/<address>/resources/batch?bytes_encoding={encoding}
+pub fn get_account_resources_batch(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "resources" / "batch")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<Vec<MoveStructTag>>())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .and(warp::query::<BytesEncodingQuery>())
+        .map(
+            |address, struct_tags, ctx, version: Version, bytes_encoding| {
+                (version.version, address, struct_tags, bytes_encoding, ctx)
+            },
+        )
+        .untuple_one()
+        .and_then(handle_get_account_resources_batch)
+        .with(metrics("get_account_resources_batch"))
+        .boxed()
+}
+
 // GET /state/module/<address>/<module_name>
 pub fn get_account_module(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "module" / MoveIdentifierParam)
-        .and(warp::get())
+        .and(get_or_head())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and(warp::query::<Version>())
         .map(|address, name, ctx, version: Version| (version.version, address, name, ctx))
@@ -55,16 +101,51 @@ async fn handle_get_account_resource(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
     struct_tag: MoveStructTagParam,
+    bytes_encoding: BytesEncodingQuery,
+    accept_type: AcceptType,
     context: Context,
 ) -> anyhow::Result<impl Reply, Rejection> {
     fail_point("endpoint_query_resource")?;
-    let struct_tag = struct_tag.parse("struct tag")?;
+    let struct_tag = struct_tag.parse_struct_tag("resource_type")?;
+    let bytes_encoding = bytes_encoding.parse()?;
+    let address = context.resolve_address(address)?;
     Ok(State::new(ledger_version, context)?.resource(
-        address.parse("account address")?.into(),
-        struct_tag
-            .clone()
-            .try_into()
-            .map_err(|_| Error::invalid_param("resource_type", struct_tag))?,
+        address.into(),
+        struct_tag,
+        bytes_encoding,
+        accept_type,
+    )?)
+}
+
+async fn handle_get_account_resources_batch(
+    ledger_version: Option<LedgerVersionParam>,
+    address: AddressParam,
+    struct_tags: Vec<MoveStructTag>,
+    bytes_encoding: BytesEncodingQuery,
+    context: Context,
+) -> anyhow::Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_resources_batch")?;
+    let bytes_encoding = bytes_encoding.parse()?;
+    if struct_tags.len() > MAX_RESOURCES_BATCH_SIZE {
+        return Err(Error::invalid_request_body(format!(
+            "{} resources requested, exceed limit {}",
+            struct_tags.len(),
+            MAX_RESOURCES_BATCH_SIZE
+        ))
+        .into());
+    }
+    let struct_tags = struct_tags
+        .into_iter()
+        .map(|tag| {
+            try_into_struct_tag(tag)
+                .map_err(|tag| Error::invalid_request_body(format!("invalid struct tag: {}", tag)))
+        })
+        .collect::<Result<Vec<StructTag>, Error>>()?;
+    let address = context.resolve_address(address)?;
+    Ok(State::new(ledger_version, context)?.resources_batch(
+        address.into(),
+        struct_tags,
+        bytes_encoding,
     )?)
 }
 
@@ -75,10 +156,8 @@ async fn handle_get_account_module(
     context: Context,
 ) -> anyhow::Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_module")?;
-    Ok(State::new(ledger_version, context)?.module(
-        address.parse("account address")?.into(),
-        name.parse("module name")?,
-    )?)
+    let address = context.resolve_address(address)?;
+    Ok(State::new(ledger_version, context)?.module(address.into(), name.parse("module name")?)?)
 }
 
 pub(crate) struct State {
@@ -99,11 +178,13 @@ impl State {
 
         if ledger_version > latest_ledger_info.version() {
             return Err(Error::not_found(
+                AptosErrorCode::VersionNotFound,
                 "ledger",
                 TransactionId::Version(ledger_version),
                 latest_ledger_info.version(),
             ));
         }
+        context.ensure_state_not_pruned(ledger_version, &latest_ledger_info)?;
 
         let state_view = context.state_view_at_version(ledger_version)?;
 
@@ -118,23 +199,54 @@ impl State {
         self,
         address: AccountAddress,
         struct_tag: StructTag,
+        bytes_encoding: BytesEncoding,
+        accept_type: AcceptType,
     ) -> Result<impl Reply, Error> {
-        let resource_key = ResourceKey::new(address, struct_tag.clone());
-        let access_path = AccessPath::resource_access_path(resource_key.clone());
-        let state_key = StateKey::AccessPath(access_path);
         let bytes = self
-            .state_view
-            .get_state_value(&state_key)?
-            .ok_or_else(|| Error::not_found("Resource", resource_key, self.ledger_version))?;
+            .resource_bytes(address, struct_tag.clone())?
+            .ok_or_else(|| {
+                Error::not_found(
+                    AptosErrorCode::ResourceNotFound,
+                    "Resource",
+                    ResourceKey::new(address, struct_tag.clone()),
+                    self.ledger_version,
+                )
+            })?;
+
+        if accept_type == AcceptType::Bcs {
+            return Ok(Response::new_bcs_bytes(self.latest_ledger_info, bytes));
+        }
 
         let resource = self
             .state_view
             .as_move_resolver()
             .as_converter()
+            .with_bytes_encoding(bytes_encoding)
             .try_into_resource(&struct_tag, &bytes)?;
         Response::new(self.latest_ledger_info, &resource)
     }
 
+    /// Renders the resources in the order of the given struct tags, the resource not found
+    /// is rendered as `null`.
+    pub fn resources_batch(
+        self,
+        address: AccountAddress,
+        struct_tags: Vec<StructTag>,
+        bytes_encoding: BytesEncoding,
+    ) -> Result<impl Reply, Error> {
+        let resolver = self.state_view.as_move_resolver();
+        let converter = resolver.as_converter().with_bytes_encoding(bytes_encoding);
+        let resources = struct_tags
+            .into_iter()
+            .map(|struct_tag| {
+                self.resource_bytes(address, struct_tag.clone())?
+                    .map(|bytes| converter.try_into_resource(&struct_tag, &bytes))
+                    .transpose()
+            })
+            .collect::<anyhow::Result<Vec<Option<MoveResource>>>>()?;
+        Response::new(self.latest_ledger_info.clone(), &resources)
+    }
+
     pub fn module(self, address: AccountAddress, name: Identifier) -> Result<impl Reply, Error> {
         let module_id = ModuleId::new(address, name);
         let access_path = AccessPath::code_access_path(module_id.clone());
@@ -142,11 +254,29 @@ impl State {
         let bytes = self
             .state_view
             .get_state_value(&state_key)?
-            .ok_or_else(|| Error::not_found("Module", module_id, self.ledger_version))?;
+            .ok_or_else(|| {
+                Error::not_found(
+                    AptosErrorCode::ModuleNotFound,
+                    "Module",
+                    module_id,
+                    self.ledger_version,
+                )
+            })?;
 
         let module = MoveModuleBytecode::new(bytes)
             .try_parse_abi()
             .map_err(Error::internal)?;
         Response::new(self.latest_ledger_info, &module)
     }
+
+    fn resource_bytes(
+        &self,
+        address: AccountAddress,
+        struct_tag: StructTag,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let resource_key = ResourceKey::new(address, struct_tag);
+        let access_path = AccessPath::resource_access_path(resource_key);
+        self.state_view
+            .get_state_value(&StateKey::AccessPath(access_path))
+    }
 }
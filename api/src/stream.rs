@@ -0,0 +1,158 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    api_key::api_key,
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    param::{AddressParam, EventKeyParam},
+};
+
+use aptos_api_types::{AptosErrorCode, AsConverter, Error, Event, U64};
+use aptos_logger::debug;
+use aptos_types::{
+    account_address::AccountAddress, contract_event::ContractEvent, event::EventKey,
+    transaction::Version,
+};
+
+use anyhow::Result;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use warp::{
+    filters::BoxedFilter,
+    ws::{Message, WebSocket, Ws},
+    Filter, Rejection, Reply,
+};
+
+// Websocket close code for policy violation, used when the subscriber falls too far behind.
+const CLOSE_CODE_POLICY_VIOLATION: u16 = 1008;
+// Websocket close code for unexpected server error.
+const CLOSE_CODE_INTERNAL_ERROR: u16 = 1011;
+
+#[derive(Clone, Debug, Deserialize)]
+struct EventStreamParams {
+    key: Option<EventKeyParam>,
+    address: Option<AddressParam>,
+}
+
+/// Streamed event, it is the event of the transaction committed at `version`.
+#[derive(Serialize)]
+struct EventStreamItem {
+    version: U64,
+    #[serde(flatten)]
+    event: Event,
+}
+
+enum EventFilter {
+    All,
+    Key(EventKey),
+    Address(AccountAddress),
+}
+
+impl EventFilter {
+    fn new(params: EventStreamParams, context: &Context) -> Result<Self, Error> {
+        match (params.key, params.address) {
+            (None, None) => Ok(Self::All),
+            (Some(key), None) => Ok(Self::Key(key.parse("event key")?.into())),
+            (None, Some(address)) => Ok(Self::Address(context.resolve_address(address)?.into())),
+            (Some(_), Some(_)) => Err(Error::bad_request(
+                AptosErrorCode::InvalidParameter,
+                "only one of the parameters key and address can be given",
+            )),
+        }
+    }
+
+    fn matches(&self, event: &ContractEvent) -> bool {
+        match self {
+            Self::All => true,
+            Self::Key(key) => event.key() == key,
+            Self::Address(address) => &event.key().get_creator_address() == address,
+        }
+    }
+}
+
+// GET /stream/events?key={event_key}
+// GET /stream/events?address={address}
+pub fn stream_events(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("stream" / "events")
+        .and(warp::get())
+        .and(warp::query::<EventStreamParams>())
+        .and(warp::ws())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_stream_events)
+        .with(metrics("stream_events"))
+        .boxed()
+}
+
+async fn handle_stream_events(
+    params: EventStreamParams,
+    ws: Ws,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_events")?;
+    let filter = EventFilter::new(params, &context)?;
+    let events = context.subscribe_events().map_err(Error::internal)?;
+    Ok(ws.on_upgrade(move |socket| send_events(socket, filter, events, context)))
+}
+
+async fn send_events(
+    socket: WebSocket,
+    filter: EventFilter,
+    mut events: broadcast::Receiver<(Version, ContractEvent)>,
+    context: Context,
+) {
+    let (mut sink, mut stream) = socket.split();
+    loop {
+        tokio::select! {
+            msg = stream.next() => match msg {
+                // messages from client are ignored, except the close message.
+                Some(Ok(msg)) if !msg.is_close() => continue,
+                _ => break,
+            },
+            event = events.recv() => match event {
+                Ok((version, event)) => {
+                    if !filter.matches(&event) {
+                        continue;
+                    }
+                    let msg = match render_event(&context, version, event) {
+                        Ok(msg) => msg,
+                        Err(err) => {
+                            let _ = sink
+                                .send(Message::close_with(CLOSE_CODE_INTERNAL_ERROR, err.to_string()))
+                                .await;
+                            break;
+                        }
+                    };
+                    if sink.send(Message::text(msg)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    let reason = format!("subscriber is lagging behind, {} events skipped", skipped);
+                    let _ = sink
+                        .send(Message::close_with(CLOSE_CODE_POLICY_VIOLATION, reason))
+                        .await;
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+    debug!("event stream is closed");
+}
+
+fn render_event(context: &Context, version: Version, event: ContractEvent) -> Result<String> {
+    let resolver = context.move_resolver()?;
+    let event = resolver
+        .as_converter()
+        .try_into_events(&[event])?
+        .pop()
+        .expect("converted one event");
+    Ok(serde_json::to_string(&EventStreamItem {
+        version: version.into(),
+        event,
+    })?)
+}
@@ -4,8 +4,14 @@
 use crate::{
     current_function_name,
     tests::{find_value, new_test_context},
+    NameResolver,
 };
-use serde_json::json;
+use aptos_api_types::{mime_types::BCS, X_APTOS_CURSOR};
+use aptos_types::account_address::AccountAddress;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use warp::http::header::{ACCEPT, ETAG, IF_NONE_MATCH};
 
 #[tokio::test]
 async fn test_get_account_resources_returns_empty_array_for_account_has_no_resources() {
@@ -150,6 +156,96 @@ async fn test_get_account_resources_by_invalid_ledger_version() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_account_resources_with_pagination() {
+    let context = new_test_context(current_function_name!());
+    let address = "0xA550C18";
+    let all_resources = context.get(&account_resources(address)).await;
+
+    let mut resources = vec![];
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut path = format!("{}?limit=2", account_resources(address));
+        if let Some(start) = &cursor {
+            path = format!(
+                "{}&start={}",
+                path,
+                utf8_percent_encode(start, NON_ALPHANUMERIC)
+            );
+        }
+        let resp = context
+            .reply(warp::test::request().method("GET").path(&path))
+            .await;
+        assert_eq!(resp.status(), 200);
+
+        cursor = resp
+            .headers()
+            .get(X_APTOS_CURSOR)
+            .map(|v| v.to_str().unwrap().to_owned());
+        let page: Vec<Value> = serde_json::from_slice(resp.body()).unwrap();
+        if cursor.is_some() {
+            assert_eq!(page.len(), 2);
+        }
+        resources.extend(page);
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(Value::Array(resources), all_resources);
+}
+
+#[tokio::test]
+async fn test_get_account_resources_by_invalid_start_cursor() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get(&format!(
+            "{}?start=0x1::Account",
+            account_resources("0xA550C18")
+        ))
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_account_resources_with_filter() {
+    let context = new_test_context(current_function_name!());
+    let all = context.get(&account_resources("0xA550C18")).await;
+    let expected: Vec<&Value> = all
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|resource| {
+            resource["type"]
+                .as_str()
+                .unwrap()
+                .starts_with("0x1::Account::")
+        })
+        .collect();
+    assert!(!expected.is_empty());
+
+    let resp = context
+        .get(&format!(
+            "{}?filter={}",
+            account_resources("0xA550C18"),
+            utf8_percent_encode(
+                "0x00000000000000000000000000000001::Account::",
+                NON_ALPHANUMERIC
+            )
+        ))
+        .await;
+    let resources: Vec<&Value> = resp.as_array().unwrap().iter().collect();
+    assert_eq!(resources, expected);
+
+    let resp = context
+        .expect_status_code(400)
+        .get(&format!(
+            "{}?filter=Account",
+            account_resources("0xA550C18")
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "invalid_parameter");
+}
+
 // figure out a working module code, no idea where the existing one comes from
 #[ignore] // TODO(issue 81): re-enable after cleaning up the compiled code in the test
 #[tokio::test]
@@ -194,6 +290,165 @@ async fn test_get_core_account_data_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_core_account_data_with_history() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let root_account = context.get("/accounts/0xA550C18").await;
+    assert_eq!(root_account["creation_version"], "0");
+    let created = context
+        .get(&format!("/accounts/{}", account.address()))
+        .await;
+    assert_eq!(
+        created["creation_version"],
+        root_account["last_transaction_version"]
+    );
+    assert_eq!(created["authentication_key_rotations"], "0");
+    assert!(created.get("last_transaction_version").is_none());
+}
+
+#[tokio::test]
+async fn test_get_core_account_data_with_etag() {
+    let mut context = new_test_context(current_function_name!());
+    let path = "/accounts/0xA550C18";
+    let resp = context.reply(get_with_etag(path, None)).await;
+    assert_eq!(resp.status(), 200);
+    let etag = resp.headers()[ETAG].to_str().unwrap().to_owned();
+    assert!(etag.starts_with("W/"));
+
+    let resp = context.reply(get_with_etag(path, Some(&etag))).await;
+    assert_eq!(resp.status(), 304);
+    assert_eq!(resp.headers()[ETAG], etag.as_str());
+    assert!(resp.body().is_empty());
+
+    // the root account sequence number is changed by the transaction
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context.reply(get_with_etag(path, Some(&etag))).await;
+    assert_eq!(resp.status(), 200);
+    assert_ne!(resp.headers()[ETAG], etag.as_str());
+}
+
+#[tokio::test]
+async fn test_get_account_resources_with_etag() {
+    let context = new_test_context(current_function_name!());
+    let path = account_resources("0xA550C18");
+    let resp = context.reply(get_with_etag(&path, None)).await;
+    assert_eq!(resp.status(), 200);
+    let etag = resp.headers()[ETAG].to_str().unwrap().to_owned();
+
+    let if_none_match = format!("\"other\", {}", etag);
+    let resp = context
+        .reply(get_with_etag(&path, Some(&if_none_match)))
+        .await;
+    assert_eq!(resp.status(), 304);
+
+    let resp = context
+        .reply(get_with_etag(&path, Some("W/\"other\"")))
+        .await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_get_account_resources_with_etag_of_other_query() {
+    let context = new_test_context(current_function_name!());
+    let path = format!("{}?limit=1", account_resources("0xA550C18"));
+    let resp = context.reply(get_with_etag(&path, None)).await;
+    assert_eq!(resp.status(), 200);
+    let etag = resp.headers()[ETAG].to_str().unwrap().to_owned();
+
+    let other_limit = format!("{}?limit=2", account_resources("0xA550C18"));
+    let resp = context
+        .reply(get_with_etag(&other_limit, Some(&etag)))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_ne!(resp.headers()[ETAG], etag.as_str());
+
+    let resp = context
+        .reply(get_with_etag(&path, Some(&etag)).header(ACCEPT, BCS))
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    // the filters are compared after parsing, so the equivalent filters share the ETag.
+    let resp = context
+        .reply(get_with_etag(&format!("{}&filter=0x1::", path), None))
+        .await;
+    let etag = resp.headers()[ETAG].to_str().unwrap().to_owned();
+    let resp = context
+        .reply(get_with_etag(
+            &format!("{}&filter=0x00000000000000000000000000000001::", path),
+            Some(&etag),
+        ))
+        .await;
+    assert_eq!(resp.status(), 304);
+}
+
+struct TestNameResolver;
+
+impl NameResolver for TestNameResolver {
+    fn resolve(&self, name: &str) -> anyhow::Result<Option<AccountAddress>> {
+        Ok(match name {
+            "root.apt" => Some(AccountAddress::from_hex_literal("0xA550C18")?),
+            _ => None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_get_account_resources_by_name() {
+    let mut context = new_test_context(current_function_name!());
+    context.context = context
+        .context
+        .clone()
+        .with_name_resolver(Arc::new(TestNameResolver));
+
+    let resp = context.get(&account_resources("root.apt")).await;
+    let expected = context.get(&account_resources("0xA550C18")).await;
+    assert_eq!(resp, expected);
+
+    let resp = context
+        .expect_status_code(400)
+        .get(&account_resources("unknown.apt"))
+        .await;
+    assert_eq!(resp["error_code"], "invalid_parameter");
+}
+
+#[tokio::test]
+async fn test_get_account_balance() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .get("/accounts/0xA550C18/balance/0x1::TestCoin::Coin")
+        .await;
+    let resource = context
+        .get("/accounts/0xA550C18/resource/0x1::TestCoin::Balance")
+        .await;
+    assert_eq!(resp["coin_type"], "0x1::TestCoin::Coin");
+    assert_eq!(resp["value"], resource["data"]["coin"]["value"]);
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/accounts/0xA550C18/balance/0x1::TestCoin::Balance")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_parameter");
+    assert_eq!(
+        resp["message"],
+        "invalid parameter coin_type: 0x1::TestCoin::Balance"
+    );
+}
+
+fn get_with_etag(path: &str, if_none_match: Option<&str>) -> warp::test::RequestBuilder {
+    let req = warp::test::request().method("GET").path(path);
+    match if_none_match {
+        Some(etag) => req.header(IF_NONE_MATCH, etag),
+        None => req,
+    }
+}
+
 fn account_resources(address: &str) -> String {
     format!("/accounts/{}/resources", address)
 }
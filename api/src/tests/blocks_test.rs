@@ -0,0 +1,100 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, response_cache::ResponseCacheKey, tests::new_test_context};
+use aptos_api_types::X_APTOS_LEDGER_VERSION;
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_get_genesis_block() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/blocks/0").await;
+    assert_eq!(resp["block_height"], json!("0"));
+    assert_eq!(resp["first_version"], json!("0"));
+    assert_eq!(resp["last_version"], json!("0"));
+    assert_eq!(resp["transactions"][0]["type"], "genesis_transaction");
+}
+
+#[tokio::test]
+async fn test_get_block_by_height() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let resp = context.get("/blocks/1").await;
+    assert_eq!(resp["block_height"], json!("1"));
+    assert_eq!(resp["first_version"], json!("1"));
+    assert_eq!(resp["last_version"], json!("2"));
+
+    let txns = resp["transactions"].as_array().unwrap();
+    assert_eq!(txns.len(), 2);
+    assert_eq!(txns[0]["type"], "block_metadata_transaction");
+    assert_eq!(txns[0]["timestamp"], resp["block_timestamp"]);
+    assert_eq!(txns[1]["type"], "user_transaction");
+    assert_eq!(
+        txns[1]["hash"],
+        json!(txn.committed_hash().to_hex_literal())
+    );
+    assert_eq!(txns[1]["events"], json!([]));
+}
+
+#[tokio::test]
+async fn test_get_block_by_height_with_events() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context.get("/blocks/1?with_events=true").await;
+    let events = resp["transactions"][1]["events"].as_array().unwrap();
+    assert!(!events.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_historical_block_from_response_cache() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root, &account);
+    context.commit_block(&vec![txn]).await;
+
+    // the latest block is not cached, it may still grow
+    let resp = context.get("/blocks/1").await;
+    let key = |id: &str| ResponseCacheKey::new("get_block_by_height", id.to_owned());
+    assert!(context
+        .context
+        .response_cache()
+        .get(&key("1:false"))
+        .is_none());
+
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root, &account);
+    context.commit_block(&vec![txn]).await;
+    assert_eq!(context.get("/blocks/1").await, resp);
+    assert!(context
+        .context
+        .response_cache()
+        .get(&key("1:false"))
+        .is_some());
+
+    // the cached body is served with the headers of the latest ledger
+    let cached = context
+        .reply(warp::test::request().method("GET").path("/blocks/1"))
+        .await;
+    let body: Value = serde_json::from_slice(cached.body()).unwrap();
+    assert_eq!(body, resp);
+    assert_eq!(cached.headers()[X_APTOS_LEDGER_VERSION], "4");
+}
+
+#[tokio::test]
+async fn test_get_block_by_height_not_found() {
+    let context = new_test_context(current_function_name!());
+    context.expect_status_code(404).get("/blocks/1").await;
+}
+
+#[tokio::test]
+async fn test_get_block_by_invalid_height() {
+    let context = new_test_context(current_function_name!());
+    context.expect_status_code(400).get("/blocks/one").await;
+}
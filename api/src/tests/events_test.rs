@@ -51,6 +51,78 @@ async fn test_get_events_by_invalid_key() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_events_by_creation_number() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context.get("/accounts/0xa550c18/events/4").await;
+    let expected = context.get(format!("/events/{}", EVENT_KEY).as_str()).await;
+    assert!(!resp.as_array().unwrap().is_empty());
+    assert_eq!(resp, expected);
+
+    let resp = context.get("/accounts/0xa550c18/events/4?start=1").await;
+    let expected = context
+        .get(format!("/events/{}?start=1", EVENT_KEY).as_str())
+        .await;
+    assert_eq!(resp, expected);
+}
+
+#[tokio::test]
+async fn test_get_events_by_invalid_creation_number() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/accounts/0xa550c18/events/invalid")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_parameter");
+}
+
+#[tokio::test]
+async fn test_get_events_by_account_event_handle_filter_by_type() {
+    let context = new_test_context(current_function_name!());
+    let path = "/accounts/0xa550c18/events/0x1::Reconfiguration::Configuration/events";
+
+    let all = context.get(path).await;
+    let resp = context
+        .get(&format!(
+            "{}?type=0x1::Reconfiguration::NewEpochEvent",
+            path
+        ))
+        .await;
+    assert_eq!(resp, all);
+
+    let resp = context
+        .get(&format!(
+            "{}?type=0x1::Reconfiguration::Configuration",
+            path
+        ))
+        .await;
+    assert_eq!(resp.as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn test_get_events_by_account_event_handle_filter_by_invalid_type() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get("/accounts/0xa550c18/events/0x1::Reconfiguration::Configuration/events?type=0x1::Reconfiguration")
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_events_by_account_event_handle_with_invalid_struct() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/accounts/0xa550c18/events/0x1::Reconfiguration/events")
+        .await;
+    assert_eq!(
+        resp["message"],
+        "invalid parameter event_handle_struct: 0x1::Reconfiguration"
+    );
+}
+
 #[tokio::test]
 async fn test_get_events_by_account_event_handle() {
     let mut context = new_test_context(current_function_name!());
@@ -0,0 +1,34 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::new_test_context};
+use aptos_api_types::GasEstimation;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_estimate_gas_price_without_user_transactions() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/estimate_gas_price").await;
+    // the min price per gas unit of the test genesis is 0
+    assert_eq!(resp, json!({"low": "0", "median": "0", "high": "0"}));
+}
+
+#[tokio::test]
+async fn test_estimate_gas_price_by_committed_user_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let resp = context.get("/estimate_gas_price").await;
+    let estimation: GasEstimation = serde_json::from_value(resp).unwrap();
+    let price = txn.gas_unit_price().into();
+    assert_eq!(
+        estimation,
+        GasEstimation {
+            low: price,
+            median: price,
+            high: price,
+        }
+    );
+}
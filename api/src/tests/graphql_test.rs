@@ -0,0 +1,103 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    current_function_name,
+    tests::{new_test_context, TestContext},
+};
+use aptos_config::config::ApiConfig;
+use aptos_types::chain_id::ChainId;
+use serde_json::json;
+
+fn new_graphql_test_context(test_name: &'static str) -> TestContext {
+    let mut context = new_test_context(test_name);
+    let api_config = ApiConfig {
+        graphql_enabled: true,
+        ..ApiConfig::default()
+    };
+    context.context = Context::new(
+        ChainId::test(),
+        context.db.clone(),
+        context.mempool.ac_client.clone(),
+        api_config,
+    );
+    context
+}
+
+#[tokio::test]
+async fn test_graphql_is_disabled_by_default() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .post("/graphql", json!({"query": "{ ledgerInfo }"}))
+        .await;
+    assert_eq!(resp["error_code"], "not_found");
+}
+
+#[tokio::test]
+async fn test_graphql_query_account_and_transactions() {
+    let context = new_graphql_test_context(current_function_name!());
+    let query = r#"{
+        account(address: "0xA550C18") {
+            address
+            data
+            resources(filter: "0x1::Account::")
+        }
+        transactions(start: "0", limit: 1)
+    }"#;
+    let resp = context.post("/graphql", json!({ "query": query })).await;
+    assert!(resp.get("errors").is_none(), "{}", resp);
+
+    let account = &resp["data"]["account"];
+    let expected = context.get("/accounts/0xA550C18").await;
+    assert_eq!(account["data"], expected);
+    let resources = account["resources"].as_array().unwrap();
+    assert!(!resources.is_empty());
+    for resource in resources {
+        assert!(resource["type"]
+            .as_str()
+            .unwrap()
+            .starts_with("0x1::Account::"));
+    }
+
+    let expected = context.get("/transactions?start=0&limit=1").await;
+    assert_eq!(resp["data"]["transactions"], expected);
+}
+
+#[tokio::test]
+async fn test_graphql_query_complexity_depends_on_page_limit() {
+    let context = new_graphql_test_context(current_function_name!());
+    let query = r#"{
+        first: transactions(start: "0", limit: 400)
+        second: transactions(start: "0", limit: 400)
+    }"#;
+    let resp = context.post("/graphql", json!({ "query": query })).await;
+    assert!(resp.get("errors").is_none(), "{}", resp);
+
+    let query = r#"{
+        first: transactions(start: "0", limit: 400)
+        second: transactions(start: "0", limit: 400)
+        third: transactions(start: "0", limit: 400)
+    }"#;
+    let resp = context.post("/graphql", json!({ "query": query })).await;
+    assert!(resp["data"].is_null(), "{}", resp);
+    assert!(resp["errors"][0]["message"]
+        .as_str()
+        .unwrap()
+        .contains("too complex"));
+}
+
+#[tokio::test]
+async fn test_graphql_error_extensions() {
+    let context = new_graphql_test_context(current_function_name!());
+    let resp = context
+        .post(
+            "/graphql",
+            json!({"query": r#"{ account(address: "0x0") { data } }"#}),
+        )
+        .await;
+    let extensions = &resp["errors"][0]["extensions"];
+    assert_eq!(extensions["code"], 404);
+    assert_eq!(extensions["error_code"], "account_not_found");
+}
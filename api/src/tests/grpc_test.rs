@@ -0,0 +1,72 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, grpc::TransactionStreamService, tests::new_test_context};
+use aptos_transaction_stream::{
+    transaction_stream_server::TransactionStream, GetTransactionsRequest,
+};
+use aptos_types::chain_id::ChainId;
+use futures::StreamExt;
+use serde_json::Value;
+use tonic::Request;
+
+fn request(starting_version: u64, batch_size: u32) -> Request<GetTransactionsRequest> {
+    Request::new(GetTransactionsRequest {
+        starting_version,
+        batch_size,
+    })
+}
+
+#[tokio::test]
+async fn test_stream_transactions_in_batches() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let expected = context.get("/transactions?start=0&limit=100").await;
+    let expected = expected.as_array().unwrap();
+
+    let service = TransactionStreamService::new(context.context.clone());
+    let mut stream = service
+        .get_transactions(request(0, 2))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut streamed = vec![];
+    while streamed.len() < expected.len() {
+        let response = stream.next().await.unwrap().unwrap();
+        assert_eq!(response.chain_id, ChainId::test().id() as u32);
+        assert!(!response.transactions.is_empty());
+        assert!(response.transactions.len() <= 2);
+        for txn in response.transactions {
+            assert_eq!(txn.version, streamed.len() as u64);
+            streamed.push(serde_json::from_str::<Value>(&txn.json).unwrap());
+        }
+    }
+    assert_eq!(&streamed, expected);
+}
+
+#[tokio::test]
+async fn test_stream_transactions_waits_for_new_commits() {
+    let mut context = new_test_context(current_function_name!());
+    let next_version = context.get_latest_ledger_info().version() + 1;
+
+    let service = TransactionStreamService::new(context.context.clone());
+    let mut stream = service
+        .get_transactions(request(next_version, 0))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let response = stream.next().await.unwrap().unwrap();
+    assert_eq!(response.transactions[0].version, next_version);
+    let hash = txn.committed_hash().to_hex_literal();
+    assert!(response
+        .transactions
+        .iter()
+        .any(|txn| serde_json::from_str::<Value>(&txn.json).unwrap()["hash"] == hash.as_str()));
+}
@@ -1,8 +1,23 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{current_function_name, tests::new_test_context};
+use crate::{
+    admin::X_APTOS_ADMIN_TOKEN, context::Context, current_function_name, index::OPEN_API_SPEC,
+    tests::new_test_context,
+};
+use aptos_api_types::{X_APTOS_CHAIN_ID, X_APTOS_LEDGER_VERSION};
+use aptos_config::config::{ApiConfig, ApiRateLimitConfig};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    download_stats::{DownloadStats, DownloadStatsReporter},
+    liveness::{LivenessReporter, LivenessSnapshot},
+};
+use aptosdb::DB_CHECKPOINT_MANIFEST_FILE;
 use serde_json::json;
+use std::{collections::BTreeSet, fs, path::Path, sync::Arc};
+use warp::http::header::CONTENT_LENGTH;
 
 #[tokio::test]
 async fn test_get_ledger_info() {
@@ -11,6 +26,60 @@ async fn test_get_ledger_info() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_node_info() {
+    let context = new_test_context(current_function_name!());
+    let ledger_info = context.get("/").await;
+    let resp = context.get("/info").await;
+    assert_eq!(resp["chain_id"], ledger_info["chain_id"]);
+    assert_eq!(resp["epoch"], ledger_info["epoch"]);
+    assert_eq!(resp["ledger_version"], ledger_info["ledger_version"]);
+    assert_eq!(resp["oldest_ledger_version"], "0");
+    assert_eq!(resp["api_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        resp["features"],
+        json!({
+            "bcs_output": true,
+            "transaction_simulation": true,
+            "table_listing": false,
+            "graphql": false,
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_head_request() {
+    let context = new_test_context(current_function_name!());
+    let get = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/accounts/0xA550C18"),
+        )
+        .await;
+    let head = context
+        .reply(
+            warp::test::request()
+                .method("HEAD")
+                .path("/accounts/0xA550C18"),
+        )
+        .await;
+    assert_eq!(head.status(), 200);
+    assert!(head.body().is_empty());
+    assert_eq!(
+        head.headers()[CONTENT_LENGTH],
+        get.body().len().to_string().as_str()
+    );
+    for header in [X_APTOS_CHAIN_ID, X_APTOS_LEDGER_VERSION] {
+        assert_eq!(head.headers()[header], get.headers()[header]);
+    }
+
+    let head = context
+        .reply(warp::test::request().method("HEAD").path("/"))
+        .await;
+    assert_eq!(head.status(), 405);
+}
+
 #[tokio::test]
 async fn test_returns_not_found_for_the_invalid_path() {
     let mut context = new_test_context(current_function_name!());
@@ -37,10 +106,291 @@ async fn test_health_check() {
     assert_eq!(resp.status(), 200)
 }
 
+#[tokio::test]
+async fn test_health_check_with_stale_ledger_info() {
+    let context = new_test_context(current_function_name!());
+    // the test ledger timestamps start from 0, so they are always behind the current time
+    let resp = context
+        .expect_status_code(503)
+        .get("/-/healthy?duration_secs=1")
+        .await;
+    assert_eq!(resp["error_code"], "health_check_failed");
+}
+
+#[tokio::test]
+async fn test_health_check_with_fresh_ledger_info() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&format!("/-/healthy?duration_secs={}", u32::MAX)),
+        )
+        .await;
+    assert_eq!(resp.status(), 200)
+}
+
+#[tokio::test]
+async fn test_health_check_with_invalid_duration_secs() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/-/healthy?duration_secs=abc")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_parameter");
+}
+
+#[tokio::test]
+async fn test_health_check_not_rate_limited() {
+    let mut context = new_test_context(current_function_name!());
+    let api_config = ApiConfig {
+        rate_limit: Some(ApiRateLimitConfig {
+            requests_per_second: 1,
+            burst_size: 1,
+        }),
+        ..ApiConfig::default()
+    };
+    context.context = Context::new(
+        ChainId::test(),
+        context.db.clone(),
+        context.mempool.ac_client.clone(),
+        api_config,
+    );
+    let request = |path: &str| {
+        warp::test::request()
+            .method("GET")
+            .path(path)
+            .remote_addr(([127, 0, 0, 1], 8080).into())
+    };
+
+    assert_eq!(context.reply(request("/")).await.status(), 200);
+    assert_eq!(context.reply(request("/")).await.status(), 429);
+    // the health check neither takes a token nor is rejected once the client is out of tokens
+    assert_eq!(context.reply(request("/-/healthy")).await.status(), 200);
+    assert_eq!(
+        context
+            .reply(request("/-/healthy?duration_secs=1"))
+            .await
+            .status(),
+        503
+    );
+}
+
+#[tokio::test]
+async fn test_admin_routes_are_disabled_without_admin_token() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get("/admin/failpoints")
+        .await;
+    assert_eq!(resp["error_code"], "not_found");
+
+    for path in [
+        "/admin/storage",
+        "/admin/state_sync",
+        "/admin/consensus",
+        "/admin/execution_profile",
+        "/admin/native_trace",
+        "/admin/gas_schedule",
+        "/mempool/info",
+        "/mempool/transactions?sender=0x1",
+    ] {
+        let resp = context.expect_status_code(404).get(path).await;
+        assert_eq!(resp["error_code"], "not_found");
+    }
+    let resp = context
+        .expect_status_code(404)
+        .post("/admin/checkpoint", json!({"name": "checkpoint"}))
+        .await;
+    assert_eq!(resp["error_code"], "not_found");
+}
+
+#[tokio::test]
+async fn test_get_mempool_info() {
+    let mut context = new_test_context(current_function_name!());
+    let api_config = ApiConfig {
+        admin_token: Some("token".to_owned()),
+        ..ApiConfig::default()
+    };
+    context.context = Context::new(
+        ChainId::test(),
+        context.db.clone(),
+        context.mempool.ac_client.clone(),
+        api_config,
+    );
+    let get_mempool_info = |token: &str| {
+        warp::test::request()
+            .method("GET")
+            .path("/mempool/info")
+            .header(X_APTOS_ADMIN_TOKEN, token)
+    };
+
+    let resp = context.execute(get_mempool_info("token")).await;
+    assert_eq!(
+        resp,
+        json!({
+            "size": 0,
+            "priority_queue_size": 0,
+            "parking_lot_size": 0,
+            "parking_lot_accounts": [],
+        })
+    );
+
+    let resp = context
+        .expect_status_code(401)
+        .execute(get_mempool_info("tokem"))
+        .await;
+    assert_eq!(resp["error_code"], "unauthorized");
+}
+
+#[tokio::test]
+async fn test_get_state_sync() {
+    let mut context = new_test_context(current_function_name!());
+    let api_config = ApiConfig {
+        admin_token: Some("token".to_owned()),
+        ..ApiConfig::default()
+    };
+    context.context = Context::new(
+        ChainId::test(),
+        context.db.clone(),
+        context.mempool.ac_client.clone(),
+        api_config,
+    )
+    .with_download_throttle(Arc::new(FakeDownloadStatsReporter));
+
+    let resp = context
+        .execute(
+            warp::test::request()
+                .method("GET")
+                .path("/admin/state_sync")
+                .header(X_APTOS_ADMIN_TOKEN, "token"),
+        )
+        .await;
+    assert_eq!(
+        resp,
+        json!({
+            "throughput_bytes_per_sec": 0,
+            "rate_limit_bytes_per_sec": 0,
+            "downloaded_bytes": 1000,
+            "throttled_data_requests": 1,
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_get_consensus() {
+    let mut context = new_test_context(current_function_name!());
+    let api_config = ApiConfig {
+        admin_token: Some("token".to_owned()),
+        ..ApiConfig::default()
+    };
+    context.context = Context::new(
+        ChainId::test(),
+        context.db.clone(),
+        context.mempool.ac_client.clone(),
+        api_config,
+    )
+    .with_liveness_diagnostics(Arc::new(FakeLivenessReporter));
+
+    let resp = context
+        .execute(
+            warp::test::request()
+                .method("GET")
+                .path("/admin/consensus")
+                .header(X_APTOS_ADMIN_TOKEN, "token"),
+        )
+        .await;
+    let mut expected = json!({
+        "current_round": 5,
+        "last_committed_round": 3,
+        "pending_votes": 2,
+        "timeout_votes": {},
+        "block_queue_depth": 1,
+    });
+    expected["timeout_votes"][AccountAddress::ONE.to_string()] = json!(4);
+    assert_eq!(resp, expected);
+}
+
+struct FakeDownloadStatsReporter;
+
+impl DownloadStatsReporter for FakeDownloadStatsReporter {
+    fn download_stats(&self) -> DownloadStats {
+        DownloadStats {
+            throughput_bytes_per_sec: 0,
+            rate_limit_bytes_per_sec: Some(0),
+            downloaded_bytes: 1000,
+            throttled_data_requests: 1,
+        }
+    }
+}
+
+struct FakeLivenessReporter;
+
+impl LivenessReporter for FakeLivenessReporter {
+    fn liveness_snapshot(&self) -> LivenessSnapshot {
+        LivenessSnapshot {
+            current_round: 5,
+            last_committed_round: 3,
+            pending_votes: 2,
+            timeout_votes: vec![(AccountAddress::ONE, 4)].into_iter().collect(),
+            pending_blocks: 1,
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_create_checkpoint() {
+    let mut context = new_test_context(current_function_name!());
+    let checkpoint_dir = TempPath::new();
+    checkpoint_dir.create_as_dir().unwrap();
+    let api_config = ApiConfig {
+        admin_token: Some("token".to_owned()),
+        checkpoint_dir: Some(checkpoint_dir.path().to_path_buf()),
+        ..ApiConfig::default()
+    };
+    context.context = Context::new(
+        ChainId::test(),
+        context.db.clone(),
+        context.mempool.ac_client.clone(),
+        api_config,
+    )
+    .with_db_checkpointer(context.db.clone());
+    let create_checkpoint = |name: &str| {
+        warp::test::request()
+            .method("POST")
+            .path("/admin/checkpoint")
+            .header(X_APTOS_ADMIN_TOKEN, "token")
+            .json(&json!({ "name": name }))
+    };
+
+    let resp = context.execute(create_checkpoint("checkpoint")).await;
+    assert_eq!(resp["version"], context.get_latest_ledger_info().version());
+    assert!(checkpoint_dir
+        .path()
+        .join("checkpoint")
+        .join(DB_CHECKPOINT_MANIFEST_FILE)
+        .exists());
+
+    // the checkpoints are only created in the configured dir
+    for name in ["../checkpoint", "/tmp/checkpoint", "..", ""] {
+        let resp = context
+            .expect_status_code(400)
+            .execute(create_checkpoint(name))
+            .await;
+        assert_eq!(resp["error_code"], "invalid_request_body");
+    }
+    // the dir of the checkpoint already exists
+    let resp = context
+        .expect_status_code(500)
+        .execute(create_checkpoint("checkpoint"))
+        .await;
+    assert_eq!(resp["error_code"], "internal_error");
+}
+
 #[tokio::test]
 async fn test_openapi_spec() {
     let context = new_test_context(current_function_name!());
-    let paths = ["/openapi.yaml", "/spec.html"];
+    let paths = ["/openapi.yaml", "/spec.json", "/spec.html"];
     for path in paths {
         let req = warp::test::request().method("GET").path(path);
         let resp = context.reply(req).await;
@@ -48,6 +398,99 @@ async fn test_openapi_spec() {
     }
 }
 
+#[test]
+fn test_openapi_spec_parsed() {
+    let spec: serde_json::Value = serde_yaml::from_str(OPEN_API_SPEC).unwrap();
+    assert!(spec["paths"].is_object());
+}
+
+#[test]
+fn test_openapi_spec_documents_all_routes() {
+    let spec: serde_json::Value = serde_yaml::from_str(OPEN_API_SPEC).unwrap();
+    let documented_routes: BTreeSet<_> = spec["paths"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .flat_map(|(path, operations)| {
+            let path = route_path(path.split('/').skip(1), |segment| segment.starts_with('{'));
+            operations
+                .as_object()
+                .unwrap()
+                .keys()
+                .map(move |method| (method.clone(), path.clone()))
+        })
+        .collect();
+    let undocumented_routes: Vec<_> = defined_routes()
+        .difference(&documented_routes)
+        .cloned()
+        .collect();
+    assert!(
+        undocumented_routes.is_empty(),
+        "routes missing from the OpenAPI spec: {:?}",
+        undocumented_routes
+    );
+}
+
+/// The (method, path) of the routes defined by the `warp::path!` filters of the crate, without
+/// the admin-gated routes, which are documented in Operation.md. A route without a method filter
+/// is documented as a GET.
+fn defined_routes() -> BTreeSet<(String, String)> {
+    let src_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let mut routes = BTreeSet::new();
+    for entry in fs::read_dir(src_dir).unwrap() {
+        let file = entry.unwrap().path();
+        if file.extension().map_or(true, |extension| extension != "rs") {
+            continue;
+        }
+        let source = fs::read_to_string(&file).unwrap();
+        for filter in source.split("warp::path!(").skip(1) {
+            let (segments, chain) = filter.split_once(')').unwrap();
+            // the filters of the route are chained up to the end of its function
+            let chain = chain.split("\n}").next().unwrap();
+            if chain.contains("admin_auth(") {
+                continue;
+            }
+            let method = ["post", "put", "delete"]
+                .iter()
+                .copied()
+                .find(|method| chain.contains(&format!("warp::{}()", method)))
+                .unwrap_or("get");
+            let path = route_path(segments.split('/').map(str::trim), |segment| {
+                !segment.starts_with('"')
+            });
+            routes.insert((method.to_owned(), path));
+        }
+    }
+    routes
+}
+
+/// Joins the segments into a path, the params written `{}` so that the paths of the routes
+/// compare to the ones of the spec.
+fn route_path<'a>(
+    segments: impl Iterator<Item = &'a str>,
+    is_param: impl Fn(&str) -> bool,
+) -> String {
+    segments
+        .map(|segment| {
+            if is_param(segment) {
+                "/{}".to_owned()
+            } else {
+                format!("/{}", segment.trim_matches('"'))
+            }
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_openapi_spec_json() {
+    let context = new_test_context(current_function_name!());
+    let spec = context.get("/spec.json").await;
+    assert_eq!(
+        spec["paths"]["/spec.json"]["get"]["operationId"],
+        "get_spec_json"
+    );
+}
+
 #[tokio::test]
 async fn test_cors() {
     let context = new_test_context(current_function_name!());
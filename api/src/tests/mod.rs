@@ -2,14 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod accounts_test;
+mod blocks_test;
 mod events_test;
+mod gas_estimation_test;
 mod golden_output;
+mod graphql_test;
+mod grpc_test;
 mod index_test;
 mod invalid_post_request_test;
 mod state_test;
+mod stream_test;
 mod string_resource_test;
 mod test_context;
 mod transactions_test;
+mod view_function_test;
 
 use serde_json::Value;
 pub use test_context::{new_test_context, TestContext};
@@ -2,6 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{current_function_name, tests::new_test_context};
+use aptos_api_types::{mime_types, BytesEncoding};
+use aptos_types::account_config::AccountResource;
+use serde_json::json;
 
 #[tokio::test]
 async fn test_get_account_resource() {
@@ -12,6 +15,89 @@ async fn test_get_account_resource() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_account_resource_in_bcs() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&get_account_resource("0xA550C18", "0x1::Account::Account"))
+                .header("Accept", mime_types::BCS),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["Content-Type"], mime_types::BCS);
+    let account: AccountResource = bcs::from_bytes(resp.body()).unwrap();
+    assert_eq!(account.address(), context.root_account().address());
+}
+
+#[tokio::test]
+async fn test_get_account_resource_with_bytes_encoding() {
+    let context = new_test_context(current_function_name!());
+    let path = get_account_resource("0xA550C18", "0x1::Account::Account");
+    let resp = context.get(&path).await;
+    let auth_key = hex::decode(
+        resp["data"]["authentication_key"]
+            .as_str()
+            .unwrap()
+            .trim_start_matches("0x"),
+    )
+    .unwrap();
+
+    let resp = context
+        .get(&format!("{}?bytes_encoding=base64", path))
+        .await;
+    assert_eq!(
+        resp["data"]["authentication_key"],
+        BytesEncoding::Base64
+            .encode(auth_key)
+            .unwrap()
+            .json()
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_get_account_resource_by_invalid_bytes_encoding() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get(&format!(
+            "{}?bytes_encoding=base58",
+            get_account_resource("0xA550C18", "0x1::Account::Account")
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "invalid_parameter");
+}
+
+#[tokio::test]
+async fn test_get_account_resources_batch() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .post(
+            "/accounts/0xA550C18/resources/batch",
+            json!(["0x1::GUID::Generator", "0x1::GUID::GeneratorX"]),
+        )
+        .await;
+    let expected = context
+        .get(&get_account_resource("0xA550C18", "0x1::GUID::Generator"))
+        .await;
+    assert_eq!(resp, json!([expected, null]));
+}
+
+#[tokio::test]
+async fn test_get_account_resources_batch_by_invalid_struct_tag() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .post(
+            "/accounts/0xA550C18/resources/batch",
+            json!(["0x1::GUID_Generator"]),
+        )
+        .await;
+}
+
 #[tokio::test]
 async fn test_get_account_resource_by_invalid_address() {
     let mut context = new_test_context(current_function_name!());
@@ -58,6 +144,22 @@ async fn test_get_account_resource_struct_tag_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_account_resource_at_ledger_version() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let version_before = context.get_latest_ledger_info().version();
+    context.commit_block(&vec![txn]).await;
+
+    let path = get_account_resource(&account.address().to_hex_literal(), "0x1::Account::Account");
+    context.get(&path).await;
+    context
+        .expect_status_code(404)
+        .get(&format!("{}?ledger_version={}", path, version_before))
+        .await;
+}
+
 #[tokio::test]
 async fn test_get_account_module() {
     let mut context = new_test_context(current_function_name!());
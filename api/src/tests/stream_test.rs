@@ -0,0 +1,36 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, index, tests::new_test_context};
+use serde_json::Value;
+
+#[tokio::test]
+async fn test_stream_events() {
+    let mut context = new_test_context(current_function_name!());
+    let mut client = warp::test::ws()
+        .path("/stream/events")
+        .handshake(index::routes(context.context.clone()))
+        .await
+        .expect("websocket handshake");
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let msg = client.recv().await.unwrap();
+    let event: Value = serde_json::from_str(msg.to_str().unwrap()).unwrap();
+    let version: u64 = event["version"].as_str().unwrap().parse().unwrap();
+    assert!(version > 0);
+    assert!(event["key"].is_string());
+    assert!(event["type"].is_string());
+}
+
+#[tokio::test]
+async fn test_stream_events_by_invalid_filter() {
+    let context = new_test_context(current_function_name!());
+    let ret = warp::test::ws()
+        .path("/stream/events?key=0x1&address=0x1")
+        .handshake(index::routes(context.context.clone()))
+        .await;
+    assert!(ret.is_err());
+}
@@ -6,8 +6,12 @@ use crate::{
     tests::{assert_json, new_test_context, pretty, TestContext},
 };
 
-use aptos_api_types::HexEncodedBytes;
+use aptos_api_types::{
+    mime_types, AsConverter, BytesEncoding, HexEncodedBytes, TransactionData,
+    TransactionOnChainData,
+};
 use aptos_crypto::{
+    hash::CryptoHash,
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
     PrivateKey, SigningKey, Uniform,
 };
@@ -17,7 +21,8 @@ use aptos_types::{
     account_address::AccountAddress,
     transaction::{
         authenticator::{AuthenticationKey, TransactionAuthenticator},
-        ChangeSet, Script, ScriptFunction, SignedTransaction,
+        ChangeSet, RawTransactionWithData, Script, ScriptFunction, SignedTransaction,
+        TransactionArgument, WriteSetPayload,
     },
     write_set::{WriteOp, WriteSetMut},
 };
@@ -218,6 +223,67 @@ async fn test_get_transactions_output_user_transaction_with_write_set_payload()
     context.check_golden_output(txns);
 }
 
+#[tokio::test]
+async fn test_convert_direct_write_set_with_raw_state_keys() {
+    let context = new_test_context(current_function_name!());
+    let written = StateKey::Raw(b"written".to_vec());
+    let deleted = StateKey::Raw(b"deleted".to_vec());
+    let account = StateKey::AccountAddressKey(AccountAddress::ONE);
+    let payload = WriteSetPayload::Direct(ChangeSet::new(
+        WriteSetMut::new(vec![
+            (written.clone(), WriteOp::Value(b"value".to_vec())),
+            (deleted.clone(), WriteOp::Deletion),
+            (account.clone(), WriteOp::Deletion),
+        ])
+        .freeze()
+        .unwrap(),
+        vec![],
+    ));
+    let resolver = context.context.move_resolver().unwrap();
+    let converted = resolver
+        .as_converter()
+        .try_into_write_set_payload(payload.clone())
+        .unwrap();
+
+    assert_json(
+        serde_json::to_value(converted).unwrap(),
+        json!({
+            "write_set": {
+                "type": "direct_write_set",
+                "changes": [
+                    {
+                        "type": "write_raw",
+                        "state_key_hash": written.hash().to_hex_literal(),
+                        "key": HexEncodedBytes::from(b"written".to_vec()),
+                        "value": HexEncodedBytes::from(b"value".to_vec()),
+                    },
+                    {
+                        "type": "delete_raw",
+                        "state_key_hash": deleted.hash().to_hex_literal(),
+                        "key": HexEncodedBytes::from(b"deleted".to_vec()),
+                    },
+                    {
+                        "type": "delete_raw",
+                        "state_key_hash": account.hash().to_hex_literal(),
+                        "key": HexEncodedBytes::from(AccountAddress::ONE.to_vec()),
+                    },
+                ],
+                "events": [],
+            }
+        }),
+    );
+
+    // the raw keys and values are in the requested bytes encoding
+    let converted = resolver
+        .as_converter()
+        .with_bytes_encoding(BytesEncoding::Utf8)
+        .try_into_write_set_payload(payload)
+        .unwrap();
+    let changes = &serde_json::to_value(converted).unwrap()["write_set"]["changes"];
+    assert_eq!(changes[0]["key"], "written");
+    assert_eq!(changes[0]["value"], "value");
+}
+
 #[tokio::test]
 async fn test_post_bcs_format_transaction() {
     let mut context = new_test_context(current_function_name!());
@@ -237,6 +303,50 @@ async fn test_post_bcs_format_transaction() {
         .await;
 }
 
+#[tokio::test]
+async fn test_post_transaction_with_idempotency_key() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    let resp = submit_with_idempotency_key(&context.expect_status_code(202), &txn).await;
+    context.commit_mempool_txns(10).await;
+
+    // the retry is not submitted again, so it doesn't fail for the used sequence number
+    let retry = submit_with_idempotency_key(&context.expect_status_code(202), &txn).await;
+    assert_eq!(retry, resp);
+
+    let other_account = context.gen_account();
+    let other = context.create_user_account(&other_account);
+    let resp = submit_with_idempotency_key(&context.expect_status_code(409), &other).await;
+    assert_eq!(resp["error_code"], "idempotency_key_reused");
+    // the transaction submitted with the key isn't told
+    let hash = txn.clone().committed_hash().to_hex_literal();
+    assert!(!resp["message"].as_str().unwrap().contains(&hash));
+
+    // the keys of the other clients are theirs
+    context
+        .expect_status_code(202)
+        .execute(idempotent_submission(&other).remote_addr("127.0.0.2:8080".parse().unwrap()))
+        .await;
+}
+
+async fn submit_with_idempotency_key(
+    context: &TestContext,
+    txn: &SignedTransaction,
+) -> serde_json::Value {
+    context.execute(idempotent_submission(txn)).await
+}
+
+fn idempotent_submission(txn: &SignedTransaction) -> warp::test::RequestBuilder {
+    warp::test::request()
+        .method("POST")
+        .path("/transactions")
+        .header("Content-Type", mime_types::BCS_SIGNED_TRANSACTION)
+        .header("Idempotency-Key", "payment-1")
+        .body(bcs::to_bytes(txn).unwrap())
+}
+
 #[tokio::test]
 async fn test_post_invalid_bcs_format_transaction() {
     let mut context = new_test_context(current_function_name!());
@@ -260,6 +370,59 @@ async fn test_post_invalid_signature_transaction() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_simulate_bcs_format_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let resp = context
+        .post_bcs_txn("/transactions/simulate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["type"], "user_transaction");
+    assert_eq!(resp["success"], true, "{}", pretty(&resp));
+    assert_eq!(resp["version"], (ledger_version + 1).to_string());
+    assert!(!resp["changes"].as_array().unwrap().is_empty());
+
+    // simulation does not commit the transaction
+    assert_eq!(context.get_latest_ledger_info().version(), ledger_version);
+    context
+        .expect_status_code(404)
+        .get(&format!("/accounts/{}", account.address().to_hex_literal()))
+        .await;
+}
+
+#[tokio::test]
+async fn test_simulate_json_format_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let pending_txn = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+
+    let resp = context.post("/transactions/simulate", pending_txn).await;
+    assert_eq!(resp["success"], true, "{}", pretty(&resp));
+}
+
+#[tokio::test]
+async fn test_simulate_transaction_does_not_check_signature() {
+    let mut context = new_test_context(current_function_name!());
+    let txn = context.create_invalid_signature_transaction();
+    let body = bcs::to_bytes(&txn).unwrap();
+    let resp = context.post_bcs_txn("/transactions/simulate", &body).await;
+    assert_eq!(resp["type"], "user_transaction");
+
+    // the signature is only skipped when simulating
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", &body)
+        .await;
+    assert_eq!(resp["message"], "invalid transaction: INVALID_SIGNATURE");
+}
+
 #[tokio::test]
 async fn test_post_transaction_rejected_by_mempool() {
     let mut context = new_test_context(current_function_name!());
@@ -280,6 +443,66 @@ async fn test_post_transaction_rejected_by_mempool() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_post_bcs_format_transactions_batch() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let account1 = context.gen_account();
+    let txn1 = context.create_user_account_by(&mut root_account, &account1);
+    let account2 = context.gen_account();
+    let txn2 = context.create_user_account_by(&mut root_account, &account2);
+
+    let resp = context
+        .expect_status_code(202)
+        .post_bcs_txn(
+            "/transactions/batch",
+            bcs::to_bytes(&vec![txn1.clone(), txn2.clone()]).unwrap(),
+        )
+        .await;
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    for (result, txn) in results.iter().zip(vec![txn1, txn2]) {
+        assert_eq!(result["status"], "accepted");
+        assert_eq!(result["transaction"]["type"], "pending_transaction");
+        assert_eq!(
+            result["transaction"]["sequence_number"],
+            txn.sequence_number().to_string()
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_post_bcs_format_transactions_batch_partially_rejected() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn1 = context.create_user_account(&account);
+    let txn2 = context.create_invalid_signature_transaction();
+
+    let resp = context
+        .expect_status_code(200)
+        .post_bcs_txn(
+            "/transactions/batch",
+            bcs::to_bytes(&vec![txn1, txn2]).unwrap(),
+        )
+        .await;
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["status"], "accepted");
+    assert_eq!(results[1]["status"], "rejected");
+    assert_eq!(results[1]["error"]["error_code"], "invalid_transaction");
+}
+
+#[tokio::test]
+async fn test_post_empty_transactions_batch() {
+    let context = new_test_context(current_function_name!());
+    let txns: Vec<SignedTransaction> = vec![];
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions/batch", bcs::to_bytes(&txns).unwrap())
+        .await;
+    assert_eq!(resp["error_code"], "invalid_request_body");
+}
+
 #[ignore]
 #[tokio::test]
 async fn test_multi_agent_signed_transaction() {
@@ -473,6 +696,214 @@ async fn test_get_transaction_by_version() {
     assert_json(resp, txns[0].clone())
 }
 
+#[tokio::test]
+async fn test_get_transaction_by_version_with_writeset() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let expected = context.get("/transactions/2").await;
+    let resp = context.get("/transactions/2?include_writeset=true").await;
+    assert_eq!(resp["hash"], expected["hash"]);
+    assert_eq!(resp["changes"], expected["changes"]);
+
+    let diffs = resp["write_set_diff"].as_array().unwrap();
+    let account_diff = |address: String| {
+        diffs
+            .iter()
+            .find(|diff| diff["address"] == address && diff["resource"] == "0x1::Account::Account")
+            .unwrap()
+            .clone()
+    };
+    // the created account has no resource before the transaction
+    let created = account_diff(account.address().to_hex_literal());
+    assert_eq!(created["before"], json!(null));
+    assert_eq!(created["after"]["type"], "0x1::Account::Account");
+
+    // the sequence number of the sender is increased by the transaction
+    let sender = account_diff(context.root_account().address().to_hex_literal());
+    let sequence_number = |value: &serde_json::Value| {
+        value["data"]["sequence_number"]
+            .as_str()
+            .unwrap()
+            .parse::<u64>()
+            .unwrap()
+    };
+    assert_eq!(
+        sequence_number(&sender["before"]) + 1,
+        sequence_number(&sender["after"])
+    );
+}
+
+#[tokio::test]
+async fn test_get_transaction_by_version_in_bcs() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/transactions/2")
+                .header("Accept", mime_types::BCS),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["Content-Type"], mime_types::BCS);
+    match bcs::from_bytes::<TransactionData>(resp.body()).unwrap() {
+        TransactionData::OnChain(data) => {
+            assert_eq!(data.version, 2);
+            assert_eq!(
+                data.transaction,
+                aptos_types::transaction::Transaction::UserTransaction(txn)
+            );
+        }
+        TransactionData::Pending(_) => panic!("expect on-chain transaction"),
+    }
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/transactions/2?include_writeset=true")
+                .header("Accept", mime_types::BCS),
+        )
+        .await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_get_transactions_summary() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let txns = context.get("/transactions").await;
+    let summaries = context.get("/transactions?summary=true").await;
+    let txns = txns.as_array().unwrap();
+    let summaries = summaries.as_array().unwrap();
+    assert_eq!(txns.len(), summaries.len());
+    for (txn, summary) in txns.iter().zip(summaries) {
+        for field in [
+            "type",
+            "hash",
+            "version",
+            "success",
+            "vm_status",
+            "gas_used",
+        ] {
+            assert_eq!(txn[field], summary[field]);
+        }
+        // the genesis transaction is rendered without timestamp
+        if txn["type"] != "genesis_transaction" {
+            assert_eq!(txn["timestamp"], summary["timestamp"]);
+        }
+        assert_eq!(summary["payload"], json!(null));
+        assert_eq!(summary["changes"], json!(null));
+    }
+
+    let sender = context.root_account().address().to_hex_literal();
+    let summaries = context
+        .get(&format!("/accounts/{}/transactions?summary=true", sender))
+        .await;
+    let summary = &summaries.as_array().unwrap()[0];
+    assert_eq!(summary["type"], "user_transaction");
+    assert_eq!(summary["sender"], sender);
+    assert_eq!(summary["success"], true);
+    assert_eq!(summary["events"], json!(null));
+}
+
+#[tokio::test]
+async fn test_get_transactions_range_in_ndjson() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let expected = context.get("/transactions?start=0&limit=10").await;
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/transactions/range?start_version=1&end_version=1000"),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["Content-Type"], mime_types::NDJSON);
+    let txns: Vec<serde_json::Value> = std::str::from_utf8(resp.body())
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let expected = expected.as_array().unwrap();
+    assert_eq!(txns.len(), expected.len() - 1);
+    for (txn, expected) in txns.iter().zip(&expected[1..]) {
+        assert_eq!(txn["hash"], expected["hash"]);
+    }
+}
+
+#[tokio::test]
+async fn test_get_transactions_range_in_bcs() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/transactions/range?start_version=0&end_version=3")
+                .header("Accept", mime_types::BCS),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["Content-Type"], mime_types::BCS);
+
+    let mut body = resp.body().as_ref();
+    let mut txns = vec![];
+    while !body.is_empty() {
+        let mut len = [0u8; 4];
+        len.copy_from_slice(&body[..4]);
+        let len = u32::from_le_bytes(len) as usize;
+        txns.push(bcs::from_bytes::<TransactionOnChainData>(&body[4..4 + len]).unwrap());
+        body = &body[4 + len..];
+    }
+    let versions: Vec<u64> = txns.iter().map(|txn| txn.version).collect();
+    assert_eq!(versions, vec![0, 1, 2]);
+    assert_eq!(
+        txns[2].transaction,
+        aptos_types::transaction::Transaction::UserTransaction(txn)
+    );
+}
+
+#[tokio::test]
+async fn test_get_transactions_range_with_invalid_range() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions/range?start_version=0")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_parameter");
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions/range?start_version=1&end_version=1")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_parameter");
+
+    let resp = context
+        .expect_status_code(404)
+        .get("/transactions/range?start_version=1000&end_version=2000")
+        .await;
+    assert_eq!(resp["error_code"], "transaction_not_found");
+}
+
 #[tokio::test]
 async fn test_get_pending_transaction_by_hash() {
     let mut context = new_test_context(current_function_name!());
@@ -489,6 +920,12 @@ async fn test_get_pending_transaction_by_hash() {
     let txn = context.get(&format!("/transactions/{}", txn_hash)).await;
     assert_json(txn, pending_txn);
 
+    let resp = context
+        .expect_status_code(400)
+        .get(&format!("/transactions/{}?include_writeset=true", txn_hash))
+        .await;
+    assert_eq!(resp["error_code"], "invalid_parameter");
+
     let not_found = context
         .expect_status_code(404)
         .get("/transactions/0xdadfeddcca7cb6396c735e9094c76c6e4e9cb3e3ef814730693aed59bd87b31d")
@@ -496,6 +933,80 @@ async fn test_get_pending_transaction_by_hash() {
     context.check_golden_output(not_found);
 }
 
+#[tokio::test]
+async fn test_get_pending_transaction_waiting_for_sequence_number() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let mut root_account = context.root_account();
+    *root_account.sequence_number_mut() = 2;
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    let pending_txn = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert!(pending_txn.get("waiting_for_sequence_number").is_none());
+
+    let txn_hash = pending_txn["hash"].as_str().unwrap();
+    let txn = context.get(&format!("/transactions/{}", txn_hash)).await;
+    assert_eq!(txn["type"], "pending_transaction");
+    assert_eq!(txn["waiting_for_sequence_number"], "0");
+}
+
+#[tokio::test]
+async fn test_wait_transaction_by_hash() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let pending_txn = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+    let txn_hash = pending_txn["hash"].as_str().unwrap();
+
+    let waiter = context.clone();
+    let path = format!("/transactions/wait_by_hash/{}?timeout_ms=10000", txn_hash);
+    let (txn, _) = tokio::join!(waiter.get(&path), context.commit_mempool_txns(1));
+    assert_eq!(txn["type"], "user_transaction");
+    assert_eq!(txn["hash"], txn_hash);
+    assert_eq!(txn["success"], true);
+}
+
+#[tokio::test]
+async fn test_wait_transaction_by_hash_timeout() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let pending_txn = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+    let txn_hash = pending_txn["hash"].as_str().unwrap();
+
+    let txn = context
+        .get(&format!(
+            "/transactions/wait_by_hash/{}?timeout_ms=100",
+            txn_hash
+        ))
+        .await;
+    assert_json(txn, pending_txn);
+}
+
+#[tokio::test]
+async fn test_wait_transaction_by_hash_not_found() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get("/transactions/wait_by_hash/0xdadfeddcca7cb6396c735e9094c76c6e4e9cb3e3ef814730693aed59bd87b31d")
+        .await;
+    assert_eq!(resp["error_code"], "transaction_not_found");
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions/wait_by_hash/0xdadfeddcca7cb6396c735e9094c76c6e4e9cb3e3ef814730693aed59bd87b31d?timeout_ms=abc")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_parameter");
+}
+
 #[tokio::test]
 async fn test_signing_message_with_script_function_payload() {
     let mut context = new_test_context(current_function_name!());
@@ -514,6 +1025,105 @@ async fn test_signing_message_with_script_function_payload() {
     test_signing_message_with_payload(context, txn, payload).await;
 }
 
+#[tokio::test]
+async fn test_script_function_batch_with_call_outputs() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+
+    // Transfers as many coins as the scaling factor returned by the first call.
+    context
+        .api_execute_txn(
+            &mut root_account,
+            json!({
+                "type": "script_function_batch_payload",
+                "calls": [
+                    {
+                        "function": "0x1::TestCoin::scaling_factor",
+                        "type_arguments": [],
+                        "arguments": [],
+                    },
+                    {
+                        "function": "0x1::TestCoin::transfer",
+                        "type_arguments": [],
+                        "arguments": [
+                            account.address().to_hex_literal(),
+                            {"call": 0, "output": 0},
+                        ],
+                    },
+                ],
+            }),
+        )
+        .await;
+
+    let resp = context
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::TestCoin::balance_of",
+                "type_arguments": [],
+                "arguments": [account.address().to_hex_literal()],
+            }),
+        )
+        .await;
+    assert_eq!(resp, json!(["1000000"]));
+}
+
+#[tokio::test]
+async fn test_signing_message_with_script_payload() {
+    let context = new_test_context(current_function_name!());
+    let code = hex::decode(GENERIC_SCRIPT).unwrap();
+    let mut root_account = context.root_account();
+    let txn = root_account.sign_with_transaction_builder(context.transaction_factory().script(
+        Script::new(
+            code,
+            vec![TypeTag::U64],
+            vec![TransactionArgument::U64(1), TransactionArgument::U64(2)],
+        ),
+    ));
+    let payload = json!({
+        "type": "script_payload",
+        "code": {
+            "bytecode": format!("0x{}", GENERIC_SCRIPT),
+        },
+        "type_arguments": ["u64"],
+        "arguments": ["1", "2"],
+    });
+
+    test_signing_message_with_payload(context, txn, payload).await;
+}
+
+#[tokio::test]
+async fn test_signing_message_with_invalid_script_argument() {
+    let context = new_test_context(current_function_name!());
+    let sender = context.root_account();
+    let body = json!({
+        "sender": sender.address().to_hex_literal(),
+        "sequence_number": sender.sequence_number().to_string(),
+        "gas_unit_price": "0",
+        "max_gas_amount": "1000000",
+        "gas_currency_code": "XUS",
+        "expiration_timestamp_secs": u64::MAX.to_string(),
+        "payload": {
+            "type": "script_payload",
+            "code": {
+                "bytecode": format!("0x{}", GENERIC_SCRIPT),
+            },
+            // vector<u64> is not a transaction argument type
+            "type_arguments": ["vector<u64>"],
+            "arguments": [["1"], "2"],
+        },
+    });
+
+    let resp = context
+        .expect_status_code(400)
+        .post("/transactions/signing_message", body)
+        .await;
+    assert_eq!(resp["error_code"], "invalid_request_body");
+}
+
 // need a correct module payload
 #[ignore]
 #[tokio::test]
@@ -616,6 +1226,50 @@ async fn test_signing_message_with_write_set_payload() {
         .await;
 }
 
+#[tokio::test]
+async fn test_signing_message_for_multi_agent_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let factory = context.transaction_factory();
+    let mut root_account = context.root_account();
+    let secondary = context.root_account();
+    let txn = root_account.sign_multi_agent_with_transaction_builder(
+        vec![&secondary],
+        factory.create_user_account(account.public_key()),
+    );
+
+    let body = json!({
+        "sender": txn.sender().to_hex_literal(),
+        "sequence_number": txn.sequence_number().to_string(),
+        "gas_unit_price": txn.gas_unit_price().to_string(),
+        "max_gas_amount": txn.max_gas_amount().to_string(),
+        "gas_currency_code": txn.gas_currency_code(),
+        "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+        "payload": {
+            "type": "script_function_payload",
+            "function": "0x1::Account::create_account",
+            "type_arguments": [],
+            "arguments": [account.address().to_hex_literal()]
+        },
+        "secondary_signers": [secondary.address().to_hex_literal()],
+    });
+    let resp = context.post("/transactions/signing_message", body).await;
+
+    let expected = aptos_crypto::signing_message(&RawTransactionWithData::new_multi_agent(
+        txn.clone().into_raw_transaction(),
+        vec![secondary.address()],
+    ));
+    assert_eq!(
+        resp["message"].as_str().unwrap(),
+        format!("0x{}", hex::encode(&expected))
+    );
+    assert_ne!(expected, txn.into_raw_transaction().signing_message());
+}
+
+// Bytecode of a script `main<T>(x: T, y: u64)` doing nothing, which is not verifiable but
+// enough for the signing message.
+const GENERIC_SCRIPT: &str = "a11ceb0b04000000010500050002090003010001000102";
+
 async fn test_signing_message_with_payload(
     mut context: TestContext,
     txn: SignedTransaction,
@@ -698,6 +1352,115 @@ async fn test_get_account_transactions() {
     assert_json(txns, expected_txns);
 }
 
+#[tokio::test]
+async fn test_get_account_participated_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let mut root_account = context.root_account();
+    let txn = root_account.sign_with_transaction_builder(
+        context
+            .transaction_factory()
+            .mint(account.address(), 1000)
+            .expiration_timestamp_secs(u64::MAX),
+    );
+    context.commit_block(&vec![txn]).await;
+
+    let root_txns = context
+        .get(
+            format!(
+                "/accounts/{}/transactions",
+                context.root_account().address()
+            )
+            .as_str(),
+        )
+        .await;
+    let root_txns = root_txns.as_array().unwrap();
+    assert_eq!(2, root_txns.len());
+
+    // the mint deposits to the event stream of the account
+    let txns = context
+        .get(format!("/accounts/{}/participated_transactions", account.address()).as_str())
+        .await;
+    let txns = txns.as_array().unwrap();
+    assert_json(txns.last().unwrap().clone(), root_txns[1].clone());
+
+    let mint_version = root_txns[1]["version"].as_str().unwrap();
+    let txns = context
+        .get(
+            format!(
+                "/accounts/{}/participated_transactions?start={}",
+                context.root_account().address(),
+                mint_version,
+            )
+            .as_str(),
+        )
+        .await;
+    assert_json(txns[0].clone(), root_txns[1].clone());
+}
+
+#[tokio::test]
+async fn test_get_account_transactions_include_pending() {
+    let mut context = new_test_context(current_function_name!());
+    let account1 = context.gen_account();
+    let txn = context.create_user_account(&account1);
+    context.commit_block(&vec![txn]).await;
+
+    let account2 = context.gen_account();
+    let txn = context.create_user_account(&account2);
+    let pending_txn = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+
+    let address = context.root_account().address();
+    let txns = context
+        .get(format!("/accounts/{}/transactions", address).as_str())
+        .await;
+    assert_eq!(1, txns.as_array().unwrap().len());
+
+    let txns = context
+        .get(format!("/accounts/{}/transactions?include_pending=true", address).as_str())
+        .await;
+    let txns = txns.as_array().unwrap();
+    assert_eq!(2, txns.len());
+    assert_eq!(txns[1]["type"], "pending_transaction");
+    assert_json(txns[1].clone(), pending_txn);
+
+    let txns = context
+        .get(
+            format!(
+                "/accounts/{}/transactions?start=1&include_pending=true",
+                address
+            )
+            .as_str(),
+        )
+        .await;
+    assert_json(txns, json!([pending_txn]));
+
+    let txns = context
+        .get(
+            format!(
+                "/accounts/{}/transactions?limit=1&include_pending=true",
+                address
+            )
+            .as_str(),
+        )
+        .await;
+    assert_eq!(1, txns.as_array().unwrap().len());
+    assert_eq!(txns[0]["type"], "user_transaction");
+
+    // The committed transaction isn't listed again while mempool still holds it.
+    context.commit_block(&vec![txn]).await;
+    let txns = context
+        .get(format!("/accounts/{}/transactions?include_pending=true", address).as_str())
+        .await;
+    let txns = txns.as_array().unwrap();
+    assert_eq!(2, txns.len());
+    assert_eq!(txns[1]["type"], "user_transaction");
+}
+
 #[tokio::test]
 async fn test_get_account_transactions_filter_transactions_by_start_sequence_number() {
     let mut context = new_test_context(current_function_name!());
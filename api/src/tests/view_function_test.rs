@@ -0,0 +1,77 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::new_test_context};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_view_function() {
+    let mut context = new_test_context(current_function_name!());
+    let request = json!({
+        "function": "0x1::Account::get_sequence_number",
+        "type_arguments": [],
+        "arguments": [context.root_account().address().to_hex_literal()],
+    });
+
+    let resp = context.post("/view", request.clone()).await;
+    assert_eq!(resp, json!(["0"]));
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context.post("/view", request).await;
+    assert_eq!(resp, json!(["1"]));
+}
+
+#[tokio::test]
+async fn test_view_function_aborted() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Account::get_sequence_number",
+                "type_arguments": [],
+                "arguments": [account.address().to_hex_literal()],
+            }),
+        )
+        .await;
+    assert_eq!(resp["error_code"], "vm_error");
+}
+
+#[tokio::test]
+async fn test_view_function_not_found() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Account::not_exist",
+                "type_arguments": [],
+                "arguments": [],
+            }),
+        )
+        .await;
+    assert_eq!(resp["error_code"], "invalid_request_body");
+}
+
+#[tokio::test]
+async fn test_view_function_with_invalid_arguments() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Account::get_sequence_number",
+                "type_arguments": [],
+                "arguments": [],
+            }),
+        )
+        .await;
+    assert_eq!(resp["error_code"], "invalid_request_body");
+}
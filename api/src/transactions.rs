@@ -2,62 +2,169 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    accept_type::{accept_type, AcceptType},
+    api_key::api_key,
     context::Context,
     failpoint::fail_point,
+    head::get_or_head,
+    idempotency::{IdempotencyKey, Reservation, IDEMPOTENCY_KEY, MAX_IDEMPOTENCY_KEY_LENGTH},
     metrics::metrics,
     page::Page,
-    param::{AddressParam, TransactionIdParam},
+    param::{
+        AddressParam, Param, TransactionHashParam, TransactionIdParam, TransactionVersionParam,
+    },
+    rate_limit::{client_key, ClientKey},
+    response_cache::ResponseCacheKey,
 };
 
 use aptos_api_types::{
-    mime_types::BCS_SIGNED_TRANSACTION, AsConverter, Error, LedgerInfo, Response, Transaction,
-    TransactionData, TransactionId, TransactionOnChainData, TransactionSigningMessage,
-    UserTransactionRequest,
+    mime_types::{self, BCS_SIGNED_TRANSACTION},
+    AptosErrorCode, AsConverter, Error, LedgerInfo, Response, Transaction, TransactionData,
+    TransactionId, TransactionOnChainData, TransactionSigningMessage,
+    TransactionSigningMessageRequest, TransactionSubmissionResult, TransactionSummary,
+    TransactionWithWriteSetDiff, UserTransactionRequest, U64,
 };
+use aptos_crypto::{signing_message, HashValue};
 use aptos_types::{
-    mempool_status::MempoolStatusCode,
-    transaction::{RawTransaction, SignedTransaction},
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    transaction::{
+        RawTransaction, RawTransactionWithData, SignedTransaction, TransactionInfo,
+        TransactionStatus,
+    },
+    vm_status::DiscardedVMStatus,
 };
 
 use anyhow::Result;
+use bytes::Bytes;
+use futures::stream;
+use serde::Deserialize;
+use std::{
+    cmp::{max, min},
+    time::Duration,
+};
 use warp::{
     filters::BoxedFilter,
     http::{header::CONTENT_TYPE, StatusCode},
+    hyper::Body,
     reply, Filter, Rejection, Reply,
 };
 
-// GET /transactions/{txn-hash / version}
+#[derive(Clone, Debug, Deserialize)]
+struct TransactionQuery {
+    include_writeset: Option<Param<bool>>,
+}
+
+// GET /transactions/{txn-hash / version}?include_writeset={bool}
 pub fn get_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions" / TransactionIdParam)
-        .and(warp::get())
+        .and(get_or_head())
+        .and(warp::query::<TransactionQuery>())
+        .and(accept_type())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_get_transaction)
         .with(metrics("get_transaction"))
         .boxed()
 }
 
-// GET /transactions?start={u64}&limit={u16}
+// Timeout of waiting for a transaction when `timeout_ms` is not given.
+const DEFAULT_WAIT_TRANSACTION_TIMEOUT_MS: u64 = 1_000;
+// The `timeout_ms` given by client is capped to this, so connections are not held forever.
+const MAX_WAIT_TRANSACTION_TIMEOUT_MS: u64 = 10_000;
+
+#[derive(Clone, Debug, Deserialize)]
+struct WaitTransactionQuery {
+    timeout_ms: Option<Param<u64>>,
+}
+
+// GET /transactions/wait_by_hash/{txn-hash}?timeout_ms={u64}
+pub fn wait_transaction_by_hash(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "wait_by_hash" / TransactionHashParam)
+        .and(warp::get())
+        .and(warp::query::<WaitTransactionQuery>())
+        .and(accept_type())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_wait_transaction_by_hash)
+        .with(metrics("wait_transaction_by_hash"))
+        .boxed()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct TransactionsQuery {
+    summary: Option<Param<bool>>,
+}
+
+// GET /transactions?start={u64}&limit={u16}&summary={bool}
 pub fn get_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions")
-        .and(warp::get())
+        .and(get_or_head())
         .and(warp::query::<Page>())
+        .and(warp::query::<TransactionsQuery>())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_get_transactions)
         .with(metrics("get_transactions"))
         .boxed()
 }
 
-// GET /accounts/{address}/transactions?start={u64}&limit={u16}
+// Max number of transactions streamed by one range request.
+const MAX_TRANSACTIONS_RANGE_SIZE: u64 = 100_000;
+// Number of transactions loaded from the database at once while streaming a range.
+const TRANSACTIONS_RANGE_BATCH_SIZE: u64 = 1000;
+
+#[derive(Clone, Debug, Deserialize)]
+struct TransactionsRangeQuery {
+    start_version: Option<TransactionVersionParam>,
+    end_version: Option<TransactionVersionParam>,
+}
+
+// GET /transactions/range?start_version={u64}&end_version={u64}
+pub fn get_transactions_range(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "range")
+        .and(get_or_head())
+        .and(warp::query::<TransactionsRangeQuery>())
+        .and(accept_type())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_transactions_range)
+        .with(metrics("get_transactions_range"))
+        .boxed()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct AccountTransactionsQuery {
+    include_pending: Option<Param<bool>>,
+    summary: Option<Param<bool>>,
+}
+
+// GET /accounts/{address}/transactions?start={u64}&limit={u16}&include_pending={bool}
+//     &summary={bool}
 pub fn get_account_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "transactions")
-        .and(warp::get())
+        .and(get_or_head())
         .and(warp::query::<Page>())
+        .and(warp::query::<AccountTransactionsQuery>())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_get_account_transactions)
         .with(metrics("get_account_transactions"))
         .boxed()
 }
 
+// GET /accounts/{address}/participated_transactions?start={u64}&limit={u16}&summary={bool}
+pub fn get_account_participated_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "participated_transactions")
+        .and(get_or_head())
+        .and(warp::query::<Page>())
+        .and(warp::query::<TransactionsQuery>())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_get_account_participated_transactions)
+        .with(metrics("get_account_participated_transactions"))
+        .boxed()
+}
+
 // POST /transactions with JSON
 pub fn submit_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions")
@@ -66,6 +173,8 @@ pub fn submit_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)>
             context.content_length_limit(),
         ))
         .and(warp::body::json::<UserTransactionRequest>())
+        .and(idempotency_key(context.clone()))
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_submit_json_transactions)
         .with(metrics("submit_json_transactions"))
@@ -89,12 +198,69 @@ pub fn submit_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
             BCS_SIGNED_TRANSACTION,
         ))
         .and(warp::body::bytes())
+        .and(idempotency_key(context.clone()))
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_submit_bcs_transactions)
         .with(metrics("submit_bcs_transactions"))
         .boxed()
 }
 
+// POST /transactions/batch with BCS
+pub fn submit_bcs_transactions_batch(context: Context) -> BoxedFilter<(impl Reply,)> {
+    // see `submit_bcs_transactions` for why the content-type header is matched exactly.
+    warp::path!("transactions" / "batch")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::header::exact(
+            CONTENT_TYPE.as_str(),
+            BCS_SIGNED_TRANSACTION,
+        ))
+        .and(warp::body::bytes())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_submit_bcs_transactions_batch)
+        .with(metrics("submit_bcs_transactions_batch"))
+        .boxed()
+}
+
+// POST /transactions/simulate with JSON
+pub fn simulate_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "simulate")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<UserTransactionRequest>())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_simulate_json_transactions)
+        .with(metrics("simulate_json_transactions"))
+        .boxed()
+}
+
+// POST /transactions/simulate with BCS
+pub fn simulate_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    // see `submit_bcs_transactions` for why the content-type header is matched exactly.
+    warp::path!("transactions" / "simulate")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::header::exact(
+            CONTENT_TYPE.as_str(),
+            BCS_SIGNED_TRANSACTION,
+        ))
+        .and(warp::body::bytes())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_simulate_bcs_transactions)
+        .with(metrics("simulate_bcs_transactions"))
+        .boxed()
+}
+
 // POST /transactions/signing_message
 pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions" / "signing_message")
@@ -102,7 +268,8 @@ pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
         .and(warp::body::content_length_limit(
             context.content_length_limit(),
         ))
-        .and(warp::body::json::<UserTransactionRequest>())
+        .and(warp::body::json::<TransactionSigningMessageRequest>())
+        .and(api_key(context.clone()))
         .and(context.filter())
         .and_then(handle_create_signing_message)
         .with(metrics("create_signing_message"))
@@ -111,63 +278,198 @@ pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
 
 async fn handle_get_transaction(
     id: TransactionIdParam,
+    query: TransactionQuery,
+    accept_type: AcceptType,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_transaction")?;
+    let include_writeset = query
+        .include_writeset
+        .map(|param| param.parse("include_writeset"))
+        .transpose()?
+        .unwrap_or(false);
     Ok(Transactions::new(context)?
-        .get_transaction(id.parse("transaction hash or version")?)
+        .get_transaction(
+            id.parse("transaction hash or version")?,
+            include_writeset,
+            accept_type,
+        )
         .await?)
 }
 
-async fn handle_get_transactions(page: Page, context: Context) -> Result<impl Reply, Rejection> {
+async fn handle_wait_transaction_by_hash(
+    hash: TransactionHashParam,
+    query: WaitTransactionQuery,
+    accept_type: AcceptType,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_wait_transaction_by_hash")?;
+    let timeout_ms = query
+        .timeout_ms
+        .map(|param| param.parse("timeout_ms"))
+        .transpose()?
+        .unwrap_or(DEFAULT_WAIT_TRANSACTION_TIMEOUT_MS);
+    let timeout = Duration::from_millis(min(timeout_ms, MAX_WAIT_TRANSACTION_TIMEOUT_MS));
+    Ok(Transactions::wait_by_hash(
+        context,
+        hash.parse("transaction hash")?.into(),
+        timeout,
+        accept_type,
+    )
+    .await?)
+}
+
+async fn handle_get_transactions(
+    page: Page,
+    query: TransactionsQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_transactions")?;
-    Ok(Transactions::new(context)?.list(page)?)
+    let summary = query
+        .summary
+        .map(|param| param.parse("summary"))
+        .transpose()?
+        .unwrap_or(false);
+    Ok(Transactions::new(context)?.list(page, summary)?)
+}
+
+async fn handle_get_transactions_range(
+    query: TransactionsRangeQuery,
+    accept_type: AcceptType,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_transactions_range")?;
+    let parse = |param: Option<TransactionVersionParam>, name: &str| {
+        param
+            .ok_or_else(|| {
+                Error::bad_request(
+                    AptosErrorCode::InvalidParameter,
+                    format!("missing parameter {}", name),
+                )
+            })?
+            .parse(name)
+    };
+    let start_version = parse(query.start_version, "start_version")?;
+    let end_version = parse(query.end_version, "end_version")?;
+    Ok(Transactions::new(context)?.stream_range(start_version, end_version, accept_type)?)
 }
 
 async fn handle_get_account_transactions(
     address: AddressParam,
     page: Page,
+    query: AccountTransactionsQuery,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_transactions")?;
-    Ok(Transactions::new(context)?.list_by_account(address, page)?)
+    let include_pending = query
+        .include_pending
+        .map(|param| param.parse("include_pending"))
+        .transpose()?
+        .unwrap_or(false);
+    let summary = query
+        .summary
+        .map(|param| param.parse("summary"))
+        .transpose()?
+        .unwrap_or(false);
+    Ok(Transactions::new(context)?
+        .list_by_account(address, page, include_pending, summary)
+        .await?)
+}
+
+async fn handle_get_account_participated_transactions(
+    address: AddressParam,
+    page: Page,
+    query: TransactionsQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_participated_transactions")?;
+    let summary = query
+        .summary
+        .map(|param| param.parse("summary"))
+        .transpose()?
+        .unwrap_or(false);
+    Ok(Transactions::new(context)?
+        .list_by_participant(address, page, summary)
+        .await?)
+}
+
+fn idempotency_key(
+    context: Context,
+) -> impl Filter<Extract = (Option<IdempotencyKey>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(IDEMPOTENCY_KEY)
+        .and(client_key(context))
+        .map(|key: Option<String>, client: Option<ClientKey>| {
+            key.map(|key| IdempotencyKey { client, key })
+        })
 }
 
 async fn handle_submit_json_transactions(
     body: UserTransactionRequest,
+    idempotency_key: Option<IdempotencyKey>,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_submit_json_transactions")?;
     Ok(Transactions::new(context)?
-        .create_from_request(body)
+        .create_from_request(body, idempotency_key)
         .await?)
 }
 
 async fn handle_submit_bcs_transactions(
     body: bytes::Bytes,
+    idempotency_key: Option<IdempotencyKey>,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_submit_bcs_transactions")?;
     let txn = bcs::from_bytes(&body)
         .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
-    Ok(Transactions::new(context)?.create(txn).await?)
+    Ok(Transactions::new(context)?
+        .create(txn, idempotency_key)
+        .await?)
 }
 
-async fn handle_create_signing_message(
+async fn handle_submit_bcs_transactions_batch(
+    body: bytes::Bytes,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_submit_bcs_transactions_batch")?;
+    let txns: Vec<SignedTransaction> = bcs::from_bytes(&body)
+        .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
+    Ok(Transactions::new(context)?.create_batch(txns).await?)
+}
+
+async fn handle_simulate_json_transactions(
     body: UserTransactionRequest,
     context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_simulate_json_transactions")?;
+    Ok(Transactions::new(context)?.simulate_from_request(body)?)
+}
+
+async fn handle_simulate_bcs_transactions(
+    body: bytes::Bytes,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_simulate_bcs_transactions")?;
+    let txn = bcs::from_bytes(&body)
+        .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
+    Ok(Transactions::new(context)?.simulate(txn)?)
+}
+
+async fn handle_create_signing_message(
+    body: TransactionSigningMessageRequest,
+    context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_create_signing_message")?;
     Ok(Transactions::new(context)?.signing_message(body)?)
 }
 
-struct Transactions {
+pub(crate) struct Transactions {
     ledger_info: LedgerInfo,
     context: Context,
 }
 
 impl Transactions {
-    fn new(context: Context) -> Result<Self, Error> {
+    pub fn new(context: Context) -> Result<Self, Error> {
         let ledger_info = context.get_latest_ledger_info()?;
         Ok(Self {
             ledger_info,
@@ -178,9 +480,60 @@ impl Transactions {
     pub async fn create_from_request(
         self,
         req: UserTransactionRequest,
+        idempotency_key: Option<IdempotencyKey>,
     ) -> Result<impl Reply, Error> {
-        let txn = self
-            .context
+        let txn = self.signed_transaction_from_request(req)?;
+        self.create(txn, idempotency_key).await
+    }
+
+    pub fn simulate_from_request(self, req: UserTransactionRequest) -> Result<impl Reply, Error> {
+        let txn = self.signed_transaction_from_request(req)?;
+        self.simulate(txn)
+    }
+
+    /// Executes the transaction against the latest ledger state without committing it, and
+    /// renders it as if it is committed as the next transaction. The signature is not checked,
+    /// so the transaction can be simulated before it is signed.
+    pub fn simulate(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
+        let (vm_status, output) = self.context.simulate_transaction(&txn)?;
+        let (write_set, events, gas_used, status) = output.unpack();
+        let status = match status {
+            TransactionStatus::Keep(status) => status,
+            TransactionStatus::Discard(_) | TransactionStatus::Retry => {
+                return Err(Error::bad_request(
+                    AptosErrorCode::InvalidTransaction,
+                    format!("invalid transaction: {:?}", vm_status),
+                ))
+            }
+        };
+        let info = TransactionInfo::new(
+            txn.clone().committed_hash(),
+            HashValue::zero(),
+            HashValue::zero(),
+            gas_used,
+            status,
+        );
+        let data = TransactionOnChainData {
+            version: self.ledger_info.version() + 1,
+            transaction: aptos_types::transaction::Transaction::UserTransaction(txn),
+            info,
+            events,
+            accumulator_root_hash: HashValue::zero(),
+            changes: write_set,
+        };
+
+        let resolver = self.context.move_resolver()?;
+        let txn = resolver
+            .as_converter()
+            .try_into_onchain_transaction(self.ledger_info.timestamp(), data)?;
+        Response::new(self.ledger_info, &txn)
+    }
+
+    fn signed_transaction_from_request(
+        &self,
+        req: UserTransactionRequest,
+    ) -> Result<SignedTransaction, Error> {
+        self.context
             .move_resolver()?
             .as_converter()
             .try_into_signed_transaction(req, self.context.chain_id())
@@ -189,33 +542,113 @@ impl Transactions {
                     "failed to create SignedTransaction from UserTransactionRequest: {}",
                     e
                 ))
-            })?;
-        self.create(txn).await
+            })
     }
 
-    pub async fn create(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
-        let (mempool_status, vm_status_opt) = self.context.submit_transaction(txn.clone()).await?;
-        match mempool_status.code {
-            MempoolStatusCode::Accepted => {
-                let resolver = self.context.move_resolver()?;
-                let pending_txn = resolver.as_converter().try_into_pending_transaction(txn)?;
-                let resp = Response::new(self.ledger_info, &pending_txn)?;
-                Ok(reply::with_status(resp, StatusCode::ACCEPTED))
+    /// Submits the transaction to mempool. When the `idempotency_key` was used by the client to
+    /// submit the same transaction before, or is being used to, the transaction is not submitted
+    /// again and the response is the same as the one of the first submission.
+    pub async fn create(
+        self,
+        txn: SignedTransaction,
+        idempotency_key: Option<IdempotencyKey>,
+    ) -> Result<impl Reply, Error> {
+        if let Some(IdempotencyKey { key, .. }) = &idempotency_key {
+            if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+                return Err(Error::invalid_param(IDEMPOTENCY_KEY, key));
             }
-            MempoolStatusCode::VmError => Err(Error::bad_request(format!(
-                "invalid transaction: {}",
-                vm_status_opt
-                    .map(|s| format!("{:?}", s))
-                    .unwrap_or_else(|| "UNKNOWN".to_owned())
-            ))),
-            _ => Err(Error::bad_request(format!(
-                "transaction is rejected: {}",
-                mempool_status,
-            ))),
         }
+        let hash = txn.clone().committed_hash();
+        let reservation = match &idempotency_key {
+            Some(key) => self.context.idempotency_cache().reserve(key.clone(), hash),
+            None => Reservation::New,
+        };
+        match reservation {
+            Reservation::New => {
+                if let Err(err) = self.submit(txn.clone()).await {
+                    if let Some(key) = &idempotency_key {
+                        self.context.idempotency_cache().release(key, hash);
+                    }
+                    return Err(err);
+                }
+            }
+            Reservation::Submitted => (),
+            Reservation::Conflict => {
+                return Err(Error::new(
+                    StatusCode::CONFLICT,
+                    AptosErrorCode::IdempotencyKeyReused,
+                    format!(
+                        "{} {} was used to submit another transaction",
+                        IDEMPOTENCY_KEY,
+                        idempotency_key.map(|key| key.key).unwrap_or_default()
+                    ),
+                ));
+            }
+        }
+        let resolver = self.context.move_resolver()?;
+        let pending_txn = resolver.as_converter().try_into_pending_transaction(txn)?;
+        let resp = Response::new(self.ledger_info, &pending_txn)?;
+        Ok(reply::with_status(resp, StatusCode::ACCEPTED))
     }
 
-    pub fn list(self, page: Page) -> Result<impl Reply, Error> {
+    async fn submit(&self, txn: SignedTransaction) -> Result<(), Error> {
+        let (mempool_status, vm_status_opt) = self.context.submit_transaction(txn).await?;
+        match submission_error(mempool_status, vm_status_opt) {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Submits the transactions to mempool one by one, a rejected transaction doesn't
+    /// stop the submission of the following ones. The batch is answered with 202 when all the
+    /// transactions are accepted and 200 otherwise, the status of each one being in its result.
+    pub async fn create_batch(self, txns: Vec<SignedTransaction>) -> Result<impl Reply, Error> {
+        let max_batch_size = self.context.max_submit_transaction_batch_size();
+        if txns.is_empty() || txns.len() > max_batch_size {
+            return Err(Error::invalid_request_body(format!(
+                "invalid batch size {}, it should be between 1 and {}",
+                txns.len(),
+                max_batch_size
+            )));
+        }
+
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+        let mut results = Vec::with_capacity(txns.len());
+        for txn in txns {
+            let (mempool_status, vm_status_opt) =
+                self.context.submit_transaction(txn.clone()).await?;
+            results.push(match submission_error(mempool_status, vm_status_opt) {
+                None => TransactionSubmissionResult::Accepted {
+                    transaction: converter.try_into_pending_transaction(txn)?,
+                },
+                Some(error) => TransactionSubmissionResult::Rejected { error },
+            });
+        }
+
+        let status = if results.iter().all(|result| result.is_accepted()) {
+            StatusCode::ACCEPTED
+        } else {
+            StatusCode::OK
+        };
+        let resp = Response::new(self.ledger_info, &results)?;
+        Ok(reply::with_status(resp, status))
+    }
+
+    /// Lists the committed transactions of the page; with `summary`, the transactions are
+    /// rendered as `TransactionSummary`.
+    pub fn list(self, page: Page, summary: bool) -> Result<impl Reply, Error> {
+        let data = self.get_committed_page(&page)?;
+        self.page_response(data, summary)
+    }
+
+    /// Returns the committed transactions of the page, the last page by default.
+    pub fn list_committed(&self, page: &Page) -> Result<Vec<Transaction>, Error> {
+        let data = self.get_committed_page(page)?;
+        Ok(self.render_transactions(data)?)
+    }
+
+    fn get_committed_page(&self, page: &Page) -> Result<Vec<TransactionOnChainData>, Error> {
         let ledger_version = self.ledger_info.version();
         let limit = page.limit()?;
         let last_page_start = if ledger_version > (limit as u64) {
@@ -223,36 +656,183 @@ impl Transactions {
         } else {
             0
         };
+        let last_page_start = max(last_page_start, self.context.get_oldest_version()?);
         let start_version = page.start(last_page_start, ledger_version)?;
+        self.context
+            .ensure_transaction_not_pruned(start_version, &self.ledger_info)?;
 
-        let data = self
+        Ok(self
             .context
-            .get_transactions(start_version, limit, ledger_version)?;
-
-        self.render_transactions(data)
+            .get_transactions(start_version, limit, ledger_version)?)
     }
 
-    pub fn list_by_account(self, address: AddressParam, page: Page) -> Result<impl Reply, Error> {
+    /// Lists the committed transactions sent by the account starting from the `start`
+    /// sequence number; with `include_pending`, the page is filled up with the transactions
+    /// of the account waiting in mempool, which follow all committed ones.
+    pub async fn list_by_account(
+        self,
+        address: AddressParam,
+        page: Page,
+        include_pending: bool,
+        summary: bool,
+    ) -> Result<impl Reply, Error> {
+        let address = self.context.resolve_address(address)?.into();
+        let start = page.start(0, u64::MAX)?;
+        let limit = page.limit()?;
         let data = self.context.get_account_transactions(
-            address.parse("account address")?.into(),
-            page.start(0, u64::MAX)?,
-            page.limit()?,
+            address,
+            start,
+            limit,
             self.ledger_info.version(),
         )?;
-        self.render_transactions(data)
+        let mut pending = vec![];
+        if include_pending && data.len() < limit as usize {
+            // mempool may still hold the transactions committed at the ledger version
+            let committed_sequence_number = match self
+                .context
+                .get_account_state(address, self.ledger_info.version())?
+            {
+                Some(state) => state
+                    .get_account_resource()?
+                    .map_or(0, |account| account.sequence_number()),
+                None => 0,
+            };
+            pending = self
+                .context
+                .get_pending_account_transactions(address)
+                .await?
+                .into_iter()
+                .filter(|txn| txn.sequence_number() >= max(start, committed_sequence_number))
+                .take(limit as usize - data.len())
+                .collect();
+        }
+
+        if summary {
+            let mut summaries = self.render_summaries(data)?;
+            summaries.extend(pending.iter().map(TransactionSummary::from));
+            return Response::new(self.ledger_info, &summaries);
+        }
+        let mut txns = self.render_transactions(data)?;
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+        for txn in pending {
+            txns.push(converter.try_into_pending_transaction(txn)?);
+        }
+        Response::new(self.ledger_info, &txns)
+    }
+
+    /// Lists the committed transactions the account participated in, as the sender, a secondary
+    /// signer or the owner of an event stream emitted to, starting from the `start` version.
+    pub async fn list_by_participant(
+        self,
+        address: AddressParam,
+        page: Page,
+        summary: bool,
+    ) -> Result<impl Reply, Error> {
+        let address = self.context.resolve_address(address)?.into();
+        let ledger_version = self.ledger_info.version();
+        let start = page.start(0, ledger_version)?;
+        let limit = page.limit()?;
+        let data = self.context.get_account_participated_transactions(
+            address,
+            start,
+            limit,
+            ledger_version,
+        )?;
+        self.page_response(data, summary)
+    }
+
+    /// Streams the committed transactions in the version range `[start_version, end_version)`,
+    /// the range is cut at the latest ledger version. The transactions are rendered as
+    /// newline-delimited JSON, or as BCS serialized `TransactionOnChainData` each prefixed with
+    /// its length in 4 bytes little-endian.
+    ///
+    /// The status is sent before the transactions are loaded, so an error while streaming
+    /// aborts the response and the client sees an incomplete body.
+    pub fn stream_range(
+        self,
+        start_version: u64,
+        end_version: u64,
+        accept_type: AcceptType,
+    ) -> Result<warp::reply::Response, Error> {
+        let ledger_version = self.ledger_info.version();
+        if start_version > ledger_version {
+            return Err(self.transaction_not_found(TransactionId::Version(start_version)));
+        }
+        if end_version <= start_version {
+            return Err(Error::invalid_param("end_version", end_version));
+        }
+        self.context
+            .ensure_transaction_not_pruned(start_version, &self.ledger_info)?;
+        let end_version = min(end_version, ledger_version + 1);
+        if end_version - start_version > MAX_TRANSACTIONS_RANGE_SIZE {
+            return Err(Error::bad_request(
+                AptosErrorCode::InvalidParameter,
+                format!(
+                    "at most {} transactions can be requested in one range",
+                    MAX_TRANSACTIONS_RANGE_SIZE
+                ),
+            ));
+        }
+
+        let mut resp = Response::new_bcs_bytes(self.ledger_info.clone(), vec![]);
+        resp.content_type = match accept_type {
+            AcceptType::Json => mime_types::NDJSON,
+            AcceptType::Bcs => mime_types::BCS,
+        };
+        let chunks = stream::try_unfold((self, start_version), move |(txns, version)| async move {
+            if version >= end_version {
+                return Ok(None);
+            }
+            let limit = min(end_version - version, TRANSACTIONS_RANGE_BATCH_SIZE);
+            let data = txns
+                .context
+                .get_transactions(version, limit as u16, ledger_version)?;
+            let chunk = txns.encode_transactions(data, accept_type)?;
+            Ok::<_, anyhow::Error>(Some((chunk, (txns, version + limit))))
+        });
+
+        let mut resp = resp.into_response();
+        *resp.body_mut() = Body::wrap_stream(chunks);
+        Ok(resp)
     }
 
-    fn render_transactions(self, data: Vec<TransactionOnChainData>) -> Result<impl Reply, Error> {
+    fn encode_transactions(
+        &self,
+        data: Vec<TransactionOnChainData>,
+        accept_type: AcceptType,
+    ) -> Result<Bytes> {
+        let mut buf = vec![];
+        match accept_type {
+            AcceptType::Json => {
+                for txn in self.render_transactions(data)? {
+                    serde_json::to_writer(&mut buf, &txn)?;
+                    buf.push(b'\n');
+                }
+            }
+            AcceptType::Bcs => {
+                for txn in data {
+                    let bytes = bcs::to_bytes(&txn)?;
+                    buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+                    buf.extend_from_slice(&bytes);
+                }
+            }
+        }
+        Ok(buf.into())
+    }
+
+    pub fn render_transactions(
+        &self,
+        data: Vec<TransactionOnChainData>,
+    ) -> Result<Vec<Transaction>> {
         if data.is_empty() {
-            let txns: Vec<Transaction> = vec![];
-            return Response::new(self.ledger_info, &txns);
+            return Ok(vec![]);
         }
         let first_version = data[0].version;
         let mut timestamp = self.context.get_block_timestamp(first_version)?;
         let resolver = self.context.move_resolver()?;
         let converter = resolver.as_converter();
-        let txns: Vec<Transaction> = data
-            .into_iter()
+        data.into_iter()
             .map(|t| {
                 let txn = converter.try_into_onchain_transaction(timestamp, t)?;
                 // update timestamp, when txn is metadata block transaction
@@ -260,17 +840,213 @@ impl Transactions {
                 timestamp = txn.timestamp();
                 Ok(txn)
             })
-            .collect::<Result<_>>()?;
-        Response::new(self.ledger_info, &txns)
+            .collect()
+    }
+
+    fn page_response(
+        self,
+        data: Vec<TransactionOnChainData>,
+        summary: bool,
+    ) -> Result<Response, Error> {
+        if summary {
+            let summaries = self.render_summaries(data)?;
+            Response::new(self.ledger_info, &summaries)
+        } else {
+            let txns = self.render_transactions(data)?;
+            Response::new(self.ledger_info, &txns)
+        }
     }
 
-    pub async fn get_transaction(self, id: TransactionId) -> Result<impl Reply, Error> {
+    /// Summarizes the committed transactions, without rendering their payloads, events and
+    /// changes.
+    fn render_summaries(
+        &self,
+        data: Vec<TransactionOnChainData>,
+    ) -> Result<Vec<TransactionSummary>> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+        let mut timestamp = self.context.get_block_timestamp(data[0].version)?;
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+        Ok(data
+            .iter()
+            .map(|t| {
+                let summary = converter.into_transaction_summary(timestamp, t);
+                // the timestamp of a metadata block transaction is the one of the following ones
+                timestamp = summary.timestamp.0;
+                summary
+            })
+            .collect())
+    }
+
+    /// Renders the transaction; with `include_writeset`, a committed transaction is rendered
+    /// with the diffs of the resources it changed in JSON. The diffs are not rendered in BCS,
+    /// and a pending transaction has none, so these requests are rejected.
+    pub async fn get_transaction(
+        self,
+        id: TransactionId,
+        include_writeset: bool,
+        accept_type: AcceptType,
+    ) -> Result<impl Reply, Error> {
+        if include_writeset && accept_type == AcceptType::Bcs {
+            return Err(Error::invalid_param(
+                "include_writeset",
+                "the write set diff is only rendered in JSON",
+            ));
+        }
+        // a committed transaction never changes, unlike a pending transaction
+        let cache_key = ResponseCacheKey::new(
+            "get_transaction",
+            format!("{}:{}:{:?}", id, include_writeset, accept_type),
+        );
+        if let Some(cached) = self.context.response_cache().get(&cache_key) {
+            self.context
+                .ensure_transaction_not_pruned(cached.version, &self.ledger_info)?;
+            if include_writeset && cached.version > 0 {
+                self.context
+                    .ensure_state_not_pruned(cached.version - 1, &self.ledger_info)?;
+            }
+            return Ok(cached.into_response(self.ledger_info));
+        }
+
         let txn_data = match id.clone() {
             TransactionId::Hash(hash) => self.get_by_hash(hash.into()).await?,
-            TransactionId::Version(version) => self.get_by_version(version)?,
+            TransactionId::Version(version) => {
+                self.context
+                    .ensure_transaction_not_pruned(version, &self.ledger_info)?;
+                self.get_by_version(version)?
+            }
         }
         .ok_or_else(|| self.transaction_not_found(id))?;
 
+        let context = self.context.clone();
+        let committed_version = match &txn_data {
+            TransactionData::OnChain(txn) => Some(txn.version),
+            TransactionData::Pending(_) => None,
+        };
+        let response = match txn_data {
+            TransactionData::OnChain(txn) if include_writeset => {
+                self.render_transaction_with_write_set_diff(txn)
+            }
+            TransactionData::Pending(_) if include_writeset => Err(Error::invalid_param(
+                "include_writeset",
+                "the transaction is pending, it has no write set yet",
+            )),
+            TransactionData::Pending(txn) if accept_type == AcceptType::Json => {
+                self.render_pending_transaction(*txn).await
+            }
+            _ => self.render_transaction_data(txn_data, accept_type),
+        }?;
+        if let Some(version) = committed_version {
+            context
+                .response_cache()
+                .insert(cache_key, version, &response);
+        }
+        Ok(response)
+    }
+
+    /// Returns the committed transaction of the version.
+    pub fn get_committed(&self, version: u64) -> Result<Transaction, Error> {
+        let id = TransactionId::Version(version);
+        self.context
+            .ensure_transaction_not_pruned(version, &self.ledger_info)?;
+        match self.get_by_version(version)? {
+            Some(TransactionData::OnChain(txn)) => self
+                .render_transactions(vec![txn])?
+                .pop()
+                .ok_or_else(|| self.transaction_not_found(id)),
+            _ => Err(self.transaction_not_found(id)),
+        }
+    }
+
+    fn render_transaction_with_write_set_diff(
+        self,
+        txn: TransactionOnChainData,
+    ) -> Result<Response, Error> {
+        // the genesis transaction has no state before it
+        let previous_state = match txn.version {
+            0 => None,
+            version => {
+                self.context
+                    .ensure_state_not_pruned(version - 1, &self.ledger_info)?;
+                Some(self.context.state_view_at_version(version - 1)?)
+            }
+        };
+        let timestamp = self.context.get_block_timestamp(txn.version)?;
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+        let write_set_diff =
+            converter.try_into_resource_diffs(&txn.changes, previous_state.as_ref())?;
+        let transaction = converter.try_into_onchain_transaction(timestamp, txn)?;
+        Response::new(
+            self.ledger_info,
+            &TransactionWithWriteSetDiff {
+                transaction,
+                write_set_diff,
+            },
+        )
+    }
+
+    /// Waits until the transaction is committed, then renders the on-chain transaction.
+    ///
+    /// The pending transaction is rendered when it is still in mempool after the timeout
+    /// elapsed; not found error is returned when it is neither committed nor in mempool,
+    /// or it is expired and can't be committed anymore.
+    pub async fn wait_by_hash(
+        context: Context,
+        hash: HashValue,
+        timeout: Duration,
+        accept_type: AcceptType,
+    ) -> Result<Response, Error> {
+        let mut commits = context.subscribe_commits().map_err(Error::internal)?;
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let txns = Self::new(context.clone())?;
+            match txns.get_by_hash(hash).await? {
+                Some(TransactionData::Pending(txn)) if !txns.is_expired(&txn) => {
+                    match tokio::time::timeout_at(deadline, commits.changed()).await {
+                        Ok(Ok(())) => continue,
+                        // timed out, or the commit watcher is gone
+                        _ => {
+                            return txns.render_transaction_data(
+                                TransactionData::Pending(txn),
+                                accept_type,
+                            )
+                        }
+                    }
+                }
+                Some(TransactionData::OnChain(txn)) => {
+                    return txns.render_transaction_data(txn.into(), accept_type)
+                }
+                Some(TransactionData::Pending(_)) => {
+                    return Err(txns.transaction_not_found(TransactionId::Hash(hash.into())))
+                }
+                None => {
+                    // the transaction may be committed and removed from mempool in between
+                    // looking it up in the database and mempool, hence check database again.
+                    let txns = Self::new(context)?;
+                    return match txns
+                        .context
+                        .get_transaction_by_hash(hash, txns.ledger_info.version())?
+                    {
+                        Some(txn) => txns.render_transaction_data(txn.into(), accept_type),
+                        None => Err(txns.transaction_not_found(TransactionId::Hash(hash.into()))),
+                    };
+                }
+            }
+        }
+    }
+
+    fn render_transaction_data(
+        self,
+        txn_data: TransactionData,
+        accept_type: AcceptType,
+    ) -> Result<Response, Error> {
+        if accept_type == AcceptType::Bcs {
+            return Response::new_bcs(self.ledger_info, &txn_data);
+        }
+
         let resolver = self.context.move_resolver()?;
         let txn = match txn_data {
             TransactionData::OnChain(txn) => {
@@ -287,23 +1063,60 @@ impl Transactions {
         Response::new(self.ledger_info, &txn)
     }
 
-    pub fn signing_message(self, txn: UserTransactionRequest) -> Result<impl Reply, Error> {
+    // Renders the pending transaction with the sequence number it waits for, if any.
+    async fn render_pending_transaction(self, txn: SignedTransaction) -> Result<Response, Error> {
+        let waiting_for = self
+            .context
+            .get_waiting_for_sequence_number(txn.committed_hash())
+            .await?;
+        let resolver = self.context.move_resolver()?;
+        let mut txn = resolver.as_converter().try_into_pending_transaction(txn)?;
+        if let Transaction::PendingTransaction(pending) = &mut txn {
+            pending.waiting_for_sequence_number = waiting_for.map(U64::from);
+        }
+        Response::new(self.ledger_info, &txn)
+    }
+
+    pub fn signing_message(
+        self,
+        request: TransactionSigningMessageRequest,
+    ) -> Result<impl Reply, Error> {
         let resolver = self.context.move_resolver()?;
         let raw_txn: RawTransaction = resolver
             .as_converter()
-            .try_into_raw_transaction(txn, self.context.chain_id())
+            .try_into_raw_transaction(request.transaction, self.context.chain_id())
             .map_err(|e| {
                 Error::invalid_request_body(format!("invalid UserTransactionRequest: {:?}", e))
             })?;
 
-        Response::new(
-            self.ledger_info,
-            &TransactionSigningMessage::new(raw_txn.signing_message()),
-        )
+        let message = if request.secondary_signers.is_empty() {
+            raw_txn.signing_message()
+        } else {
+            let secondary_signers = request
+                .secondary_signers
+                .into_iter()
+                .map(|address| address.into())
+                .collect();
+            signing_message(&RawTransactionWithData::new_multi_agent(
+                raw_txn,
+                secondary_signers,
+            ))
+        };
+        Response::new(self.ledger_info, &TransactionSigningMessage::new(message))
+    }
+
+    // A transaction can't be committed once the ledger timestamp reaches its expiration time.
+    fn is_expired(&self, txn: &SignedTransaction) -> bool {
+        self.ledger_info.timestamp() / 1_000_000 >= txn.expiration_timestamp_secs()
     }
 
     fn transaction_not_found(&self, id: TransactionId) -> Error {
-        Error::not_found("transaction", id, self.ledger_info.version())
+        Error::not_found(
+            AptosErrorCode::TransactionNotFound,
+            "transaction",
+            id,
+            self.ledger_info.version(),
+        )
     }
 
     fn get_by_version(&self, version: u64) -> Result<Option<TransactionData>> {
@@ -335,3 +1148,42 @@ impl Transactions {
         })
     }
 }
+
+// Converts the mempool status of a rejected transaction into an error, returns None when
+// the transaction is accepted.
+fn submission_error(
+    mempool_status: MempoolStatus,
+    vm_status_opt: Option<DiscardedVMStatus>,
+) -> Option<Error> {
+    match mempool_status.code {
+        MempoolStatusCode::Accepted => None,
+        MempoolStatusCode::VmError => Some(Error {
+            vm_error_code: vm_status_opt.map(|s| s as u64),
+            ..Error::bad_request(
+                AptosErrorCode::InvalidTransaction,
+                format!(
+                    "invalid transaction: {}",
+                    vm_status_opt
+                        .map(|s| format!("{:?}", s))
+                        .unwrap_or_else(|| "UNKNOWN".to_owned())
+                ),
+            )
+        }),
+        MempoolStatusCode::MempoolIsFull => Some(Error::bad_request(
+            AptosErrorCode::MempoolFull,
+            format!("transaction is rejected: {}", mempool_status),
+        )),
+        MempoolStatusCode::TooManyTransactions => Some(Error::bad_request(
+            AptosErrorCode::SenderLimitExceeded,
+            format!("transaction is rejected: {}", mempool_status),
+        )),
+        MempoolStatusCode::TooManyBytes => Some(Error::bad_request(
+            AptosErrorCode::SenderBytesLimitExceeded,
+            format!("transaction is rejected: {}", mempool_status),
+        )),
+        _ => Some(Error::bad_request(
+            AptosErrorCode::TransactionRejected,
+            format!("transaction is rejected: {}", mempool_status),
+        )),
+    }
+}
@@ -7,5 +7,6 @@ use serde::Deserialize;
 
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) struct Version {
+    #[serde(alias = "ledger_version")]
     pub(crate) version: Option<LedgerVersionParam>,
 }
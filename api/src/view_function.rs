@@ -0,0 +1,61 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    api_key::api_key, bytes_encoding::BytesEncodingQuery, context::Context, failpoint::fail_point,
+    metrics::metrics,
+};
+
+use aptos_api_types::{AptosErrorCode, AsConverter, BytesEncoding, Error, Response, ViewRequest};
+
+use anyhow::Result;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+// POST /view?bytes_encoding={encoding}
+pub fn view_function(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("view")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<ViewRequest>())
+        .and(warp::query::<BytesEncodingQuery>())
+        .and(api_key(context.clone()))
+        .and(context.filter())
+        .and_then(handle_view_function)
+        .with(metrics("view_function"))
+        .boxed()
+}
+
+async fn handle_view_function(
+    request: ViewRequest,
+    bytes_encoding: BytesEncodingQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_view_function")?;
+    Ok(view(request, bytes_encoding.parse()?, context)?)
+}
+
+/// Executes the public function against the latest ledger state without committing any
+/// changes, and renders its return values.
+fn view(
+    request: ViewRequest,
+    bytes_encoding: BytesEncoding,
+    context: Context,
+) -> Result<Response, Error> {
+    let ledger_info = context.get_latest_ledger_info()?;
+    let resolver = context.move_resolver()?;
+    let converter = resolver.as_converter().with_bytes_encoding(bytes_encoding);
+    let (function, return_types) = converter
+        .try_into_view_function(request)
+        .map_err(|e| Error::invalid_request_body(format!("invalid ViewRequest: {:?}", e)))?;
+
+    let values = context.execute_view_function(function)?.map_err(|status| {
+        Error::bad_request(
+            AptosErrorCode::VmError,
+            format!("view function execution failed: {:?}", status),
+        )
+    })?;
+    let values = converter.try_into_move_return_values(&return_types, values)?;
+    Response::new(ledger_info, &values)
+}
@@ -0,0 +1,9 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The gRPC protocol of the transaction stream served by the node alongside the REST API,
+//! see `proto/transaction_stream.proto`. Both the server and the client are generated.
+
+#![allow(clippy::all)]
+
+tonic::include_proto!("aptos.transaction_stream.v1");
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{HexEncodedBytes, U64};
+use crate::{HexEncodedBytes, MoveStructTag, U64};
 
 use aptos_types::account_config::AccountResource;
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,22 @@ use serde::{Deserialize, Serialize};
 pub struct AccountData {
     pub sequence_number: U64,
     pub authentication_key: HexEncodedBytes,
+    /// The version of the transaction creating the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creation_version: Option<U64>,
+    /// The number of times the authentication key was rotated since the account creation.
+    #[serde(default)]
+    pub authentication_key_rotations: U64,
+    /// The version of the latest transaction sent by the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_transaction_version: Option<U64>,
+}
+
+/// The balance of a coin held by an account.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountBalance {
+    pub coin_type: MoveStructTag,
+    pub value: U64,
 }
 
 impl From<AccountResource> for AccountData {
@@ -17,6 +33,9 @@ impl From<AccountResource> for AccountData {
         Self {
             sequence_number: ar.sequence_number().into(),
             authentication_key: ar.authentication_key().to_vec().into(),
+            creation_version: None,
+            authentication_key_rotations: 0.into(),
+            last_transaction_version: None,
         }
     }
 }
@@ -0,0 +1,17 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Transaction, U64};
+
+use serde::{Deserialize, Serialize};
+
+/// A committed block, its first transaction is the `BlockMetadataTransaction`
+/// of the block, except for the genesis block (height 0).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Block {
+    pub block_height: U64,
+    pub block_timestamp: U64,
+    pub first_version: U64,
+    pub last_version: U64,
+    pub transactions: Vec<Transaction>,
+}
@@ -2,25 +2,31 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    Bytecode, DirectWriteSet, Event, HexEncodedBytes, MoveFunction, MoveModuleBytecode,
-    MoveResource, MoveScriptBytecode, MoveType, MoveValue, ScriptFunctionId, ScriptFunctionPayload,
+    Bytecode, BytesEncoding, DirectWriteSet, Event, HexEncodedBytes, MoveAbort, MoveFunction,
+    MoveModuleBytecode, MoveModuleId, MoveResource, MoveScriptBytecode, MoveType, MoveValue,
+    ResourceDiff, ScriptFunctionBatchPayload, ScriptFunctionId, ScriptFunctionPayload,
     ScriptPayload, ScriptWriteSet, Transaction, TransactionInfo, TransactionOnChainData,
-    TransactionPayload, UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
+    TransactionPayload, TransactionSummary, UserTransactionRequest, ViewRequest, WriteSet,
+    WriteSetChange, WriteSetPayload,
 };
 use aptos_crypto::HashValue;
+use aptos_state_view::StateView;
 use aptos_transaction_builder::error_explain;
 use aptos_types::{
     access_path::{AccessPath, Path},
     chain_id::ChainId,
     contract_event::ContractEvent,
-    transaction::{ModuleBundle, RawTransaction, Script, ScriptFunction, SignedTransaction},
+    transaction::{
+        BatchedCall, BatchedCallArgument, ModuleBundle, RawTransaction, Script, ScriptFunction,
+        ScriptFunctionBatch, SignedTransaction,
+    },
     vm_status::{AbortLocation, KeptVMStatus},
     write_set::WriteOp,
 };
 use move_binary_format::file_format::FunctionHandleIndex;
 use move_core_types::{
-    identifier::Identifier,
-    language_storage::{ModuleId, StructTag},
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, StructTag, TypeTag},
 };
 use move_resource_viewer::MoveValueAnnotator;
 
@@ -29,24 +35,28 @@ use anyhow::{ensure, format_err, Result};
 use aptos_crypto::hash::CryptoHash;
 use aptos_types::state_store::state_key::StateKey;
 use move_core_types::resolver::MoveResolver;
-use serde_json::Value;
-use std::{
-    convert::{TryFrom, TryInto},
-    iter::IntoIterator,
-    rc::Rc,
-};
+use serde_json::{json, Value};
+use std::{convert::TryInto, iter::IntoIterator, rc::Rc};
 
 pub struct MoveConverter<'a, R: ?Sized> {
     inner: MoveValueAnnotator<'a, R>,
+    bytes_encoding: BytesEncoding,
 }
 
 impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
     pub fn new(inner: &'a R) -> Self {
         Self {
             inner: MoveValueAnnotator::new(inner),
+            bytes_encoding: BytesEncoding::default(),
         }
     }
 
+    /// Sets the encoding of the `vector<u8>` values in the converted Move values.
+    pub fn with_bytes_encoding(mut self, bytes_encoding: BytesEncoding) -> Self {
+        self.bytes_encoding = bytes_encoding;
+        self
+    }
+
     pub fn try_into_resources<'b>(
         &self,
         data: impl Iterator<Item = (StructTag, &'b [u8])>,
@@ -56,7 +66,7 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
     }
 
     pub fn try_into_resource<'b>(&self, typ: &StructTag, bytes: &'b [u8]) -> Result<MoveResource> {
-        self.inner.view_resource(typ, bytes)?.try_into()
+        MoveResource::try_from_annotated(self.inner.view_resource(typ, bytes)?, self.bytes_encoding)
     }
 
     pub fn move_struct_fields<'b>(
@@ -95,6 +105,7 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
                 (info, payload, events).into()
             }
             BlockMetadata(txn) => (&txn, info).into(),
+            BlockMetadataExt(txn) => (txn.block_metadata(), info).into(),
             StateCheckpoint => {
                 Transaction::StateCheckpointTransaction(StateCheckpointTransaction {
                     info,
@@ -104,6 +115,36 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
         })
     }
 
+    /// Summarizes the on-chain transaction, its payload, events and changes aren't converted.
+    pub fn into_transaction_summary(
+        &self,
+        timestamp: u64,
+        data: &TransactionOnChainData,
+    ) -> TransactionSummary {
+        use aptos_types::transaction::Transaction::*;
+        let (typ, sender, timestamp) = match &data.transaction {
+            UserTransaction(txn) => ("user_transaction", Some(txn.sender().into()), timestamp),
+            GenesisTransaction(_) => ("genesis_transaction", None, 0),
+            BlockMetadata(txn) => ("block_metadata_transaction", None, txn.timestamp_usec()),
+            BlockMetadataExt(txn) => (
+                "block_metadata_transaction",
+                None,
+                txn.block_metadata().timestamp_usec(),
+            ),
+            StateCheckpoint => ("state_checkpoint_transaction", None, timestamp),
+        };
+        TransactionSummary {
+            typ: typ.to_owned(),
+            hash: data.info.transaction_hash().into(),
+            version: Some(data.version.into()),
+            sender,
+            success: data.info.status().is_success(),
+            vm_status: self.explain_vm_status(data.info.status()),
+            gas_used: Some(data.info.gas_used().into()),
+            timestamp: timestamp.into(),
+        }
+    }
+
     pub fn into_transaction_info(
         &self,
         version: u64,
@@ -119,6 +160,7 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
             gas_used: info.gas_used().into(),
             success: info.status().is_success(),
             vm_status: self.explain_vm_status(info.status()),
+            move_abort: self.try_into_move_abort(info.status()),
             accumulator_root_hash: accumulator_root_hash.into(),
             // TODO: the resource value is interpreted by the type definition at the version of the converter, not the version of the tx: must be fixed before we allow module updates
             changes: write_set
@@ -150,7 +192,7 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
                 let json_args = match func_args {
                     Ok(values) => values
                         .into_iter()
-                        .map(|v| MoveValue::try_from(v)?.json())
+                        .map(|v| MoveValue::try_from_annotated(v, self.bytes_encoding)?.json())
                         .collect::<Result<_>>()?,
                     Err(_e) => args
                         .into_iter()
@@ -167,10 +209,87 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
                     type_arguments: ty_args.into_iter().map(|arg| arg.into()).collect(),
                 })
             }
+            ScriptFunctionBatch(batch) => {
+                TransactionPayload::ScriptFunctionBatchPayload(ScriptFunctionBatchPayload {
+                    calls: batch
+                        .into_inner()
+                        .into_iter()
+                        .map(|call| self.try_into_batched_call_payload(call))
+                        .collect::<Result<_>>()?,
+                })
+            }
         };
         Ok(ret)
     }
 
+    fn try_into_batched_call_payload(&self, call: BatchedCall) -> Result<ScriptFunctionPayload> {
+        let (module, function, ty_args, args) = call.into_inner();
+        let type_arguments: Vec<MoveType> = ty_args.into_iter().map(|arg| arg.into()).collect();
+        let arg_types = self
+            .find_batched_call_function(&module, &function)
+            .and_then(|func| self.batched_call_arg_types(func, &type_arguments))
+            .ok();
+        let arguments = args
+            .into_iter()
+            .enumerate()
+            .map(|(i, arg)| match arg {
+                BatchedCallArgument::Value(bytes) => {
+                    let typ = arg_types.as_ref().and_then(|types| types.get(i));
+                    match typ.map(|typ| self.inner.view_value(typ, &bytes)) {
+                        Some(Ok(value)) => {
+                            MoveValue::try_from_annotated(value, self.bytes_encoding)?.json()
+                        }
+                        _ => HexEncodedBytes::from(bytes).json(),
+                    }
+                }
+                BatchedCallArgument::Output { call, output } => {
+                    Ok(json!({ "call": call, "output": output }))
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok(ScriptFunctionPayload {
+            function: ScriptFunctionId {
+                module: module.into(),
+                name: function,
+            },
+            type_arguments,
+            arguments,
+        })
+    }
+
+    /// Finds the function called by a call of a script function batch, which is either a script
+    /// or a public function.
+    fn find_batched_call_function(
+        &self,
+        module: &ModuleId,
+        function: &IdentStr,
+    ) -> Result<MoveFunction> {
+        let code = self.inner.get_module(module)? as Rc<dyn Bytecode>;
+        code.find_script_function(function)
+            .or_else(|| code.find_public_function(function))
+            .ok_or_else(|| {
+                format_err!(
+                    "could not find script or public function by {}::{}",
+                    MoveModuleId::from(module.clone()),
+                    function
+                )
+            })
+    }
+
+    /// Returns the types of the parameters of the function after its leading signers, which are
+    /// the ones the VM passes the signers of the transaction to.
+    fn batched_call_arg_types(
+        &self,
+        func: MoveFunction,
+        type_arguments: &[MoveType],
+    ) -> Result<Vec<TypeTag>> {
+        func.params
+            .into_iter()
+            .skip_while(|p| matches!(p, MoveType::Signer))
+            .map(|p| p.instantiate(type_arguments)?.try_into())
+            .collect()
+    }
+
     pub fn try_into_write_set_payload(
         &self,
         payload: aptos_types::transaction::WriteSetPayload,
@@ -190,7 +309,9 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
                         // TODO: the resource value is interpreted by the type definition at the version of the converter, not the version of the tx: must be fixed before we allow module updates
                         changes: write_set
                             .into_iter()
-                            .map(|(state_key, op)| self.try_into_write_set_change(state_key, op))
+                            .map(|(state_key, op)| {
+                                self.try_into_direct_write_set_change(state_key, op)
+                            })
                             .collect::<Result<_>>()?,
                         events: self.try_into_events(&events)?,
                     }),
@@ -221,6 +342,34 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
         }
     }
 
+    /// Converts the change of a direct write set, which may write any state key: the changes
+    /// of the keys that aren't access paths are rendered as raw changes, whose key is the raw
+    /// key or the account address.
+    pub fn try_into_direct_write_set_change(
+        &self,
+        state_key: StateKey,
+        op: WriteOp,
+    ) -> Result<WriteSetChange> {
+        let key = match &state_key {
+            StateKey::AccessPath(_) => return self.try_into_write_set_change(state_key, op),
+            StateKey::AccountAddressKey(address) => address.to_vec(),
+            StateKey::Raw(key) => key.clone(),
+        };
+        let state_key_hash = state_key.hash().to_hex_literal();
+        let key = self.bytes_encoding.encode(key)?.json()?;
+        Ok(match op {
+            WriteOp::Deletion => WriteSetChange::DeleteRaw {
+                state_key_hash,
+                key,
+            },
+            WriteOp::Value(value) => WriteSetChange::WriteRaw {
+                state_key_hash,
+                key,
+                value: self.bytes_encoding.encode(value)?.json()?,
+            },
+        })
+    }
+
     pub fn try_access_path_into_write_set_change(
         &self,
         access_path: AccessPath,
@@ -258,13 +407,53 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
         Ok(ret)
     }
 
+    /// Converts the resource changes of the write set into the diffs of the resource values,
+    /// the values before the changes are read from `previous_state`, which is the state right
+    /// before the write set is applied, or `None` when there is no state before, i.e. genesis.
+    pub fn try_into_resource_diffs(
+        &self,
+        write_set: &aptos_types::write_set::WriteSet,
+        previous_state: Option<&impl StateView>,
+    ) -> Result<Vec<ResourceDiff>> {
+        let mut diffs = vec![];
+        for (state_key, op) in write_set {
+            let access_path = match state_key {
+                StateKey::AccessPath(access_path) => access_path,
+                _ => continue,
+            };
+            let typ = match access_path.get_path() {
+                Path::Resource(typ) => typ,
+                Path::Code(_) => continue,
+            };
+            let before = match previous_state {
+                Some(state) => state.get_state_value(state_key)?,
+                None => None,
+            };
+            let after = match op {
+                WriteOp::Value(val) => Some(self.try_into_resource(&typ, val)?),
+                WriteOp::Deletion => None,
+            };
+            diffs.push(ResourceDiff {
+                address: access_path.address.into(),
+                state_key_hash: state_key.hash().to_hex_literal(),
+                before: before
+                    .map(|bytes| self.try_into_resource(&typ, &bytes))
+                    .transpose()?,
+                after,
+                resource: typ.into(),
+            });
+        }
+        Ok(diffs)
+    }
+
     pub fn try_into_events(&self, events: &[ContractEvent]) -> Result<Vec<Event>> {
         let mut ret = vec![];
         for event in events {
             let data = self
                 .inner
                 .view_value(event.type_tag(), event.event_data())?;
-            ret.push((event, MoveValue::try_from(data)?.json()?).into());
+            let data = MoveValue::try_from_annotated(data, self.bytes_encoding)?;
+            ret.push((event, data.json()?).into());
         }
         Ok(ret)
     }
@@ -338,7 +527,7 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
                     type_arguments.len()
                 );
                 let args = self
-                    .try_into_move_values(func, arguments)?
+                    .try_into_move_values(func, &type_arguments, arguments)?
                     .iter()
                     .map(bcs::to_bytes)
                     .collect::<Result<_, bcs::Error>>()?;
@@ -353,6 +542,15 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
                     args,
                 ))
             }
+            TransactionPayload::ScriptFunctionBatchPayload(batch) => {
+                Target::ScriptFunctionBatch(ScriptFunctionBatch::new(
+                    batch
+                        .calls
+                        .into_iter()
+                        .map(|call| self.try_into_batched_call(call))
+                        .collect::<Result<_>>()?,
+                ))
+            }
             TransactionPayload::ModuleBundlePayload(payload) => {
                 Target::ModuleBundle(ModuleBundle::new(
                     payload
@@ -372,16 +570,35 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
                 let MoveScriptBytecode { bytecode, abi } = code.try_parse_abi();
                 match abi {
                     Some(func) => {
-                        let args = self.try_into_move_values(func, arguments)?;
+                        ensure!(
+                            func.generic_type_params.len() == type_arguments.len(),
+                            "expect {} type arguments for script, but got {}",
+                            func.generic_type_params.len(),
+                            type_arguments.len()
+                        );
+                        let args = self
+                            .try_into_move_values(func, &type_arguments, arguments)?
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, arg)| {
+                                // the script arguments are limited to the transaction
+                                // argument types, e.g. `vector<u64>` is not one of them.
+                                arg.try_into().map_err(|e| {
+                                    format_err!(
+                                        "arguments[{}] is not a valid script argument: {}",
+                                        i,
+                                        e
+                                    )
+                                })
+                            })
+                            .collect::<Result<_>>()?;
                         Target::Script(Script::new(
                             bytecode.into(),
                             type_arguments
                                 .into_iter()
                                 .map(|v| v.try_into())
                                 .collect::<Result<_>>()?,
-                            args.into_iter()
-                                .map(|arg| arg.try_into())
-                                .collect::<Result<_>>()?,
+                            args,
                         ))
                     }
                     None => return Err(anyhow::anyhow!("invalid transaction script bytecode")),
@@ -396,16 +613,154 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
         Ok(ret)
     }
 
+    fn try_into_batched_call(&self, payload: ScriptFunctionPayload) -> Result<BatchedCall> {
+        let ScriptFunctionPayload {
+            function,
+            type_arguments,
+            arguments,
+        } = payload;
+
+        let module: ModuleId = function.module.clone().into();
+        let func = self.find_batched_call_function(&module, function.name.as_ident_str())?;
+        ensure!(
+            func.generic_type_params.len() == type_arguments.len(),
+            "expect {} type arguments for function {}, but got {}",
+            func.generic_type_params.len(),
+            function,
+            type_arguments.len()
+        );
+        // skipped like by the VM
+        let arg_types = func
+            .params
+            .into_iter()
+            .skip_while(|p| matches!(p, MoveType::Signer))
+            .map(|p| p.instantiate(&type_arguments))
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(
+            arg_types.len() == arguments.len(),
+            "expected {} arguments for function {}, but got {}",
+            arg_types.len(),
+            function,
+            arguments.len(),
+        );
+        let args = arg_types
+            .iter()
+            .zip(arguments)
+            .enumerate()
+            .map(|(i, (arg_type, arg))| {
+                if let Some(output) = try_into_batched_call_output(&arg) {
+                    return Ok(output);
+                }
+                let value = self.try_into_move_value(arg_type, arg).map_err(|e| {
+                    format_err!(
+                        "parse arguments[{}] failed, expect {}, caused by error: {}",
+                        i,
+                        arg_type.json_type_name(),
+                        e,
+                    )
+                })?;
+                Ok(BatchedCallArgument::Value(bcs::to_bytes(&value)?))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(BatchedCall::new(
+            module,
+            function.name,
+            type_arguments
+                .into_iter()
+                .map(|v| v.try_into())
+                .collect::<Result<_>>()?,
+            args,
+        ))
+    }
+
+    /// Converts the view request into the public function to execute, and returns the
+    /// types of the function return values along with it.
+    pub fn try_into_view_function(
+        &self,
+        request: ViewRequest,
+    ) -> Result<(ScriptFunction, Vec<TypeTag>)> {
+        let ViewRequest {
+            function,
+            type_arguments,
+            arguments,
+        } = request;
+
+        let module = function.module.clone();
+        let code = self.inner.get_module(&module.clone().into())? as Rc<dyn Bytecode>;
+        let func = code
+            .find_public_function(function.name.as_ident_str())
+            .ok_or_else(|| format_err!("could not find public function by {}", function))?;
+        ensure!(
+            func.generic_type_params.len() == type_arguments.len(),
+            "expect {} type arguments for function {}, but got {}",
+            func.generic_type_params.len(),
+            function,
+            type_arguments.len()
+        );
+        let return_types = func
+            .return_
+            .iter()
+            .map(|typ| typ.instantiate(&type_arguments)?.try_into())
+            .collect::<Result<_>>()?;
+        let args = self
+            .try_into_move_values(func, &type_arguments, arguments)?
+            .iter()
+            .map(bcs::to_bytes)
+            .collect::<Result<_, bcs::Error>>()?;
+
+        let function = ScriptFunction::new(
+            module.into(),
+            function.name,
+            type_arguments
+                .into_iter()
+                .map(|v| v.try_into())
+                .collect::<Result<_>>()?,
+            args,
+        );
+        Ok((function, return_types))
+    }
+
+    pub fn try_into_move_return_values(
+        &self,
+        return_types: &[TypeTag],
+        values: Vec<move_core_types::value::MoveValue>,
+    ) -> Result<Vec<MoveValue>> {
+        ensure!(
+            return_types.len() == values.len(),
+            "expect {} return values, but got {}",
+            return_types.len(),
+            values.len()
+        );
+        return_types
+            .iter()
+            .zip(values)
+            .map(|(typ, value)| {
+                let bytes = value
+                    .simple_serialize()
+                    .ok_or_else(|| format_err!("could not serialize return value {:?}", value))?;
+                MoveValue::try_from_annotated(
+                    self.inner.view_value(typ, &bytes)?,
+                    self.bytes_encoding,
+                )
+            })
+            .collect()
+    }
+
+    /// Converts the JSON arguments into the values of the function parameters, the generic
+    /// type params in the parameter types are substituted with the type arguments.
     pub fn try_into_move_values(
         &self,
         func: MoveFunction,
+        type_arguments: &[MoveType],
         args: Vec<serde_json::Value>,
     ) -> Result<Vec<move_core_types::value::MoveValue>> {
         let arg_types = func
             .params
             .into_iter()
             .filter(|p| !p.is_signer())
-            .collect::<Vec<_>>();
+            .map(|p| p.instantiate(type_arguments))
+            .collect::<Result<Vec<_>>>()?;
         ensure!(
             arg_types.len() == args.len(),
             "expected {} arguments [{}], but got {} ({:?})",
@@ -493,7 +848,8 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
                     explanation
                         .map(|ec| {
                             format!(
-                                "Move abort by {} - {}\n{}\n{}",
+                                "Move abort in {}: {} - {}\n{}\n{}",
+                                MoveModuleId::from(module_id.clone()),
                                 ec.category.code_name,
                                 ec.reason.code_name,
                                 ec.category.code_description,
@@ -532,6 +888,33 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
         }
     }
 
+    fn try_into_move_abort(&self, status: &KeptVMStatus) -> Option<MoveAbort> {
+        let (location, abort_code) = match status {
+            KeptVMStatus::MoveAbort(location, abort_code) => (location, *abort_code),
+            _ => return None,
+        };
+        let module = match location {
+            AbortLocation::Module(module_id) => module_id,
+            AbortLocation::Script => {
+                return Some(MoveAbort {
+                    module: None,
+                    abort_code: abort_code.into(),
+                    category: None,
+                    reason: None,
+                    reason_description: None,
+                })
+            }
+        };
+        let explanation = error_explain::get_explanation(module, abort_code);
+        Some(MoveAbort {
+            module: Some(module.clone().into()),
+            abort_code: abort_code.into(),
+            category: explanation.as_ref().map(|ec| ec.category.code_name.clone()),
+            reason: explanation.as_ref().map(|ec| ec.reason.code_name.clone()),
+            reason_description: explanation.map(|ec| ec.reason.code_description),
+        })
+    }
+
     fn explain_function_index(&self, module_id: &ModuleId, function: &u16) -> Result<String> {
         let code = self.inner.get_module(&module_id.clone())? as Rc<dyn Bytecode>;
         let func = code.function_handle_at(FunctionHandleIndex::new(*function));
@@ -540,6 +923,19 @@ impl<'a, R: MoveResolver + ?Sized> MoveConverter<'a, R> {
     }
 }
 
+/// Parses `{"call": <index of the call>, "output": <index of the return value>}`, no argument
+/// of a call is a JSON object otherwise.
+fn try_into_batched_call_output(arg: &Value) -> Option<BatchedCallArgument> {
+    let object = arg.as_object()?;
+    if object.len() != 2 {
+        return None;
+    }
+    Some(BatchedCallArgument::Output {
+        call: serde_json::from_value(object.get("call")?.clone()).ok()?,
+        output: serde_json::from_value(object.get("output")?.clone()).ok()?,
+    })
+}
+
 pub trait AsConverter<R> {
     fn as_converter(&self) -> MoveConverter<R>;
 }
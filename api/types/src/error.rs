@@ -8,52 +8,156 @@ use std::{
 };
 use warp::{http::StatusCode, reject::Reject};
 
-use crate::U64;
+use crate::{InvalidUtf8Bytes, U64};
+
+/// `AptosErrorCode` is a machine-readable error code included in every error response,
+/// clients should branch on it instead of parsing the error message.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AptosErrorCode {
+    /// A path or query parameter is invalid.
+    InvalidParameter,
+    /// The request body can't be deserialized or is invalid.
+    InvalidRequestBody,
+    /// The transaction failed the validation of the Move VM.
+    InvalidTransaction,
+    /// The transaction is rejected by mempool because it is full.
+    MempoolFull,
+    /// The transaction is rejected by mempool, e.g. it is already in mempool.
+    TransactionRejected,
+    /// The transaction is rejected by mempool because its sender reached the max number of
+    /// transactions per sender in mempool.
+    SenderLimitExceeded,
+    /// The transaction is rejected by mempool because its sender reached the max total size
+    /// of transactions per sender in mempool.
+    SenderBytesLimitExceeded,
+    /// The Move VM failed executing a view function, e.g. the function aborted.
+    VmError,
+    /// The requested API route doesn't exist.
+    NotFound,
+    /// The requested ledger version is newer than the latest ledger version.
+    VersionNotFound,
+    /// The requested version has been pruned by the node, the `oldest_ledger_version` of the
+    /// error tells the oldest version available.
+    VersionPruned,
+    AccountNotFound,
+    ResourceNotFound,
+    ModuleNotFound,
+    TransactionNotFound,
+    BlockNotFound,
+    /// The request is forbidden by the CORS policy.
+    CorsForbidden,
+    ContentLengthRequired,
+    PayloadTooLarge,
+    UnsupportedMediaType,
+    MethodNotAllowed,
+    /// The `Idempotency-Key` of the submission was used to submit another transaction.
+    IdempotencyKeyReused,
+    /// The client exceeds its rate limit, the `Retry-After` response header tells how
+    /// many seconds to wait before retrying.
+    RateLimited,
+    /// The latest ledger info is older than the `duration_secs` of the health check.
+    HealthCheckFailed,
+    /// The admin token or the API key of the request is missing or doesn't match a
+    /// configured one.
+    Unauthorized,
+    InternalError,
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct Error {
     pub code: u16,
     pub message: String,
+    pub error_code: AptosErrorCode,
     /// Diem blockchain latest onchain ledger version.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aptos_ledger_version: Option<U64>,
+    /// The oldest version not pruned, only given when the requested version is pruned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oldest_ledger_version: Option<U64>,
+    /// The status code of the Move VM, only given when the transaction failed its validation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vm_error_code: Option<u64>,
 }
 
 impl Error {
-    pub fn new(code: StatusCode, message: String) -> Self {
+    pub fn new(code: StatusCode, error_code: AptosErrorCode, message: String) -> Self {
         Self {
             code: code.as_u16(),
             message,
+            error_code,
             aptos_ledger_version: None,
+            oldest_ledger_version: None,
+            vm_error_code: None,
         }
     }
 
-    pub fn from_anyhow_error(code: StatusCode, err: anyhow::Error) -> Self {
-        Self::new(code, err.to_string())
+    pub fn from_anyhow_error(
+        code: StatusCode,
+        error_code: AptosErrorCode,
+        err: anyhow::Error,
+    ) -> Self {
+        Self::new(code, error_code, err.to_string())
     }
 
-    pub fn bad_request<S: Display>(msg: S) -> Self {
-        Self::new(StatusCode::BAD_REQUEST, msg.to_string())
+    pub fn bad_request<S: Display>(error_code: AptosErrorCode, msg: S) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, error_code, msg.to_string())
     }
 
-    pub fn not_found<S: Display>(resource: &str, identifier: S, ledger_version: u64) -> Self {
+    pub fn not_found<S: Display>(
+        error_code: AptosErrorCode,
+        resource: &str,
+        identifier: S,
+        ledger_version: u64,
+    ) -> Self {
         Self::new(
             StatusCode::NOT_FOUND,
+            error_code,
             format!("{} not found by {}", resource, identifier),
         )
         .aptos_ledger_version(ledger_version)
     }
 
+    pub fn version_pruned<S: Display>(
+        resource: &str,
+        identifier: S,
+        oldest_ledger_version: u64,
+        ledger_version: u64,
+    ) -> Self {
+        Self {
+            oldest_ledger_version: Some(oldest_ledger_version.into()),
+            ..Self::new(
+                StatusCode::GONE,
+                AptosErrorCode::VersionPruned,
+                format!(
+                    "{} by {} is pruned, the oldest available version is {}",
+                    resource, identifier, oldest_ledger_version
+                ),
+            )
+        }
+        .aptos_ledger_version(ledger_version)
+    }
+
     pub fn invalid_param<S: Display>(name: &str, value: S) -> Self {
-        Self::bad_request(format!("invalid parameter {}: {}", name, value))
+        Self::bad_request(
+            AptosErrorCode::InvalidParameter,
+            format!("invalid parameter {}: {}", name, value),
+        )
     }
 
     pub fn invalid_request_body<S: Display>(msg: S) -> Self {
-        Self::bad_request(format!("invalid request body: {}", msg))
+        Self::bad_request(
+            AptosErrorCode::InvalidRequestBody,
+            format!("invalid request body: {}", msg),
+        )
     }
 
     pub fn internal(err: anyhow::Error) -> Self {
-        Self::from_anyhow_error(StatusCode::INTERNAL_SERVER_ERROR, err)
+        Self::from_anyhow_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            AptosErrorCode::InternalError,
+            err,
+        )
     }
 
     pub fn status_code(&self) -> StatusCode {
@@ -79,7 +183,12 @@ impl fmt::Display for Error {
 impl Reject for Error {}
 
 impl From<anyhow::Error> for Error {
+    /// The `vector<u8>` values that can't be encoded as requested are the client's error, the
+    /// other errors are internal.
     fn from(e: anyhow::Error) -> Self {
+        if e.downcast_ref::<InvalidUtf8Bytes>().is_some() {
+            return Self::invalid_param("bytes_encoding", e);
+        }
         Self::internal(e)
     }
 }
@@ -92,12 +201,19 @@ impl From<serde_json::error::Error> for Error {
 
 #[cfg(test)]
 mod tests {
-    use crate::error::Error;
+    use crate::{
+        error::{AptosErrorCode, Error},
+        BytesEncoding,
+    };
     use warp::http::StatusCode;
 
     #[test]
     fn test_to_string() {
-        let err = Error::new(StatusCode::BAD_REQUEST, "invalid address".to_owned());
+        let err = Error::new(
+            StatusCode::BAD_REQUEST,
+            AptosErrorCode::InvalidParameter,
+            "invalid address".to_owned(),
+        );
         assert_eq!(err.to_string(), "400 Bad Request: invalid address")
     }
 
@@ -107,10 +223,22 @@ mod tests {
         assert_eq!(err.to_string(), "500 Internal Server Error: hello")
     }
 
+    #[test]
+    fn test_from_invalid_utf8_bytes_as_invalid_param() {
+        let err = BytesEncoding::Utf8.encode(vec![0xff]).unwrap_err();
+        let err = Error::from(anyhow::Error::new(err).context("resource"));
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.error_code, AptosErrorCode::InvalidParameter);
+    }
+
     #[test]
     fn test_to_string_with_aptos_ledger_version() {
-        let err = Error::new(StatusCode::BAD_REQUEST, "invalid address".to_owned())
-            .aptos_ledger_version(123);
+        let err = Error::new(
+            StatusCode::BAD_REQUEST,
+            AptosErrorCode::InvalidParameter,
+            "invalid address".to_owned(),
+        )
+        .aptos_ledger_version(123);
         assert_eq!(
             err.to_string(),
             "400 Bad Request: invalid address\ndiem ledger version: 123"
@@ -120,6 +248,36 @@ mod tests {
     #[test]
     fn test_internal_error() {
         let err = Error::internal(anyhow::format_err!("hello"));
-        assert_eq!(err.to_string(), "500 Internal Server Error: hello")
+        assert_eq!(err.to_string(), "500 Internal Server Error: hello");
+        assert_eq!(err.error_code, AptosErrorCode::InternalError);
+    }
+
+    #[test]
+    fn test_serialize_version_pruned() {
+        let err = Error::version_pruned("transaction", "version(1)", 10, 20);
+        assert_eq!(err.status_code(), StatusCode::GONE);
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({
+                "code": 410,
+                "message": "transaction by version(1) is pruned, the oldest available version is 10",
+                "error_code": "version_pruned",
+                "aptos_ledger_version": "20",
+                "oldest_ledger_version": "10",
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_error_code() {
+        let err = Error::invalid_param("limit", 0);
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({
+                "code": 400,
+                "message": "invalid parameter limit: 0",
+                "error_code": "invalid_parameter",
+            })
+        );
     }
 }
@@ -0,0 +1,15 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::U64;
+
+use serde::{Deserialize, Serialize};
+
+/// Gas unit price suggestions, computed from the gas unit prices of recently
+/// committed user transactions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GasEstimation {
+    pub low: U64,
+    pub median: U64,
+    pub high: U64,
+}
@@ -3,37 +3,48 @@
 
 mod account;
 mod address;
+mod block;
 mod bytecode;
 mod convert;
 mod error;
 mod event_key;
+mod gas_estimation;
 mod hash;
 mod ledger_info;
 pub mod mime_types;
 mod move_types;
+mod node_info;
 mod response;
 mod transaction;
+mod view_function;
 
-pub use account::AccountData;
+pub use account::{AccountBalance, AccountData};
 pub use address::Address;
+pub use block::Block;
 pub use bytecode::Bytecode;
 pub use convert::{AsConverter, MoveConverter};
-pub use error::Error;
+pub use error::{AptosErrorCode, Error};
 pub use event_key::EventKey;
+pub use gas_estimation::GasEstimation;
 pub use hash::HashValue;
 pub use ledger_info::LedgerInfo;
 pub use move_types::{
-    HexEncodedBytes, MoveFunction, MoveModule, MoveModuleBytecode, MoveModuleId, MoveResource,
-    MoveScriptBytecode, MoveStructTag, MoveStructValue, MoveType, MoveValue, ScriptFunctionId,
-    U128, U64,
+    BytesEncoding, HexEncodedBytes, InvalidUtf8Bytes, MoveFunction, MoveModule, MoveModuleBytecode,
+    MoveModuleId, MoveResource, MoveScriptBytecode, MoveStructTag, MoveStructValue, MoveType,
+    MoveValue, ScriptFunctionId, U128, U64,
 };
+pub use node_info::{ApiFeatures, NodeInfo};
 pub use response::{
-    Response, X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+    Response, X_APTOS_CHAIN_ID, X_APTOS_CURSOR, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP,
+    X_APTOS_LEDGER_VERSION,
 };
 pub use transaction::{
-    BlockMetadataTransaction, DirectWriteSet, Event, GenesisTransaction, PendingTransaction,
-    ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, Transaction, TransactionData,
-    TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
-    TransactionSigningMessage, UserTransaction, UserTransactionRequest, WriteSet, WriteSetChange,
+    BlockMetadataTransaction, DirectWriteSet, Event, GenesisTransaction, MoveAbort,
+    PendingTransaction, ResourceDiff, ScriptFunctionBatchPayload, ScriptFunctionPayload,
+    ScriptPayload, ScriptWriteSet, Transaction, TransactionData, TransactionId, TransactionInfo,
+    TransactionOnChainData, TransactionPayload, TransactionSigningMessage,
+    TransactionSigningMessageRequest, TransactionSubmissionResult, TransactionSummary,
+    TransactionWithWriteSetDiff, UserTransaction, UserTransactionRequest, WriteSet, WriteSetChange,
     WriteSetPayload,
 };
+pub use view_function::ViewRequest;
@@ -40,14 +40,23 @@ impl TryFrom<AnnotatedMoveStruct> for MoveResource {
     type Error = anyhow::Error;
 
     fn try_from(s: AnnotatedMoveStruct) -> anyhow::Result<Self> {
+        Self::try_from_annotated(s, BytesEncoding::default())
+    }
+}
+
+impl MoveResource {
+    pub fn try_from_annotated(
+        s: AnnotatedMoveStruct,
+        encoding: BytesEncoding,
+    ) -> anyhow::Result<Self> {
         Ok(Self {
             typ: s.type_.clone().into(),
-            data: s.try_into()?,
+            data: MoveStructValue::try_from_annotated(s, encoding)?,
         })
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Copy)]
+#[derive(Clone, Debug, Default, PartialEq, Copy)]
 pub struct U64(pub u64);
 
 impl U64 {
@@ -245,15 +254,84 @@ impl HexEncodedBytes {
     }
 }
 
+/// The encoding of Move `vector<u8>` values in JSON.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BytesEncoding {
+    /// `0x` prefixed hex string, the default encoding.
+    Hex,
+    /// Standard base64 string with padding.
+    Base64,
+    /// UTF-8 string, the values that are not valid UTF-8 can't be encoded.
+    Utf8,
+}
+
+impl BytesEncoding {
+    pub fn encode(&self, bytes: Vec<u8>) -> Result<MoveValue, InvalidUtf8Bytes> {
+        Ok(match self {
+            BytesEncoding::Hex => MoveValue::Bytes(HexEncodedBytes(bytes)),
+            BytesEncoding::Base64 => MoveValue::String(base64::encode(&bytes)),
+            BytesEncoding::Utf8 => MoveValue::String(
+                String::from_utf8(bytes)
+                    .map_err(|e| InvalidUtf8Bytes(HexEncodedBytes(e.into_bytes())))?,
+            ),
+        })
+    }
+}
+
+/// The `vector<u8>` value can't be encoded by `BytesEncoding::Utf8`, it isn't valid UTF-8.
+#[derive(Debug)]
+pub struct InvalidUtf8Bytes(pub HexEncodedBytes);
+
+impl fmt::Display for InvalidUtf8Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the vector<u8> value {} is not valid UTF-8, use the hex or base64 bytes encoding",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidUtf8Bytes {}
+
+impl Default for BytesEncoding {
+    fn default() -> Self {
+        BytesEncoding::Hex
+    }
+}
+
+impl FromStr for BytesEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, anyhow::Error> {
+        match s {
+            "hex" => Ok(BytesEncoding::Hex),
+            "base64" => Ok(BytesEncoding::Base64),
+            "utf8" => Ok(BytesEncoding::Utf8),
+            _ => bail!("unknown bytes encoding: {}", s),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct MoveStructValue(BTreeMap<Identifier, serde_json::Value>);
 
 impl TryFrom<AnnotatedMoveStruct> for MoveStructValue {
     type Error = anyhow::Error;
     fn try_from(s: AnnotatedMoveStruct) -> anyhow::Result<Self> {
+        Self::try_from_annotated(s, BytesEncoding::default())
+    }
+}
+
+impl MoveStructValue {
+    pub fn try_from_annotated(
+        s: AnnotatedMoveStruct,
+        encoding: BytesEncoding,
+    ) -> anyhow::Result<Self> {
         let mut map = BTreeMap::new();
         for (id, val) in s.value {
-            map.insert(id, MoveValue::try_from(val)?.json()?);
+            map.insert(id, MoveValue::try_from_annotated(val, encoding)?.json()?);
         }
         Ok(Self(map))
     }
@@ -296,6 +374,16 @@ impl TryFrom<AnnotatedMoveValue> for MoveValue {
     type Error = anyhow::Error;
 
     fn try_from(val: AnnotatedMoveValue) -> anyhow::Result<Self> {
+        Self::try_from_annotated(val, BytesEncoding::default())
+    }
+}
+
+impl MoveValue {
+    /// Converts the annotated value, `vector<u8>` values are encoded with the given encoding.
+    pub fn try_from_annotated(
+        val: AnnotatedMoveValue,
+        encoding: BytesEncoding,
+    ) -> anyhow::Result<Self> {
         Ok(match val {
             AnnotatedMoveValue::U8(v) => MoveValue::U8(v),
             AnnotatedMoveValue::U64(v) => MoveValue::U64(U64(v)),
@@ -304,15 +392,15 @@ impl TryFrom<AnnotatedMoveValue> for MoveValue {
             AnnotatedMoveValue::Address(v) => MoveValue::Address(v.into()),
             AnnotatedMoveValue::Vector(_, vals) => MoveValue::Vector(
                 vals.into_iter()
-                    .map(MoveValue::try_from)
+                    .map(|v| MoveValue::try_from_annotated(v, encoding))
                     .collect::<anyhow::Result<_>>()?,
             ),
-            AnnotatedMoveValue::Bytes(v) => MoveValue::Bytes(HexEncodedBytes(v)),
+            AnnotatedMoveValue::Bytes(v) => encoding.encode(v)?,
             AnnotatedMoveValue::Struct(v) => {
                 if MoveValue::is_ascii_string(&v.type_) {
                     MoveValue::convert_ascii_string(v)?
                 } else {
-                    MoveValue::Struct(v.try_into()?)
+                    MoveValue::Struct(MoveStructValue::try_from_annotated(v, encoding)?)
                 }
             }
         })
@@ -474,6 +562,32 @@ impl MoveType {
             MoveType::Reference { mutable: _, to } => to.json_type_name(),
         }
     }
+
+    /// Substitutes the generic type params in the type with the given type arguments.
+    pub fn instantiate(&self, type_args: &[MoveType]) -> anyhow::Result<MoveType> {
+        Ok(match self {
+            MoveType::GenericTypeParam { index } => type_args
+                .get(*index as usize)
+                .cloned()
+                .ok_or_else(|| format_err!("type argument T{} is not given", index))?,
+            MoveType::Vector { items } => MoveType::Vector {
+                items: Box::new(items.instantiate(type_args)?),
+            },
+            MoveType::Struct(s) => MoveType::Struct(MoveStructTag {
+                generic_type_params: s
+                    .generic_type_params
+                    .iter()
+                    .map(|typ| typ.instantiate(type_args))
+                    .collect::<anyhow::Result<_>>()?,
+                ..s.clone()
+            }),
+            MoveType::Reference { mutable, to } => MoveType::Reference {
+                mutable: *mutable,
+                to: Box::new(to.instantiate(type_args)?),
+            },
+            _ => self.clone(),
+        })
+    }
 }
 
 impl fmt::Display for MoveType {
@@ -964,8 +1078,8 @@ impl fmt::Display for ScriptFunctionId {
 #[cfg(test)]
 mod tests {
     use crate::{
-        move_types::ScriptFunctionId, HexEncodedBytes, MoveModuleId, MoveResource, MoveType, U128,
-        U64,
+        move_types::ScriptFunctionId, BytesEncoding, HexEncodedBytes, MoveModuleId, MoveResource,
+        MoveType, U128, U64,
     };
 
     use aptos_types::account_address::AccountAddress;
@@ -1084,6 +1198,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_serialize_move_resource_with_bytes_encoding() {
+        let values = || {
+            annotated_move_struct(
+                "Values",
+                vec![
+                    (
+                        identifier("text"),
+                        AnnotatedMoveValue::Bytes(b"hello".to_vec()),
+                    ),
+                    (
+                        identifier("binary"),
+                        AnnotatedMoveValue::Bytes(vec![0xff, 0xfe]),
+                    ),
+                ],
+            )
+        };
+        for (encoding, text, binary) in [
+            (BytesEncoding::Hex, "0x68656c6c6f", "0xfffe"),
+            (BytesEncoding::Base64, "aGVsbG8=", "//4="),
+        ] {
+            let res = MoveResource::try_from_annotated(values(), encoding).unwrap();
+            assert_json(
+                to_value(&res).unwrap(),
+                json!({
+                    "type": "0x1::Type::Values",
+                    "data": {
+                        "text": text,
+                        "binary": binary,
+                    }
+                }),
+            );
+        }
+
+        let err = MoveResource::try_from_annotated(values(), BytesEncoding::Utf8).unwrap_err();
+        assert!(err.downcast_ref::<InvalidUtf8Bytes>().is_some());
+        assert_eq!(
+            BytesEncoding::Utf8.encode(b"hello".to_vec()).unwrap(),
+            MoveValue::String("hello".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_parse_bytes_encoding() {
+        assert_eq!("hex".parse::<BytesEncoding>().unwrap(), BytesEncoding::Hex);
+        assert_eq!(
+            "base64".parse::<BytesEncoding>().unwrap(),
+            BytesEncoding::Base64
+        );
+        assert_eq!(
+            "utf8".parse::<BytesEncoding>().unwrap(),
+            BytesEncoding::Utf8
+        );
+        assert!("base58".parse::<BytesEncoding>().is_err());
+    }
+
     #[test]
     fn test_serialize_deserialize_u64() {
         test_serialize_deserialize(U64::from(u64::MAX), json!(u64::MAX.to_string()))
@@ -1215,6 +1385,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_instantiate_move_type() {
+        let generic = MoveType::Vector {
+            items: Box::new(MoveType::GenericTypeParam { index: 1 }),
+        };
+        let type_args = vec![MoveType::U8, MoveType::U64];
+        assert_eq!(
+            generic.instantiate(&type_args).unwrap(),
+            MoveType::Vector {
+                items: Box::new(MoveType::U64)
+            }
+        );
+        assert!(generic.instantiate(&type_args[..1]).is_err());
+        assert_eq!(MoveType::Bool.instantiate(&[]).unwrap(), MoveType::Bool);
+    }
+
     #[test]
     fn test_serialize_deserialize_hex_encoded_bytes() {
         let bytes = hex::decode("abcd").unwrap();
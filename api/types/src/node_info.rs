@@ -0,0 +1,32 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::U64;
+
+use serde::{Deserialize, Serialize};
+
+/// Describes the node serving the API and the chain it follows, clients detect the supported
+/// features from it instead of guessing them from the node version.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct NodeInfo {
+    pub chain_id: u8,
+    pub epoch: u64,
+    /// `validator` or `full_node`, absent when the API is not served by a node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    pub api_version: String,
+    pub ledger_version: U64,
+    /// The oldest transaction version not pruned by the node.
+    pub oldest_ledger_version: U64,
+    pub features: ApiFeatures,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ApiFeatures {
+    /// Responses are rendered in BCS when asked by the `Accept: application/x-bcs` header.
+    pub bcs_output: bool,
+    pub transaction_simulation: bool,
+    pub table_listing: bool,
+    /// The `/graphql` endpoint is served.
+    pub graphql: bool,
+}
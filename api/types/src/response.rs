@@ -1,20 +1,29 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{Error, LedgerInfo};
+use crate::{mime_types, Error, LedgerInfo};
 
 use anyhow::Result;
 use serde::Serialize;
-use warp::http::header::{HeaderValue, CONTENT_TYPE};
+use warp::http::{
+    header::{HeaderValue, CONTENT_TYPE, ETAG},
+    StatusCode,
+};
 
 pub const X_APTOS_CHAIN_ID: &str = "X-Aptos-Chain-Id";
 pub const X_APTOS_EPOCH: &str = "X-Aptos-Epoch";
 pub const X_APTOS_LEDGER_VERSION: &str = "X-Aptos-Ledger-Version";
 pub const X_APTOS_LEDGER_TIMESTAMP: &str = "X-Aptos-Ledger-TimestampUsec";
+pub const X_APTOS_CURSOR: &str = "X-Aptos-Cursor";
 
 pub struct Response {
     pub ledger_info: LedgerInfo,
     pub body: Vec<u8>,
+    pub content_type: &'static str,
+    /// Cursor for fetching the next page, only set when there are more items to fetch.
+    pub cursor: Option<String>,
+    pub etag: Option<String>,
+    pub status: StatusCode,
 }
 
 impl Response {
@@ -22,16 +31,63 @@ impl Response {
         Ok(Self {
             ledger_info,
             body: serde_json::to_vec(body)?,
+            content_type: mime_types::JSON,
+            cursor: None,
+            etag: None,
+            status: StatusCode::OK,
         })
     }
+
+    /// Creates response with the BCS serialized body, for clients that deserialize the
+    /// on-chain data locally.
+    pub fn new_bcs<T: Serialize>(ledger_info: LedgerInfo, body: &T) -> Result<Self, Error> {
+        let bytes = bcs::to_bytes(body).map_err(anyhow::Error::from)?;
+        Ok(Self::new_bcs_bytes(ledger_info, bytes))
+    }
+
+    /// Creates response with the given bytes that are already BCS serialized.
+    pub fn new_bcs_bytes(ledger_info: LedgerInfo, body: Vec<u8>) -> Self {
+        Self {
+            ledger_info,
+            body,
+            content_type: mime_types::BCS,
+            cursor: None,
+            etag: None,
+            status: StatusCode::OK,
+        }
+    }
+
+    /// Creates a `304 Not Modified` response without body, for the request that has an
+    /// `If-None-Match` header matching the current `etag` of the requested data.
+    pub fn not_modified(ledger_info: LedgerInfo, etag: String) -> Self {
+        Self {
+            ledger_info,
+            body: vec![],
+            content_type: mime_types::JSON,
+            cursor: None,
+            etag: Some(etag),
+            status: StatusCode::NOT_MODIFIED,
+        }
+    }
+
+    pub fn with_cursor(mut self, cursor: Option<String>) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
+    pub fn with_etag(mut self, etag: String) -> Self {
+        self.etag = Some(etag);
+        self
+    }
 }
 
 impl warp::Reply for Response {
     fn into_response(self) -> warp::reply::Response {
         let mut res = warp::reply::Response::new(self.body.into());
+        *res.status_mut() = self.status;
         let headers = res.headers_mut();
 
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(self.content_type));
         headers.insert(X_APTOS_CHAIN_ID, (self.ledger_info.chain_id as u16).into());
         headers.insert(
             X_APTOS_LEDGER_VERSION,
@@ -42,6 +98,16 @@ impl warp::Reply for Response {
             self.ledger_info.ledger_timestamp.into(),
         );
         headers.insert(X_APTOS_EPOCH, self.ledger_info.epoch.into());
+        if let Some(cursor) = self.cursor {
+            if let Ok(value) = HeaderValue::from_str(&cursor) {
+                headers.insert(X_APTOS_CURSOR, value);
+            }
+        }
+        if let Some(etag) = self.etag {
+            if let Ok(value) = HeaderValue::from_str(&etag) {
+                headers.insert(ETAG, value);
+            }
+        }
 
         res
     }
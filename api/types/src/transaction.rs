@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    Address, EventKey, HashValue, HexEncodedBytes, MoveModuleBytecode, MoveModuleId, MoveResource,
-    MoveScriptBytecode, MoveStructTag, MoveType, MoveValue, ScriptFunctionId, U64,
+    Address, Error, EventKey, HashValue, HexEncodedBytes, MoveModuleBytecode, MoveModuleId,
+    MoveResource, MoveScriptBytecode, MoveStructTag, MoveType, MoveValue, ScriptFunctionId, U64,
 };
 
 use anyhow::bail;
@@ -30,7 +30,7 @@ use std::{
     str::FromStr,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum TransactionData {
     OnChain(TransactionOnChainData),
     Pending(Box<SignedTransaction>),
@@ -48,7 +48,7 @@ impl From<SignedTransaction> for TransactionData {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct TransactionOnChainData {
     pub version: u64,
     pub transaction: aptos_types::transaction::Transaction,
@@ -205,11 +205,48 @@ impl Transaction {
     }
 }
 
+/// The compact form of a transaction for list views: the payload, events and changes are
+/// left out.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionSummary {
+    #[serde(rename = "type")]
+    pub typ: String,
+    pub hash: HashValue,
+    /// Absent for pending transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<U64>,
+    /// Only present for user transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<Address>,
+    pub success: bool,
+    pub vm_status: String,
+    /// Absent for pending transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<U64>,
+    pub timestamp: U64,
+}
+
+impl From<&SignedTransaction> for TransactionSummary {
+    fn from(txn: &SignedTransaction) -> Self {
+        Self {
+            typ: "pending_transaction".to_owned(),
+            hash: txn.clone().committed_hash().into(),
+            version: None,
+            sender: Some(txn.sender().into()),
+            success: false,
+            vm_status: "pending".to_owned(),
+            gas_used: None,
+            timestamp: U64(0),
+        }
+    }
+}
+
 impl From<(SignedTransaction, TransactionPayload)> for Transaction {
     fn from((txn, payload): (SignedTransaction, TransactionPayload)) -> Self {
         Transaction::PendingTransaction(PendingTransaction {
             request: (&txn, payload).into(),
             hash: txn.committed_hash().into(),
+            waiting_for_sequence_number: None,
         })
     }
 }
@@ -293,15 +330,40 @@ pub struct TransactionInfo {
     pub gas_used: U64,
     pub success: bool,
     pub vm_status: String,
+    /// The details of the Move abort the transaction failed with, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub move_abort: Option<MoveAbort>,
     pub accumulator_root_hash: HashValue,
     pub changes: Vec<WriteSetChange>,
 }
 
+/// A Move abort, along with the category and the reason of its abort code as documented in the
+/// error map of the module that aborted, when the module is part of the framework.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MoveAbort {
+    /// The module that aborted, `None` when the script did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<MoveModuleId>,
+    pub abort_code: U64,
+    /// The category of the abort code, e.g. `LIMIT_EXCEEDED`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// The reason of the abort code, e.g. `EINSUFFICIENT_BALANCE`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason_description: Option<String>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PendingTransaction {
     pub hash: HashValue,
     #[serde(flatten)]
     pub request: UserTransactionRequest,
+    /// The sequence number of the sender missing in mempool, which the transaction waits for
+    /// before it can be broadcast and committed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting_for_sequence_number: Option<U64>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -385,11 +447,19 @@ pub enum GenesisPayload {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TransactionPayload {
     ScriptFunctionPayload(ScriptFunctionPayload),
+    ScriptFunctionBatchPayload(ScriptFunctionBatchPayload),
     ScriptPayload(ScriptPayload),
     ModuleBundlePayload(ModuleBundlePayload),
     WriteSetPayload(WriteSetPayload),
 }
 
+/// The calls of a script function batch, an argument of a call may be the return value of an
+/// earlier call, given as `{"call": <index of the call>, "output": <index of the return value>}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScriptFunctionBatchPayload {
+    pub calls: Vec<ScriptFunctionPayload>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ModuleBundlePayload {
     pub modules: Vec<MoveModuleBytecode>,
@@ -472,6 +542,17 @@ pub enum WriteSetChange {
         state_key_hash: String,
         data: MoveResource,
     },
+    /// The change of a state key that isn't an access path, only rendered in direct write sets.
+    /// The key and the value are bytes, in the requested bytes encoding.
+    DeleteRaw {
+        state_key_hash: String,
+        key: serde_json::Value,
+    },
+    WriteRaw {
+        state_key_hash: String,
+        key: serde_json::Value,
+        value: serde_json::Value,
+    },
 }
 
 impl WriteSetChange {
@@ -481,10 +562,31 @@ impl WriteSetChange {
             WriteSetChange::DeleteResource { .. } => "delete_resource",
             WriteSetChange::WriteModule { .. } => "write_module",
             WriteSetChange::WriteResource { .. } => "write_resource",
+            WriteSetChange::DeleteRaw { .. } => "delete_raw",
+            WriteSetChange::WriteRaw { .. } => "write_raw",
         }
     }
 }
 
+/// The change a transaction made to a resource, `before` and `after` are the values of the
+/// resource right before and after the transaction, `None` when the resource doesn't exist.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResourceDiff {
+    pub address: Address,
+    pub state_key_hash: String,
+    pub resource: MoveStructTag,
+    pub before: Option<MoveResource>,
+    pub after: Option<MoveResource>,
+}
+
+/// An on-chain transaction rendered together with the diffs of the resources it changed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionWithWriteSetDiff {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    pub write_set_diff: Vec<ResourceDiff>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TransactionSignature {
@@ -794,6 +896,16 @@ impl fmt::Display for TransactionId {
     }
 }
 
+/// The request body of creating a signing message. When `secondary_signers` is given, the
+/// message is for signing a multi-agent transaction, and every signer must sign the same message.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionSigningMessageRequest {
+    #[serde(flatten)]
+    pub transaction: UserTransactionRequest,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secondary_signers: Vec<Address>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TransactionSigningMessage {
     pub message: HexEncodedBytes,
@@ -806,3 +918,18 @@ impl TransactionSigningMessage {
         }
     }
 }
+
+/// The result of submitting one transaction of a batch, the results of a batch are in the
+/// same order as the submitted transactions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionSubmissionResult {
+    Accepted { transaction: Transaction },
+    Rejected { error: Error },
+}
+
+impl TransactionSubmissionResult {
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Self::Accepted { .. })
+    }
+}
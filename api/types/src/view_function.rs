@@ -0,0 +1,15 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{MoveType, ScriptFunctionId};
+
+use serde::{Deserialize, Serialize};
+
+/// Request of executing a public Move function without submitting a transaction, the
+/// arguments are JSON values in the same format as the `ScriptFunctionPayload` arguments.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ViewRequest {
+    pub function: ScriptFunctionId,
+    pub type_arguments: Vec<MoveType>,
+    pub arguments: Vec<serde_json::Value>,
+}
@@ -1,8 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{counters::*, data_cache::StateViewCache};
+use crate::{counters::*, data_cache::StateViewCache, profiling};
 use anyhow::Result;
+use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_state_view::StateView;
 use aptos_types::{
     transaction::{SignatureCheckedTransaction, SignedTransaction, VMValidatorResult},
@@ -18,7 +19,7 @@ use crate::{
 use aptos_logger::prelude::*;
 use aptos_types::{
     access_path::AccessPath,
-    block_metadata::BlockMetadata,
+    block_metadata::{BlockMetadata, BlockMetadataExt},
     state_store::state_key::StateKey,
     transaction::{
         Transaction, TransactionArgument, TransactionOutput, TransactionPayload, TransactionStatus,
@@ -62,7 +63,7 @@ pub trait VMAdapter {
     fn run_prologue<S: MoveResolver>(
         &self,
         session: &mut SessionExt<S>,
-        transaction: &SignatureCheckedTransaction,
+        transaction: &SignedTransaction,
         log_context: &AdapterLogSchema,
     ) -> Result<(), VMStatus>;
 
@@ -128,10 +129,11 @@ pub fn validate_signed_transaction<A: VMAdapter>(
     VMValidatorResult::new(status, gas_price)
 }
 
+/// Validates a transaction whose signature is checked by the caller, or which is simulated.
 pub(crate) fn validate_signature_checked_transaction<S: MoveResolver, A: VMAdapter>(
     adapter: &A,
     session: &mut SessionExt<S>,
-    transaction: &SignatureCheckedTransaction,
+    transaction: &SignedTransaction,
     allow_too_new: bool,
     log_context: &AdapterLogSchema,
 ) -> Result<(), VMStatus> {
@@ -189,6 +191,11 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
         transactions.len()
     );
 
+    // The user transactions are profiled once the outputs of the block are final
+    let hashes: Option<Vec<HashValue>> = profiling::is_profiling_enabled()
+        .then(|| transactions.par_iter().map(CryptoHash::hash).collect());
+    let mut measurements = vec![];
+
     let signature_verified_block: Vec<PreprocessedTransaction>;
     {
         // Verify the signatures of all the transactions in parallel.
@@ -212,14 +219,18 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
             let txn_output =
                 TransactionOutput::new(WriteSet::default(), vec![], 0, TransactionStatus::Retry);
             result.push((VMStatus::Error(StatusCode::UNKNOWN_STATUS), txn_output));
+            measurements.push(None);
             debug!(log_context, "Retry after reconfiguration");
             continue;
         };
-        let (vm_status, output, sender) = adapter.execute_single_transaction(
+        let (execution, measurement) = profiling::execute_measured(
+            adapter,
             &txn,
             &data_cache.as_move_resolver(),
             &log_context,
-        )?;
+        );
+        let (vm_status, output, sender) = execution?;
+        measurements.push(measurement);
         if !output.status().is_discarded() {
             data_cache.push_write_set(output.write_set());
         } else {
@@ -247,6 +258,15 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
         assume!(result.len() < usize::max_value());
         result.push((vm_status, output))
     }
+    if let Some(hashes) = hashes {
+        profiling::record_block_profiles(
+            &hashes,
+            result
+                .iter()
+                .map(|(_vm_status, output)| output)
+                .zip(measurements),
+        );
+    }
     Ok(result)
 }
 
@@ -258,6 +278,7 @@ pub enum PreprocessedTransaction {
     UserTransaction(Box<SignatureCheckedTransaction>),
     WaypointWriteSet(WriteSetPayload),
     BlockMetadata(BlockMetadata),
+    BlockMetadataExt(BlockMetadataExt),
     WriteSet(Box<SignatureCheckedTransaction>),
     InvalidSignature,
     StateCheckpoint,
@@ -286,6 +307,7 @@ pub(crate) fn preprocess_transaction<A: VMAdapter>(txn: Transaction) -> Preproce
             }
         }
         Transaction::StateCheckpoint => PreprocessedTransaction::StateCheckpoint,
+        Transaction::BlockMetadataExt(b) => PreprocessedTransaction::BlockMetadataExt(b),
     }
 }
 
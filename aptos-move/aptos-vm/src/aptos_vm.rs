@@ -14,9 +14,11 @@ use crate::{
     counters::*,
     data_cache::{RemoteStorage, StateViewCache},
     errors::expect_only_successful_execution,
+    gas_schedule,
     logging::AdapterLogSchema,
-    move_vm_ext::{SessionExt, SessionId},
-    script_to_script_function,
+    module_cache::ModuleCache,
+    move_vm_ext::{MoveVmExt, SessionExt, SessionId},
+    native_tracing, randomness, script_to_script_function,
     system_module_names::*,
     transaction_metadata::TransactionMetadata,
     VMExecutor, VMValidator,
@@ -30,17 +32,20 @@ use aptos_types::{
     block_metadata::BlockMetadata,
     on_chain_config::{
         OnChainConfig, ParallelExecutionConfig, VMConfig, VMPublishingOption, Version,
+        APTOS_VERSION_5,
     },
     transaction::{
-        ChangeSet, ModuleBundle, SignatureCheckedTransaction, SignedTransaction, Transaction,
-        TransactionOutput, TransactionPayload, TransactionStatus, VMValidatorResult,
-        WriteSetPayload,
+        BatchedCall, BatchedCallArgument, ChangeSet, ModuleBundle, ScriptFunctionBatch,
+        SignatureCheckedTransaction, SignedTransaction, Transaction, TransactionOutput,
+        TransactionPayload, TransactionStatus, VMValidatorResult, WriteSetPayload,
     },
     vm_status::{KeptVMStatus, StatusCode, VMStatus},
     write_set::{WriteSet, WriteSetMut},
 };
 use fail::fail_point;
-use move_binary_format::{errors::VMResult, CompiledModule};
+use move_binary_format::{
+    access::ModuleAccess, errors::VMResult, file_format::Visibility, CompiledModule,
+};
 use move_core_types::{
     account_address::AccountAddress,
     gas_schedule::{GasAlgebra, GasUnits},
@@ -66,6 +71,10 @@ impl AptosVM {
         Self(AptosVMImpl::new(state))
     }
 
+    pub(crate) fn new_with_move_vm<S: StateView>(move_vm: Arc<MoveVmExt>, state: &S) -> Self {
+        Self(AptosVMImpl::new_with_move_vm(move_vm, state))
+    }
+
     pub fn new_for_validation<S: StateView>(state: &S) -> Self {
         info!(
             AdapterLogSchema::new(state.id(), 0),
@@ -121,6 +130,28 @@ impl AptosVM {
         if !func.return_.is_empty() {
             return Err(VMStatus::Error(StatusCode::INVALID_MAIN_FUNCTION_SIGNATURE));
         }
+        AptosVM::combine_signer_and_args(senders, args, func)
+    }
+
+    /// validation and generate args for a call of a script function batch, same as for an entry
+    /// function except that the function may return values, which have to be of the types the
+    /// args are limited to, so that they can be passed to the later calls of the batch.
+    fn validate_combine_signer_and_batched_call_args(
+        senders: Vec<AccountAddress>,
+        args: Vec<Vec<u8>>,
+        func: &LoadedFunctionInstantiation,
+    ) -> Result<Vec<Vec<u8>>, VMStatus> {
+        if !func.return_.iter().all(AptosVM::is_valid_for_constant_type) {
+            return Err(VMStatus::Error(StatusCode::INVALID_MAIN_FUNCTION_SIGNATURE));
+        }
+        AptosVM::combine_signer_and_args(senders, args, func)
+    }
+
+    fn combine_signer_and_args(
+        senders: Vec<AccountAddress>,
+        args: Vec<Vec<u8>>,
+        func: &LoadedFunctionInstantiation,
+    ) -> Result<Vec<Vec<u8>>, VMStatus> {
         let mut signer_param_cnt = 0;
         // find all signer params at the beginning
         for ty in func.parameters.iter() {
@@ -338,7 +369,9 @@ impl AptosVM {
                         gas_status,
                     )
                 }
-                TransactionPayload::ModuleBundle(_) | TransactionPayload::WriteSet(_) => {
+                TransactionPayload::ModuleBundle(_)
+                | TransactionPayload::ScriptFunctionBatch(_)
+                | TransactionPayload::WriteSet(_) => {
                     return Err(VMStatus::Error(StatusCode::UNREACHABLE));
                 }
             }
@@ -356,6 +389,119 @@ impl AptosVM {
         }
     }
 
+    fn execute_script_function_batch<S: MoveResolver>(
+        &self,
+        storage: &S,
+        mut session: SessionExt<S>,
+        gas_status: &mut GasStatus,
+        txn_data: &TransactionMetadata,
+        batch: &ScriptFunctionBatch,
+        account_currency_symbol: &IdentStr,
+        log_context: &AdapterLogSchema,
+    ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
+        fail_point!("move_adapter::execute_script_function_batch", |_| {
+            Err(VMStatus::Error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+            ))
+        });
+
+        gas_status
+            .charge_intrinsic_gas(txn_data.transaction_size())
+            .map_err(|e| e.into_vm_status())?;
+
+        let mut senders = vec![txn_data.sender()];
+        senders.extend(txn_data.secondary_signers());
+        // The return values of the calls executed so far, along with their types
+        let mut outputs: Vec<Vec<(Vec<u8>, Type)>> = vec![];
+        for call in batch.calls() {
+            self.check_batched_call_visibility(storage, call)?;
+            let function = session.load_function(call.module(), call.function(), call.ty_args())?;
+            let params: Vec<&Type> = function
+                .parameters
+                .iter()
+                .skip_while(|ty| matches!(ty, Type::Signer))
+                .collect();
+            if params.len() != call.args().len() {
+                return Err(VMStatus::Error(StatusCode::NUMBER_OF_ARGUMENTS_MISMATCH));
+            }
+            let args = call
+                .args()
+                .iter()
+                .zip(params)
+                .map(|(arg, param)| match arg {
+                    BatchedCallArgument::Value(bytes) => Ok(bytes.clone()),
+                    BatchedCallArgument::Output {
+                        call: index,
+                        output,
+                    } => match outputs
+                        .get(*index as usize)
+                        .and_then(|values| values.get(*output as usize))
+                    {
+                        Some((bytes, ty)) if ty == param => Ok(bytes.clone()),
+                        Some(_) => Err(VMStatus::Error(StatusCode::TYPE_MISMATCH)),
+                        // Only the outputs of the calls before this one can be passed to it.
+                        None => Err(VMStatus::Error(StatusCode::INDEX_OUT_OF_BOUNDS)),
+                    },
+                })
+                .collect::<Result<_, _>>()?;
+            let args = AptosVM::validate_combine_signer_and_batched_call_args(
+                senders.clone(),
+                args,
+                &function,
+            )?;
+
+            let values = session
+                .execute_function_bypass_visibility(
+                    call.module(),
+                    call.function(),
+                    call.ty_args().to_vec(),
+                    args,
+                    gas_status,
+                )
+                .map_err(|e| e.into_vm_status())?;
+            outputs.push(
+                values
+                    .return_values
+                    .into_iter()
+                    .map(|(bytes, _layout)| bytes)
+                    .zip(function.return_)
+                    .collect(),
+            );
+        }
+
+        charge_global_write_gas_usage(gas_status, &session, &txn_data.sender())?;
+
+        self.success_transaction_cleanup(
+            session,
+            gas_status,
+            txn_data,
+            account_currency_symbol,
+            log_context,
+        )
+    }
+
+    /// The calls of a batch are executed bypassing the visibility checks of the Move VM, so that
+    /// they can return values: they are limited to the script and the public functions here.
+    fn check_batched_call_visibility<S: MoveResolver>(
+        &self,
+        storage: &S,
+        call: &BatchedCall,
+    ) -> Result<(), VMStatus> {
+        let module = self
+            .0
+            .load_module(call.module(), storage)
+            .map_err(|e| e.into_vm_status())?;
+        let is_visible = module.function_defs().iter().any(|def| {
+            let handle = module.function_handle_at(def.function);
+            module.identifier_at(handle.name) == call.function()
+                && matches!(def.visibility, Visibility::Public | Visibility::Script)
+        });
+        if !is_visible {
+            return Err(VMStatus::Error(StatusCode::FUNCTION_RESOLUTION_FAILURE));
+        }
+        Ok(())
+    }
+
     fn execute_modules<S: MoveResolver>(
         &self,
         mut session: SessionExt<S>,
@@ -382,6 +528,12 @@ impl AptosVM {
             .charge_intrinsic_gas(txn_data.transaction_size())
             .map_err(|e| e.into_vm_status())?;
 
+        // The modules which don't deserialize are rejected when published.
+        for module in modules.iter() {
+            if let Ok(module) = CompiledModule::deserialize(module.code()) {
+                randomness::check_module_draws(&module)?;
+            }
+        }
         session
             .publish_module_bundle(modules.clone().into_inner(), module_address, gas_status)
             .map_err(|e| e.into_vm_status())?;
@@ -402,6 +554,17 @@ impl AptosVM {
         storage: &S,
         txn: &SignatureCheckedTransaction,
         log_context: &AdapterLogSchema,
+    ) -> (VMStatus, TransactionOutput) {
+        self.execute_user_transaction_impl(storage, txn, log_context)
+    }
+
+    /// Executes the user transaction, whose signature is checked by the caller unless the
+    /// transaction is simulated.
+    fn execute_user_transaction_impl<S: MoveResolver>(
+        &self,
+        storage: &S,
+        txn: &SignedTransaction,
+        log_context: &AdapterLogSchema,
     ) -> (VMStatus, TransactionOutput) {
         macro_rules! unwrap_or_discard {
             ($res: expr) => {
@@ -425,7 +588,10 @@ impl AptosVM {
         }
 
         // Revalidate the transaction.
-        let mut session = self.0.new_session(storage, SessionId::txn(txn));
+        let draws_allowed = randomness::draws_allowed(&self.0, txn.payload(), storage);
+        let mut session =
+            self.0
+                .new_session_with_draws(storage, SessionId::txn(txn), draws_allowed);
         if let Err(err) = validate_signature_checked_transaction::<S, Self>(
             self,
             &mut session,
@@ -451,6 +617,15 @@ impl AptosVM {
                     &account_currency_symbol,
                     log_context,
                 ),
+            TransactionPayload::ScriptFunctionBatch(batch) => self.execute_script_function_batch(
+                storage,
+                session,
+                &mut gas_status,
+                &txn_data,
+                batch,
+                &account_currency_symbol,
+                log_context,
+            ),
             TransactionPayload::ModuleBundle(m) => self.execute_modules(
                 session,
                 &mut gas_status,
@@ -586,10 +761,14 @@ impl AptosVM {
         ))
     }
 
+    /// Runs the `block_prologue` of the framework, or its `block_prologue_ext` once the on-chain
+    /// version supports it, which seeds the randomness with the hash of the block. The failed
+    /// proposers of the block can only be reported from that version on.
     pub(crate) fn process_block_prologue<S: MoveResolver>(
         &self,
         storage: &S,
         block_metadata: BlockMetadata,
+        failed_proposers: Option<Vec<AccountAddress>>,
         log_context: &AdapterLogSchema,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
         fail_point!("move_adapter::process_block_prologue", |_| {
@@ -608,25 +787,23 @@ impl AptosVM {
             .0
             .new_session(storage, SessionId::block_meta(&block_metadata));
 
-        let (round, timestamp, previous_vote, proposer) = block_metadata.into_inner();
-        let args = serialize_values(&vec![
-            MoveValue::Signer(txn_data.sender),
-            MoveValue::U64(round),
-            MoveValue::U64(timestamp),
-            MoveValue::Vector(previous_vote.into_iter().map(MoveValue::Address).collect()),
-            MoveValue::Address(proposer),
-        ]);
+        let ext_supported = self.0.get_version()? >= APTOS_VERSION_5;
+        if failed_proposers.is_some() && !ext_supported {
+            return Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING));
+        }
+        let (function_name, args) =
+            block_prologue_args(&block_metadata, failed_proposers.as_ref(), ext_supported);
         session
             .execute_function_bypass_visibility(
                 &BLOCK_MODULE,
-                BLOCK_PROLOGUE,
+                function_name,
                 vec![],
-                args,
+                serialize_values(&args),
                 &mut gas_status,
             )
             .map(|_return_vals| ())
             .or_else(|e| {
-                expect_only_successful_execution(e, BLOCK_PROLOGUE.as_str(), log_context)
+                expect_only_successful_execution(e, function_name.as_str(), log_context)
             })?;
         SYSTEM_TRANSACTIONS_EXECUTED.inc();
 
@@ -682,7 +859,8 @@ impl AptosVM {
                 TransactionPayload::WriteSet(writeset_payload) => writeset_payload,
                 TransactionPayload::ModuleBundle(_)
                 | TransactionPayload::Script(_)
-                | TransactionPayload::ScriptFunction(_) => {
+                | TransactionPayload::ScriptFunction(_)
+                | TransactionPayload::ScriptFunctionBatch(_) => {
                     log_context.alert();
                     error!(*log_context, "[aptos_vm] UNREACHABLE");
                     return Ok(discard_error_vm_status(VMStatus::Error(
@@ -790,16 +968,35 @@ impl AptosVM {
         ))
     }
 
+    /// Executes a user transaction against the given state without checking its signature.
+    /// The output is not applied to the state, it is for previewing the gas used and the
+    /// effects of a transaction before submitting it.
+    pub fn simulate_signed_transaction(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+    ) -> (VMStatus, TransactionOutput) {
+        let vm = AptosVM::new(state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        vm.execute_user_transaction_impl(&RemoteStorage::new(state_view), txn, &log_context)
+    }
+
     /// Alternate form of 'execute_block' that keeps the vm_status before it goes into the
     /// `TransactionOutput`
     pub fn execute_block_and_keep_vm_status(
         transactions: Vec<Transaction>,
         state_view: &impl StateView,
     ) -> Result<Vec<(VMStatus, TransactionOutput)>, VMStatus> {
-        let mut state_view_cache = StateViewCache::new(state_view);
         let count = transactions.len();
-        let vm = AptosVM::new(&state_view_cache);
+        let module_cache = ModuleCache::global();
+        let move_vm = module_cache.checkout(state_view.id(), &transactions);
+        let mut state_view_cache = StateViewCache::new(state_view);
+        let vm = AptosVM::new_with_move_vm(move_vm.clone(), &state_view_cache);
         let res = adapter_common::execute_block_impl(&vm, transactions, &mut state_view_cache)?;
+        module_cache.checkin(
+            move_vm,
+            state_view.id(),
+            res.iter().map(|(_vm_status, output)| output),
+        );
         // Record the histogram count for transactions per block.
         BLOCK_TRANSACTION_COUNT.observe(count as f64);
         Ok(res)
@@ -823,6 +1020,7 @@ impl VMExecutor for AptosVM {
             ))
         });
 
+        gas_schedule::activate_vm_config(&RemoteStorage::new(state_view));
         // Execute transactions in parallel if on chain config is set and loaded.
         if let Some(_read_write_set_analysis) =
             ParallelExecutionConfig::fetch_config(&RemoteStorage::new(state_view))
@@ -842,6 +1040,22 @@ impl VMExecutor for AptosVM {
                 .collect())
         }
     }
+
+    fn execute_block_in_parallel(
+        transactions: Vec<Transaction>,
+        state_view: &impl StateView,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        fail_point!("move_adapter::execute_block", |_| {
+            Err(VMStatus::Error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+            ))
+        });
+
+        gas_schedule::activate_vm_config(&RemoteStorage::new(state_view));
+        let (result, _) =
+            crate::parallel_executor::ParallelAptosVM::execute_block(transactions, state_view)?;
+        Ok(result)
+    }
 }
 
 // VMValidator external API
@@ -912,7 +1126,7 @@ impl VMAdapter for AptosVM {
     fn run_prologue<S: MoveResolver>(
         &self,
         session: &mut SessionExt<S>,
-        transaction: &SignatureCheckedTransaction,
+        transaction: &SignedTransaction,
         log_context: &AdapterLogSchema,
     ) -> Result<(), VMStatus> {
         let currency_code = get_gas_currency_code(transaction)?;
@@ -924,8 +1138,15 @@ impl VMAdapter for AptosVM {
                 self.0
                     .run_script_prologue(session, &txn_data, &currency_code, log_context)
             }
-            TransactionPayload::ScriptFunction(_) => {
-                // NOTE: Script and ScriptFunction shares the same prologue
+            TransactionPayload::ScriptFunction(_) | TransactionPayload::ScriptFunctionBatch(_) => {
+                if matches!(
+                    transaction.payload(),
+                    TransactionPayload::ScriptFunctionBatch(_)
+                ) && self.0.get_version()? < APTOS_VERSION_5
+                {
+                    return Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING));
+                }
+                // NOTE: Script, ScriptFunction and ScriptFunctionBatch share the same prologue
                 self.0.check_gas(&txn_data, log_context)?;
                 self.0
                     .run_script_prologue(session, &txn_data, &currency_code, log_context)
@@ -958,10 +1179,24 @@ impl VMAdapter for AptosVM {
     ) -> Result<(VMStatus, TransactionOutput, Option<String>), VMStatus> {
         Ok(match txn {
             PreprocessedTransaction::BlockMetadata(block_metadata) => {
-                let (vm_status, output) =
-                    self.process_block_prologue(data_cache, block_metadata.clone(), log_context)?;
+                let (vm_status, output) = self.process_block_prologue(
+                    data_cache,
+                    block_metadata.clone(),
+                    None,
+                    log_context,
+                )?;
                 (vm_status, output, Some("block_prologue".to_string()))
             }
+            PreprocessedTransaction::BlockMetadataExt(block_metadata_ext) => {
+                let (block_metadata, failed_proposers) = block_metadata_ext.clone().into_inner();
+                let (vm_status, output) = self.process_block_prologue(
+                    data_cache,
+                    block_metadata,
+                    Some(failed_proposers),
+                    log_context,
+                )?;
+                (vm_status, output, Some("block_prologue_ext".to_string()))
+            }
             PreprocessedTransaction::WaypointWriteSet(write_set_payload) => {
                 let (vm_status, output) =
                     self.process_waypoint_change_set(data_cache, write_set_payload.clone())?;
@@ -970,8 +1205,10 @@ impl VMAdapter for AptosVM {
             PreprocessedTransaction::UserTransaction(txn) => {
                 let sender = txn.sender().to_string();
                 let _timer = TXN_TOTAL_SECONDS.start_timer();
+                native_tracing::start_transaction_trace();
                 let (vm_status, output) =
                     self.execute_user_transaction(data_cache, txn, log_context);
+                native_tracing::finish_transaction_trace(txn);
 
                 // Increment the counter for user transactions executed.
                 let counter_label = match output.status() {
@@ -1018,3 +1255,38 @@ impl AsMut<AptosVMImpl> for AptosVM {
         &mut self.0
     }
 }
+
+/// The block prologue function to run for the block, and its arguments: `block_prologue_ext`,
+/// passed the hash of the block, once `ext_supported` by the on-chain version, `block_prologue`
+/// otherwise, which takes no failed proposers.
+pub(crate) fn block_prologue_args(
+    block_metadata: &BlockMetadata,
+    failed_proposers: Option<&Vec<AccountAddress>>,
+    ext_supported: bool,
+) -> (&'static IdentStr, Vec<MoveValue>) {
+    let (round, timestamp, previous_vote, proposer) = block_metadata.clone().into_inner();
+    let mut args = vec![MoveValue::Signer(account_config::reserved_vm_address())];
+    if ext_supported {
+        args.push(MoveValue::Address(AccountAddress::new(
+            *block_metadata.id(),
+        )));
+    }
+    args.extend(vec![
+        MoveValue::U64(round),
+        MoveValue::U64(timestamp),
+        MoveValue::Vector(previous_vote.into_iter().map(MoveValue::Address).collect()),
+        MoveValue::Address(proposer),
+    ]);
+    if !ext_supported {
+        return (BLOCK_PROLOGUE, args);
+    }
+    let failed_proposers = failed_proposers.map_or_else(Vec::new, |failed_proposers| {
+        failed_proposers
+            .iter()
+            .copied()
+            .map(MoveValue::Address)
+            .collect()
+    });
+    args.push(MoveValue::Vector(failed_proposers));
+    (BLOCK_PROLOGUE_EXT, args)
+}
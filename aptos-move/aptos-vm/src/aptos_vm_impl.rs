@@ -6,6 +6,7 @@ use crate::{
     counters::*,
     data_cache::RemoteStorage,
     errors::{convert_epilogue_error, convert_prologue_error, expect_only_successful_execution},
+    gas_schedule,
     logging::AdapterLogSchema,
     move_vm_ext::{MoveVmExt, SessionExt, SessionId},
     transaction_metadata::TransactionMetadata,
@@ -60,8 +61,13 @@ impl AptosVMImpl {
     pub fn new<S: StateView>(state: &S) -> Self {
         let inner = MoveVmExt::new()
             .expect("should be able to create Move VM; check if there are duplicated natives");
+        Self::new_with_move_vm(Arc::new(inner), state)
+    }
+
+    /// Creates the VM on top of an existing Move VM, e.g. the one cached across the blocks.
+    pub(crate) fn new_with_move_vm<S: StateView>(move_vm: Arc<MoveVmExt>, state: &S) -> Self {
         let mut vm = Self {
-            move_vm: Arc::new(inner),
+            move_vm,
             on_chain_config: None,
             version: None,
             publishing_option: None,
@@ -114,7 +120,7 @@ impl AptosVMImpl {
     }
 
     fn load_configs_impl<S: ConfigStorage>(&mut self, data_cache: &S) {
-        self.on_chain_config = VMConfig::fetch_config(data_cache);
+        self.on_chain_config = gas_schedule::fetch_vm_config(data_cache);
         self.version = Version::fetch_config(data_cache);
         self.publishing_option = VMPublishingOption::fetch_config(data_cache);
     }
@@ -510,6 +516,16 @@ impl AptosVMImpl {
         self.move_vm.new_session(r, session_id)
     }
 
+    pub fn new_session_with_draws<'r, R: MoveResolver>(
+        &self,
+        r: &'r R,
+        session_id: SessionId,
+        draws_allowed: bool,
+    ) -> SessionExt<'r, '_, R> {
+        self.move_vm
+            .new_session_with_draws(r, session_id, draws_allowed)
+    }
+
     pub fn load_module<'r, R: MoveResolver>(
         &self,
         module_id: &ModuleId,
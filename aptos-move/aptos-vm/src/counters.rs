@@ -66,6 +66,56 @@ pub static TXN_GAS_USAGE: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!("aptos_vm_txn_gas_usage", "Gas used per transaction").unwrap()
 });
 
+/// The number of state values read per user transaction, when the execution is profiled.
+pub static TXN_STATE_READS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_vm_txn_state_reads",
+        "Number of state values read per profiled user transaction"
+    )
+    .unwrap()
+});
+
+/// The number of state values written per user transaction, when the execution is profiled.
+pub static TXN_STATE_WRITES: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_vm_txn_state_writes",
+        "Number of state values written per profiled user transaction"
+    )
+    .unwrap()
+});
+
+/// Count the blocks whose parallel execution fell back to the sequential one, with a "reason"
+/// label to distinguish the failures of the read write set inference from the conflicts.
+pub static PARALLEL_EXECUTION_FALLBACKS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_vm_parallel_execution_fallbacks",
+        "Number of blocks whose parallel execution fell back to the sequential one",
+        &["reason"]
+    )
+    .unwrap()
+});
+
+/// Count the lookups of the Move VM cached across the blocks, with a "result" label to
+/// distinguish the hits from the misses, as the cache was empty, the Move VM cached was for another
+/// state, e.g. of another fork, or the block may publish modules.
+pub static MODULE_CACHE_LOOKUPS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_vm_module_cache_lookups",
+        "Number of lookups of the Move VM cached across the blocks",
+        &["result"]
+    )
+    .unwrap()
+});
+
+/// Count the reloads of the gas schedule, once it changed on chain, e.g. at an epoch boundary.
+pub static GAS_SCHEDULE_RELOADS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_vm_gas_schedule_reloads",
+        "Number of reloads of the gas schedule"
+    )
+    .unwrap()
+});
+
 /// Count the number of critical errors. This is not intended for display
 /// on a dashboard but rather for triggering alerts.
 pub static CRITICAL_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
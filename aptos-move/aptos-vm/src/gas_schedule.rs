@@ -0,0 +1,194 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The gas schedule of the on-chain VM config. The VM config only changes at reconfigurations, so
+//! the VMs share the one the block executor last activated as long as its bytes are unchanged on
+//! chain, and a governance change of the gas parameters takes effect from the first block of the
+//! next epoch without restarting the node.
+
+use crate::{counters::GAS_SCHEDULE_RELOADS, data_cache::RemoteStorage};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::AccessPath,
+    on_chain_config::{
+        access_path_for_config, config_address, dpn_access_path_for_config, ConfigStorage,
+        ConfigurationResource, OnChainConfig, VMConfig,
+    },
+};
+use move_core_types::{gas_schedule::GasConstants, move_resource::MoveResource};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+static ACTIVE_VM_CONFIG: Lazy<Mutex<Option<ActiveVMConfig>>> = Lazy::new(|| Mutex::new(None));
+
+/// A description of a gas schedule, to tell which one a node executes the blocks with
+#[derive(Clone, Debug, Serialize)]
+pub struct GasScheduleInfo {
+    /// The epoch of the state the gas schedule was loaded from
+    pub epoch: u64,
+    /// The hash of the on-chain VM config the gas schedule is part of
+    pub hash: HashValue,
+    pub instructions: usize,
+    pub natives: usize,
+    pub gas_constants: GasConstants,
+}
+
+struct ActiveVMConfig {
+    info: GasScheduleInfo,
+    config: VMConfig,
+}
+
+/// Returns the gas schedule the VM executed the latest blocks with, if it executed any
+pub fn active_gas_schedule() -> Option<GasScheduleInfo> {
+    ACTIVE_VM_CONFIG
+        .lock()
+        .as_ref()
+        .map(|active| active.info.clone())
+}
+
+/// Returns the gas schedule of the state, without making it the active one
+pub fn gas_schedule_of(state_view: &impl StateView) -> Option<GasScheduleInfo> {
+    let storage = RemoteStorage::new(state_view);
+    let bytes = fetch_vm_config_bytes(&storage)?;
+    let config = VMConfig::deserialize_into_config(&bytes).ok()?;
+    Some(gas_schedule_info(
+        fetch_epoch(&storage),
+        HashValue::sha3_256_of(&bytes),
+        &config,
+    ))
+}
+
+/// Fetches the VM config of the state, only deserializing it when it differs from the active
+/// one. The active one is left as is, e.g. by the VMs simulating transactions on past versions.
+pub(crate) fn fetch_vm_config(data_cache: &impl ConfigStorage) -> Option<VMConfig> {
+    let bytes = fetch_vm_config_bytes(data_cache)?;
+    let hash = HashValue::sha3_256_of(&bytes);
+    if let Some(active) = ACTIVE_VM_CONFIG.lock().as_ref() {
+        if active.info.hash == hash {
+            return Some(active.config.clone());
+        }
+    }
+    VMConfig::deserialize_into_config(&bytes).ok()
+}
+
+/// Makes the VM config of the state the active one, unless it already is. Only the block
+/// executor activates the VM config, of the state it executes a block on.
+pub(crate) fn activate_vm_config(data_cache: &impl ConfigStorage) {
+    let bytes = match fetch_vm_config_bytes(data_cache) {
+        Some(bytes) => bytes,
+        None => return,
+    };
+    let hash = HashValue::sha3_256_of(&bytes);
+    let mut active = ACTIVE_VM_CONFIG.lock();
+    if active
+        .as_ref()
+        .map_or(false, |active| active.info.hash == hash)
+    {
+        return;
+    }
+    let config = match VMConfig::deserialize_into_config(&bytes) {
+        Ok(config) => config,
+        Err(_) => return,
+    };
+    let epoch = fetch_epoch(data_cache);
+    info!(
+        epoch = epoch,
+        hash = hash,
+        "Loaded the gas schedule of the on-chain VM config"
+    );
+    GAS_SCHEDULE_RELOADS.inc();
+    *active = Some(ActiveVMConfig {
+        info: gas_schedule_info(epoch, hash, &config),
+        config,
+    });
+}
+
+fn gas_schedule_info(epoch: u64, hash: HashValue, config: &VMConfig) -> GasScheduleInfo {
+    GasScheduleInfo {
+        epoch,
+        hash,
+        instructions: config.gas_schedule.instruction_table.len(),
+        natives: config.gas_schedule.native_table.len(),
+        gas_constants: config.gas_schedule.gas_constants.clone(),
+    }
+}
+
+fn fetch_vm_config_bytes(data_cache: &impl ConfigStorage) -> Option<Vec<u8>> {
+    data_cache
+        .fetch_config(access_path_for_config(VMConfig::CONFIG_ID))
+        .or_else(|| data_cache.fetch_config(dpn_access_path_for_config(VMConfig::CONFIG_ID)))
+}
+
+/// Returns the epoch of the state, 0 before genesis
+fn fetch_epoch(data_cache: &impl ConfigStorage) -> u64 {
+    data_cache
+        .fetch_config(AccessPath::new(
+            config_address(),
+            ConfigurationResource::resource_path(),
+        ))
+        .and_then(|bytes| bcs::from_bytes::<ConfigurationResource>(&bytes).ok())
+        .map_or(0, |configuration| configuration.epoch())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{activate_vm_config, active_gas_schedule, fetch_vm_config};
+    use aptos_types::{
+        access_path::AccessPath,
+        on_chain_config::{access_path_for_config, ConfigStorage, OnChainConfig, VMConfig},
+    };
+    use move_core_types::gas_schedule::{GasConstants, GasCost};
+    use std::collections::HashMap;
+
+    struct FakeConfigStorage(HashMap<AccessPath, Vec<u8>>);
+
+    impl ConfigStorage for FakeConfigStorage {
+        fn fetch_config(&self, access_path: AccessPath) -> Option<Vec<u8>> {
+            self.0.get(&access_path).cloned()
+        }
+    }
+
+    /// A state whose gas schedule has the given number of natives
+    fn state_with_natives(natives: usize) -> FakeConfigStorage {
+        let instruction_table: Vec<GasCost> = vec![];
+        let native_table = vec![GasCost::new(1, 1); natives];
+        // the BCS layout of the on-chain VM config
+        let bytes = bcs::to_bytes(&(
+            bcs::to_bytes(&instruction_table).unwrap(),
+            bcs::to_bytes(&native_table).unwrap(),
+            GasConstants::default(),
+        ))
+        .unwrap();
+        let mut configs = HashMap::new();
+        configs.insert(access_path_for_config(VMConfig::CONFIG_ID), bytes);
+        FakeConfigStorage(configs)
+    }
+
+    fn natives(config: Option<VMConfig>) -> usize {
+        config.unwrap().gas_schedule.native_table.len()
+    }
+
+    #[test]
+    fn test_vm_config_activated_by_block_executor_only() {
+        let (old, new) = (state_with_natives(1), state_with_natives(2));
+        activate_vm_config(&old);
+        assert_eq!(active_gas_schedule().unwrap().natives, 1);
+
+        // e.g. a transaction simulated on another version
+        assert_eq!(natives(fetch_vm_config(&new)), 2);
+        assert_eq!(active_gas_schedule().unwrap().natives, 1);
+        assert_eq!(natives(fetch_vm_config(&old)), 1);
+
+        activate_vm_config(&new);
+        let active = active_gas_schedule().unwrap();
+        assert_eq!(active.natives, 2);
+        assert_eq!(natives(fetch_vm_config(&new)), 2);
+        // the VM config of an older state is activated as well, e.g. when replaying the blocks
+        activate_vm_config(&old);
+        assert_eq!(active_gas_schedule().unwrap().natives, 1);
+        assert_ne!(active_gas_schedule().unwrap().hash, active.hash);
+    }
+}
@@ -114,14 +114,20 @@ mod adapter_common;
 pub mod aptos_vm;
 mod aptos_vm_impl;
 mod errors;
+pub mod gas_schedule;
 pub mod logging;
+mod module_cache;
 pub mod move_vm_ext;
+pub mod native_tracing;
 pub mod natives;
 pub mod parallel_executor;
+pub mod profiling;
+mod randomness;
 pub mod read_write_set_analysis;
 pub mod script_to_script_function;
 pub mod system_module_names;
 pub mod transaction_metadata;
+pub mod view_function;
 
 pub use crate::{aptos_vm::AptosVM, aptos_vm_impl::convert_changeset_and_events};
 
@@ -158,6 +164,16 @@ pub trait VMExecutor: Send + Sync {
         transactions: Vec<Transaction>,
         state_view: &impl StateView,
     ) -> Result<Vec<TransactionOutput>, VMStatus>;
+
+    /// Executes a block of transactions with the parallel executor regardless of the on-chain
+    /// config, e.g. to replay the transactions already committed to the ledger. Falls back on
+    /// `execute_block` for the VMs without a parallel executor.
+    fn execute_block_in_parallel(
+        transactions: Vec<Transaction>,
+        state_view: &impl StateView,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block(transactions, state_view)
+    }
 }
 
 /// Get the AccessPath to a resource stored under `address` with type name `tag`
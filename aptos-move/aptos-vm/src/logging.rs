@@ -32,7 +32,7 @@ pub struct AdapterLogSchema {
 impl AdapterLogSchema {
     pub fn new(view_id: StateViewId, txn_id: usize) -> Self {
         match view_id {
-            StateViewId::BlockExecution { block_id } => Self {
+            StateViewId::BlockExecution { block_id, .. } => Self {
                 name: LogEntry::Execution,
                 block_id: Some(block_id),
                 first_version: None,
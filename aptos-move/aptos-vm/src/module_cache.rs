@@ -0,0 +1,300 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A cache of the Move VM shared across the blocks. The loader of the Move VM keeps the modules it
+//! deserialized and verified for its lifetime, so a block is executed with the Move VM of its
+//! parent block, and a chunk with the one of the previous chunk, as long as those published no
+//! module. The blocks on another fork, the ones after a module was published, and the ones which
+//! may publish modules get a fresh Move VM: the Move VM keeps executing the modules it loaded
+//! before they were republished.
+
+use crate::{counters::MODULE_CACHE_LOOKUPS, move_vm_ext::MoveVmExt};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_state_view::StateViewId;
+use aptos_types::{
+    access_path::Path,
+    state_store::state_key::StateKey,
+    transaction::{Transaction, TransactionOutput, TransactionPayload, TransactionStatus, Version},
+};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+static MODULE_CACHE: Lazy<ModuleCache> = Lazy::new(ModuleCache::default);
+
+/// The state a cached Move VM is valid for, the modules it loaded being unchanged in it
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum CachedState {
+    /// The state after the block with the id
+    Block(HashValue),
+    /// The state before the transaction of the version
+    Version(Version),
+}
+
+impl CachedState {
+    /// The state read by the view executing a block or a chunk, none for the other views
+    fn base_of(state_view_id: StateViewId) -> Option<Self> {
+        match state_view_id {
+            StateViewId::BlockExecution {
+                parent_block_id, ..
+            } => Some(Self::Block(parent_block_id)),
+            StateViewId::ChunkExecution { first_version } => Some(Self::Version(first_version)),
+            _ => None,
+        }
+    }
+
+    /// The state resulting from the execution of the block or the chunk read by the view
+    fn result_of(state_view_id: StateViewId, num_committed: usize) -> Option<Self> {
+        match state_view_id {
+            StateViewId::BlockExecution { block_id, .. } => Some(Self::Block(block_id)),
+            StateViewId::ChunkExecution { first_version } => {
+                Some(Self::Version(first_version + num_committed as Version))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ModuleCache {
+    cached: Mutex<Option<(CachedState, Arc<MoveVmExt>)>>,
+}
+
+impl ModuleCache {
+    pub(crate) fn global() -> &'static Self {
+        &MODULE_CACHE
+    }
+
+    /// Takes the Move VM to execute the transactions of the block or the chunk read by the view
+    /// with out of the cache, or creates a new one if it isn't valid for the state read or the
+    /// transactions may publish modules. It's only put back into the cache with `checkin`, the
+    /// blocks executed concurrently get their own Move VM.
+    pub(crate) fn checkout(
+        &self,
+        state_view_id: StateViewId,
+        transactions: &[Transaction],
+    ) -> Arc<MoveVmExt> {
+        // The other views, e.g. of the simulations, don't take the Move VM of the blocks.
+        let base = match CachedState::base_of(state_view_id) {
+            Some(base) => base,
+            None => return new_move_vm(),
+        };
+        let cached = self.cached.lock().take();
+        let (move_vm, result) = match cached {
+            _ if transactions.iter().any(may_publish_modules) => (new_move_vm(), "publishing"),
+            Some((state, move_vm)) if state == base => (move_vm, "hit"),
+            Some(_) => (new_move_vm(), "stale"),
+            None => (new_move_vm(), "empty"),
+        };
+        MODULE_CACHE_LOOKUPS.with_label_values(&[result]).inc();
+        move_vm
+    }
+
+    /// Puts the Move VM back into the cache once the block or the chunk is executed, unless its
+    /// write set publishes modules: the Move VM may have loaded the modules published by the block
+    /// before they were published.
+    pub(crate) fn checkin<'a>(
+        &self,
+        move_vm: Arc<MoveVmExt>,
+        state_view_id: StateViewId,
+        outputs: impl IntoIterator<Item = &'a TransactionOutput>,
+    ) {
+        let mut num_committed = 0;
+        for output in outputs {
+            if let TransactionStatus::Retry = output.status() {
+                continue;
+            }
+            num_committed += 1;
+            if output
+                .write_set()
+                .iter()
+                .any(|(state_key, _)| is_module(state_key))
+            {
+                return;
+            }
+        }
+        if let Some(state) = CachedState::result_of(state_view_id, num_committed) {
+            *self.cached.lock() = Some((state, move_vm));
+        }
+    }
+}
+
+fn new_move_vm() -> Arc<MoveVmExt> {
+    Arc::new(
+        MoveVmExt::new()
+            .expect("should be able to create Move VM; check if there are duplicated natives"),
+    )
+}
+
+/// Whether the transaction may publish modules, when executed
+fn may_publish_modules(transaction: &Transaction) -> bool {
+    match transaction {
+        Transaction::UserTransaction(txn) => matches!(
+            txn.payload(),
+            TransactionPayload::ModuleBundle(_) | TransactionPayload::WriteSet(_)
+        ),
+        Transaction::GenesisTransaction(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether a module is stored under `state_key`
+fn is_module(state_key: &StateKey) -> bool {
+    match state_key {
+        StateKey::AccessPath(access_path) => {
+            matches!(bcs::from_bytes(&access_path.path), Ok(Path::Code(_)))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ModuleCache;
+    use aptos_crypto::HashValue;
+    use aptos_state_view::StateViewId;
+    use aptos_types::{
+        access_path::AccessPath,
+        account_address::AccountAddress,
+        state_store::state_key::StateKey,
+        transaction::{
+            ChangeSet, Transaction, TransactionOutput, TransactionStatus, WriteSetPayload,
+        },
+        vm_status::KeptVMStatus,
+        write_set::{WriteOp, WriteSet, WriteSetMut},
+    };
+    use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+    use std::sync::Arc;
+
+    fn block(block_id: HashValue, parent_block_id: HashValue) -> StateViewId {
+        StateViewId::BlockExecution {
+            block_id,
+            parent_block_id,
+        }
+    }
+
+    fn output(publishes_module: bool) -> TransactionOutput {
+        let mut write_set = WriteSetMut::new(vec![]);
+        if publishes_module {
+            let module_id = ModuleId::new(AccountAddress::ONE, Identifier::new("M").unwrap());
+            write_set.push((
+                StateKey::AccessPath(AccessPath::from(&module_id)),
+                WriteOp::Value(vec![]),
+            ));
+        }
+        TransactionOutput::new(
+            write_set.freeze().unwrap(),
+            vec![],
+            0,
+            TransactionStatus::Keep(KeptVMStatus::Executed),
+        )
+    }
+
+    #[test]
+    fn test_reused_by_child_block() {
+        let cache = ModuleCache::default();
+        let (parent, block_id, child) = (
+            HashValue::random(),
+            HashValue::random(),
+            HashValue::random(),
+        );
+        let move_vm = cache.checkout(block(block_id, parent), &[]);
+        cache.checkin(move_vm.clone(), block(block_id, parent), &[output(false)]);
+
+        assert!(Arc::ptr_eq(
+            &cache.checkout(block(child, block_id), &[]),
+            &move_vm
+        ));
+    }
+
+    #[test]
+    fn test_not_reused_on_fork() {
+        let cache = ModuleCache::default();
+        let (parent, block_id, sibling) = (
+            HashValue::random(),
+            HashValue::random(),
+            HashValue::random(),
+        );
+        let move_vm = cache.checkout(block(block_id, parent), &[]);
+        cache.checkin(move_vm.clone(), block(block_id, parent), &[output(false)]);
+
+        assert!(!Arc::ptr_eq(
+            &cache.checkout(block(sibling, parent), &[]),
+            &move_vm
+        ));
+        // the stale Move VM is dropped
+        assert!(cache.cached.lock().is_none());
+    }
+
+    #[test]
+    fn test_dropped_after_publishing() {
+        let cache = ModuleCache::default();
+        let (parent, block_id) = (HashValue::random(), HashValue::random());
+        let move_vm = cache.checkout(block(block_id, parent), &[]);
+        cache.checkin(
+            move_vm,
+            block(block_id, parent),
+            &[output(false), output(true)],
+        );
+
+        assert!(cache.cached.lock().is_none());
+    }
+
+    #[test]
+    fn test_not_reused_by_publishing_block() {
+        let cache = ModuleCache::default();
+        let (parent, block_id, child) = (
+            HashValue::random(),
+            HashValue::random(),
+            HashValue::random(),
+        );
+        let move_vm = cache.checkout(block(block_id, parent), &[]);
+        cache.checkin(move_vm.clone(), block(block_id, parent), &[output(false)]);
+
+        let write_set_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(
+            ChangeSet::new(WriteSet::default(), vec![]),
+        ));
+        assert!(!Arc::ptr_eq(
+            &cache.checkout(block(child, block_id), &[write_set_txn]),
+            &move_vm
+        ));
+    }
+
+    #[test]
+    fn test_reused_by_next_chunk() {
+        let cache = ModuleCache::default();
+        let chunk = StateViewId::ChunkExecution { first_version: 10 };
+        let move_vm = cache.checkout(chunk, &[]);
+        let retried = TransactionOutput::new(
+            WriteSetMut::new(vec![]).freeze().unwrap(),
+            vec![],
+            0,
+            TransactionStatus::Retry,
+        );
+        cache.checkin(
+            move_vm.clone(),
+            chunk,
+            &[output(false), output(false), retried],
+        );
+
+        // the retried transactions are executed again by the next chunk
+        let next_chunk = StateViewId::ChunkExecution { first_version: 12 };
+        assert!(Arc::ptr_eq(&cache.checkout(next_chunk, &[]), &move_vm));
+    }
+
+    #[test]
+    fn test_other_views_bypass_cache() {
+        let cache = ModuleCache::default();
+        let (parent, block_id) = (HashValue::random(), HashValue::random());
+        let move_vm = cache.checkout(block(block_id, parent), &[]);
+        cache.checkin(move_vm.clone(), block(block_id, parent), &[output(false)]);
+
+        let other = cache.checkout(StateViewId::Miscellaneous, &[]);
+        assert!(!Arc::ptr_eq(&other, &move_vm));
+        cache.checkin(other, StateViewId::Miscellaneous, &[]);
+        assert!(Arc::ptr_eq(
+            &cache.checkout(block(HashValue::random(), block_id), &[]),
+            &move_vm
+        ));
+    }
+}
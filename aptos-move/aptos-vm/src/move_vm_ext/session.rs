@@ -9,7 +9,7 @@ use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
 use aptos_types::{
     block_metadata::BlockMetadata,
-    transaction::{ChangeSet, SignatureCheckedTransaction},
+    transaction::{ChangeSet, SignedTransaction},
 };
 use move_binary_format::errors::VMResult;
 use move_core_types::{
@@ -44,7 +44,7 @@ pub enum SessionId {
 }
 
 impl SessionId {
-    pub fn txn(txn: &SignatureCheckedTransaction) -> Self {
+    pub fn txn(txn: &SignedTransaction) -> Self {
         Self::Txn {
             sender: txn.sender(),
             sequence_number: txn.sequence_number(),
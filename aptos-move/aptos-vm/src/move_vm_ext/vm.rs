@@ -5,6 +5,8 @@ use crate::{
     move_vm_ext::{SessionExt, SessionId},
     natives::aptos_natives,
 };
+use aptos_crypto::hash::CryptoHash;
+use framework::natives::randomness::NativeRandomnessContext;
 use move_binary_format::errors::VMResult;
 use move_core_types::resolver::MoveResolver;
 use move_vm_runtime::{move_vm::MoveVM, native_extensions::NativeContextExtensions};
@@ -24,10 +26,24 @@ impl MoveVmExt {
     pub fn new_session<'r, S: MoveResolver>(
         &self,
         remote: &'r S,
-        _session_id: SessionId,
+        session_id: SessionId,
+    ) -> SessionExt<'r, '_, S> {
+        self.new_session_with_draws(remote, session_id, false)
+    }
+
+    /// Same as `new_session`, the randomness being drawable in the session if `draws_allowed`.
+    pub fn new_session_with_draws<'r, S: MoveResolver>(
+        &self,
+        remote: &'r S,
+        session_id: SessionId,
+        draws_allowed: bool,
     ) -> SessionExt<'r, '_, S> {
         // TODO: install table extension
-        let extensions = NativeContextExtensions::<'r>::default();
+        let mut extensions = NativeContextExtensions::<'r>::default();
+        extensions.add(NativeRandomnessContext::new(
+            session_id.hash(),
+            draws_allowed,
+        ));
 
         SessionExt::new(self.inner.new_session_with_extensions(remote, extensions))
     }
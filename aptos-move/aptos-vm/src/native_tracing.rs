@@ -0,0 +1,212 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tracing of the native functions called by the user transactions. Once enabled, the natives of
+//! the Move VMs created afterwards are wrapped to record the size of the arguments, the gas charged
+//! and the wall time of each call, and the VM keeps the calls of the last user transactions it
+//! executed in a ring buffer, to debug the gas anomalies of the framework natives.
+//!
+//! The natives are plain function pointers, so each of them is wrapped by a distinct function
+//! reading the native it wraps from a slot of `TRACED_NATIVES`. Enabling the tracing panics when
+//! the first Move VM is created if the table has more natives than there are wrappers.
+
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_types::{account_address::AccountAddress, transaction::SignatureCheckedTransaction};
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::{gas_schedule::GasAlgebra, identifier::Identifier};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction, NativeFunctionTable};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, values::Value,
+};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Instant,
+};
+
+static TRACER: Lazy<NativeTracer> = Lazy::new(NativeTracer::default);
+
+/// The name and the function of the native wrapped by each wrapper, in the order of the table
+static TRACED_NATIVES: OnceCell<Vec<(String, NativeFunction)>> = OnceCell::new();
+
+thread_local! {
+    /// The native calls of the user transaction executed by the thread, if it's traced
+    static TRANSACTION_CALLS: RefCell<Option<Vec<NativeCall>>> = RefCell::new(None);
+}
+
+/// A call of a native function
+#[derive(Clone, Debug, Serialize)]
+pub struct NativeCall {
+    /// The native function called, e.g. `0x1::Hash::sha3_256`
+    pub function: String,
+    /// The size of each argument, in the abstract memory units the gas is charged for
+    pub arg_sizes: Vec<u64>,
+    pub gas_charged: u64,
+    pub duration_us: u64,
+}
+
+/// The native calls of a user transaction, in the order they were made
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionNativeTrace {
+    pub hash: HashValue,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub calls: Vec<NativeCall>,
+}
+
+#[derive(Default)]
+struct NativeTracer {
+    enabled: AtomicBool,
+    max_traces: AtomicUsize,
+    traces: Mutex<VecDeque<TransactionNativeTrace>>,
+}
+
+/// Enables the tracing of the native calls of the user transactions, keeping the traces of the
+/// last `max_traces` of them. It must be enabled before the Move VMs are created, e.g. at startup.
+pub fn enable_native_tracing(max_traces: usize) {
+    TRACER.max_traces.store(max_traces, Ordering::Relaxed);
+    TRACER.enabled.store(true, Ordering::Relaxed);
+}
+
+pub fn is_native_tracing_enabled() -> bool {
+    TRACER.enabled.load(Ordering::Relaxed)
+}
+
+/// Returns the native traces of the last executed user transactions, in the order they were
+/// executed. A transaction executed again by the parallel executor is traced for each execution.
+pub fn native_traces() -> Vec<TransactionNativeTrace> {
+    TRACER.traces.lock().iter().cloned().collect()
+}
+
+/// Wraps the natives of the table to trace their calls, if the tracing is enabled
+pub(crate) fn trace_natives(natives: NativeFunctionTable) -> NativeFunctionTable {
+    if !is_native_tracing_enabled() {
+        return natives;
+    }
+    assert!(
+        natives.len() <= NATIVE_WRAPPERS.len(),
+        "Can't trace {} natives with {} wrappers, add wrappers to native_wrappers!",
+        natives.len(),
+        NATIVE_WRAPPERS.len()
+    );
+    let traced_natives = TRACED_NATIVES.get_or_init(|| {
+        natives
+            .iter()
+            .map(|(address, module_name, function_name, function)| {
+                (native_name(address, module_name, function_name), *function)
+            })
+            .collect()
+    });
+    natives
+        .into_iter()
+        .enumerate()
+        .map(|(index, (address, module_name, function_name, function))| {
+            // The natives are expected in the same order in each table.
+            let function = match traced_natives.get(index) {
+                Some((name, _)) if *name == native_name(&address, &module_name, &function_name) => {
+                    NATIVE_WRAPPERS[index]
+                }
+                _ => function,
+            };
+            (address, module_name, function_name, function)
+        })
+        .collect()
+}
+
+fn native_name(
+    address: &AccountAddress,
+    module_name: &Identifier,
+    function_name: &Identifier,
+) -> String {
+    format!(
+        "0x{}::{}::{}",
+        address.short_str_lossless(),
+        module_name,
+        function_name
+    )
+}
+
+/// Starts recording the native calls of the user transaction about to be executed by the thread
+pub(crate) fn start_transaction_trace() {
+    if is_native_tracing_enabled() {
+        TRANSACTION_CALLS.with(|calls| *calls.borrow_mut() = Some(vec![]));
+    }
+}
+
+/// Stops recording the native calls of the user transaction executed by the thread
+pub(crate) fn finish_transaction_trace(txn: &SignatureCheckedTransaction) {
+    let calls = match TRANSACTION_CALLS.with(|calls| calls.borrow_mut().take()) {
+        Some(calls) => calls,
+        None => return,
+    };
+    let trace = TransactionNativeTrace {
+        hash: txn.clone().into_inner().committed_hash(),
+        sender: txn.sender(),
+        sequence_number: txn.sequence_number(),
+        calls,
+    };
+
+    let max_traces = TRACER.max_traces.load(Ordering::Relaxed);
+    let mut traces = TRACER.traces.lock();
+    traces.push_back(trace);
+    while traces.len() > max_traces {
+        traces.pop_front();
+    }
+}
+
+fn call_traced_native(
+    index: usize,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    arguments: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    let (name, function) = &TRACED_NATIVES
+        .get()
+        .expect("natives must be traced before being called")[index];
+    let arg_sizes = arguments
+        .iter()
+        .map(|argument| argument.size().get())
+        .collect();
+    let start_time = Instant::now();
+    let result = function(context, ty_args, arguments);
+    let duration_us = start_time.elapsed().as_micros() as u64;
+
+    if let Ok(native_result) = &result {
+        TRANSACTION_CALLS.with(|calls| {
+            if let Some(calls) = calls.borrow_mut().as_mut() {
+                calls.push(NativeCall {
+                    function: name.clone(),
+                    arg_sizes,
+                    gas_charged: native_result.cost.get(),
+                    duration_us,
+                });
+            }
+        });
+    }
+    result
+}
+
+macro_rules! native_wrappers {
+    ($($index:literal),*) => {
+        const NATIVE_WRAPPERS: &[NativeFunction] = &[$({
+            fn wrapper(
+                context: &mut NativeContext,
+                ty_args: Vec<Type>,
+                arguments: VecDeque<Value>,
+            ) -> PartialVMResult<NativeResult> {
+                call_traced_native($index, context, ty_args, arguments)
+            }
+            wrapper
+        }),*];
+    };
+}
+
+native_wrappers!(
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49,
+    50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63
+);
@@ -7,8 +7,13 @@ mod vm_wrapper;
 use crate::{
     adapter_common::{preprocess_transaction, PreprocessedTransaction},
     aptos_vm::AptosVM,
+    counters::PARALLEL_EXECUTION_FALLBACKS,
+    module_cache::ModuleCache,
     parallel_executor::vm_wrapper::AptosVMWrapper,
+    profiling::{self, TransactionMeasurement},
 };
+use aptos_crypto::hash::CryptoHash;
+use aptos_logger::prelude::*;
 use aptos_parallel_executor::{
     errors::Error,
     executor::ParallelTransactionExecutor,
@@ -21,22 +26,27 @@ use aptos_types::{
     write_set::{WriteOp, WriteSet},
 };
 use move_core_types::vm_status::{StatusCode, VMStatus};
+use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 
+pub use aptos_parallel_executor::executor::ParallelExecutorConfig;
+
+static PARALLEL_EXECUTOR_CONFIG: OnceCell<ParallelExecutorConfig> = OnceCell::new();
+
 impl PTransaction for PreprocessedTransaction {
     type Key = StateKey;
     type Value = WriteOp;
 }
 
-// Wrapper to avoid orphan rule
-pub(crate) struct AptosTransactionOutput(TransactionOutput);
+// Wrapper to avoid orphan rule, along with the measurement of the execution giving the output
+pub(crate) struct AptosTransactionOutput(TransactionOutput, Option<TransactionMeasurement>);
 
 impl AptosTransactionOutput {
-    pub fn new(output: TransactionOutput) -> Self {
-        Self(output)
+    pub fn new(output: TransactionOutput, measurement: Option<TransactionMeasurement>) -> Self {
+        Self(output, measurement)
     }
-    pub fn into(self) -> TransactionOutput {
-        self.0
+    pub fn into_inner(self) -> (TransactionOutput, Option<TransactionMeasurement>) {
+        (self.0, self.1)
     }
 }
 
@@ -49,21 +59,37 @@ impl PTransactionOutput for AptosTransactionOutput {
 
     /// Execution output for transactions that comes after SkipRest signal.
     fn skip_output() -> Self {
-        Self(TransactionOutput::new(
-            WriteSet::default(),
-            vec![],
-            0,
-            TransactionStatus::Retry,
-        ))
+        Self(
+            TransactionOutput::new(WriteSet::default(), vec![], 0, TransactionStatus::Retry),
+            None,
+        )
     }
 }
 
 pub struct ParallelAptosVM();
 
 impl ParallelAptosVM {
+    /// Sets the tuning of the parallel execution of the blocks, instead of the default one using
+    /// all the CPUs. Only the first call has an effect.
+    pub fn set_config(config: ParallelExecutorConfig) {
+        if PARALLEL_EXECUTOR_CONFIG.set(config).is_err() {
+            warn!("The parallel executor config is already set");
+        }
+    }
+
     pub fn execute_block<S: StateView>(
         transactions: Vec<Transaction>,
         state_view: &S,
+    ) -> Result<(Vec<TransactionOutput>, Option<Error<VMStatus>>), VMStatus> {
+        let config = PARALLEL_EXECUTOR_CONFIG.get().copied().unwrap_or_default();
+        Self::execute_block_with_config(transactions, state_view, config)
+    }
+
+    /// Executes the block with the tuning given, rather than the one set with `set_config`.
+    pub fn execute_block_with_config<S: StateView>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        config: ParallelExecutorConfig,
     ) -> Result<(Vec<TransactionOutput>, Option<Error<VMStatus>>), VMStatus> {
         // Verify the signatures of all the transactions in parallel.
         // This is time consuming so don't wait and do the checking
@@ -73,17 +99,43 @@ impl ParallelAptosVM {
             .map(|txn| preprocess_transaction::<AptosVM>(txn.clone()))
             .collect();
 
-        match ParallelTransactionExecutor::<PreprocessedTransaction, AptosVMWrapper<S>>::new()
-            .execute_transactions_parallel(state_view, signature_verified_block)
-        {
-            Ok(results) => Ok((
-                results
+        let module_cache = ModuleCache::global();
+        let move_vm = module_cache.checkout(state_view.id(), &transactions);
+        let executor = ParallelTransactionExecutor::<
+            PreprocessedTransaction,
+            AptosVMWrapper<S>,
+        >::new_with_config(config);
+        let result = executor
+            .execute_transactions_parallel((state_view, &move_vm), signature_verified_block);
+
+        // The Move VM isn't put back into the cache when falling back to the sequential
+        // execution, it may have loaded the modules published by the aborted executions. The
+        // sequential execution gets a fresh one.
+        match result {
+            Ok(results) => {
+                let (outputs, measurements): (Vec<_>, Vec<_>) = results
                     .into_iter()
-                    .map(AptosTransactionOutput::into)
-                    .collect(),
-                None,
-            )),
-            Err(err @ Error::InferencerError) | Err(err @ Error::UnestimatedWrite) => {
+                    .map(AptosTransactionOutput::into_inner)
+                    .unzip();
+                // Only the executions giving the outputs are profiled, not the speculative ones
+                if profiling::is_profiling_enabled() {
+                    let hashes: Vec<_> = transactions.par_iter().map(CryptoHash::hash).collect();
+                    profiling::record_block_profiles(&hashes, outputs.iter().zip(measurements));
+                }
+                module_cache.checkin(move_vm, state_view.id(), &outputs);
+                Ok((outputs, None))
+            }
+            Err(err @ Error::InferencerError)
+            | Err(err @ Error::UnestimatedWrite)
+            | Err(err @ Error::TooManyAborts) => {
+                let reason = match err {
+                    Error::InferencerError => "inferencer_error",
+                    Error::UnestimatedWrite => "unestimated_write",
+                    _ => "too_many_aborts",
+                };
+                PARALLEL_EXECUTION_FALLBACKS
+                    .with_label_values(&[reason])
+                    .inc();
                 let output = AptosVM::execute_block_and_keep_vm_status(transactions, state_view)?;
                 Ok((
                     output
@@ -6,7 +6,9 @@ use crate::{
     aptos_vm::AptosVM,
     data_cache::RemoteStorage,
     logging::AdapterLogSchema,
+    move_vm_ext::MoveVmExt,
     parallel_executor::{storage_wrapper::VersionedView, AptosTransactionOutput},
+    profiling,
 };
 use aptos_logger::prelude::*;
 use aptos_parallel_executor::{
@@ -20,6 +22,7 @@ use move_core_types::{
     language_storage::{ModuleId, CORE_CODE_ADDRESS},
     vm_status::VMStatus,
 };
+use std::sync::Arc;
 
 pub(crate) struct AptosVMWrapper<'a, S> {
     vm: AptosVM,
@@ -30,10 +33,10 @@ impl<'a, S: 'a + StateView> ExecutorTask for AptosVMWrapper<'a, S> {
     type T = PreprocessedTransaction;
     type Output = AptosTransactionOutput;
     type Error = VMStatus;
-    type Argument = &'a S;
+    type Argument = (&'a S, &'a Arc<MoveVmExt>);
 
-    fn init(argument: &'a S) -> Self {
-        let vm = AptosVM::new(argument);
+    fn init((argument, move_vm): (&'a S, &'a Arc<MoveVmExt>)) -> Self {
+        let vm = AptosVM::new_with_move_vm(move_vm.clone(), argument);
 
         // Loading `0x1::Account` and its transitive dependency into the code cache.
         //
@@ -62,10 +65,9 @@ impl<'a, S: 'a + StateView> ExecutorTask for AptosVMWrapper<'a, S> {
         let log_context = AdapterLogSchema::new(self.base_view.id(), view.txn_idx());
         let versioned_view = VersionedView::new_view(self.base_view, view);
 
-        match self
-            .vm
-            .execute_single_transaction(txn, &versioned_view, &log_context)
-        {
+        let (execution, measurement) =
+            profiling::execute_measured(&self.vm, txn, &versioned_view, &log_context);
+        match execution {
             Ok((vm_status, output, sender)) => {
                 if output.status().is_discarded() {
                     match sender {
@@ -81,9 +83,9 @@ impl<'a, S: 'a + StateView> ExecutorTask for AptosVMWrapper<'a, S> {
                     };
                 }
                 if AptosVM::should_restart_execution(&output) {
-                    ExecutionStatus::SkipRest(AptosTransactionOutput::new(output))
+                    ExecutionStatus::SkipRest(AptosTransactionOutput::new(output, measurement))
                 } else {
-                    ExecutionStatus::Success(AptosTransactionOutput::new(output))
+                    ExecutionStatus::Success(AptosTransactionOutput::new(output, measurement))
                 }
             }
             Err(err) => ExecutionStatus::Abort(err),
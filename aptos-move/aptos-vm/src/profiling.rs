@@ -0,0 +1,199 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Profiling of the execution of the user transactions. Once enabled, the VM records the wall
+//! time, the gas used and the number of state reads and writes of each user transaction kept by
+//! the blocks it executes, keeping the last of them in a ring buffer, so the operators can spot
+//! the contracts that are pathologically expensive to execute.
+
+use crate::{
+    adapter_common::{PreprocessedTransaction, VMAdapter},
+    counters::{TXN_STATE_READS, TXN_STATE_WRITES},
+    data_cache::AsMoveResolver,
+    logging::AdapterLogSchema,
+};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_state_view::{StateView, StateViewId};
+use aptos_types::{
+    account_address::AccountAddress,
+    state_store::state_key::StateKey,
+    transaction::{TransactionOutput, TransactionPayload, TransactionStatus},
+};
+use move_core_types::{resolver::MoveResolver, vm_status::VMStatus};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+static PROFILER: Lazy<TransactionProfiler> = Lazy::new(TransactionProfiler::default);
+
+/// The profile of the execution of a user transaction
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionProfile {
+    pub hash: HashValue,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    /// The script function called, e.g. `0x1::Coin::transfer`, if any
+    pub script_function: Option<String>,
+    pub wall_time_us: u64,
+    pub gas_used: u64,
+    pub state_reads: u64,
+    pub state_writes: u64,
+}
+
+#[derive(Default)]
+struct TransactionProfiler {
+    enabled: AtomicBool,
+    max_profiles: AtomicUsize,
+    profiles: Mutex<VecDeque<TransactionProfile>>,
+}
+
+/// Enables the profiling of the user transactions, keeping the profiles of the last
+/// `max_profiles` of them.
+pub fn enable_profiling(max_profiles: usize) {
+    PROFILER.max_profiles.store(max_profiles, Ordering::Relaxed);
+    PROFILER.enabled.store(true, Ordering::Relaxed);
+}
+
+pub fn is_profiling_enabled() -> bool {
+    PROFILER.enabled.load(Ordering::Relaxed)
+}
+
+/// Returns the profiles of the last executed user transactions, in the order they were executed
+pub fn transaction_profiles() -> Vec<TransactionProfile> {
+    PROFILER.profiles.lock().iter().cloned().collect()
+}
+
+/// The measurement of an execution of a user transaction. The parallel execution may execute a
+/// transaction several times, only the measurement of the execution giving its output is kept.
+#[derive(Clone, Debug)]
+pub(crate) struct TransactionMeasurement {
+    sender: AccountAddress,
+    sequence_number: u64,
+    script_function: Option<String>,
+    wall_time: Duration,
+    state_reads: u64,
+}
+
+/// Executes the transaction, measuring its execution if it's a user transaction and the
+/// profiling is enabled.
+pub(crate) fn execute_measured<A: VMAdapter, S: MoveResolver + StateView>(
+    adapter: &A,
+    txn: &PreprocessedTransaction,
+    data_cache: &S,
+    log_context: &AdapterLogSchema,
+) -> (
+    Result<(VMStatus, TransactionOutput, Option<String>), VMStatus>,
+    Option<TransactionMeasurement>,
+) {
+    let user_txn = match txn {
+        PreprocessedTransaction::UserTransaction(user_txn) if is_profiling_enabled() => user_txn,
+        _ => {
+            return (
+                adapter.execute_single_transaction(txn, data_cache, log_context),
+                None,
+            )
+        }
+    };
+    let start_time = Instant::now();
+    let counting_view = CountingStateView::new(data_cache);
+    let result =
+        adapter.execute_single_transaction(txn, &counting_view.as_move_resolver(), log_context);
+    let script_function = match user_txn.payload() {
+        TransactionPayload::ScriptFunction(script_function) => Some(format!(
+            "{}::{}",
+            script_function.module(),
+            script_function.function()
+        )),
+        _ => None,
+    };
+    let measurement = TransactionMeasurement {
+        sender: user_txn.sender(),
+        sequence_number: user_txn.sequence_number(),
+        script_function,
+        wall_time: start_time.elapsed(),
+        state_reads: counting_view.reads(),
+    };
+    (result, Some(measurement))
+}
+
+/// Records the profiles of the user transactions kept by a block once its outputs are final, the
+/// ones discarded or to be retried aren't committed. The hashes are the ones of the transactions
+/// of the block, in order.
+pub(crate) fn record_block_profiles<'a>(
+    hashes: &[HashValue],
+    outputs: impl Iterator<Item = (&'a TransactionOutput, Option<TransactionMeasurement>)>,
+) {
+    let new_profiles: Vec<_> = hashes
+        .iter()
+        .zip(outputs)
+        .filter_map(|(hash, (output, measurement))| {
+            let measurement = measurement?;
+            if !matches!(output.status(), TransactionStatus::Keep(_)) {
+                return None;
+            }
+            let state_writes = output.write_set().iter().count() as u64;
+            TXN_STATE_READS.observe(measurement.state_reads as f64);
+            TXN_STATE_WRITES.observe(state_writes as f64);
+            Some(TransactionProfile {
+                hash: *hash,
+                sender: measurement.sender,
+                sequence_number: measurement.sequence_number,
+                script_function: measurement.script_function,
+                wall_time_us: measurement.wall_time.as_micros() as u64,
+                gas_used: output.gas_used(),
+                state_reads: measurement.state_reads,
+                state_writes,
+            })
+        })
+        .collect();
+    if new_profiles.is_empty() {
+        return;
+    }
+
+    let max_profiles = PROFILER.max_profiles.load(Ordering::Relaxed);
+    let mut profiles = PROFILER.profiles.lock();
+    profiles.extend(new_profiles);
+    while profiles.len() > max_profiles {
+        profiles.pop_front();
+    }
+}
+
+/// A state view counting the state values read from the underlying view
+pub(crate) struct CountingStateView<'a, S> {
+    inner: &'a S,
+    reads: AtomicU64,
+}
+
+impl<'a, S: StateView> CountingStateView<'a, S> {
+    pub(crate) fn new(inner: &'a S) -> Self {
+        Self {
+            inner,
+            reads: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn reads(&self) -> u64 {
+        self.reads.load(Ordering::Relaxed)
+    }
+}
+
+impl<'a, S: StateView> StateView for CountingStateView<'a, S> {
+    fn id(&self) -> StateViewId {
+        self.inner.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.inner.get_state_value(state_key)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.inner.is_genesis()
+    }
+}
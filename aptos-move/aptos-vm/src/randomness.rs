@@ -0,0 +1,139 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The checks keeping the transactions from dropping the values they draw from
+//! `InsecureRandomness` by aborting. The randomness is only drawn by the script function a
+//! transaction calls and by the private functions of its module, so the code of the module decides
+//! what's done with the values. The sender can still drop a draw by giving the transaction too
+//! little gas for the module's code to complete after it, which these checks can't prevent.
+
+use crate::{aptos_vm_impl::AptosVMImpl, system_module_names::RANDOMNESS_MODULE};
+use aptos_types::{
+    transaction::TransactionPayload,
+    vm_status::{StatusCode, VMStatus},
+};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, FunctionHandleIndex, Visibility},
+    CompiledModule,
+};
+use move_core_types::resolver::MoveResolver;
+use std::collections::HashMap;
+
+/// Rejects the module if one of its public or friend functions draws randomness, directly or
+/// through the other functions of the module, as the module calling it could abort after an
+/// unwanted draw.
+pub(crate) fn check_module_draws(module: &CompiledModule) -> Result<(), VMStatus> {
+    if module.self_id() == *RANDOMNESS_MODULE {
+        return Ok(());
+    }
+    let exposed = module
+        .function_defs()
+        .iter()
+        .zip(drawing_functions(module))
+        .any(|(def, draws)| {
+            draws && matches!(def.visibility, Visibility::Public | Visibility::Friend)
+        });
+    if exposed {
+        return Err(VMStatus::Error(StatusCode::CONSTRAINT_NOT_SATISFIED));
+    }
+    Ok(())
+}
+
+/// Returns whether each function defined by the module draws randomness, directly or through
+/// the other functions of the module.
+fn drawing_functions(module: &CompiledModule) -> Vec<bool> {
+    let def_indexes: HashMap<FunctionHandleIndex, usize> = module
+        .function_defs()
+        .iter()
+        .enumerate()
+        .map(|(index, def)| (def.function, index))
+        .collect();
+    let callees: Vec<Vec<FunctionHandleIndex>> = module
+        .function_defs()
+        .iter()
+        .map(|def| {
+            def.code.as_ref().map_or_else(Vec::new, |code| {
+                code.code
+                    .iter()
+                    .filter_map(|instruction| match instruction {
+                        Bytecode::Call(handle) => Some(*handle),
+                        Bytecode::CallGeneric(index) => {
+                            Some(module.function_instantiation_at(*index).handle)
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+        })
+        .collect();
+
+    let calls_randomness = |handle: &FunctionHandleIndex| {
+        let module_handle = module.module_handle_at(module.function_handle_at(*handle).module);
+        module.module_id_for_handle(module_handle) == *RANDOMNESS_MODULE
+    };
+    let mut draws: Vec<bool> = callees
+        .iter()
+        .map(|callees| callees.iter().any(calls_randomness))
+        .collect();
+    // the callers of the functions drawing randomness draw it too, till there's no new one
+    loop {
+        let mut changed = false;
+        for (index, callees) in callees.iter().enumerate() {
+            if !draws[index]
+                && callees.iter().any(|handle| {
+                    def_indexes
+                        .get(handle)
+                        .map_or(false, |callee| draws[*callee])
+                })
+            {
+                draws[index] = true;
+                changed = true;
+            }
+        }
+        if !changed {
+            return draws;
+        }
+    }
+}
+
+/// Returns true iff the transaction with the payload can draw randomness: it calls a script
+/// function of a module using `InsecureRandomness`, which calls no script function of another module,
+/// as the other module could draw randomness for the transaction to abort after an unwanted draw.
+pub(crate) fn draws_allowed<S: MoveResolver>(
+    vm: &AptosVMImpl,
+    payload: &TransactionPayload,
+    storage: &S,
+) -> bool {
+    let module_id = match payload {
+        TransactionPayload::ScriptFunction(script_function) => script_function.module(),
+        _ => return false,
+    };
+    let module = match vm.load_module(module_id, storage) {
+        Ok(module) => module,
+        Err(_) => return false,
+    };
+    let uses_randomness = module
+        .module_handles()
+        .iter()
+        .any(|handle| module.module_id_for_handle(handle) == *RANDOMNESS_MODULE);
+    if !uses_randomness {
+        return false;
+    }
+    module.function_handles().iter().all(|handle| {
+        if handle.module == module.self_handle_idx() {
+            return true;
+        }
+        let callee_id = module.module_id_for_handle(module.module_handle_at(handle.module));
+        let callee = match vm.load_module(&callee_id, storage) {
+            Ok(callee) => callee,
+            Err(_) => return false,
+        };
+        let name = module.identifier_at(handle.name);
+        callee
+            .function_defs()
+            .iter()
+            .find(|def| callee.identifier_at(callee.function_handle_at(def.function).name) == name)
+            .map_or(false, |def| def.visibility != Visibility::Script)
+    })
+}
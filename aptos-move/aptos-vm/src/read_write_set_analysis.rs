@@ -3,16 +3,21 @@
 
 use crate::{
     adapter_common::PreprocessedTransaction,
+    aptos_vm::block_prologue_args,
     script_to_script_function::remapping,
-    system_module_names::{BLOCK_MODULE, BLOCK_PROLOGUE, SCRIPT_PROLOGUE_NAME, USER_EPILOGUE_NAME},
+    system_module_names::{
+        BLOCK_MODULE, BLOCK_PROLOGUE, BLOCK_PROLOGUE_EXT, SCRIPT_PROLOGUE_NAME, USER_EPILOGUE_NAME,
+    },
 };
 use anyhow::{anyhow, bail, Result};
 use aptos_types::{
     account_config,
+    block_metadata::BlockMetadata,
     transaction::{SignedTransaction, TransactionPayload},
 };
 use move_bytecode_utils::module_cache::SyncModuleCache;
 use move_core_types::{
+    account_address::AccountAddress,
     ident_str,
     identifier::{IdentStr, Identifier},
     language_storage::{ModuleId, ResourceKey, StructTag, TypeTag},
@@ -34,6 +39,7 @@ const TRANSACTION_FEES_NAME: &IdentStr = ident_str!("TransactionFee");
 pub fn add_on_functions_list() -> Vec<(ModuleId, Identifier)> {
     vec![
         (BLOCK_MODULE.clone(), BLOCK_PROLOGUE.to_owned()),
+        (BLOCK_MODULE.clone(), BLOCK_PROLOGUE_EXT.to_owned()),
         (
             account_config::constants::DIEM_ACCOUNT_MODULE.clone(),
             SCRIPT_PROLOGUE_NAME.to_owned(),
@@ -174,25 +180,25 @@ impl<'a, R: MoveResolver> ReadWriteSetAnalysis<'a, R> {
                 self.get_keys_user_transaction_impl(tx, concretize)
             }
             PreprocessedTransaction::BlockMetadata(block_metadata) => {
-                let (round, timestamp, previous_vote, proposer) =
-                    block_metadata.clone().into_inner();
-                let args = serialize_values(&vec![
-                    MoveValue::Signer(account_config::reserved_vm_address()),
-                    MoveValue::U64(round),
-                    MoveValue::U64(timestamp),
-                    MoveValue::Vector(previous_vote.into_iter().map(MoveValue::Address).collect()),
-                    MoveValue::Address(proposer),
-                ]);
-                let metadata_access = self.get_partially_concretized_summary(
-                    &BLOCK_MODULE,
-                    BLOCK_PROLOGUE,
-                    &[],
-                    &args,
-                    &[],
-                    &self.module_cache,
-                )?;
-                self.concretize_secondary_indexes(metadata_access, concretize)
+                // The VM runs `block_prologue_ext` instead once the on-chain version supports it,
+                // the framework having it.
+                let (mut reads, mut writes) =
+                    self.get_keys_block_prologue(block_metadata, None, false, concretize)?;
+                if let Ok((ext_reads, ext_writes)) =
+                    self.get_keys_block_prologue(block_metadata, None, true, concretize)
+                {
+                    reads.extend(ext_reads);
+                    writes.extend(ext_writes);
+                }
+                Ok((reads, writes))
             }
+            PreprocessedTransaction::BlockMetadataExt(block_metadata_ext) => self
+                .get_keys_block_prologue(
+                    block_metadata_ext.block_metadata(),
+                    Some(block_metadata_ext.failed_proposers()),
+                    true,
+                    concretize,
+                ),
             PreprocessedTransaction::InvalidSignature => Ok((vec![], vec![])),
             PreprocessedTransaction::WriteSet(_) | PreprocessedTransaction::WaypointWriteSet(_) => {
                 bail!("Unsupported writeset transaction")
@@ -201,6 +207,26 @@ impl<'a, R: MoveResolver> ReadWriteSetAnalysis<'a, R> {
         }
     }
 
+    fn get_keys_block_prologue(
+        &self,
+        block_metadata: &BlockMetadata,
+        failed_proposers: Option<&Vec<AccountAddress>>,
+        ext_supported: bool,
+        concretize: bool,
+    ) -> Result<(Vec<ResourceKey>, Vec<ResourceKey>)> {
+        let (function_name, args) =
+            block_prologue_args(block_metadata, failed_proposers, ext_supported);
+        let metadata_access = self.get_partially_concretized_summary(
+            &BLOCK_MODULE,
+            function_name,
+            &[],
+            &serialize_values(&args),
+            &[],
+            &self.module_cache,
+        )?;
+        self.concretize_secondary_indexes(metadata_access, concretize)
+    }
+
     fn get_concretized_keys_script_function(
         &self,
         tx: &SignedTransaction,
@@ -15,6 +15,14 @@ pub static BLOCK_MODULE: Lazy<ModuleId> = Lazy::new(|| {
     )
 });
 
+/// The ModuleId of the (insecure) randomness module
+pub static RANDOMNESS_MODULE: Lazy<ModuleId> = Lazy::new(|| {
+    ModuleId::new(
+        account_config::CORE_CODE_ADDRESS,
+        ident_str!("InsecureRandomness").to_owned(),
+    )
+});
+
 // TZ: TODO: remove these except for the block-related names
 // Names for special functions and structs
 pub const SCRIPT_PROLOGUE_NAME: &IdentStr = ident_str!("script_prologue");
@@ -24,3 +32,4 @@ pub const WRITESET_PROLOGUE_NAME: &IdentStr = ident_str!("writeset_prologue");
 pub const WRITESET_EPILOGUE_NAME: &IdentStr = ident_str!("writeset_epilogue");
 pub const USER_EPILOGUE_NAME: &IdentStr = ident_str!("epilogue");
 pub const BLOCK_PROLOGUE: &IdentStr = ident_str!("block_prologue");
+pub const BLOCK_PROLOGUE_EXT: &IdentStr = ident_str!("block_prologue_ext");
@@ -0,0 +1,70 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Read-only invocation of Move functions, for the REST API, the CLI and the indexers to call the
+//! functions of the on-chain modules against a state without setting up a VM session themselves.
+
+use crate::{
+    aptos_vm::AptosVM, data_cache::RemoteStorage, logging::AdapterLogSchema, move_vm_ext::SessionId,
+};
+use aptos_state_view::StateView;
+use aptos_types::vm_status::{StatusCode, VMStatus};
+use move_core_types::{
+    gas_schedule::GasUnits,
+    identifier::IdentStr,
+    language_storage::{ModuleId, TypeTag},
+    value::MoveValue,
+};
+use move_vm_types::gas_schedule::GasStatus;
+
+/// Max gas units a view function can use, so that a view function never loops forever.
+pub const VIEW_FUNCTION_MAX_GAS_AMOUNT: u64 = 1_000_000;
+
+/// Executes the function against the given state in a throwaway session and returns its return
+/// values, the changes made by the function are discarded. `args` are the BCS serialized
+/// arguments of the function, its visibility isn't checked.
+pub fn execute_view_function(
+    state_view: &impl StateView,
+    module: &ModuleId,
+    function: &IdentStr,
+    ty_args: Vec<TypeTag>,
+    args: Vec<Vec<u8>>,
+) -> Result<Vec<MoveValue>, VMStatus> {
+    execute_view_function_with_gas(
+        state_view,
+        module,
+        function,
+        ty_args,
+        args,
+        VIEW_FUNCTION_MAX_GAS_AMOUNT,
+    )
+}
+
+/// Same as `execute_view_function`, with the given gas limit.
+pub fn execute_view_function_with_gas(
+    state_view: &impl StateView,
+    module: &ModuleId,
+    function: &IdentStr,
+    ty_args: Vec<TypeTag>,
+    args: Vec<Vec<u8>>,
+    max_gas_amount: u64,
+) -> Result<Vec<MoveValue>, VMStatus> {
+    let vm = AptosVM::new(state_view);
+    let log_context = AdapterLogSchema::new(state_view.id(), 0);
+    let gas_schedule = vm.0.get_gas_schedule(&log_context)?;
+    let mut gas_status = GasStatus::new(gas_schedule, GasUnits::new(max_gas_amount));
+    let storage = RemoteStorage::new(state_view);
+    let mut session = vm.0.new_session(&storage, SessionId::void());
+
+    let values = session
+        .execute_function_bypass_visibility(module, function, ty_args, args, &mut gas_status)
+        .map_err(|e| e.into_vm_status())?;
+    values
+        .return_values
+        .into_iter()
+        .map(|(bytes, layout)| {
+            MoveValue::simple_deserialize(&bytes, &layout)
+                .map_err(|_| VMStatus::Error(StatusCode::VALUE_DESERIALIZATION_ERROR))
+        })
+        .collect()
+}
@@ -1019,6 +1019,17 @@ fn replay_trace<P: AsRef<Path>>(
                             }
                             return Ok(());
                         }
+                        TransactionPayload::ScriptFunctionBatch(_) => {
+                            // TODO: the replay only follows a single script function per
+                            // transaction, exit the test and call it successful
+                            if flags.warning {
+                                eprintln!(
+                                    "[!] Replay stopped due to script function batch: {}",
+                                    test_name
+                                );
+                            }
+                            return Ok(());
+                        }
                         TransactionPayload::WriteSet(WriteSetPayload::Direct(change_set)) => {
                             for (key, _) in change_set.write_set().iter() {
                                 let ap = AccessPath::try_from(key.clone()).unwrap();
@@ -1083,6 +1094,15 @@ fn replay_trace<P: AsRef<Path>>(
                     }
                 }
                 Transaction::StateCheckpoint => {}
+                Transaction::BlockMetadataExt(_) => {
+                    if flags.warning {
+                        eprintln!(
+                            "[!] Replay stopped due to an unsupported block metadata ext transaction: {}",
+                            test_name
+                        );
+                    }
+                    return Ok(());
+                }
             }
         }
     }
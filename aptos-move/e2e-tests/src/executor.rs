@@ -18,7 +18,7 @@ use crate::{
 };
 use aptos_crypto::HashValue;
 use aptos_keygen::KeyGen;
-use aptos_state_view::StateView;
+use aptos_state_view::{StateView, StateViewId};
 use aptos_types::{
     access_path::AccessPath,
     account_config::{AccountResource, BalanceResource, TransferEventsResource, CORE_CODE_ADDRESS},
@@ -35,7 +35,7 @@ use aptos_types::{
 use aptos_vm::{
     data_cache::{AsMoveResolver, RemoteStorage},
     move_vm_ext::{MoveVmExt, SessionId},
-    parallel_executor::ParallelAptosVM,
+    parallel_executor::{ParallelAptosVM, ParallelExecutorConfig},
     AptosVM, VMExecutor, VMValidator,
 };
 use move_core_types::{
@@ -366,6 +366,37 @@ impl FakeExecutor {
         Ok(result)
     }
 
+    /// Executes the block `block_id` on top of the block `parent_block_id`, as the block executor
+    /// does, so the VM may reuse the Move VM it cached once the parent block was executed. The
+    /// block is executed in parallel with the `parallel_config`, if any, sequentially otherwise.
+    /// This doesn't apply the results of successful transactions to the data store.
+    pub fn execute_block_on_parent(
+        &self,
+        block_id: HashValue,
+        parent_block_id: HashValue,
+        txn_block: Vec<SignedTransaction>,
+        parallel_config: Option<ParallelExecutorConfig>,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        let state_view = BlockStateView {
+            data_store: &self.data_store,
+            id: StateViewId::BlockExecution {
+                block_id,
+                parent_block_id,
+            },
+        };
+        let txn_block = txn_block
+            .into_iter()
+            .map(Transaction::UserTransaction)
+            .collect();
+        match parallel_config {
+            Some(config) => {
+                ParallelAptosVM::execute_block_with_config(txn_block, &state_view, config)
+                    .map(|(outputs, _fallback)| outputs)
+            }
+            None => AptosVM::execute_block(txn_block, &state_view),
+        }
+    }
+
     pub fn execute_transaction_block(
         &self,
         txn_block: Vec<Transaction>,
@@ -565,3 +596,23 @@ impl FakeExecutor {
         Ok(writeset)
     }
 }
+
+/// The data store as read by the execution of a block
+struct BlockStateView<'a> {
+    data_store: &'a FakeDataStore,
+    id: StateViewId,
+}
+
+impl<'a> StateView for BlockStateView<'a> {
+    fn id(&self) -> StateViewId {
+        self.id
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> anyhow::Result<Option<Vec<u8>>> {
+        self.data_store.get_state_value(state_key)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.data_store.is_genesis()
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    access_path::AccessPath,
+    on_chain_config::VMPublishingOption,
+    state_store::state_key::StateKey,
+    transaction::{ScriptFunction, TransactionOutput, TransactionPayload, TransactionStatus},
+    vm_status::KeptVMStatus,
+};
+use language_e2e_tests::{
+    account::AccountData,
+    compile::{compile_module, compile_script},
+    executor::FakeExecutor,
+};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag},
+    value::MoveValue,
+};
+
+struct Env {
+    executor: FakeExecutor,
+    account: AccountData,
+    sequence_number: u64,
+}
+
+impl Env {
+    /// An account with the module `M`, whose script function draws a value, stores it, and then
+    /// loops for the given number of iterations
+    fn new() -> Self {
+        let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::open());
+        let account = executor.create_raw_account_data(1_000_000, 0);
+        executor.add_account_data(&account);
+        let mut env = Self {
+            executor,
+            account,
+            sequence_number: 0,
+        };
+        let output = env.publish(&format!(
+            "
+            module 0x{}.M {{
+                import 0x1.InsecureRandomness;
+                struct Draw has key {{ v: u64 }}
+
+                public(script) draw(account: signer, iterations: u64) {{
+                    let v: u64;
+                label b0:
+                    v = InsecureRandomness.next_u64();
+                    move_to<Draw>(&account, Draw {{ v: move(v) }});
+                label b1:
+                    jump_if (copy(iterations) == 0) b3;
+                label b2:
+                    iterations = move(iterations) - 1;
+                    jump b1;
+                label b3:
+                    return;
+                }}
+            }}
+            ",
+            env.account.address(),
+        ));
+        assert!(executed(&output));
+        env
+    }
+
+    fn publish(&mut self, module: &str) -> TransactionOutput {
+        let txn = self
+            .account
+            .account()
+            .transaction()
+            .module(compile_module(module).1)
+            .sequence_number(self.sequence_number)
+            .sign();
+        self.sequence_number += 1;
+        self.executor.execute_and_apply(txn)
+    }
+
+    fn draw(&mut self, iterations: u64) -> TransactionOutput {
+        let txn = self
+            .account
+            .account()
+            .transaction()
+            .payload(TransactionPayload::ScriptFunction(ScriptFunction::new(
+                ModuleId::new(*self.account.address(), Identifier::new("M").unwrap()),
+                Identifier::new("draw").unwrap(),
+                vec![],
+                vec![MoveValue::U64(iterations).simple_serialize().unwrap()],
+            )))
+            .sequence_number(self.sequence_number)
+            .sign();
+        self.sequence_number += 1;
+        self.executor.execute_and_apply(txn)
+    }
+
+    /// Returns true iff the output stores the value drawn
+    fn stores_draw(&self, output: &TransactionOutput) -> bool {
+        let draw = StateKey::AccessPath(AccessPath::new(
+            *self.account.address(),
+            AccessPath::resource_access_vec(StructTag {
+                address: *self.account.address(),
+                module: Identifier::new("M").unwrap(),
+                name: Identifier::new("Draw").unwrap(),
+                type_params: vec![],
+            }),
+        ));
+        output.write_set().iter().any(|(key, _)| *key == draw)
+    }
+}
+
+fn executed(output: &TransactionOutput) -> bool {
+    output.status() == &TransactionStatus::Keep(KeptVMStatus::Executed)
+}
+
+#[test]
+fn test_draw_by_script_function() {
+    let mut env = Env::new();
+    let output = env.draw(0);
+    assert!(executed(&output));
+    assert!(env.stores_draw(&output));
+}
+
+#[test]
+fn test_draw_dropped_by_running_out_of_gas() {
+    // The sender can drop a draw by giving the transaction too little gas for the code run after
+    // it, which is why the randomness is insecure.
+    let mut env = Env::new();
+    let output = env.draw(u64::MAX);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(KeptVMStatus::OutOfGas)
+    );
+    assert!(!env.stores_draw(&output));
+}
+
+#[test]
+fn test_draw_in_script_aborts() {
+    let mut env = Env::new();
+    let script = compile_script(
+        "
+        import 0x1.InsecureRandomness;
+        main() {
+            let v: u64;
+        label b0:
+            v = InsecureRandomness.next_u64();
+            return;
+        }
+        ",
+        vec![],
+    );
+    let txn = env
+        .account
+        .account()
+        .transaction()
+        .script(script)
+        .sequence_number(env.sequence_number)
+        .sign();
+    let output = env.executor.execute_transaction(txn);
+    assert!(matches!(
+        output.status(),
+        TransactionStatus::Keep(KeptVMStatus::MoveAbort(_, _))
+    ));
+}
+
+#[test]
+fn test_module_exposing_draws_rejected() {
+    // The module calling `draw` could abort after an unwanted draw.
+    let mut env = Env::new();
+    let output = env.publish(&format!(
+        "
+        module 0x{}.N {{
+            import 0x1.InsecureRandomness;
+
+            public draw(): u64 {{
+            label b0:
+                return InsecureRandomness.next_u64();
+            }}
+        }}
+        ",
+        env.account.address(),
+    ));
+    assert!(!executed(&output));
+}
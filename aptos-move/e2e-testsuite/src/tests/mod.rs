@@ -18,11 +18,15 @@ mod execution_strategies;
 mod failed_transaction_tests;
 mod genesis;
 mod genesis_initializations;
+mod insecure_randomness;
 mod mint;
+mod module_cache;
 mod module_publishing;
 mod on_chain_configs;
 mod peer_to_peer;
+mod profiling;
 mod rotate_key;
+mod script_function_batch;
 mod scripts;
 mod transaction_fuzzer;
 mod verify_txn;
@@ -0,0 +1,258 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The Move VM cached across the blocks must execute the modules of the state each block is
+//! executed on, after the modules are republished, on another fork, and once the parallel
+//! execution of a block falls back to the sequential one.
+
+use aptos_crypto::HashValue;
+use aptos_types::{
+    on_chain_config::VMPublishingOption,
+    transaction::{Module, Script, SignedTransaction, TransactionStatus},
+    vm_status::KeptVMStatus,
+};
+use aptos_vm::parallel_executor::ParallelExecutorConfig;
+use language_e2e_tests::{
+    account::AccountData,
+    compile::{compile_module, compile_script},
+    executor::FakeExecutor,
+};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+// The Move VM is cached by the block ids, the tests executing blocks mustn't take each other's.
+static BLOCK_EXECUTION: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+struct Env {
+    executor: FakeExecutor,
+    account: AccountData,
+    sequence_number: u64,
+    modules: Vec<Module>,
+    script: Script,
+    parent_block_id: HashValue,
+}
+
+impl Env {
+    /// An account with the versions of the module `M`, whose function `f` aborts with the
+    /// version, and a script calling `f`.
+    fn new() -> Self {
+        let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::open());
+        let account = executor.create_raw_account_data(1_000_000, 0);
+        executor.add_account_data(&account);
+
+        let (compiled_modules, modules): (Vec<_>, Vec<_>) = (1..=2)
+            .map(|version| {
+                compile_module(&format!(
+                    "
+                    module 0x{}.M {{
+                        public f() {{
+                        label b0:
+                            abort {};
+                        }}
+                    }}
+                    ",
+                    account.address(),
+                    version,
+                ))
+            })
+            .unzip();
+        let script = compile_script(
+            &format!(
+                "
+                import 0x{}.M;
+
+                main() {{
+                label b0:
+                    M.f();
+                    return;
+                }}
+                ",
+                account.address(),
+            ),
+            vec![compiled_modules[0].clone()],
+        );
+        Self {
+            executor,
+            account,
+            sequence_number: 0,
+            modules,
+            script,
+            parent_block_id: HashValue::random(),
+        }
+    }
+
+    fn module_id(&self) -> ModuleId {
+        ModuleId::new(*self.account.address(), Identifier::new("M").unwrap())
+    }
+
+    fn publish(&mut self, version: usize) -> SignedTransaction {
+        let txn = self
+            .account
+            .account()
+            .transaction()
+            .module(self.modules[version - 1].clone())
+            .sequence_number(self.sequence_number)
+            .sign();
+        self.sequence_number += 1;
+        txn
+    }
+
+    fn call(&mut self) -> SignedTransaction {
+        let txn = self
+            .account
+            .account()
+            .transaction()
+            .script(self.script.clone())
+            .sequence_number(self.sequence_number)
+            .sign();
+        self.sequence_number += 1;
+        txn
+    }
+
+    /// Executes the block on top of the previous one and applies its outputs, returning the
+    /// statuses of its transactions.
+    fn execute_block(
+        &mut self,
+        txns: Vec<SignedTransaction>,
+        parallel_config: Option<ParallelExecutorConfig>,
+    ) -> Vec<TransactionStatus> {
+        let block_id = HashValue::random();
+        let statuses =
+            self.execute_block_on_parent(block_id, self.parent_block_id, txns, parallel_config);
+        self.parent_block_id = block_id;
+        statuses
+    }
+
+    fn execute_block_on_parent(
+        &mut self,
+        block_id: HashValue,
+        parent_block_id: HashValue,
+        txns: Vec<SignedTransaction>,
+        parallel_config: Option<ParallelExecutorConfig>,
+    ) -> Vec<TransactionStatus> {
+        let outputs = self
+            .executor
+            .execute_block_on_parent(block_id, parent_block_id, txns, parallel_config)
+            .unwrap();
+        outputs
+            .iter()
+            .map(|output| {
+                self.executor.apply_write_set(output.write_set());
+                output.status().clone()
+            })
+            .collect()
+    }
+}
+
+/// The version of `M` executed by the transaction calling it
+fn executed_version(status: &TransactionStatus) -> u64 {
+    match status {
+        TransactionStatus::Keep(KeptVMStatus::MoveAbort(_, abort_code)) => *abort_code,
+        status => panic!("Unexpected status: {:?}", status),
+    }
+}
+
+fn executed(status: &TransactionStatus) -> bool {
+    status == &TransactionStatus::Keep(KeptVMStatus::Executed)
+}
+
+fn republished_module_executed(parallel_config: Option<ParallelExecutorConfig>) {
+    let _guard = BLOCK_EXECUTION
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+    let mut env = Env::new();
+
+    let txn = env.publish(1);
+    assert!(executed(&env.execute_block(vec![txn], parallel_config)[0]));
+    // executes `M` once loaded, the Move VM being cached with it
+    let txn = env.call();
+    assert_eq!(
+        executed_version(&env.execute_block(vec![txn], parallel_config)[0]),
+        1
+    );
+
+    let txns = vec![env.call(), env.publish(2)];
+    let statuses = env.execute_block(txns, parallel_config);
+    assert_eq!(executed_version(&statuses[0]), 1);
+    assert!(executed(&statuses[1]));
+
+    // the blocks after the one republishing `M` execute the new version
+    let txn = env.call();
+    assert_eq!(
+        executed_version(&env.execute_block(vec![txn], parallel_config)[0]),
+        2
+    );
+}
+
+#[test]
+fn test_republished_module_executed() {
+    republished_module_executed(None);
+}
+
+#[test]
+fn test_republished_module_executed_in_parallel() {
+    republished_module_executed(Some(ParallelExecutorConfig::default()));
+}
+
+#[test]
+fn test_module_of_fork_executed() {
+    let _guard = BLOCK_EXECUTION
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+    let mut env = Env::new();
+
+    let txn = env.publish(1);
+    assert!(executed(&env.execute_block(vec![txn], None)[0]));
+    let txn = env.call();
+    assert_eq!(executed_version(&env.execute_block(vec![txn], None)[0]), 1);
+    let fork_point = env.parent_block_id;
+
+    // `M` is republished on a fork, the Move VM being cached with the new version loaded
+    let txn = env.publish(2);
+    assert!(executed(&env.execute_block(vec![txn], None)[0]));
+    let txn = env.call();
+    assert_eq!(executed_version(&env.execute_block(vec![txn], None)[0]), 2);
+
+    // the other fork, where `M` wasn't republished, still executes the first version
+    let module_id = env.module_id();
+    let module_blob = env.modules[0].code().to_vec();
+    env.executor.add_module(&module_id, module_blob);
+    let txn = env.call();
+    let statuses = env.execute_block_on_parent(HashValue::random(), fork_point, vec![txn], None);
+    assert_eq!(executed_version(&statuses[0]), 1);
+}
+
+#[test]
+fn test_republished_module_executed_after_sequential_fallback() {
+    let _guard = BLOCK_EXECUTION
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+    let mut env = Env::new();
+    // gives up the parallel execution at the first conflict between the transactions
+    let fallback_config = ParallelExecutorConfig {
+        max_aborts_per_txn: Some(0),
+        ..ParallelExecutorConfig::default()
+    };
+
+    let txn = env.publish(1);
+    assert!(executed(&env.execute_block(vec![txn], None)[0]));
+    let txn = env.call();
+    assert_eq!(
+        executed_version(&env.execute_block(vec![txn], Some(fallback_config))[0]),
+        1
+    );
+
+    // the transactions of the same account conflict, so the block falls back to the sequential
+    // execution unless they're executed one after the other
+    let txns = vec![env.call(), env.publish(2)];
+    let statuses = env.execute_block(txns, Some(fallback_config));
+    assert_eq!(executed_version(&statuses[0]), 1);
+    assert!(executed(&statuses[1]));
+
+    let txn = env.call();
+    assert_eq!(
+        executed_version(&env.execute_block(vec![txn], Some(fallback_config))[0]),
+        2
+    );
+}
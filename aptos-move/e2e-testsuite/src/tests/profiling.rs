@@ -0,0 +1,74 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use aptos_types::transaction::SignedTransaction;
+use aptos_vm::{parallel_executor::ParallelExecutorConfig, profiling};
+use language_e2e_tests::{common_transactions::peer_to_peer_txn, executor::FakeExecutor};
+
+fn profiled_times(hash: HashValue) -> usize {
+    profiling::transaction_profiles()
+        .iter()
+        .filter(|profile| profile.hash == hash)
+        .count()
+}
+
+fn transactions_profiled_once(parallel_config: Option<ParallelExecutorConfig>) {
+    profiling::enable_profiling(100_000);
+    let mut executor = FakeExecutor::from_genesis_file();
+    let receiver = executor.create_raw_account_data(100_000, 0);
+    executor.add_account_data(&receiver);
+    // the transfers to the same receiver conflict, so the parallel execution re-executes them
+    let mut txns: Vec<SignedTransaction> = (0..10)
+        .map(|_| {
+            let sender = executor.create_raw_account_data(1_000_000, 0);
+            executor.add_account_data(&sender);
+            peer_to_peer_txn(sender.account(), receiver.account(), 0, 1_000)
+        })
+        .collect();
+    // the transaction with a sequence number too new is discarded, it isn't committed
+    let sender = executor.create_raw_account_data(1_000_000, 0);
+    executor.add_account_data(&sender);
+    let discarded_txn = peer_to_peer_txn(sender.account(), receiver.account(), 5, 1_000);
+    txns.push(discarded_txn.clone());
+
+    let outputs = executor
+        .execute_block_on_parent(
+            HashValue::random(),
+            HashValue::random(),
+            txns.clone(),
+            parallel_config,
+        )
+        .unwrap();
+    assert!(outputs[..10]
+        .iter()
+        .all(|output| !output.status().is_discarded()));
+    assert!(outputs[10].status().is_discarded());
+
+    for (txn, output) in txns[..10].iter().zip(&outputs) {
+        let hash = txn.clone().committed_hash();
+        assert_eq!(profiled_times(hash), 1);
+        let profile = profiling::transaction_profiles()
+            .into_iter()
+            .find(|profile| profile.hash == hash)
+            .unwrap();
+        assert_eq!(profile.sender, txn.sender());
+        assert_eq!(profile.gas_used, output.gas_used());
+        assert!(profile.state_reads > 0);
+        assert_eq!(
+            profile.state_writes,
+            output.write_set().iter().count() as u64
+        );
+    }
+    assert_eq!(profiled_times(discarded_txn.committed_hash()), 0);
+}
+
+#[test]
+fn test_transactions_profiled_once() {
+    transactions_profiled_once(None);
+}
+
+#[test]
+fn test_transactions_profiled_once_in_parallel() {
+    transactions_profiled_once(Some(ParallelExecutorConfig::default()));
+}
@@ -0,0 +1,220 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    on_chain_config::{access_path_for_config, OnChainConfig, Version, APTOS_VERSION_4},
+    state_store::state_key::StateKey,
+    transaction::{
+        BatchedCall, BatchedCallArgument, ScriptFunctionBatch, SignedTransaction,
+        TransactionPayload, TransactionStatus,
+    },
+    vm_status::{KeptVMStatus, StatusCode},
+    write_set::{WriteOp, WriteSetMut},
+};
+use language_e2e_tests::{account::AccountData, compile::compile_module, executor::FakeExecutor};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId, value::MoveValue};
+
+struct Env {
+    executor: FakeExecutor,
+    account: AccountData,
+    sequence_number: u64,
+}
+
+impl Env {
+    /// An account with the module `M`, calling it by batches
+    fn new() -> Self {
+        let mut executor = FakeExecutor::from_genesis_file();
+        let account = executor.create_raw_account_data(1_000_000, 0);
+        executor.add_account_data(&account);
+        let (_, module) = compile_module(&format!(
+            "
+            module 0x{}.M {{
+                import 0x1.Signer;
+                struct R has key {{ v: u64 }}
+
+                public double(x: u64): u64 {{
+                label b0:
+                    return move(x) * 2;
+                }}
+
+                double_privately(x: u64): u64 {{
+                label b0:
+                    return move(x) * 2;
+                }}
+
+                public value_of(account: signer): u64 acquires R {{
+                    let r: &Self.R;
+                label b0:
+                    r = borrow_global<R>(Signer.address_of(&account));
+                    return *&move(r).R::v;
+                }}
+
+                public(script) store(account: signer, v: u64) {{
+                label b0:
+                    move_to<R>(&account, R {{ v: move(v) }});
+                    return;
+                }}
+
+                public(script) abort_with(v: u64) {{
+                label b0:
+                    abort move(v);
+                }}
+
+                public(script) abort_with_address(a: address) {{
+                label b0:
+                    abort 0;
+                }}
+            }}
+            ",
+            account.address(),
+        ));
+        let module_id = ModuleId::new(*account.address(), Identifier::new("M").unwrap());
+        executor.add_module(&module_id, module.code().to_vec());
+        Self {
+            executor,
+            account,
+            sequence_number: 0,
+        }
+    }
+
+    fn call(&self, function: &str, args: Vec<BatchedCallArgument>) -> BatchedCall {
+        BatchedCall::new(
+            ModuleId::new(*self.account.address(), Identifier::new("M").unwrap()),
+            Identifier::new(function).unwrap(),
+            vec![],
+            args,
+        )
+    }
+
+    fn batch(&mut self, calls: Vec<BatchedCall>) -> SignedTransaction {
+        let txn = self
+            .account
+            .account()
+            .transaction()
+            .payload(TransactionPayload::ScriptFunctionBatch(
+                ScriptFunctionBatch::new(calls),
+            ))
+            .sequence_number(self.sequence_number)
+            .sign();
+        self.sequence_number += 1;
+        txn
+    }
+
+    fn execute(&mut self, calls: Vec<BatchedCall>) -> TransactionStatus {
+        let txn = self.batch(calls);
+        self.executor.execute_and_apply(txn).status().clone()
+    }
+}
+
+fn value(value: u64) -> BatchedCallArgument {
+    BatchedCallArgument::Value(MoveValue::U64(value).simple_serialize().unwrap())
+}
+
+fn output(call: u16, output: u16) -> BatchedCallArgument {
+    BatchedCallArgument::Output { call, output }
+}
+
+fn aborted_with(status: &TransactionStatus) -> u64 {
+    match status {
+        TransactionStatus::Keep(KeptVMStatus::MoveAbort(_, abort_code)) => *abort_code,
+        status => panic!("Unexpected status: {:?}", status),
+    }
+}
+
+fn executed(status: &TransactionStatus) -> bool {
+    status == &TransactionStatus::Keep(KeptVMStatus::Executed)
+}
+
+#[test]
+fn test_batch_passes_return_values() {
+    let mut env = Env::new();
+    let calls = vec![
+        env.call("double", vec![value(21)]),
+        env.call("abort_with", vec![output(0, 0)]),
+    ];
+    assert_eq!(aborted_with(&env.execute(calls)), 42);
+}
+
+#[test]
+fn test_batch_passes_signers_by_value() {
+    let mut env = Env::new();
+    let calls = vec![
+        env.call("double", vec![value(3)]),
+        env.call("store", vec![output(0, 0)]),
+    ];
+    assert!(executed(&env.execute(calls)));
+
+    let calls = vec![
+        env.call("value_of", vec![]),
+        env.call("abort_with", vec![output(0, 0)]),
+    ];
+    assert_eq!(aborted_with(&env.execute(calls)), 6);
+}
+
+#[test]
+fn test_batch_aborted_atomically() {
+    let mut env = Env::new();
+    let calls = vec![
+        env.call("store", vec![value(1)]),
+        env.call("abort_with", vec![value(7)]),
+    ];
+    assert_eq!(aborted_with(&env.execute(calls)), 7);
+
+    // the resource stored by the first call isn't kept, so it can be stored again
+    let calls = vec![env.call("store", vec![value(2)])];
+    assert!(executed(&env.execute(calls)));
+}
+
+#[test]
+fn test_batch_with_invalid_arguments() {
+    let mut env = Env::new();
+    // only the return values of the earlier calls can be passed
+    let calls = vec![
+        env.call("abort_with", vec![output(1, 0)]),
+        env.call("double", vec![value(1)]),
+    ];
+    assert!(!executed(&env.execute(calls)));
+
+    // the return value must be of the type of the parameter
+    let calls = vec![
+        env.call("double", vec![value(1)]),
+        env.call("abort_with_address", vec![output(0, 0)]),
+    ];
+    assert!(!executed(&env.execute(calls)));
+
+    let calls = vec![env.call("double", vec![value(1), value(2)])];
+    assert!(!executed(&env.execute(calls)));
+}
+
+#[test]
+fn test_batch_calls_only_visible_functions() {
+    let mut env = Env::new();
+    let calls = vec![
+        env.call("double_privately", vec![value(1)]),
+        env.call("abort_with", vec![output(0, 0)]),
+    ];
+    assert!(!executed(&env.execute(calls)));
+}
+
+#[test]
+fn test_batch_gated_by_aptos_version() {
+    let mut env = Env::new();
+    env.executor.apply_write_set(
+        &WriteSetMut::new(vec![(
+            StateKey::AccessPath(access_path_for_config(Version::CONFIG_ID)),
+            WriteOp::Value(bcs::to_bytes(&APTOS_VERSION_4).unwrap()),
+        )])
+        .freeze()
+        .unwrap(),
+    );
+
+    let txn = env.batch(vec![env.call("abort_with", vec![value(1)])]);
+    assert_eq!(
+        env.executor.verify_transaction(txn.clone()).status(),
+        Some(StatusCode::FEATURE_UNDER_GATING)
+    );
+    assert_eq!(
+        env.executor.execute_transaction(txn).status(),
+        &TransactionStatus::Discard(StatusCode::FEATURE_UNDER_GATING)
+    );
+}
@@ -2,7 +2,9 @@
 module AptosFramework::Block {
     use Std::Errors;
     use Std::Event;
+    use Std::Vector;
     use AptosFramework::ValidatorSet;
+    use AptosFramework::InsecureRandomness;
     use AptosFramework::Timestamp;
     use AptosFramework::SystemAddresses;
 
@@ -20,6 +22,10 @@ module AptosFramework::Block {
 
         /// On-chain time during  he block at the given height
         time_microseconds: u64,
+
+        /// Proposers of the rounds after the parent block that failed, in the order of the rounds.
+        /// Last, so that the events emitted before it was added still decode.
+        failed_proposers: vector<address>,
     }
 
     /// The `BlockMetadata` resource is in an invalid state
@@ -57,6 +63,41 @@ module AptosFramework::Block {
         timestamp: u64,
         previous_block_votes: vector<address>,
         proposer: address
+    ) acquires BlockMetadata {
+        update_block_metadata(vm, round, timestamp, previous_block_votes, proposer, Vector::empty())
+    }
+
+    /// Same as `block_prologue`, also seeding the randomness with the hash of the block, and for
+    /// the blocks reporting the proposers of the failed rounds since their parent. The runtime
+    /// runs it instead once the on-chain version supports them.
+    fun block_prologue_ext(
+        vm: signer,
+        hash: address,
+        round: u64,
+        timestamp: u64,
+        previous_block_votes: vector<address>,
+        proposer: address,
+        failed_proposers: vector<address>
+    ) acquires BlockMetadata {
+        SystemAddresses::assert_vm(&vm);
+        InsecureRandomness::on_new_block(&vm, hash);
+        update_block_metadata(
+            vm,
+            round,
+            timestamp,
+            previous_block_votes,
+            proposer,
+            failed_proposers
+        )
+    }
+
+    fun update_block_metadata(
+        vm: signer,
+        round: u64,
+        timestamp: u64,
+        previous_block_votes: vector<address>,
+        proposer: address,
+        failed_proposers: vector<address>
     ) acquires BlockMetadata {
         Timestamp::assert_operating();
         // Operational constraint: can only be invoked by the VM.
@@ -78,6 +119,7 @@ module AptosFramework::Block {
                 proposer,
                 previous_block_votes,
                 time_microseconds: timestamp,
+                failed_proposers,
             }
         );
     }
@@ -9,6 +9,7 @@ module AptosFramework::Genesis {
     use AptosFramework::Version;
     use AptosFramework::Block;
     use AptosFramework::ChainId;
+    use AptosFramework::InsecureRandomness;
     use AptosFramework::Reconfiguration;
     use AptosFramework::TestCoin;
     use AptosFramework::Timestamp;
@@ -102,6 +103,7 @@ module AptosFramework::Genesis {
         ChainId::initialize(core_resource_account, chain_id);
         Reconfiguration::initialize(core_resource_account);
         Block::initialize_block_metadata(core_resource_account);
+        InsecureRandomness::initialize(core_resource_account);
         Timestamp::set_time_has_started(core_resource_account);
     }
 
@@ -0,0 +1,99 @@
+/// This module provides an insecure randomness to the transactions, which must not be relied upon
+/// where anyone has a stake in the values drawn (e.g. lotteries or games with a prize).
+///
+/// It keeps a seed updated from the hash of each block, which is only known once the block is
+/// proposed, after the transactions of the block were submitted, and mixes it with an entropy
+/// distinct for each transaction and each draw within it, so the values drawn can't be predicted
+/// when submitting a transaction as the ones derived from the timestamp can. But:
+/// - the proposer of a block knows its hash before the block is executed, and can bias the seed
+/// by choosing the transactions of the block;
+/// - the sender of a transaction can drop the values it doesn't want by giving the transaction
+/// too little gas for the code run after the draw, the transaction then running out of gas, and
+/// submit it again till it draws a value it wants.
+///
+/// A transaction drawing a value it doesn't want could otherwise just abort, which is prevented as
+/// the randomness is only drawn by the script function a transaction calls and by the private
+/// functions of its module, whose code decides what's done with the values:
+/// - a module can't be published if one of its public or friend functions draws randomness,
+/// directly or through the other functions of the module;
+/// - the randomness can only be drawn in a transaction calling a script function of a module
+/// using `InsecureRandomness`, and which calls no script function of another module. Drawing it in
+/// a script or in a batch of script functions aborts.
+module AptosFramework::InsecureRandomness {
+    use Std::BCS;
+    use Std::Errors;
+    use Std::Hash;
+    use Std::Vector;
+    use AptosFramework::SystemAddresses;
+    use AptosFramework::Timestamp;
+
+    friend AptosFramework::Block;
+
+    /// A singleton resource holding the seed of the current block
+    struct RandomnessSeed has key {
+        seed: vector<u8>,
+    }
+
+    /// The `RandomnessSeed` resource is in an invalid state
+    const ERANDOMNESS_SEED: u64 = 0;
+    /// The range to draw a value from is empty
+    const EEMPTY_RANGE: u64 = 1;
+    /// The randomness can't be drawn by the transaction
+    const EDRAW_NOT_ALLOWED: u64 = 2;
+
+    /// This can only be invoked by the Association address, and only a single time.
+    /// Currently, it is invoked in the genesis transaction
+    public fun initialize(account: &signer) {
+        Timestamp::assert_genesis();
+        // Operational constraint, only callable by the Association address
+        SystemAddresses::assert_core_resource(account);
+
+        assert!(!exists<RandomnessSeed>(@CoreResources), Errors::already_published(ERANDOMNESS_SEED));
+        move_to(account, RandomnessSeed { seed: Vector::empty() });
+    }
+
+    /// Updates the seed from the hash of the new block, before its transactions are executed.
+    /// A chain created before the seed was introduced has no randomness.
+    public(friend) fun on_new_block(vm: &signer, block_hash: address) acquires RandomnessSeed {
+        SystemAddresses::assert_vm(vm);
+        if (!exists<RandomnessSeed>(@CoreResources)) {
+            return
+        };
+        let seed = &mut borrow_global_mut<RandomnessSeed>(@CoreResources).seed;
+        Vector::append(seed, BCS::to_bytes(&block_hash));
+        *seed = Hash::sha3_256(*seed);
+    }
+
+    /// Returns 32 random bytes, distinct for each call
+    public fun bytes(): vector<u8> acquires RandomnessSeed {
+        assert!(exists<RandomnessSeed>(@CoreResources), Errors::not_published(ERANDOMNESS_SEED));
+        let entropy = next_entropy();
+        assert!(!Vector::is_empty(&entropy), Errors::invalid_state(EDRAW_NOT_ALLOWED));
+        let bytes = *&borrow_global<RandomnessSeed>(@CoreResources).seed;
+        Vector::append(&mut bytes, entropy);
+        Hash::sha3_256(bytes)
+    }
+
+    /// Returns a random u64
+    public fun next_u64(): u64 acquires RandomnessSeed {
+        let bytes = bytes();
+        let value = 0;
+        let i = 0;
+        while (i < 8) {
+            value = (value << 8) | (*Vector::borrow(&bytes, i) as u64);
+            i = i + 1;
+        };
+        value
+    }
+
+    /// Returns a random u64 in the range [`min`, `max`), slightly biased towards its lower values
+    /// unless its length is a power of 2
+    public fun u64_in_range(min: u64, max: u64): u64 acquires RandomnessSeed {
+        assert!(min < max, Errors::invalid_argument(EEMPTY_RANGE));
+        min + next_u64() % (max - min)
+    }
+
+    /// Returns the entropy of the next draw of the transaction, empty if the transaction can't
+    /// draw the randomness
+    native fun next_entropy(): vector<u8>;
+}
@@ -0,0 +1,88 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coverage of the Move unit tests of the packages. With `MOVE_COVERAGE_DIR` set, the Move unit
+//! tests of the framework packages compute their coverage, and the coverage map of each package is
+//! written to the dir along with a summary of the coverage of its modules, so the CI can gate the
+//! changes to the framework on the Move coverage as it does on the Rust coverage.
+
+use anyhow::{ensure, Result};
+use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
+use move_coverage::{coverage_map::CoverageMap, summary::summarize_inst_cov};
+use move_package::BuildConfig;
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The env var naming the dir to write the coverage artifacts to
+pub const MOVE_COVERAGE_DIR: &str = "MOVE_COVERAGE_DIR";
+
+/// The env var holding the minimal percentage of the instructions of a package its unit tests must
+/// cover, if any
+pub const MOVE_COVERAGE_THRESHOLD: &str = "MOVE_COVERAGE_THRESHOLD";
+
+/// The coverage map the Move unit tests write to the package dir when computing the coverage
+const COVERAGE_MAP_FILE: &str = ".coverage_map.mvcov";
+
+/// Returns the dir to write the coverage artifacts to, if the coverage is computed
+pub fn coverage_dir() -> Option<PathBuf> {
+    std::env::var_os(MOVE_COVERAGE_DIR).map(PathBuf::from)
+}
+
+/// Moves the coverage map computed by the unit tests of the package to `coverage_dir`, as
+/// `<package>.mvcov`, and writes the summary of the coverage of its modules to
+/// `<package>.coverage.txt`. Fails if the coverage is below `MOVE_COVERAGE_THRESHOLD`, returns the
+/// percentage of the instructions covered otherwise.
+pub fn write_coverage_artifacts(
+    package_path: &Path,
+    build_config: BuildConfig,
+    coverage_dir: &Path,
+) -> Result<f64> {
+    // The test only code isn't part of the coverage.
+    let build_config = BuildConfig {
+        test_mode: false,
+        ..build_config
+    };
+    let package = build_config.compile_package(package_path, &mut Vec::new())?;
+    let package_name = package.compiled_package_info.package_name.as_str();
+
+    fs::create_dir_all(coverage_dir)?;
+    let coverage_map_path = coverage_dir.join(format!("{}.mvcov", package_name));
+    fs::rename(package_path.join(COVERAGE_MAP_FILE), &coverage_map_path)?;
+    let coverage_map = CoverageMap::from_binary_file(&coverage_map_path).to_unified_exec_map();
+
+    let mut summary = File::create(coverage_dir.join(format!("{}.coverage.txt", package_name)))?;
+    let (mut covered, mut total) = (0, 0);
+    for unit in &package.compiled_units {
+        if let CompiledUnit::Module(NamedCompiledModule { module, .. }) = &unit.unit {
+            let (module_covered, module_total) =
+                summarize_inst_cov(module, &coverage_map).summarize_human(&mut summary, true)?;
+            covered += module_covered;
+            total += module_total;
+        }
+    }
+    let coverage = if total == 0 {
+        100.0
+    } else {
+        covered as f64 * 100.0 / total as f64
+    };
+    writeln!(
+        summary,
+        "+-------------------------+\n| {}: {:.2}% ({}/{})",
+        package_name, coverage, covered, total
+    )?;
+
+    if let Some(threshold) = std::env::var_os(MOVE_COVERAGE_THRESHOLD) {
+        let threshold: f64 = threshold.to_string_lossy().parse()?;
+        ensure!(
+            coverage >= threshold,
+            "The unit tests of {} cover {:.2}% of its instructions, below the threshold {}%.",
+            package_name,
+            coverage,
+            threshold
+        );
+    }
+    Ok(coverage)
+}
@@ -15,6 +15,7 @@ use std::{collections::BTreeMap, path::PathBuf};
 use tempfile::tempdir;
 
 pub mod aptos;
+pub mod coverage;
 pub mod natives;
 pub mod release;
 
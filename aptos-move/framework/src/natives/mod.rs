@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod account;
+pub mod randomness;
 pub mod signature;
 
 use move_core_types::{account_address::AccountAddress, identifier::Identifier};
@@ -20,6 +21,11 @@ pub fn all_natives(framework_addr: AccountAddress) -> NativeFunctionTable {
             signature::native_ed25519_signature_verification,
         ),
         ("Account", "create_signer", account::native_create_signer),
+        (
+            "InsecureRandomness",
+            "next_entropy",
+            randomness::native_next_entropy,
+        ),
     ];
     NATIVES
         .iter()
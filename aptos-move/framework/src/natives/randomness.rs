@@ -0,0 +1,59 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::PartialVMResult;
+use move_vm_runtime::native_functions::NativeContext;
+use move_vm_types::{
+    gas_schedule::NativeCostIndex,
+    loaded_data::runtime_types::Type,
+    natives::function::{native_gas, NativeResult},
+    values::Value,
+};
+use smallvec::smallvec;
+use std::{cell::Cell, collections::VecDeque};
+
+/// The entropy of a session, distinct for each session and each draw within it, which
+/// `InsecureRandomness` mixes with the seed of the block. The randomness can only be drawn in the sessions
+/// of the transactions the VM allows to.
+#[derive(Tid)]
+pub struct NativeRandomnessContext {
+    session_hash: HashValue,
+    draws_allowed: bool,
+    draws: Cell<u64>,
+}
+
+impl NativeRandomnessContext {
+    pub fn new(session_hash: HashValue, draws_allowed: bool) -> Self {
+        Self {
+            session_hash,
+            draws_allowed,
+            draws: Cell::new(0),
+        }
+    }
+}
+
+pub fn native_next_entropy(
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    arguments: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(arguments.is_empty());
+
+    let randomness = context.extensions().get::<NativeRandomnessContext>();
+    let (bytes, entropy) = if randomness.draws_allowed {
+        let draw = randomness.draws.get();
+        randomness.draws.set(draw + 1);
+        let mut bytes = randomness.session_hash.to_vec();
+        bytes.extend_from_slice(&draw.to_le_bytes());
+        let entropy = HashValue::sha3_256_of(&bytes).to_vec();
+        (bytes, entropy)
+    } else {
+        (vec![], vec![])
+    };
+
+    let cost = native_gas(context.cost_table(), NativeCostIndex::SHA3_256, bytes.len());
+    Ok(NativeResult::ok(cost, smallvec![Value::vector_u8(entropy)]))
+}
@@ -2,25 +2,30 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_vm::natives::aptos_natives;
-use framework::path_in_crate;
+use framework::{coverage, path_in_crate};
 use move_cli::package::cli;
 use move_unit_test::UnitTestingConfig;
 use tempfile::tempdir;
 
 fn run_tests_for_pkg(path_to_pkg: impl Into<String>) {
     let pkg_path = path_in_crate(path_to_pkg);
+    let coverage_dir = coverage::coverage_dir();
+    let build_config = move_package::BuildConfig {
+        test_mode: true,
+        install_dir: Some(tempdir().unwrap().path().to_path_buf()),
+        ..Default::default()
+    };
     cli::run_move_unit_tests(
         &pkg_path,
-        move_package::BuildConfig {
-            test_mode: true,
-            install_dir: Some(tempdir().unwrap().path().to_path_buf()),
-            ..Default::default()
-        },
+        build_config.clone(),
         UnitTestingConfig::default_with_bound(Some(100_000)),
         aptos_natives(),
-        /* compute_coverage */ false,
+        /* compute_coverage */ coverage_dir.is_some(),
     )
     .unwrap();
+    if let Some(coverage_dir) = coverage_dir {
+        coverage::write_coverage_artifacts(&pkg_path, build_config, &coverage_dir).unwrap();
+    }
 }
 #[test]
 fn move_unit_tests() {
@@ -0,0 +1,24 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics::{register_int_counter, IntCounter};
+use once_cell::sync::Lazy;
+
+/// Count the number of transaction incarnations aborted by a failed validation.
+pub static PARALLEL_EXECUTION_ABORTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_parallel_executor_aborts",
+        "Number of transaction incarnations aborted by a failed validation"
+    )
+    .unwrap()
+});
+
+/// Count the number of transaction executions but the first one of each transaction, i.e. the
+/// executions of the incarnations following an abort or a read dependency.
+pub static PARALLEL_EXECUTION_REEXECUTIONS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_parallel_executor_reexecutions",
+        "Number of transaction executions but the first one of each transaction"
+    )
+    .unwrap()
+});
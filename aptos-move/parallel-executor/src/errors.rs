@@ -11,6 +11,9 @@ pub enum Error<E> {
     /// A transaction write to a key that wasn't estimated by the inferencer, abort the execution
     /// because we don't have a good way of handling read-after-write dependency. Will relax this limitation later.
     UnestimatedWrite,
+    /// A transaction was aborted more times than allowed by `ParallelExecutorConfig`, the block is
+    /// likely to have too many conflicts to be worth executing in parallel.
+    TooManyAborts,
     /// Execution of a thread yields a non-recoverable error, such error will be propagated back to
     /// the caller.
     UserError(E),
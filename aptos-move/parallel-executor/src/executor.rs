@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    counters::{PARALLEL_EXECUTION_ABORTS, PARALLEL_EXECUTION_REEXECUTIONS},
     errors::*,
     outcome_array::OutcomeArray,
     scheduler::{Scheduler, SchedulerTask, TaskGuard, TxnIndex, Version},
@@ -13,7 +14,16 @@ use mvhashmap::MVHashMap;
 use num_cpus;
 use once_cell::sync::Lazy;
 use rayon::prelude::*;
-use std::{collections::HashSet, hash::Hash, marker::PhantomData, sync::Arc, thread::spawn};
+use std::{
+    collections::HashSet,
+    hash::Hash,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::spawn,
+};
 
 static RAYON_EXEC_POOL: Lazy<rayon::ThreadPool> = Lazy::new(|| {
     rayon::ThreadPoolBuilder::new()
@@ -99,10 +109,30 @@ impl<'a, K: PartialOrd + Send + Clone + Hash + Eq, V: Send + Sync> MVHashMapView
     }
 }
 
+/// The tuning of the parallel execution.
+#[derive(Clone, Copy, Debug)]
+pub struct ParallelExecutorConfig {
+    /// Number of threads executing a block, at most the number of CPUs.
+    pub concurrency_level: usize,
+    /// Number of times a transaction may be aborted before the parallel execution of the block is
+    /// given up with `Error::TooManyAborts`, if any.
+    pub max_aborts_per_txn: Option<usize>,
+}
+
+impl Default for ParallelExecutorConfig {
+    fn default() -> Self {
+        Self {
+            concurrency_level: num_cpus::get(),
+            max_aborts_per_txn: None,
+        }
+    }
+}
+
 pub struct ParallelTransactionExecutor<T: Transaction, E: ExecutorTask> {
     // number of active concurrent tasks, corresponding to the maximum number of rayon
     // threads that may be concurrently participating in parallel execution.
     concurrency_level: usize,
+    max_aborts_per_txn: Option<usize>,
     phantom: PhantomData<(T, E)>,
 }
 
@@ -112,9 +142,13 @@ where
     E: ExecutorTask<T = T>,
 {
     pub fn new() -> Self {
+        Self::new_with_config(ParallelExecutorConfig::default())
+    }
+
+    pub fn new_with_config(config: ParallelExecutorConfig) -> Self {
         Self {
-            // TODO: must be a configurable parameter.
-            concurrency_level: num_cpus::get(),
+            concurrency_level: config.concurrency_level.clamp(1, num_cpus::get()),
+            max_aborts_per_txn: config.max_aborts_per_txn,
             phantom: PhantomData,
         }
     }
@@ -132,9 +166,13 @@ where
         versioned_data_cache: &MVHashMap<<T as Transaction>::Key, <T as Transaction>::Value>,
         scheduler: &'a Scheduler,
         executor: &E,
+        too_many_aborts: &AtomicBool,
     ) -> SchedulerTask<'a> {
         let (idx_to_execute, incarnation) = version;
         let txn = &signature_verified_block[idx_to_execute];
+        if incarnation > 0 {
+            PARALLEL_EXECUTION_REEXECUTIONS.inc();
+        }
 
         let state_view = MVHashMapView {
             versioned_map: versioned_data_cache,
@@ -184,6 +222,11 @@ where
         }
 
         last_input_output.record(idx_to_execute, state_view.take_reads(), result);
+        if matches!(self.max_aborts_per_txn, Some(max_aborts) if incarnation > max_aborts) {
+            // Drain the tasks, the block is to be executed sequentially.
+            too_many_aborts.store(true, Ordering::Relaxed);
+            scheduler.set_stop_idx(0);
+        }
         scheduler.finish_execution(idx_to_execute, incarnation, writes_outside, guard)
     }
 
@@ -215,6 +258,7 @@ where
         let aborted = !valid && scheduler.try_abort(idx_to_validate, incarnation);
 
         if aborted {
+            PARALLEL_EXECUTION_ABORTS.inc();
             // Not valid and successfully aborted, mark the latest write-set as estimates.
             for k in &last_input_output.write_set(idx_to_validate) {
                 versioned_data_cache.mark_estimate(k, idx_to_validate);
@@ -237,6 +281,7 @@ where
         >,
         versioned_data_cache: &MVHashMap<<T as Transaction>::Key, <T as Transaction>::Value>,
         scheduler: &Scheduler,
+        too_many_aborts: &AtomicBool,
     ) {
         // Make executor for each task. TODO: fast concurrent executor.
         let executor = E::init(*executor_arguments);
@@ -259,6 +304,7 @@ where
                     versioned_data_cache,
                     scheduler,
                     &executor,
+                    too_many_aborts,
                 ),
                 SchedulerTask::ExecutionTask(_, Some(condvar), _guard) => {
                     let (lock, cvar) = &*condvar;
@@ -291,6 +337,7 @@ where
         let outcomes = OutcomeArray::new(num_txns);
         let last_input_output = TxnLastInputOutput::new(num_txns);
         let scheduler = Scheduler::new(num_txns);
+        let too_many_aborts = AtomicBool::new(false);
 
         RAYON_EXEC_POOL.scope(|s| {
             for _ in 0..self.concurrency_level {
@@ -301,10 +348,14 @@ where
                         &last_input_output,
                         &versioned_data_cache,
                         &scheduler,
+                        &too_many_aborts,
                     );
                 });
             }
         });
+        if too_many_aborts.load(Ordering::Relaxed) {
+            return Err(Error::TooManyAborts);
+        }
 
         // Extract outputs in parallel
         let valid_results_size = scheduler.num_txn_to_execute();
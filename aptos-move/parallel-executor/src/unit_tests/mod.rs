@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    executor::ParallelTransactionExecutor,
+    executor::{ParallelExecutorConfig, ParallelTransactionExecutor},
     proptest_types::types::{ExpectedOutput, Task, Transaction},
     scheduler::{Scheduler, SchedulerTask, TaskGuard},
 };
@@ -135,6 +135,31 @@ fn early_skips() {
     run_and_assert(transactions)
 }
 
+#[test]
+fn single_thread_without_aborts() {
+    let mut transactions = vec![];
+    let keys: Vec<_> = (0..TXN_PER_BLOCK).map(|_| random::<[u8; 32]>()).collect();
+    for key in &keys {
+        transactions.push(Transaction::Write {
+            incarnation: Arc::new(AtomicUsize::new(0)),
+            reads: vec![keys.clone()],
+            writes: vec![vec![(*key, random::<u64>())]],
+        })
+    }
+
+    // A single thread executes the transactions in order, so none of them is aborted.
+    let config = ParallelExecutorConfig {
+        concurrency_level: 1,
+        max_aborts_per_txn: Some(0),
+    };
+    let output =
+        ParallelTransactionExecutor::<Transaction<_, _>, Task<_, _>>::new_with_config(config)
+            .execute_transactions_parallel((), transactions.clone());
+
+    let baseline = ExpectedOutput::generate_baseline(&transactions);
+    assert!(baseline.check_output(&output))
+}
+
 #[test]
 fn scheduler_tasks() {
     let s = Scheduler::new(6);
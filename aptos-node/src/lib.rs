@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_api::runtime::bootstrap as bootstrap_api;
+use aptos_api::runtime::{bootstrap_with_node_components as bootstrap_api, NodeComponents};
 use aptos_config::{
     config::{
         AptosDataClientConfig, DataStreamingServiceConfig, NetworkConfig, NodeConfig,
@@ -23,17 +23,25 @@ use aptos_types::{
     account_config::aptos_root_address,
     account_state::AccountState,
     chain_id::ChainId,
+    liveness::LivenessReporter,
     move_resource::MoveStorage,
     on_chain_config::{VMPublishingOption, ON_CHAIN_CONFIG_REGISTRY},
     state_store::state_key::StateKey,
     waypoint::Waypoint,
 };
-use aptos_vm::AptosVM;
+use aptos_vm::{
+    parallel_executor::{ParallelAptosVM, ParallelExecutorConfig},
+    AptosVM,
+};
 use aptosdb::AptosDB;
 use backup_service::start_backup_service;
-use consensus::consensus_provider::start_consensus;
+use consensus::{
+    consensus_observer::start_consensus_observer, consensus_provider::start_consensus,
+    diagnostics::LivenessDiagnostics,
+};
 use consensus_notifications::ConsensusNotificationListener;
 use data_streaming_service::{
+    download_throttle::DownloadThrottle,
     streaming_client::{new_streaming_service_client_listener_pair, StreamingServiceClient},
     streaming_service::DataStreamingService,
 };
@@ -80,9 +88,11 @@ pub struct AptosHandle {
     _api: Runtime,
     _backup: Runtime,
     _consensus_runtime: Option<Runtime>,
+    _consensus_observer_runtime: Option<Runtime>,
     _debug: NodeDebugService,
     _mempool: Runtime,
     _network_runtimes: Vec<Runtime>,
+    _rosetta: Option<Runtime>,
     _state_sync_runtimes: StateSyncRuntimes,
     _telemetry_runtime: Runtime,
 }
@@ -271,7 +281,7 @@ fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
     waypoint: Waypoint,
     event_subscription_service: EventSubscriptionService,
     db_rw: DbReaderWriter,
-) -> StateSyncRuntimes {
+) -> (StateSyncRuntimes, Arc<DownloadThrottle>) {
     // Start the state sync storage service
     let storage_service_runtime = setup_state_sync_storage_service(
         node_config.state_sync.storage_service,
@@ -288,10 +298,11 @@ fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
     );
 
     // Start the data streaming service
-    let (streaming_service_client, streaming_service_runtime) = setup_data_streaming_service(
-        node_config.state_sync.data_streaming_service,
-        aptos_data_client.clone(),
-    );
+    let (streaming_service_client, download_throttle, streaming_service_runtime) =
+        setup_data_streaming_service(
+            node_config.state_sync.data_streaming_service,
+            aptos_data_client.clone(),
+        );
 
     // Create the chunk executor
     let chunk_executor = Arc::new(
@@ -313,23 +324,25 @@ fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
     );
 
     // Create and return the new state sync handle
-    StateSyncRuntimes::new(
+    let state_sync_runtimes = StateSyncRuntimes::new(
         aptos_data_client_runtime,
         state_sync_multiplexer,
         storage_service_runtime,
         streaming_service_runtime,
-    )
+    );
+    (state_sync_runtimes, download_throttle)
 }
 
 fn setup_data_streaming_service(
     config: DataStreamingServiceConfig,
     aptos_data_client: AptosNetDataClient,
-) -> (StreamingServiceClient, Runtime) {
+) -> (StreamingServiceClient, Arc<DownloadThrottle>, Runtime) {
     // Create the data streaming service
     let (streaming_service_client, streaming_service_listener) =
         new_streaming_service_client_listener_pair();
     let data_streaming_service =
         DataStreamingService::new(config, aptos_data_client, streaming_service_listener);
+    let download_throttle = data_streaming_service.download_throttle();
 
     // Start the data streaming service
     let streaming_service_runtime = Builder::new_multi_thread()
@@ -339,7 +352,11 @@ fn setup_data_streaming_service(
         .expect("Failed to create data streaming service!");
     streaming_service_runtime.spawn(data_streaming_service.start_service());
 
-    (streaming_service_client, streaming_service_runtime)
+    (
+        streaming_service_client,
+        download_throttle,
+        streaming_service_runtime,
+    )
 }
 
 fn setup_aptos_data_client(
@@ -497,13 +514,33 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         metric_server::start_server(public_metric_host, public_metrics_port, true)
     });
 
+    if node_config.execution.profiling_enabled {
+        aptos_vm::profiling::enable_profiling(node_config.execution.max_profiled_transactions);
+    }
+    if node_config.execution.native_tracing_enabled {
+        aptos_vm::native_tracing::enable_native_tracing(
+            node_config.execution.max_native_traced_transactions,
+        );
+    }
+    let mut parallel_executor_config = ParallelExecutorConfig::default();
+    if let Some(concurrency_level) = node_config.execution.concurrency_level {
+        parallel_executor_config.concurrency_level = concurrency_level;
+    }
+    parallel_executor_config.max_aborts_per_txn = node_config.execution.max_aborts_per_txn;
+    ParallelAptosVM::set_config(parallel_executor_config);
+
     let mut instant = Instant::now();
     let (aptos_db, db_rw) = DbReaderWriter::wrap(
-        AptosDB::open(
+        AptosDB::open_with_state_shards(
             &node_config.storage.dir(),
+            &node_config.storage.state_shard_dirs(),
             false, /* readonly */
             node_config.storage.storage_pruner_config,
-            node_config.storage.rocksdb_config,
+            node_config.storage.rocksdb_config.clone(),
+            node_config.storage.state_cache_size,
+            node_config.storage.enable_event_type_index,
+            node_config.storage.enable_commit_pipelining,
+            node_config.storage.cold_ledger_config(),
         )
         .expect("DB should open."),
     );
@@ -532,6 +569,7 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
     let mut state_sync_network_handles = vec![];
     let mut mempool_network_handles = vec![];
     let mut consensus_network_handles = None;
+    let mut consensus_observer_network_handles = vec![];
     let mut storage_service_server_network_handles = vec![];
     let mut storage_service_client_network_handles = HashMap::new();
 
@@ -626,6 +664,20 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         );
         mempool_network_handles.push((network_id, mempool_sender, mempool_events));
 
+        // Create the endpoints to connect the Network to the consensus observer and
+        // publisher (if enabled), which serve the fullnodes.
+        let observer_config = node_config.consensus.observer;
+        if !network_id.is_validator_network()
+            && (observer_config.observer_enabled || observer_config.publisher_enabled)
+        {
+            let (observer_sender, observer_events) = network_builder.add_p2p_service(
+                &consensus::consensus_observer::network_interface::network_endpoint_config(
+                    observer_config.max_network_channel_size,
+                ),
+            );
+            consensus_observer_network_handles.push((network_id, observer_sender, observer_events));
+        }
+
         // Perform steps relevant specifically to Validator networks.
         if network_id.is_validator_network() {
             // A valid config is allowed to have at most one ValidatorNetwork
@@ -659,7 +711,7 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         );
 
     // Create the state sync runtimes
-    let state_sync_runtimes = create_state_sync_runtimes(
+    let (state_sync_runtimes, download_throttle) = create_state_sync_runtimes(
         node_config,
         storage_service_server_network_handles,
         storage_service_client_network_handles,
@@ -674,7 +726,31 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
 
     let (mp_client_sender, mp_client_events) = channel(AC_SMP_CHANNEL_BUFFER_SIZE);
 
-    let api_runtime = bootstrap_api(node_config, chain_id, aptos_db, mp_client_sender).unwrap();
+    let rosetta_runtime = aptos_rosetta::bootstrap(
+        node_config,
+        chain_id,
+        aptos_db.clone(),
+        mp_client_sender.clone(),
+    )
+    .unwrap();
+    // Only the nodes running consensus report its liveness
+    let liveness_diagnostics = consensus_network_handles
+        .as_ref()
+        .map(|_| Arc::new(LivenessDiagnostics::default()));
+    let api_runtime = bootstrap_api(
+        node_config,
+        chain_id,
+        aptos_db.clone(),
+        mp_client_sender,
+        NodeComponents {
+            db_checkpointer: Some(aptos_db),
+            download_throttle: Some(download_throttle),
+            liveness_diagnostics: liveness_diagnostics
+                .clone()
+                .map(|liveness_diagnostics| liveness_diagnostics as Arc<dyn LivenessReporter>),
+        },
+    )
+    .unwrap();
 
     let mut consensus_runtime = None;
     let (consensus_to_mempool_sender, consensus_requests) = channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
@@ -692,6 +768,29 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
     );
     debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
+    // Start the consensus publisher and observer (if enabled). Like consensus, the
+    // observer executes blocks so state sync has to be initialized first.
+    let consensus_observer_gate = consensus_notifier.consensus_observer_gate();
+    let consensus_notifier = Arc::new(consensus_notifier);
+    let mut consensus_observer_runtime = None;
+    let mut consensus_publisher = None;
+    if !consensus_observer_network_handles.is_empty() {
+        if node_config.consensus.observer.observer_enabled && !node_config.base.role.is_validator()
+        {
+            state_sync_runtimes.block_until_initialized();
+        }
+        let (runtime, publisher) = start_consensus_observer(
+            node_config,
+            consensus_observer_network_handles,
+            consensus_notifier.clone(),
+            consensus_observer_gate,
+            consensus_to_mempool_sender.clone(),
+            db_rw.clone(),
+        );
+        consensus_observer_runtime = Some(runtime);
+        consensus_publisher = publisher;
+    }
+
     // StateSync should be instantiated and started before Consensus to avoid a cyclic dependency:
     // network provider -> consensus -> state synchronizer -> network provider.  This has resulted
     // in a deadlock as observed in GitHub issue #749.
@@ -710,12 +809,14 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
             node_config,
             consensus_network_sender,
             consensus_network_events,
-            Arc::new(consensus_notifier),
+            consensus_notifier,
             consensus_to_mempool_sender,
             db_rw.clone(),
             consensus_reconfig_subscription
                 .expect("Consensus requires a reconfiguration subscription!"),
             peer_metadata_storage,
+            consensus_publisher,
+            liveness_diagnostics.expect("Consensus reports its liveness!"),
         ));
         debug!("Consensus started in {} ms", instant.elapsed().as_millis());
     }
@@ -740,9 +841,11 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         _api: api_runtime,
         _backup: backup_service,
         _consensus_runtime: consensus_runtime,
+        _consensus_observer_runtime: consensus_observer_runtime,
         _debug: debug_if,
         _mempool: mempool,
         _network_runtimes: network_runtimes,
+        _rosetta: rosetta_runtime,
         _state_sync_runtimes: state_sync_runtimes,
         _telemetry_runtime: telemery_runtime,
     }
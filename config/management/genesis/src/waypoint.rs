@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::config::{RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_config::config::{RocksdbConfig, DEFAULT_STATE_CACHE_SIZE, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_management::{config::ConfigPath, error::Error, secure_backend::SharedBackend};
 use aptos_temppath::TempPath;
 use aptos_types::{chain_id::ChainId, transaction::Transaction, waypoint::Waypoint};
@@ -45,6 +45,8 @@ pub fn create_genesis_waypoint(genesis: &Transaction) -> Result<Waypoint, Error>
         false,
         NO_OP_STORAGE_PRUNER_CONFIG,
         RocksdbConfig::default(),
+        DEFAULT_STATE_CACHE_SIZE,
+        false, /* enable_event_type_index */
     )
     .map_err(|e| Error::UnexpectedError(e.to_string()))?;
     let db_rw = DbReaderWriter::new(aptosdb);
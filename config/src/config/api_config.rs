@@ -3,7 +3,7 @@
 
 use crate::utils;
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::PathBuf};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -15,14 +15,154 @@ pub struct ApiConfig {
     pub tls_cert_path: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tls_key_path: Option<String>,
+    // requires the clients to present a certificate signed by this CA (mutual TLS), only
+    // valid with `tls_cert_path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_client_ca_path: Option<String>,
     // optional for compatible with old configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_length_limit: Option<u64>,
+    // max number of transactions submitted in one batch request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_submit_transaction_batch_size: Option<usize>,
+    // compresses responses when the client accepts gzip or brotli encoding
+    #[serde(default = "default_enabled")]
+    pub compression_enabled: bool,
+    // rate limiting is disabled when not configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<ApiRateLimitConfig>,
+    // the requests are accepted without an API key when not configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_keys: Option<ApiKeysConfig>,
+    #[serde(default)]
+    pub cors: CorsConfig,
+    // shared secret of the admin routes (e.g. /admin/failpoints), which are disabled when
+    // not configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_token: Option<String>,
+    // the dir `/admin/checkpoint` creates the checkpoints of the DB in, which is disabled when
+    // not configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint_dir: Option<PathBuf>,
+    // access logging is disabled when not configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_log: Option<ApiAccessLogConfig>,
+    // serves the GraphQL endpoint `/graphql` when enabled
+    #[serde(default)]
+    pub graphql_enabled: bool,
+    // serves the Rosetta API on this address when configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rosetta_address: Option<SocketAddr>,
+    // serves the gRPC transaction stream on this address when configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grpc_address: Option<SocketAddr>,
+    #[serde(default)]
+    pub response_cache: ApiResponseCacheConfig,
+    // the API is also served on these addresses, e.g. on both IPv4 and IPv6 interfaces,
+    // each with its own TLS setting
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_addresses: Vec<ApiListenAddress>,
+}
+
+/// An address the API server listens on, with TLS when the certificate path is given, and
+/// mutual TLS when the client CA path is given as well.
+///
+/// Note that on most systems, binding the IPv6 unspecified address `[::]` also accepts IPv4
+/// connections on the same port, in which case `0.0.0.0` can't be bound to the port as well.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiListenAddress {
+    pub address: SocketAddr,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_cert_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_key_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls_client_ca_path: Option<String>,
+}
+
+/// Token bucket rate limiting applied per client, a client is identified by the API token
+/// in the request header when the token is given, otherwise by the remote IP address.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiRateLimitConfig {
+    /// The rate at which the tokens of a client's bucket are refilled.
+    pub requests_per_second: u32,
+    /// The capacity of a client's bucket, i.e. the max number of requests in a burst.
+    pub burst_size: u32,
+}
+
+/// The API keys accepted in the `X-Aptos-Api-Key` request header, each with its own quota.
+/// The requests without a known key are rejected unless `allow_anonymous` is set.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiKeysConfig {
+    pub keys: Vec<ApiKeyConfig>,
+    /// Accepts the requests without an API key, they are not accounted to any key.
+    pub allow_anonymous: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyConfig {
+    /// The name the usage of the key is reported with, so the key itself is never reported.
+    pub name: String,
+    pub key: String,
+    /// The max number of requests per UTC day, unlimited when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_quota: Option<u64>,
+    /// The max number of requests per minute, unlimited when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_per_minute: Option<u32>,
+}
+
+/// Logs a random sample of the API requests with their JSON bodies, so that the requests of
+/// a client can be replayed when debugging its issues. The signatures in the logged bodies
+/// are redacted.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiAccessLogConfig {
+    /// The fraction of the requests logged, from 0.0 (none) to 1.0 (all).
+    pub sample_rate: f64,
+    /// The JSON request and response bodies larger than this size are not logged, no body
+    /// is logged when it is 0.
+    pub max_body_bytes: usize,
+}
+
+/// Limits of the in-process cache of the responses of immutable data, e.g. committed
+/// transactions and historical blocks.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiResponseCacheConfig {
+    /// The max number of cached responses, caching is disabled when it is 0.
+    pub max_entries: usize,
+    /// The max total size of the cached response bodies.
+    pub max_bytes: usize,
+}
+
+/// Cross-origin resource sharing policy of the API server.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    /// The origins allowed to make cross-origin requests, any origin is allowed when empty.
+    pub allowed_origins: Vec<String>,
+    /// The methods allowed in cross-origin requests.
+    pub allowed_methods: Vec<String>,
+    /// The headers allowed in cross-origin requests in addition to `Content-Type`,
+    /// `X-Aptos-Api-Key` and `Idempotency-Key`, which are always allowed.
+    pub allowed_headers: Vec<String>,
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
 pub const DEFAULT_PORT: u16 = 8080;
 pub const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 4 * 1024 * 1024; // 4mb
+pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 100;
+pub const DEFAULT_RATE_LIMIT_REQUESTS_PER_SECOND: u32 = 100;
+pub const DEFAULT_RATE_LIMIT_BURST_SIZE: u32 = 200;
+pub const DEFAULT_ACCESS_LOG_SAMPLE_RATE: f64 = 0.01;
+pub const DEFAULT_ACCESS_LOG_MAX_BODY_BYTES: usize = 16 * 1024; // 16kb
+pub const DEFAULT_RESPONSE_CACHE_MAX_ENTRIES: usize = 10_000;
+pub const DEFAULT_RESPONSE_CACHE_MAX_BYTES: usize = 64 * 1024 * 1024; // 64mb
 
 fn default_enabled() -> bool {
     true
@@ -37,7 +177,58 @@ impl Default for ApiConfig {
                 .unwrap(),
             tls_cert_path: None,
             tls_key_path: None,
+            tls_client_ca_path: None,
             content_length_limit: None,
+            max_submit_transaction_batch_size: None,
+            compression_enabled: default_enabled(),
+            rate_limit: None,
+            api_keys: None,
+            cors: CorsConfig::default(),
+            admin_token: None,
+            checkpoint_dir: None,
+            access_log: None,
+            graphql_enabled: false,
+            rosetta_address: None,
+            grpc_address: None,
+            response_cache: ApiResponseCacheConfig::default(),
+            additional_addresses: vec![],
+        }
+    }
+}
+
+impl Default for ApiRateLimitConfig {
+    fn default() -> ApiRateLimitConfig {
+        ApiRateLimitConfig {
+            requests_per_second: DEFAULT_RATE_LIMIT_REQUESTS_PER_SECOND,
+            burst_size: DEFAULT_RATE_LIMIT_BURST_SIZE,
+        }
+    }
+}
+
+impl Default for ApiAccessLogConfig {
+    fn default() -> ApiAccessLogConfig {
+        ApiAccessLogConfig {
+            sample_rate: DEFAULT_ACCESS_LOG_SAMPLE_RATE,
+            max_body_bytes: DEFAULT_ACCESS_LOG_MAX_BODY_BYTES,
+        }
+    }
+}
+
+impl Default for ApiResponseCacheConfig {
+    fn default() -> ApiResponseCacheConfig {
+        ApiResponseCacheConfig {
+            max_entries: DEFAULT_RESPONSE_CACHE_MAX_ENTRIES,
+            max_bytes: DEFAULT_RESPONSE_CACHE_MAX_BYTES,
+        }
+    }
+}
+
+impl Default for CorsConfig {
+    fn default() -> CorsConfig {
+        CorsConfig {
+            allowed_origins: vec![],
+            allowed_methods: vec!["GET".to_owned(), "POST".to_owned()],
+            allowed_headers: vec![],
         }
     }
 }
@@ -45,6 +236,22 @@ impl Default for ApiConfig {
 impl ApiConfig {
     pub fn randomize_ports(&mut self) {
         self.address.set_port(utils::get_available_port());
+        for listen_address in &mut self.additional_addresses {
+            listen_address.address.set_port(utils::get_available_port());
+        }
+    }
+
+    /// Returns all addresses the API server listens on, starting with `address`.
+    pub fn listen_addresses(&self) -> Vec<ApiListenAddress> {
+        let primary = ApiListenAddress {
+            address: self.address,
+            tls_cert_path: self.tls_cert_path.clone(),
+            tls_key_path: self.tls_key_path.clone(),
+            tls_client_ca_path: self.tls_client_ca_path.clone(),
+        };
+        std::iter::once(primary)
+            .chain(self.additional_addresses.iter().cloned())
+            .collect()
     }
 
     pub fn content_length_limit(&self) -> u64 {
@@ -53,4 +260,9 @@ impl ApiConfig {
             None => DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT,
         }
     }
+
+    pub fn max_submit_transaction_batch_size(&self) -> usize {
+        self.max_submit_transaction_batch_size
+            .unwrap_or(DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE)
+    }
 }
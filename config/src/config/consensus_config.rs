@@ -11,6 +11,18 @@ use std::{collections::HashMap, path::PathBuf};
 pub struct ConsensusConfig {
     pub contiguous_rounds: u32,
     pub max_block_size: u64,
+    // Max total size in bytes of the transactions of a proposed block
+    pub max_block_bytes: u64,
+    // Max total gas of the transactions of a proposed block, estimated by their max gas amounts,
+    // so that blocks full of expensive transactions don't blow the execution deadlines
+    pub max_block_gas: u64,
+    // Max number of failed proposers of the previous rounds added to a proposed block, which the
+    // leader reputation takes into account. They're only added once the on-chain version
+    // supports them.
+    pub max_failed_authors_to_store: usize,
+    // Number of the last committed blocks whose transactions are excluded from the proposed
+    // blocks, as mempool may not have been notified of their commit yet.
+    pub max_committed_blocks_to_exclude: usize,
     pub max_pruned_blocks_in_mem: usize,
     // Timeout for consensus to get an ack from mempool for executed transactions (in milliseconds)
     pub mempool_executed_txn_timeout_ms: u64,
@@ -26,6 +38,7 @@ pub struct ConsensusConfig {
     // the period = (poll_count - 1) * 30ms
     pub mempool_poll_count: u64,
     pub channel_size: usize,
+    pub observer: ConsensusObserverConfig,
 }
 
 impl Default for ConsensusConfig {
@@ -33,18 +46,22 @@ impl Default for ConsensusConfig {
         ConsensusConfig {
             contiguous_rounds: 2,
             max_block_size: 3000,
+            max_block_bytes: 5 * 1024 * 1024, // 5MB
+            max_block_gas: 1_000_000_000,
+            max_failed_authors_to_store: 10,
+            max_committed_blocks_to_exclude: 5,
             max_pruned_blocks_in_mem: 100,
             mempool_txn_pull_timeout_ms: 1000,
             mempool_executed_txn_timeout_ms: 1000,
             round_initial_timeout_ms: 1000,
-            proposer_type: ConsensusProposerType::LeaderReputation(LeaderReputationConfig {
-                active_weights: 99,
-                inactive_weights: 1,
-            }),
+            proposer_type: ConsensusProposerType::LeaderReputation(
+                LeaderReputationConfig::default(),
+            ),
             safety_rules: SafetyRulesConfig::default(),
             sync_only: false,
             mempool_poll_count: 20,
             channel_size: 30, // hard-coded
+            observer: ConsensusObserverConfig::default(),
         }
     }
 }
@@ -55,6 +72,37 @@ impl ConsensusConfig {
     }
 }
 
+/// The consensus observer lets fullnodes follow consensus: the upstream nodes publish the ordered
+/// blocks and the commit decisions to their subscribers, which execute the ordered blocks ahead of
+/// the commit decisions instead of waiting to sync the committed transactions.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsensusObserverConfig {
+    // Subscribe to the upstream nodes and execute the blocks they publish (fullnodes only)
+    pub observer_enabled: bool,
+    // Publish the ordered blocks and the commit decisions to the subscribed downstream nodes
+    pub publisher_enabled: bool,
+    // Max number of pending messages in the network channel
+    pub max_network_channel_size: usize,
+    // Max number of ordered blocks executed ahead of the commit decisions
+    pub max_pending_blocks: usize,
+    // State sync takes over again if no commit decision is applied for this long (in
+    // milliseconds), e.g. when the upstream nodes are unavailable
+    pub observer_fallback_duration_ms: u64,
+}
+
+impl Default for ConsensusObserverConfig {
+    fn default() -> ConsensusObserverConfig {
+        ConsensusObserverConfig {
+            observer_enabled: false,
+            publisher_enabled: false,
+            max_network_channel_size: 1000,
+            max_pending_blocks: 100,
+            observer_fallback_duration_ms: 10_000,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum ConsensusProposerType {
@@ -71,8 +119,24 @@ pub enum ConsensusProposerType {
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
-#[serde(deny_unknown_fields)]
+#[serde(default, deny_unknown_fields)]
 pub struct LeaderReputationConfig {
     pub active_weights: u64,
     pub inactive_weights: u64,
+    // Weight of the candidates whose failed proposals in the window exceed the threshold below
+    pub failed_weights: u64,
+    // Percentage of failed out of all proposals of a candidate in the window, above which the
+    // candidate is given the failed weight
+    pub failure_threshold_percent: u32,
+}
+
+impl Default for LeaderReputationConfig {
+    fn default() -> LeaderReputationConfig {
+        LeaderReputationConfig {
+            active_weights: 99,
+            inactive_weights: 1,
+            failed_weights: 1,
+            failure_threshold_percent: 10,
+        }
+    }
 }
@@ -19,6 +19,28 @@ pub struct ExecutionConfig {
     pub genesis: Option<Transaction>,
     pub genesis_file_location: PathBuf,
     pub network_timeout_ms: u64,
+    // Records the wall time, gas used and state reads and writes of each user transaction
+    // executed, reported by the `/admin/execution_profile` endpoint of the API.
+    pub profiling_enabled: bool,
+    // Number of the last executed user transactions whose profiles are kept.
+    pub max_profiled_transactions: usize,
+    // Records the size of the arguments, gas charged and wall time of each call of a native
+    // function by a user transaction, reported by the `/admin/native_trace` endpoint of the API.
+    pub native_tracing_enabled: bool,
+    // Number of the last executed user transactions whose native calls are kept.
+    pub max_native_traced_transactions: usize,
+    // Dir the write sets and events of the transactions of each executed block are dumped to, if
+    // any, to be compared with `aptos-storage-inspector diff-trace` when the validators disagree
+    // on the state of a block.
+    pub trace_dir: Option<PathBuf>,
+    // Number of the last executed blocks whose traces are kept in `trace_dir`.
+    pub max_traced_blocks: usize,
+    // Number of threads executing a block in parallel, at most the number of CPUs. Defaults to
+    // the number of CPUs.
+    pub concurrency_level: Option<usize>,
+    // Number of times a transaction may be aborted while executing a block in parallel before
+    // falling back to the sequential execution of the block, if any.
+    pub max_aborts_per_txn: Option<usize>,
 }
 
 impl std::fmt::Debug for ExecutionConfig {
@@ -44,6 +66,14 @@ impl Default for ExecutionConfig {
             genesis_file_location: PathBuf::new(),
             // Default value of 30 seconds for the network timeout.
             network_timeout_ms: 30_000,
+            profiling_enabled: false,
+            max_profiled_transactions: 1000,
+            native_tracing_enabled: false,
+            max_native_traced_transactions: 1000,
+            trace_dir: None,
+            max_traced_blocks: 1000,
+            concurrency_level: None,
+            max_aborts_per_txn: None,
         }
     }
 }
@@ -1,24 +1,76 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_types::account_address::AccountAddress;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MempoolConfig {
     pub capacity: usize,
-    pub capacity_per_user: usize,
+    // max number of transactions of a sender in mempool
+    #[serde(alias = "capacity_per_user")]
+    pub max_transactions_per_sender: usize,
+    // max total size in bytes of the transactions of a sender in mempool
+    pub max_bytes_per_sender: usize,
     // number of failovers to broadcast to when the primary network is alive
     pub default_failovers: usize,
     pub max_broadcasts_per_peer: usize,
     pub mempool_snapshot_interval_secs: u64,
     pub shared_mempool_ack_timeout_ms: u64,
     pub shared_mempool_backoff_interval_ms: u64,
+    // when mempool is filled above this percentage of its capacity, incoming broadcasts are
+    // answered with a backoff message asking the sender to pause broadcasting for
+    // `shared_mempool_backoff_interval_ms`
+    pub shared_mempool_backoff_threshold_percent: u64,
+    // the longest pause a backoff message of a peer is honored for, longer pauses are cut to it
+    pub shared_mempool_max_backoff_interval_ms: u64,
     pub shared_mempool_batch_size: usize,
     pub shared_mempool_max_concurrent_inbound_syncs: usize,
     pub shared_mempool_tick_interval_ms: u64,
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
+    // transactions with a lower gas unit price are rejected, 0 accepts any gas unit price
+    pub min_gas_unit_price: u64,
+    // the min gas unit prices of the priority lanes above the lowest lane, consensus pulls the
+    // transactions of a higher lane before those of the lower lanes. Empty by default, all the
+    // transactions being in a single lane.
+    pub gas_price_lanes: Vec<u64>,
+    // a pending transaction is replaced by any transaction of the same sequence number whose gas
+    // unit price is higher by at least this percentage
+    pub replace_by_fee_min_bump_percent: u64,
+    // how the transaction to evict is picked when mempool is full
+    pub eviction_policy: MempoolEvictionPolicyType,
+    // the accepted transactions are logged to this file and replayed at startup, they are lost
+    // on restart when not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persistence_path: Option<PathBuf>,
+    // the transactions denied by this filter are rejected before VM validation
+    pub transaction_filter: TransactionFilterConfig,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransactionFilterConfig {
+    // the transactions of these senders are rejected
+    pub denied_senders: Vec<AccountAddress>,
+    // only these senders can publish modules when set, any sender can when not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_publishers: Option<Vec<AccountAddress>>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolEvictionPolicyType {
+    // evicts the last non-ready transaction of a random sender
+    ParkingLot,
+    // evicts the transaction closest to its system expiration time
+    Ttl,
+    // evicts the transaction with the lowest gas price when the incoming one pays more
+    LowestGasFirst,
+    // evicts the last transaction of the sender with the most transactions
+    FairShare,
 }
 
 impl Default for MempoolConfig {
@@ -26,16 +78,25 @@ impl Default for MempoolConfig {
         MempoolConfig {
             shared_mempool_tick_interval_ms: 50,
             shared_mempool_backoff_interval_ms: 30_000,
+            shared_mempool_backoff_threshold_percent: 90,
+            shared_mempool_max_backoff_interval_ms: 60_000,
             shared_mempool_batch_size: 100,
             shared_mempool_ack_timeout_ms: 2_000,
             shared_mempool_max_concurrent_inbound_syncs: 2,
             max_broadcasts_per_peer: 1,
             mempool_snapshot_interval_secs: 180,
             capacity: 1_000_000,
-            capacity_per_user: 100,
+            max_transactions_per_sender: 100,
+            max_bytes_per_sender: 10 * 1024 * 1024,
             default_failovers: 3,
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
+            min_gas_unit_price: 0,
+            gas_price_lanes: vec![],
+            replace_by_fee_min_bump_percent: 10,
+            eviction_policy: MempoolEvictionPolicyType::ParkingLot,
+            persistence_path: None,
+            transaction_filter: TransactionFilterConfig::default(),
         }
     }
 }
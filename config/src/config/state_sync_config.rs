@@ -56,7 +56,9 @@ impl Default for StateSyncConfig {
 }
 
 /// The bootstrapping mode determines how the node will bootstrap to the latest
-/// blockchain state, e.g., directly download the latest account states.
+/// blockchain state, e.g., directly download the latest account states. Once
+/// the account states have been downloaded, a node that falls behind (e.g.,
+/// after a restart) catches up by applying transaction outputs.
 #[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum BootstrappingMode {
     ApplyTransactionOutputsFromGenesis, // Applies transaction outputs (starting at genesis)
@@ -81,6 +83,7 @@ pub struct StateSyncDriverConfig {
     pub continuous_syncing_mode: ContinuousSyncingMode, // The mode by which to sync after bootstrapping
     pub progress_check_interval_ms: u64, // The interval (ms) at which to check state sync progress
     pub max_connection_deadline_secs: u64, // The max time (secs) to wait for connections from peers
+    pub max_parallel_executed_chunks: u64, // The max number of transaction chunks to execute in parallel (1 = off)
     pub max_pending_data_chunks: u64, // The max number of data chunks pending execution or commit
     pub max_stream_wait_time_ms: u64, // The max time (ms) to wait for a data stream notification
 }
@@ -95,6 +98,7 @@ impl Default for StateSyncDriverConfig {
             continuous_syncing_mode: ContinuousSyncingMode::ApplyTransactionOutputs,
             progress_check_interval_ms: 100,
             max_connection_deadline_secs: 10,
+            max_parallel_executed_chunks: 4,
             max_pending_data_chunks: 100,
             max_stream_wait_time_ms: 10_000,
         }
@@ -141,6 +145,11 @@ pub struct DataStreamingServiceConfig {
     // messages will be retrieved using FIFO ordering.
     pub max_data_stream_channel_sizes: u64,
 
+    // Maximum number of bytes per second to download from the network (across
+    // all streams), outside of the off-peak hours. If not set, downloads are not
+    // throttled. If 0, downloads are paused.
+    pub max_download_bytes_per_sec: Option<u64>,
+
     // Maximum number of retries for a single client request before a data
     // stream will terminate.
     pub max_request_retry: u64,
@@ -149,6 +158,16 @@ pub struct DataStreamingServiceConfig {
     // memory. Once the number grows beyond this value, garbage collection occurs.
     pub max_notification_id_mappings: u64,
 
+    // The hours of the day during which downloads are throttled by
+    // `off_peak_max_download_bytes_per_sec` (instead of
+    // `max_download_bytes_per_sec`). If not set, there are no off-peak hours.
+    pub off_peak_hours: Option<OffPeakHours>,
+
+    // Maximum number of bytes per second to download from the network (across
+    // all streams) during the off-peak hours. If not set, downloads are not
+    // throttled during the off-peak hours.
+    pub off_peak_max_download_bytes_per_sec: Option<u64>,
+
     // The interval (milliseconds) at which to check the progress of each stream.
     pub progress_check_interval_ms: u64,
 }
@@ -159,13 +178,50 @@ impl Default for DataStreamingServiceConfig {
             global_summary_refresh_interval_ms: 300,
             max_concurrent_requests: 1,
             max_data_stream_channel_sizes: 1000,
+            max_download_bytes_per_sec: None,
             max_request_retry: 3,
             max_notification_id_mappings: 2000,
+            off_peak_hours: None,
+            off_peak_max_download_bytes_per_sec: None,
             progress_check_interval_ms: 100,
         }
     }
 }
 
+impl DataStreamingServiceConfig {
+    /// Returns the maximum number of bytes per second to download at the given
+    /// hour of the day (UTC), or `None` if downloads are not throttled.
+    pub fn max_download_bytes_per_sec_at(&self, hour: u8) -> Option<u64> {
+        match self.off_peak_hours {
+            Some(off_peak_hours) if off_peak_hours.contains(hour) => {
+                self.off_peak_max_download_bytes_per_sec
+            }
+            _ => self.max_download_bytes_per_sec,
+        }
+    }
+}
+
+/// A daily window of hours (UTC), from `start_hour` (inclusive) to `end_hour`
+/// (exclusive). The window wraps around midnight if `end_hour` is lower than
+/// `start_hour`, e.g., from 22 to 6.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct OffPeakHours {
+    pub start_hour: u8,
+    pub end_hour: u8,
+}
+
+impl OffPeakHours {
+    /// Returns true iff the given hour of the day (UTC) is in the window
+    pub fn contains(&self, hour: u8) -> bool {
+        if self.start_hour <= self.end_hour {
+            self.start_hour <= hour && hour < self.end_hour
+        } else {
+            self.start_hour <= hour || hour < self.end_hour
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct AptosDataClientConfig {
@@ -4,6 +4,7 @@
 use crate::utils;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
 };
@@ -11,11 +12,64 @@ use std::{
 /// Port selected RocksDB options for tuning underlying rocksdb instance of AptosDB.
 /// see https://github.com/facebook/rocksdb/blob/master/include/rocksdb/options.h
 /// for detailed explanations.
-#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct RocksdbConfig {
     pub max_open_files: i32,
     pub max_total_wal_size: u64,
+    /// Options of the column families not listed in `column_families`.
+    pub default_column_family: RocksdbColumnFamilyConfig,
+    /// Options of individual column families, by column family name, e.g. a larger write
+    /// buffer for the event column family of event heavy workloads.
+    pub column_families: BTreeMap<String, RocksdbColumnFamilyConfig>,
+}
+
+impl RocksdbConfig {
+    pub fn column_family(&self, name: &str) -> &RocksdbColumnFamilyConfig {
+        self.column_families
+            .get(name)
+            .unwrap_or(&self.default_column_family)
+    }
+}
+
+/// Port selected RocksDB options of a column family.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RocksdbColumnFamilyConfig {
+    /// Size of the LRU block cache of the column family, in bytes.
+    pub block_cache_size: usize,
+    pub compression: RocksdbCompressionType,
+    /// Size of the memtable of the column family, in bytes, before it's flushed to disk.
+    pub write_buffer_size: usize,
+    pub compaction_style: RocksdbCompactionStyle,
+}
+
+impl Default for RocksdbColumnFamilyConfig {
+    fn default() -> Self {
+        Self {
+            // Same as the RocksDB defaults.
+            block_cache_size: 8 << 20,
+            compression: RocksdbCompressionType::Lz4,
+            write_buffer_size: 64 << 20,
+            compaction_style: RocksdbCompactionStyle::Level,
+        }
+    }
+}
+
+/// The compression types supported by the RocksDB build of AptosDB.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbCompressionType {
+    None,
+    Lz4,
+    Lz4hc,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbCompactionStyle {
+    Level,
+    Universal,
 }
 
 impl Default for RocksdbConfig {
@@ -28,6 +82,8 @@ impl Default for RocksdbConfig {
             // families are updated at non-uniform frequencies.
             #[allow(clippy::integer_arithmetic)] // TODO: remove once clippy lint fixed
             max_total_wal_size: 1u64 << 30,
+            default_column_family: RocksdbColumnFamilyConfig::default(),
+            column_families: BTreeMap::new(),
         }
     }
 }
@@ -46,11 +102,51 @@ pub struct StorageConfig {
     pub timeout_ms: u64,
     /// Rocksdb-specific configurations
     pub rocksdb_config: RocksdbConfig,
+    /// Number of state values, with proof, read at a version that are cached in memory; 0
+    /// disables the cache
+    pub state_cache_size: usize,
+    /// Index the events by their Move type as they are committed, so the events of a type can be
+    /// looked up across all event streams. Costs an extra record per event; only the events
+    /// committed while enabled are indexed.
+    pub enable_event_type_index: bool,
+    /// Dirs of the RocksDB instances the state Merkle tree is sharded across, e.g. one on each
+    /// NVMe device, by the hash of the state key; empty keeps it in the main DB. The number of
+    /// dirs must divide 16 and, once the DB has state, can only be changed from none by the
+    /// `migrate-state-to-shards` command of the storage inspector. Relative dirs are relative to
+    /// the data dir.
+    pub state_shard_dirs: Vec<PathBuf>,
+    /// Commit the state Merkle tree update of the transactions in the background, after their
+    /// ledger, so the commit latency isn't bounded by the tree update. The reads of the state of
+    /// the transactions wait for it to be committed.
+    pub enable_commit_pipelining: bool,
+    /// The optional tier the old transactions and events are moved to; None keeps them all in
+    /// the main DB.
+    pub cold_ledger_config: Option<ColdLedgerConfig>,
+}
+
+/// The transactions and events older than `hot_window` versions are moved from the main DB to a
+/// RocksDB instance in `dir`, e.g. on a cheaper and slower disk, and read from there transparently.
+/// Once anything is moved, the DB can't be opened without the tier. The pruner doesn't prune the
+/// data moved to the tier.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColdLedgerConfig {
+    /// Relative dirs are relative to the data dir.
+    pub dir: PathBuf,
+    /// Number of the latest versions whose transactions and events are kept in the main DB.
+    pub hot_window: u64,
+    /// Maximum number of versions moved per batch, to avoid spikes in disk IO.
+    pub max_versions_to_move_per_batch: u64,
 }
 
+pub const DEFAULT_STATE_CACHE_SIZE: usize = 10_000;
+
 pub const NO_OP_STORAGE_PRUNER_CONFIG: StoragePrunerConfig = StoragePrunerConfig {
     state_store_prune_window: None,
     default_prune_window: None,
+    transaction_store_prune_window: None,
+    event_store_prune_window: None,
+    write_set_prune_window: None,
     max_version_to_prune_per_batch: Some(100),
 };
 
@@ -64,6 +160,14 @@ pub struct StoragePrunerConfig {
     /// being big in size, we might want to configure a smaller window for state store vs other
     /// store.
     pub default_prune_window: Option<u64>,
+    /// Pruning windows of the transaction, event and write set stores, overriding the default
+    /// pruning window when specified, e.g. to keep transactions much longer than the state.
+    #[serde(default)]
+    pub transaction_store_prune_window: Option<u64>,
+    #[serde(default)]
+    pub event_store_prune_window: Option<u64>,
+    #[serde(default)]
+    pub write_set_prune_window: Option<u64>,
 
     /// Maximum version to prune per batch, should not be too large to avoid spike in disk IO caused
     /// by large batches in the pruner.
@@ -79,6 +183,9 @@ impl StoragePrunerConfig {
         StoragePrunerConfig {
             state_store_prune_window,
             default_prune_window: default_store_prune_window,
+            transaction_store_prune_window: None,
+            event_store_prune_window: None,
+            write_set_prune_window: None,
             max_version_to_prune_per_batch,
         }
     }
@@ -100,12 +207,20 @@ impl Default for StorageConfig {
             storage_pruner_config: StoragePrunerConfig {
                 state_store_prune_window: Some(1_000_000),
                 default_prune_window: Some(10_000_000),
+                transaction_store_prune_window: None,
+                event_store_prune_window: None,
+                write_set_prune_window: None,
                 max_version_to_prune_per_batch: Some(100),
             },
             data_dir: PathBuf::from("/opt/aptos/data"),
             // Default read/write/connection timeout, in milliseconds
             timeout_ms: 30_000,
             rocksdb_config: RocksdbConfig::default(),
+            state_cache_size: DEFAULT_STATE_CACHE_SIZE,
+            enable_event_type_index: false,
+            state_shard_dirs: vec![],
+            enable_commit_pipelining: true,
+            cold_ledger_config: None,
         }
     }
 }
@@ -119,6 +234,32 @@ impl StorageConfig {
         }
     }
 
+    pub fn state_shard_dirs(&self) -> Vec<PathBuf> {
+        self.state_shard_dirs
+            .iter()
+            .map(|dir| {
+                if dir.is_relative() {
+                    self.data_dir.join(dir)
+                } else {
+                    dir.clone()
+                }
+            })
+            .collect()
+    }
+
+    pub fn cold_ledger_config(&self) -> Option<ColdLedgerConfig> {
+        self.cold_ledger_config
+            .as_ref()
+            .map(|config| ColdLedgerConfig {
+                dir: if config.dir.is_relative() {
+                    self.data_dir.join(&config.dir)
+                } else {
+                    config.dir.clone()
+                },
+                ..config.clone()
+            })
+    }
+
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
         self.data_dir = data_dir;
     }
@@ -129,3 +270,30 @@ impl StorageConfig {
             .set_port(utils::get_available_port());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_column_family_config() {
+        let config: RocksdbConfig = serde_yaml::from_str(
+            r#"
+            column_families:
+                event:
+                    write_buffer_size: 268435456
+                    compaction_style: universal
+            "#,
+        )
+        .unwrap();
+
+        let event = config.column_family("event");
+        assert_eq!(event.write_buffer_size, 256 << 20);
+        assert_eq!(event.compaction_style, RocksdbCompactionStyle::Universal);
+        assert_eq!(event.compression, RocksdbCompressionType::Lz4);
+        assert_eq!(
+            *config.column_family("transaction"),
+            RocksdbColumnFamilyConfig::default()
+        );
+    }
+}
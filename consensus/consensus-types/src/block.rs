@@ -12,7 +12,7 @@ use aptos_infallible::duration_since_epoch;
 use aptos_types::{
     account_address::AccountAddress,
     block_info::BlockInfo,
-    block_metadata::BlockMetadata,
+    block_metadata::{BlockMetadata, BlockMetadataExt},
     epoch_state::EpochState,
     ledger_info::LedgerInfo,
     transaction::{Transaction, Version},
@@ -224,7 +224,7 @@ impl Block {
         match self.block_data.block_type() {
             BlockType::Genesis => bail!("We should not accept genesis from others"),
             BlockType::NilBlock => self.quorum_cert().verify(validator),
-            BlockType::Proposal { author, .. } => {
+            BlockType::Proposal { author, .. } | BlockType::ProposalExt { author, .. } => {
                 let signature = self
                     .signature
                     .as_ref()
@@ -290,7 +290,17 @@ impl Block {
     }
 
     pub fn transactions_to_execute(&self) -> Vec<Transaction> {
-        std::iter::once(Transaction::BlockMetadata(self.into()))
+        let block_metadata = match self.block_data.failed_authors() {
+            Some(failed_authors) => Transaction::BlockMetadataExt(BlockMetadataExt::new(
+                self.into(),
+                failed_authors
+                    .iter()
+                    .map(|(_round, author)| *author)
+                    .collect(),
+            )),
+            None => Transaction::BlockMetadata(self.into()),
+        };
+        std::iter::once(block_metadata)
             .chain(
                 self.payload()
                     .unwrap_or(&Vec::new())
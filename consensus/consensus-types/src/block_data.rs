@@ -32,6 +32,15 @@ pub enum BlockType {
     /// from the previous epoch.  The genesis block is used as the the first root block of the
     /// BlockTree for all epochs.
     Genesis,
+    /// A proposal that also reports the failed rounds since its parent, only proposed once the
+    /// on-chain version is at least APTOS_VERSION_5 since the older validators can't decode it.
+    ProposalExt {
+        payload: Payload,
+        author: Author,
+        /// Authors of the rounds between the parent block and this block that failed, along with
+        /// their rounds (capped to the most recent ones)
+        failed_authors: Vec<(Round, Author)>,
+    },
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, CryptoHasher, BCSCryptoHash)]
@@ -69,10 +78,11 @@ pub struct BlockData {
 
 impl BlockData {
     pub fn author(&self) -> Option<Author> {
-        if let BlockType::Proposal { author, .. } = self.block_type {
-            Some(author)
-        } else {
-            None
+        match self.block_type {
+            BlockType::Proposal { author, .. } | BlockType::ProposalExt { author, .. } => {
+                Some(author)
+            }
+            _ => None,
         }
     }
 
@@ -89,8 +99,18 @@ impl BlockData {
     }
 
     pub fn payload(&self) -> Option<&Payload> {
-        if let BlockType::Proposal { payload, .. } = &self.block_type {
-            Some(payload)
+        match &self.block_type {
+            BlockType::Proposal { payload, .. } | BlockType::ProposalExt { payload, .. } => {
+                Some(payload)
+            }
+            _ => None,
+        }
+    }
+
+    /// The failed authors reported by a ProposalExt
+    pub fn failed_authors(&self) -> Option<&Vec<(Round, Author)>> {
+        if let BlockType::ProposalExt { failed_authors, .. } = &self.block_type {
+            Some(failed_authors)
         } else {
             None
         }
@@ -201,6 +221,27 @@ impl BlockData {
         }
     }
 
+    pub fn new_proposal_ext(
+        payload: Payload,
+        author: Author,
+        failed_authors: Vec<(Round, Author)>,
+        round: Round,
+        timestamp_usecs: u64,
+        quorum_cert: QuorumCert,
+    ) -> Self {
+        Self {
+            epoch: quorum_cert.certified_block().epoch(),
+            round,
+            timestamp_usecs,
+            quorum_cert,
+            block_type: BlockType::ProposalExt {
+                payload,
+                author,
+                failed_authors,
+            },
+        }
+    }
+
     /// It's a reconfiguration suffix block if the parent block's executed state indicates next epoch.
     pub fn is_reconfiguration_suffix(&self) -> bool {
         self.quorum_cert.certified_block().has_reconfiguration()
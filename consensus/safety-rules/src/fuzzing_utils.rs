@@ -22,6 +22,7 @@ use aptos_types::{
 use consensus_types::block::Block;
 use consensus_types::{
     block_data::{BlockData, BlockType},
+    common::Round,
     quorum_cert::QuorumCert,
     timeout::Timeout,
     vote_data::VoteData,
@@ -102,6 +103,22 @@ prop_compose! {
     }
 }
 
+// This generates an arbitrary BlockType::ProposalExt enum instance.
+prop_compose! {
+    pub fn arb_block_type_proposal_ext(
+    )(
+        author in any::<AccountAddress>(),
+        payload in prop::collection::vec(any::<SignedTransaction>(), 0..MAX_PROPOSAL_TRANSACTIONS),
+        failed_authors in prop::collection::vec(any::<(Round, AccountAddress)>(), 0..10),
+    ) -> BlockType {
+        BlockType::ProposalExt{
+            payload,
+            author,
+            failed_authors,
+        }
+    }
+}
+
 // This generates an arbitrary MaybeSignedVoteProposal.
 prop_compose! {
     pub fn arb_maybe_signed_vote_proposal(
@@ -229,6 +246,7 @@ fn arb_block_type() -> impl Strategy<Value = BlockType> {
         arb_block_type_proposal(),
         Just(BlockType::NilBlock),
         Just(BlockType::Genesis),
+        arb_block_type_proposal_ext(),
     ]
 }
 
@@ -516,6 +516,10 @@ impl BlockReader for BlockStore {
         self.inner.read().path_from_commit_root(block_id)
     }
 
+    fn recently_committed_blocks(&self, count: usize) -> Vec<Arc<ExecutedBlock>> {
+        self.inner.read().recently_committed_blocks(count)
+    }
+
     fn highest_certified_block(&self) -> Arc<ExecutedBlock> {
         self.inner.read().highest_certified_block()
     }
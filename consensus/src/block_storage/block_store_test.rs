@@ -10,7 +10,9 @@ use crate::{
 };
 use aptos_crypto::{HashValue, PrivateKey};
 use aptos_types::{
-    validator_signer::ValidatorSigner, validator_verifier::random_validator_verifier,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
 };
 use consensus_types::{
     block::{
@@ -21,11 +23,16 @@ use consensus_types::{
         Block,
     },
     common::Author,
+    executed_block::ExecutedBlock,
     vote::Vote,
     vote_data::VoteData,
 };
 use proptest::prelude::*;
-use std::{cmp::min, collections::HashSet};
+use std::{
+    cmp::min,
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
 
 #[tokio::test]
 async fn test_highest_block_and_quorum_cert() {
@@ -234,6 +241,45 @@ async fn test_block_tree_gc() {
     }
 }
 
+#[tokio::test]
+async fn test_recently_committed_blocks() {
+    // build a chain of 20 blocks, max_pruned_nodes_in_mem = 10
+    let mut inserter = TreeInserter::default();
+    let block_store = inserter.block_store();
+    let genesis = block_store.ordered_root();
+    let mut cur_node = inserter
+        .insert_block_with_qc(certificate_for_genesis(), &genesis, 1)
+        .await;
+    let mut added_blocks = vec![cur_node.clone()];
+    for round in 2..=20 {
+        cur_node = inserter.insert_block(&cur_node, round, None).await;
+        added_blocks.push(cur_node.clone());
+    }
+    assert!(block_store.recently_committed_blocks(5).is_empty());
+
+    for block in &added_blocks {
+        let ledger_info = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(block.block_info(), HashValue::zero()),
+            BTreeMap::new(),
+        );
+        block_store.inner.write().commit_callback(
+            block_store.storage.clone(),
+            &[block.clone()],
+            ledger_info,
+        );
+    }
+    let ids = |blocks: &[Arc<ExecutedBlock>]| blocks.iter().map(|b| b.id()).collect::<Vec<_>>();
+    assert_eq!(
+        ids(&block_store.recently_committed_blocks(5)),
+        ids(&added_blocks[15..])
+    );
+    // Only the last max_pruned_blocks_in_mem committed blocks are kept.
+    assert_eq!(
+        ids(&block_store.recently_committed_blocks(100)),
+        ids(&added_blocks[10..])
+    );
+}
+
 #[tokio::test]
 async fn test_path_from_root() {
     let mut inserter = TreeInserter::default();
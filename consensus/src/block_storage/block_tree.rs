@@ -90,6 +90,9 @@ pub struct BlockTree {
     pruned_block_ids: VecDeque<HashValue>,
     /// Num pruned blocks to keep in memory.
     max_pruned_blocks_in_mem: usize,
+    /// The last committed blocks, in order, up to `max_pruned_blocks_in_mem` of them. Their
+    /// transactions may still be in mempool till it's notified of the commit.
+    recently_committed_blocks: VecDeque<Arc<ExecutedBlock>>,
 }
 
 impl BlockTree {
@@ -135,6 +138,7 @@ impl BlockTree {
             pruned_block_ids,
             max_pruned_blocks_in_mem,
             highest_2chain_timeout_cert,
+            recently_committed_blocks: VecDeque::with_capacity(max_pruned_blocks_in_mem),
         }
     }
 
@@ -427,6 +431,16 @@ impl BlockTree {
         self.max_pruned_blocks_in_mem
     }
 
+    /// Returns the last `count` committed blocks, in order.
+    pub(super) fn recently_committed_blocks(&self, count: usize) -> Vec<Arc<ExecutedBlock>> {
+        let skip = self.recently_committed_blocks.len().saturating_sub(count);
+        self.recently_committed_blocks
+            .iter()
+            .skip(skip)
+            .cloned()
+            .collect()
+    }
+
     pub(super) fn get_all_block_id(&self) -> Vec<HashValue> {
         self.id_to_block.keys().cloned().collect()
     }
@@ -457,6 +471,12 @@ impl BlockTree {
         }
         self.process_pruned_blocks(id_to_remove);
         self.update_highest_ledger_info(commit_proof);
+
+        self.recently_committed_blocks
+            .extend(blocks_to_commit.iter().cloned());
+        while self.recently_committed_blocks.len() > self.max_pruned_blocks_in_mem {
+            self.recently_committed_blocks.pop_front();
+        }
     }
 }
 
@@ -41,6 +41,9 @@ pub trait BlockReader: Send + Sync {
 
     fn path_from_commit_root(&self, block_id: HashValue) -> Option<Vec<Arc<ExecutedBlock>>>;
 
+    /// Return the last `count` committed blocks, in order, as far as they're kept in memory.
+    fn recently_committed_blocks(&self, count: usize) -> Vec<Arc<ExecutedBlock>>;
+
     /// Return the certified block with the highest round.
     fn highest_certified_block(&self) -> Arc<ExecutedBlock>;
 
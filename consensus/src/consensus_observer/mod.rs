@@ -0,0 +1,170 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The consensus observer lets the fullnodes follow consensus. The publisher of a validator (or
+//! of a fullnode, forwarding what it observes) sends the ordered blocks, along with the proof of
+//! their ordering, and then their commit decisions to the subscribed downstream nodes. The
+//! observer of a fullnode subscribes to its upstream peers, executes the ordered blocks as soon
+//! as they're received and commits them as soon as their commit decision is, which saves the
+//! round trip of state sync fetching the committed transactions. State sync takes over again if
+//! the observer stops committing.
+
+pub mod network_interface;
+mod observer;
+pub mod publisher;
+
+#[cfg(test)]
+mod observer_test;
+
+use crate::{
+    consensus_observer::{
+        network_interface::{
+            ConsensusObserverMessage, ConsensusObserverNetworkEvents,
+            ConsensusObserverNetworkSender,
+        },
+        observer::ConsensusObserver,
+        publisher::ConsensusPublisher,
+    },
+    state_computer::ExecutionProxy,
+    txn_manager::MempoolProxy,
+};
+use aptos_config::{
+    config::NodeConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_logger::prelude::*;
+use aptos_mempool::ConsensusRequest;
+use aptos_vm::AptosVM;
+use consensus_notifications::{ConsensusNotificationSender, ConsensusObserverGate};
+use executor::block_executor::BlockExecutor;
+use futures::{channel::mpsc, stream::select_all, StreamExt};
+use netcore::transport::ConnectionOrigin;
+use network::protocols::network::Event;
+use std::{collections::HashMap, sync::Arc};
+use storage_interface::DbReaderWriter;
+use tokio::runtime::{self, Runtime};
+
+/// Starts the consensus publisher and, on fullnodes, the consensus observer, as enabled in the
+/// configuration. Returns the runtime and the publisher, which consensus publishes to.
+pub fn start_consensus_observer(
+    node_config: &NodeConfig,
+    network_handles: Vec<(
+        NetworkId,
+        ConsensusObserverNetworkSender,
+        ConsensusObserverNetworkEvents,
+    )>,
+    state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
+    consensus_observer_gate: ConsensusObserverGate,
+    consensus_to_mempool_sender: mpsc::Sender<ConsensusRequest>,
+    aptos_db: DbReaderWriter,
+) -> (Runtime, Option<Arc<ConsensusPublisher>>) {
+    let runtime = runtime::Builder::new_multi_thread()
+        .thread_name("consensus-observer")
+        .enable_all()
+        .build()
+        .expect("Failed to create Tokio runtime!");
+    let config = node_config.consensus.observer;
+
+    let mut network_senders = HashMap::new();
+    let mut network_events = vec![];
+    for (network_id, network_sender, events) in network_handles {
+        network_senders.insert(network_id, network_sender);
+        network_events.push(events.map(move |event| (network_id, event)));
+    }
+
+    let publisher = if config.publisher_enabled {
+        Some(Arc::new(ConsensusPublisher::new(network_senders.clone())))
+    } else {
+        None
+    };
+    let observer = if config.observer_enabled && !node_config.base.role.is_validator() {
+        let txn_manager = Arc::new(MempoolProxy::new(
+            consensus_to_mempool_sender,
+            node_config.consensus.mempool_poll_count,
+            node_config.consensus.mempool_txn_pull_timeout_ms,
+            node_config.consensus.mempool_executed_txn_timeout_ms,
+        ));
+        let state_computer = Arc::new(ExecutionProxy::new(
+            Box::new(BlockExecutor::<AptosVM>::new(aptos_db.clone())),
+            txn_manager,
+            state_sync_notifier,
+            runtime.handle(),
+        ));
+        Some(ConsensusObserver::new(
+            config,
+            network_senders,
+            state_computer,
+            aptos_db.reader,
+            publisher.clone(),
+            consensus_observer_gate,
+        ))
+    } else {
+        None
+    };
+
+    runtime.spawn(process_network_events(
+        select_all(network_events),
+        publisher.clone(),
+        observer,
+    ));
+
+    debug!("Consensus observer started.");
+    (runtime, publisher)
+}
+
+/// Handles the subscriptions of the downstream peers with the publisher, and the messages of the
+/// upstream peers with the observer.
+async fn process_network_events(
+    mut network_events: impl StreamExt<Item = (NetworkId, Event<ConsensusObserverMessage>)> + Unpin,
+    publisher: Option<Arc<ConsensusPublisher>>,
+    mut observer: Option<ConsensusObserver>,
+) {
+    while let Some((network_id, event)) = network_events.next().await {
+        match event {
+            Event::NewPeer(metadata) => {
+                // Only the peers dialed by this node are upstream.
+                if let (Some(observer), ConnectionOrigin::Outbound) = (&observer, metadata.origin) {
+                    observer.subscribe(PeerNetworkId::new(network_id, metadata.remote_peer_id));
+                }
+            }
+            Event::LostPeer(metadata) => {
+                if let Some(publisher) = &publisher {
+                    publisher.remove_subscriber(&PeerNetworkId::new(
+                        network_id,
+                        metadata.remote_peer_id,
+                    ));
+                }
+            }
+            Event::Message(peer_id, message) => {
+                let peer = PeerNetworkId::new(network_id, peer_id);
+                match (message, &publisher, &mut observer) {
+                    (ConsensusObserverMessage::Subscribe, Some(publisher), _) => {
+                        publisher.add_subscriber(peer)
+                    }
+                    (ConsensusObserverMessage::Unsubscribe, Some(publisher), _) => {
+                        publisher.remove_subscriber(&peer)
+                    }
+                    (
+                        message @ (ConsensusObserverMessage::OrderedBlock { .. }
+                        | ConsensusObserverMessage::CommitDecision { .. }),
+                        _,
+                        Some(observer),
+                    ) => observer.process_message(peer, message).await,
+                    (message, _, _) => {
+                        debug!(
+                            peer = %peer,
+                            "Ignored {} from a consensus observer peer",
+                            message.name(),
+                        );
+                    }
+                }
+            }
+            Event::RpcRequest(peer_id, ..) => {
+                warn!(
+                    remote_peer = peer_id,
+                    "Unexpected rpc request to the consensus observer"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Interface between the consensus observer and publisher, and the Network layer.
+
+use crate::counters;
+use aptos_types::{ledger_info::LedgerInfoWithSignatures, PeerId};
+use channel::{aptos_channel, message_queues::QueueStyle};
+use consensus_types::block::Block;
+use network::{
+    error::NetworkError,
+    peer_manager::{ConnectionRequestSender, PeerManagerRequestSender},
+    protocols::network::{AppConfig, NetworkEvents, NetworkSender, NewNetworkSender},
+    ProtocolId,
+};
+use serde::{Deserialize, Serialize};
+
+/// Network type for the consensus observer
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ConsensusObserverMessage {
+    /// Request of a downstream node to receive the messages below.
+    Subscribe,
+    /// Request of a downstream node to stop receiving them.
+    Unsubscribe,
+    /// Blocks ordered by consensus, from a child of the last ordered block to the block certified
+    /// by the ordering proof.
+    OrderedBlock {
+        /// The ordered blocks, in order
+        blocks: Vec<Block>,
+        /// The ledger info certifying the last block is ordered
+        ordered_proof: LedgerInfoWithSignatures,
+    },
+    /// The commit decision of the ordered blocks up to the block certified by the commit proof.
+    CommitDecision {
+        /// The ledger info certifying the executed state of the last committed block
+        commit_proof: LedgerInfoWithSignatures,
+    },
+}
+
+impl ConsensusObserverMessage {
+    /// The label of the message type in the metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConsensusObserverMessage::Subscribe => "subscribe",
+            ConsensusObserverMessage::Unsubscribe => "unsubscribe",
+            ConsensusObserverMessage::OrderedBlock { .. } => "ordered_block",
+            ConsensusObserverMessage::CommitDecision { .. } => "commit_decision",
+        }
+    }
+}
+
+/// The interface from Network to the consensus observer and publisher.
+pub type ConsensusObserverNetworkEvents = NetworkEvents<ConsensusObserverMessage>;
+
+/// The interface from the consensus observer and publisher to Network.
+#[derive(Clone)]
+pub struct ConsensusObserverNetworkSender {
+    inner: NetworkSender<ConsensusObserverMessage>,
+}
+
+/// Configuration for the network endpoints to support the consensus observer. The messages of
+/// a peer are delivered in order, as the blocks are applied in order.
+pub fn network_endpoint_config(max_network_channel_size: usize) -> AppConfig {
+    AppConfig::p2p(
+        [ProtocolId::ConsensusObserverDirectSend],
+        aptos_channel::Config::new(max_network_channel_size)
+            .queue_style(QueueStyle::FIFO)
+            .counters(&counters::PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS),
+    )
+}
+
+impl NewNetworkSender for ConsensusObserverNetworkSender {
+    fn new(
+        peer_mgr_reqs_tx: PeerManagerRequestSender,
+        connection_reqs_tx: ConnectionRequestSender,
+    ) -> Self {
+        Self {
+            inner: NetworkSender::new(peer_mgr_reqs_tx, connection_reqs_tx),
+        }
+    }
+}
+
+impl ConsensusObserverNetworkSender {
+    /// Send a single message to the destination peer.
+    pub fn send_to(
+        &self,
+        recipient: PeerId,
+        message: ConsensusObserverMessage,
+    ) -> Result<(), NetworkError> {
+        self.inner
+            .send_to(recipient, ProtocolId::ConsensusObserverDirectSend, message)
+    }
+
+    /// Send a single message to the destination peers.
+    pub fn send_to_many(
+        &self,
+        recipients: impl Iterator<Item = PeerId>,
+        message: ConsensusObserverMessage,
+    ) -> Result<(), NetworkError> {
+        self.inner
+            .send_to_many(recipients, ProtocolId::ConsensusObserverDirectSend, message)
+    }
+}
@@ -0,0 +1,387 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::{
+        network_interface::{ConsensusObserverMessage, ConsensusObserverNetworkSender},
+        publisher::ConsensusPublisher,
+    },
+    counters,
+    state_replication::StateComputer,
+};
+use anyhow::{ensure, format_err, Result};
+use aptos_config::{
+    config::ConsensusObserverConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_types::{
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+};
+use consensus_notifications::ConsensusObserverGate;
+use consensus_types::{block::Block, executed_block::ExecutedBlock};
+use std::{collections::HashMap, sync::Arc};
+use storage_interface::DbReader;
+
+/// Follows consensus on a fullnode: verifies and executes the ordered blocks published by the
+/// upstream nodes, then commits them as soon as their commit decision is received, instead of
+/// waiting for state sync to fetch the committed transactions.
+///
+/// If a commit decision can't be applied to the executed blocks (e.g., some ordered blocks were
+/// missed or dropped), the observer syncs to it instead, and follows the blocks ordered after it.
+///
+/// Only one of the observer and state sync writes to storage at a time: the observer holds the
+/// gate shared with state sync while it executes and commits blocks, and it's paused while state
+/// sync has taken over (e.g., the upstream nodes were unavailable). It then requests to resume
+/// once it receives a commit decision again, and follows the latest ledger info in storage.
+pub struct ConsensusObserver {
+    config: ConsensusObserverConfig,
+    network_senders: HashMap<NetworkId, ConsensusObserverNetworkSender>,
+    state_computer: Arc<dyn StateComputer>,
+    db: Arc<dyn DbReader>,
+    /// Forwards the verified messages to the downstream nodes, if enabled
+    publisher: Option<Arc<ConsensusPublisher>>,
+    /// The gate through which the observer takes turns with state sync to write to storage
+    gate: ConsensusObserverGate,
+    /// True iff the observer has been paused by state sync (and hasn't resumed yet)
+    paused: bool,
+    /// The epoch state verifying the proofs of the current epoch
+    epoch_state: EpochState,
+    /// The ledger info of the last committed block, the parent of the first pending block
+    root: LedgerInfoWithSignatures,
+    /// The ordered blocks executed ahead of their commit decision, in order
+    pending_blocks: Vec<Arc<ExecutedBlock>>,
+}
+
+impl ConsensusObserver {
+    pub fn new(
+        config: ConsensusObserverConfig,
+        network_senders: HashMap<NetworkId, ConsensusObserverNetworkSender>,
+        state_computer: Arc<dyn StateComputer>,
+        db: Arc<dyn DbReader>,
+        publisher: Option<Arc<ConsensusPublisher>>,
+        gate: ConsensusObserverGate,
+    ) -> Self {
+        let (epoch_state, root) = load_root(db.as_ref()).expect("Failed to load the root");
+        Self::new_with_root(
+            config,
+            network_senders,
+            state_computer,
+            db,
+            publisher,
+            gate,
+            epoch_state,
+            root,
+        )
+    }
+
+    /// Creates an observer following the blocks ordered after the given root
+    pub(crate) fn new_with_root(
+        config: ConsensusObserverConfig,
+        network_senders: HashMap<NetworkId, ConsensusObserverNetworkSender>,
+        state_computer: Arc<dyn StateComputer>,
+        db: Arc<dyn DbReader>,
+        publisher: Option<Arc<ConsensusPublisher>>,
+        gate: ConsensusObserverGate,
+        epoch_state: EpochState,
+        root: LedgerInfoWithSignatures,
+    ) -> Self {
+        Self {
+            config,
+            network_senders,
+            state_computer,
+            db,
+            publisher,
+            gate,
+            paused: false,
+            epoch_state,
+            root,
+            pending_blocks: vec![],
+        }
+    }
+
+    /// Subscribes to the messages of an upstream peer
+    pub fn subscribe(&self, peer: PeerNetworkId) {
+        let result = match self.network_senders.get(&peer.network_id()) {
+            Some(network_sender) => {
+                network_sender.send_to(peer.peer_id(), ConsensusObserverMessage::Subscribe)
+            }
+            None => return,
+        };
+        match result {
+            Ok(()) => info!(peer = %peer, "Subscribed to the consensus publisher"),
+            Err(e) => warn!(
+                error = ?e,
+                peer = %peer,
+                "Failed to subscribe to the consensus publisher"
+            ),
+        }
+    }
+
+    /// Processes a message published by an upstream peer
+    pub async fn process_message(
+        &mut self,
+        peer: PeerNetworkId,
+        message: ConsensusObserverMessage,
+    ) {
+        let message_type = message.name();
+        let result = match message {
+            ConsensusObserverMessage::OrderedBlock {
+                blocks,
+                ordered_proof,
+            } => self.process_ordered_block(blocks, ordered_proof).await,
+            ConsensusObserverMessage::CommitDecision { commit_proof } => {
+                self.process_commit_decision(commit_proof).await
+            }
+            ConsensusObserverMessage::Subscribe | ConsensusObserverMessage::Unsubscribe => {
+                Err(format_err!("Unexpected subscription request"))
+            }
+        };
+        let result_label = match result {
+            Ok(true) => "success",
+            Ok(false) => "ignored",
+            Err(e) => {
+                warn!(
+                    error = ?e,
+                    peer = %peer,
+                    "Failed to process {} from the consensus publisher",
+                    message_type,
+                );
+                "error"
+            }
+        };
+        counters::CONSENSUS_OBSERVER_MESSAGES
+            .with_label_values(&[message_type, result_label])
+            .inc();
+        counters::CONSENSUS_OBSERVER_PENDING_BLOCKS.set(self.pending_blocks.len() as i64);
+    }
+
+    /// Verifies and executes the ordered blocks, returns false if they don't follow the last
+    /// pending block (e.g. they're published again by another upstream peer) or if the observer
+    /// is paused.
+    pub(crate) async fn process_ordered_block(
+        &mut self,
+        blocks: Vec<Block>,
+        ordered_proof: LedgerInfoWithSignatures,
+    ) -> Result<bool> {
+        let gate = self.gate.clone();
+        let _guard = match gate.lock_observer().await {
+            Some(guard) => guard,
+            None => {
+                self.pause();
+                return Ok(false);
+            }
+        };
+        self.resume().await;
+
+        let last_block = blocks
+            .last()
+            .ok_or_else(|| format_err!("No ordered blocks"))?;
+        if blocks[0].parent_id() != self.last_block_id() {
+            return Ok(false);
+        }
+        ensure!(
+            self.pending_blocks.len() + blocks.len() <= self.config.max_pending_blocks,
+            "Too many pending blocks"
+        );
+        self.verify_proof(&ordered_proof)?;
+        ensure!(
+            last_block.id() == ordered_proof.commit_info().id(),
+            "The ordered proof doesn't certify the last block"
+        );
+        for (parent, block) in blocks.iter().zip(blocks.iter().skip(1)) {
+            ensure!(
+                block.parent_id() == parent.id(),
+                "The blocks aren't chained"
+            );
+        }
+        for block in &blocks {
+            ensure!(
+                block.epoch() == self.epoch_state.epoch,
+                "The block isn't in the current epoch"
+            );
+            block.verify_well_formed()?;
+        }
+
+        if let Some(publisher) = &self.publisher {
+            publisher.publish(ConsensusObserverMessage::OrderedBlock {
+                blocks: blocks.clone(),
+                ordered_proof,
+            });
+        }
+        for block in blocks {
+            let compute_result = self
+                .state_computer
+                .compute(&block, self.last_block_id())
+                .await?;
+            self.pending_blocks
+                .push(Arc::new(ExecutedBlock::new(block, compute_result)));
+        }
+        Ok(true)
+    }
+
+    /// Commits the pending blocks up to the block of the commit decision, or syncs to it if they
+    /// don't match. Returns false if the block is already committed. If the observer is paused,
+    /// it requests to resume instead (as the upstream nodes are committing again).
+    pub(crate) async fn process_commit_decision(
+        &mut self,
+        commit_proof: LedgerInfoWithSignatures,
+    ) -> Result<bool> {
+        let gate = self.gate.clone();
+        let _guard = match gate.lock_observer().await {
+            Some(guard) => guard,
+            None => {
+                self.pause();
+                // State sync has been writing to storage meanwhile
+                self.reload_root();
+                if !self.is_newer_than_root(&commit_proof) {
+                    return Ok(false);
+                }
+                self.verify_proof(&commit_proof)?;
+                gate.request_resume().await;
+                return Ok(true);
+            }
+        };
+        self.resume().await;
+
+        if !self.is_newer_than_root(&commit_proof) {
+            return Ok(false);
+        }
+        self.verify_proof(&commit_proof)?;
+
+        let commit_info = commit_proof.commit_info();
+        if let Some(publisher) = &self.publisher {
+            publisher.publish(ConsensusObserverMessage::CommitDecision {
+                commit_proof: commit_proof.clone(),
+            });
+        }
+        let committed_blocks = match self
+            .pending_blocks
+            .iter()
+            .position(|block| block.block_info() == *commit_info)
+        {
+            Some(index) => self.pending_blocks.drain(..=index).collect::<Vec<_>>(),
+            None => {
+                self.sync_to(commit_proof).await;
+                return Ok(true);
+            }
+        };
+        // State sync is notified of the commit by the state computer.
+        let callback = Box::new(|_: &[Arc<ExecutedBlock>], _: LedgerInfoWithSignatures| {});
+        if let Err(e) = self
+            .state_computer
+            .commit(&committed_blocks, commit_proof.clone(), callback)
+            .await
+        {
+            warn!(error = ?e, "Failed to commit the ordered blocks, syncing instead");
+            self.sync_to(commit_proof).await;
+            return Ok(true);
+        }
+
+        if let Some(next_epoch_state) = commit_proof.ledger_info().next_epoch_state() {
+            self.epoch_state = next_epoch_state.clone();
+            // The rest of the blocks of the epoch are a reconfiguration suffix.
+            self.pending_blocks.clear();
+        }
+        self.root = commit_proof;
+        counters::CONSENSUS_OBSERVER_LAST_COMMITTED_ROUND
+            .set(self.root.commit_info().round() as i64);
+        Ok(true)
+    }
+
+    /// Drops the pending blocks once state sync has paused the observer
+    fn pause(&mut self) {
+        if !self.paused {
+            info!("Paused by state sync");
+            self.paused = true;
+            self.pending_blocks.clear();
+        }
+    }
+
+    /// Follows the latest ledger info in storage once state sync has resumed the observer. The
+    /// executor is reset by syncing to it, as state sync has committed the blocks after the ones
+    /// it executed.
+    async fn resume(&mut self) {
+        if !self.paused {
+            return;
+        }
+        info!("Resumed by state sync");
+        self.paused = false;
+        self.reload_root();
+        if let Err(e) = self.state_computer.sync_to(self.root.clone()).await {
+            warn!(error = ?e, "Failed to reset the executor");
+        }
+    }
+
+    /// Syncs to the commit decision with state sync, then follows the blocks ordered after the
+    /// latest ledger info in storage.
+    async fn sync_to(&mut self, commit_proof: LedgerInfoWithSignatures) {
+        info!(
+            epoch = commit_proof.ledger_info().epoch(),
+            round = commit_proof.commit_info().round(),
+            "Syncing to the commit decision"
+        );
+        // This resets the executor even if it fails, e.g. if state sync is already beyond it.
+        if let Err(e) = self.state_computer.sync_to(commit_proof).await {
+            warn!(error = ?e, "Failed to sync to the commit decision");
+        }
+        self.pending_blocks.clear();
+        self.reload_root();
+    }
+
+    /// Loads the epoch state and the root from the latest ledger info in storage
+    fn reload_root(&mut self) {
+        match load_root(self.db.as_ref()) {
+            Ok((epoch_state, root)) => {
+                self.epoch_state = epoch_state;
+                self.root = root;
+            }
+            Err(e) => error!(error = ?e, "Failed to load the root"),
+        }
+    }
+
+    fn is_newer_than_root(&self, commit_proof: &LedgerInfoWithSignatures) -> bool {
+        let commit_info = commit_proof.commit_info();
+        let root_info = self.root.commit_info();
+        (commit_info.epoch(), commit_info.round()) > (root_info.epoch(), root_info.round())
+    }
+
+    fn verify_proof(&self, proof: &LedgerInfoWithSignatures) -> Result<()> {
+        ensure!(
+            proof.ledger_info().epoch() == self.epoch_state.epoch,
+            "The proof isn't in the current epoch"
+        );
+        proof.verify_signatures(&self.epoch_state.verifier)?;
+        Ok(())
+    }
+
+    fn last_block_id(&self) -> HashValue {
+        self.pending_blocks.last().map_or_else(
+            || root_block_id(self.root.ledger_info()),
+            |block| block.id(),
+        )
+    }
+}
+
+/// Loads the epoch state and the latest ledger info from storage
+fn load_root(db: &dyn DbReader) -> Result<(EpochState, LedgerInfoWithSignatures)> {
+    let startup_info = db
+        .get_startup_info()?
+        .ok_or_else(|| format_err!("Missing startup info"))?;
+    Ok((
+        startup_info.get_epoch_state().clone(),
+        startup_info.latest_ledger_info,
+    ))
+}
+
+/// The id of the block committed by the ledger info, which is the genesis block of the next epoch
+/// if it ends the epoch.
+fn root_block_id(ledger_info: &LedgerInfo) -> HashValue {
+    if ledger_info.ends_epoch() {
+        Block::make_genesis_block_from_ledger_info(ledger_info).id()
+    } else {
+        ledger_info.consensus_block_id()
+    }
+}
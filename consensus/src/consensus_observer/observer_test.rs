@@ -0,0 +1,240 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::observer::ConsensusObserver,
+    test_utils::{mock_state_computer::MockStateComputer, mock_storage::MockStorage},
+};
+use anyhow::Result;
+use aptos_config::config::ConsensusObserverConfig;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_types::{
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+    validator_verifier::random_validator_verifier,
+};
+use consensus_notifications::ConsensusObserverGate;
+use consensus_types::{
+    block::{
+        block_test_utils::{certificate_for_genesis, gen_test_certificate},
+        Block,
+    },
+    executed_block::ExecutedBlock,
+};
+use executor_types::StateComputeResult;
+use futures::{channel::mpsc, StreamExt};
+use std::{collections::BTreeMap, sync::Arc};
+use storage_interface::{mock::MockDbReaderWriter, DbReader, StartupInfo, TreeState};
+
+/// A storage whose latest ledger info is set by the tests, e.g. as synced by state sync
+struct MockLedgerDb {
+    epoch_state: EpochState,
+    latest_ledger_info: Mutex<LedgerInfoWithSignatures>,
+}
+
+impl DbReader for MockLedgerDb {
+    fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
+        Ok(Some(StartupInfo::new(
+            self.latest_ledger_info.lock().clone(),
+            Some(self.epoch_state.clone()),
+            TreeState::new(0, vec![], HashValue::zero()),
+            None,
+        )))
+    }
+}
+
+fn sign_ledger_info(signer: &ValidatorSigner, block: &Block) -> LedgerInfoWithSignatures {
+    // The observers execute the blocks with the dummy compute result of the mock state computer.
+    let block_info =
+        ExecutedBlock::new(block.clone(), StateComputeResult::new_dummy()).block_info();
+    let ledger_info = LedgerInfo::new(block_info, HashValue::zero());
+    let mut signatures = BTreeMap::new();
+    signatures.insert(signer.author(), signer.sign(&ledger_info));
+    LedgerInfoWithSignatures::new(ledger_info, signatures)
+}
+
+fn make_block(signer: &ValidatorSigner, parent: Option<&Block>, round: u64) -> Block {
+    let quorum_cert = match parent {
+        Some(parent) => {
+            let parent_info =
+                ExecutedBlock::new(parent.clone(), StateComputeResult::new_dummy()).block_info();
+            gen_test_certificate(
+                vec![signer],
+                parent_info,
+                parent.quorum_cert().certified_block().clone(),
+                None,
+            )
+        }
+        None => certificate_for_genesis(),
+    };
+    Block::new_proposal(vec![], round, round, quorum_cert, signer)
+}
+
+#[tokio::test]
+async fn test_commit_ordered_blocks() {
+    let (signers, verifier) = random_validator_verifier(1, None, false);
+    let signer = &signers[0];
+    let (commit_sender, mut commit_receiver) = mpsc::unbounded();
+    let (state_sync_sender, _state_sync_receiver) = mpsc::unbounded();
+    let (_, storage) = MockStorage::start_for_testing((&verifier).into());
+    let state_computer = Arc::new(MockStateComputer::new(
+        state_sync_sender,
+        commit_sender,
+        storage,
+    ));
+    let mut observer = ConsensusObserver::new_with_root(
+        ConsensusObserverConfig::default(),
+        Default::default(),
+        state_computer,
+        Arc::new(MockDbReaderWriter),
+        None,
+        ConsensusObserverGate::default(),
+        EpochState { epoch: 1, verifier },
+        LedgerInfoWithSignatures::new(LedgerInfo::mock_genesis(None), BTreeMap::new()),
+    );
+
+    let b1 = make_block(signer, None, 1);
+    let b2 = make_block(signer, Some(&b1), 2);
+    let b3 = make_block(signer, Some(&b2), 3);
+
+    // The blocks not following the last ordered block are ignored.
+    assert!(!observer
+        .process_ordered_block(vec![b2.clone()], sign_ledger_info(signer, &b2))
+        .await
+        .unwrap());
+    // The ordered proof must certify the last block.
+    assert!(observer
+        .process_ordered_block(vec![b1.clone(), b2.clone()], sign_ledger_info(signer, &b1))
+        .await
+        .is_err());
+    assert!(observer
+        .process_ordered_block(vec![b1.clone(), b2.clone()], sign_ledger_info(signer, &b2))
+        .await
+        .unwrap());
+    // Published again, e.g. by another upstream peer.
+    assert!(!observer
+        .process_ordered_block(vec![b1.clone(), b2.clone()], sign_ledger_info(signer, &b2))
+        .await
+        .unwrap());
+    assert!(observer
+        .process_ordered_block(vec![b3.clone()], sign_ledger_info(signer, &b3))
+        .await
+        .unwrap());
+
+    // The commit proof signed by another validator is rejected.
+    let (other_signers, _) = random_validator_verifier(1, None, false);
+    assert!(observer
+        .process_commit_decision(sign_ledger_info(&other_signers[0], &b2))
+        .await
+        .is_err());
+
+    // The executed blocks are committed up to the block of the commit decision.
+    let commit_proof = sign_ledger_info(signer, &b2);
+    assert!(observer
+        .process_commit_decision(commit_proof.clone())
+        .await
+        .unwrap());
+    assert_eq!(commit_receiver.next().await.unwrap(), commit_proof);
+    assert!(!observer
+        .process_commit_decision(commit_proof)
+        .await
+        .unwrap());
+
+    let commit_proof = sign_ledger_info(signer, &b3);
+    assert!(observer
+        .process_commit_decision(commit_proof.clone())
+        .await
+        .unwrap());
+    assert_eq!(commit_receiver.next().await.unwrap(), commit_proof);
+}
+
+#[tokio::test]
+async fn test_pause_and_resume() {
+    let (signers, verifier) = random_validator_verifier(1, None, false);
+    let signer = &signers[0];
+    let (commit_sender, mut commit_receiver) = mpsc::unbounded();
+    let (state_sync_sender, _state_sync_receiver) = mpsc::unbounded();
+    let (_, storage) = MockStorage::start_for_testing((&verifier).into());
+    let state_computer = Arc::new(MockStateComputer::new(
+        state_sync_sender,
+        commit_sender,
+        storage,
+    ));
+    let epoch_state = EpochState { epoch: 1, verifier };
+    let genesis = LedgerInfoWithSignatures::new(LedgerInfo::mock_genesis(None), BTreeMap::new());
+    let db = Arc::new(MockLedgerDb {
+        epoch_state: epoch_state.clone(),
+        latest_ledger_info: Mutex::new(genesis.clone()),
+    });
+    let gate = ConsensusObserverGate::default();
+    let mut observer = ConsensusObserver::new_with_root(
+        ConsensusObserverConfig::default(),
+        Default::default(),
+        state_computer,
+        db.clone(),
+        None,
+        gate.clone(),
+        epoch_state,
+        genesis,
+    );
+
+    let b1 = make_block(signer, None, 1);
+    let b2 = make_block(signer, Some(&b1), 2);
+    let b3 = make_block(signer, Some(&b2), 3);
+    let b4 = make_block(signer, Some(&b3), 4);
+    assert!(observer
+        .process_ordered_block(vec![b1.clone(), b2.clone()], sign_ledger_info(signer, &b2))
+        .await
+        .unwrap());
+
+    // State sync falls back (e.g., the upstream nodes are unavailable), so the paused observer
+    // ignores the ordered blocks while state sync commits b2.
+    assert!(gate.try_pause_observer());
+    assert!(!observer
+        .process_ordered_block(vec![b3.clone()], sign_ledger_info(signer, &b3))
+        .await
+        .unwrap());
+    *db.latest_ledger_info.lock() = sign_ledger_info(signer, &b2);
+
+    // The commit decisions synced by state sync don't resume the observer, the newer ones
+    // request to resume, but the observer doesn't commit until state sync hands back.
+    assert!(!observer
+        .process_commit_decision(sign_ledger_info(signer, &b2))
+        .await
+        .unwrap());
+    assert!(!gate.is_resume_requested());
+    assert!(observer
+        .process_commit_decision(sign_ledger_info(signer, &b3))
+        .await
+        .unwrap());
+    assert!(gate.is_resume_requested());
+    assert!(!observer
+        .process_ordered_block(vec![b4.clone()], sign_ledger_info(signer, &b4))
+        .await
+        .unwrap());
+    assert!(commit_receiver.try_next().is_err());
+
+    // Once resumed, the observer resets the executor to the ledger info synced by state sync and
+    // follows the blocks ordered after it.
+    assert!(gate.try_resume_observer());
+    assert!(!observer
+        .process_ordered_block(vec![b4.clone()], sign_ledger_info(signer, &b4))
+        .await
+        .unwrap());
+    assert_eq!(
+        commit_receiver.next().await.unwrap(),
+        sign_ledger_info(signer, &b2)
+    );
+    assert!(observer
+        .process_ordered_block(vec![b3.clone()], sign_ledger_info(signer, &b3))
+        .await
+        .unwrap());
+    let commit_proof = sign_ledger_info(signer, &b3);
+    assert!(observer
+        .process_commit_decision(commit_proof.clone())
+        .await
+        .unwrap());
+    assert_eq!(commit_receiver.next().await.unwrap(), commit_proof);
+}
@@ -0,0 +1,146 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    consensus_observer::network_interface::{
+        ConsensusObserverMessage, ConsensusObserverNetworkSender,
+    },
+    counters,
+    error::StateSyncError,
+    state_replication::{StateComputer, StateComputerCommitCallBackType},
+};
+use anyhow::Result;
+use aptos_config::network_id::{NetworkId, PeerNetworkId};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use consensus_types::{block::Block, executed_block::ExecutedBlock};
+use executor_types::{Error as ExecutionError, StateComputeResult};
+use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// Publishes the ordered blocks and the commit decisions to the subscribed downstream nodes.
+pub struct ConsensusPublisher {
+    network_senders: HashMap<NetworkId, ConsensusObserverNetworkSender>,
+    subscribers: Mutex<HashSet<PeerNetworkId>>,
+}
+
+impl ConsensusPublisher {
+    /// Creates a publisher sending through the given networks, without any subscriber
+    pub fn new(network_senders: HashMap<NetworkId, ConsensusObserverNetworkSender>) -> Self {
+        Self {
+            network_senders,
+            subscribers: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Adds a downstream peer the messages are published to
+    pub fn add_subscriber(&self, peer: PeerNetworkId) {
+        let mut subscribers = self.subscribers.lock();
+        if subscribers.insert(peer) {
+            info!(peer = %peer, "New consensus observer subscribed");
+        }
+        counters::CONSENSUS_PUBLISHER_SUBSCRIBERS.set(subscribers.len() as i64);
+    }
+
+    /// Removes a downstream peer, e.g. once disconnected
+    pub fn remove_subscriber(&self, peer: &PeerNetworkId) {
+        let mut subscribers = self.subscribers.lock();
+        if subscribers.remove(peer) {
+            info!(peer = %peer, "Consensus observer unsubscribed");
+        }
+        counters::CONSENSUS_PUBLISHER_SUBSCRIBERS.set(subscribers.len() as i64);
+    }
+
+    /// Returns the subscribed downstream peers
+    pub fn subscribers(&self) -> Vec<PeerNetworkId> {
+        self.subscribers.lock().iter().copied().collect()
+    }
+
+    /// Sends the message to all the subscribers, on a best effort basis: the subscribers falling
+    /// behind catch up with state sync.
+    pub fn publish(&self, message: ConsensusObserverMessage) {
+        counters::CONSENSUS_PUBLISHER_MESSAGES
+            .with_label_values(&[message.name()])
+            .inc();
+        let subscribers_per_network = self
+            .subscribers()
+            .into_iter()
+            .into_group_map_by(|peer| peer.network_id());
+        for (network_id, peers) in subscribers_per_network {
+            let network_sender = match self.network_senders.get(&network_id) {
+                Some(network_sender) => network_sender,
+                None => continue,
+            };
+            if let Err(e) = network_sender
+                .send_to_many(peers.iter().map(|peer| peer.peer_id()), message.clone())
+            {
+                warn!(
+                    error = ?e,
+                    network_id = %network_id,
+                    "Failed to publish {} to the consensus observers",
+                    message.name(),
+                );
+            }
+        }
+    }
+}
+
+/// Wraps the state computer consensus commits the ordered blocks through, publishing the ordered
+/// blocks when they're sent to commit and the commit decisions when the commit callback is
+/// invoked.
+pub(crate) struct PublishingStateComputer {
+    inner: Arc<dyn StateComputer>,
+    publisher: Arc<ConsensusPublisher>,
+}
+
+impl PublishingStateComputer {
+    pub(crate) fn new(inner: Arc<dyn StateComputer>, publisher: Arc<ConsensusPublisher>) -> Self {
+        Self { inner, publisher }
+    }
+}
+
+#[async_trait::async_trait]
+impl StateComputer for PublishingStateComputer {
+    async fn compute(
+        &self,
+        block: &Block,
+        parent_block_id: HashValue,
+    ) -> Result<StateComputeResult, ExecutionError> {
+        self.inner.compute(block, parent_block_id).await
+    }
+
+    async fn commit(
+        &self,
+        blocks: &[Arc<ExecutedBlock>],
+        finality_proof: LedgerInfoWithSignatures,
+        callback: StateComputerCommitCallBackType,
+    ) -> Result<(), ExecutionError> {
+        self.publisher
+            .publish(ConsensusObserverMessage::OrderedBlock {
+                blocks: blocks.iter().map(|block| block.block().clone()).collect(),
+                ordered_proof: finality_proof.clone(),
+            });
+
+        let publisher = self.publisher.clone();
+        let wrapped_callback =
+            move |committed_blocks: &[Arc<ExecutedBlock>],
+                  commit_decision: LedgerInfoWithSignatures| {
+                publisher.publish(ConsensusObserverMessage::CommitDecision {
+                    commit_proof: commit_decision.clone(),
+                });
+                callback(committed_blocks, commit_decision);
+            };
+        self.inner
+            .commit(blocks, finality_proof, Box::new(wrapped_callback))
+            .await
+    }
+
+    async fn sync_to(&self, target: LedgerInfoWithSignatures) -> Result<(), StateSyncError> {
+        self.inner.sync_to(target).await
+    }
+}
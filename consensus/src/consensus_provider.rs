@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    consensus_observer::publisher::ConsensusPublisher,
     counters,
+    diagnostics::LivenessDiagnostics,
     epoch_manager::EpochManager,
     network::NetworkTask,
     network_interface::{ConsensusNetworkEvents, ConsensusNetworkSender},
@@ -17,14 +19,15 @@ use aptos_mempool::ConsensusRequest;
 use aptos_vm::AptosVM;
 use consensus_notifications::ConsensusNotificationSender;
 use event_notifications::ReconfigNotificationListener;
-use executor::block_executor::BlockExecutor;
+use executor::{block_executor::BlockExecutor, execution_tracer::ExecutionTracer};
 use futures::channel::mpsc;
 use network::application::storage::PeerMetadataStorage;
 use std::sync::Arc;
 use storage_interface::DbReaderWriter;
 use tokio::runtime::{self, Runtime};
 
-/// Helper function to start consensus based on configuration and return the runtime
+/// Helper function to start consensus based on configuration and return the runtime, consensus
+/// reports its liveness to `liveness_diagnostics`.
 pub fn start_consensus(
     node_config: &NodeConfig,
     mut network_sender: ConsensusNetworkSender,
@@ -34,6 +37,8 @@ pub fn start_consensus(
     aptos_db: DbReaderWriter,
     reconfig_events: ReconfigNotificationListener,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    liveness_diagnostics: Arc<LivenessDiagnostics>,
 ) -> Runtime {
     let runtime = runtime::Builder::new_multi_thread()
         .thread_name("consensus")
@@ -48,8 +53,15 @@ pub fn start_consensus(
         node_config.consensus.mempool_executed_txn_timeout_ms,
     ));
 
+    let mut block_executor = BlockExecutor::<AptosVM>::new(aptos_db);
+    if let Some(trace_dir) = &node_config.execution.trace_dir {
+        let tracer =
+            ExecutionTracer::new(trace_dir.clone(), node_config.execution.max_traced_blocks)
+                .expect("Failed to create the execution trace dir");
+        block_executor = block_executor.with_tracer(tracer);
+    }
     let state_computer = Arc::new(ExecutionProxy::new(
-        Box::new(BlockExecutor::<AptosVM>::new(aptos_db)),
+        Box::new(block_executor),
         txn_manager.clone(),
         state_sync_notifier,
         runtime.handle(),
@@ -71,6 +83,8 @@ pub fn start_consensus(
         state_computer,
         storage,
         reconfig_events,
+        consensus_publisher,
+        liveness_diagnostics,
     );
 
     let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
@@ -89,6 +89,15 @@ pub static COMMITTED_PROPOSALS_IN_WINDOW: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Failed proposals from this validator when using LeaderReputation as the ProposerElection
+pub static FAILED_PROPOSALS_IN_WINDOW: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_failed_proposals_in_window",
+        "Total number of this validator's failed proposals in the current reputation window"
+    )
+    .unwrap()
+});
+
 /// Committed votes from this validator when using LeaderReputation as the ProposerElection
 pub static COMMITTED_VOTES_IN_WINDOW: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -136,6 +145,26 @@ pub static TIMEOUT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!("aptos_consensus_timeout_count", "Count the number of timeouts a node experienced since last restart (close to 0 in happy path).").unwrap()
 });
 
+/// Count of the timeout votes received from each validator since last restart, telling which
+/// validators gave up on the rounds.
+pub static TIMEOUT_VOTES_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_timeout_votes_count",
+        "Count of the timeout votes received from each validator since last restart",
+        &["peer_id"]
+    )
+    .unwrap()
+});
+
+/// Count of the validators whose votes for the current round are pending a QC or a TC.
+pub static PENDING_VOTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_pending_votes",
+        "Count of the validators whose votes for the current round are pending a QC or a TC"
+    )
+    .unwrap()
+});
+
 /// The timeout of the current round.
 pub static ROUND_TIMEOUT_MS: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -300,3 +329,60 @@ pub static BLOCK_RETRIEVAL_CHANNEL_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Counter of pending network events to the consensus observer and publisher
+pub static PENDING_CONSENSUS_OBSERVER_NETWORK_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_pending_network_events",
+        "Counters(queued,dequeued,dropped) related to pending network notifications to the observer",
+        &["state"]
+    )
+    .unwrap()
+});
+
+/// Count of the messages published to the observers, by message type
+pub static CONSENSUS_PUBLISHER_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_publisher_messages_count",
+        "Count of the messages published to the consensus observers, by message type",
+        &["type"]
+    )
+    .unwrap()
+});
+
+/// Number of peers subscribed to the consensus publisher
+pub static CONSENSUS_PUBLISHER_SUBSCRIBERS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_publisher_subscribers",
+        "Number of peers subscribed to the consensus publisher"
+    )
+    .unwrap()
+});
+
+/// Count of the messages received by the consensus observer, by message type and result
+pub static CONSENSUS_OBSERVER_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_observer_messages_count",
+        "Count of the messages received by the consensus observer, by message type and result",
+        &["type", "result"]
+    )
+    .unwrap()
+});
+
+/// Number of ordered blocks executed by the consensus observer ahead of the commit decisions
+pub static CONSENSUS_OBSERVER_PENDING_BLOCKS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_observer_pending_blocks",
+        "Number of ordered blocks executed by the consensus observer ahead of the commit decisions"
+    )
+    .unwrap()
+});
+
+/// The round of the last block committed by the consensus observer
+pub static CONSENSUS_OBSERVER_LAST_COMMITTED_ROUND: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_observer_last_committed_round",
+        "The round of the last block committed by the consensus observer"
+    )
+    .unwrap()
+});
@@ -0,0 +1,71 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::block_storage::{BlockReader, BlockStore};
+use aptos_infallible::RwLock;
+use aptos_types::liveness::{LivenessReporter, LivenessSnapshot};
+use consensus_types::common::{Author, Round};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The liveness of consensus, updated by the round state and read from the block store of the
+/// current epoch, to tell why the rounds stall.
+#[derive(Default)]
+pub struct LivenessDiagnostics {
+    round: RwLock<RoundDiagnostics>,
+    block_store: RwLock<Option<Arc<BlockStore>>>,
+}
+
+#[derive(Clone, Default)]
+struct RoundDiagnostics {
+    current_round: Round,
+    pending_votes: usize,
+    timeout_votes: BTreeMap<Author, u64>,
+}
+
+impl LivenessDiagnostics {
+    /// Returns the current liveness of consensus
+    pub fn snapshot(&self) -> LivenessSnapshot {
+        let round = self.round.read().clone();
+        let (last_committed_round, pending_blocks) = match &*self.block_store.read() {
+            Some(block_store) => {
+                let ordered_root = block_store.ordered_root();
+                let pending_blocks = block_store
+                    .path_from_commit_root(ordered_root.id())
+                    .map_or(0, |blocks| blocks.len());
+                (block_store.commit_root().round(), pending_blocks)
+            }
+            None => (0, 0),
+        };
+        LivenessSnapshot {
+            current_round: round.current_round,
+            last_committed_round,
+            pending_votes: round.pending_votes,
+            timeout_votes: round.timeout_votes,
+            pending_blocks,
+        }
+    }
+
+    pub(crate) fn set_block_store(&self, block_store: Arc<BlockStore>) {
+        *self.block_store.write() = Some(block_store);
+    }
+
+    pub(crate) fn start_round(&self, round: Round) {
+        let mut diagnostics = self.round.write();
+        diagnostics.current_round = round;
+        diagnostics.pending_votes = 0;
+    }
+
+    pub(crate) fn set_pending_votes(&self, pending_votes: usize) {
+        self.round.write().pending_votes = pending_votes;
+    }
+
+    pub(crate) fn add_timeout_vote(&self, author: Author) {
+        *self.round.write().timeout_votes.entry(author).or_default() += 1;
+    }
+}
+
+impl LivenessReporter for LivenessDiagnostics {
+    fn liveness_snapshot(&self) -> LivenessSnapshot {
+        self.snapshot()
+    }
+}
@@ -3,7 +3,9 @@
 
 use crate::{
     block_storage::BlockStore,
+    consensus_observer::publisher::{ConsensusPublisher, PublishingStateComputer},
     counters,
+    diagnostics::LivenessDiagnostics,
     error::{error_kind, DbError},
     experimental::{
         buffer_manager::{OrderedBlocks, ResetRequest},
@@ -11,7 +13,7 @@ use crate::{
         ordering_state_computer::OrderingStateComputer,
     },
     liveness::{
-        leader_reputation::{ActiveInactiveHeuristic, AptosDBBackend, LeaderReputation},
+        leader_reputation::{AptosDBBackend, LeaderReputation, ProposerAndVoterHeuristic},
         proposal_generator::ProposalGenerator,
         proposer_election::ProposerElection,
         rotating_proposer_election::{choose_leader, RotatingProposer},
@@ -36,7 +38,9 @@ use aptos_types::{
     account_address::AccountAddress,
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
-    on_chain_config::{OnChainConfigPayload, OnChainConsensusConfig, ValidatorSet},
+    on_chain_config::{
+        OnChainConfigPayload, OnChainConsensusConfig, ValidatorSet, Version, APTOS_VERSION_5,
+    },
     validator_verifier::ValidatorVerifier,
 };
 use channel::{aptos_channel, message_queues::QueueStyle};
@@ -90,6 +94,10 @@ pub struct EpochManager {
     storage: Arc<dyn PersistentLivenessStorage>,
     safety_rules_manager: SafetyRulesManager,
     reconfig_events: ReconfigNotificationListener,
+    // publishes the ordered blocks and the commit decisions to the consensus observers
+    consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    // the liveness of consensus, reported by the admin API
+    liveness_diagnostics: Arc<LivenessDiagnostics>,
     // channels to buffer manager
     buffer_manager_msg_tx: Option<aptos_channel::Sender<AccountAddress, VerifiedEvent>>,
     buffer_manager_reset_tx: Option<UnboundedSender<ResetRequest>>,
@@ -111,6 +119,8 @@ impl EpochManager {
         commit_state_computer: Arc<dyn StateComputer>,
         storage: Arc<dyn PersistentLivenessStorage>,
         reconfig_events: ReconfigNotificationListener,
+        consensus_publisher: Option<Arc<ConsensusPublisher>>,
+        liveness_diagnostics: Arc<LivenessDiagnostics>,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
@@ -128,6 +138,8 @@ impl EpochManager {
             storage,
             safety_rules_manager,
             reconfig_events,
+            consensus_publisher,
+            liveness_diagnostics,
             buffer_manager_msg_tx: None,
             buffer_manager_reset_tx: None,
             round_manager_tx: None,
@@ -158,6 +170,7 @@ impl EpochManager {
             6,
         ));
         RoundState::new(time_interval, time_service, timeout_sender)
+            .with_diagnostics(self.liveness_diagnostics.clone())
     }
 
     /// Create a proposer election handler based on proposers
@@ -188,10 +201,12 @@ impl EpochManager {
                     proposers.len(),
                     self.storage.aptos_db(),
                 ));
-                let heuristic = Box::new(ActiveInactiveHeuristic::new(
+                let heuristic = Box::new(ProposerAndVoterHeuristic::new(
                     self.author,
                     heuristic_config.active_weights,
                     heuristic_config.inactive_weights,
+                    heuristic_config.failed_weights,
+                    heuristic_config.failure_threshold_percent,
                 ));
                 Box::new(LeaderReputation::new(
                     proposers,
@@ -378,6 +393,7 @@ impl EpochManager {
         recovery_data: RecoveryData,
         epoch_state: EpochState,
         onchain_config: OnChainConsensusConfig,
+        version: Option<Version>,
     ) {
         let epoch = epoch_state.epoch;
         counters::EPOCH.set(epoch_state.epoch as i64);
@@ -425,6 +441,13 @@ impl EpochManager {
         } else {
             self.commit_state_computer.clone()
         };
+        let state_computer: Arc<dyn StateComputer> = match &self.consensus_publisher {
+            Some(publisher) => Arc::new(PublishingStateComputer::new(
+                state_computer,
+                publisher.clone(),
+            )),
+            None => state_computer,
+        };
 
         info!(epoch = epoch, "Create BlockStore");
         let block_store = Arc::new(BlockStore::new(
@@ -435,6 +458,8 @@ impl EpochManager {
             Arc::clone(&self.time_service),
             onchain_config.back_pressure_limit(),
         ));
+        self.liveness_diagnostics
+            .set_block_store(block_store.clone());
 
         info!(epoch = epoch, "Create ProposalGenerator");
         // txn manager is required both by proposal generator (to pull the proposers)
@@ -445,6 +470,13 @@ impl EpochManager {
             self.txn_manager.clone(),
             self.time_service.clone(),
             self.config.max_block_size,
+            self.config.max_block_bytes,
+            self.config.max_block_gas,
+            // The older validators can't decode the proposals reporting failed authors
+            version
+                .filter(|version| *version >= APTOS_VERSION_5)
+                .map(|_| self.config.max_failed_authors_to_store),
+            self.config.max_committed_blocks_to_exclude,
         );
 
         let mut round_manager = RoundManager::new(
@@ -481,13 +513,14 @@ impl EpochManager {
         self.shutdown_current_processor().await;
 
         let onchain_config: OnChainConsensusConfig = payload.get().unwrap_or_default();
+        let version: Option<Version> = payload.get().ok();
         self.epoch_state = Some(epoch_state.clone());
 
         let initial_data = self
             .storage
             .start()
             .expect_recovery_data("Consensusdb is corrupted, need to do a backup and restore");
-        self.start_round_manager(initial_data, epoch_state, onchain_config)
+        self.start_round_manager(initial_data, epoch_state, onchain_config, version)
             .await;
     }
 
@@ -36,8 +36,12 @@ mod twins;
 mod txn_manager;
 mod util;
 
+/// Consensus observer of the fullnodes, and its publisher
+pub mod consensus_observer;
 /// AptosBFT implementation
 pub mod consensus_provider;
+/// Liveness diagnostics of consensus, reported by the admin API
+pub mod diagnostics;
 /// AptosNet interface.
 pub mod network_interface;
 
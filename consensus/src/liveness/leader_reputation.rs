@@ -2,13 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    counters::{COMMITTED_PROPOSALS_IN_WINDOW, COMMITTED_VOTES_IN_WINDOW},
+    counters::{
+        COMMITTED_PROPOSALS_IN_WINDOW, COMMITTED_VOTES_IN_WINDOW, FAILED_PROPOSALS_IN_WINDOW,
+    },
     liveness::proposer_election::{next, ProposerElection},
 };
 use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
-use aptos_types::block_metadata::{new_block_event_key, NewBlockEvent};
+use aptos_types::{
+    block_metadata::{new_block_event_key, NewBlockEvent},
+    transaction::Version,
+};
 use consensus_types::{
     block::Block,
     common::{Author, Round},
@@ -22,15 +27,29 @@ use storage_interface::{DbReader, Order};
 
 /// Interface to query committed BlockMetadata.
 pub trait MetadataBackend: Send + Sync {
-    /// Return a contiguous BlockMetadata window in which last one is at target_round or
-    /// latest committed, return all previous one if not enough.
+    /// Return a contiguous BlockMetadata window in which last one is the latest committed at or
+    /// before target_round, return all previous one if not enough.
     fn get_block_metadata(&self, target_round: Round) -> Vec<NewBlockEvent>;
 }
 
+/// The most recent NewBlockEvents committed at a version, latest first
+#[derive(Default)]
+struct CommittedEvents {
+    version: Version,
+    events: Vec<NewBlockEvent>,
+    // Sequence number of the oldest fetched event, None if none is fetched yet
+    oldest_sequence_number: Option<u64>,
+    // Whether the events go back to the first one
+    exhausted: bool,
+}
+
+/// Reads the window from the NewBlockEvents of the DB, whatever the target round: the proposers
+/// of past rounds (e.g. the failed authors of a proposal) are computed the same by every
+/// validator that committed the window.
 pub struct AptosDBBackend {
     window_size: usize,
     aptos_db: Arc<dyn DbReader>,
-    window: Mutex<Vec<(u64, NewBlockEvent)>>,
+    committed_events: Mutex<CommittedEvents>,
 }
 
 impl AptosDBBackend {
@@ -38,56 +57,74 @@ impl AptosDBBackend {
         Self {
             window_size,
             aptos_db,
-            window: Mutex::new(vec![]),
+            committed_events: Mutex::new(CommittedEvents::default()),
         }
     }
 
-    fn refresh_window(&self, target_round: Round) -> anyhow::Result<()> {
-        // assumes target round is not too far from latest commit
+    fn window(&self, events: &[NewBlockEvent], target_round: Round) -> Vec<NewBlockEvent> {
+        events
+            .iter()
+            .filter(|event| event.round() <= target_round)
+            .take(self.window_size)
+            .cloned()
+            .collect()
+    }
+
+    fn fetch_window(&self, target_round: Round) -> anyhow::Result<Vec<NewBlockEvent>> {
+        let latest_version = self.aptos_db.get_latest_version()?;
+        let mut committed_events = self.committed_events.lock();
+        if committed_events.version != latest_version {
+            *committed_events = CommittedEvents {
+                version: latest_version,
+                ..CommittedEvents::default()
+            };
+        }
+        // The events after the target round are skipped, so there may be more to fetch than the
+        // window, e.g. for the rounds long before the latest commit.
         let buffer = 10;
-        let events = self.aptos_db.get_events(
-            &new_block_event_key(),
-            u64::max_value(),
-            Order::Descending,
-            self.window_size as u64 + buffer,
-        )?;
-        let mut result = vec![];
-        for (v, e) in events {
-            let e = bcs::from_bytes::<NewBlockEvent>(e.event_data())?;
-            if e.round() <= target_round && result.len() < self.window_size {
-                result.push((v, e));
+        loop {
+            let window = self.window(&committed_events.events, target_round);
+            if window.len() == self.window_size || committed_events.exhausted {
+                return Ok(window);
+            }
+            let start = match committed_events.oldest_sequence_number {
+                Some(0) => {
+                    committed_events.exhausted = true;
+                    continue;
+                }
+                Some(sequence_number) => sequence_number - 1,
+                None => u64::max_value(),
+            };
+            let limit = self.window_size as u64 + buffer;
+            let events = self.aptos_db.get_events(
+                &new_block_event_key(),
+                start,
+                Order::Descending,
+                limit,
+            )?;
+            committed_events.exhausted = (events.len() as u64) < limit;
+            for (version, event) in events {
+                committed_events.oldest_sequence_number = Some(event.sequence_number());
+                // Committed while fetching
+                if version > committed_events.version {
+                    continue;
+                }
+                committed_events
+                    .events
+                    .push(NewBlockEvent::try_from_bytes(event.event_data())?);
             }
         }
-        *self.window.lock() = result;
-        Ok(())
     }
 }
 
 impl MetadataBackend for AptosDBBackend {
-    // assume the target_round only increases
     fn get_block_metadata(&self, target_round: Round) -> Vec<NewBlockEvent> {
-        let (known_version, known_round) = self
-            .window
-            .lock()
-            .first()
-            .map(|(v, e)| (*v, e.round()))
-            .unwrap_or((0, 0));
-        if !(known_round == target_round
-            || known_version == self.aptos_db.get_latest_version().unwrap_or(0))
-        {
-            if let Err(e) = self.refresh_window(target_round) {
-                error!(
-                    error = ?e, "[leader reputation] Fail to refresh window",
-                );
-                return vec![];
-            }
-        }
-        self.window
-            .lock()
-            .clone()
-            .into_iter()
-            .map(|(_, e)| e)
-            .collect()
+        self.fetch_window(target_round).unwrap_or_else(|e| {
+            error!(
+                error = ?e, "[leader reputation] Fail to refresh window",
+            );
+            vec![]
+        })
     }
 }
 
@@ -153,6 +190,62 @@ impl ReputationHeuristic for ActiveInactiveHeuristic {
     }
 }
 
+/// Candidates whose proposals failed in the history (i.e. their rounds timed out, as reported by
+/// the next committed proposal) more often than the threshold are assigned failed_weight, so that a
+/// degraded validator is rarely chosen and the rounds don't keep timing out. The other candidates
+/// are weighted by ActiveInactiveHeuristic.
+pub struct ProposerAndVoterHeuristic {
+    author: Author,
+    active_inactive: ActiveInactiveHeuristic,
+    failed_weight: u64,
+    failure_threshold_percent: u32,
+}
+
+impl ProposerAndVoterHeuristic {
+    pub fn new(
+        author: Author,
+        active_weight: u64,
+        inactive_weight: u64,
+        failed_weight: u64,
+        failure_threshold_percent: u32,
+    ) -> Self {
+        Self {
+            author,
+            active_inactive: ActiveInactiveHeuristic::new(author, active_weight, inactive_weight),
+            failed_weight,
+            failure_threshold_percent,
+        }
+    }
+}
+
+impl ReputationHeuristic for ProposerAndVoterHeuristic {
+    fn get_weights(&self, candidates: &[Author], history: &[NewBlockEvent]) -> Vec<u64> {
+        let mut proposals: HashMap<Author, (u64, u64)> = HashMap::new();
+        for meta in history {
+            proposals.entry(meta.proposer()).or_default().0 += 1;
+            for failed_proposer in meta.failed_proposers() {
+                proposals.entry(*failed_proposer).or_default().1 += 1;
+            }
+        }
+        FAILED_PROPOSALS_IN_WINDOW.set(proposals.get(&self.author).map_or(0, |p| p.1) as i64);
+
+        self.active_inactive
+            .get_weights(candidates, history)
+            .into_iter()
+            .zip(candidates)
+            .map(|(weight, author)| {
+                let (committed, failed) = proposals.get(author).copied().unwrap_or_default();
+                // failed / (committed + failed) > threshold, without the division
+                if failed * 100 > (committed + failed) * self.failure_threshold_percent as u64 {
+                    self.failed_weight
+                } else {
+                    weight
+                }
+            })
+            .collect()
+    }
+}
+
 /// Committed history based proposer election implementation that could help bias towards
 /// successful leaders to help improve performance.
 pub struct LeaderReputation {
@@ -3,15 +3,28 @@
 
 use crate::liveness::{
     leader_reputation::{
-        ActiveInactiveHeuristic, LeaderReputation, MetadataBackend, ReputationHeuristic,
+        ActiveInactiveHeuristic, AptosDBBackend, LeaderReputation, MetadataBackend,
+        ProposerAndVoterHeuristic, ReputationHeuristic,
     },
     proposer_election::{next, ProposerElection},
 };
-use aptos_types::{block_metadata::NewBlockEvent, validator_signer::ValidatorSigner};
+use anyhow::Result;
+use aptos_infallible::Mutex;
+use aptos_types::{
+    account_address::AccountAddress,
+    block_metadata::{new_block_event_key, NewBlockEvent},
+    contract_event::ContractEvent,
+    event::EventKey,
+    transaction::Version,
+    validator_signer::ValidatorSigner,
+};
 use consensus_types::{
     block::{block_test_utils::certificate_for_genesis, Block},
     common::{Author, Round},
 };
+use move_core_types::language_storage::TypeTag;
+use std::sync::Arc;
+use storage_interface::{DbReader, Order};
 
 struct MockHistory {
     window_size: usize,
@@ -35,8 +48,68 @@ impl MetadataBackend for MockHistory {
     }
 }
 
+/// The NewBlockEvents committed at their versions, in the order of their sequence numbers
+#[derive(Default)]
+struct MockDb {
+    events: Mutex<Vec<(Version, Vec<u8>)>>,
+}
+
+impl MockDb {
+    fn commit(&self, version: Version, event_data: Vec<u8>) {
+        self.events.lock().push((version, event_data));
+    }
+}
+
+impl DbReader for MockDb {
+    fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+    ) -> Result<Vec<(u64, ContractEvent)>> {
+        assert_eq!(*event_key, new_block_event_key());
+        assert!(order == Order::Descending);
+        let events = self.events.lock();
+        Ok(events
+            .iter()
+            .enumerate()
+            .take(start.saturating_add(1) as usize)
+            .rev()
+            .take(limit as usize)
+            .map(|(sequence_number, (version, event_data))| {
+                let event = ContractEvent::new(
+                    *event_key,
+                    sequence_number as u64,
+                    TypeTag::Bool,
+                    event_data.clone(),
+                );
+                (*version, event)
+            })
+            .collect())
+    }
+
+    fn get_latest_version(&self) -> Result<Version> {
+        Ok(self.events.lock().last().map_or(0, |(version, _)| *version))
+    }
+}
+
 fn create_block(proposer: Author, voters: Vec<&ValidatorSigner>) -> NewBlockEvent {
-    NewBlockEvent::new(0, proposer, voters.iter().map(|v| v.author()).collect(), 0)
+    create_block_with_failed_proposers(proposer, voters, vec![])
+}
+
+fn create_block_with_failed_proposers(
+    proposer: Author,
+    voters: Vec<&ValidatorSigner>,
+    failed_proposers: Vec<Author>,
+) -> NewBlockEvent {
+    NewBlockEvent::new(
+        0,
+        proposer,
+        voters.iter().map(|v| v.author()).collect(),
+        failed_proposers,
+        0,
+    )
 }
 
 #[test]
@@ -76,6 +149,66 @@ fn test_simple_heuristic() {
     }
 }
 
+#[test]
+fn test_proposer_and_voter_heuristic() {
+    let active_weight = 9;
+    let inactive_weight = 1;
+    let failed_weight = 2;
+    let mut proposers = vec![];
+    let mut signers = vec![];
+    for i in 0..6 {
+        let signer = ValidatorSigner::random([i; 32]);
+        proposers.push(signer.author());
+        signers.push(signer);
+    }
+    let heuristic = ProposerAndVoterHeuristic::new(
+        proposers[0],
+        active_weight,
+        inactive_weight,
+        failed_weight,
+        50, /* failure_threshold_percent */
+    );
+    // Sliding window with
+    // [proposer 0, voters 1, 2, failed proposers 1, 3],
+    // [proposer 1, voters 2, failed proposers 4],
+    // [proposer 0, voters 2, failed proposers 1]
+    let weights = heuristic.get_weights(
+        &proposers,
+        &[
+            create_block_with_failed_proposers(
+                proposers[0],
+                vec![&signers[1], &signers[2]],
+                vec![proposers[1], proposers[3]],
+            ),
+            create_block_with_failed_proposers(proposers[1], vec![&signers[2]], vec![proposers[4]]),
+            create_block_with_failed_proposers(proposers[0], vec![&signers[2]], vec![proposers[1]]),
+        ],
+    );
+    // Proposer 1 failed 2 out of 3 proposals, and proposers 3 and 4 all of theirs, while the
+    // other active ones didn't fail, and proposer 5 isn't in the window.
+    assert_eq!(
+        weights,
+        vec![
+            active_weight,
+            failed_weight,
+            active_weight,
+            failed_weight,
+            failed_weight,
+            inactive_weight,
+        ]
+    );
+
+    // At the threshold, the proposals aren't considered failing.
+    let weights = heuristic.get_weights(
+        &proposers[..2],
+        &[
+            create_block_with_failed_proposers(proposers[0], vec![], vec![proposers[1]]),
+            create_block(proposers[1], vec![]),
+        ],
+    );
+    assert_eq!(weights, vec![active_weight, active_weight]);
+}
+
 #[test]
 fn test_api() {
     let active_weight = 9;
@@ -159,3 +292,45 @@ fn test_api() {
     // good proposal still passes
     assert!(proposer_election.is_valid_proposal(&good_proposal));
 }
+
+#[test]
+fn test_aptos_db_backend_any_target_round() {
+    let db = Arc::new(MockDb::default());
+    let proposer = AccountAddress::random();
+    // The events emitted before the failed proposers were added are still in the window.
+    for round in 1..=5u64 {
+        db.commit(
+            round * 2,
+            bcs::to_bytes(&(round, proposer, Vec::<AccountAddress>::new(), 0u64)).unwrap(),
+        );
+    }
+    for round in 6..=50u64 {
+        let event = NewBlockEvent::new(round, proposer, vec![], vec![proposer], 0);
+        db.commit(round * 2, bcs::to_bytes(&event).unwrap());
+    }
+    let backend = AptosDBBackend::new(4, db.clone());
+    let rounds = |target_round| {
+        backend
+            .get_block_metadata(target_round)
+            .iter()
+            .map(|event| event.round())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(rounds(50), vec![50, 49, 48, 47]);
+    // Far before the latest commit, and going back from a later round
+    assert_eq!(rounds(10), vec![10, 9, 8, 7]);
+    assert_eq!(rounds(4), vec![4, 3, 2, 1]);
+    assert!(backend
+        .get_block_metadata(4)
+        .iter()
+        .all(|event| event.failed_proposers().is_empty()));
+    assert_eq!(rounds(2), vec![2, 1]);
+    assert_eq!(rounds(30), vec![30, 29, 28, 27]);
+
+    // The window of a round doesn't change with the later commits.
+    let event = NewBlockEvent::new(51, proposer, vec![], vec![], 0);
+    db.commit(102, bcs::to_bytes(&event).unwrap());
+    assert_eq!(rounds(30), vec![30, 29, 28, 27]);
+    assert_eq!(rounds(60), vec![51, 50, 49, 48]);
+}
@@ -2,19 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::BlockReader, state_replication::TxnManager, util::time_service::TimeService,
+    block_storage::BlockReader, liveness::proposer_election::ProposerElection,
+    state_replication::TxnManager, util::time_service::TimeService,
 };
 use anyhow::{bail, ensure, format_err, Context};
 use consensus_types::{
     block::Block,
     block_data::BlockData,
-    common::{Author, Round},
+    common::{Author, Payload, Round},
     quorum_cert::QuorumCert,
 };
 
 use aptos_infallible::Mutex;
 use futures::future::BoxFuture;
-use std::sync::Arc;
+use std::{cmp::max, sync::Arc};
 
 #[cfg(test)]
 #[path = "proposal_generator_test.rs"]
@@ -41,6 +42,16 @@ pub struct ProposalGenerator {
     time_service: Arc<dyn TimeService>,
     // Max number of transactions to be added to a proposed block.
     max_block_size: u64,
+    // Max number of bytes of the transactions to be added to a proposed block.
+    max_block_bytes: u64,
+    // Max gas of the transactions to be added to a proposed block, estimated by their max gas
+    // amounts.
+    max_block_gas: u64,
+    // Max number of failed authors to be added to a proposed block, None if the on-chain version
+    // predates the proposals reporting them.
+    max_failed_authors_to_store: Option<usize>,
+    // Number of the last committed blocks whose transactions are excluded from the proposed block.
+    max_committed_blocks_to_exclude: usize,
     // Last round that a proposal was generated
     last_round_generated: Mutex<Round>,
 }
@@ -52,6 +63,10 @@ impl ProposalGenerator {
         txn_manager: Arc<dyn TxnManager>,
         time_service: Arc<dyn TimeService>,
         max_block_size: u64,
+        max_block_bytes: u64,
+        max_block_gas: u64,
+        max_failed_authors_to_store: Option<usize>,
+        max_committed_blocks_to_exclude: usize,
     ) -> Self {
         Self {
             author,
@@ -59,6 +74,10 @@ impl ProposalGenerator {
             txn_manager,
             time_service,
             max_block_size,
+            max_block_bytes,
+            max_block_gas,
+            max_failed_authors_to_store,
+            max_committed_blocks_to_exclude,
             last_round_generated: Mutex::new(0),
         }
     }
@@ -83,9 +102,12 @@ impl ProposalGenerator {
     /// 2. The round is provided by the caller.
     /// 3. In case a given round is not greater than the calculated parent, return an OldRound
     /// error.
+    /// 4. The proposers of the rounds skipped since the parent are reported as failed authors, if
+    /// the on-chain version supports it.
     pub async fn generate_proposal(
         &mut self,
         round: Round,
+        proposer_election: &(dyn ProposerElection + Send + Sync),
         wait_callback: BoxFuture<'static, ()>,
     ) -> anyhow::Result<BlockData> {
         {
@@ -98,6 +120,8 @@ impl ProposalGenerator {
         }
 
         let hqc = self.ensure_highest_quorum_cert(round)?;
+        let failed_authors =
+            self.compute_failed_authors(round, hqc.certified_block().round(), proposer_election);
 
         let (payload, timestamp) = if hqc.certified_block().has_reconfiguration() {
             // Reconfiguration rule - we propose empty blocks with parents' timestamp
@@ -112,7 +136,16 @@ impl ProposalGenerator {
                 .ok_or_else(|| format_err!("HQC {} already pruned", hqc.certified_block().id()))?;
             // Avoid txn manager long poll if the root block has txns, so that the leader can
             // deliver the commit proof to others without delay.
-            pending_blocks.push(self.block_store.commit_root());
+            let commit_root = self.block_store.commit_root();
+            // The transactions of the recently committed blocks may still be in mempool if its
+            // commit notification is racing with the broadcast of the transactions.
+            pending_blocks.extend(
+                self.block_store
+                    .recently_committed_blocks(self.max_committed_blocks_to_exclude)
+                    .into_iter()
+                    .filter(|block| block.id() != commit_root.id()),
+            );
+            pending_blocks.push(commit_root);
 
             // Exclude all the pending transactions: these are all the ancestors of
             // parent (including) up to the root (including).
@@ -137,6 +170,8 @@ impl ProposalGenerator {
                 .txn_manager
                 .pull_txns(
                     self.max_block_size,
+                    self.max_block_bytes,
+                    self.max_block_gas,
                     exclude_payload,
                     wait_callback,
                     pending_ordering,
@@ -148,13 +183,79 @@ impl ProposalGenerator {
         };
 
         // create block proposal
-        Ok(BlockData::new_proposal(
-            payload,
-            self.author,
-            round,
-            timestamp,
-            hqc.as_ref().clone(),
-        ))
+        Ok(match failed_authors {
+            Some(failed_authors) => BlockData::new_proposal_ext(
+                payload,
+                self.author,
+                failed_authors,
+                round,
+                timestamp,
+                hqc.as_ref().clone(),
+            ),
+            None => BlockData::new_proposal(
+                payload,
+                self.author,
+                round,
+                timestamp,
+                hqc.as_ref().clone(),
+            ),
+        })
+    }
+
+    /// Returns the proposers of the rounds between `previous_round` and `round` (both exclusive),
+    /// whose proposals failed to be certified, keeping the most recent
+    /// `max_failed_authors_to_store` of them, or None if the proposals don't report them.
+    pub fn compute_failed_authors(
+        &self,
+        round: Round,
+        previous_round: Round,
+        proposer_election: &dyn ProposerElection,
+    ) -> Option<Vec<(Round, Author)>> {
+        let max_failed_authors_to_store = self.max_failed_authors_to_store?;
+        let start = max(
+            previous_round.saturating_add(1),
+            round.saturating_sub(max_failed_authors_to_store as u64),
+        );
+        Some(
+            (start..round)
+                .map(|failed_round| {
+                    (
+                        failed_round,
+                        proposer_election.get_valid_proposer(failed_round),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Checks that the payload of a received proposal is within the bytes and gas limits the
+    /// proposals are generated with. Like the generated ones, a proposal exceeding the limits
+    /// with its first transaction only is accepted.
+    pub fn ensure_payload_within_limits(&self, payload: &Payload) -> anyhow::Result<()> {
+        if payload.len() <= 1 {
+            return Ok(());
+        }
+        let bytes: u64 = payload
+            .iter()
+            .map(|txn| txn.txn_bytes_len() as u64)
+            .fold(0, u64::saturating_add);
+        let gas: u64 = payload
+            .iter()
+            .map(|txn| txn.max_gas_amount())
+            .fold(0, u64::saturating_add);
+        ensure!(
+            bytes <= self.max_block_bytes,
+            "Payload of {} bytes exceeds the limit of {} bytes",
+            bytes,
+            self.max_block_bytes
+        );
+        ensure!(
+            gas <= self.max_block_gas,
+            "Payload of {} gas exceeds the limit of {} gas",
+            gas,
+            self.max_block_gas
+        );
+        Ok(())
     }
 
     fn ensure_highest_quorum_cert(&self, round: Round) -> anyhow::Result<Arc<QuorumCert>> {
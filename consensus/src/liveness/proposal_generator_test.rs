@@ -3,12 +3,17 @@
 
 use crate::{
     block_storage::BlockReader,
-    liveness::proposal_generator::ProposalGenerator,
+    liveness::{
+        proposal_generator::ProposalGenerator, rotating_proposer_election::RotatingProposer,
+    },
     test_utils::{build_empty_tree, MockTransactionManager, TreeInserter},
     util::mock_time_service::SimulatedTimeService,
 };
 use aptos_types::validator_signer::ValidatorSigner;
-use consensus_types::block::{block_test_utils::certificate_for_genesis, Block};
+use consensus_types::block::{
+    block_test_utils::{certificate_for_genesis, random_payload},
+    Block,
+};
 use futures::{future::BoxFuture, FutureExt};
 use std::sync::Arc;
 
@@ -26,22 +31,28 @@ async fn test_proposal_generation_empty_tree() {
         Arc::new(MockTransactionManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        u64::MAX,
+        u64::MAX,
+        Some(10),
+        5,
     );
+    let proposer_election = RotatingProposer::new(vec![signer.author()], 1);
     let genesis = block_store.ordered_root();
 
     // Generate proposals for an empty tree.
     let proposal_data = proposal_generator
-        .generate_proposal(1, empty_callback())
+        .generate_proposal(1, &proposer_election, empty_callback())
         .await
         .unwrap();
     let proposal = Block::new_proposal_from_block_data(proposal_data, &signer);
     assert_eq!(proposal.parent_id(), genesis.id());
     assert_eq!(proposal.round(), 1);
     assert_eq!(proposal.quorum_cert().certified_block().id(), genesis.id());
+    assert_eq!(proposal.block_data().failed_authors(), Some(&vec![]));
 
     // Duplicate proposals on the same round are not allowed
     let proposal_err = proposal_generator
-        .generate_proposal(1, empty_callback())
+        .generate_proposal(1, &proposer_election, empty_callback())
         .await
         .err();
     assert!(proposal_err.is_some());
@@ -57,7 +68,12 @@ async fn test_proposal_generation_parent() {
         Arc::new(MockTransactionManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        u64::MAX,
+        u64::MAX,
+        Some(5),
+        5,
     );
+    let proposer_election = RotatingProposer::new(vec![inserter.signer().author()], 1);
     let genesis = block_store.ordered_root();
     let a1 = inserter
         .insert_block_with_qc(certificate_for_genesis(), &genesis, 1)
@@ -68,29 +84,36 @@ async fn test_proposal_generation_parent() {
 
     // With no certifications the parent is genesis
     // generate proposals for an empty tree.
+    let genesis_child_res = proposal_generator
+        .generate_proposal(10, &proposer_election, empty_callback())
+        .await
+        .unwrap();
+    assert_eq!(genesis_child_res.parent_id(), genesis.id());
+    // The proposers of the most recent skipped rounds are reported
+    let author = inserter.signer().author();
     assert_eq!(
-        proposal_generator
-            .generate_proposal(10, empty_callback())
-            .await
-            .unwrap()
-            .parent_id(),
-        genesis.id()
+        genesis_child_res.failed_authors(),
+        Some(&(5..10).map(|round| (round, author)).collect())
     );
 
     // Once a1 is certified, it should be the one to choose from
     inserter.insert_qc_for_block(a1.as_ref(), None);
     let a1_child_res = proposal_generator
-        .generate_proposal(11, empty_callback())
+        .generate_proposal(11, &proposer_election, empty_callback())
         .await
         .unwrap();
     assert_eq!(a1_child_res.parent_id(), a1.id());
     assert_eq!(a1_child_res.round(), 11);
     assert_eq!(a1_child_res.quorum_cert().certified_block().id(), a1.id());
+    assert_eq!(
+        a1_child_res.failed_authors(),
+        Some(&(6..11).map(|round| (round, author)).collect())
+    );
 
     // Once b1 is certified, it should be the one to choose from
     inserter.insert_qc_for_block(b1.as_ref(), None);
     let b1_child_res = proposal_generator
-        .generate_proposal(12, empty_callback())
+        .generate_proposal(12, &proposer_election, empty_callback())
         .await
         .unwrap();
     assert_eq!(b1_child_res.parent_id(), b1.id());
@@ -108,7 +131,12 @@ async fn test_old_proposal_generation() {
         Arc::new(MockTransactionManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        u64::MAX,
+        u64::MAX,
+        Some(10),
+        5,
     );
+    let proposer_election = RotatingProposer::new(vec![inserter.signer().author()], 1);
     let genesis = block_store.ordered_root();
     let a1 = inserter
         .insert_block_with_qc(certificate_for_genesis(), &genesis, 1)
@@ -116,8 +144,42 @@ async fn test_old_proposal_generation() {
     inserter.insert_qc_for_block(a1.as_ref(), None);
 
     let proposal_err = proposal_generator
-        .generate_proposal(1, empty_callback())
+        .generate_proposal(1, &proposer_election, empty_callback())
         .await
         .err();
     assert!(proposal_err.is_some());
 }
+
+#[test]
+fn test_payload_limits() {
+    let payload = random_payload(3);
+    let payload_bytes: u64 = payload.iter().map(|txn| txn.txn_bytes_len() as u64).sum();
+    let payload_gas: u64 = payload.iter().map(|txn| txn.max_gas_amount()).sum();
+    let proposal_generator = |max_block_bytes, max_block_gas| {
+        ProposalGenerator::new(
+            ValidatorSigner::random(None).author(),
+            build_empty_tree(),
+            Arc::new(MockTransactionManager::new(None)),
+            Arc::new(SimulatedTimeService::new()),
+            10,
+            max_block_bytes,
+            max_block_gas,
+            Some(10),
+            5,
+        )
+    };
+
+    assert!(proposal_generator(payload_bytes, payload_gas)
+        .ensure_payload_within_limits(&payload)
+        .is_ok());
+    assert!(proposal_generator(payload_bytes - 1, u64::MAX)
+        .ensure_payload_within_limits(&payload)
+        .is_err());
+    assert!(proposal_generator(u64::MAX, payload_gas - 1)
+        .ensure_payload_within_limits(&payload)
+        .is_err());
+    // a transaction exceeding the limits on its own is proposed
+    assert!(proposal_generator(1, 1)
+        .ensure_payload_within_limits(&random_payload(1))
+        .is_ok());
+}
@@ -3,6 +3,7 @@
 
 use crate::{
     counters,
+    diagnostics::LivenessDiagnostics,
     pending_votes::{PendingVotes, VoteReceptionResult},
     util::time_service::{SendTask, TimeService},
 };
@@ -152,6 +153,8 @@ pub struct RoundState {
     vote_sent: Option<Vote>,
     // The handle to cancel previous timeout task when moving to next round.
     abort_handle: Option<AbortHandle>,
+    // The liveness of the rounds, reported by the admin API.
+    diagnostics: Arc<LivenessDiagnostics>,
 }
 
 #[derive(Default, Schema)]
@@ -197,9 +200,16 @@ impl RoundState {
             pending_votes: PendingVotes::new(),
             vote_sent: None,
             abort_handle: None,
+            diagnostics: Arc::new(LivenessDiagnostics::default()),
         }
     }
 
+    /// Reports the liveness of the rounds to `diagnostics`.
+    pub fn with_diagnostics(mut self, diagnostics: Arc<LivenessDiagnostics>) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
     /// Return the current round.
     pub fn current_round(&self) -> Round {
         self.current_round
@@ -233,6 +243,8 @@ impl RoundState {
             // Start a new round.
             self.current_round = new_round;
             self.pending_votes = PendingVotes::new();
+            counters::PENDING_VOTES.set(0);
+            self.diagnostics.start_round(new_round);
             self.vote_sent = None;
             let timeout = self.setup_timeout();
             // The new round reason is QCReady in case both QC.round + 1 == new_round, otherwise
@@ -259,7 +271,22 @@ impl RoundState {
         verifier: &ValidatorVerifier,
     ) -> VoteReceptionResult {
         if vote.vote_data().proposed().round() == self.current_round {
-            self.pending_votes.insert_vote(vote, verifier)
+            let result = self.pending_votes.insert_vote(vote, verifier);
+            counters::PENDING_VOTES.set(self.pending_votes.num_authors() as i64);
+            self.diagnostics
+                .set_pending_votes(self.pending_votes.num_authors());
+            if vote.is_timeout()
+                && !matches!(
+                    result,
+                    VoteReceptionResult::DuplicateVote | VoteReceptionResult::EquivocateVote
+                )
+            {
+                counters::TIMEOUT_VOTES_COUNT
+                    .with_label_values(&[&vote.author().to_string()])
+                    .inc();
+                self.diagnostics.add_timeout_vote(vote.author());
+            }
+            result
         } else {
             VoteReceptionResult::UnexpectedRound(
                 vote.vote_data().proposed().round(),
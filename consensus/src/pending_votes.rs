@@ -219,6 +219,11 @@ impl PendingVotes {
 
         VoteReceptionResult::VoteAdded(voting_power)
     }
+
+    /// Returns the count of the authors whose votes have been collected
+    pub fn num_authors(&self) -> usize {
+        self.author_to_vote.len()
+    }
 }
 
 //
@@ -266,7 +266,11 @@ impl RoundManager {
         .boxed();
         let proposal = self
             .proposal_generator
-            .generate_proposal(new_round_event.round, callback)
+            .generate_proposal(
+                new_round_event.round,
+                self.proposer_election.as_ref(),
+                callback,
+            )
             .await?;
         let signature = self.safety_rules.lock().sign_proposal(&proposal)?;
         let signed_proposal =
@@ -542,6 +546,27 @@ impl RoundManager {
             proposal,
         );
 
+        // The proposals report their failed authors iff the on-chain version supports it.
+        let expected_failed_authors = self.proposal_generator.compute_failed_authors(
+            proposal.round(),
+            proposal.quorum_cert().certified_block().round(),
+            self.proposer_election.as_ref(),
+        );
+        ensure!(
+            proposal.block_data().failed_authors() == expected_failed_authors.as_ref(),
+            "[RoundManager] Proposal for round {} has invalid failed authors {:?}, \
+            expected {:?}",
+            proposal.round(),
+            proposal.block_data().failed_authors(),
+            expected_failed_authors,
+        );
+
+        if let Some(payload) = proposal.payload() {
+            self.proposal_generator
+                .ensure_payload_within_limits(payload)
+                .with_context(|| format!("[RoundManager] Proposal {} is too large", proposal))?;
+        }
+
         let block_time_since_epoch = Duration::from_micros(proposal.timestamp_usecs());
 
         ensure!(
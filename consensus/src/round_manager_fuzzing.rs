@@ -145,6 +145,10 @@ fn create_node_for_fuzzing() -> RoundManager {
         Arc::new(MockTransactionManager::new(None)),
         time_service,
         1,
+        u64::MAX,
+        u64::MAX,
+        None,
+        5,
     );
 
     //
@@ -39,6 +39,7 @@ use consensus_types::{
         block_test_utils::{certificate_for_genesis, gen_test_certificate},
         Block,
     },
+    block_data::BlockData,
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalStatus},
     common::{Author, Payload},
     proposal_msg::ProposalMsg,
@@ -78,6 +79,7 @@ pub struct NodeSetup {
     commit_cb_receiver: mpsc::UnboundedReceiver<LedgerInfoWithSignatures>,
     _state_sync_receiver: mpsc::UnboundedReceiver<Payload>,
     id: usize,
+    max_failed_authors_to_store: Option<usize>,
 }
 
 impl NodeSetup {
@@ -96,6 +98,17 @@ impl NodeSetup {
         playground: &mut NetworkPlayground,
         executor: Handle,
         num_nodes: usize,
+    ) -> Vec<Self> {
+        Self::create_nodes_with_failed_authors(playground, executor, num_nodes, None)
+    }
+
+    /// Creates nodes whose proposals report up to `max_failed_authors_to_store` failed authors,
+    /// as if the on-chain version supported them
+    fn create_nodes_with_failed_authors(
+        playground: &mut NetworkPlayground,
+        executor: Handle,
+        num_nodes: usize,
+        max_failed_authors_to_store: Option<usize>,
     ) -> Vec<Self> {
         let (signers, validators) = random_validator_verifier(num_nodes, None, false);
         let proposer_author = signers[0].author();
@@ -137,6 +150,7 @@ impl NodeSetup {
                 initial_data,
                 safety_rules_manager,
                 id,
+                max_failed_authors_to_store,
             ));
         }
         nodes
@@ -151,6 +165,7 @@ impl NodeSetup {
         initial_data: RecoveryData,
         safety_rules_manager: SafetyRulesManager,
         id: usize,
+        max_failed_authors_to_store: Option<usize>,
     ) -> Self {
         let epoch_state = EpochState {
             epoch: 1,
@@ -204,6 +219,10 @@ impl NodeSetup {
             Arc::new(MockTransactionManager::new(None)),
             time_service.clone(),
             1,
+            u64::MAX,
+            u64::MAX,
+            max_failed_authors_to_store,
+            5,
         );
 
         let round_state = Self::create_round_state(time_service);
@@ -236,6 +255,7 @@ impl NodeSetup {
             commit_cb_receiver,
             _state_sync_receiver,
             id,
+            max_failed_authors_to_store,
         }
     }
 
@@ -253,6 +273,7 @@ impl NodeSetup {
             recover_data,
             self.safety_rules_manager,
             self.id,
+            self.max_failed_authors_to_store,
         )
     }
 
@@ -550,6 +571,99 @@ fn new_round_on_timeout_certificate() {
     });
 }
 
+#[test]
+/// We don't vote for proposals that skip rounds without reporting their failed authors
+fn no_vote_on_invalid_failed_authors() {
+    let mut runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut node = NodeSetup::create_nodes_with_failed_authors(
+        &mut playground,
+        runtime.handle().clone(),
+        1,
+        Some(10),
+    )
+    .pop()
+    .unwrap();
+    let genesis_qc = certificate_for_genesis();
+    let block_missing_failed_authors =
+        Block::new_proposal(vec![], 2, 2, genesis_qc.clone(), &node.signer);
+    let block_with_failed_authors = Block::new_proposal_from_block_data(
+        BlockData::new_proposal_ext(
+            vec![],
+            node.signer.author(),
+            vec![(1, node.signer.author())],
+            2,
+            3,
+            genesis_qc.clone(),
+        ),
+        &node.signer,
+    );
+    let timeout = Timeout::new(1, 1);
+    let timeout_signature = timeout.sign(&node.signer);
+
+    let mut tc = TimeoutCertificate::new(timeout);
+    tc.add_signature(node.signer.author(), timeout_signature);
+
+    timed_block_on(&mut runtime, async {
+        let bad_proposal = ProposalMsg::new(
+            block_missing_failed_authors,
+            SyncInfo::new(
+                genesis_qc.clone(),
+                genesis_qc.clone(),
+                Some(tc.clone()),
+                None,
+            ),
+        );
+        assert!(node
+            .round_manager
+            .process_proposal_msg(bad_proposal)
+            .await
+            .is_err());
+        let good_proposal = ProposalMsg::new(
+            block_with_failed_authors,
+            SyncInfo::new(genesis_qc.clone(), genesis_qc.clone(), Some(tc), None),
+        );
+        node.round_manager
+            .process_proposal_msg(good_proposal)
+            .await
+            .unwrap();
+    });
+}
+
+#[test]
+/// We don't vote for proposals reporting failed authors until the on-chain version supports them
+fn no_vote_on_failed_authors_before_enabled() {
+    let mut runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut node = NodeSetup::create_nodes(&mut playground, runtime.handle().clone(), 1)
+        .pop()
+        .unwrap();
+    let genesis_qc = certificate_for_genesis();
+    let block_with_failed_authors = Block::new_proposal_from_block_data(
+        BlockData::new_proposal_ext(
+            vec![],
+            node.signer.author(),
+            vec![],
+            1,
+            1,
+            genesis_qc.clone(),
+        ),
+        &node.signer,
+    );
+
+    timed_block_on(&mut runtime, async {
+        let proposal = ProposalMsg::new(
+            block_with_failed_authors,
+            SyncInfo::new(genesis_qc.clone(), genesis_qc, None, None),
+        );
+        assert!(node
+            .round_manager
+            .process_proposal_msg(proposal)
+            .await
+            .is_err());
+    });
+}
+
 #[test]
 fn response_on_block_retrieval() {
     let mut runtime = consensus_runtime();
@@ -20,11 +20,16 @@ pub trait TxnManager: Send + Sync {
     /// The `exclude_txns` list includes the transactions that are already pending in the
     /// branch of blocks consensus is trying to extend.
     ///
+    /// At most `max_size` transactions are pulled, with at most `max_bytes` in total and at most
+    /// `max_gas` gas in total as estimated by their max gas amounts.
+    ///
     /// wait_callback is executed when there's no transactions available and it decides to wait.
     /// pending_ordering indicates if we should long poll mempool or propose empty blocks to help commit pending txns
     async fn pull_txns(
         &self,
         max_size: u64,
+        max_bytes: u64,
+        max_gas: u64,
         exclude: Vec<&Payload>,
         wait_callback: BoxFuture<'static, ()>,
         pending_ordering: bool,
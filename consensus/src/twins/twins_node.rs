@@ -3,6 +3,7 @@
 
 use crate::{
     counters,
+    diagnostics::LivenessDiagnostics,
     epoch_manager::EpochManager,
     network::NetworkTask,
     network_interface::{ConsensusNetworkEvents, ConsensusNetworkSender},
@@ -134,6 +135,8 @@ impl SMRNode {
             state_computer,
             storage.clone(),
             reconfig_listener,
+            None,
+            Arc::new(LivenessDiagnostics::default()),
         );
         let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
 
@@ -0,0 +1,559 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides an API for the BLS multi-signature scheme over the BLS12-381 curve, as
+//! defined in the [IETF draft](https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-bls-signature-04),
+//! with the public keys in G1 and the signatures in G2 (the `minimal-pubkey-size` variant).
+//!
+//! The signatures of several signers on the same message aggregate into a single signature,
+//! verified against the aggregate of their public keys with a single pairing check. This makes
+//! the certificates of a quorum of signers as small, and as cheap to verify, as one signature.
+//!
+//! Aggregating the public keys is only safe against rogue-key attacks if each of them comes with
+//! a verified [`Bls12381ProofOfPossession`] of its private key, as required by the
+//! proof-of-possession ciphersuite implemented here.
+//!
+//! Only the crypto suite is provided for now: the consensus votes and quorum certificates are
+//! still signed with Ed25519. Aggregating them with BLS needs the validators to register a BLS
+//! public key (with its proof of possession) on chain, a new wire format of the votes and of the
+//! `LedgerInfoWithSignatures` (a bitmap of the signers and one aggregate signature), and
+//! `ValidatorVerifier` to verify it, all gated by a new on-chain version.
+//!
+//! # Examples
+//!
+//! ```
+//! use aptos_crypto_derive::{CryptoHasher, BCSCryptoHash};
+//! use aptos_crypto::{
+//!     bls12381::*,
+//!     traits::{Signature, SigningKey, Uniform},
+//! };
+//! use rand::{rngs::StdRng, SeedableRng};
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+//! pub struct TestCryptoDocTest(String);
+//! let message = TestCryptoDocTest("Test message".to_string());
+//!
+//! let mut rng: StdRng = SeedableRng::from_seed([0; 32]);
+//! let private_keys: Vec<_> = (0..3).map(|_| Bls12381PrivateKey::generate(&mut rng)).collect();
+//! let public_keys: Vec<_> = private_keys.iter().map(Bls12381PublicKey::from).collect();
+//! let signatures: Vec<_> = private_keys.iter().map(|key| key.sign(&message)).collect();
+//!
+//! let signature = Bls12381Signature::aggregate(signatures).unwrap();
+//! let public_keys: Vec<_> = public_keys.iter().collect();
+//! assert!(signature.verify_aggregate(&message, &public_keys).is_ok());
+//! ```
+//! **Note**: The above example generates a private key using a private function intended only for
+//! testing purposes. Production code should find an alternate means for secure key generation.
+
+use crate::{
+    hash::{CryptoHash, CryptoHasher},
+    traits::*,
+};
+use anyhow::{anyhow, Result};
+use aptos_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use blst::{
+    blst_scalar,
+    min_pk::{AggregatePublicKey, AggregateSignature},
+    BLST_ERROR,
+};
+use core::convert::TryFrom;
+use rand::RngCore;
+use serde::Serialize;
+use std::fmt;
+
+/// The length of the Bls12381PrivateKey
+pub const BLS12381_PRIVATE_KEY_LENGTH: usize = 32;
+/// The length of the Bls12381PublicKey, a compressed point of G1
+pub const BLS12381_PUBLIC_KEY_LENGTH: usize = 48;
+/// The length of the Bls12381Signature, a compressed point of G2
+pub const BLS12381_SIGNATURE_LENGTH: usize = 96;
+
+/// The domain separation tag of the signatures of the proof-of-possession ciphersuite
+const DST_BLS_SIG: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// The domain separation tag of the proofs of possession, distinct from the one of the
+/// signatures so a proof of possession is never a valid signature of a message
+const DST_BLS_POP: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// A BLS12-381 private key
+#[derive(DeserializeKey, SerializeKey, SilentDebug, SilentDisplay)]
+pub struct Bls12381PrivateKey(blst::min_pk::SecretKey);
+
+#[cfg(feature = "assert-private-keys-not-cloneable")]
+static_assertions::assert_not_impl_any!(Bls12381PrivateKey: Clone);
+
+#[cfg(any(test, feature = "cloneable-private-keys"))]
+impl Clone for Bls12381PrivateKey {
+    fn clone(&self) -> Self {
+        Bls12381PrivateKey(self.0.clone())
+    }
+}
+
+/// A BLS12-381 public key, validated to be in the prime-order subgroup of G1
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Bls12381PublicKey(blst::min_pk::PublicKey);
+
+/// A BLS12-381 signature, or the aggregate of the signatures of several signers on the same
+/// message. The signatures are validated to be in the prime-order subgroup of G2 when verified.
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Bls12381Signature(blst::min_pk::Signature);
+
+/// A proof of possession of the private key of a public key: the signature of the public key
+/// itself, under a dedicated domain separation tag.
+#[derive(DeserializeKey, Clone, SerializeKey)]
+pub struct Bls12381ProofOfPossession(blst::min_pk::Signature);
+
+fn check_blst_result(result: BLST_ERROR) -> Result<()> {
+    match result {
+        BLST_ERROR::BLST_SUCCESS => Ok(()),
+        e => Err(anyhow!("{:?}", e)),
+    }
+}
+
+impl Bls12381PrivateKey {
+    /// The length of the Bls12381PrivateKey
+    pub const LENGTH: usize = BLS12381_PRIVATE_KEY_LENGTH;
+
+    /// Serialize a Bls12381PrivateKey.
+    pub fn to_bytes(&self) -> [u8; BLS12381_PRIVATE_KEY_LENGTH] {
+        self.0.to_bytes()
+    }
+
+    /// Private function aimed at minimizing code duplication between sign
+    /// methods of the SigningKey implementation. This should remain private.
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Bls12381Signature {
+        Bls12381Signature(self.0.sign(message, DST_BLS_SIG, &[]))
+    }
+}
+
+impl Bls12381PublicKey {
+    /// Serialize a Bls12381PublicKey, in its compressed form.
+    pub fn to_bytes(&self) -> [u8; BLS12381_PUBLIC_KEY_LENGTH] {
+        self.0.to_bytes()
+    }
+
+    /// Aggregates the public keys of the signers of an aggregate signature. The caller must have
+    /// verified the proof of possession of each of the keys, e.g. when they were registered,
+    /// otherwise a signer could forge the aggregate signature of the others.
+    pub fn aggregate(public_keys: Vec<&Self>) -> Result<Self> {
+        let public_keys: Vec<_> = public_keys.iter().map(|key| &key.0).collect();
+        // The keys are validated on deserialization.
+        let aggregate = AggregatePublicKey::aggregate(&public_keys, false)
+            .map_err(|e| anyhow!("Failed to aggregate the public keys: {:?}", e))?;
+        Ok(Bls12381PublicKey(aggregate.to_public_key()))
+    }
+}
+
+impl Bls12381Signature {
+    /// The length of the Bls12381Signature
+    pub const LENGTH: usize = BLS12381_SIGNATURE_LENGTH;
+
+    /// Serialize a Bls12381Signature, in its compressed form.
+    pub fn to_bytes(&self) -> [u8; BLS12381_SIGNATURE_LENGTH] {
+        self.0.to_bytes()
+    }
+
+    /// return the signature of a dummy message (for test only)
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn dummy_signature() -> Self {
+        Bls12381PrivateKey::genesis().sign_arbitrary_message(b"dummy")
+    }
+
+    /// Aggregates the signatures of several signers on the same message into one signature,
+    /// verified with [`Self::verify_aggregate`] against the public keys of the signers.
+    pub fn aggregate(signatures: Vec<Self>) -> Result<Self> {
+        let signatures: Vec<_> = signatures.iter().map(|signature| &signature.0).collect();
+        let aggregate = AggregateSignature::aggregate(&signatures, true)
+            .map_err(|e| anyhow!("Failed to aggregate the signatures: {:?}", e))?;
+        Ok(Bls12381Signature(aggregate.to_signature()))
+    }
+
+    /// Verifies an aggregate signature of the same message by all the given public keys, with
+    /// a single pairing check. The proofs of possession of the keys must have been verified.
+    pub fn verify_aggregate<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_keys: &[&Bls12381PublicKey],
+    ) -> Result<()> {
+        let public_keys: Vec<_> = public_keys.iter().map(|key| &key.0).collect();
+        check_blst_result(self.0.fast_aggregate_verify(
+            true,
+            &signing_message(message),
+            DST_BLS_SIG,
+            &public_keys,
+        ))
+    }
+}
+
+impl Bls12381ProofOfPossession {
+    /// Creates the proof of possession of a private key.
+    pub fn create(private_key: &Bls12381PrivateKey) -> Self {
+        let public_key = Bls12381PublicKey::from(private_key);
+        Bls12381ProofOfPossession(private_key.0.sign(&public_key.to_bytes(), DST_BLS_POP, &[]))
+    }
+
+    /// Verifies that the proof of possession was created with the private key of the public key.
+    pub fn verify(&self, public_key: &Bls12381PublicKey) -> Result<()> {
+        check_blst_result(self.0.verify(
+            true,
+            &public_key.to_bytes(),
+            DST_BLS_POP,
+            &[],
+            &public_key.0,
+            false,
+        ))
+    }
+
+    /// Serialize a Bls12381ProofOfPossession, in its compressed form.
+    pub fn to_bytes(&self) -> [u8; BLS12381_SIGNATURE_LENGTH] {
+        self.0.to_bytes()
+    }
+}
+
+///////////////////////
+// PrivateKey Traits //
+///////////////////////
+
+impl PrivateKey for Bls12381PrivateKey {
+    type PublicKeyMaterial = Bls12381PublicKey;
+}
+
+impl SigningKey for Bls12381PrivateKey {
+    type VerifyingKeyMaterial = Bls12381PublicKey;
+    type SignatureMaterial = Bls12381Signature;
+
+    fn sign<T: CryptoHash + Serialize>(&self, message: &T) -> Bls12381Signature {
+        Bls12381PrivateKey::sign_arbitrary_message(self, signing_message(message).as_ref())
+    }
+
+    #[cfg(any(test, feature = "fuzzing"))]
+    fn sign_arbitrary_message(&self, message: &[u8]) -> Bls12381Signature {
+        Bls12381PrivateKey::sign_arbitrary_message(self, message)
+    }
+}
+
+impl Uniform for Bls12381PrivateKey {
+    fn generate<R>(rng: &mut R) -> Self
+    where
+        R: ::rand::RngCore + ::rand::CryptoRng + ::rand_core::CryptoRng + ::rand_core::RngCore,
+    {
+        // The key is derived from 32 bytes of input keying material, as in the IETF draft.
+        let mut ikm = [0u8; 32];
+        rng.fill_bytes(&mut ikm);
+        Bls12381PrivateKey(
+            blst::min_pk::SecretKey::key_gen(&ikm, &[])
+                .expect("The input keying material is long enough"),
+        )
+    }
+}
+
+impl PartialEq<Self> for Bls12381PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Bls12381PrivateKey {}
+
+impl TryFrom<&[u8]> for Bls12381PrivateKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Bls12381PrivateKey. This method will also check that the key is a non-zero
+    /// scalar smaller than the order of the groups.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Bls12381PrivateKey, CryptoMaterialError> {
+        if bytes.len() != BLS12381_PRIVATE_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        blst::min_pk::SecretKey::from_bytes(bytes)
+            .map(Bls12381PrivateKey)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl Length for Bls12381PrivateKey {
+    fn length(&self) -> usize {
+        Self::LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Bls12381PrivateKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl Genesis for Bls12381PrivateKey {
+    fn genesis() -> Self {
+        let mut buf = [0u8; BLS12381_PRIVATE_KEY_LENGTH];
+        buf[BLS12381_PRIVATE_KEY_LENGTH - 1] = 1;
+        Self::try_from(buf.as_ref()).unwrap()
+    }
+}
+
+//////////////////////
+// PublicKey Traits //
+//////////////////////
+
+impl From<&Bls12381PrivateKey> for Bls12381PublicKey {
+    fn from(private_key: &Bls12381PrivateKey) -> Self {
+        Bls12381PublicKey(private_key.0.sk_to_pk())
+    }
+}
+
+impl PublicKey for Bls12381PublicKey {
+    type PrivateKeyMaterial = Bls12381PrivateKey;
+}
+
+impl std::hash::Hash for Bls12381PublicKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write(&self.to_bytes());
+    }
+}
+
+impl PartialEq for Bls12381PublicKey {
+    fn eq(&self, other: &Bls12381PublicKey) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Bls12381PublicKey {}
+
+impl VerifyingKey for Bls12381PublicKey {
+    type SigningKeyMaterial = Bls12381PrivateKey;
+    type SignatureMaterial = Bls12381Signature;
+}
+
+impl fmt::Display for Bls12381PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()))
+    }
+}
+
+impl fmt::Debug for Bls12381PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bls12381PublicKey({})", self)
+    }
+}
+
+impl TryFrom<&[u8]> for Bls12381PublicKey {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Bls12381PublicKey. This method will also check that the key is in the
+    /// prime-order subgroup and isn't the identity, which rules out small subgroup attacks.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Bls12381PublicKey, CryptoMaterialError> {
+        if bytes.len() != BLS12381_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        blst::min_pk::PublicKey::key_validate(bytes)
+            .map(Bls12381PublicKey)
+            .map_err(|_| CryptoMaterialError::ValidationError)
+    }
+}
+
+impl Length for Bls12381PublicKey {
+    fn length(&self) -> usize {
+        BLS12381_PUBLIC_KEY_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Bls12381PublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+//////////////////////
+// Signature Traits //
+//////////////////////
+
+impl Signature for Bls12381Signature {
+    type VerifyingKeyMaterial = Bls12381PublicKey;
+    type SigningKeyMaterial = Bls12381PrivateKey;
+
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Bls12381PublicKey,
+    ) -> Result<()> {
+        let mut bytes = <T::Hasher as CryptoHasher>::seed().to_vec();
+        bcs::serialize_into(&mut bytes, &message)
+            .map_err(|_| CryptoMaterialError::SerializationError)?;
+        Self::verify_arbitrary_msg(self, &bytes, public_key)
+    }
+
+    /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`. The
+    /// signature is checked to be in the prime-order subgroup of G2.
+    fn verify_arbitrary_msg(&self, message: &[u8], public_key: &Bls12381PublicKey) -> Result<()> {
+        check_blst_result(
+            self.0
+                .verify(true, message, DST_BLS_SIG, &[], &public_key.0, false),
+        )
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+
+    /// Verifies that each of the signatures is valid, with a single multi-pairing check. Unlike
+    /// the verification of their aggregate, which a pair of invalid signatures summing to the
+    /// aggregate would pass, each signature is weighted by a random 64-bit scalar.
+    fn batch_verify<T: CryptoHash + Serialize>(
+        message: &T,
+        keys_and_signatures: Vec<(Self::VerifyingKeyMaterial, Self)>,
+    ) -> Result<()> {
+        let message = signing_message(message);
+        let messages = vec![message.as_slice(); keys_and_signatures.len()];
+        let (public_keys, signatures): (Vec<_>, Vec<_>) = keys_and_signatures
+            .iter()
+            .map(|(public_key, signature)| (&public_key.0, &signature.0))
+            .unzip();
+        let mut rng = rand::thread_rng();
+        let rands: Vec<_> = keys_and_signatures
+            .iter()
+            .map(|_| {
+                // A zero scalar would leave its signature unchecked.
+                let mut rand = 0;
+                while rand == 0 {
+                    rand = rng.next_u64();
+                }
+                let mut scalar = blst_scalar::default();
+                scalar.b[..8].copy_from_slice(&rand.to_le_bytes());
+                scalar
+            })
+            .collect();
+        // The public keys are validated on deserialization.
+        check_blst_result(
+            blst::min_pk::Signature::verify_multiple_aggregate_signatures(
+                &messages,
+                DST_BLS_SIG,
+                &public_keys,
+                false,
+                &signatures,
+                true,
+                &rands,
+                64,
+            ),
+        )
+    }
+}
+
+impl Length for Bls12381Signature {
+    fn length(&self) -> usize {
+        BLS12381_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Bls12381Signature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl std::hash::Hash for Bls12381Signature {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write(&self.to_bytes());
+    }
+}
+
+impl TryFrom<&[u8]> for Bls12381Signature {
+    type Error = CryptoMaterialError;
+
+    /// Deserialize a Bls12381Signature. The point is only checked to be on the curve, the
+    /// subgroup check is deferred to the verification.
+    fn try_from(bytes: &[u8]) -> std::result::Result<Bls12381Signature, CryptoMaterialError> {
+        if bytes.len() != BLS12381_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        blst::min_pk::Signature::from_bytes(bytes)
+            .map(Bls12381Signature)
+            .map_err(|_| CryptoMaterialError::DeserializationError)
+    }
+}
+
+impl PartialEq for Bls12381Signature {
+    fn eq(&self, other: &Bls12381Signature) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Bls12381Signature {}
+
+impl fmt::Display for Bls12381Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(&self.to_bytes()[..]))
+    }
+}
+
+impl fmt::Debug for Bls12381Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bls12381Signature({})", self)
+    }
+}
+
+///////////////////////////////
+// ProofOfPossession Traits //
+///////////////////////////////
+
+impl TryFrom<&[u8]> for Bls12381ProofOfPossession {
+    type Error = CryptoMaterialError;
+
+    fn try_from(
+        bytes: &[u8],
+    ) -> std::result::Result<Bls12381ProofOfPossession, CryptoMaterialError> {
+        Bls12381Signature::try_from(bytes).map(|signature| Bls12381ProofOfPossession(signature.0))
+    }
+}
+
+impl Length for Bls12381ProofOfPossession {
+    fn length(&self) -> usize {
+        BLS12381_SIGNATURE_LENGTH
+    }
+}
+
+impl ValidCryptoMaterial for Bls12381ProofOfPossession {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+}
+
+impl PartialEq for Bls12381ProofOfPossession {
+    fn eq(&self, other: &Bls12381ProofOfPossession) -> bool {
+        self.to_bytes()[..] == other.to_bytes()[..]
+    }
+}
+
+impl Eq for Bls12381ProofOfPossession {}
+
+impl fmt::Debug for Bls12381ProofOfPossession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Bls12381ProofOfPossession({})",
+            hex::encode(&self.to_bytes()[..])
+        )
+    }
+}
+
+#[cfg(any(test, feature = "fuzzing"))]
+use crate::test_utils::{self, KeyPair};
+
+/// Produces a uniformly random BLS12-381 keypair from a seed
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn keypair_strategy() -> impl Strategy<Value = KeyPair<Bls12381PrivateKey, Bls12381PublicKey>> {
+    test_utils::uniform_keypair_strategy::<Bls12381PrivateKey, Bls12381PublicKey>()
+}
+
+#[cfg(any(test, feature = "fuzzing"))]
+use proptest::prelude::*;
+
+#[cfg(any(test, feature = "fuzzing"))]
+impl proptest::arbitrary::Arbitrary for Bls12381PublicKey {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        crate::test_utils::uniform_keypair_strategy::<Bls12381PrivateKey, Bls12381PublicKey>()
+            .prop_map(|v| v.public_key)
+            .boxed()
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bls12381::{
+        Bls12381PrivateKey, Bls12381ProofOfPossession, Bls12381PublicKey, Bls12381Signature,
+        BLS12381_PUBLIC_KEY_LENGTH, BLS12381_SIGNATURE_LENGTH,
+    },
+    test_utils::{TestAptosCrypto, TEST_SEED},
+    traits::*,
+    CryptoMaterialError,
+};
+use core::convert::TryFrom;
+use once_cell::sync::Lazy;
+use rand::{rngs::StdRng, SeedableRng};
+
+static MESSAGE: Lazy<TestAptosCrypto> = Lazy::new(|| TestAptosCrypto("Test Message".to_string()));
+fn message() -> &'static TestAptosCrypto {
+    &MESSAGE
+}
+
+// Helper function to generate N bls12381 private keys.
+fn generate_keys(n: usize) -> Vec<Bls12381PrivateKey> {
+    let mut rng = StdRng::from_seed(TEST_SEED);
+    (0..n)
+        .map(|_| Bls12381PrivateKey::generate(&mut rng))
+        .collect()
+}
+
+#[test]
+fn test_bls12381_sign_and_verify() {
+    let private_key = &generate_keys(1)[0];
+    let public_key = private_key.public_key();
+    let signature = private_key.sign(message());
+    assert!(signature.verify(message(), &public_key).is_ok());
+
+    let other_message = TestAptosCrypto("Other Message".to_string());
+    assert!(signature.verify(&other_message, &public_key).is_err());
+    let other_public_key = generate_keys(2)[1].public_key();
+    assert!(signature.verify(message(), &other_public_key).is_err());
+}
+
+#[test]
+fn test_bls12381_serialization() {
+    let private_key = &generate_keys(1)[0];
+    let public_key = private_key.public_key();
+    let signature = private_key.sign(message());
+
+    let serialized = Bls12381PrivateKey::try_from(&private_key.to_bytes()[..]).unwrap();
+    assert_eq!(&serialized, private_key);
+    let serialized = public_key.to_bytes();
+    assert_eq!(serialized.len(), BLS12381_PUBLIC_KEY_LENGTH);
+    assert_eq!(
+        Bls12381PublicKey::try_from(&serialized[..]).unwrap(),
+        public_key
+    );
+    let serialized = signature.to_bytes();
+    assert_eq!(serialized.len(), BLS12381_SIGNATURE_LENGTH);
+    assert_eq!(
+        Bls12381Signature::try_from(&serialized[..]).unwrap(),
+        signature
+    );
+    let bcs_serialized = bcs::to_bytes(&signature).unwrap();
+    assert_eq!(
+        bcs::from_bytes::<Bls12381Signature>(&bcs_serialized).unwrap(),
+        signature
+    );
+
+    assert_eq!(
+        Bls12381PublicKey::try_from(&serialized[..BLS12381_PUBLIC_KEY_LENGTH - 1]),
+        Err(CryptoMaterialError::WrongLengthError)
+    );
+    // The identity isn't a valid public key.
+    let mut identity = [0u8; BLS12381_PUBLIC_KEY_LENGTH];
+    identity[0] = 0xc0;
+    assert_eq!(
+        Bls12381PublicKey::try_from(&identity[..]),
+        Err(CryptoMaterialError::ValidationError)
+    );
+}
+
+#[test]
+fn test_bls12381_aggregate_signature() {
+    let private_keys = generate_keys(10);
+    let public_keys: Vec<_> = private_keys.iter().map(Bls12381PublicKey::from).collect();
+    let signatures: Vec<_> = private_keys.iter().map(|key| key.sign(message())).collect();
+
+    let aggregate = Bls12381Signature::aggregate(signatures.clone()).unwrap();
+    let signers: Vec<_> = public_keys.iter().collect();
+    assert!(aggregate.verify_aggregate(message(), &signers).is_ok());
+    // The aggregate has the size of a single signature.
+    assert_eq!(aggregate.to_bytes().len(), BLS12381_SIGNATURE_LENGTH);
+    // It's also the signature of the aggregate public key.
+    let aggregate_public_key = Bls12381PublicKey::aggregate(signers.clone()).unwrap();
+    assert!(aggregate.verify(message(), &aggregate_public_key).is_ok());
+
+    // A missing signer fails the verification.
+    let partial_aggregate = Bls12381Signature::aggregate(signatures[1..].to_vec()).unwrap();
+    assert!(partial_aggregate
+        .verify_aggregate(message(), &signers)
+        .is_err());
+    assert!(partial_aggregate
+        .verify_aggregate(message(), &signers[1..])
+        .is_ok());
+
+    let keys_and_signatures = public_keys.into_iter().zip(signatures).collect();
+    assert!(Bls12381Signature::batch_verify(message(), keys_and_signatures).is_ok());
+}
+
+#[test]
+fn test_bls12381_batch_verify() {
+    let private_keys = generate_keys(3);
+    let public_keys: Vec<_> = private_keys.iter().map(Bls12381PublicKey::from).collect();
+    let signatures: Vec<_> = private_keys.iter().map(|key| key.sign(message())).collect();
+    let keys_and_signatures: Vec<_> = public_keys
+        .iter()
+        .cloned()
+        .zip(signatures.iter().cloned())
+        .collect();
+    assert!(Bls12381Signature::batch_verify(message(), keys_and_signatures.clone()).is_ok());
+
+    // A single invalid signature fails the batch.
+    let mut invalid = keys_and_signatures.clone();
+    invalid[1].1 = private_keys[1].sign(&TestAptosCrypto("Other Message".to_string()));
+    assert!(Bls12381Signature::batch_verify(message(), invalid).is_err());
+
+    // Swapping the signatures of two signers keeps their aggregate valid, but each of them is
+    // invalid, so the batch is rejected.
+    let mut swapped = keys_and_signatures;
+    swapped[0].1 = signatures[1].clone();
+    swapped[1].1 = signatures[0].clone();
+    let swapped_aggregate =
+        Bls12381Signature::aggregate(swapped.iter().map(|(_, s)| s.clone()).collect()).unwrap();
+    let signers: Vec<_> = public_keys.iter().collect();
+    assert!(swapped_aggregate
+        .verify_aggregate(message(), &signers)
+        .is_ok());
+    assert!(Bls12381Signature::batch_verify(message(), swapped).is_err());
+}
+
+#[test]
+fn test_bls12381_proof_of_possession() {
+    let private_keys = generate_keys(2);
+    let public_key = private_keys[0].public_key();
+    let proof = Bls12381ProofOfPossession::create(&private_keys[0]);
+    assert!(proof.verify(&public_key).is_ok());
+    assert!(proof.verify(&private_keys[1].public_key()).is_err());
+    assert_eq!(
+        Bls12381ProofOfPossession::try_from(&proof.to_bytes()[..]).unwrap(),
+        proof
+    );
+
+    // A proof of possession isn't the signature of the public key.
+    let signature = Bls12381Signature::try_from(&proof.to_bytes()[..]).unwrap();
+    assert!(signature
+        .verify_arbitrary_msg(&public_key.to_bytes(), &public_key)
+        .is_err());
+}
@@ -222,6 +222,9 @@ mod tests {
             let account_data = AccountData {
                 authentication_key: auth_vec.into(),
                 sequence_number: account.sequence_number.into(),
+                creation_version: None,
+                authentication_key_rotations: 0.into(),
+                last_transaction_version: None,
             };
             Ok(response(&account_data))
         } else {
@@ -244,6 +247,7 @@ mod tests {
                     gas_used: 0.into(),
                     success: true,
                     vm_status: "Executed".to_string(),
+                    move_abort: None,
                     accumulator_root_hash: HashValue::zero().into(),
                     changes: vec![],
                 };
@@ -301,6 +305,7 @@ mod tests {
         let pending_txn = PendingTransaction {
             hash: HashValue::zero().into(),
             request: (&txn, dummy_payload()).into(),
+            waiting_for_sequence_number: None,
         };
 
         *last_txn.lock().unwrap() = Some(Transaction::UserTransaction(txn));
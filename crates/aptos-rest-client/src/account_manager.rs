@@ -0,0 +1,171 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Submission of the transactions of a local account, tracking its sequence number locally. The
+//! submissions rejected because the sequence number is behind the chain, e.g. when another client
+//! sends transactions from the same account, re-sync the sequence number from the chain and are
+//! submitted again. The submissions rejected by a full mempool, or because the account reached
+//! its limit of transactions in mempool, are retried by the retry policy, with a gas unit price
+//! bumped at every retry if the bump is configured. The submissions failed by connection errors
+//! or transient server errors are retried by the same policy, the client never retrying them.
+
+use crate::{Client, RestError, RetryPolicy};
+use anyhow::Result;
+use aptos_api_types::{AptosErrorCode, PendingTransaction};
+use aptos_sdk::{
+    move_types::vm_status::StatusCode,
+    transaction_builder::TransactionFactory,
+    types::{transaction::TransactionPayload, LocalAccount},
+};
+
+/// How the gas unit price of a transaction is raised when its submission is retried
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GasBump {
+    /// The percentage the gas unit price is raised by at every retry, by at least 1
+    pub percent: u64,
+    pub max_gas_unit_price: u64,
+}
+
+pub struct AccountManager {
+    client: Client,
+    account: LocalAccount,
+    transaction_factory: TransactionFactory,
+    retry_policy: RetryPolicy,
+    gas_bump: Option<GasBump>,
+}
+
+impl AccountManager {
+    /// The sequence number of the account is expected to be the next one of the chain, e.g. after
+    /// `sync_sequence_number`.
+    pub fn new(
+        client: Client,
+        account: LocalAccount,
+        transaction_factory: TransactionFactory,
+    ) -> Self {
+        Self {
+            client,
+            account,
+            transaction_factory,
+            retry_policy: RetryPolicy::default(),
+            gas_bump: None,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_gas_bump(mut self, gas_bump: GasBump) -> Self {
+        self.gas_bump = Some(gas_bump);
+        self
+    }
+
+    pub fn account(&self) -> &LocalAccount {
+        &self.account
+    }
+
+    pub fn into_account(self) -> LocalAccount {
+        self.account
+    }
+
+    /// Sets the sequence number of the account to the one of the chain, and returns it
+    pub async fn sync_sequence_number(&mut self) -> Result<u64> {
+        let sequence_number = self
+            .client
+            .get_account(self.account.address())
+            .await?
+            .into_inner()
+            .sequence_number;
+        *self.account.sequence_number_mut() = sequence_number;
+        Ok(sequence_number)
+    }
+
+    /// Signs the payload with the next sequence number of the account and submits it. The
+    /// sequence number is only consumed when the transaction is accepted.
+    pub async fn submit(&mut self, payload: TransactionPayload) -> Result<PendingTransaction> {
+        let mut delays = self.retry_policy.delays();
+        let mut gas_unit_price = None;
+        let mut synced = false;
+        loop {
+            let mut builder = self.transaction_factory.payload(payload.clone());
+            if let Some(gas_unit_price) = gas_unit_price {
+                builder = builder.gas_unit_price(gas_unit_price);
+            }
+            let raw_txn = builder
+                .sender(self.account.address())
+                .sequence_number(self.account.sequence_number())
+                .build();
+            let current_gas_unit_price = raw_txn.gas_unit_price();
+            let txn = self.account.sign_transaction(raw_txn);
+
+            let err = match self.client.submit(&txn).await {
+                Ok(pending_txn) => {
+                    *self.account.sequence_number_mut() += 1;
+                    return Ok(pending_txn.into_inner());
+                }
+                Err(err) => err,
+            };
+            let failure = Failure::of(&err);
+            if failure == Failure::SequenceNumberTooOld && !synced {
+                // Re-syncing again wouldn't help if the chain is behind the returned sequence
+                // number, e.g. when the requests are load balanced between nodes.
+                synced = true;
+                self.sync_sequence_number().await?;
+                continue;
+            }
+            if !matches!(failure, Failure::MempoolRejected | Failure::Transient) {
+                return Err(err);
+            }
+            match delays.next() {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(err),
+            }
+            // the gas unit price only helps the transaction into a full mempool
+            if failure != Failure::MempoolRejected {
+                continue;
+            }
+            if let Some(gas_bump) = &self.gas_bump {
+                let bump = current_gas_unit_price.saturating_mul(gas_bump.percent) / 100;
+                let bumped_gas_unit_price = current_gas_unit_price
+                    .saturating_add(std::cmp::max(bump, 1))
+                    .min(gas_bump.max_gas_unit_price);
+                gas_unit_price = Some(bumped_gas_unit_price.max(current_gas_unit_price));
+            }
+        }
+    }
+}
+
+/// The failures of a submission the manager handles
+#[derive(Debug, Eq, PartialEq)]
+enum Failure {
+    SequenceNumberTooOld,
+    /// Mempool is full or the account reached its limit of transactions in mempool
+    MempoolRejected,
+    /// A connection error or a transient server error
+    Transient,
+    Other,
+}
+
+impl Failure {
+    fn of(err: &anyhow::Error) -> Self {
+        if let Some(err) = err.downcast_ref::<RestError>() {
+            return match err.error_code {
+                Some(AptosErrorCode::MempoolFull)
+                | Some(AptosErrorCode::SenderLimitExceeded)
+                | Some(AptosErrorCode::SenderBytesLimitExceeded) => Self::MempoolRejected,
+                Some(AptosErrorCode::InvalidTransaction)
+                    if err.vm_error_code == Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD as u64) =>
+                {
+                    Self::SequenceNumberTooOld
+                }
+                _ if err.code == 429 || (500..600).contains(&err.code) => Self::Transient,
+                _ => Self::Other,
+            };
+        }
+        match err.downcast_ref::<reqwest::Error>() {
+            Some(err) if err.is_connect() => Self::Transient,
+            _ => Self::Other,
+        }
+    }
+}
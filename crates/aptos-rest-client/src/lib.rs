@@ -2,23 +2,46 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{anyhow, Result};
+use aptos_api_types::{
+    Block, Event, GasEstimation, TransactionSubmissionResult, UserTransaction, ViewRequest,
+};
 pub use aptos_api_types::{MoveModuleBytecode, PendingTransaction, Transaction};
 use aptos_crypto::HashValue;
-use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
-use move_core_types::identifier::Identifier;
-use reqwest::{header::CONTENT_TYPE, Client as ReqwestClient, StatusCode};
+use aptos_sdk::transaction_builder::ScriptFunctionAbi;
+use aptos_types::{
+    account_address::AccountAddress, event::EventKey, transaction::SignedTransaction,
+};
+use move_core_types::{
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, StructTag},
+    move_resource::MoveResource,
+};
+use reqwest::{
+    header::{ACCEPT, CONTENT_TYPE},
+    Client as ReqwestClient, RequestBuilder, StatusCode,
+};
 use serde::{de::DeserializeOwned, Deserialize};
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use url::Url;
 
 pub use aptos_api_types;
 use aptos_types::account_config::aptos_root_address;
 
+pub mod account_manager;
+pub use account_manager::{AccountManager, GasBump};
 pub mod error;
 pub mod faucet;
 pub use faucet::FaucetClient;
 pub mod response;
 pub use response::Response;
+pub mod retry;
+pub use retry::RetryPolicy;
 mod state;
 use state::State;
 pub mod types;
@@ -28,12 +51,17 @@ pub use types::{Account, Resource, RestError};
 pub mod aptos;
 
 const BCS_CONTENT_TYPE: &str = "application/x.diem.signed_transaction+bcs";
+const BCS_ACCEPT_TYPE: &str = "application/x-bcs";
 const USER_AGENT: &str = concat!("aptos-client-sdk-rust / ", env!("CARGO_PKG_VERSION"));
 
 #[derive(Clone, Debug)]
 pub struct Client {
     inner: ReqwestClient,
     base_url: Url,
+    retry_policy: RetryPolicy,
+    // The highest ledger version returned by the server, only tracked when the ledger
+    // version check is enabled. It is shared by the clones of the client.
+    ledger_version: Option<Arc<AtomicU64>>,
 }
 
 impl Client {
@@ -45,7 +73,25 @@ impl Client {
             .build()
             .unwrap();
 
-        Self { inner, base_url }
+        Self {
+            inner,
+            base_url,
+            retry_policy: RetryPolicy::default(),
+            ledger_version: None,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Rejects responses with a ledger version older than the one of a previous response,
+    /// e.g. when the requests are load balanced between nodes that are not equally synced.
+    /// Such responses are retried like transient server errors.
+    pub fn with_ledger_version_check(mut self) -> Self {
+        self.ledger_version = Some(Arc::new(AtomicU64::new(0)));
+        self
     }
 
     pub async fn get_aptos_version(&self) -> Result<Response<AptosVersion>> {
@@ -77,7 +123,7 @@ impl Client {
             ledger_timestamp: u64,
         }
 
-        let response = self.inner.get(self.base_url.clone()).send().await?;
+        let response = self.send(self.inner.get(self.base_url.clone())).await?;
 
         let response = self.json::<Response>(response).await?.map(|r| State {
             chain_id: r.chain_id,
@@ -93,15 +139,48 @@ impl Client {
         let txn_payload = bcs::to_bytes(txn)?;
         let url = self.base_url.join("transactions")?;
 
-        let response = self
+        let request = self
             .inner
             .post(url)
             .header(CONTENT_TYPE, BCS_CONTENT_TYPE)
-            .body(txn_payload)
-            .send()
-            .await?;
+            .body(txn_payload);
 
-        self.json(response).await
+        // the submission may have been accepted by a failed attempt, it isn't repeated
+        self.json(self.send_once(request).await?).await
+    }
+
+    /// Submits the transactions at once, the results are in the same order as the transactions.
+    pub async fn submit_batch(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<Response<Vec<TransactionSubmissionResult>>> {
+        let txn_payload = bcs::to_bytes(txns)?;
+        let url = self.base_url.join("transactions/batch")?;
+
+        let request = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, BCS_CONTENT_TYPE)
+            .body(txn_payload);
+
+        // the submission may have been accepted by a failed attempt, it isn't repeated
+        self.json(self.send_once(request).await?).await
+    }
+
+    pub async fn simulate(
+        &self,
+        txn: &SignedTransaction,
+    ) -> Result<Response<Vec<UserTransaction>>> {
+        let txn_payload = bcs::to_bytes(txn)?;
+        let url = self.base_url.join("transactions/simulate")?;
+
+        let request = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, BCS_CONTENT_TYPE)
+            .body(txn_payload);
+
+        self.json(self.send(request).await?).await
     }
 
     pub async fn submit_and_wait(&self, txn: &SignedTransaction) -> Result<Response<Transaction>> {
@@ -187,7 +266,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
 
         self.json(response).await
     }
@@ -216,7 +295,7 @@ impl Client {
             .base_url
             .join(&format!("transactions/{}", version_or_hash))?;
 
-        Ok(self.inner.get(url).send().await?)
+        self.send(self.inner.get(url)).await
     }
 
     pub async fn get_account_transactions(
@@ -238,7 +317,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
 
         self.json(response).await
     }
@@ -249,7 +328,7 @@ impl Client {
     ) -> Result<Response<Vec<u8>>> {
         let url = self.base_url.join(&format!("accounts/{}/blob", address))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
         let (response, state) = self.check_response(response).await?;
         let blob = response.json().await?;
         Ok(Response::new(blob, state))
@@ -263,7 +342,7 @@ impl Client {
             .base_url
             .join(&format!("accounts/{}/resources", address))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
 
         self.json(response).await
     }
@@ -309,6 +388,27 @@ impl Client {
         })
     }
 
+    /// Fetches the resource of the account as the Rust type mapped to it, e.g. with the
+    /// `MoveResource` derive of the SDK. The resource is fetched in BCS.
+    pub async fn get_move_resource<T: MoveResource>(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Response<T>> {
+        let resource_type = T::struct_tag();
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/resource/{}", address, resource_type))?;
+
+        let response = self
+            .send(self.inner.get(url).header(ACCEPT, BCS_ACCEPT_TYPE))
+            .await?;
+        let (response, state) = self.check_response(response).await?;
+        let bytes = response.bytes().await?;
+        let resource = bcs::from_bytes(&bytes)
+            .map_err(|e| anyhow!("deserialize {} failed: {}", resource_type, e))?;
+        Ok(Response::new(resource, state))
+    }
+
     pub async fn get_account_resource(
         &self,
         address: AccountAddress,
@@ -318,7 +418,7 @@ impl Client {
             .base_url
             .join(&format!("accounts/{}/resource/{}", address, resource_type))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
 
         self.json(response).await
     }
@@ -331,24 +431,187 @@ impl Client {
             .base_url
             .join(&format!("accounts/{}/modules", address))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
+
+        self.json(response).await
+    }
+
+    pub async fn get_account_module(
+        &self,
+        address: AccountAddress,
+        module_name: &IdentStr,
+    ) -> Result<Response<MoveModuleBytecode>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/module/{}", address, module_name))?;
+
+        let response = self.send(self.inner.get(url)).await?;
 
         self.json(response).await
     }
 
+    /// Fetches the module of the script function, to check its calls before submitting them.
+    pub async fn get_script_function_abi(
+        &self,
+        module_id: &ModuleId,
+        function: &IdentStr,
+    ) -> Result<Response<ScriptFunctionAbi>> {
+        let resp = self
+            .get_account_module(*module_id.address(), module_id.name())
+            .await?;
+        resp.and_then(|module| {
+            ScriptFunctionAbi::from_module_bytes(module.bytecode.inner(), function)
+                .map_err(|e| anyhow!("{}", e))
+        })
+    }
+
     pub async fn get_account(&self, address: AccountAddress) -> Result<Response<Account>> {
         let url = self.base_url.join(&format!("accounts/{}", address))?;
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
         self.json(response).await
     }
 
+    pub async fn get_block_by_height(
+        &self,
+        height: u64,
+        with_events: bool,
+    ) -> Result<Response<Block>> {
+        let url = self.base_url.join(&format!("blocks/{}", height))?;
+        let request = self.inner.get(url).query(&[("with_events", with_events)]);
+        self.json(self.send(request).await?).await
+    }
+
+    pub async fn get_events_by_key(
+        &self,
+        key: EventKey,
+        start: Option<u64>,
+        limit: Option<u16>,
+    ) -> Result<Response<Vec<Event>>> {
+        let url = self.base_url.join(&format!("events/{}", key))?;
+        self.get_events(url, start, limit).await
+    }
+
+    pub async fn get_account_events(
+        &self,
+        address: AccountAddress,
+        struct_tag: &StructTag,
+        field_name: &str,
+        start: Option<u64>,
+        limit: Option<u16>,
+    ) -> Result<Response<Vec<Event>>> {
+        let url = self.base_url.join(&format!(
+            "accounts/{}/events/{}/{}",
+            address, struct_tag, field_name
+        ))?;
+        self.get_events(url, start, limit).await
+    }
+
+    async fn get_events(
+        &self,
+        url: Url,
+        start: Option<u64>,
+        limit: Option<u16>,
+    ) -> Result<Response<Vec<Event>>> {
+        let mut request = self.inner.get(url);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        self.json(self.send(request).await?).await
+    }
+
+    pub async fn estimate_gas_price(&self) -> Result<Response<GasEstimation>> {
+        let url = self.base_url.join("estimate_gas_price")?;
+        self.json(self.send(self.inner.get(url)).await?).await
+    }
+
+    /// Executes a view function, the return values are rendered as JSON values.
+    pub async fn view(&self, request: &ViewRequest) -> Result<Response<Vec<serde_json::Value>>> {
+        let url = self.base_url.join("view")?;
+        self.json(self.send(self.inner.post(url).json(request)).await?)
+            .await
+    }
+
+    /// Sends the request once, for the requests that aren't idempotent or whose error response
+    /// is the answer, e.g. the health check.
+    async fn send_once(&self, request: RequestBuilder) -> Result<reqwest::Response> {
+        Ok(request.send().await?)
+    }
+
+    /// Sends the idempotent request, retrying it by the retry policy when it fails with a
+    /// connection error, a transient server error or, if the check is enabled, a stale ledger
+    /// version.
+    async fn send(&self, request: RequestBuilder) -> Result<reqwest::Response> {
+        let mut delays = self.retry_policy.delays();
+        loop {
+            // requests with a streaming body can't be cloned, they are never retried
+            let attempt = match request.try_clone() {
+                Some(attempt) => attempt,
+                None => return Ok(request.send().await?),
+            };
+            let err = match attempt.send().await {
+                Ok(response) if RetryPolicy::is_retriable(response.status()) => {
+                    match delays.next() {
+                        Some(delay) => {
+                            tokio::time::sleep(delay).await;
+                            continue;
+                        }
+                        // the error response is returned as it is when out of retries
+                        None => return Ok(response),
+                    }
+                }
+                Ok(response) => match self.check_ledger_version(&response) {
+                    Ok(()) => return Ok(response),
+                    Err(err) => err,
+                },
+                Err(err) if err.is_connect() || err.is_timeout() => err.into(),
+                Err(err) => return Err(err.into()),
+            };
+            match delays.next() {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(err),
+            }
+        }
+    }
+
+    fn check_ledger_version(&self, response: &reqwest::Response) -> Result<()> {
+        let ledger_version = match &self.ledger_version {
+            Some(ledger_version) => ledger_version,
+            None => return Ok(()),
+        };
+        let version = match response
+            .headers()
+            .get(aptos_api_types::X_APTOS_LEDGER_VERSION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|s| s.parse::<u64>().ok())
+        {
+            Some(version) => version,
+            // e.g. health check responses don't have the ledger headers
+            None => return Ok(()),
+        };
+        let highest = ledger_version.fetch_max(version, Ordering::SeqCst);
+        if version < highest {
+            return Err(anyhow!(
+                "stale response: ledger version {} is older than the seen version {}",
+                version,
+                highest
+            ));
+        }
+        Ok(())
+    }
+
     async fn check_response(
         &self,
         response: reqwest::Response,
     ) -> Result<(reqwest::Response, State)> {
         if !response.status().is_success() {
             let error_response = response.json::<RestError>().await?;
-            return Err(anyhow::anyhow!("Request failed: {:?}", error_response));
+            // kept as it is, so that the callers can branch on it by downcasting the error
+            return Err(error_response.into());
         }
         let state = State::from_headers(response.headers())?;
 
@@ -366,11 +629,9 @@ impl Client {
 
     pub async fn health_check(&self, seconds: u64) -> Result<()> {
         let url = self.base_url.join("-/healthy")?;
+        // an unhealthy node answers 503, which isn't retried
         let response = self
-            .inner
-            .get(url)
-            .query(&[("duration_secs", seconds)])
-            .send()
+            .send_once(self.inner.get(url).query(&[("duration_secs", seconds)]))
             .await?;
 
         if !response.status().is_success() {
@@ -0,0 +1,47 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// How the client retries the idempotent requests failed by connection errors or transient server
+/// errors (429 and 5xx), the delay between two attempts grows by half after every retry up to
+/// `max_delay`. The submissions and the health checks are never retried.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: usize,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy sending every request only once.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn delays(&self) -> impl Iterator<Item = Duration> {
+        aptos_retrier::exp_retry_strategy(
+            self.initial_delay.as_millis() as u64,
+            self.max_delay.as_millis() as u64,
+            self.max_retries,
+        )
+    }
+
+    pub(crate) fn is_retriable(status: StatusCode) -> bool {
+        status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
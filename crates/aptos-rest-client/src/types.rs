@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_api_types::{Address, U64};
+use aptos_api_types::{Address, AptosErrorCode, U64};
 use aptos_types::transaction::authenticator::AuthenticationKey;
 use move_core_types::{language_storage::StructTag, parser::parse_struct_tag};
 use serde::{Deserialize, Deserializer, Serialize};
@@ -11,9 +11,26 @@ use std::str::FromStr;
 pub struct RestError {
     pub code: u32,
     pub message: String,
+    /// Absent when the server predates structured error codes.
+    #[serde(default)]
+    pub error_code: Option<AptosErrorCode>,
     pub aptos_ledger_version: Option<U64>,
+    /// Given when the requested version is pruned.
+    #[serde(default)]
+    pub oldest_ledger_version: Option<U64>,
+    /// The status code of the Move VM, given when the transaction failed its validation.
+    #[serde(default)]
+    pub vm_error_code: Option<u64>,
 }
 
+impl std::fmt::Display for RestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Request failed: {:?}", self)
+    }
+}
+
+impl std::error::Error for RestError {}
+
 #[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct Resource {
     #[serde(rename = "type", deserialize_with = "deserialize_resource_type")]
@@ -0,0 +1,217 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::{
+    X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+};
+use aptos_rest_client::{AccountManager, Client, GasBump, RetryPolicy};
+use aptos_sdk::{
+    move_types::{account_address::AccountAddress, vm_status::StatusCode},
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{
+        chain_id::ChainId,
+        transaction::{SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde_json::{json, Value};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use warp::{http::Response, hyper::body::Bytes, Filter};
+
+const CHAIN_SEQUENCE_NUMBER: u64 = 7;
+
+/// A mocked API answering the submissions with the given responses in turn, and accepting them
+/// once out of responses. The submitted transactions are recorded.
+struct MockApi {
+    submitted: Arc<Mutex<Vec<SignedTransaction>>>,
+    client: Client,
+}
+
+impl MockApi {
+    fn start(responses: Vec<(u16, Value)>) -> Self {
+        let responses = Arc::new(Mutex::new(VecDeque::from(responses)));
+        let submitted = Arc::new(Mutex::new(vec![]));
+
+        let submissions = submitted.clone();
+        let submit = warp::path!("transactions")
+            .and(warp::post())
+            .and(warp::body::bytes())
+            .map(move |body: Bytes| {
+                let txn: SignedTransaction = bcs::from_bytes(&body).unwrap();
+                submissions.lock().unwrap().push(txn.clone());
+                let (status, body) = responses
+                    .lock()
+                    .unwrap()
+                    .pop_front()
+                    .unwrap_or_else(|| (202, pending_transaction(&txn)));
+                respond(status, body)
+            });
+        let account = warp::path!("accounts" / String).and(warp::get()).map(|_| {
+            respond(
+                200,
+                json!({
+                    "authentication_key": format!("0x{}", "00".repeat(32)),
+                    "sequence_number": CHAIN_SEQUENCE_NUMBER.to_string(),
+                }),
+            )
+        });
+        let (address, server) = warp::serve(submit.or(account)).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let client = Client::new(format!("http://{}", address).parse().unwrap());
+        Self { submitted, client }
+    }
+
+    fn submitted(&self) -> Vec<SignedTransaction> {
+        self.submitted.lock().unwrap().clone()
+    }
+}
+
+fn respond(status: u16, body: Value) -> Response<String> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .header(X_APTOS_CHAIN_ID, "4")
+        .header(X_APTOS_EPOCH, "1")
+        .header(X_APTOS_LEDGER_VERSION, "10")
+        .header(X_APTOS_LEDGER_TIMESTAMP, "1000")
+        .body(body.to_string())
+        .unwrap()
+}
+
+fn pending_transaction(txn: &SignedTransaction) -> Value {
+    json!({
+        "hash": txn.clone().committed_hash().to_hex_literal(),
+        "sender": txn.sender().to_hex_literal(),
+        "sequence_number": txn.sequence_number().to_string(),
+        "max_gas_amount": txn.max_gas_amount().to_string(),
+        "gas_unit_price": txn.gas_unit_price().to_string(),
+        "gas_currency_code": txn.gas_currency_code(),
+        "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+        "payload": {
+            "type": "script_function_payload",
+            "function": "0x1::TestCoin::transfer",
+            "type_arguments": [],
+            "arguments": [],
+        },
+    })
+}
+
+fn error(status: u16, error_code: &str, vm_error_code: Option<StatusCode>) -> (u16, Value) {
+    let mut body = json!({
+        "code": status,
+        "message": "rejected",
+        "error_code": error_code,
+    });
+    if let Some(vm_error_code) = vm_error_code {
+        body["vm_error_code"] = json!(vm_error_code as u64);
+    }
+    (status, body)
+}
+
+fn retry_policy(max_retries: usize) -> RetryPolicy {
+    RetryPolicy {
+        max_retries,
+        initial_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(1),
+    }
+}
+
+fn account_manager(api: &MockApi) -> AccountManager {
+    let account = LocalAccount::generate(&mut StdRng::seed_from_u64(0));
+    let transaction_factory = TransactionFactory::new(ChainId::test()).with_gas_unit_price(100);
+    AccountManager::new(api.client.clone(), account, transaction_factory)
+}
+
+fn payload() -> TransactionPayload {
+    aptos_stdlib::encode_transfer_script_function(AccountAddress::random(), 1)
+}
+
+#[tokio::test]
+async fn test_sequence_number_too_old_re_syncs_once() {
+    let too_old = error(
+        400,
+        "invalid_transaction",
+        Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD),
+    );
+    let api = MockApi::start(vec![too_old.clone()]);
+    let mut manager = account_manager(&api).with_retry_policy(retry_policy(0));
+
+    manager.submit(payload()).await.unwrap();
+    let sequence_numbers: Vec<_> = api
+        .submitted()
+        .iter()
+        .map(|txn| txn.sequence_number())
+        .collect();
+    assert_eq!(sequence_numbers, vec![0, CHAIN_SEQUENCE_NUMBER]);
+    assert_eq!(
+        manager.account().sequence_number(),
+        CHAIN_SEQUENCE_NUMBER + 1
+    );
+
+    // the chain being behind, re-syncing again wouldn't help
+    let api = MockApi::start(vec![too_old.clone(), too_old]);
+    let mut manager = account_manager(&api).with_retry_policy(retry_policy(0));
+    manager.submit(payload()).await.unwrap_err();
+    assert_eq!(api.submitted().len(), 2);
+    assert_eq!(manager.account().sequence_number(), CHAIN_SEQUENCE_NUMBER);
+}
+
+#[tokio::test]
+async fn test_mempool_full_retried_with_bumped_gas() {
+    let api = MockApi::start(vec![
+        error(400, "mempool_full", None),
+        error(400, "sender_limit_exceeded", None),
+        error(400, "sender_bytes_limit_exceeded", None),
+    ]);
+    let mut manager = account_manager(&api)
+        .with_retry_policy(retry_policy(3))
+        .with_gas_bump(GasBump {
+            percent: 50,
+            max_gas_unit_price: 200,
+        });
+
+    manager.submit(payload()).await.unwrap();
+    let gas_unit_prices: Vec<_> = api
+        .submitted()
+        .iter()
+        .map(|txn| txn.gas_unit_price())
+        .collect();
+    assert_eq!(gas_unit_prices, vec![100, 150, 200, 200]);
+    assert_eq!(manager.account().sequence_number(), 1);
+}
+
+#[tokio::test]
+async fn test_submission_retried_once_per_backoff() {
+    // the retries of the client aren't stacked on the ones of the manager
+    let api = MockApi::start(vec![error(503, "internal_error", None); 10]);
+    let mut manager = account_manager(&api).with_retry_policy(retry_policy(2));
+    manager.submit(payload()).await.unwrap_err();
+    let submitted = api.submitted();
+    assert_eq!(submitted.len(), 3);
+    // the gas unit price is only bumped for mempool
+    assert!(submitted.iter().all(|txn| txn.gas_unit_price() == 100));
+    assert_eq!(manager.account().sequence_number(), 0);
+
+    let api = MockApi::start(vec![error(500, "internal_error", None)]);
+    let mut manager = account_manager(&api).with_retry_policy(retry_policy(2));
+    manager.submit(payload()).await.unwrap();
+    assert_eq!(api.submitted().len(), 2);
+}
+
+#[tokio::test]
+async fn test_other_errors_not_retried() {
+    let api = MockApi::start(vec![error(
+        400,
+        "invalid_transaction",
+        Some(StatusCode::INVALID_SIGNATURE),
+    )]);
+    let mut manager = account_manager(&api).with_retry_policy(retry_policy(3));
+    manager.submit(payload()).await.unwrap_err();
+    assert_eq!(api.submitted().len(), 1);
+}
@@ -0,0 +1,134 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::{
+    X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+};
+use aptos_rest_client::{Client, RetryPolicy};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{chain_id::ChainId, transaction::SignedTransaction, LocalAccount},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use serde_json::{json, Value};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use warp::{http::Response, Filter};
+
+/// A mocked API answering every request with 503, but the account requests once they have
+/// failed `account_failures` times. The requests are counted.
+struct MockApi {
+    requests: Arc<AtomicUsize>,
+    client: Client,
+}
+
+impl MockApi {
+    fn start(account_failures: usize) -> Self {
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let counter = requests.clone();
+        let account = warp::path!("accounts" / String)
+            .and(warp::get())
+            .map(move |_| {
+                if counter.fetch_add(1, Ordering::SeqCst) < account_failures {
+                    return unavailable();
+                }
+                respond(
+                    200,
+                    json!({
+                        "authentication_key": format!("0x{}", "00".repeat(32)),
+                        "sequence_number": "0",
+                    }),
+                )
+            });
+        let counter = requests.clone();
+        let others = warp::any().map(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            unavailable()
+        });
+        let (address, server) = warp::serve(account.or(others)).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let client = Client::new(format!("http://{}", address).parse().unwrap()).with_retry_policy(
+            RetryPolicy {
+                max_retries: 3,
+                initial_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(1),
+            },
+        );
+        Self { requests, client }
+    }
+
+    fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+}
+
+fn respond(status: u16, body: Value) -> Response<String> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .header(X_APTOS_CHAIN_ID, "4")
+        .header(X_APTOS_EPOCH, "1")
+        .header(X_APTOS_LEDGER_VERSION, "10")
+        .header(X_APTOS_LEDGER_TIMESTAMP, "1000")
+        .body(body.to_string())
+        .unwrap()
+}
+
+fn unavailable() -> Response<String> {
+    respond(
+        503,
+        json!({
+            "code": 503,
+            "message": "unavailable",
+            "error_code": "internal_error",
+        }),
+    )
+}
+
+fn transaction() -> SignedTransaction {
+    let mut account = LocalAccount::generate(&mut StdRng::seed_from_u64(0));
+    let payload = aptos_stdlib::encode_transfer_script_function(AccountAddress::random(), 1);
+    account.sign_with_transaction_builder(TransactionFactory::new(ChainId::test()).payload(payload))
+}
+
+#[tokio::test]
+async fn test_idempotent_requests_retried() {
+    let api = MockApi::start(2);
+    api.client
+        .get_account(AccountAddress::random())
+        .await
+        .unwrap();
+    assert_eq!(api.requests(), 3);
+
+    let api = MockApi::start(10);
+    api.client
+        .get_account(AccountAddress::random())
+        .await
+        .unwrap_err();
+    assert_eq!(api.requests(), 4);
+}
+
+#[tokio::test]
+async fn test_submissions_not_retried() {
+    let api = MockApi::start(0);
+    api.client.submit(&transaction()).await.unwrap_err();
+    assert_eq!(api.requests(), 1);
+
+    api.client.submit_batch(&[transaction()]).await.unwrap_err();
+    assert_eq!(api.requests(), 2);
+}
+
+#[tokio::test]
+async fn test_unhealthy_node_not_retried() {
+    let api = MockApi::start(0);
+    api.client.health_check(10).await.unwrap_err();
+    assert_eq!(api.requests(), 1);
+}
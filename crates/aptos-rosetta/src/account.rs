@@ -0,0 +1,67 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block::{block_version_range, resolve_height},
+    check_network,
+    error::ApiError,
+    native_currency,
+    operations::parse_address,
+    types::{AccountBalanceRequest, AccountBalanceResponse, Amount, BlockIdentifier},
+};
+
+use aptos_api::Context;
+use warp::{filters::BoxedFilter, reply, Filter, Rejection, Reply};
+
+// POST /account/balance
+pub fn balance(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("account" / "balance")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_balance)
+        .boxed()
+}
+
+/// Responds the balance of the native coin at the end of the requested block, the latest
+/// block by default.
+async fn handle_balance(
+    request: AccountBalanceRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let address = parse_address(&request.account_identifier.address)?;
+    let ledger_info = context.get_latest_ledger_info().map_err(ApiError::from)?;
+    let ledger_version = ledger_info.version();
+    let height = resolve_height(
+        &context,
+        &request.block_identifier.unwrap_or_default(),
+        ledger_version,
+    )?;
+    let (_, version) = block_version_range(&context, height, ledger_version)?;
+    context
+        .ensure_state_not_pruned(version, &ledger_info)
+        .map_err(ApiError::from)?;
+
+    let balance = context
+        .get_account_state(address, version)
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::AccountNotFound(address.to_hex_literal()))?
+        .get_balance_resources()
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::AccountNotFound(address.to_hex_literal()))?;
+
+    Ok(reply::json(&AccountBalanceResponse {
+        block_identifier: BlockIdentifier {
+            index: height,
+            hash: context
+                .get_accumulator_root_hash(version)
+                .map_err(ApiError::from)?
+                .to_hex_literal(),
+        },
+        balances: vec![Amount {
+            value: balance.coin().to_string(),
+            currency: native_currency(),
+        }],
+    }))
+}
@@ -0,0 +1,289 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    check_network,
+    error::ApiError,
+    operations,
+    types::{Block, BlockIdentifier, BlockRequest, BlockResponse, PartialBlockIdentifier},
+};
+
+use aptos_api::Context;
+use aptos_types::transaction::Version;
+use std::cmp::min;
+use warp::{filters::BoxedFilter, reply, Filter, Rejection, Reply};
+
+// Max number of transactions loaded from the database at once while rendering a block.
+const BLOCK_TRANSACTIONS_BATCH_SIZE: u64 = 1000;
+
+// POST /block
+pub fn block(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("block")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_block)
+        .boxed()
+}
+
+async fn handle_block(request: BlockRequest, context: Context) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let ledger_info = context.get_latest_ledger_info().map_err(ApiError::from)?;
+    let ledger_version = ledger_info.version();
+    let height = resolve_height(&context, &request.block_identifier, ledger_version)?;
+    let (first_version, last_version) = block_version_range(&context, height, ledger_version)?;
+    context
+        .ensure_transaction_not_pruned(first_version, &ledger_info)
+        .map_err(ApiError::from)?;
+
+    let block_identifier = BlockIdentifier {
+        index: height,
+        hash: block_hash(&context, last_version)?,
+    };
+    // the genesis block is its own parent
+    let parent_block_identifier = match height {
+        0 => block_identifier.clone(),
+        _ => BlockIdentifier {
+            index: height - 1,
+            hash: block_hash(&context, first_version - 1)?,
+        },
+    };
+
+    let mut transactions = vec![];
+    let mut version = first_version;
+    while version <= last_version {
+        let limit = min(last_version - version + 1, BLOCK_TRANSACTIONS_BATCH_SIZE);
+        for data in context.get_transactions(version, limit as u16, ledger_version)? {
+            transactions.push(operations::transaction(&data)?);
+        }
+        version += limit;
+    }
+
+    Ok(reply::json(&BlockResponse {
+        block: Block {
+            block_identifier,
+            parent_block_identifier,
+            timestamp: block_timestamp(&context, first_version)?,
+            transactions,
+        },
+    }))
+}
+
+pub fn latest_block_height(context: &Context, ledger_version: Version) -> Result<u64, ApiError> {
+    Ok(context.get_latest_block_height(ledger_version)?)
+}
+
+/// Returns the identifier and the timestamp in milliseconds of the block at the height.
+pub fn block_identifier(
+    context: &Context,
+    height: u64,
+    ledger_version: Version,
+) -> Result<(BlockIdentifier, u64), ApiError> {
+    let (first_version, last_version) = block_version_range(context, height, ledger_version)?;
+    let identifier = BlockIdentifier {
+        index: height,
+        hash: block_hash(context, last_version)?,
+    };
+    Ok((identifier, block_timestamp(context, first_version)?))
+}
+
+/// Returns the height of the block identified by the request, the latest block when the
+/// request doesn't identify one. The hash is checked when it is given with the height,
+/// blocks can't be looked up by hash alone.
+pub fn resolve_height(
+    context: &Context,
+    id: &PartialBlockIdentifier,
+    ledger_version: Version,
+) -> Result<u64, ApiError> {
+    let height = match (id.index, &id.hash) {
+        (Some(index), _) => index,
+        (None, None) => latest_block_height(context, ledger_version)?,
+        (None, Some(_)) => {
+            return Err(ApiError::InvalidRequest(
+                "blocks can only be looked up by index".to_owned(),
+            ))
+        }
+    };
+    if let Some(hash) = &id.hash {
+        let (identifier, _) = block_identifier(context, height, ledger_version)?;
+        if &identifier.hash != hash {
+            return Err(ApiError::BlockNotFound(format!(
+                "index({}) and hash({})",
+                height, hash
+            )));
+        }
+    }
+    Ok(height)
+}
+
+pub fn block_version_range(
+    context: &Context,
+    height: u64,
+    ledger_version: Version,
+) -> Result<(Version, Version), ApiError> {
+    context
+        .get_block_version_range(height, ledger_version)?
+        .ok_or_else(|| ApiError::BlockNotFound(format!("index({})", height)))
+}
+
+// The accumulator root hash of the last transaction of a block identifies the block.
+fn block_hash(context: &Context, last_version: Version) -> Result<String, ApiError> {
+    Ok(context
+        .get_accumulator_root_hash(last_version)?
+        .to_hex_literal())
+}
+
+fn block_timestamp(context: &Context, first_version: Version) -> Result<u64, ApiError> {
+    // block timestamps are in microseconds
+    Ok(context.get_block_timestamp(first_version)? / 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{network_identifier, routes, types::NetworkStatusResponse};
+    use anyhow::Result;
+    use aptos_api::Context;
+    use aptos_config::config::ApiConfig;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        block_info::BlockInfo,
+        chain_id::ChainId,
+        contract_event::ContractEvent,
+        event::EventKey,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+        transaction::Version,
+    };
+    use move_core_types::language_storage::TypeTag;
+    use serde_json::{json, Value};
+    use std::{collections::BTreeMap, sync::Arc};
+    use storage_interface::{DbReader, Order};
+
+    /// A ledger where the blocks after genesis start at `block_versions`
+    struct MockDb {
+        ledger_version: Version,
+        block_versions: Vec<Version>,
+    }
+
+    impl DbReader for MockDb {
+        fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+            let block_info = BlockInfo::new(
+                1,
+                0,
+                HashValue::zero(),
+                HashValue::zero(),
+                self.ledger_version,
+                0,
+                None,
+            );
+            Ok(Some(LedgerInfoWithSignatures::new(
+                LedgerInfo::new(block_info, HashValue::zero()),
+                BTreeMap::new(),
+            )))
+        }
+
+        fn get_latest_event_sequence_number(
+            &self,
+            _event_key: &EventKey,
+            ledger_version: Version,
+        ) -> Result<Option<u64>> {
+            let committed = self
+                .block_versions
+                .iter()
+                .filter(|version| **version <= ledger_version)
+                .count() as u64;
+            Ok(committed.checked_sub(1))
+        }
+
+        fn get_events(
+            &self,
+            event_key: &EventKey,
+            start: u64,
+            order: Order,
+            limit: u64,
+        ) -> Result<Vec<(u64, ContractEvent)>> {
+            assert!(matches!(order, Order::Ascending));
+            Ok(self
+                .block_versions
+                .iter()
+                .enumerate()
+                .skip(start as usize)
+                .take(limit as usize)
+                .map(|(sequence_number, version)| {
+                    let event = ContractEvent::new(
+                        *event_key,
+                        sequence_number as u64,
+                        TypeTag::Bool,
+                        vec![],
+                    );
+                    (*version, event)
+                })
+                .collect())
+        }
+
+        fn get_accumulator_root_hash(&self, version: Version) -> Result<HashValue> {
+            Ok(HashValue::sha3_256_of(&version.to_le_bytes()))
+        }
+
+        fn get_block_timestamp(&self, version: Version) -> Result<u64> {
+            Ok(version * 1000)
+        }
+    }
+
+    async fn network_status(ledger_version: Version, block_versions: Vec<Version>) -> Value {
+        let (mp_sender, _) = futures::channel::mpsc::channel(1);
+        let db = Arc::new(MockDb {
+            ledger_version,
+            block_versions,
+        });
+        let context = Context::new(ChainId::test(), db, mp_sender, ApiConfig::default());
+        let response = warp::test::request()
+            .method("POST")
+            .path("/network/status")
+            .json(&json!({
+                "network_identifier": network_identifier(ChainId::test())
+            }))
+            .reply(&routes(context))
+            .await;
+        serde_json::from_slice(response.body()).unwrap()
+    }
+
+    fn hash_of(version: Version) -> String {
+        HashValue::sha3_256_of(&version.to_le_bytes()).to_hex_literal()
+    }
+
+    #[tokio::test]
+    async fn test_network_status_of_latest_block() {
+        let status: NetworkStatusResponse =
+            serde_json::from_value(network_status(10, vec![1, 4, 9]).await).unwrap();
+        assert_eq!(status.current_block_identifier.index, 3);
+        assert_eq!(status.current_block_identifier.hash, hash_of(10));
+        assert_eq!(status.current_block_timestamp, 9);
+        assert_eq!(status.genesis_block_identifier.index, 0);
+        assert_eq!(status.genesis_block_identifier.hash, hash_of(0));
+
+        // the block committed after the ledger version isn't the latest one yet
+        let status: NetworkStatusResponse =
+            serde_json::from_value(network_status(6, vec![1, 4, 9]).await).unwrap();
+        assert_eq!(status.current_block_identifier.index, 2);
+        assert_eq!(status.current_block_identifier.hash, hash_of(6));
+        assert_eq!(status.current_block_timestamp, 4);
+    }
+
+    #[tokio::test]
+    async fn test_network_status_of_genesis() {
+        let status: NetworkStatusResponse =
+            serde_json::from_value(network_status(0, vec![]).await).unwrap();
+        assert_eq!(status.current_block_identifier.index, 0);
+        assert_eq!(
+            status.current_block_identifier,
+            status.genesis_block_identifier
+        );
+    }
+
+    #[tokio::test]
+    async fn test_network_status_without_block_events() {
+        // the height isn't taken to be the one of genesis when the new block events are missing
+        let error = network_status(5, vec![]).await;
+        assert_eq!(error["code"], 7);
+    }
+}
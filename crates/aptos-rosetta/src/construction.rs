@@ -0,0 +1,318 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The construction API builds, signs and submits the transfers of the native coin. Only
+//! `/construction/metadata` and `/construction/submit` read the node, the other endpoints
+//! work offline.
+
+use crate::{
+    check_network,
+    error::ApiError,
+    native_currency,
+    operations::{parse_address, Transfer},
+    types::{
+        AccountIdentifier, Amount, ConstructionCombineRequest, ConstructionCombineResponse,
+        ConstructionDeriveRequest, ConstructionDeriveResponse, ConstructionHashRequest,
+        ConstructionMetadata, ConstructionMetadataRequest, ConstructionMetadataResponse,
+        ConstructionParseRequest, ConstructionParseResponse, ConstructionPayloadsRequest,
+        ConstructionPayloadsResponse, ConstructionPreprocessRequest,
+        ConstructionPreprocessResponse, ConstructionSubmitRequest, MetadataOptions, SignatureType,
+        SigningPayload, TransactionIdentifier, TransactionIdentifierResponse,
+    },
+};
+
+use aptos_api::Context;
+use aptos_crypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use aptos_types::{
+    chain_id::ChainId,
+    mempool_status::MempoolStatusCode,
+    transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+};
+use serde::de::DeserializeOwned;
+use std::convert::TryFrom;
+use warp::{filters::BoxedFilter, reply, Filter, Rejection, Reply};
+
+const DEFAULT_MAX_GAS_AMOUNT: u64 = 2_000;
+// The gas is paid in the native coin.
+const GAS_CURRENCY_CODE: &str = "TestCoin";
+// Seconds a constructed transaction stays valid after its metadata is read from the node.
+const TRANSACTION_EXPIRATION_SECS: u64 = 600;
+
+// POST /construction/derive
+pub fn derive(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("construction" / "derive")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_derive)
+        .boxed()
+}
+
+// POST /construction/preprocess
+pub fn preprocess(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("construction" / "preprocess")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_preprocess)
+        .boxed()
+}
+
+// POST /construction/metadata
+pub fn metadata(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("construction" / "metadata")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_metadata)
+        .boxed()
+}
+
+// POST /construction/payloads
+pub fn payloads(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("construction" / "payloads")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_payloads)
+        .boxed()
+}
+
+// POST /construction/combine
+pub fn combine(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("construction" / "combine")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_combine)
+        .boxed()
+}
+
+// POST /construction/parse
+pub fn parse(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("construction" / "parse")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_parse)
+        .boxed()
+}
+
+// POST /construction/hash
+pub fn hash(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("construction" / "hash")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_hash)
+        .boxed()
+}
+
+// POST /construction/submit
+pub fn submit(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("construction" / "submit")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_submit)
+        .boxed()
+}
+
+async fn handle_derive(
+    request: ConstructionDeriveRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let bytes = decode_hex(&request.public_key.hex_bytes)?;
+    let public_key = Ed25519PublicKey::try_from(bytes.as_slice())
+        .map_err(|e| ApiError::InvalidRequest(format!("invalid public key: {}", e)))?;
+    let address = AuthenticationKey::ed25519(&public_key).derived_address();
+    Ok(reply::json(&ConstructionDeriveResponse {
+        account_identifier: AccountIdentifier {
+            address: address.to_hex_literal(),
+        },
+    }))
+}
+
+async fn handle_preprocess(
+    request: ConstructionPreprocessRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let transfer = Transfer::from_operations(&request.operations)?;
+    Ok(reply::json(&ConstructionPreprocessResponse {
+        options: MetadataOptions {
+            sender: AccountIdentifier {
+                address: transfer.sender.to_hex_literal(),
+            },
+        },
+    }))
+}
+
+/// Responds the sequence number of the sender and the suggested gas unit price; the
+/// transaction expires `TRANSACTION_EXPIRATION_SECS` after the latest ledger timestamp.
+async fn handle_metadata(
+    request: ConstructionMetadataRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let sender = parse_address(&request.options.sender.address)?;
+    let ledger_info = context.get_latest_ledger_info().map_err(ApiError::from)?;
+    let sequence_number = context
+        .get_account_state(sender, ledger_info.version())
+        .map_err(ApiError::from)?
+        .and_then(|state| state.get_account_resource().ok().flatten())
+        .ok_or_else(|| ApiError::AccountNotFound(sender.to_hex_literal()))?
+        .sequence_number();
+    let gas_unit_price = context
+        .estimate_gas_price(ledger_info.version())
+        .map_err(ApiError::from)?
+        .median
+        .0;
+    let metadata = ConstructionMetadata {
+        sequence_number,
+        max_gas_amount: DEFAULT_MAX_GAS_AMOUNT,
+        gas_unit_price,
+        expiration_timestamp_secs: ledger_info.timestamp() / 1_000_000
+            + TRANSACTION_EXPIRATION_SECS,
+        chain_id: ledger_info.chain_id,
+    };
+    Ok(reply::json(&ConstructionMetadataResponse {
+        suggested_fee: vec![Amount {
+            value: (DEFAULT_MAX_GAS_AMOUNT as u128 * gas_unit_price as u128).to_string(),
+            currency: native_currency(),
+        }],
+        metadata,
+    }))
+}
+
+async fn handle_payloads(
+    request: ConstructionPayloadsRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let transfer = Transfer::from_operations(&request.operations)?;
+    let metadata = request.metadata;
+    let raw_txn = RawTransaction::new(
+        transfer.sender,
+        metadata.sequence_number,
+        transfer.payload(),
+        metadata.max_gas_amount,
+        metadata.gas_unit_price,
+        GAS_CURRENCY_CODE.to_owned(),
+        metadata.expiration_timestamp_secs,
+        ChainId::new(metadata.chain_id),
+    );
+    Ok(reply::json(&ConstructionPayloadsResponse {
+        unsigned_transaction: encode_bcs(&raw_txn)?,
+        payloads: vec![SigningPayload {
+            account_identifier: Some(AccountIdentifier {
+                address: transfer.sender.to_hex_literal(),
+            }),
+            hex_bytes: hex::encode(raw_txn.signing_message()),
+            signature_type: Some(SignatureType::Ed25519),
+        }],
+    }))
+}
+
+async fn handle_combine(
+    request: ConstructionCombineRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let raw_txn: RawTransaction = decode_bcs(&request.unsigned_transaction)?;
+    let signature = match request.signatures.as_slice() {
+        [signature] => signature,
+        _ => {
+            return Err(ApiError::InvalidRequest(
+                "a transfer is signed by exactly one signature".to_owned(),
+            )
+            .into())
+        }
+    };
+    let public_key =
+        Ed25519PublicKey::try_from(decode_hex(&signature.public_key.hex_bytes)?.as_slice())
+            .map_err(|e| ApiError::InvalidRequest(format!("invalid public key: {}", e)))?;
+    let signature = Ed25519Signature::try_from(decode_hex(&signature.hex_bytes)?.as_slice())
+        .map_err(|e| ApiError::InvalidRequest(format!("invalid signature: {}", e)))?;
+    let signed_txn = SignedTransaction::new(raw_txn, public_key, signature);
+    Ok(reply::json(&ConstructionCombineResponse {
+        signed_transaction: encode_bcs(&signed_txn)?,
+    }))
+}
+
+async fn handle_parse(
+    request: ConstructionParseRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let (raw_txn, signers) = if request.signed {
+        let signed_txn: SignedTransaction = decode_bcs(&request.transaction)?;
+        let signer = AccountIdentifier {
+            address: signed_txn.sender().to_hex_literal(),
+        };
+        (signed_txn.into_raw_transaction(), vec![signer])
+    } else {
+        (decode_bcs::<RawTransaction>(&request.transaction)?, vec![])
+    };
+    let sender = raw_txn.sender();
+    let transfer = Transfer::from_payload(sender, &raw_txn.into_payload())?;
+    Ok(reply::json(&ConstructionParseResponse {
+        operations: transfer.operations(None),
+        account_identifier_signers: signers,
+    }))
+}
+
+async fn handle_hash(
+    request: ConstructionHashRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let signed_txn: SignedTransaction = decode_bcs(&request.signed_transaction)?;
+    Ok(reply::json(&TransactionIdentifierResponse {
+        transaction_identifier: TransactionIdentifier {
+            hash: signed_txn.committed_hash().to_hex_literal(),
+        },
+    }))
+}
+
+async fn handle_submit(
+    request: ConstructionSubmitRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let signed_txn: SignedTransaction = decode_bcs(&request.signed_transaction)?;
+    let hash = signed_txn.clone().committed_hash();
+    let (mempool_status, vm_status) = context
+        .submit_transaction(signed_txn)
+        .await
+        .map_err(ApiError::from)?;
+    if mempool_status.code != MempoolStatusCode::Accepted {
+        return Err(ApiError::TransactionRejected(match vm_status {
+            Some(vm_status) => format!("{:?}", vm_status),
+            None => mempool_status.message,
+        })
+        .into());
+    }
+    Ok(reply::json(&TransactionIdentifierResponse {
+        transaction_identifier: TransactionIdentifier {
+            hash: hash.to_hex_literal(),
+        },
+    }))
+}
+
+fn decode_hex(hex_bytes: &str) -> Result<Vec<u8>, ApiError> {
+    hex::decode(hex_bytes.trim_start_matches("0x"))
+        .map_err(|e| ApiError::InvalidRequest(format!("invalid hex bytes: {}", e)))
+}
+
+fn decode_bcs<T: DeserializeOwned>(hex_bytes: &str) -> Result<T, ApiError> {
+    bcs::from_bytes(&decode_hex(hex_bytes)?)
+        .map_err(|e| ApiError::InvalidRequest(format!("invalid transaction: {}", e)))
+}
+
+fn encode_bcs<T: serde::Serialize>(value: &T) -> Result<String, ApiError> {
+    Ok(hex::encode(
+        bcs::to_bytes(value).map_err(anyhow::Error::from)?,
+    ))
+}
@@ -0,0 +1,143 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types;
+
+use aptos_api_types::AptosErrorCode;
+use serde_json::json;
+use warp::reject::Reject;
+
+/// The errors of the Rosetta API. Rosetta clients tell them apart by the code; the detail
+/// is given in the `details` of the error object.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ApiError {
+    NetworkNotSupported(String),
+    BlockNotFound(String),
+    AccountNotFound(String),
+    InvalidRequest(String),
+    UnsupportedOperations(String),
+    TransactionRejected(String),
+    InternalError(String),
+}
+
+impl ApiError {
+    /// Returns an error of each kind without detail, for the `/network/options` response.
+    pub fn all() -> Vec<types::Error> {
+        [
+            ApiError::NetworkNotSupported(String::new()),
+            ApiError::BlockNotFound(String::new()),
+            ApiError::AccountNotFound(String::new()),
+            ApiError::InvalidRequest(String::new()),
+            ApiError::UnsupportedOperations(String::new()),
+            ApiError::TransactionRejected(String::new()),
+            ApiError::InternalError(String::new()),
+        ]
+        .iter()
+        .map(|err| types::Error {
+            details: None,
+            ..err.to_error()
+        })
+        .collect()
+    }
+
+    pub fn code(&self) -> u32 {
+        match self {
+            ApiError::NetworkNotSupported(_) => 1,
+            ApiError::BlockNotFound(_) => 2,
+            ApiError::AccountNotFound(_) => 3,
+            ApiError::InvalidRequest(_) => 4,
+            ApiError::UnsupportedOperations(_) => 5,
+            ApiError::TransactionRejected(_) => 6,
+            ApiError::InternalError(_) => 7,
+        }
+    }
+
+    pub fn retriable(&self) -> bool {
+        // a block or account not found may be committed later
+        matches!(
+            self,
+            ApiError::BlockNotFound(_) | ApiError::AccountNotFound(_) | ApiError::InternalError(_)
+        )
+    }
+
+    fn message(&self) -> &'static str {
+        match self {
+            ApiError::NetworkNotSupported(_) => "network not supported",
+            ApiError::BlockNotFound(_) => "block not found",
+            ApiError::AccountNotFound(_) => "account not found",
+            ApiError::InvalidRequest(_) => "invalid request",
+            ApiError::UnsupportedOperations(_) => "unsupported operations",
+            ApiError::TransactionRejected(_) => "transaction rejected",
+            ApiError::InternalError(_) => "internal error",
+        }
+    }
+
+    fn detail(&self) -> &str {
+        match self {
+            ApiError::NetworkNotSupported(detail)
+            | ApiError::BlockNotFound(detail)
+            | ApiError::AccountNotFound(detail)
+            | ApiError::InvalidRequest(detail)
+            | ApiError::UnsupportedOperations(detail)
+            | ApiError::TransactionRejected(detail)
+            | ApiError::InternalError(detail) => detail,
+        }
+    }
+
+    pub fn to_error(&self) -> types::Error {
+        types::Error {
+            code: self.code(),
+            message: self.message().to_owned(),
+            retriable: self.retriable(),
+            details: Some(json!({ "error": self.detail() })),
+        }
+    }
+}
+
+impl Reject for ApiError {}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        ApiError::InternalError(err.to_string())
+    }
+}
+
+impl From<aptos_api_types::Error> for ApiError {
+    fn from(err: aptos_api_types::Error) -> Self {
+        match err.error_code {
+            AptosErrorCode::AccountNotFound | AptosErrorCode::ResourceNotFound => {
+                ApiError::AccountNotFound(err.message)
+            }
+            AptosErrorCode::BlockNotFound
+            | AptosErrorCode::VersionNotFound
+            | AptosErrorCode::VersionPruned => ApiError::BlockNotFound(err.message),
+            _ if err.code < 500 => ApiError::InvalidRequest(err.message),
+            _ => ApiError::InternalError(err.message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApiError;
+    use aptos_api_types::{AptosErrorCode, Error};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_error_codes_are_unique() {
+        let errors = ApiError::all();
+        let codes: HashSet<u32> = errors.iter().map(|err| err.code).collect();
+        assert_eq!(codes.len(), errors.len());
+        assert!(errors.iter().all(|err| err.details.is_none()));
+    }
+
+    #[test]
+    fn test_from_rest_error() {
+        let err = Error::not_found(AptosErrorCode::AccountNotFound, "account", "0x1", 1);
+        assert_eq!(ApiError::from(err).code(), 3);
+        let err = Error::invalid_param("address", "0xzz");
+        assert_eq!(ApiError::from(err).code(), 4);
+        let err = Error::internal(anyhow::format_err!("db error"));
+        assert!(ApiError::from(err).retriable());
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A Rosetta Data and Construction API server for exchange integrations, see
+//! https://www.rosetta-api.org. It is backed by the same `Context` as the REST API, and
+//! supports the transfers of the native coin `0x1::TestCoin::Coin`.
+
+mod account;
+mod block;
+mod construction;
+mod error;
+mod network;
+mod operations;
+pub mod types;
+
+use crate::{
+    error::ApiError,
+    types::{Currency, NetworkIdentifier},
+};
+
+use aptos_api::Context;
+use aptos_config::config::NodeConfig;
+use aptos_logger::info;
+use aptos_mempool::MempoolClientSender;
+use aptos_types::chain_id::ChainId;
+use std::{convert::Infallible, sync::Arc};
+use storage_interface::DbReader;
+use tokio::runtime::{Builder, Runtime};
+use warp::{http::StatusCode, reply, Filter, Rejection, Reply};
+
+/// The version of the Rosetta specification implemented.
+pub const ROSETTA_VERSION: &str = "1.4.12";
+pub const BLOCKCHAIN: &str = "aptos";
+
+/// Starts the Rosetta server when `rosetta_address` is set in the API config, returns the
+/// Tokio runtime serving it.
+pub fn bootstrap(
+    config: &NodeConfig,
+    chain_id: ChainId,
+    db: Arc<dyn DbReader>,
+    mp_sender: MempoolClientSender,
+) -> anyhow::Result<Option<Runtime>> {
+    let address = match config.api.rosetta_address {
+        Some(address) => address,
+        None => return Ok(None),
+    };
+    let runtime = Builder::new_multi_thread()
+        .thread_name("rosetta")
+        .enable_all()
+        .build()
+        .expect("[rosetta] failed to create runtime");
+
+    let context =
+        Context::new(chain_id, db, mp_sender, config.api.clone()).with_role(config.base.role);
+    runtime.spawn(async move {
+        info!("Rosetta server listening on {}", address);
+        warp::serve(routes(context)).bind(address).await;
+    });
+    Ok(Some(runtime))
+}
+
+pub fn routes(context: Context) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone {
+    network::list(context.clone())
+        .or(network::options(context.clone()))
+        .or(network::status(context.clone()))
+        .or(block::block(context.clone()))
+        .or(account::balance(context.clone()))
+        .or(construction::derive(context.clone()))
+        .or(construction::preprocess(context.clone()))
+        .or(construction::metadata(context.clone()))
+        .or(construction::payloads(context.clone()))
+        .or(construction::combine(context.clone()))
+        .or(construction::parse(context.clone()))
+        .or(construction::hash(context.clone()))
+        .or(construction::submit(context))
+        .recover(handle_rejection)
+}
+
+/// The currency of the native coin `0x1::TestCoin::Coin`.
+pub fn native_currency() -> Currency {
+    Currency {
+        symbol: "TC".to_owned(),
+        decimals: 0,
+    }
+}
+
+pub fn network_identifier(chain_id: ChainId) -> NetworkIdentifier {
+    NetworkIdentifier {
+        blockchain: BLOCKCHAIN.to_owned(),
+        network: chain_id.to_string(),
+    }
+}
+
+/// Rejects the requests for a network other than the chain of the node.
+fn check_network(network: &NetworkIdentifier, context: &Context) -> Result<(), ApiError> {
+    if network == &network_identifier(context.chain_id()) {
+        Ok(())
+    } else {
+        Err(ApiError::NetworkNotSupported(format!(
+            "{}/{}",
+            network.blockchain, network.network
+        )))
+    }
+}
+
+// Rosetta responds 500 with an `Error` object for all errors.
+async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
+    let error = if let Some(error) = err.find::<ApiError>() {
+        error.clone()
+    } else if let Some(cause) = err.find::<warp::body::BodyDeserializeError>() {
+        ApiError::InvalidRequest(cause.to_string())
+    } else if err.is_not_found() {
+        ApiError::InvalidRequest("unknown endpoint".to_owned())
+    } else {
+        ApiError::InternalError(format!("unexpected error: {:?}", err))
+    };
+    Ok(reply::with_status(
+        reply::json(&error.to_error()),
+        StatusCode::INTERNAL_SERVER_ERROR,
+    ))
+}
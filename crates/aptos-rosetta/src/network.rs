@@ -0,0 +1,101 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block::{block_identifier, latest_block_height},
+    check_network,
+    error::ApiError,
+    network_identifier,
+    operations::{FAILURE, FEE, SUCCESS, TRANSFER},
+    types::{
+        Allow, MetadataRequest, NetworkListResponse, NetworkOptionsResponse, NetworkRequest,
+        NetworkStatusResponse, OperationStatus, Version,
+    },
+    ROSETTA_VERSION,
+};
+
+use aptos_api::Context;
+use warp::{filters::BoxedFilter, reply, Filter, Rejection, Reply};
+
+// POST /network/list
+pub fn list(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("network" / "list")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_list)
+        .boxed()
+}
+
+// POST /network/options
+pub fn options(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("network" / "options")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_options)
+        .boxed()
+}
+
+// POST /network/status
+pub fn status(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("network" / "status")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(context.filter())
+        .and_then(handle_status)
+        .boxed()
+}
+
+async fn handle_list(_: MetadataRequest, context: Context) -> Result<impl Reply, Rejection> {
+    Ok(reply::json(&NetworkListResponse {
+        network_identifiers: vec![network_identifier(context.chain_id())],
+    }))
+}
+
+async fn handle_options(
+    request: NetworkRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    Ok(reply::json(&NetworkOptionsResponse {
+        version: Version {
+            rosetta_version: ROSETTA_VERSION.to_owned(),
+            node_version: env!("CARGO_PKG_VERSION").to_owned(),
+        },
+        allow: Allow {
+            operation_statuses: vec![
+                OperationStatus {
+                    status: SUCCESS.to_owned(),
+                    successful: true,
+                },
+                OperationStatus {
+                    status: FAILURE.to_owned(),
+                    successful: false,
+                },
+            ],
+            operation_types: vec![TRANSFER.to_owned(), FEE.to_owned()],
+            errors: ApiError::all(),
+            historical_balance_lookup: true,
+        },
+    }))
+}
+
+async fn handle_status(request: NetworkRequest, context: Context) -> Result<impl Reply, Rejection> {
+    check_network(&request.network_identifier, &context)?;
+    let ledger_version = context
+        .get_latest_ledger_info()
+        .map_err(ApiError::from)?
+        .version();
+    let height = latest_block_height(&context, ledger_version)?;
+    let (current_block_identifier, current_block_timestamp) =
+        block_identifier(&context, height, ledger_version)?;
+    let (genesis_block_identifier, _) = block_identifier(&context, 0, ledger_version)?;
+    Ok(reply::json(&NetworkStatusResponse {
+        current_block_identifier,
+        current_block_timestamp,
+        genesis_block_identifier,
+        // the peers of the node are not exposed through the API
+        peers: vec![],
+    }))
+}
@@ -0,0 +1,302 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::ApiError,
+    native_currency,
+    types::{self, AccountIdentifier, Amount, Operation, OperationIdentifier},
+};
+
+use aptos_api_types::TransactionOnChainData;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::CORE_CODE_ADDRESS,
+    transaction::{Transaction, TransactionPayload},
+    vm_status::KeptVMStatus,
+};
+use cached_framework_packages::aptos_stdlib;
+use move_core_types::{
+    ident_str,
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
+use serde::Deserialize;
+
+pub const TRANSFER: &str = "transfer";
+pub const FEE: &str = "fee";
+
+pub const SUCCESS: &str = "success";
+pub const FAILURE: &str = "failure";
+
+// The events emitted by `0x1::TestCoin::transfer`.
+#[derive(Deserialize)]
+struct SentEvent {
+    amount: u64,
+    #[allow(dead_code)]
+    to: AccountAddress,
+}
+
+#[derive(Deserialize)]
+struct ReceivedEvent {
+    amount: u64,
+    #[allow(dead_code)]
+    from: AccountAddress,
+}
+
+/// A transfer of the native coin, the only transaction built by the construction API. It
+/// is the withdrawal from the sender and the deposit to the receiver of the same amount.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transfer {
+    pub sender: AccountAddress,
+    pub receiver: AccountAddress,
+    pub amount: u64,
+}
+
+impl Transfer {
+    pub fn from_operations(operations: &[Operation]) -> Result<Self, ApiError> {
+        let amounts = operations
+            .iter()
+            .map(|op| {
+                if op.operation_type != TRANSFER {
+                    return Err(unsupported(format!("operation type {}", op.operation_type)));
+                }
+                let account = op
+                    .account
+                    .as_ref()
+                    .ok_or_else(|| unsupported("operation without account".to_owned()))?;
+                let amount = op
+                    .amount
+                    .as_ref()
+                    .ok_or_else(|| unsupported("operation without amount".to_owned()))?;
+                Ok((parse_address(&account.address)?, parse_amount(amount)?))
+            })
+            .collect::<Result<Vec<_>, ApiError>>()?;
+
+        let ((sender, withdrawn), (receiver, deposited)) = match amounts.as_slice() {
+            [a, b] if a.1 < 0 => (*a, *b),
+            [a, b] => (*b, *a),
+            _ => return Err(unsupported("a transfer is made of 2 operations".to_owned())),
+        };
+        if withdrawn >= 0 || deposited != -withdrawn || deposited > u64::MAX as i128 {
+            return Err(unsupported(
+                "a transfer withdraws from the sender the amount deposited to the receiver"
+                    .to_owned(),
+            ));
+        }
+        Ok(Self {
+            sender,
+            receiver,
+            amount: deposited as u64,
+        })
+    }
+
+    /// Parses the transfer from the payload of a transaction sent by `sender`.
+    pub fn from_payload(
+        sender: AccountAddress,
+        payload: &TransactionPayload,
+    ) -> Result<Self, ApiError> {
+        let function = match payload {
+            TransactionPayload::ScriptFunction(function)
+                if function.module() == &test_coin_module()
+                    && function.function() == ident_str!("transfer")
+                    && function.ty_args().is_empty() =>
+            {
+                function
+            }
+            _ => return Err(unsupported("the payload is not a transfer".to_owned())),
+        };
+        match function.args() {
+            [receiver, amount] => Ok(Self {
+                sender,
+                receiver: bcs::from_bytes(receiver).map_err(invalid_argument)?,
+                amount: bcs::from_bytes(amount).map_err(invalid_argument)?,
+            }),
+            _ => Err(unsupported(
+                "the transfer doesn't have 2 arguments".to_owned(),
+            )),
+        }
+    }
+
+    pub fn payload(&self) -> TransactionPayload {
+        aptos_stdlib::encode_transfer_script_function(self.receiver, self.amount)
+    }
+
+    pub fn operations(&self, status: Option<&str>) -> Vec<Operation> {
+        vec![
+            operation(0, TRANSFER, status, self.sender, -(self.amount as i128)),
+            operation(1, TRANSFER, status, self.receiver, self.amount as i128),
+        ]
+    }
+}
+
+/// Renders a committed transaction with the operations on the native coin: the transfers
+/// told by its events, and the fee paid by the sender of a user transaction.
+pub fn transaction(data: &TransactionOnChainData) -> Result<types::Transaction, ApiError> {
+    let mut operations = vec![];
+    for event in &data.events {
+        let account = event.key().get_creator_address();
+        let amount = if event.type_tag() == &test_coin_event("SentEvent") {
+            -(bcs::from_bytes::<SentEvent>(event.event_data())
+                .map_err(anyhow::Error::from)?
+                .amount as i128)
+        } else if event.type_tag() == &test_coin_event("ReceivedEvent") {
+            bcs::from_bytes::<ReceivedEvent>(event.event_data())
+                .map_err(anyhow::Error::from)?
+                .amount as i128
+        } else {
+            continue;
+        };
+        operations.push(operation(
+            operations.len() as u64,
+            TRANSFER,
+            Some(SUCCESS),
+            account,
+            amount,
+        ));
+    }
+
+    if let Transaction::UserTransaction(txn) = &data.transaction {
+        // a failed transfer emits no event, it is told by the payload
+        if data.info.status() != &KeptVMStatus::Executed {
+            if let Ok(transfer) = Transfer::from_payload(txn.sender(), txn.payload()) {
+                for mut op in transfer.operations(Some(FAILURE)) {
+                    op.operation_identifier.index = operations.len() as u64;
+                    operations.push(op);
+                }
+            }
+        }
+        let fee = data.info.gas_used() as i128 * txn.gas_unit_price() as i128;
+        if fee > 0 {
+            operations.push(operation(
+                operations.len() as u64,
+                FEE,
+                Some(SUCCESS),
+                txn.sender(),
+                -fee,
+            ));
+        }
+    }
+
+    Ok(types::Transaction {
+        transaction_identifier: types::TransactionIdentifier {
+            hash: data.info.transaction_hash().to_hex_literal(),
+        },
+        operations,
+    })
+}
+
+pub fn parse_address(address: &str) -> Result<AccountAddress, ApiError> {
+    AccountAddress::from_hex_literal(address)
+        .map_err(|_| ApiError::InvalidRequest(format!("invalid address {}", address)))
+}
+
+fn parse_amount(amount: &Amount) -> Result<i128, ApiError> {
+    if amount.currency != native_currency() {
+        return Err(unsupported(format!("currency {}", amount.currency.symbol)));
+    }
+    amount
+        .value
+        .parse()
+        .map_err(|_| ApiError::InvalidRequest(format!("invalid amount {}", amount.value)))
+}
+
+fn operation(
+    index: u64,
+    operation_type: &str,
+    status: Option<&str>,
+    account: AccountAddress,
+    value: i128,
+) -> Operation {
+    Operation {
+        operation_identifier: OperationIdentifier { index },
+        operation_type: operation_type.to_owned(),
+        status: status.map(str::to_owned),
+        account: Some(AccountIdentifier {
+            address: account.to_hex_literal(),
+        }),
+        amount: Some(Amount {
+            value: value.to_string(),
+            currency: native_currency(),
+        }),
+    }
+}
+
+fn test_coin_module() -> ModuleId {
+    ModuleId::new(CORE_CODE_ADDRESS, ident_str!("TestCoin").to_owned())
+}
+
+fn test_coin_event(name: &str) -> TypeTag {
+    TypeTag::Struct(StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: ident_str!("TestCoin").to_owned(),
+        name: Identifier::new(name).expect("valid identifier"),
+        type_params: vec![],
+    })
+}
+
+fn unsupported(detail: String) -> ApiError {
+    ApiError::UnsupportedOperations(detail)
+}
+
+fn invalid_argument(err: bcs::Error) -> ApiError {
+    ApiError::InvalidRequest(format!("invalid transfer argument: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Transfer, FAILURE};
+    use aptos_types::account_address::AccountAddress;
+
+    fn transfer() -> Transfer {
+        Transfer {
+            sender: AccountAddress::from_hex_literal("0xa").unwrap(),
+            receiver: AccountAddress::from_hex_literal("0xb").unwrap(),
+            amount: 100,
+        }
+    }
+
+    #[test]
+    fn test_transfer_operations_round_trip() {
+        let transfer = transfer();
+        let mut operations = transfer.operations(None);
+        assert_eq!(operations[0].amount.as_ref().unwrap().value, "-100");
+        assert_eq!(Transfer::from_operations(&operations).unwrap(), transfer);
+
+        operations.reverse();
+        assert_eq!(Transfer::from_operations(&operations).unwrap(), transfer);
+        assert!(transfer
+            .operations(Some(FAILURE))
+            .iter()
+            .all(|op| op.status.as_deref() == Some(FAILURE)));
+    }
+
+    #[test]
+    fn test_transfer_operations_must_balance() {
+        let mut operations = transfer().operations(None);
+        operations[1].amount.as_mut().unwrap().value = "99".to_owned();
+        assert_eq!(
+            Transfer::from_operations(&operations).unwrap_err().code(),
+            5
+        );
+        assert_eq!(
+            Transfer::from_operations(&operations[..1])
+                .unwrap_err()
+                .code(),
+            5
+        );
+
+        let mut operations = transfer().operations(None);
+        operations[0].amount.as_mut().unwrap().currency.symbol = "BTC".to_owned();
+        assert_eq!(
+            Transfer::from_operations(&operations).unwrap_err().code(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_transfer_payload_round_trip() {
+        let transfer = transfer();
+        let parsed = Transfer::from_payload(transfer.sender, &transfer.payload()).unwrap();
+        assert_eq!(parsed, transfer);
+    }
+}
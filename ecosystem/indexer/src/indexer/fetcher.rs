@@ -2,18 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::counters::{FETCHED_TRANSACTION, UNABLE_TO_FETCH_TRANSACTION};
+use anyhow::{ensure, format_err};
 use aptos_rest_client::{Client as RestClient, Transaction};
+use aptos_transaction_stream::{
+    transaction_stream_client::TransactionStreamClient, GetTransactionsRequest,
+    TransactionsResponse,
+};
 use std::time::Duration;
 use tokio::sync::Mutex;
+use tonic::Streaming;
 use url::Url;
 
 // TODO: make this configurable
 const RETRY_TIME_MILLIS: u64 = 5000;
 const TRANSACTION_FETCH_BATCH_SIZE: u64 = 500;
 
-#[derive(Debug)]
 pub struct TransactionFetcher {
     client: RestClient,
+    // when set, the transactions are streamed from the gRPC transaction stream of the node
+    // instead of polled from the REST API
+    grpc_url: Option<String>,
+    stream: Option<Streaming<TransactionsResponse>>,
     version: u64,
     transactions_buffer: Mutex<Vec<Transaction>>,
 }
@@ -24,6 +33,8 @@ impl TransactionFetcher {
 
         Self {
             client,
+            grpc_url: None,
+            stream: None,
             version: starting_version.unwrap_or(0),
             transactions_buffer: Default::default(),
         }
@@ -31,6 +42,13 @@ impl TransactionFetcher {
 
     pub fn set_version(&mut self, version: u64) {
         self.version = version;
+        // the stream restarts from the new version
+        self.stream = None;
+    }
+
+    pub fn set_grpc_url(&mut self, grpc_url: String) {
+        self.grpc_url = Some(grpc_url);
+        self.stream = None;
     }
 
     /// Fetches the next version based on its internal version counter
@@ -40,49 +58,122 @@ impl TransactionFetcher {
         let mut transactions_buffer = self.transactions_buffer.lock().await;
         if transactions_buffer.is_empty() {
             // Fill it up!
-            loop {
-                let res = self
-                    .client
-                    .get_transactions(Some(self.version), Some(TRANSACTION_FETCH_BATCH_SIZE))
-                    .await;
-                match res {
-                    Ok(response) => {
-                        FETCHED_TRANSACTION.inc();
-                        let mut transactions = response.into_inner();
-                        transactions.reverse();
-                        *transactions_buffer = transactions;
-                        break;
-                    }
-                    Err(err) => {
-                        let err_str = err.to_string();
-                        // If it's a 404, then we're all caught up; no need to increment the `UNABLE_TO_FETCH_TRANSACTION` counter
-                        if err_str.contains("404") {
-                            aptos_logger::debug!(
+            let mut transactions = match &self.grpc_url {
+                Some(grpc_url) => {
+                    Self::stream_batch(&mut self.stream, grpc_url, self.version).await
+                }
+                None => self.fetch_batch().await,
+            };
+            transactions.reverse();
+            *transactions_buffer = transactions;
+        }
+        // At this point we're guaranteed to have something in the buffer
+        let transaction = transactions_buffer.pop().unwrap();
+        self.version += 1;
+        transaction
+    }
+
+    async fn fetch_batch(&self) -> Vec<Transaction> {
+        loop {
+            let res = self
+                .client
+                .get_transactions(Some(self.version), Some(TRANSACTION_FETCH_BATCH_SIZE))
+                .await;
+            match res {
+                Ok(response) => {
+                    FETCHED_TRANSACTION.inc();
+                    return response.into_inner();
+                }
+                Err(err) => {
+                    let err_str = err.to_string();
+                    // If it's a 404, then we're all caught up; no need to increment the `UNABLE_TO_FETCH_TRANSACTION` counter
+                    if err_str.contains("404") {
+                        aptos_logger::debug!(
                             "Could not fetch {} transactions starting at {}: all caught up. Will check again in {}ms.",
                             TRANSACTION_FETCH_BATCH_SIZE,
                             self.version,
                             RETRY_TIME_MILLIS,
-                        );
-                            tokio::time::sleep(Duration::from_millis(RETRY_TIME_MILLIS)).await;
-                            continue;
-                        }
-                        UNABLE_TO_FETCH_TRANSACTION.inc();
-                        aptos_logger::error!(
-                            "Could not fetch {} transactions starting at {}, will retry in {}ms. Err: {:?}",
-                            TRANSACTION_FETCH_BATCH_SIZE,
-                            self.version,
-                            RETRY_TIME_MILLIS,
-                            err
                         );
                         tokio::time::sleep(Duration::from_millis(RETRY_TIME_MILLIS)).await;
+                        continue;
                     }
-                };
+                    UNABLE_TO_FETCH_TRANSACTION.inc();
+                    aptos_logger::error!(
+                        "Could not fetch {} transactions starting at {}, will retry in {}ms. Err: {:?}",
+                        TRANSACTION_FETCH_BATCH_SIZE,
+                        self.version,
+                        RETRY_TIME_MILLIS,
+                        err
+                    );
+                    tokio::time::sleep(Duration::from_millis(RETRY_TIME_MILLIS)).await;
+                }
+            };
+        }
+    }
+
+    /// Receives the next batch of the transaction stream, the stream waits for new
+    /// transactions when all are fetched, hence there is no polling.
+    /// In the event it can't, it will reconnect from `version` every RETRY_TIME_MILLIS ms
+    async fn stream_batch(
+        stream: &mut Option<Streaming<TransactionsResponse>>,
+        grpc_url: &str,
+        version: u64,
+    ) -> Vec<Transaction> {
+        loop {
+            match Self::try_stream_batch(stream, grpc_url, version).await {
+                Ok(transactions) => {
+                    FETCHED_TRANSACTION.inc();
+                    return transactions;
+                }
+                Err(err) => {
+                    UNABLE_TO_FETCH_TRANSACTION.inc();
+                    *stream = None;
+                    aptos_logger::error!(
+                        "Could not stream transactions starting at {}, will retry in {}ms. Err: {:?}",
+                        version,
+                        RETRY_TIME_MILLIS,
+                        err
+                    );
+                    tokio::time::sleep(Duration::from_millis(RETRY_TIME_MILLIS)).await;
+                }
             }
         }
-        // At this point we're guaranteed to have something in the buffer
-        let transaction = transactions_buffer.pop().unwrap();
-        self.version += 1;
-        transaction
+    }
+
+    async fn try_stream_batch(
+        stream: &mut Option<Streaming<TransactionsResponse>>,
+        grpc_url: &str,
+        version: u64,
+    ) -> anyhow::Result<Vec<Transaction>> {
+        let stream = match stream {
+            Some(stream) => stream,
+            None => {
+                let mut client = TransactionStreamClient::connect(grpc_url.to_owned()).await?;
+                let request = GetTransactionsRequest {
+                    starting_version: version,
+                    batch_size: TRANSACTION_FETCH_BATCH_SIZE as u32,
+                };
+                stream.insert(client.get_transactions(request).await?.into_inner())
+            }
+        };
+        let response = stream
+            .message()
+            .await?
+            .ok_or_else(|| format_err!("transaction stream ended"))?;
+        response
+            .transactions
+            .into_iter()
+            .zip(version..)
+            .map(|(txn, expected_version)| {
+                ensure!(
+                    txn.version == expected_version,
+                    "streamed version {} != expected version {}",
+                    txn.version,
+                    expected_version
+                );
+                Ok(serde_json::from_str(&txn.json)?)
+            })
+            .collect()
     }
 
     /// fetches one version; this used for error checking/repair/etc
@@ -113,6 +113,13 @@ impl Tailer {
         lowest
     }
 
+    /// Streams the transactions from the gRPC transaction stream of the node, instead of
+    /// polling the REST API.
+    pub async fn set_fetcher_grpc_url(&self, grpc_url: String) {
+        aptos_logger::info!("Will stream transactions from {}", grpc_url);
+        self.transaction_fetcher.lock().await.set_grpc_url(grpc_url);
+    }
+
     pub async fn set_fetcher_version(&self, version: u64) -> u64 {
         self.transaction_fetcher.lock().await.set_version(version);
         aptos_logger::info!("Will start fetching from version {}", version);
@@ -26,6 +26,11 @@ struct IndexerArgs {
     #[clap(long)]
     node_url: String,
 
+    /// URL of the gRPC transaction stream of the node, ex: "http://localhost:50051".
+    /// If set, transactions are streamed from it instead of polled from `--node-url`
+    #[clap(long)]
+    node_grpc_url: Option<String>,
+
     /// If set, don't run any migrations
     #[clap(long)]
     skip_migrations: bool,
@@ -70,6 +75,10 @@ async fn main() -> std::io::Result<()> {
         tailer.run_migrations();
     }
 
+    if let Some(grpc_url) = args.node_grpc_url {
+        tailer.set_fetcher_grpc_url(grpc_url).await;
+    }
+
     let pg_transaction_processor = DefaultTransactionProcessor::new(conn_pool);
     tailer.add_processor(Arc::new(pg_transaction_processor));
 
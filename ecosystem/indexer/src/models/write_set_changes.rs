@@ -85,6 +85,33 @@ impl WriteSetChange {
                 data: serde_json::to_value(data).unwrap(),
                 inserted_at: chrono::Utc::now().naive_utc(),
             },
+            APIWriteSetChange::DeleteRaw {
+                state_key_hash,
+                key,
+            } => WriteSetChange {
+                transaction_hash,
+                hash: state_key_hash.clone(),
+                type_: write_set_change.type_str().to_string(),
+                address: Default::default(),
+                module: Default::default(),
+                resource: Default::default(),
+                data: serde_json::json!({ "key": key }),
+                inserted_at: chrono::Utc::now().naive_utc(),
+            },
+            APIWriteSetChange::WriteRaw {
+                state_key_hash,
+                key,
+                value,
+            } => WriteSetChange {
+                transaction_hash,
+                hash: state_key_hash.clone(),
+                type_: write_set_change.type_str().to_string(),
+                address: Default::default(),
+                module: Default::default(),
+                resource: Default::default(),
+                data: serde_json::json!({ "key": key, "value": value }),
+                inserted_at: chrono::Utc::now().naive_utc(),
+            },
         }
     }
 
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{ensure, format_err, Context, Result};
-use aptos_config::config::{RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_config::config::{RocksdbConfig, DEFAULT_STATE_CACHE_SIZE, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_temppath::TempPath;
 use aptos_types::{transaction::Transaction, waypoint::Waypoint};
 use aptos_vm::AptosVM;
@@ -53,6 +53,8 @@ fn main() -> Result<()> {
             false,
             NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
             RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
         )
     } else {
         // When not committing, we open the DB as secondary so the tool is usable along side a
@@ -62,6 +64,8 @@ fn main() -> Result<()> {
             opt.db_dir.as_path(),
             tmpdir.path(),
             RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
         )
     }
     .with_context(|| format_err!("Failed to open DB."))?;
@@ -6,7 +6,7 @@ use crate::{
     TransactionCommitter,
 };
 use aptos_config::{
-    config::{RocksdbConfig, StoragePrunerConfig},
+    config::{RocksdbConfig, StoragePrunerConfig, DEFAULT_STATE_CACHE_SIZE},
     utils::get_genesis_txn,
 };
 use aptos_jellyfish_merkle::metrics::{
@@ -52,6 +52,8 @@ pub fn run(
             false,                 /* readonly */
             storage_pruner_config, /* pruner */
             RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
         )
         .expect("DB should open."),
     );
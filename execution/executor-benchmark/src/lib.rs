@@ -10,7 +10,9 @@ use crate::{
     transaction_committer::TransactionCommitter, transaction_executor::TransactionExecutor,
     transaction_generator::TransactionGenerator,
 };
-use aptos_config::config::{NodeConfig, RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_config::config::{
+    NodeConfig, RocksdbConfig, DEFAULT_STATE_CACHE_SIZE, NO_OP_STORAGE_PRUNER_CONFIG,
+};
 use aptos_logger::prelude::*;
 
 use aptos_vm::AptosVM;
@@ -31,6 +33,8 @@ pub fn init_db_and_executor(config: &NodeConfig) -> (Arc<dyn DbReader>, BlockExe
             false,                       /* readonly */
             NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
             RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
         )
         .expect("DB should open."),
     );
@@ -59,6 +63,8 @@ pub fn run_benchmark(
         true,                        /* readonly */
         NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
         RocksdbConfig::default(),
+        DEFAULT_STATE_CACHE_SIZE,
+        false, /* enable_event_type_index */
     )
     .expect("db open failure.")
     .create_checkpoint(checkpoint_dir.as_ref().join("aptosdb"))
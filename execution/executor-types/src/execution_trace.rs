@@ -0,0 +1,181 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use crate::ExecutedChunk;
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_types::{
+    contract_event::ContractEvent,
+    state_store::state_key::StateKey,
+    transaction::{TransactionInfo, Version},
+    write_set::{WriteOp, WriteSet},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// The outputs of the transactions of an executed block, dumped to disk to be compared with the
+/// ones of another node when they disagree on the state of the block.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BlockExecutionTrace {
+    pub block_id: HashValue,
+    pub parent_block_id: HashValue,
+    /// The version of the last transaction of the block, if any
+    pub version: Option<Version>,
+    /// The root hash of the transaction accumulator once the block is executed
+    pub root_hash: HashValue,
+    pub state_root: HashValue,
+    pub transactions: Vec<TransactionExecutionTrace>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TransactionExecutionTrace {
+    pub txn_info: TransactionInfo,
+    pub write_set: WriteSet,
+    pub events: Vec<ContractEvent>,
+}
+
+impl BlockExecutionTrace {
+    pub fn new(block_id: HashValue, parent_block_id: HashValue, output: &ExecutedChunk) -> Self {
+        Self {
+            block_id,
+            parent_block_id,
+            version: output.result_view.version(),
+            root_hash: output.result_view.state_id(),
+            state_root: output.result_view.state_root(),
+            transactions: output
+                .to_commit
+                .iter()
+                .map(|(_, txn_data)| TransactionExecutionTrace {
+                    txn_info: txn_data.txn_info.clone(),
+                    write_set: txn_data.write_set().clone(),
+                    events: txn_data.events().to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        Ok(bcs::from_bytes(&fs::read(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        Ok(fs::write(path, bcs::to_bytes(self)?)?)
+    }
+
+    /// Describes the differences with the trace of the same block executed by another node, from
+    /// the first transaction whose output differs. It's empty if the traces are the same.
+    pub fn diff(&self, other: &Self) -> Vec<String> {
+        let mut diffs = vec![];
+        if self.block_id != other.block_id || self.parent_block_id != other.parent_block_id {
+            diffs.push(format!(
+                "blocks: {} (parent {}) != {} (parent {})",
+                self.block_id, self.parent_block_id, other.block_id, other.parent_block_id
+            ));
+            return diffs;
+        }
+        if self.root_hash != other.root_hash {
+            diffs.push(format!(
+                "root hash: {} != {}",
+                self.root_hash, other.root_hash
+            ));
+        }
+        if self.state_root != other.state_root {
+            diffs.push(format!(
+                "state root: {} != {}",
+                self.state_root, other.state_root
+            ));
+        }
+        if self.transactions.len() != other.transactions.len() {
+            diffs.push(format!(
+                "number of transactions: {} != {}",
+                self.transactions.len(),
+                other.transactions.len()
+            ));
+        }
+
+        let first_diff = self
+            .transactions
+            .iter()
+            .zip(&other.transactions)
+            .enumerate()
+            .find(|(_, (txn, other_txn))| txn != other_txn);
+        if let Some((index, (txn, other_txn))) = first_diff {
+            diffs.extend(
+                txn.diff(other_txn)
+                    .into_iter()
+                    .map(|diff| format!("transaction {}: {}", index, diff)),
+            );
+        }
+        diffs
+    }
+}
+
+impl TransactionExecutionTrace {
+    fn diff(&self, other: &Self) -> Vec<String> {
+        let (txn_info, other_txn_info) = (&self.txn_info, &other.txn_info);
+        if txn_info.transaction_hash() != other_txn_info.transaction_hash() {
+            return vec![format!(
+                "transaction hash: {} != {}",
+                txn_info.transaction_hash(),
+                other_txn_info.transaction_hash()
+            )];
+        }
+
+        let mut diffs = vec![];
+        if txn_info.status() != other_txn_info.status() {
+            diffs.push(format!(
+                "status: {:?} != {:?}",
+                txn_info.status(),
+                other_txn_info.status()
+            ));
+        }
+        if txn_info.gas_used() != other_txn_info.gas_used() {
+            diffs.push(format!(
+                "gas used: {} != {}",
+                txn_info.gas_used(),
+                other_txn_info.gas_used()
+            ));
+        }
+
+        let writes: BTreeMap<&StateKey, &WriteOp> =
+            self.write_set.iter().map(|(k, v)| (k, v)).collect();
+        let other_writes: BTreeMap<&StateKey, &WriteOp> =
+            other.write_set.iter().map(|(k, v)| (k, v)).collect();
+        for (state_key, write_op) in &writes {
+            match other_writes.get(state_key) {
+                Some(other_write_op) if other_write_op == write_op => (),
+                other_write_op => diffs.push(format!(
+                    "write to {:?}: {:?} != {:?}",
+                    state_key,
+                    Some(write_op),
+                    other_write_op
+                )),
+            }
+        }
+        for (state_key, other_write_op) in &other_writes {
+            if !writes.contains_key(state_key) {
+                diffs.push(format!(
+                    "write to {:?}: None != {:?}",
+                    state_key,
+                    Some(other_write_op)
+                ));
+            }
+        }
+
+        if self.events.len() != other.events.len() {
+            diffs.push(format!(
+                "number of events: {} != {}",
+                self.events.len(),
+                other.events.len()
+            ));
+        }
+        for (index, (event, other_event)) in self.events.iter().zip(&other.events).enumerate() {
+            if event != other_event {
+                diffs.push(format!("event {}: {:?} != {:?}", index, event, other_event));
+            }
+        }
+        diffs
+    }
+}
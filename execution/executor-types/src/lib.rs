@@ -5,8 +5,10 @@
 
 mod error;
 mod executed_chunk;
+mod execution_trace;
 
 pub use error::Error;
+pub use execution_trace::{BlockExecutionTrace, TransactionExecutionTrace};
 
 use anyhow::Result;
 use aptos_crypto::{
@@ -53,6 +55,19 @@ pub trait ChunkExecutorTrait: Send + Sync {
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
     ) -> Result<()>;
 
+    /// Verifies and executes consecutive chunks in order, like calling `execute_chunk` on each of
+    /// them, except that the transactions of the chunks of an epoch are executed together in
+    /// parallel. Returns the result of each chunk up to, and including, the first failure; the
+    /// chunks after it are not executed.
+    fn execute_chunks(
+        &self,
+        chunks: Vec<(
+            TransactionListWithProof,
+            &LedgerInfoWithSignatures,
+            Option<&LedgerInfoWithSignatures>,
+        )>,
+    ) -> Vec<Result<()>>;
+
     /// Similar to `execute_chunk`, but instead of executing transactions, apply the transaction
     /// outputs directly to get the executed result.
     fn apply_chunk(
@@ -16,6 +16,7 @@ use std::marker::PhantomData;
 
 use crate::{
     components::{block_tree::BlockTree, chunk_output::ChunkOutput},
+    execution_tracer::ExecutionTracer,
     metrics::{
         APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS, APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS,
         APTOS_EXECUTOR_SAVE_TRANSACTIONS_SECONDS, APTOS_EXECUTOR_TRANSACTIONS_SAVED,
@@ -27,6 +28,7 @@ use storage_interface::DbReaderWriter;
 pub struct BlockExecutor<V> {
     pub db: DbReaderWriter,
     block_tree: BlockTree,
+    tracer: Option<ExecutionTracer>,
     phantom: PhantomData<V>,
 }
 
@@ -39,9 +41,17 @@ where
         Self {
             db,
             block_tree,
+            tracer: None,
             phantom: PhantomData,
         }
     }
+
+    /// Dumps the trace of each executed block with the tracer, for the operators to compare it
+    /// with the one of another node when they disagree on the state of the block.
+    pub fn with_tracer(mut self, tracer: ExecutionTracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
 }
 
 impl<V> BlockExecutorTrait for BlockExecutor<V>
@@ -94,7 +104,10 @@ where
             let _timer = APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS.start_timer();
             let state_view = parent_view.state_view(
                 &committed_block.output.result_view,
-                StateViewId::BlockExecution { block_id },
+                StateViewId::BlockExecution {
+                    block_id,
+                    parent_block_id,
+                },
                 self.db.reader.clone(),
             );
 
@@ -110,6 +123,9 @@ where
             chunk_output.trace_log_transaction_status();
 
             let (output, _, _) = chunk_output.apply_to_ledger(parent_accumulator)?;
+            if let Some(tracer) = &self.tracer {
+                tracer.trace_block(block_id, parent_block_id, &output);
+            }
             output
         };
 
@@ -11,19 +11,23 @@ use crate::{
     },
     logging::{LogEntry, LogSchema},
     metrics::{
-        APTOS_EXECUTOR_APPLY_CHUNK_SECONDS, APTOS_EXECUTOR_COMMIT_CHUNK_SECONDS,
+        APTOS_EXECUTOR_APPLY_CHUNK_SECONDS, APTOS_EXECUTOR_CHUNKS_REEXECUTED,
+        APTOS_EXECUTOR_COMMIT_CHUNK_SECONDS, APTOS_EXECUTOR_EXECUTE_CHUNKS_SECONDS,
         APTOS_EXECUTOR_EXECUTE_CHUNK_SECONDS, APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS,
     },
 };
 use anyhow::Result;
+use aptos_crypto::hash::{CryptoHash, TransactionAccumulatorHasher};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_state_view::StateViewId;
 use aptos_types::{
     contract_event::ContractEvent,
     ledger_info::LedgerInfoWithSignatures,
+    proof::accumulator::InMemoryAccumulator,
     transaction::{
-        Transaction, TransactionInfo, TransactionListWithProof, TransactionOutputListWithProof,
+        Transaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
+        TransactionOutputListWithProof, Version,
     },
 };
 use aptos_vm::VMExecutor;
@@ -32,6 +36,16 @@ use fail::fail_point;
 use std::{marker::PhantomData, sync::Arc};
 use storage_interface::{verified_state_view::VerifiedStateView, DbReaderWriter};
 
+/// A chunk verified against the ledger it extends, with the transactions already in the ledger
+/// skipped.
+struct VerifiedChunk<'a> {
+    first_version: Version,
+    transactions: Vec<Transaction>,
+    transaction_infos: Vec<TransactionInfo>,
+    verified_target_li: &'a LedgerInfoWithSignatures,
+    epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
+}
+
 pub struct ChunkExecutor<V> {
     db: DbReaderWriter,
     commit_queue: Mutex<ChunkCommitQueue>,
@@ -89,6 +103,36 @@ impl<V> ChunkExecutor<V> {
         Ok(executed_chunk)
     }
 
+    /// Verifies the transaction list against the target ledger info and the ledger `accumulator`,
+    /// skipping the transactions already in the ledger.
+    fn verify_chunk<'a>(
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: &'a LedgerInfoWithSignatures,
+        epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
+        accumulator: &InMemoryAccumulator<TransactionAccumulatorHasher>,
+    ) -> Result<VerifiedChunk<'a>> {
+        let first_version_in_request = txn_list_with_proof.first_transaction_version;
+        txn_list_with_proof.verify(verified_target_li.ledger_info(), first_version_in_request)?;
+
+        let txns_to_skip = txn_list_with_proof.proof.verify_extends_ledger(
+            accumulator.num_leaves(),
+            accumulator.root_hash(),
+            first_version_in_request,
+        )?;
+        let mut transactions = txn_list_with_proof.transactions;
+        transactions.drain(..txns_to_skip);
+        let mut transaction_infos = txn_list_with_proof.proof.transaction_infos;
+        transaction_infos.drain(..txns_to_skip);
+
+        Ok(VerifiedChunk {
+            first_version: accumulator.num_leaves(),
+            transactions,
+            transaction_infos,
+            verified_target_li,
+            epoch_change_li,
+        })
+    }
+
     fn commit_chunk_impl(&self) -> Result<Arc<ExecutedChunk>> {
         let (base_view, to_commit) = self.commit_queue.lock().next_chunk_to_commit()?;
         let txns_to_commit = to_commit.transactions_to_commit()?;
@@ -168,6 +212,36 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
         Ok(())
     }
 
+    fn execute_chunks(
+        &self,
+        chunks: Vec<(
+            TransactionListWithProof,
+            &LedgerInfoWithSignatures,
+            Option<&LedgerInfoWithSignatures>,
+        )>,
+    ) -> Vec<Result<()>> {
+        let _timer = APTOS_EXECUTOR_EXECUTE_CHUNKS_SECONDS.start_timer();
+
+        // The transactions after a reconfiguration can't be executed in the same block, so the
+        // chunks are executed by epoch.
+        let mut results = Vec::with_capacity(chunks.len());
+        let mut chunks = chunks.into_iter().peekable();
+        while chunks.peek().is_some() {
+            let mut chunks_of_epoch = vec![];
+            for chunk in chunks.by_ref() {
+                let ends_epoch = chunk.2.is_some();
+                chunks_of_epoch.push(chunk);
+                if ends_epoch {
+                    break;
+                }
+            }
+            if !self.execute_chunks_of_epoch(chunks_of_epoch, &mut results) {
+                break;
+            }
+        }
+        results
+    }
+
     fn apply_chunk(
         &self,
         txn_output_list_with_proof: TransactionOutputListWithProof,
@@ -263,7 +337,167 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
     }
 }
 
-impl<V: VMExecutor> ChunkExecutor<V> {}
+impl<V: VMExecutor> ChunkExecutor<V> {
+    /// Executes the transactions of consecutive chunks, which must not cross an epoch boundary
+    /// except at the end of the last chunk, as one block with the parallel executor, then applies
+    /// the outputs of each chunk in order and adds it to the commit queue. A chunk whose outputs
+    /// fail to apply, e.g. if they don't match its transaction infos, is executed again on its
+    /// own. Returns false if a chunk fails.
+    fn execute_chunks_of_epoch(
+        &self,
+        chunks: Vec<(
+            TransactionListWithProof,
+            &LedgerInfoWithSignatures,
+            Option<&LedgerInfoWithSignatures>,
+        )>,
+        results: &mut Vec<Result<()>>,
+    ) -> bool {
+        let (persisted_view, mut latest_view) =
+            self.commit_queue.lock().persisted_and_latest_view();
+
+        // Verify the chunks in order against the ledger they extend, which is known from the
+        // transaction infos before execution.
+        let mut verified_chunks = vec![];
+        let mut verification_error = None;
+        let mut accumulator = Arc::clone(latest_view.txn_accumulator());
+        for (txn_list_with_proof, verified_target_li, epoch_change_li) in chunks {
+            match Self::verify_chunk(
+                txn_list_with_proof,
+                verified_target_li,
+                epoch_change_li,
+                &accumulator,
+            ) {
+                Ok(verified_chunk) => {
+                    let txn_info_hashes = verified_chunk
+                        .transaction_infos
+                        .iter()
+                        .map(CryptoHash::hash)
+                        .collect::<Vec<_>>();
+                    accumulator = Arc::new(accumulator.append(&txn_info_hashes));
+                    verified_chunks.push(verified_chunk);
+                }
+                Err(error) => {
+                    verification_error = Some(error);
+                    break;
+                }
+            }
+        }
+
+        // Execute the transactions of all the chunks together.
+        let transactions = verified_chunks
+            .iter()
+            .flat_map(|chunk| chunk.transactions.iter().cloned())
+            .collect::<Vec<_>>();
+        let num_txns = transactions.len();
+        let outputs = {
+            let _timer = APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS.start_timer();
+            V::execute_block_in_parallel(
+                transactions,
+                &self.state_view(&latest_view, &persisted_view),
+            )
+        };
+        let mut outputs = match outputs {
+            Ok(outputs) if outputs.len() == num_txns => Some(outputs.into_iter()),
+            Ok(outputs) => {
+                warn!(
+                    num_txns = num_txns,
+                    num_outputs = outputs.len(),
+                    "Parallel execution returned a wrong number of outputs.",
+                );
+                None
+            }
+            Err(error) => {
+                warn!(error = ?error, "Parallel execution of chunks failed.");
+                None
+            }
+        };
+
+        // Apply the outputs of each chunk on top of the previous one.
+        for chunk in verified_chunks {
+            let chunk_outputs = outputs
+                .as_mut()
+                .map(|outputs| outputs.by_ref().take(chunk.transactions.len()).collect());
+            match self.execute_verified_chunk(&chunk, chunk_outputs, &persisted_view, &latest_view)
+            {
+                Ok(executed_chunk) => {
+                    latest_view = executed_chunk.result_view.clone();
+                    self.commit_queue.lock().enqueue(executed_chunk);
+                    info!(
+                        LogSchema::new(LogEntry::ChunkExecutor)
+                            .first_version_in_request(chunk.first_version)
+                            .num_txns_in_request(chunk.transactions.len()),
+                        "Executed transaction chunk in parallel!",
+                    );
+                    results.push(Ok(()));
+                }
+                Err(error) => {
+                    results.push(Err(error));
+                    return false;
+                }
+            }
+        }
+
+        match verification_error {
+            Some(error) => {
+                results.push(Err(error));
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Applies the `outputs` of the chunk from its parallel execution, or executes it on its own if
+    /// missing or if they fail to apply.
+    fn execute_verified_chunk(
+        &self,
+        chunk: &VerifiedChunk,
+        outputs: Option<Vec<TransactionOutput>>,
+        persisted_view: &ExecutedTrees,
+        latest_view: &ExecutedTrees,
+    ) -> Result<ExecutedChunk> {
+        if let Some(outputs) = outputs {
+            let txns_and_outputs = chunk.transactions.iter().cloned().zip(outputs).collect();
+            match ChunkOutput::by_transaction_output(
+                txns_and_outputs,
+                self.state_view(latest_view, persisted_view),
+            )
+            .and_then(|chunk_output| {
+                Self::apply_chunk_output_for_state_sync(
+                    chunk.verified_target_li,
+                    chunk.epoch_change_li,
+                    latest_view,
+                    chunk_output,
+                    &chunk.transaction_infos,
+                )
+            }) {
+                Ok(executed_chunk) => return Ok(executed_chunk),
+                Err(error) => {
+                    warn!(
+                        first_version = chunk.first_version,
+                        error = ?error,
+                        "Failed to apply the outputs of the parallel execution, re-executing.",
+                    );
+                    APTOS_EXECUTOR_CHUNKS_REEXECUTED.inc();
+                }
+            }
+        }
+
+        let chunk_output = {
+            let _timer = APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS.start_timer();
+            ChunkOutput::by_transaction_execution::<V>(
+                chunk.transactions.clone(),
+                self.state_view(latest_view, persisted_view),
+            )?
+        };
+        Self::apply_chunk_output_for_state_sync(
+            chunk.verified_target_li,
+            chunk.epoch_change_li,
+            latest_view,
+            chunk_output,
+            &chunk.transaction_infos,
+        )
+    }
+}
 
 impl<V: VMExecutor> TransactionReplayer for ChunkExecutor<V> {
     fn replay(
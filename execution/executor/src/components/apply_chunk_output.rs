@@ -334,13 +334,14 @@ fn ensure_txn_valid_for_vacant_entry(transaction: &Transaction) -> Result<()> {
     // maybe other writeset transactions).
     match transaction {
         Transaction::GenesisTransaction(_) => (),
-        Transaction::BlockMetadata(_) => {
+        Transaction::BlockMetadata(_) | Transaction::BlockMetadataExt(_) => {
             bail!("Write set should be a subset of read set.")
         }
         Transaction::UserTransaction(txn) => match txn.payload() {
             TransactionPayload::ModuleBundle(_)
             | TransactionPayload::Script(_)
-            | TransactionPayload::ScriptFunction(_) => {
+            | TransactionPayload::ScriptFunction(_)
+            | TransactionPayload::ScriptFunctionBatch(_) => {
                 bail!("Write set should be a subset of read set.")
             }
             TransactionPayload::WriteSet(_) => (),
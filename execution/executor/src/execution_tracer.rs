@@ -0,0 +1,174 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use crate::logging::{LogEntry, LogSchema};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use executor_types::{BlockExecutionTrace, ExecutedChunk};
+use std::{
+    collections::{HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::SystemTime,
+};
+
+const TRACE_EXTENSION: &str = "trace";
+
+/// Dumps the trace of each executed block to a dir, in a file named after the block id, keeping
+/// the traces of the last `max_traced_blocks` blocks only. The traces are written by a background
+/// thread, and a block executed again, e.g. after a restart, isn't traced again.
+pub struct ExecutionTracer {
+    // The blocks whose traces are written or about to be.
+    traced_blocks: Arc<Mutex<HashSet<HashValue>>>,
+    sender: Mutex<Option<Sender<BlockExecutionTrace>>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl ExecutionTracer {
+    pub fn new(trace_dir: PathBuf, max_traced_blocks: usize) -> Result<Self> {
+        fs::create_dir_all(&trace_dir)?;
+
+        // The traces dumped before a restart are rotated along with the new ones.
+        let mut traces = vec![];
+        for entry in fs::read_dir(&trace_dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == TRACE_EXTENSION) {
+                let block_id = match path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| HashValue::from_str(stem).ok())
+                {
+                    Some(block_id) => block_id,
+                    None => continue,
+                };
+                let modified = fs::metadata(&path)?
+                    .modified()
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                traces.push((modified, block_id));
+            }
+        }
+        traces.sort();
+        let mut written_blocks: VecDeque<_> =
+            traces.into_iter().map(|(_, block_id)| block_id).collect();
+        let traced_blocks = Arc::new(Mutex::new(written_blocks.iter().copied().collect()));
+        rotate(
+            &trace_dir,
+            max_traced_blocks,
+            &mut written_blocks,
+            &traced_blocks,
+        );
+
+        let (sender, receiver) = channel();
+        let writer = {
+            let traced_blocks = traced_blocks.clone();
+            thread::Builder::new()
+                .name("execution-tracer".to_owned())
+                .spawn(move || {
+                    write_traces(
+                        receiver,
+                        trace_dir,
+                        max_traced_blocks,
+                        written_blocks,
+                        traced_blocks,
+                    )
+                })?
+        };
+        Ok(Self {
+            traced_blocks,
+            sender: Mutex::new(Some(sender)),
+            writer: Some(writer),
+        })
+    }
+
+    /// Dumps the trace of the block, unless it's already traced, on a best effort basis: a
+    /// failure is logged, the execution of the block isn't affected.
+    pub fn trace_block(
+        &self,
+        block_id: HashValue,
+        parent_block_id: HashValue,
+        output: &ExecutedChunk,
+    ) {
+        if !self.traced_blocks.lock().insert(block_id) {
+            return;
+        }
+        let trace = BlockExecutionTrace::new(block_id, parent_block_id, output);
+        if let Some(sender) = &*self.sender.lock() {
+            if sender.send(trace).is_err() {
+                warn!(
+                    LogSchema::new(LogEntry::BlockExecutor).block_id(block_id),
+                    "The execution trace writer is gone, the trace isn't dumped"
+                );
+            }
+        }
+    }
+}
+
+impl Drop for ExecutionTracer {
+    /// Waits for the pending traces to be written.
+    fn drop(&mut self) {
+        self.sender.lock().take();
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                warn!("The execution trace writer panicked");
+            }
+        }
+    }
+}
+
+fn trace_path(trace_dir: &Path, block_id: HashValue) -> PathBuf {
+    trace_dir.join(format!("{}.{}", block_id.to_hex(), TRACE_EXTENSION))
+}
+
+fn write_traces(
+    receiver: Receiver<BlockExecutionTrace>,
+    trace_dir: PathBuf,
+    max_traced_blocks: usize,
+    mut written_blocks: VecDeque<HashValue>,
+    traced_blocks: Arc<Mutex<HashSet<HashValue>>>,
+) {
+    for trace in receiver {
+        let path = trace_path(&trace_dir, trace.block_id);
+        if let Err(e) = trace.write(&path) {
+            warn!(
+                LogSchema::new(LogEntry::BlockExecutor).block_id(trace.block_id),
+                "Failed to dump the execution trace to {:?}: {}", path, e
+            );
+            traced_blocks.lock().remove(&trace.block_id);
+            continue;
+        }
+        written_blocks.push_back(trace.block_id);
+        rotate(
+            &trace_dir,
+            max_traced_blocks,
+            &mut written_blocks,
+            &traced_blocks,
+        );
+    }
+}
+
+fn rotate(
+    trace_dir: &Path,
+    max_traced_blocks: usize,
+    written_blocks: &mut VecDeque<HashValue>,
+    traced_blocks: &Mutex<HashSet<HashValue>>,
+) {
+    while written_blocks.len() > max_traced_blocks {
+        if let Some(block_id) = written_blocks.pop_front() {
+            traced_blocks.lock().remove(&block_id);
+            let path = trace_path(trace_dir, block_id);
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Failed to remove the execution trace {:?}: {}", path, e);
+            }
+        }
+    }
+}
@@ -97,3 +97,21 @@ pub static APTOS_EXECUTOR_TRANSACTIONS_SAVED: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static APTOS_EXECUTOR_EXECUTE_CHUNKS_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "aptos_executor_execute_chunks_seconds",
+        // metric description
+        "The time spent in seconds of executing consecutive chunks in parallel in Aptos executor"
+    )
+    .unwrap()
+});
+
+pub static APTOS_EXECUTOR_CHUNKS_REEXECUTED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_executor_chunks_reexecuted_total",
+        "Cumulative number of chunks re-executed sequentially after their parallel execution failed"
+    )
+    .unwrap()
+});
@@ -377,6 +377,9 @@ fn decode_transaction(txn: &SignedTransaction) -> MockVMTransaction {
             // TODO: we need to migrate Script to ScriptFunction later
             unimplemented!("MockVM does not support script function transaction payload.")
         }
+        TransactionPayload::ScriptFunctionBatch(_) => {
+            unimplemented!("MockVM does not support script function batch transaction payload.")
+        }
         TransactionPayload::WriteSet(_) => {
             // Use WriteSet for reconfig only for testing.
             MockVMTransaction::Reconfiguration
@@ -225,6 +225,95 @@ fn test_executor_execute_and_commit_chunk_restart() {
     }
 }
 
+#[test]
+fn test_executor_execute_chunks() {
+    let first_batch_size = 30;
+    let second_batch_size = 40;
+    let third_batch_size = 20;
+    let overlapping_size = 5;
+
+    let first_batch_start = 1;
+    let second_batch_start = first_batch_start + first_batch_size;
+    let third_batch_start = second_batch_start + second_batch_size - overlapping_size;
+
+    let (chunks, ledger_info) = {
+        tests::create_transaction_chunks(vec![
+            first_batch_start..first_batch_start + first_batch_size,
+            second_batch_start..second_batch_start + second_batch_size,
+            third_batch_start..third_batch_start + third_batch_size,
+        ])
+    };
+
+    let TestExecutor {
+        _path,
+        db,
+        executor,
+    } = TestExecutor::new();
+
+    // Execute all the chunks together, then commit them one by one.
+    let results = executor.execute_chunks(
+        chunks
+            .iter()
+            .map(|chunk| (chunk.clone(), &ledger_info, None))
+            .collect(),
+    );
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(Result::is_ok));
+    for _ in 0..2 {
+        executor.commit_chunk().unwrap();
+        let li = db.reader.get_latest_ledger_info().unwrap();
+        assert_eq!(li.ledger_info().version(), 0);
+    }
+    executor.commit_chunk().unwrap();
+    let li = db.reader.get_latest_ledger_info().unwrap();
+    assert_eq!(li, ledger_info);
+}
+
+#[test]
+fn test_executor_execute_chunks_with_gap() {
+    let first_batch_size = 30;
+    let second_batch_size = 40;
+    let third_batch_size = 20;
+
+    let first_batch_start = 1;
+    let second_batch_start = first_batch_start + first_batch_size;
+    let third_batch_start = second_batch_start + second_batch_size;
+
+    let (chunks, ledger_info) = {
+        tests::create_transaction_chunks(vec![
+            first_batch_start..first_batch_start + first_batch_size,
+            second_batch_start..second_batch_start + second_batch_size,
+            third_batch_start..third_batch_start + third_batch_size,
+        ])
+    };
+
+    let TestExecutor {
+        _path,
+        db,
+        executor,
+    } = TestExecutor::new();
+
+    // The third chunk doesn't extend the first one, so its failure stops the execution.
+    let results = executor.execute_chunks(vec![
+        (chunks[0].clone(), &ledger_info, None),
+        (chunks[2].clone(), &ledger_info, None),
+        (chunks[1].clone(), &ledger_info, None),
+    ]);
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+
+    // Only the first chunk was queued for commit.
+    executor.commit_chunk().unwrap();
+    assert!(executor.commit_chunk().is_err());
+    let (latest_version, _) = db
+        .reader
+        .get_latest_transaction_info_option()
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest_version, first_batch_start + first_batch_size - 1);
+}
+
 #[test]
 fn test_executor_execute_and_commit_chunk_local_result_mismatch() {
     let first_batch_size = 10;
@@ -6,6 +6,7 @@ use crate::{
     chunk_executor::ChunkExecutor,
     components::{apply_chunk_output::IntoLedgerView, chunk_output::ChunkOutput},
     db_bootstrapper::{generate_waypoint, maybe_bootstrap},
+    execution_tracer::ExecutionTracer,
     mock_vm::{
         encode_mint_transaction, encode_reconfiguration_transaction, encode_transfer_transaction,
         MockVM, DISCARD_STATUS, KEEP_STATUS,
@@ -29,7 +30,9 @@ use aptos_types::{
     write_set::{WriteOp, WriteSet, WriteSetMut},
 };
 use aptosdb::AptosDB;
-use executor_types::{BlockExecutorTrait, ChunkExecutorTrait, ExecutedTrees, TransactionReplayer};
+use executor_types::{
+    BlockExecutionTrace, BlockExecutorTrait, ChunkExecutorTrait, ExecutedTrees, TransactionReplayer,
+};
 use proptest::prelude::*;
 use std::collections::BTreeMap;
 use storage_interface::DbReaderWriter;
@@ -151,6 +154,42 @@ fn test_executor_status() {
     );
 }
 
+#[test]
+fn test_execution_trace() {
+    let mut executor = TestExecutor::new();
+    let trace_dir = aptos_temppath::TempPath::new();
+    let tracer = ExecutionTracer::new(trace_dir.path().to_path_buf(), 2).unwrap();
+    executor.executor = BlockExecutor::new(executor.db.clone()).with_tracer(tracer);
+
+    let mut parent_block_id = executor.committed_block_id();
+    for i in 0..3 {
+        parent_block_id = execute_and_commit_block(&executor, parent_block_id, i);
+    }
+    // Dropping the tracer waits for the traces to be written.
+    executor.executor = BlockExecutor::new(executor.db.clone());
+
+    // Only the traces of the last two blocks are kept.
+    let trace_path = |index| {
+        trace_dir
+            .path()
+            .join(format!("{}.trace", gen_block_id(index).to_hex()))
+    };
+    assert!(!trace_path(1).exists());
+    assert!(trace_path(2).exists());
+    let trace = BlockExecutionTrace::read(&trace_path(3)).unwrap();
+    assert_eq!(trace.block_id, gen_block_id(3));
+    assert_eq!(trace.version, Some(3));
+    assert_eq!(trace.transactions.len(), 1);
+    assert!(trace.diff(&trace).is_empty());
+
+    let mut other_trace = trace.clone();
+    other_trace.state_root = HashValue::zero();
+    other_trace.transactions[0].write_set = WriteSet::default();
+    let diffs = trace.diff(&other_trace);
+    assert!(diffs[0].starts_with("state root"));
+    assert!(diffs[1].starts_with("transaction 0: write to"));
+}
+
 #[test]
 fn test_executor_one_block() {
     let executor = TestExecutor::new();
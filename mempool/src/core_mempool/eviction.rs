@@ -0,0 +1,295 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Policies picking the transaction to evict when Mempool is full.
+use crate::core_mempool::{
+    index::{AccountTransactions, ParkingLotIndex, TTLIndex, TTLOrderingKey, TxnPointer},
+    transaction::MempoolTransaction,
+};
+use aptos_config::config::MempoolEvictionPolicyType;
+use aptos_types::account_address::AccountAddress;
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::Duration,
+};
+
+/// Picks the transaction to evict to make room for an incoming "ready" transaction when Mempool
+/// is full; the incoming transaction is rejected when no transaction is picked.
+///
+/// Policies other than the parking lot one only pick the last transaction of a sender, so that
+/// evicting it never makes the other transactions of the sender "non-ready", and never pick a
+/// transaction of the incoming sender. They keep the candidates ordered in their own index, which
+/// the store updates whenever the transactions of a sender change, so that picking one doesn't
+/// scan all the senders.
+pub(crate) trait MempoolEvictionPolicy: Send + Sync {
+    fn select(
+        &self,
+        incoming: &MempoolTransaction,
+        parking_lot: &ParkingLotIndex,
+    ) -> Option<TxnPointer>;
+
+    /// Updates the index of the policy with the transactions of the sender, `None` when the
+    /// sender has no transaction left.
+    fn update(&mut self, address: &AccountAddress, transactions: Option<&AccountTransactions>);
+
+    /// Removes the transactions expired at `now` from the TTL index, the system TTL or the client
+    /// expiration one, and returns them for the store to remove them.
+    fn gc(&mut self, index: &mut TTLIndex, now: Duration) -> Vec<TTLOrderingKey> {
+        index.gc(now)
+    }
+}
+
+pub(crate) fn eviction_policy(
+    policy_type: MempoolEvictionPolicyType,
+) -> Box<dyn MempoolEvictionPolicy> {
+    match policy_type {
+        MempoolEvictionPolicyType::ParkingLot => Box::new(ParkingLotEviction),
+        MempoolEvictionPolicyType::Ttl => Box::new(TtlEviction::default()),
+        MempoolEvictionPolicyType::LowestGasFirst => Box::new(LowestGasFirstEviction::default()),
+        MempoolEvictionPolicyType::FairShare => Box::new(FairShareEviction::default()),
+    }
+}
+
+/// Evicts the last "non-ready" transaction of a random sender in the parking lot.
+pub(crate) struct ParkingLotEviction;
+
+impl MempoolEvictionPolicy for ParkingLotEviction {
+    fn select(
+        &self,
+        _incoming: &MempoolTransaction,
+        parking_lot: &ParkingLotIndex,
+    ) -> Option<TxnPointer> {
+        parking_lot.get_poppable()
+    }
+
+    fn update(&mut self, _address: &AccountAddress, _transactions: Option<&AccountTransactions>) {}
+}
+
+/// Evicts the transaction closest to its system expiration time.
+#[derive(Default)]
+pub(crate) struct TtlEviction(SenderIndex<Duration>);
+
+impl MempoolEvictionPolicy for TtlEviction {
+    fn select(
+        &self,
+        incoming: &MempoolTransaction,
+        _parking_lot: &ParkingLotIndex,
+    ) -> Option<TxnPointer> {
+        self.0
+            .ascending()
+            .find(|(_, pointer)| pointer.0 != incoming.get_sender())
+            .map(|(_, pointer)| pointer)
+    }
+
+    fn update(&mut self, address: &AccountAddress, transactions: Option<&AccountTransactions>) {
+        self.0
+            .update(address, transactions, |txn, _| txn.expiration_time);
+    }
+}
+
+/// Evicts the transaction with the lowest gas ranking score, as long as it is lower than the
+/// one of the incoming transaction.
+#[derive(Default)]
+pub(crate) struct LowestGasFirstEviction(SenderIndex<u64>);
+
+impl MempoolEvictionPolicy for LowestGasFirstEviction {
+    fn select(
+        &self,
+        incoming: &MempoolTransaction,
+        _parking_lot: &ParkingLotIndex,
+    ) -> Option<TxnPointer> {
+        self.0
+            .ascending()
+            .find(|(_, pointer)| pointer.0 != incoming.get_sender())
+            .filter(|(ranking_score, _)| *ranking_score < incoming.ranking_score)
+            .map(|(_, pointer)| pointer)
+    }
+
+    fn update(&mut self, address: &AccountAddress, transactions: Option<&AccountTransactions>) {
+        self.0
+            .update(address, transactions, |txn, _| txn.ranking_score);
+    }
+}
+
+/// Evicts the last transaction of the sender with the most transactions, as long as it has
+/// more transactions than the incoming sender would have.
+#[derive(Default)]
+pub(crate) struct FairShareEviction(SenderIndex<usize>);
+
+impl MempoolEvictionPolicy for FairShareEviction {
+    fn select(
+        &self,
+        incoming: &MempoolTransaction,
+        _parking_lot: &ParkingLotIndex,
+    ) -> Option<TxnPointer> {
+        let incoming_share = self.0.key(&incoming.get_sender()).unwrap_or(0) + 1;
+        self.0
+            .descending()
+            .find(|(_, pointer)| pointer.0 != incoming.get_sender())
+            .filter(|(len, _)| *len > incoming_share)
+            .map(|(_, pointer)| pointer)
+    }
+
+    fn update(&mut self, address: &AccountAddress, transactions: Option<&AccountTransactions>) {
+        self.0.update(address, transactions, |_, txns| txns.len());
+    }
+}
+
+/// The last transaction, i.e. the one with the highest sequence number, of each sender, ordered
+/// by the key of the policy.
+#[derive(Default)]
+struct SenderIndex<K> {
+    keys: HashMap<AccountAddress, (K, u64)>,
+    ordered: BTreeSet<(K, AccountAddress, u64)>,
+}
+
+impl<K: Copy + Ord> SenderIndex<K> {
+    fn update(
+        &mut self,
+        address: &AccountAddress,
+        transactions: Option<&AccountTransactions>,
+        key: impl Fn(&MempoolTransaction, &AccountTransactions) -> K,
+    ) {
+        if let Some((key, sequence_number)) = self.keys.remove(address) {
+            self.ordered.remove(&(key, *address, sequence_number));
+        }
+        let last = transactions.and_then(|txns| txns.values().next_back().map(|txn| (txn, txns)));
+        if let Some((txn, txns)) = last {
+            let key = key(txn, txns);
+            let sequence_number = txn.sequence_info.transaction_sequence_number;
+            self.keys.insert(*address, (key, sequence_number));
+            self.ordered.insert((key, *address, sequence_number));
+        }
+    }
+
+    fn key(&self, address: &AccountAddress) -> Option<K> {
+        self.keys.get(address).map(|(key, _)| *key)
+    }
+
+    fn ascending(&self) -> impl Iterator<Item = (K, TxnPointer)> + '_ {
+        self.ordered
+            .iter()
+            .map(|(key, address, sequence_number)| (*key, (*address, *sequence_number)))
+    }
+
+    fn descending(&self) -> impl Iterator<Item = (K, TxnPointer)> + '_ {
+        self.ordered
+            .iter()
+            .rev()
+            .map(|(key, address, sequence_number)| (*key, (*address, *sequence_number)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FairShareEviction, LowestGasFirstEviction, MempoolEvictionPolicy, TtlEviction};
+    use crate::{
+        core_mempool::{
+            index::{AccountTransactions, ParkingLotIndex},
+            transaction::{MempoolTransaction, TimelineState},
+        },
+        tests::common::TestTransaction,
+    };
+    use aptos_types::{account_address::AccountAddress, account_config::AccountSequenceInfo};
+    use std::{collections::HashMap, time::Duration};
+
+    fn txn(
+        sender: usize,
+        sequence_number: u64,
+        gas_price: u64,
+        expiration_secs: u64,
+    ) -> MempoolTransaction {
+        MempoolTransaction::new(
+            TestTransaction::new(sender, sequence_number, gas_price).make_signed_transaction(),
+            Duration::from_secs(expiration_secs),
+            0,
+            gas_price,
+            TimelineState::NotReady,
+            AccountSequenceInfo::Sequential(0),
+        )
+    }
+
+    fn store(
+        policy: &mut dyn MempoolEvictionPolicy,
+        txns: Vec<MempoolTransaction>,
+    ) -> HashMap<AccountAddress, AccountTransactions> {
+        let mut store: HashMap<_, AccountTransactions> = HashMap::new();
+        for txn in txns {
+            let address = txn.get_sender();
+            store
+                .entry(address)
+                .or_default()
+                .insert(txn.sequence_info.transaction_sequence_number, txn);
+            policy.update(&address, store.get(&address));
+        }
+        store
+    }
+
+    #[test]
+    fn test_ttl_eviction() {
+        let mut policy = TtlEviction::default();
+        store(
+            &mut policy,
+            vec![txn(1, 0, 1, 30), txn(1, 1, 1, 40), txn(2, 0, 1, 35)],
+        );
+        assert_eq!(
+            policy.select(&txn(3, 0, 1, 50), &ParkingLotIndex::new()),
+            Some((TestTransaction::get_address(2), 0))
+        );
+        // the sender of the candidate has no transaction left
+        policy.update(&TestTransaction::get_address(2), None);
+        assert_eq!(
+            policy.select(&txn(3, 0, 1, 50), &ParkingLotIndex::new()),
+            Some((TestTransaction::get_address(1), 1))
+        );
+    }
+
+    #[test]
+    fn test_lowest_gas_first_eviction() {
+        let mut policy = LowestGasFirstEviction::default();
+        store(&mut policy, vec![txn(1, 0, 5, 30), txn(2, 0, 3, 30)]);
+        let parking_lot = ParkingLotIndex::new();
+        assert_eq!(
+            policy.select(&txn(3, 0, 4, 30), &parking_lot),
+            Some((TestTransaction::get_address(2), 0))
+        );
+        // the incoming transaction doesn't pay more than any other
+        assert_eq!(policy.select(&txn(3, 0, 3, 30), &parking_lot), None);
+        // the transactions of the incoming sender are never picked
+        assert_eq!(policy.select(&txn(2, 1, 4, 30), &parking_lot), None);
+    }
+
+    #[test]
+    fn test_fair_share_eviction() {
+        let mut policy = FairShareEviction::default();
+        let mut transactions = store(
+            &mut policy,
+            vec![
+                txn(1, 0, 1, 30),
+                txn(1, 1, 1, 30),
+                txn(1, 2, 1, 30),
+                txn(2, 0, 1, 30),
+                txn(2, 1, 1, 30),
+            ],
+        );
+        let parking_lot = ParkingLotIndex::new();
+        assert_eq!(
+            policy.select(&txn(3, 0, 1, 30), &parking_lot),
+            Some((TestTransaction::get_address(1), 2))
+        );
+        // the incoming sender would have as many transactions as the heaviest sender
+        assert_eq!(policy.select(&txn(2, 2, 1, 30), &parking_lot), None);
+
+        // the evicted transaction isn't picked again
+        let address = TestTransaction::get_address(1);
+        let txns = transactions.get_mut(&address).unwrap();
+        txns.remove(&2);
+        policy.update(&address, Some(txns));
+        assert_eq!(
+            policy
+                .select(&txn(3, 0, 1, 30), &parking_lot)
+                .map(|(_, sequence_number)| sequence_number),
+            Some(1)
+        );
+    }
+}
@@ -12,8 +12,7 @@ use aptos_types::account_address::AccountAddress;
 use rand::seq::SliceRandom;
 use std::{
     cmp::Ordering,
-    collections::{btree_set::Iter, BTreeMap, BTreeSet, HashMap},
-    iter::Rev,
+    collections::{BTreeMap, BTreeSet, HashMap},
     ops::Bound,
     time::Duration,
 };
@@ -23,32 +22,52 @@ pub type AccountTransactions = BTreeMap<u64, MempoolTransaction>;
 /// PriorityIndex represents the main Priority Queue in Mempool.
 /// It's used to form the transaction block for Consensus.
 /// Transactions are ordered by gas price. Second level ordering is done by expiration time.
+/// The queue is split into lanes by the configured gas unit price bounds, each lane being a queue
+/// of its own, and the transactions of a higher lane come before all the transactions of the
+/// lower lanes. Without bounds there's a single lane.
 ///
 /// We don't store the full content of transactions in the index.
 /// Instead we use `OrderedQueueKey` - logical reference to the transaction in the main store.
 pub struct PriorityIndex {
-    data: BTreeSet<OrderedQueueKey>,
+    // the queues of the lanes, from the lowest lane to the highest
+    lanes: Vec<BTreeSet<OrderedQueueKey>>,
+    // the min gas unit prices of the lanes above the lowest lane, in ascending order
+    lane_bounds: Vec<u64>,
 }
 
-pub type PriorityQueueIter<'a> = Rev<Iter<'a, OrderedQueueKey>>;
+pub type PriorityQueueIter<'a> = Box<dyn Iterator<Item = &'a OrderedQueueKey> + 'a>;
 
 impl PriorityIndex {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(gas_price_lanes: &[u64]) -> Self {
+        let mut lane_bounds = gas_price_lanes.to_vec();
+        lane_bounds.sort_unstable();
+        lane_bounds.dedup();
         Self {
-            data: BTreeSet::new(),
+            lanes: vec![BTreeSet::new(); lane_bounds.len() + 1],
+            lane_bounds,
         }
     }
 
     pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
-        self.data.insert(self.make_key(txn));
+        let lane = self.lane(txn.get_gas_price());
+        self.lanes[lane].insert(self.make_key(txn));
     }
 
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
-        self.data.remove(&self.make_key(txn));
+        let lane = self.lane(txn.get_gas_price());
+        self.lanes[lane].remove(&self.make_key(txn));
     }
 
     pub(crate) fn contains(&self, txn: &MempoolTransaction) -> bool {
-        self.data.contains(&self.make_key(txn))
+        self.lanes[self.lane(txn.get_gas_price())].contains(&self.make_key(txn))
+    }
+
+    /// The lane of the gas unit price, 0 being the lowest lane.
+    fn lane(&self, gas_unit_price: u64) -> usize {
+        self.lane_bounds
+            .iter()
+            .take_while(|bound| **bound <= gas_unit_price)
+            .count()
     }
 
     fn make_key(&self, txn: &MempoolTransaction) -> OrderedQueueKey {
@@ -61,11 +80,16 @@ impl PriorityIndex {
     }
 
     pub(crate) fn iter(&self) -> PriorityQueueIter {
-        self.data.iter().rev()
+        Box::new(self.lanes.iter().rev().flat_map(|lane| lane.iter().rev()))
     }
 
     pub(crate) fn size(&self) -> usize {
-        self.data.len()
+        self.lanes.iter().map(BTreeSet::len).sum()
+    }
+
+    /// The number of transactions in each lane, from the lowest lane to the highest.
+    pub(crate) fn lane_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.lanes.iter().map(BTreeSet::len)
     }
 }
 
@@ -345,6 +369,14 @@ impl ParkingLotIndex {
     pub(crate) fn size(&self) -> usize {
         self.size
     }
+
+    /// Returns the accounts with "non-ready" transactions and their number of such
+    /// transactions.
+    pub(crate) fn accounts(&self) -> impl Iterator<Item = (AccountAddress, usize)> + '_ {
+        self.data
+            .iter()
+            .map(|(account, txns)| (*account, txns.len()))
+    }
 }
 
 /// Logical pointer to `MempoolTransaction`.
@@ -12,6 +12,7 @@ use crate::{
     },
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
+    MempoolInfo,
 };
 use aptos_config::config::NodeConfig;
 use aptos_crypto::HashValue;
@@ -38,6 +39,8 @@ pub struct Mempool {
     // takes to pick it up by consensus.
     pub(crate) metrics_cache: TtlCache<(AccountAddress, u64), SystemTime>,
     pub system_transaction_timeout: Duration,
+    // transactions with a lower gas unit price are not admitted
+    min_gas_unit_price: u64,
 }
 
 impl Mempool {
@@ -49,6 +52,7 @@ impl Mempool {
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
+            min_gas_unit_price: config.mempool.min_gas_unit_price,
         }
     }
 
@@ -119,12 +123,27 @@ impl Mempool {
         }
     }
 
+    /// Returns the time the transaction was inserted in Mempool, unless it was received
+    /// without being qualified for broadcast or is no longer tracked.
+    pub(crate) fn insertion_time(
+        &self,
+        account: AccountAddress,
+        sequence_number: u64,
+    ) -> Option<SystemTime> {
+        self.metrics_cache.get(&(account, sequence_number)).cloned()
+    }
+
     pub(crate) fn get_by_hash(&self, hash: HashValue) -> Option<SignedTransaction> {
         self.transactions.get_by_hash(hash)
     }
 
+    /// Returns the transactions of the account in mempool, ordered by sequence number.
+    pub(crate) fn get_by_account(&self, address: &AccountAddress) -> Vec<SignedTransaction> {
+        self.transactions.get_by_account(address)
+    }
+
     /// Used to add a transaction to the Mempool.
-    /// Performs basic validation: checks account's sequence number.
+    /// Performs basic validation: checks account's sequence number and the min gas unit price.
     pub(crate) fn add_txn(
         &mut self,
         txn: SignedTransaction,
@@ -139,6 +158,13 @@ impl Mempool {
                 .txns(TxnsLog::new_txn(txn.sender(), txn.sequence_number())),
             committed_seq_number = db_sequence_number
         );
+        if txn.gas_unit_price() < self.min_gas_unit_price {
+            return MempoolStatus::new(MempoolStatusCode::GasPriceTooLow).with_message(format!(
+                "transaction gas unit price is {}, min gas unit price is {}",
+                txn.gas_unit_price(),
+                self.min_gas_unit_price,
+            ));
+        }
         let cached_value = self.sequence_number_cache.get(&txn.sender());
         let sequence_number = match crsn_or_seqno {
             AccountSequenceInfo::CRSN { .. } => crsn_or_seqno,
@@ -179,15 +205,42 @@ impl Mempool {
 
     /// Fetches next block of transactions for consensus.
     /// `batch_size` - size of requested block.
+    /// `max_bytes` - max total size of the transactions in the block.
+    /// `max_gas` - max total gas of the transactions in the block, estimated by the max gas
+    ///  amounts of the transactions.
     /// `seen_txns` - transactions that were sent to Consensus but were not committed yet,
     ///  mempool should filter out such transactions.
+    /// The block is cut before the first transaction exceeding the limits, except that the first
+    /// transaction of the block is always included, so that one exceeding the limits on its own is
+    /// still proposed.
     #[allow(clippy::explicit_counter_loop)]
     pub(crate) fn get_block(
         &self,
         batch_size: u64,
+        max_bytes: u64,
+        max_gas: u64,
         mut seen: HashSet<TxnPointer>,
     ) -> Vec<SignedTransaction> {
         let mut result = vec![];
+        let mut block_bytes = 0u64;
+        let mut block_gas = 0u64;
+        // Accounts for a transaction in the block, returning false if it doesn't fit
+        let mut fits_in_block = |(address, tx_seq): TxnPointer, block_len: usize| {
+            let (bytes, gas) = self
+                .transactions
+                .get_bytes_and_gas(&address, tx_seq)
+                .unwrap_or((0, 0));
+            let (new_bytes, new_gas) = (
+                block_bytes.saturating_add(bytes),
+                block_gas.saturating_add(gas),
+            );
+            if block_len > 0 && (new_bytes > max_bytes || new_gas > max_gas) {
+                return false;
+            }
+            block_bytes = new_bytes;
+            block_gas = new_gas;
+            true
+        };
         // Helper DS. Helps to mitigate scenarios where account submits several transactions
         // with increasing gas price (e.g. user submits transactions with sequence number 1, 2
         // and gas_price 1, 10 respectively)
@@ -215,6 +268,9 @@ impl Mempool {
                 || matches!(account_seqtype, AccountSequenceInfo::CRSN { .. })
             {
                 let ptr = TxnPointer::from(txn);
+                if !fits_in_block(ptr, result.len()) {
+                    break;
+                }
                 seen.insert(ptr);
                 result.push(ptr);
                 if (result.len() as u64) == batch_size {
@@ -225,6 +281,9 @@ impl Mempool {
                 // that were skipped before for given account
                 let mut skipped_txn = (txn.address, tx_seq + 1);
                 while skipped.contains(&skipped_txn) {
+                    if !fits_in_block(skipped_txn, result.len()) {
+                        break 'main;
+                    }
                     seen.insert(skipped_txn);
                     result.push(skipped_txn);
                     if (result.len() as u64) == batch_size {
@@ -253,7 +312,9 @@ impl Mempool {
             walked = txn_walked,
             seen_after = seen.len(),
             result_size = result_size,
-            block_size = block.len()
+            block_size = block.len(),
+            block_bytes = block_bytes,
+            block_gas = block_gas
         );
         for transaction in &block {
             self.log_latency(
@@ -296,6 +357,41 @@ impl Mempool {
         self.transactions.timeline_range(start_id, end_id)
     }
 
+    /// Returns the occupancy of mempool, with the `max_accounts` accounts having the most
+    /// transactions in the parking lot.
+    pub(crate) fn info(&self, max_accounts: usize) -> MempoolInfo {
+        self.transactions.info(max_accounts)
+    }
+
+    /// Returns the pointer of the transaction of the hash when it is in the parking lot.
+    pub(crate) fn get_parked_by_hash(&self, hash: HashValue) -> Option<TxnPointer> {
+        self.transactions.get_parked_by_hash(hash)
+    }
+
+    /// Returns the first sequence number of the sender from `account_sequence_number` which is
+    /// not in mempool, as long as it is lower than `sequence_number`.
+    pub(crate) fn first_missing_sequence_number(
+        &self,
+        sender: &AccountAddress,
+        account_sequence_number: u64,
+        sequence_number: u64,
+    ) -> Option<u64> {
+        self.transactions.first_missing_sequence_number(
+            sender,
+            account_sequence_number,
+            sequence_number,
+        )
+    }
+
+    pub(crate) fn is_saturated(&self, threshold_percent: u64) -> bool {
+        self.transactions.is_saturated(threshold_percent)
+    }
+
+    /// Returns all the transactions in mempool, ordered by sequence number for each account.
+    pub(crate) fn all_transactions(&self) -> Vec<SignedTransaction> {
+        self.transactions.all_transactions()
+    }
+
     pub fn gen_snapshot(&self) -> TxnsLog {
         self.transactions.gen_snapshot(&self.metrics_cache)
     }
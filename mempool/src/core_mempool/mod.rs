@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod eviction;
 mod index;
 mod mempool;
 mod transaction;
@@ -15,6 +15,8 @@ pub struct MempoolTransaction {
     // System expiration time of the transaction. It should be removed from mempool by that time.
     pub expiration_time: Duration,
     pub gas_amount: u64,
+    // Size of the signed transaction, as it's sent in the blocks.
+    pub bytes_len: u64,
     pub ranking_score: u64,
     pub timeline_state: TimelineState,
     pub sequence_info: SequenceInfo,
@@ -34,6 +36,7 @@ impl MempoolTransaction {
                 transaction_sequence_number: txn.sequence_number(),
                 account_sequence_number_type: seqno_type,
             },
+            bytes_len: txn.txn_bytes_len() as u64,
             txn,
             expiration_time,
             gas_amount,
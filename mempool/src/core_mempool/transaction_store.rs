@@ -3,15 +3,17 @@
 
 use crate::{
     core_mempool::{
+        eviction::{eviction_policy, MempoolEvictionPolicy},
         index::{
             AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
-            TimelineIndex,
+            TimelineIndex, TxnPointer,
         },
         transaction::{MempoolTransaction, TimelineState},
         ttl_cache::TtlCache,
     },
     counters,
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
+    MempoolInfo,
 };
 use aptos_config::config::MempoolConfig;
 use aptos_crypto::HashValue;
@@ -54,7 +56,10 @@ pub struct TransactionStore {
 
     // configuration
     capacity: usize,
-    capacity_per_user: usize,
+    max_transactions_per_sender: usize,
+    max_bytes_per_sender: usize,
+    replace_by_fee_min_bump_percent: u64,
+    eviction_policy: Box<dyn MempoolEvictionPolicy>,
 }
 
 impl TransactionStore {
@@ -68,14 +73,17 @@ impl TransactionStore {
             expiration_time_index: TTLIndex::new(Box::new(|t: &MempoolTransaction| {
                 Duration::from_secs(t.txn.expiration_timestamp_secs())
             })),
-            priority_index: PriorityIndex::new(),
+            priority_index: PriorityIndex::new(&config.gas_price_lanes),
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
             hash_index: HashMap::new(),
 
             // configuration
             capacity: config.capacity,
-            capacity_per_user: config.capacity_per_user,
+            max_transactions_per_sender: config.max_transactions_per_sender,
+            max_bytes_per_sender: config.max_bytes_per_sender,
+            replace_by_fee_min_bump_percent: config.replace_by_fee_min_bump_percent,
+            eviction_policy: eviction_policy(config.eviction_policy),
         }
     }
 
@@ -95,6 +103,19 @@ impl TransactionStore {
         None
     }
 
+    /// Fetch the size in bytes and the max gas amount of a transaction by account address +
+    /// sequence_number.
+    pub(crate) fn get_bytes_and_gas(
+        &self,
+        address: &AccountAddress,
+        sequence_number: u64,
+    ) -> Option<(u64, u64)> {
+        self.transactions
+            .get(address)
+            .and_then(|txns| txns.get(&sequence_number))
+            .map(|txn| (txn.bytes_len, txn.gas_amount))
+    }
+
     pub(crate) fn get_by_hash(&self, hash: HashValue) -> Option<SignedTransaction> {
         match self.hash_index.get(&hash) {
             Some((address, seq)) => self.get(address, *seq),
@@ -102,6 +123,31 @@ impl TransactionStore {
         }
     }
 
+    pub(crate) fn get_parked_by_hash(&self, hash: HashValue) -> Option<TxnPointer> {
+        self.hash_index
+            .get(&hash)
+            .filter(|(address, seq)| self.parking_lot_index.contains(address, seq))
+            .copied()
+    }
+
+    pub(crate) fn first_missing_sequence_number(
+        &self,
+        address: &AccountAddress,
+        account_sequence_number: u64,
+        sequence_number: u64,
+    ) -> Option<u64> {
+        let txns = self.transactions.get(address)?;
+        (account_sequence_number..sequence_number).find(|seq| !txns.contains_key(seq))
+    }
+
+    /// Fetch all transactions of the account, ordered by sequence number.
+    pub(crate) fn get_by_account(&self, address: &AccountAddress) -> Vec<SignedTransaction> {
+        self.transactions
+            .get(address)
+            .map(|txns| txns.values().map(|txn| txn.txn.clone()).collect())
+            .unwrap_or_default()
+    }
+
     /// Fetch mempool transaction by account address + sequence_number.
     pub(crate) fn get_mempool_txn(
         &self,
@@ -121,35 +167,47 @@ impl TransactionStore {
 
         // check if transaction is already present in Mempool
         // e.g. given request is update
-        // we allow increase in gas price to speed up process.
+        // we allow increase in gas price to speed up process, and replacing the transaction by
+        // any transaction paying enough more per gas unit (replace-by-fee).
         // ignores the case transaction hash is same for retrying submit transaction.
-        if let Some(txns) = self.transactions.get_mut(&address) {
-            if let Some(current_version) =
-                txns.get_mut(&sequence_number.transaction_sequence_number)
-            {
+        // The transaction replaced is only removed once its replacement passes all the checks.
+        let min_bump_percent = self.replace_by_fee_min_bump_percent;
+        let mut replaced_bytes = None;
+        if let Some(txns) = self.transactions.get(&address) {
+            if let Some(current_version) = txns.get(&sequence_number.transaction_sequence_number) {
                 if current_version.txn == txn.txn {
                     return MempoolStatus::new(MempoolStatusCode::Accepted);
                 }
-                if current_version.txn.max_gas_amount() == txn.txn.max_gas_amount()
+                let is_gas_price_update = current_version.txn.max_gas_amount()
+                    == txn.txn.max_gas_amount()
                     && current_version.txn.payload() == txn.txn.payload()
                     && current_version.txn.expiration_timestamp_secs()
-                        == txn.txn.expiration_timestamp_secs()
-                    && current_version.get_gas_price() < txn.get_gas_price()
+                        == txn.txn.expiration_timestamp_secs();
+                if current_version.get_gas_price() < txn.get_gas_price()
+                    && (is_gas_price_update || is_fee_bump(current_version, &txn, min_bump_percent))
                 {
-                    if let Some(txn) = txns.remove(&txn.sequence_info.transaction_sequence_number) {
-                        self.index_remove(&txn);
-                    }
+                    replaced_bytes = Some(current_version.txn.raw_txn_bytes_len());
                 } else {
-                    return MempoolStatus::new(MempoolStatusCode::InvalidUpdate)
-                        .with_message("Transaction already in mempool".to_string());
+                    return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
+                        format!(
+                            "Transaction already in mempool, a replacement requires a gas unit \
+                             price at least {}% higher than {}",
+                            min_bump_percent,
+                            current_version.get_gas_price(),
+                        ),
+                    );
                 }
             }
         }
 
-        if self.check_is_full_after_eviction(
-            &txn,
-            sequence_number.account_sequence_number_type.min_seq(),
-        ) {
+        // a replacement takes the place of the transaction it replaces
+        let is_replacement = replaced_bytes.is_some();
+        if !is_replacement
+            && self.check_is_full_after_eviction(
+                &txn,
+                sequence_number.account_sequence_number_type.min_seq(),
+            )
+        {
             return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
                 "mempool size: {}, capacity: {}",
                 self.system_ttl_index.size(),
@@ -166,18 +224,44 @@ impl TransactionStore {
             sequence_number.account_sequence_number_type.min_seq(),
         );
 
-        if let Some(txns) = self.transactions.get_mut(&address) {
+        if let Some(txns) = self.transactions.get(&address) {
             // capacity check
-            if txns.len() >= self.capacity_per_user {
+            if !is_replacement && txns.len() >= self.max_transactions_per_sender {
                 return MempoolStatus::new(MempoolStatusCode::TooManyTransactions).with_message(
                     format!(
-                        "txns length: {} capacity per user: {}",
+                        "txns length: {} max transactions per sender: {}",
                         txns.len(),
-                        self.capacity_per_user,
+                        self.max_transactions_per_sender,
                     ),
                 );
             }
+            let sender_bytes: usize = txns
+                .values()
+                .map(|txn| txn.txn.raw_txn_bytes_len())
+                .sum::<usize>()
+                .saturating_sub(replaced_bytes.unwrap_or(0));
+            if sender_bytes + txn.txn.raw_txn_bytes_len() > self.max_bytes_per_sender {
+                return MempoolStatus::new(MempoolStatusCode::TooManyBytes).with_message(format!(
+                    "txns bytes: {} txn bytes: {} max bytes per sender: {}",
+                    sender_bytes,
+                    txn.txn.raw_txn_bytes_len(),
+                    self.max_bytes_per_sender,
+                ));
+            }
+        }
+
+        if is_replacement {
+            // the replacement gets a new timeline id, so it's broadcast again
+            let replaced = self
+                .transactions
+                .get_mut(&address)
+                .and_then(|txns| txns.remove(&sequence_number.transaction_sequence_number));
+            if let Some(replaced) = replaced {
+                self.index_remove(&replaced);
+            }
+        }
 
+        if let Some(txns) = self.transactions.get_mut(&address) {
             // insert into storage and other indexes
             self.system_ttl_index.insert(&txn);
             self.expiration_time_index.insert(&txn);
@@ -191,6 +275,7 @@ impl TransactionStore {
             txns.insert(sequence_number.transaction_sequence_number, txn);
             self.track_indices();
         }
+        self.update_eviction_index(&address);
         self.process_ready_transactions(&address, sequence_number.account_sequence_number_type);
         MempoolStatus::new(MempoolStatusCode::Accepted)
     }
@@ -208,6 +293,9 @@ impl TransactionStore {
             counters::PRIORITY_INDEX_LABEL,
             self.priority_index.size(),
         );
+        for (lane, size) in self.priority_index.lane_sizes().enumerate() {
+            counters::core_mempool_lane_size(lane, size);
+        }
         counters::core_mempool_index_size(
             counters::PARKING_LOT_INDEX_LABEL,
             self.parking_lot_index.size(),
@@ -233,8 +321,10 @@ impl TransactionStore {
         if self.system_ttl_index.size() >= self.capacity
             && self.check_txn_ready(txn, curr_sequence_number)
         {
-            // try to free some space in Mempool from ParkingLot by evicting a non-ready txn
-            if let Some((address, sequence_number)) = self.parking_lot_index.get_poppable() {
+            // try to free some space in Mempool by evicting the txn picked by the eviction policy
+            if let Some((address, sequence_number)) =
+                self.eviction_policy.select(txn, &self.parking_lot_index)
+            {
                 if let Some(txn) = self
                     .transactions
                     .get_mut(&address)
@@ -247,12 +337,18 @@ impl TransactionStore {
                         ))
                     );
                     self.index_remove(&txn);
+                    self.update_eviction_index(&address);
                 }
             }
         }
         self.system_ttl_index.size() >= self.capacity
     }
 
+    /// Whether mempool is filled to at least `threshold_percent` of its capacity.
+    pub(crate) fn is_saturated(&self, threshold_percent: u64) -> bool {
+        self.system_ttl_index.size() as u64 * 100 >= self.capacity as u64 * threshold_percent
+    }
+
     /// Check if a transaction would be ready for broadcast in mempool upon insertion (without inserting it).
     /// Two ways this can happen:
     /// 1. txn sequence number == curr_sequence_number
@@ -366,6 +462,7 @@ impl TransactionStore {
                 );
                 self.index_remove(transaction);
             }
+            self.update_eviction_index(address);
             trace!(
                 LogSchema::new(LogEntry::CleanCommittedTxn).txns(rm_txns),
                 "txns cleaned with committing tx {}:{}",
@@ -397,6 +494,7 @@ impl TransactionStore {
                 );
                 self.index_remove(transaction);
             }
+            self.update_eviction_index(account);
             debug!(LogSchema::new(LogEntry::CleanRejectedTxn).txns(txns_log));
         }
     }
@@ -413,6 +511,12 @@ impl TransactionStore {
         self.track_indices();
     }
 
+    /// Updates the index of the eviction policy once the transactions of the sender changed.
+    fn update_eviction_index(&mut self, address: &AccountAddress) {
+        self.eviction_policy
+            .update(address, self.transactions.get(address));
+    }
+
     /// Read `count` transactions from timeline since `timeline_id`.
     /// Returns block of transactions and new last_timeline_id.
     pub(crate) fn read_timeline(
@@ -492,7 +596,7 @@ impl TransactionStore {
             .with_label_values(&[metric_label])
             .inc();
 
-        let mut gc_txns = index.gc(now);
+        let mut gc_txns = self.eviction_policy.gc(index, now);
         // sort the expired txns by order of sequence number per account
         gc_txns.sort_by_key(|key| (key.address, key.sequence_number));
         let mut gc_iter = gc_txns.iter().peekable();
@@ -534,6 +638,7 @@ impl TransactionStore {
 
                     // remove txn
                     self.index_remove(&txn);
+                    self.update_eviction_index(&account);
                 }
             }
         }
@@ -542,6 +647,27 @@ impl TransactionStore {
         self.track_indices();
     }
 
+    /// Returns the occupancy of the store, with the `max_accounts` accounts having the most
+    /// transactions in the parking lot.
+    pub(crate) fn info(&self, max_accounts: usize) -> MempoolInfo {
+        let mut parking_lot_accounts: Vec<_> = self.parking_lot_index.accounts().collect();
+        parking_lot_accounts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        parking_lot_accounts.truncate(max_accounts);
+        MempoolInfo {
+            size: self.system_ttl_index.size(),
+            priority_queue_size: self.priority_index.size(),
+            parking_lot_size: self.parking_lot_index.size(),
+            parking_lot_accounts,
+        }
+    }
+
+    pub(crate) fn all_transactions(&self) -> Vec<SignedTransaction> {
+        self.transactions
+            .values()
+            .flat_map(|txns| txns.values().map(|txn| txn.txn.clone()))
+            .collect()
+    }
+
     pub(crate) fn iter_queue(&self) -> PriorityQueueIter {
         self.priority_index.iter()
     }
@@ -570,3 +696,15 @@ impl TransactionStore {
         self.parking_lot_index.size()
     }
 }
+
+/// Whether the gas unit price of `new` is higher than the one of `current` by at least
+/// `min_bump_percent`.
+fn is_fee_bump(
+    current: &MempoolTransaction,
+    new: &MempoolTransaction,
+    min_bump_percent: u64,
+) -> bool {
+    let current_price = current.get_gas_price() as u128;
+    let new_price = new.get_gas_price() as u128;
+    new_price * 100 >= current_price * (100 + min_bump_percent as u128)
+}
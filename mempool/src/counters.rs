@@ -52,6 +52,9 @@ pub const SUCCESS_LABEL: &str = "success";
 // Bounded executor task labels
 pub const CLIENT_EVENT_LABEL: &str = "client_event";
 pub const CLIENT_EVENT_GET_TXN_LABEL: &str = "client_event_get_txn";
+pub const CLIENT_EVENT_GET_ACCOUNT_TXNS_LABEL: &str = "client_event_get_account_txns";
+pub const CLIENT_EVENT_GET_MEMPOOL_INFO_LABEL: &str = "client_event_get_mempool_info";
+pub const CLIENT_EVENT_GET_WAITING_FOR_SEQ_NUM_LABEL: &str = "client_event_get_waiting_for_seq_num";
 pub const RECONFIG_EVENT_LABEL: &str = "reconfig";
 pub const PEER_BROADCAST_EVENT_LABEL: &str = "peer_broadcast";
 
@@ -62,6 +65,7 @@ pub const START_LABEL: &str = "start";
 // Mempool network msg failure type labels:
 pub const BROADCAST_TXNS: &str = "broadcast_txns";
 pub const ACK_TXNS: &str = "ack_txns";
+pub const BACKOFF_TXNS: &str = "backoff_txns";
 
 // Broadcast/ACK type labels
 pub const EXPIRED_BROADCAST_LABEL: &str = "expired";
@@ -92,6 +96,22 @@ pub fn core_mempool_index_size(label: &'static str, size: usize) {
         .set(size as i64)
 }
 
+/// Counter tracking size of the gas price lanes of the priority index
+static CORE_MEMPOOL_LANE_SIZE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "core_mempool_lane_size",
+        "Size of a gas price lane of the core mempool priority index",
+        &["lane"]
+    )
+    .unwrap()
+});
+
+pub fn core_mempool_lane_size(lane: usize, size: usize) {
+    CORE_MEMPOOL_LANE_SIZE
+        .with_label_values(&[&lane.to_string()])
+        .set(size as i64)
+}
+
 /// Counter tracking number of txns removed from core mempool
 pub static CORE_MEMPOOL_REMOVED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -112,6 +132,29 @@ pub static CORE_MEMPOOL_TXN_COMMIT_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Buckets (in seconds) of the broadcast latency, which is longer than the default buckets under
+/// congestion; insertion times are only kept for 100 seconds
+const TXN_BROADCAST_LATENCY_BUCKETS: &[f64] = &[
+    0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 40.0, 60.0, 80.0, 100.0,
+];
+
+/// Counter tracking latency from a txn entering core mempool to its first broadcast to a peer
+static SHARED_MEMPOOL_TXN_BROADCAST_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "shared_mempool_txn_broadcast_latency",
+        "Latency of txn being broadcast to a peer after insertion in core mempool",
+        &["network"],
+        TXN_BROADCAST_LATENCY_BUCKETS.to_vec()
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_txn_broadcast_latency(network_id: NetworkId, latency: Duration) {
+    SHARED_MEMPOOL_TXN_BROADCAST_LATENCY
+        .with_label_values(&[network_id.as_str()])
+        .observe(latency.as_secs_f64());
+}
+
 /// Counter for number of periodic garbage-collection (=GC) events that happen, regardless of
 /// how many txns were actually cleaned up in this GC event
 pub static CORE_MEMPOOL_GC_EVENT_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -281,6 +324,26 @@ pub fn shared_mempool_pending_broadcasts(peer: &PeerNetworkId) -> IntGauge {
     ])
 }
 
+/// Counter tracking the backoff messages sent to and received from each peer
+static SHARED_MEMPOOL_BACKOFF_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "shared_mempool_backoff_count",
+        "Number of backoff messages sent to or received from a peer",
+        &["network", "peer", "direction"]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_backoff_inc(peer: &PeerNetworkId, direction: &str) {
+    SHARED_MEMPOOL_BACKOFF_COUNT
+        .with_label_values(&[
+            peer.network_id().as_str(),
+            peer.peer_id().short_str().as_str(),
+            direction,
+        ])
+        .inc();
+}
+
 static SHARED_MEMPOOL_TRANSACTIONS_PROCESSED: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "shared_mempool_transactions_processed",
@@ -299,6 +362,25 @@ pub fn shared_mempool_transactions_processed_inc(status: &str, network: &str) {
         .inc();
 }
 
+/// Counter for number of transactions rejected by mempool, by the reason of the rejection
+static MEMPOOL_REJECTED_TRANSACTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "mempool_rejected_transactions_count",
+        "Number of transactions rejected by mempool",
+        &[
+            "reason",  // MempoolStatusCode of the rejection, or "vm_validation"
+            "network", // network the txn was received from, or "client"
+        ]
+    )
+    .unwrap()
+});
+
+pub fn mempool_rejected_transactions_inc(reason: &str, network: &str) {
+    MEMPOOL_REJECTED_TRANSACTIONS
+        .with_label_values(&[reason, network])
+        .inc();
+}
+
 /// Counter for number of transactions in each mempool broadcast sent
 static SHARED_MEMPOOL_TRANSACTION_BROADCAST_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
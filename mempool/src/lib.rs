@@ -9,9 +9,12 @@
 //! executed.
 //!
 //! **Flow**: AC sends transactions into mempool which holds them for a period of time before
-//! sending them into consensus.  When a new transaction is added, Mempool shares this transaction
-//! with other nodes in the system.  This is a form of “shared mempool” in that transactions between
-//! mempools are shared with other validators.  This helps maintain a pseudo global ordering since
+//! sending them into consensus. Incoming transactions first go through the `TransactionFilter`s
+//! of the node (the one of `transaction_filter` in the config, then the ones passed to
+//! `bootstrap_with_transaction_filters`), before being validated by the VM.  When a new
+//! transaction is added, Mempool shares this transaction with other nodes in the system.  This is
+//! a form of “shared mempool” in that transactions between mempools are shared with other
+//! validators.  This helps maintain a pseudo global ordering since
 //! when a validator receives a transaction from another mempool, it will be ordered when added in
 //! the ordered queue of the recipient validator. To reduce network consumption, in “shared mempool”
 //! each validator is responsible for delivery of its own transactions (we don't rebroadcast
@@ -35,9 +38,11 @@
 //! with various indexes built on top of it. The main index `PriorityIndex` is an ordered queue of
 //! transactions that are “ready” to be included in next block(i.e. have sequence number sequential
 //! to current for account). This queue is ordered by gas price so that if a client is willing to
-//! pay more (than other clients) per unit of execution, then they can enter consensus earlier. Note
-//! that although global ordering is maintained by gas price, for a single account, transactions are
-//! ordered by sequence number.
+//! pay more (than other clients) per unit of execution, then they can enter consensus earlier. The
+//! queue can be split into lanes by the configured gas unit price bounds (`gas_price_lanes`), each
+//! lane being a queue of its own, the transactions of a higher lane being pulled before all the
+//! transactions of the lower lanes. Note that although global ordering is maintained by gas price, for a single account, transactions are
+//! ordered by sequence number. Transactions below `min_gas_unit_price` are not admitted.
 //!
 //! All transactions that are not ready to be included in the next block are part of separate
 //! `ParkingLotIndex`. They will be moved to the ordered queue once some event unblocks them. For
@@ -58,10 +63,11 @@
 #[cfg(any(test, feature = "fuzzing"))]
 mod tests;
 pub use shared_mempool::{
-    bootstrap, network,
+    bootstrap, bootstrap_with_transaction_filters, network,
+    transaction_filter::TransactionFilter,
     types::{
         ConsensusRequest, ConsensusResponse, MempoolClientRequest, MempoolClientSender,
-        MempoolEventsReceiver, SubmissionStatus, TransactionSummary,
+        MempoolEventsReceiver, MempoolInfo, SubmissionStatus, TransactionSummary,
     },
 };
 #[cfg(any(test, feature = "fuzzing"))]
@@ -130,12 +130,16 @@ pub enum LogEntry {
     ReconfigUpdate,
     JsonRpc,
     GetTransaction,
+    GetMempoolInfo,
+    GetWaitingForSequenceNumber,
     GetBlock,
     Consensus,
     StateSyncCommit,
     BroadcastTransaction,
     BroadcastACK,
     ReceiveACK,
+    BroadcastBackoff,
+    ReceiveBackoff,
     InvariantViolated,
     AddTxn,
     RemoveTxn,
@@ -147,6 +151,7 @@ pub enum LogEntry {
     DBError,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
+    TransactionLog,
 }
 
 #[derive(Clone, Copy, Serialize)]
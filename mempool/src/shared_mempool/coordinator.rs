@@ -8,6 +8,7 @@ use crate::{
     logging::{LogEntry, LogEvent, LogSchema},
     network::{MempoolNetworkEvents, MempoolSyncMsg},
     shared_mempool::{
+        persistence::TransactionLog,
         tasks,
         tasks::process_committed_transactions,
         types::{notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification},
@@ -147,6 +148,59 @@ async fn handle_client_request<V>(
                 ))
                 .await;
         }
+        MempoolClientRequest::GetAccountTransactions(address, callback) => {
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_ACCOUNT_TXNS_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_ACCOUNT_TXNS_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_account_transactions(
+                    smp.clone(),
+                    address,
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
+        MempoolClientRequest::GetMempoolInfo(callback) => {
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_MEMPOOL_INFO_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_MEMPOOL_INFO_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_mempool_info(
+                    smp.clone(),
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
+        MempoolClientRequest::GetWaitingForSequenceNumber(hash, callback) => {
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_WAITING_FOR_SEQ_NUM_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_WAITING_FOR_SEQ_NUM_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_waiting_for_sequence_number(
+                    smp.clone(),
+                    hash,
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
     }
 }
 
@@ -312,6 +366,16 @@ async fn handle_network_event<V>(
                         ack_timestamp,
                     );
                 }
+                MempoolSyncMsg::BroadcastBackoff {
+                    request_id,
+                    duration_ms,
+                } => {
+                    smp.network_interface.process_broadcast_backoff(
+                        PeerNetworkId::new(network_id, peer_id),
+                        request_id,
+                        Duration::from_millis(duration_ms),
+                    );
+                }
             }
         }
         Event::RpcRequest(peer_id, _msg, _, _res_tx) => {
@@ -326,7 +390,11 @@ async fn handle_network_event<V>(
 }
 
 /// Garbage collect all expired transactions by SystemTTL.
-pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval_ms: u64) {
+pub(crate) async fn gc_coordinator(
+    mempool: Arc<Mutex<CoreMempool>>,
+    gc_interval_ms: u64,
+    transaction_log: Option<Arc<Mutex<TransactionLog>>>,
+) {
     info!(LogSchema::event_log(LogEntry::GCRuntime, LogEvent::Start));
     let mut interval = IntervalStream::new(interval(Duration::from_millis(gc_interval_ms)));
     while let Some(_interval) = interval.next().await {
@@ -335,6 +403,19 @@ pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval
             info!(LogSchema::event_log(LogEntry::GCRuntime, LogEvent::Live))
         );
         mempool.lock().gc();
+        if let Some(transaction_log) = &transaction_log {
+            // the compaction writes to disk, so it's kept off the threads of the runtime
+            let (mempool, transaction_log) = (mempool.clone(), transaction_log.clone());
+            let result = tokio::task::spawn_blocking(move || {
+                compact_transaction_log(&mempool, &transaction_log)
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+            if let Err(e) = result {
+                error!(LogSchema::new(LogEntry::TransactionLog).error(&e));
+            }
+        }
     }
 
     error!(LogSchema::event_log(
@@ -343,6 +424,22 @@ pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval
     ));
 }
 
+/// Rewrites the transaction log with the transactions in mempool, once the log holds at least as
+/// many records of the transactions gone from mempool as of the ones still in it.
+fn compact_transaction_log(
+    mempool: &Mutex<CoreMempool>,
+    transaction_log: &Mutex<TransactionLog>,
+) -> anyhow::Result<()> {
+    // the log is locked while reading mempool, so that no transaction accepted in the
+    // meantime is dropped from the log by the rewrite
+    let mut transaction_log = transaction_log.lock();
+    if !transaction_log.needs_compaction(mempool.lock().info(0).size) {
+        return Ok(());
+    }
+    let txns = mempool.lock().all_transactions();
+    transaction_log.rewrite(&txns)
+}
+
 /// Periodically logs a snapshot of transactions in core mempool.
 /// In the future we may want an interactive way to directly query mempool's internal state.
 /// For now, we will rely on this periodic snapshot to observe the internal state.
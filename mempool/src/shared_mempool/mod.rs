@@ -2,10 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod network;
+mod persistence;
 mod runtime;
 pub(crate) mod types;
-pub use runtime::bootstrap;
 #[cfg(any(test, feature = "fuzzing"))]
 pub(crate) use runtime::start_shared_mempool;
+pub use runtime::{bootstrap, bootstrap_with_transaction_filters};
 mod coordinator;
 pub(crate) mod tasks;
+pub(crate) mod transaction_filter;
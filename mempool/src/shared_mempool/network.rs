@@ -68,6 +68,15 @@ pub enum MempoolSyncMsg {
         /// A backpressure signal from the recipient when it is overwhelmed (e.g., mempool is full).
         backoff: bool,
     },
+    /// Backpressure signal issued instead of an ack by a receiver too saturated to process the
+    /// broadcast (e.g., mempool is nearly full). The sender pauses broadcasting to the receiver
+    /// for `duration_ms`, then resends the broadcast.
+    /// It's only sent on `ProtocolId::MempoolBackoffDirectSend`, to the peers advertising the
+    /// protocol at handshake, as the older peers can't decode it.
+    BroadcastBackoff {
+        request_id: Vec<u8>,
+        duration_ms: u64,
+    },
 }
 
 /// The interface from Network to Mempool layer.
@@ -93,7 +102,10 @@ pub struct MempoolNetworkSender {
 
 pub fn network_endpoint_config(max_broadcasts_per_peer: usize) -> AppConfig {
     AppConfig::p2p(
-        [ProtocolId::MempoolDirectSend],
+        [
+            ProtocolId::MempoolDirectSend,
+            ProtocolId::MempoolBackoffDirectSend,
+        ],
         aptos_channel::Config::new(max_broadcasts_per_peer)
             .queue_style(QueueStyle::KLAST)
             .counters(&counters::PENDING_MEMPOOL_NETWORK_EVENTS),
@@ -117,7 +129,10 @@ impl ApplicationNetworkSender<MempoolSyncMsg> for MempoolNetworkSender {
         fail_point!("mempool::send_to", |_| {
             Err(anyhow::anyhow!("Injected error in mempool::send_to").into())
         });
-        let protocol = ProtocolId::MempoolDirectSend;
+        let protocol = match message {
+            MempoolSyncMsg::BroadcastBackoff { .. } => ProtocolId::MempoolBackoffDirectSend,
+            _ => ProtocolId::MempoolDirectSend,
+        };
         self.inner.send_to(recipient, protocol, message)
     }
 
@@ -149,6 +164,8 @@ pub enum BroadcastError {
     PeerNotPrioritized(PeerNetworkId, usize),
     #[error("Peer {0} not scheduled for backoff")]
     PeerNotScheduled(PeerNetworkId),
+    #[error("Peer {0} asked to pause broadcasts")]
+    PeerPaused(PeerNetworkId),
     #[error("Peer {0} is over the limit for pending broadcasts")]
     TooManyPendingBroadcasts(PeerNetworkId),
 }
@@ -324,6 +341,74 @@ impl MempoolNetworkInterface {
         }
     }
 
+    /// Whether `peer` can decode the backoff messages, i.e. advertised their protocol at handshake
+    pub fn supports_broadcast_backoff(&self, peer: &PeerNetworkId) -> bool {
+        self.peer_metadata_storage
+            .read(*peer)
+            .map_or(false, |info| {
+                info.supports_protocol(ProtocolId::MempoolBackoffDirectSend)
+            })
+    }
+
+    /// Pauses broadcasting to `peer` for `duration`, up to the configured max, and schedules the
+    /// batch it didn't process for a resend.
+    pub fn process_broadcast_backoff(
+        &self,
+        peer: PeerNetworkId,
+        request_id_bytes: Vec<u8>,
+        duration: Duration,
+    ) {
+        let batch_id = if let Ok(id) = bcs::from_bytes::<BatchId>(&request_id_bytes) {
+            id
+        } else {
+            counters::invalid_ack_inc(peer.network_id(), counters::INVALID_REQUEST_ID);
+            return;
+        };
+
+        let mut sync_states = self.sync_states.write_lock();
+        let sync_state = if let Some(state) = sync_states.get_mut(&peer) {
+            state
+        } else {
+            counters::invalid_ack_inc(peer.network_id(), counters::UNKNOWN_PEER);
+            return;
+        };
+
+        debug!(
+            LogSchema::new(LogEntry::ReceiveBackoff)
+                .peer(&peer)
+                .batch_id(&batch_id),
+            duration_ms = duration.as_millis() as u64,
+        );
+        counters::shared_mempool_backoff_inc(&peer, counters::RECEIVED_LABEL);
+
+        if sync_state
+            .broadcast_info
+            .sent_batches
+            .remove(&batch_id)
+            .is_some()
+        {
+            counters::shared_mempool_pending_broadcasts(&peer).dec();
+            sync_state.broadcast_info.retry_batches.insert(batch_id);
+        }
+        // The duration is sent by the peer, it can't be trusted to fit in an `Instant`.
+        let duration = std::cmp::min(
+            duration,
+            Duration::from_millis(self.mempool_config.shared_mempool_max_backoff_interval_ms),
+        );
+        if let Some(paused_until) = Instant::now().checked_add(duration) {
+            sync_state.broadcast_info.paused_until =
+                std::cmp::max(sync_state.broadcast_info.paused_until, Some(paused_until));
+        }
+    }
+
+    /// The time until which `peer` asked to not be broadcast to, if any.
+    pub fn paused_until(&self, peer: &PeerNetworkId) -> Option<Instant> {
+        self.sync_states
+            .read(peer)
+            .and_then(|state| state.broadcast_info.paused_until)
+            .filter(|paused_until| *paused_until > Instant::now())
+    }
+
     pub fn is_backoff_mode(&self, peer: &PeerNetworkId) -> bool {
         if let Some(state) = self.sync_states.write_lock().get(peer) {
             state.broadcast_info.backoff_mode
@@ -379,6 +464,13 @@ impl MempoolNetworkInterface {
             return Err(BroadcastError::PeerNotScheduled(peer));
         }
 
+        // Honor the pause asked by the peer, including for broadcasts scheduled before it.
+        if let Some(paused_until) = state.broadcast_info.paused_until {
+            if Instant::now() < paused_until {
+                return Err(BroadcastError::PeerPaused(peer));
+            }
+        }
+
         // Sync peer's pending broadcasts with latest mempool state.
         // A pending broadcast might become empty if the corresponding txns were committed through
         // another peer, so don't track broadcasts for committed txns.
@@ -438,6 +530,19 @@ impl MempoolNetworkInterface {
                         state.timeline_id,
                         self.mempool_config.shared_mempool_batch_size,
                     );
+                    // A txn is in only one fresh broadcast to a peer, so retries are not observed.
+                    let now = SystemTime::now();
+                    for txn in txns.iter() {
+                        let latency = mempool
+                            .insertion_time(txn.sender(), txn.sequence_number())
+                            .and_then(|insertion_time| now.duration_since(insertion_time).ok());
+                        if let Some(latency) = latency {
+                            counters::shared_mempool_txn_broadcast_latency(
+                                peer.network_id(),
+                                latency,
+                            );
+                        }
+                    }
                     (BatchId(state.timeline_id, new_timeline_id), txns, None)
                 }
             };
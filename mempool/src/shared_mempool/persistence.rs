@@ -0,0 +1,211 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Append log of the transactions accepted by mempool, replayed at startup so that a restart
+//! doesn't drop the transactions waiting in mempool.
+//!
+//! The log is a sequence of records, each being a BCS serialized `SignedTransaction` prefixed
+//! with its length in 4 bytes little-endian. The log only grows between compactions, which
+//! rewrite it with the transactions currently in mempool once the records of the transactions
+//! gone from mempool make up half of the log.
+use anyhow::Result;
+use aptos_types::transaction::SignedTransaction;
+use std::{
+    convert::TryInto,
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+
+pub(crate) struct TransactionLog {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    // the number of records in the log
+    records: usize,
+}
+
+impl TransactionLog {
+    /// Opens the log at `path`, creating it when it doesn't exist, and returns the logged
+    /// transactions. A truncated or corrupted tail, e.g. of a crash in the middle of a write,
+    /// is ignored.
+    pub fn open(path: &Path) -> Result<(Self, Vec<SignedTransaction>)> {
+        let txns = match File::open(path) {
+            Ok(mut file) => {
+                let mut bytes = vec![];
+                file.read_to_end(&mut bytes)?;
+                decode_records(&bytes)
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e.into()),
+        };
+        let log = Self {
+            path: path.to_path_buf(),
+            writer: open_for_append(path)?,
+            records: txns.len(),
+        };
+        Ok((log, txns))
+    }
+
+    pub fn append(&mut self, txns: &[SignedTransaction]) -> Result<()> {
+        for txn in txns {
+            write_record(&mut self.writer, txn)?;
+        }
+        self.writer.flush()?;
+        self.records += txns.len();
+        Ok(())
+    }
+
+    /// Whether the log should be rewritten, holding at least as many records of the transactions
+    /// gone from mempool as of the `live` ones in mempool.
+    pub fn needs_compaction(&self, live: usize) -> bool {
+        self.records > 2 * live
+    }
+
+    /// Replaces the content of the log with `txns`; the log is written to a temporary file
+    /// first, so a crash while rewriting leaves the previous log intact.
+    pub fn rewrite(&mut self, txns: &[SignedTransaction]) -> Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        for txn in txns {
+            write_record(&mut writer, txn)?;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.writer = open_for_append(&self.path)?;
+        self.records = txns.len();
+        Ok(())
+    }
+}
+
+fn open_for_append(path: &Path) -> Result<BufWriter<File>> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(BufWriter::new(file))
+}
+
+fn write_record(writer: &mut impl Write, txn: &SignedTransaction) -> Result<()> {
+    let bytes = bcs::to_bytes(txn)?;
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+fn decode_records(mut bytes: &[u8]) -> Vec<SignedTransaction> {
+    let mut txns = vec![];
+    while bytes.len() >= 4 {
+        let len = u32::from_le_bytes(bytes[..4].try_into().unwrap()) as usize;
+        let record = match bytes.get(4..4 + len) {
+            Some(record) => record,
+            None => break,
+        };
+        match bcs::from_bytes(record) {
+            Ok(txn) => txns.push(txn),
+            Err(_) => break,
+        }
+        bytes = &bytes[4 + len..];
+    }
+    txns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransactionLog;
+    use crate::{
+        core_mempool::CoreMempool,
+        shared_mempool::{runtime::replay_transaction_log, types::SharedMempool},
+        tests::common::TestTransaction,
+    };
+    use aptos_config::{config::NodeConfig, network_id::NetworkId};
+    use aptos_infallible::{Mutex, RwLock};
+    use aptos_temppath::TempPath;
+    use network::application::storage::PeerMetadataStorage;
+    use std::{collections::HashMap, fs::OpenOptions, io::Write, sync::Arc};
+    use storage_interface::mock::MockDbReaderWriter;
+    use vm_validator::mocks::mock_vm_validator::MockVMValidator;
+
+    #[test]
+    fn test_append_and_replay() {
+        let path = TempPath::new();
+        let txns: Vec<_> = (0..3)
+            .map(|seq| TestTransaction::new(0, seq, 1).make_signed_transaction())
+            .collect();
+
+        let (mut log, replayed) = TransactionLog::open(path.path()).unwrap();
+        assert!(replayed.is_empty());
+        log.append(&txns[..2]).unwrap();
+        log.append(&txns[2..]).unwrap();
+        drop(log);
+
+        let (_, replayed) = TransactionLog::open(path.path()).unwrap();
+        assert_eq!(replayed, txns);
+    }
+
+    #[test]
+    fn test_ignore_truncated_tail() {
+        let path = TempPath::new();
+        let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+        let (mut log, _) = TransactionLog::open(path.path()).unwrap();
+        log.append(&[txn.clone()]).unwrap();
+        drop(log);
+
+        // a record whose length is larger than the remaining bytes
+        let mut file = OpenOptions::new().append(true).open(path.path()).unwrap();
+        file.write_all(&[100, 0, 0, 0, 1, 2]).unwrap();
+        drop(file);
+
+        let (_, replayed) = TransactionLog::open(path.path()).unwrap();
+        assert_eq!(replayed, vec![txn]);
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let path = TempPath::new();
+        let txns: Vec<_> = (0..3)
+            .map(|seq| TestTransaction::new(0, seq, 1).make_signed_transaction())
+            .collect();
+        let (mut log, _) = TransactionLog::open(path.path()).unwrap();
+        log.append(&txns).unwrap();
+        assert!(log.needs_compaction(1));
+        log.rewrite(&txns[1..2]).unwrap();
+        log.append(&txns[2..]).unwrap();
+        assert!(!log.needs_compaction(1));
+        drop(log);
+
+        let (_, replayed) = TransactionLog::open(path.path()).unwrap();
+        assert_eq!(replayed, txns[1..].to_vec());
+    }
+
+    #[test]
+    fn test_replay_into_mempool() {
+        let path = TempPath::new();
+        let txns: Vec<_> = (0..3)
+            .map(|seq| TestTransaction::new(0, seq, 1).make_signed_transaction())
+            .collect();
+        let (mut log, _) = TransactionLog::open(path.path()).unwrap();
+        log.append(&txns).unwrap();
+        // a transaction logged twice, e.g. when it's submitted again
+        log.append(&txns[..1]).unwrap();
+        drop(log);
+
+        let config = NodeConfig::default();
+        let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
+        let smp = SharedMempool::new(
+            mempool.clone(),
+            config.mempool.clone(),
+            HashMap::new(),
+            Arc::new(MockDbReaderWriter),
+            Arc::new(RwLock::new(MockVMValidator)),
+            vec![],
+            config.base.role,
+            PeerMetadataStorage::new(&[NetworkId::Validator]),
+        );
+        drop(replay_transaction_log(&smp, path.path()));
+        assert_eq!(mempool.lock().all_transactions(), txns);
+
+        // the log is compacted to the transactions accepted by mempool
+        let (_, replayed) = TransactionLog::open(path.path()).unwrap();
+        assert_eq!(replayed, txns);
+    }
+}
@@ -2,22 +2,31 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::CoreMempool,
+    core_mempool::{CoreMempool, TimelineState},
+    logging::{LogEntry, LogSchema},
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator, snapshot_job},
+        persistence::TransactionLog,
+        tasks,
+        transaction_filter::TransactionFilter,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     ConsensusRequest,
 };
 use aptos_config::{config::NodeConfig, network_id::NetworkId};
 use aptos_infallible::{Mutex, RwLock};
+use aptos_logger::prelude::*;
 
 use event_notifications::ReconfigNotificationListener;
 use futures::channel::mpsc::{self, Receiver, UnboundedSender};
 use mempool_notifications::MempoolNotificationListener;
 use network::application::storage::PeerMetadataStorage;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
 use storage_interface::DbReader;
 use tokio::runtime::{Builder, Handle, Runtime};
 use vm_validator::vm_validator::{TransactionValidation, VMValidator};
@@ -26,7 +35,8 @@ use vm_validator::vm_validator::{TransactionValidation, VMValidator};
 /// Creates a separate Tokio Runtime that runs the following routines:
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers).
 ///   - inbound_network_task (task that handles inbound mempool messages and network events).
-///   - gc_task (task that performs GC of all expired transactions by SystemTTL).
+///   - gc_task (task that performs GC of all expired transactions by SystemTTL, and compacts the
+///     transaction log when persistence is enabled).
 pub(crate) fn start_shared_mempool<V>(
     executor: &Handle,
     config: &NodeConfig,
@@ -42,6 +52,8 @@ pub(crate) fn start_shared_mempool<V>(
     validator: Arc<RwLock<V>>,
     subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
+    // Filters run after the one of the config.
+    transaction_filters: Vec<Arc<dyn TransactionFilter>>,
 ) where
     V: TransactionValidation + 'static,
 {
@@ -52,7 +64,7 @@ pub(crate) fn start_shared_mempool<V>(
         network_senders.insert(network_id, network_sender);
     }
 
-    let smp = SharedMempool::new(
+    let mut smp = SharedMempool::new(
         mempool.clone(),
         config.mempool.clone(),
         network_senders,
@@ -62,6 +74,11 @@ pub(crate) fn start_shared_mempool<V>(
         config.base.role,
         peer_metadata_storage,
     );
+    smp.transaction_filters.extend(transaction_filters);
+    if let Some(path) = &config.mempool.persistence_path {
+        smp.transaction_log = Some(replay_transaction_log(&smp, path));
+    }
+    let transaction_log = smp.transaction_log.clone();
 
     executor.spawn(coordinator(
         smp,
@@ -76,6 +93,7 @@ pub(crate) fn start_shared_mempool<V>(
     executor.spawn(gc_coordinator(
         mempool.clone(),
         config.mempool.system_transaction_gc_interval_ms,
+        transaction_log,
     ));
 
     executor.spawn(snapshot_job(
@@ -84,6 +102,39 @@ pub(crate) fn start_shared_mempool<V>(
     ));
 }
 
+/// Re-validates and adds to mempool the transactions of the log at `path`, which is then
+/// rewritten with the accepted ones, e.g. without the transactions committed before the restart.
+pub(crate) fn replay_transaction_log<V>(
+    smp: &SharedMempool<V>,
+    path: &Path,
+) -> Arc<Mutex<TransactionLog>>
+where
+    V: TransactionValidation + 'static,
+{
+    let (mut transaction_log, mut txns) =
+        TransactionLog::open(path).expect("[shared mempool] failed to open transaction log");
+    let replayed = txns.len();
+    // a transaction is logged again when it's submitted again
+    let mut logged = HashSet::new();
+    txns.retain(|txn| logged.insert(txn.clone()));
+    let accepted = if txns.is_empty() {
+        vec![]
+    } else {
+        let statuses = tasks::process_incoming_transactions(smp, txns, TimelineState::NotReady);
+        tasks::accepted_transactions(&statuses)
+    };
+    info!(
+        LogSchema::new(LogEntry::TransactionLog),
+        replayed = replayed,
+        accepted = accepted.len(),
+        "replayed mempool transaction log"
+    );
+    if let Err(e) = transaction_log.rewrite(&accepted) {
+        error!(LogSchema::new(LogEntry::TransactionLog).error(&e));
+    }
+    Arc::new(Mutex::new(transaction_log))
+}
+
 pub fn bootstrap(
     config: &NodeConfig,
     db: Arc<dyn DbReader>,
@@ -95,6 +146,32 @@ pub fn bootstrap(
     mempool_listener: MempoolNotificationListener,
     mempool_reconfig_events: ReconfigNotificationListener,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
+) -> Runtime {
+    bootstrap_with_transaction_filters(
+        config,
+        db,
+        mempool_network_handles,
+        client_events,
+        consensus_requests,
+        mempool_listener,
+        mempool_reconfig_events,
+        peer_metadata_storage,
+        vec![],
+    )
+}
+
+/// Bootstraps shared mempool like `bootstrap`, with custom filters which all incoming
+/// transactions go through after the filter of the config, ahead of VM validation.
+pub fn bootstrap_with_transaction_filters(
+    config: &NodeConfig,
+    db: Arc<dyn DbReader>,
+    mempool_network_handles: Vec<(NetworkId, MempoolNetworkSender, MempoolNetworkEvents)>,
+    client_events: MempoolEventsReceiver,
+    consensus_requests: Receiver<ConsensusRequest>,
+    mempool_listener: MempoolNotificationListener,
+    mempool_reconfig_events: ReconfigNotificationListener,
+    peer_metadata_storage: Arc<PeerMetadataStorage>,
+    transaction_filters: Vec<Arc<dyn TransactionFilter>>,
 ) -> Runtime {
     let runtime = Builder::new_multi_thread()
         .thread_name("shared-mem")
@@ -116,6 +193,7 @@ pub fn bootstrap(
         vm_validator,
         vec![],
         peer_metadata_storage,
+        transaction_filters,
     );
     runtime
 }
@@ -11,7 +11,7 @@ use crate::{
         notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
         SubmissionStatusBundle, TransactionSummary,
     },
-    ConsensusRequest, ConsensusResponse, SubmissionStatus,
+    ConsensusRequest, ConsensusResponse, MempoolInfo, SubmissionStatus,
 };
 use anyhow::Result;
 use aptos_config::network_id::PeerNetworkId;
@@ -20,6 +20,8 @@ use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_metrics::HistogramTimer;
 use aptos_types::{
+    account_address::AccountAddress,
+    account_config::AccountSequenceInfo,
     mempool_status::{MempoolStatus, MempoolStatusCode},
     on_chain_config::OnChainConfigPayload,
     transaction::SignedTransaction,
@@ -37,6 +39,9 @@ use std::{
 use tokio::runtime::Handle;
 use vm_validator::vm_validator::{get_account_sequence_number, TransactionValidation};
 
+// Max number of senders whose parking lot occupancy is reported in the mempool info.
+const MAX_PARKING_LOT_ACCOUNTS: usize = 100;
+
 // ============================== //
 //  broadcast_coordinator tasks  //
 // ============================== //
@@ -82,8 +87,14 @@ pub(crate) async fn execute_broadcast<V>(
         smp.config.shared_mempool_tick_interval_ms
     };
 
+    let mut deadline = Instant::now() + Duration::from_millis(interval_ms);
+    // Don't wake up before the end of a pause asked by the peer
+    if let Some(paused_until) = network_interface.paused_until(&peer) {
+        deadline = std::cmp::max(deadline, paused_until);
+    }
+
     scheduled_broadcasts.push(ScheduledBroadcast::new(
-        Instant::now() + Duration::from_millis(interval_ms),
+        deadline,
         peer,
         schedule_backoff,
         executor,
@@ -141,6 +152,88 @@ pub(crate) async fn process_client_get_transaction<V>(
     }
 }
 
+/// Processes get transactions by account request by client.
+pub(crate) async fn process_client_get_account_transactions<V>(
+    smp: SharedMempool<V>,
+    address: AccountAddress,
+    callback: oneshot::Sender<Vec<SignedTransaction>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let _timer = counters::process_get_txn_latency_timer_client();
+    let txns = smp.mempool.lock().get_by_account(&address);
+
+    if callback.send(txns).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetTransaction,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get mempool info request by client.
+pub(crate) async fn process_client_get_mempool_info<V>(
+    smp: SharedMempool<V>,
+    callback: oneshot::Sender<MempoolInfo>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let info = smp.mempool.lock().info(MAX_PARKING_LOT_ACCOUNTS);
+
+    if callback.send(info).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetMempoolInfo,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get waiting for sequence number request by client.
+pub(crate) async fn process_client_get_waiting_for_sequence_number<V>(
+    smp: SharedMempool<V>,
+    hash: HashValue,
+    callback: oneshot::Sender<Option<u64>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let parked = smp.mempool.lock().get_parked_by_hash(hash);
+    // The account sequence number is read from storage without holding the mempool lock
+    let waiting_for =
+        parked.and_then(|(sender, sequence_number)| {
+            match get_account_sequence_number(smp.db.as_ref(), sender) {
+                Ok(AccountSequenceInfo::Sequential(account_sequence_number)) => {
+                    smp.mempool.lock().first_missing_sequence_number(
+                        &sender,
+                        account_sequence_number,
+                        sequence_number,
+                    )
+                }
+                Ok(AccountSequenceInfo::CRSN { .. }) => None,
+                Err(e) => {
+                    error!(LogSchema::new(LogEntry::DBError).error(&e));
+                    counters::DB_ERROR.inc();
+                    None
+                }
+            }
+        });
+
+    if callback.send(waiting_for).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetWaitingForSequenceNumber,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<V>(
     smp: SharedMempool<V>,
@@ -153,6 +246,18 @@ pub(crate) async fn process_transaction_broadcast<V>(
     V: TransactionValidation,
 {
     timer.stop_and_record();
+    // A saturated mempool asks the peer to pause broadcasting and resend the transactions later,
+    // rather than rejecting them once full. The older peers only get the backoff signal of the
+    // ack once mempool is full.
+    let saturated = smp
+        .mempool
+        .lock()
+        .is_saturated(smp.config.shared_mempool_backoff_threshold_percent);
+    if saturated && smp.network_interface.supports_broadcast_backoff(&peer) {
+        send_backoff(&smp, request_id, &peer);
+        return;
+    }
+
     let _timer = counters::process_txn_submit_latency_timer(peer.network_id());
     let results = process_incoming_transactions(&smp, transactions, timeline_state);
     log_txn_process_results(&results, Some(peer));
@@ -171,6 +276,27 @@ pub(crate) async fn process_transaction_broadcast<V>(
     notify_subscribers(SharedMempoolNotification::ACK, &smp.subscribers);
 }
 
+fn send_backoff<V>(smp: &SharedMempool<V>, request_id: Vec<u8>, peer: &PeerNetworkId)
+where
+    V: TransactionValidation,
+{
+    let backoff = MempoolSyncMsg::BroadcastBackoff {
+        request_id,
+        duration_ms: smp.config.shared_mempool_backoff_interval_ms,
+    };
+    counters::shared_mempool_backoff_inc(peer, counters::SENT_LABEL);
+    if let Err(e) = smp.network_interface.sender().send_to(*peer, backoff) {
+        counters::network_send_fail_inc(counters::BACKOFF_TXNS);
+        error!(
+            LogSchema::event_log(LogEntry::BroadcastBackoff, LogEvent::NetworkSendFail)
+                .peer(peer)
+                .error(&e.into())
+        );
+        return;
+    }
+    notify_subscribers(SharedMempoolNotification::ACK, &smp.subscribers);
+}
+
 /// If `MempoolIsFull` on any of the transactions, provide backpressure to the downstream peer.
 fn gen_ack_response(
     request_id: Vec<u8>,
@@ -232,6 +358,30 @@ where
 {
     let mut statuses = vec![];
 
+    // Reject the transactions denied by a transaction filter, ahead of any validation
+    let transactions: Vec<_> = transactions
+        .into_iter()
+        .filter_map(|t| {
+            let filtered = smp
+                .transaction_filters
+                .iter()
+                .try_for_each(|filter| filter.filter(&t));
+            match filtered {
+                Ok(()) => Some(t),
+                Err(reason) => {
+                    statuses.push((
+                        t,
+                        (
+                            MempoolStatus::new(MempoolStatusCode::Filtered).with_message(reason),
+                            None,
+                        ),
+                    ));
+                    None
+                }
+            }
+        })
+        .collect();
+
     let start_storage_read = Instant::now();
     // Track latency: fetching seq number
     let seq_numbers = transactions
@@ -319,10 +469,26 @@ where
             }
         }
     }
+    if let Some(transaction_log) = &smp.transaction_log {
+        let accepted = accepted_transactions(&statuses);
+        if !accepted.is_empty() {
+            if let Err(e) = transaction_log.lock().append(&accepted) {
+                error!(LogSchema::new(LogEntry::TransactionLog).error(&e));
+            }
+        }
+    }
     notify_subscribers(SharedMempoolNotification::NewTransactions, &smp.subscribers);
     statuses
 }
 
+pub(crate) fn accepted_transactions(statuses: &[SubmissionStatusBundle]) -> Vec<SignedTransaction> {
+    statuses
+        .iter()
+        .filter(|(_, (mempool_status, _))| mempool_status.code == MempoolStatusCode::Accepted)
+        .map(|(txn, _)| txn.clone())
+        .collect()
+}
+
 fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<PeerNetworkId>) {
     let network = match sender {
         Some(peer) => peer.network_id().to_string(),
@@ -340,6 +506,7 @@ fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<Pe
                 counters::VM_VALIDATION_LABEL,
                 &network,
             );
+            counters::mempool_rejected_transactions_inc(counters::VM_VALIDATION_LABEL, &network);
             continue;
         }
         match mempool_status.code {
@@ -347,10 +514,11 @@ fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<Pe
                 counters::SUCCESS_LABEL,
                 &network,
             ),
-            _ => counters::shared_mempool_transactions_processed_inc(
-                &mempool_status.code.to_string(),
-                &network,
-            ),
+            code => {
+                let reason = code.to_string();
+                counters::shared_mempool_transactions_processed_inc(&reason, &network);
+                counters::mempool_rejected_transactions_inc(&reason, &network);
+            }
         }
     }
 }
@@ -370,7 +538,13 @@ pub(crate) fn process_consensus_request<V: TransactionValidation>(
     debug!(LogSchema::event_log(LogEntry::Consensus, LogEvent::Received).consensus_msg(&req));
 
     let (resp, callback, counter_label) = match req {
-        ConsensusRequest::GetBlockRequest(max_block_size, transactions, callback) => {
+        ConsensusRequest::GetBlockRequest(
+            max_block_size,
+            max_block_bytes,
+            max_block_gas,
+            transactions,
+            callback,
+        ) => {
             let exclude_transactions: HashSet<TxnPointer> = transactions
                 .iter()
                 .map(|txn| (txn.sender, txn.sequence_number))
@@ -383,7 +557,12 @@ pub(crate) fn process_consensus_request<V: TransactionValidation>(
                 let curr_time = aptos_infallible::duration_since_epoch();
                 mempool.gc_by_expiration_time(curr_time);
                 let block_size = cmp::max(max_block_size, 1);
-                txns = mempool.get_block(block_size, exclude_transactions);
+                txns = mempool.get_block(
+                    block_size,
+                    max_block_bytes,
+                    max_block_gas,
+                    exclude_transactions,
+                );
             }
             counters::mempool_service_transactions(counters::GET_BLOCK_LABEL, txns.len());
             txns.len();
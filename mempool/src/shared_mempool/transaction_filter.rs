@@ -0,0 +1,89 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Admission control of the transactions submitted to mempool, run ahead of VM validation.
+use aptos_config::config::TransactionFilterConfig;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{SignedTransaction, TransactionPayload},
+};
+use std::collections::HashSet;
+
+/// Decides whether a transaction is admitted into mempool before it's validated by the VM, e.g.
+/// to enforce the policy of a private deployment. A denied transaction is rejected with the
+/// `Filtered` mempool status, whose message is the returned reason.
+pub trait TransactionFilter: Send + Sync {
+    fn filter(&self, txn: &SignedTransaction) -> Result<(), String>;
+}
+
+/// The filter configured in the node config, which all transactions go through.
+pub(crate) struct ConfigTransactionFilter {
+    denied_senders: HashSet<AccountAddress>,
+    module_publishers: Option<HashSet<AccountAddress>>,
+}
+
+impl ConfigTransactionFilter {
+    pub fn new(config: &TransactionFilterConfig) -> Self {
+        Self {
+            denied_senders: config.denied_senders.iter().copied().collect(),
+            module_publishers: config
+                .module_publishers
+                .as_ref()
+                .map(|publishers| publishers.iter().copied().collect()),
+        }
+    }
+}
+
+impl TransactionFilter for ConfigTransactionFilter {
+    fn filter(&self, txn: &SignedTransaction) -> Result<(), String> {
+        let sender = txn.sender();
+        if self.denied_senders.contains(&sender) {
+            return Err(format!("sender {} is denied", sender));
+        }
+        if let TransactionPayload::ModuleBundle(_) = txn.payload() {
+            if let Some(publishers) = &self.module_publishers {
+                if !publishers.contains(&sender) {
+                    return Err(format!(
+                        "sender {} is not allowed to publish modules",
+                        sender
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigTransactionFilter, TransactionFilter};
+    use crate::tests::common::TestTransaction;
+    use aptos_config::config::TransactionFilterConfig;
+
+    #[test]
+    fn test_denied_senders() {
+        let filter = ConfigTransactionFilter::new(&TransactionFilterConfig {
+            denied_senders: vec![TestTransaction::get_address(1)],
+            module_publishers: None,
+        });
+        let denied = TestTransaction::new(1, 0, 1).make_signed_transaction();
+        assert!(filter.filter(&denied).is_err());
+        let allowed = TestTransaction::new(2, 0, 1).make_signed_transaction();
+        assert!(filter.filter(&allowed).is_ok());
+    }
+
+    #[test]
+    fn test_module_publishers() {
+        let filter = ConfigTransactionFilter::new(&TransactionFilterConfig {
+            denied_senders: vec![],
+            module_publishers: Some(vec![TestTransaction::get_address(1)]),
+        });
+        let publish = TestTransaction::new(2, 0, 1).make_module_publish_transaction();
+        assert!(filter.filter(&publish).is_err());
+        let publish = TestTransaction::new(1, 0, 1).make_module_publish_transaction();
+        assert!(filter.filter(&publish).is_ok());
+        // transactions other than module publishing are not restricted
+        let script = TestTransaction::new(2, 0, 1).make_signed_transaction();
+        assert!(filter.filter(&script).is_ok());
+    }
+}
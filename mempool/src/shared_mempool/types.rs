@@ -3,8 +3,13 @@
 
 //! Objects used by/related to shared mempool
 use crate::{
-    core_mempool::CoreMempool, network::MempoolNetworkInterface,
-    shared_mempool::network::MempoolNetworkSender,
+    core_mempool::CoreMempool,
+    network::MempoolNetworkInterface,
+    shared_mempool::{
+        network::MempoolNetworkSender,
+        persistence::TransactionLog,
+        transaction_filter::{ConfigTransactionFilter, TransactionFilter},
+    },
 };
 use anyhow::Result;
 use aptos_config::{
@@ -48,6 +53,11 @@ where
     pub db: Arc<dyn DbReader>,
     pub validator: Arc<RwLock<V>>,
     pub subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
+    // the log the accepted transactions are appended to, when persistence is enabled
+    pub(crate) transaction_log: Option<Arc<Mutex<TransactionLog>>>,
+    // the filters all incoming transactions go through ahead of VM validation, the first one
+    // being the filter of the config
+    pub(crate) transaction_filters: Vec<Arc<dyn TransactionFilter>>,
 }
 
 impl<V: TransactionValidation + 'static> SharedMempool<V> {
@@ -61,6 +71,7 @@ impl<V: TransactionValidation + 'static> SharedMempool<V> {
         role: RoleType,
         peer_metadata_storage: Arc<PeerMetadataStorage>,
     ) -> Self {
+        let transaction_filter = ConfigTransactionFilter::new(&config.transaction_filter);
         let network_interface = MempoolNetworkInterface::new(
             peer_metadata_storage,
             network_senders,
@@ -74,6 +85,8 @@ impl<V: TransactionValidation + 'static> SharedMempool<V> {
             db,
             validator,
             subscribers,
+            transaction_log: None,
+            transaction_filters: vec![Arc::new(transaction_filter)],
         }
     }
 }
@@ -151,6 +164,10 @@ pub enum ConsensusRequest {
     GetBlockRequest(
         // max block size
         u64,
+        // max block size in bytes
+        u64,
+        // max block gas, estimated by the max gas amounts of the transactions
+        u64,
         // transactions to exclude from the requested block
         Vec<TransactionSummary>,
         // callback to respond to
@@ -168,14 +185,21 @@ pub enum ConsensusRequest {
 impl fmt::Display for ConsensusRequest {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let payload = match self {
-            ConsensusRequest::GetBlockRequest(block_size, excluded_txns, _) => {
+            ConsensusRequest::GetBlockRequest(
+                block_size,
+                block_bytes,
+                block_gas,
+                excluded_txns,
+                _,
+            ) => {
                 let mut txns_str = "".to_string();
                 for tx in excluded_txns.iter() {
                     txns_str += &format!("{} ", tx);
                 }
                 format!(
-                    "GetBlockRequest [block_size: {}, excluded_txns: {}]",
-                    block_size, txns_str
+                    "GetBlockRequest [block_size: {}, block_bytes: {}, block_gas: {}, \
+                    excluded_txns: {}]",
+                    block_size, block_bytes, block_gas, txns_str
                 )
             }
             ConsensusRequest::RejectNotification(rejected_txns, _) => {
@@ -213,9 +237,28 @@ pub type SubmissionStatus = (MempoolStatus, Option<DiscardedVMStatus>);
 
 pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 
+/// The occupancy of mempool, reported to operators diagnosing stuck transactions.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MempoolInfo {
+    /// The number of transactions in mempool.
+    pub size: usize,
+    /// The number of transactions ready to be pulled by consensus.
+    pub priority_queue_size: usize,
+    /// The number of transactions waiting for a preceding sequence number of their sender.
+    pub parking_lot_size: usize,
+    /// The senders with the most parked transactions and their number of parked transactions,
+    /// in descending order.
+    pub parking_lot_accounts: Vec<(AccountAddress, usize)>,
+}
+
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    GetAccountTransactions(AccountAddress, oneshot::Sender<Vec<SignedTransaction>>),
+    GetMempoolInfo(oneshot::Sender<MempoolInfo>),
+    /// The sequence number missing in mempool which the transaction of the hash waits for in the
+    /// parking lot, `None` when the transaction is not in the parking lot.
+    GetWaitingForSequenceNumber(HashValue, oneshot::Sender<Option<u64>>),
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
@@ -268,6 +311,8 @@ pub struct BroadcastInfo {
     pub retry_batches: BTreeSet<BatchId>,
     // Whether broadcasting to this peer is in backoff mode, e.g. broadcasting at longer intervals.
     pub backoff_mode: bool,
+    // Broadcasting to this peer is paused until then, as asked by a backoff message of the peer.
+    pub paused_until: Option<Instant>,
 }
 
 impl BroadcastInfo {
@@ -276,6 +321,7 @@ impl BroadcastInfo {
             sent_batches: BTreeMap::new(),
             retry_batches: BTreeSet::new(),
             backoff_mode: false,
+            paused_until: None,
         }
     }
 }
@@ -10,7 +10,9 @@ use aptos_types::{
     account_config::{AccountSequenceInfo, XUS_NAME},
     chain_id::ChainId,
     mempool_status::MempoolStatusCode,
-    transaction::{RawTransaction, Script, SignedTransaction},
+    transaction::{
+        Module, ModuleBundle, RawTransaction, Script, SignedTransaction, TransactionPayload,
+    },
 };
 use once_cell::sync::Lazy;
 use rand::{rngs::StdRng, SeedableRng};
@@ -63,29 +65,50 @@ impl TestTransaction {
         &self,
         exp_timestamp_secs: u64,
     ) -> SignedTransaction {
-        self.make_signed_transaction_impl(100, exp_timestamp_secs)
+        self.make_signed_transaction_impl(100, exp_timestamp_secs, Self::script_payload())
     }
 
     pub(crate) fn make_signed_transaction_with_max_gas_amount(
         &self,
         max_gas_amount: u64,
     ) -> SignedTransaction {
-        self.make_signed_transaction_impl(max_gas_amount, u64::max_value())
+        self.make_signed_transaction_impl(max_gas_amount, u64::max_value(), Self::script_payload())
     }
 
     pub(crate) fn make_signed_transaction(&self) -> SignedTransaction {
-        self.make_signed_transaction_impl(100, u64::max_value())
+        self.make_signed_transaction_impl(100, u64::max_value(), Self::script_payload())
+    }
+
+    pub(crate) fn make_signed_transaction_with_payload(
+        &self,
+        payload: TransactionPayload,
+    ) -> SignedTransaction {
+        self.make_signed_transaction_impl(100, u64::max_value(), payload)
+    }
+
+    pub(crate) fn make_module_publish_transaction(&self) -> SignedTransaction {
+        let modules = ModuleBundle::from(Module::new(vec![]));
+        self.make_signed_transaction_impl(
+            100,
+            u64::max_value(),
+            TransactionPayload::ModuleBundle(modules),
+        )
+    }
+
+    fn script_payload() -> TransactionPayload {
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![]))
     }
 
     fn make_signed_transaction_impl(
         &self,
         max_gas_amount: u64,
         exp_timestamp_secs: u64,
+        payload: TransactionPayload,
     ) -> SignedTransaction {
-        let raw_txn = RawTransaction::new_script(
+        let raw_txn = RawTransaction::new(
             TestTransaction::get_address(self.address),
             self.sequence_number,
-            Script::new(vec![], vec![], vec![]),
+            payload,
             max_gas_amount,
             self.gas_price,
             XUS_NAME.to_owned(),
@@ -171,7 +194,7 @@ impl ConsensusMock {
         mempool: &mut CoreMempool,
         block_size: u64,
     ) -> Vec<SignedTransaction> {
-        let block = mempool.get_block(block_size, self.0.clone());
+        let block = mempool.get_block(block_size, u64::MAX, u64::MAX, self.0.clone());
         self.0 = self
             .0
             .union(
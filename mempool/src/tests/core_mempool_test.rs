@@ -5,12 +5,16 @@ use crate::{
     core_mempool::{CoreMempool, TimelineState, TtlCache},
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, exist_in_metrics_cache, setup_mempool,
-        TestTransaction,
+        ConsensusMock, TestTransaction,
     },
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{MempoolEvictionPolicyType, NodeConfig};
 use aptos_crypto::HashValue;
-use aptos_types::{account_config::AccountSequenceInfo, transaction::SignedTransaction};
+use aptos_types::{
+    account_config::AccountSequenceInfo,
+    mempool_status::MempoolStatusCode,
+    transaction::{Script, SignedTransaction, TransactionPayload},
+};
 use std::{
     collections::HashSet,
     time::{Duration, SystemTime},
@@ -269,20 +273,73 @@ fn test_update_invalid_transaction_in_mempool() {
     let (mut mempool, mut consensus) = setup_mempool();
     let txns = add_txns_to_mempool(
         &mut mempool,
-        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(1, 0, 2)],
+        vec![
+            TestTransaction::new(0, 0, 100),
+            TestTransaction::new(1, 0, 200),
+        ],
     );
     let updated_txn = TestTransaction::make_signed_transaction_with_max_gas_amount(
-        &TestTransaction::new(0, 0, 5),
+        &TestTransaction::new(0, 0, 105),
         200,
     );
     let _added_tnx = add_signed_txn(&mut mempool, updated_txn);
 
-    // Since both gas price and mas gas amount were updated, the ordering should not have changed.
-    // The second transaction with gas price 2 should come first.
+    // Since the max gas amount was updated and the gas price was not bumped enough for a
+    // replacement, the ordering should not have changed.
+    // The second transaction with gas price 200 should come first.
     assert_eq!(consensus.get_block(&mut mempool, 1), vec![txns[1].clone()]);
     let next_tnx = consensus.get_block(&mut mempool, 1);
     assert_eq!(next_tnx, vec![txns[0].clone()]);
-    assert_eq!(next_tnx[0].gas_unit_price(), 1);
+    assert_eq!(next_tnx[0].gas_unit_price(), 100);
+}
+
+#[test]
+fn test_replace_transaction_by_fee() {
+    let (mut mempool, mut consensus) = setup_mempool();
+    let txns = add_txns_to_mempool(&mut mempool, vec![TestTransaction::new(0, 0, 10)]);
+    let replacement = TestTransaction::make_signed_transaction_with_max_gas_amount(
+        &TestTransaction::new(0, 0, 11),
+        200,
+    );
+    assert!(add_signed_txn(&mut mempool, replacement.clone()).is_ok());
+
+    assert!(mempool
+        .get_by_hash(txns[0].clone().committed_hash())
+        .is_none());
+    assert_eq!(consensus.get_block(&mut mempool, 1), vec![replacement]);
+}
+
+#[test]
+fn test_replace_transaction_by_fee_checks_before_removing() {
+    let txn_bytes = TestTransaction::new(0, 0, 10)
+        .make_signed_transaction()
+        .raw_txn_bytes_len();
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 1;
+    config.mempool.max_transactions_per_sender = 1;
+    config.mempool.max_bytes_per_sender = txn_bytes;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(0, 0, 10)).unwrap();
+
+    // The replacement takes the place of the transaction it replaces in the full mempool.
+    let replacement = TestTransaction::make_signed_transaction_with_max_gas_amount(
+        &TestTransaction::new(0, 0, 11),
+        200,
+    );
+    add_signed_txn(&mut pool, replacement.clone()).unwrap();
+
+    // The replacement over the limit of bytes of the sender leaves the transaction in mempool.
+    let too_big = TestTransaction::new(0, 0, 20).make_signed_transaction_with_payload(
+        TransactionPayload::Script(Script::new(vec![0; 100], vec![], vec![])),
+    );
+    assert!(add_signed_txn(&mut pool, too_big).is_err());
+    assert!(pool
+        .get_by_hash(replacement.clone().committed_hash())
+        .is_some());
+    assert_eq!(
+        ConsensusMock::new().get_block(&mut pool, 1),
+        vec![replacement]
+    );
 }
 
 #[test]
@@ -291,22 +348,23 @@ fn test_update_invalid_transaction_in_mempool_crsn() {
     let txns = add_txns_to_mempool(
         &mut mempool,
         vec![
-            TestTransaction::new(0, 0, 1).crsn(0),
-            TestTransaction::new(1, 0, 2).crsn(0),
+            TestTransaction::new(0, 0, 100).crsn(0),
+            TestTransaction::new(1, 0, 200).crsn(0),
         ],
     );
     let updated_txn = TestTransaction::make_signed_transaction_with_max_gas_amount(
-        &TestTransaction::new(0, 0, 5).crsn(0),
+        &TestTransaction::new(0, 0, 105).crsn(0),
         200,
     );
     let _added_tnx = add_signed_txn(&mut mempool, updated_txn);
 
-    // Since both gas price and mas gas amount were updated, the ordering should not have changed.
-    // The second transaction with gas price 2 should come first.
+    // Since the max gas amount was updated and the gas price was not bumped enough for a
+    // replacement, the ordering should not have changed.
+    // The second transaction with gas price 200 should come first.
     assert_eq!(consensus.get_block(&mut mempool, 1), vec![txns[1].clone()]);
     let next_tnx = consensus.get_block(&mut mempool, 1);
     assert_eq!(next_tnx, vec![txns[0].clone()]);
-    assert_eq!(next_tnx[0].gas_unit_price(), 1);
+    assert_eq!(next_tnx[0].gas_unit_price(), 100);
 }
 
 #[test]
@@ -375,7 +433,7 @@ fn test_system_ttl() {
 
     // GC routine should clear transaction from first insert but keep last one.
     mempool.gc();
-    let batch = mempool.get_block(1, HashSet::new());
+    let batch = mempool.get_block(1, u64::MAX, u64::MAX, HashSet::new());
     assert_eq!(vec![transaction.make_signed_transaction()], batch);
 }
 
@@ -387,11 +445,16 @@ fn test_commit_callback() {
     let txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(1, 6, 1)]);
 
     // Check that pool is empty.
-    assert!(pool.get_block(1, HashSet::new()).is_empty());
+    assert!(pool
+        .get_block(1, u64::MAX, u64::MAX, HashSet::new())
+        .is_empty());
     // Transaction 5 got back from consensus.
     pool.remove_transaction(&TestTransaction::get_address(1), 5, false);
     // Verify that we can execute transaction 6.
-    assert_eq!(pool.get_block(1, HashSet::new())[0], txns[0]);
+    assert_eq!(
+        pool.get_block(1, u64::MAX, u64::MAX, HashSet::new())[0],
+        txns[0]
+    );
 }
 
 #[test]
@@ -405,7 +468,10 @@ fn test_sequence_number_cache() {
     // for AC is 0).
     add_txns_to_mempool(&mut pool, vec![TestTransaction::new(1, 6, 1)]);
     // Verify that we can execute transaction 6.
-    assert_eq!(pool.get_block(1, HashSet::new()).len(), 1);
+    assert_eq!(
+        pool.get_block(1, u64::MAX, u64::MAX, HashSet::new()).len(),
+        1
+    );
 }
 
 #[test]
@@ -502,7 +568,7 @@ fn test_parking_lot_eviction() {
     }
     // Make sure that we have correct txns in Mempool.
     let mut txns: Vec<_> = pool
-        .get_block(5, HashSet::new())
+        .get_block(5, u64::MAX, u64::MAX, HashSet::new())
         .iter()
         .map(SignedTransaction::sequence_number)
         .collect();
@@ -513,6 +579,27 @@ fn test_parking_lot_eviction() {
     assert!(add_txn(&mut pool, TestTransaction::new(0, 2, 1)).is_err());
 }
 
+#[test]
+fn test_fair_share_eviction() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    config.mempool.eviction_policy = MempoolEvictionPolicyType::FairShare;
+    let mut pool = CoreMempool::new(&config);
+    for seq in 0..3 {
+        add_txn(&mut pool, TestTransaction::new(1, seq, 1)).unwrap();
+    }
+
+    // the last transaction of the heaviest sender is evicted
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    let ret = pool.get_by_account(&TestTransaction::get_address(1));
+    assert_eq!(
+        ret.iter()
+            .map(SignedTransaction::sequence_number)
+            .collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+}
+
 #[test]
 fn test_parking_lot_evict_only_for_ready_txn_insertion() {
     let mut config = NodeConfig::random();
@@ -531,7 +618,7 @@ fn test_parking_lot_evict_only_for_ready_txn_insertion() {
 
     // Make sure that we have correct txns in Mempool.
     let mut txns: Vec<_> = pool
-        .get_block(5, HashSet::new())
+        .get_block(5, u64::MAX, u64::MAX, HashSet::new())
         .iter()
         .map(SignedTransaction::sequence_number)
         .collect();
@@ -573,7 +660,7 @@ fn test_gc_ready_transaction() {
     pool.gc_by_expiration_time(Duration::from_secs(1));
 
     // Make sure txns 2 and 3 became not ready and we can't read them from any API.
-    let block = pool.get_block(10, HashSet::new());
+    let block = pool.get_block(10, u64::MAX, u64::MAX, HashSet::new());
     assert_eq!(block.len(), 1);
     assert_eq!(block[0].sequence_number(), 0);
 
@@ -597,7 +684,7 @@ fn test_clean_stuck_transactions() {
         AccountSequenceInfo::Sequential(db_sequence_number),
         TimelineState::NotReady,
     );
-    let block = pool.get_block(10, HashSet::new());
+    let block = pool.get_block(10, u64::MAX, u64::MAX, HashSet::new());
     assert_eq!(block.len(), 1);
     assert_eq!(block[0].sequence_number(), 10);
 }
@@ -677,3 +764,197 @@ fn test_get_transaction_by_hash_after_the_txn_is_updated() {
     let txn_by_new_hash = pool.get_by_hash(new_txn_hash);
     assert_eq!(txn_by_new_hash, Some(new_txn));
 }
+
+#[test]
+fn test_get_transactions_by_account() {
+    let mut pool = setup_mempool().0;
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(1, 0, 1),
+            TestTransaction::new(0, 0, 1),
+        ],
+    );
+
+    let ret = pool.get_by_account(&TestTransaction::get_address(0));
+    assert_eq!(ret, vec![txns[2].clone(), txns[0].clone()]);
+
+    let ret = pool.get_by_account(&TestTransaction::get_address(2));
+    assert!(ret.is_empty());
+}
+
+#[test]
+fn test_gas_price_lanes() {
+    let low_lane = TestTransaction::new(0, 0, 5).make_signed_transaction();
+    let high_lane = TestTransaction::new(1, 0, 10).make_signed_transaction();
+    let get_block = |gas_price_lanes: Vec<u64>| {
+        let mut config = NodeConfig::random();
+        config.mempool.gas_price_lanes = gas_price_lanes;
+        let mut pool = CoreMempool::new(&config);
+        for (txn, ranking_score) in [(&low_lane, 50), (&high_lane, 1)] {
+            pool.add_txn(
+                txn.clone(),
+                0,
+                ranking_score,
+                AccountSequenceInfo::Sequential(0),
+                TimelineState::NotReady,
+            );
+        }
+        pool.get_block(2, u64::MAX, u64::MAX, HashSet::new())
+    };
+
+    // without lanes, the transactions are ordered by ranking score only
+    assert_eq!(get_block(vec![]), vec![low_lane.clone(), high_lane.clone()]);
+    // the lane of the gas unit price comes before the ranking score
+    assert_eq!(get_block(vec![10]), vec![high_lane, low_lane]);
+}
+
+#[test]
+fn test_block_limits() {
+    let mut pool = setup_mempool().0;
+    let txns: Vec<_> = (0..3)
+        .map(|address| TestTransaction::new(address, 0, 1).make_signed_transaction())
+        .collect();
+    for txn in &txns {
+        pool.add_txn(
+            txn.clone(),
+            100,
+            txn.gas_unit_price(),
+            AccountSequenceInfo::Sequential(0),
+            TimelineState::NotReady,
+        );
+    }
+    let txn_bytes = txns[0].txn_bytes_len() as u64;
+
+    // the block is cut before the transaction exceeding the gas or bytes limit
+    assert_eq!(pool.get_block(10, u64::MAX, 250, HashSet::new()).len(), 2);
+    assert_eq!(
+        pool.get_block(10, 2 * txn_bytes + txn_bytes / 2, u64::MAX, HashSet::new())
+            .len(),
+        2
+    );
+    // a transaction exceeding the limits on its own is still proposed
+    assert_eq!(pool.get_block(10, 1, 1, HashSet::new()).len(), 1);
+}
+
+#[test]
+fn test_min_gas_unit_price() {
+    let mut config = NodeConfig::random();
+    config.mempool.min_gas_unit_price = 2;
+    let mut pool = CoreMempool::new(&config);
+
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 0, 1)).is_err());
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 0, 2)).is_ok());
+}
+
+#[test]
+fn test_mempool_info() {
+    let mut pool = setup_mempool().0;
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(1, 1, 1),
+            TestTransaction::new(2, 1, 1),
+            TestTransaction::new(2, 2, 1),
+        ],
+    );
+
+    let info = pool.info(1);
+    assert_eq!(info.size, 4);
+    assert_eq!(info.priority_queue_size, 1);
+    assert_eq!(info.parking_lot_size, 3);
+    assert_eq!(
+        info.parking_lot_accounts,
+        vec![(TestTransaction::get_address(2), 2)]
+    );
+}
+
+#[test]
+fn test_max_transactions_per_sender() {
+    let mut config = NodeConfig::random();
+    config.mempool.max_transactions_per_sender = 2;
+    let mut pool = CoreMempool::new(&config);
+
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(0, 1, 1)).unwrap();
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(0, 2, 1)),
+        MempoolStatusCode::TooManyTransactions
+    );
+
+    // other senders have their own limit
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 0, 1)).is_ok());
+}
+
+#[test]
+fn test_max_bytes_per_sender() {
+    let txn_bytes = TestTransaction::new(0, 0, 1)
+        .make_signed_transaction()
+        .raw_txn_bytes_len();
+    let mut config = NodeConfig::random();
+    config.mempool.max_bytes_per_sender = 2 * txn_bytes;
+    let mut pool = CoreMempool::new(&config);
+
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(0, 1, 1)).unwrap();
+    assert_eq!(
+        add_txn_status(&mut pool, TestTransaction::new(0, 2, 1)),
+        MempoolStatusCode::TooManyBytes
+    );
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 0, 1)).is_ok());
+}
+
+fn add_txn_status(pool: &mut CoreMempool, transaction: TestTransaction) -> MempoolStatusCode {
+    let txn = transaction.make_signed_transaction();
+    pool.add_txn(
+        txn.clone(),
+        0,
+        txn.gas_unit_price(),
+        AccountSequenceInfo::Sequential(0),
+        TimelineState::NotReady,
+    )
+    .code
+}
+
+#[test]
+fn test_is_saturated() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 4;
+    let mut pool = CoreMempool::new(&config);
+
+    add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(1, 0, 1)],
+    );
+    assert!(pool.is_saturated(50));
+    assert!(!pool.is_saturated(75));
+
+    add_txn(&mut pool, TestTransaction::new(2, 0, 1)).unwrap();
+    assert!(pool.is_saturated(75));
+}
+
+#[test]
+fn test_waiting_for_sequence_number() {
+    let mut pool = setup_mempool().0;
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(0, 4, 1),
+        ],
+    );
+    let sender = TestTransaction::get_address(0);
+
+    // the ready transactions are not parked
+    assert_eq!(pool.get_parked_by_hash(txns[1].committed_hash()), None);
+    assert_eq!(
+        pool.get_parked_by_hash(txns[2].committed_hash()),
+        Some((sender, 4))
+    );
+    assert_eq!(pool.first_missing_sequence_number(&sender, 0, 4), Some(2));
+    // the transactions from the account sequence number to the parked one are in mempool
+    assert_eq!(pool.first_missing_sequence_number(&sender, 4, 4), None);
+}
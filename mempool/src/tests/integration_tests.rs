@@ -291,6 +291,59 @@ async fn test_mempool_full_rebroadcast() {
         .await;
 }
 
+/// A peer asking to back off isn't broadcast to until the end of the pause, then gets the
+/// transactions again
+#[tokio::test]
+async fn test_broadcast_backoff() {
+    let mut node = MempoolTestFrameworkBuilder::single_validator();
+    let (other_peer_network_id, other_metadata) =
+        validator_mock_connection(ConnectionOrigin::Outbound, &ALL_PROTOCOLS);
+
+    node.add_txns_via_client(ALL_TXNS).await;
+    node.connect_self(other_peer_network_id.network_id(), other_metadata.clone());
+    node.send_broadcast_and_receive_backoff(
+        other_peer_network_id,
+        ALL_TXNS,
+        Duration::from_millis(500),
+    )
+    .await;
+
+    // Nothing is sent during the pause, even though the tick interval is much shorter
+    node.wait_for_no_msg(
+        other_peer_network_id.network_id(),
+        Duration::from_millis(200),
+    )
+    .await;
+    node.send_broadcast_and_receive_ack(other_peer_network_id, ALL_TXNS)
+        .await;
+}
+
+/// A backoff longer than an `Instant` can hold is cut to the configured max, rather than
+/// crashing the node
+#[tokio::test]
+async fn test_broadcast_backoff_duration_is_capped() {
+    let mut node = MempoolTestFrameworkBuilder::single_validator();
+    let (other_peer_network_id, other_metadata) =
+        validator_mock_connection(ConnectionOrigin::Outbound, &ALL_PROTOCOLS);
+
+    node.add_txns_via_client(&ALL_TXNS[..1]).await;
+    node.connect_self(other_peer_network_id.network_id(), other_metadata.clone());
+    node.send_broadcast_and_receive_backoff(
+        other_peer_network_id,
+        &ALL_TXNS[..1],
+        Duration::from_millis(u64::MAX),
+    )
+    .await;
+
+    // The node is still running and processes new transactions
+    node.add_txns_via_client(&ALL_TXNS[1..]).await;
+    node.wait_for_no_msg(
+        other_peer_network_id.network_id(),
+        Duration::from_millis(200),
+    )
+    .await;
+}
+
 // -- Multi node tests below here --
 
 /// Tests if the node is a VFN, and it's getting forwarded messages from a PFN.  It should forward
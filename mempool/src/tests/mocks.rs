@@ -135,6 +135,7 @@ impl MockSharedMempool {
             Arc::new(RwLock::new(validator)),
             vec![],
             peer_metadata_storage,
+            vec![],
         );
 
         (ac_client, mempool, consensus_sender, mempool_notifier)
@@ -164,7 +165,7 @@ impl MockSharedMempool {
 
     pub fn get_txns(&self, size: u64) -> Vec<SignedTransaction> {
         let pool = self.mempool.lock();
-        pool.get_block(size, HashSet::new())
+        pool.get_block(size, u64::MAX, u64::MAX, HashSet::new())
     }
 
     pub fn remove_txn(&self, txn: &SignedTransaction) {
@@ -597,6 +597,7 @@ fn start_node_mempool(
         Arc::new(RwLock::new(MockVMValidator)),
         vec![sender],
         peer_metadata_storage,
+        vec![],
     );
 
     (mempool, runtime, subscriber)
@@ -152,7 +152,10 @@ impl MempoolNode {
     /// Asynchronously waits for up to 1 second for txns to appear in mempool
     pub async fn wait_on_txns_in_mempool(&self, txns: &[TestTransaction]) {
         for _ in 0..10 {
-            let block = self.mempool.lock().get_block(100, HashSet::new());
+            let block = self
+                .mempool
+                .lock()
+                .get_block(100, u64::MAX, u64::MAX, HashSet::new());
 
             if block_contains_all_transactions(&block, txns) {
                 break;
@@ -202,7 +205,10 @@ impl MempoolNode {
         txns: &[TestTransaction],
         condition: Condition,
     ) -> Result<(), (Vec<(AccountAddress, u64)>, Vec<(AccountAddress, u64)>)> {
-        let block = self.mempool.lock().get_block(100, HashSet::new());
+        let block = self
+            .mempool
+            .lock()
+            .get_block(100, u64::MAX, u64::MAX, HashSet::new());
         if !condition(&block, txns) {
             let actual: Vec<_> = block
                 .iter()
@@ -304,8 +310,11 @@ impl MempoolNode {
         self.send_broadcast_and_receive_response(
             expected_peer_network_id,
             expected_txns,
-            false,
-            false,
+            |request_id| MempoolSyncMsg::BroadcastTransactionsResponse {
+                request_id,
+                retry: false,
+                backoff: false,
+            },
         )
         .await
     }
@@ -319,8 +328,28 @@ impl MempoolNode {
         self.send_broadcast_and_receive_response(
             expected_peer_network_id,
             expected_txns,
-            true,
-            false,
+            |request_id| MempoolSyncMsg::BroadcastTransactionsResponse {
+                request_id,
+                retry: true,
+                backoff: false,
+            },
+        )
+        .await
+    }
+
+    pub async fn send_broadcast_and_receive_backoff(
+        &mut self,
+        expected_peer_network_id: PeerNetworkId,
+        expected_txns: &[TestTransaction],
+        duration: Duration,
+    ) {
+        self.send_broadcast_and_receive_response(
+            expected_peer_network_id,
+            expected_txns,
+            |request_id| MempoolSyncMsg::BroadcastBackoff {
+                request_id,
+                duration_ms: duration.as_millis() as u64,
+            },
         )
         .await
     }
@@ -330,8 +359,7 @@ impl MempoolNode {
         &mut self,
         expected_peer_network_id: PeerNetworkId,
         expected_txns: &[TestTransaction],
-        retry: bool,
-        backoff: bool,
+        response: impl FnOnce(Vec<u8>) -> MempoolSyncMsg,
     ) {
         let network_id = expected_peer_network_id.network_id();
         let expected_peer_id = expected_peer_network_id.peer_id();
@@ -373,15 +401,12 @@ impl MempoolNode {
                 }
                 request_id
             }
-            MempoolSyncMsg::BroadcastTransactionsResponse { .. } => {
+            MempoolSyncMsg::BroadcastTransactionsResponse { .. }
+            | MempoolSyncMsg::BroadcastBackoff { .. } => {
                 panic!("We aren't supposed to be getting as response here");
             }
         };
-        let response = MempoolSyncMsg::BroadcastTransactionsResponse {
-            request_id,
-            retry,
-            backoff,
-        };
+        let response = response(request_id);
         let bytes = protocol_id.to_bytes(&response).unwrap();
 
         if let Some(rpc_sender) = maybe_rpc_sender {
@@ -501,6 +526,7 @@ fn setup_mempool(
         vm_validator,
         vec![sender],
         peer_metadata_storage,
+        vec![],
     );
 
     (
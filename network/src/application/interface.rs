@@ -111,4 +111,19 @@ impl<TMessage: Clone + Message + Send, Sender: ApplicationNetworkSender<TMessage
             .send_rpc(recipient.peer_id(), req_msg, timeout)
             .await
     }
+
+    /// Same as `send_rpc`, also returning the number of bytes the response was received in.
+    pub async fn send_rpc_with_num_bytes(
+        &self,
+        recipient: PeerNetworkId,
+        req_msg: TMessage,
+        timeout: Duration,
+    ) -> Result<(TMessage, usize), RpcError>
+    where
+        Sender: Sync,
+    {
+        self.sender(&recipient.network_id())
+            .send_rpc_with_num_bytes(recipient.peer_id(), req_msg, timeout)
+            .await
+    }
 }
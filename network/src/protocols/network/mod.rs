@@ -340,6 +340,20 @@ impl<TMessage: Message> NetworkSender<TMessage> {
         req_msg: TMessage,
         timeout: Duration,
     ) -> Result<TMessage, RpcError> {
+        let (res_msg, _) = self
+            .send_rpc_with_num_bytes(recipient, protocol, req_msg, timeout)
+            .await?;
+        Ok(res_msg)
+    }
+
+    /// Same as `send_rpc`, also returning the number of bytes the response was received in.
+    pub async fn send_rpc_with_num_bytes(
+        &self,
+        recipient: PeerId,
+        protocol: ProtocolId,
+        req_msg: TMessage,
+        timeout: Duration,
+    ) -> Result<(TMessage, usize), RpcError> {
         // serialize request
         let req_data = protocol.to_bytes(&req_msg)?.into();
         let res_data = self
@@ -347,7 +361,7 @@ impl<TMessage: Message> NetworkSender<TMessage> {
             .send_rpc(recipient, protocol, req_data, timeout)
             .await?;
         let res_msg: TMessage = protocol.from_bytes(&res_data)?;
-        Ok(res_msg)
+        Ok((res_msg, res_data.len()))
     }
 }
 
@@ -373,6 +387,16 @@ pub trait ApplicationNetworkSender<TMessage: Send>: Clone {
         req_msg: TMessage,
         timeout: Duration,
     ) -> Result<TMessage, RpcError>;
+
+    /// Same as `send_rpc`, also returning the number of bytes the response was received in.
+    async fn send_rpc_with_num_bytes(
+        &self,
+        _recipient: PeerId,
+        _req_msg: TMessage,
+        _timeout: Duration,
+    ) -> Result<(TMessage, usize), RpcError> {
+        unimplemented!()
+    }
 }
 
 /// Generalized functionality for any request across `DirectSend` and `Rpc`.
@@ -51,6 +51,9 @@ pub enum ProtocolId {
     ConsensusRpcJson = 7,
     StorageServiceRpc = 8,
     MempoolRpc = 9,
+    ConsensusObserverDirectSend = 10,
+    // only advertised by the peers decoding `MempoolSyncMsg::BroadcastBackoff`
+    MempoolBackoffDirectSend = 11,
 }
 
 /// The encoding types for Protocols
@@ -73,6 +76,8 @@ impl ProtocolId {
             ConsensusRpcJson => "ConsensusRpcJson",
             StorageServiceRpc => "StorageServiceRpc",
             MempoolRpc => "MempoolRpc",
+            ConsensusObserverDirectSend => "ConsensusObserverDirectSend",
+            MempoolBackoffDirectSend => "MempoolBackoffDirectSend",
         }
     }
 
@@ -88,6 +93,8 @@ impl ProtocolId {
             ProtocolId::ConsensusRpcJson,
             ProtocolId::StorageServiceRpc,
             ProtocolId::MempoolRpc,
+            ProtocolId::ConsensusObserverDirectSend,
+            ProtocolId::MempoolBackoffDirectSend,
         ]
     }
 
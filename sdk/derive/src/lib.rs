@@ -0,0 +1,156 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! # Derive macros for the Move resources
+//! This crate contains the derive macros mapping a Rust struct to a Move struct, for the
+//! `aptos_sdk::move_resource` module (look there for details):
+//!
+//! - `MoveStructType`, which implements `move_core_types::move_resource::MoveStructType`, to
+//!   type the structs nested in a resource and the type arguments of a generic resource.
+//! - `MoveResource`, which implements `MoveStructType` and `MoveResource` as well, for the
+//!   resources fetched from the accounts.
+//!
+//! The Move struct is named by the `move_module` and `move_struct` attributes, and the
+//! `move_address` attribute when it's not published at `0x1`. Each type parameter of a generic
+//! struct must itself implement `MoveStructType`.
+//!
+//! ```ignore
+//! #[derive(Deserialize, MoveResource)]
+//! #[move_module = "TestCoin"]
+//! #[move_struct = "Balance"]
+//! struct Balance {
+//!     coin: Coin,
+//! }
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_quote, DeriveInput};
+
+const ADDRESS_LENGTH: usize = 32;
+
+#[proc_macro_derive(MoveStructType, attributes(move_address, move_module, move_struct))]
+pub fn derive_move_struct_type(input: TokenStream) -> TokenStream {
+    let ast: DeriveInput = syn::parse(input).expect("Incorrect macro input");
+    impl_move_struct_type(&ast)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(MoveResource, attributes(move_address, move_module, move_struct))]
+pub fn derive_move_resource(input: TokenStream) -> TokenStream {
+    let ast: DeriveInput = syn::parse(input).expect("Incorrect macro input");
+    impl_move_struct_type(&ast)
+        .map(|struct_type| {
+            let name = &ast.ident;
+            let mut generics = ast.generics.clone();
+            add_move_struct_type_bounds(&mut generics);
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(Self: ::serde::de::DeserializeOwned));
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            quote! {
+                #struct_type
+
+                impl #impl_generics ::aptos_sdk::move_types::move_resource::MoveResource
+                    for #name #ty_generics #where_clause {}
+            }
+        })
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn impl_move_struct_type(ast: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &ast.ident;
+    let address = match find_attr(&ast.attrs, "move_address")? {
+        Some(address) => parse_address(&address)?,
+        None => {
+            let mut address = [0u8; ADDRESS_LENGTH];
+            address[ADDRESS_LENGTH - 1] = 1;
+            address
+        }
+    };
+    let module_name = require_attr(&ast.attrs, "move_module")?;
+    let struct_name = require_attr(&ast.attrs, "move_struct")?;
+
+    let type_params = ast.generics.type_params().map(|param| {
+        let ident = &param.ident;
+        quote! {
+            ::aptos_sdk::move_types::language_storage::TypeTag::Struct(
+                <#ident as ::aptos_sdk::move_types::move_resource::MoveStructType>::struct_tag()
+            )
+        }
+    });
+    let mut generics = ast.generics.clone();
+    add_move_struct_type_bounds(&mut generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::aptos_sdk::move_types::move_resource::MoveStructType
+            for #name #ty_generics #where_clause
+        {
+            const ADDRESS: ::aptos_sdk::move_types::account_address::AccountAddress =
+                ::aptos_sdk::move_types::account_address::AccountAddress::new([#(#address),*]);
+            const MODULE_NAME: &'static ::aptos_sdk::move_types::identifier::IdentStr =
+                ::aptos_sdk::move_types::ident_str!(#module_name);
+            const STRUCT_NAME: &'static ::aptos_sdk::move_types::identifier::IdentStr =
+                ::aptos_sdk::move_types::ident_str!(#struct_name);
+
+            fn type_params() -> Vec<::aptos_sdk::move_types::language_storage::TypeTag> {
+                vec![#(#type_params),*]
+            }
+        }
+    })
+}
+
+fn add_move_struct_type_bounds(generics: &mut syn::Generics) {
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(
+            ::aptos_sdk::move_types::move_resource::MoveStructType
+        ));
+    }
+}
+
+/// Parses an address in hex, e.g. `0x1`, left padded with zeros
+fn parse_address(address: &syn::LitStr) -> syn::Result<[u8; ADDRESS_LENGTH]> {
+    let invalid_address = || syn::Error::new_spanned(address, "Could not parse move_address");
+    let value = address.value();
+    let hex_digits = value.strip_prefix("0x").unwrap_or(&value);
+    if hex_digits.is_empty() || hex_digits.len() > 2 * ADDRESS_LENGTH {
+        return Err(invalid_address());
+    }
+    let padded = format!("{:0>width$}", hex_digits, width = 2 * ADDRESS_LENGTH);
+    let mut bytes = [0u8; ADDRESS_LENGTH];
+    hex::decode_to_slice(padded, &mut bytes).map_err(|_| invalid_address())?;
+    Ok(bytes)
+}
+
+fn require_attr(attrs: &[syn::Attribute], attr_name: &str) -> syn::Result<syn::LitStr> {
+    find_attr(attrs, attr_name)?.ok_or_else(|| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("Could not find attribute {}", attr_name),
+        )
+    })
+}
+
+fn find_attr(attrs: &[syn::Attribute], attr_name: &str) -> syn::Result<Option<syn::LitStr>> {
+    attrs
+        .iter()
+        .find(|attr| attr.path.is_ident(attr_name))
+        .map(|attr| match attr.parse_meta()? {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) => Ok(lit),
+            bad => Err(syn::Error::new_spanned(
+                bad,
+                &format!("Could not parse {} attribute", attr_name)[..],
+            )),
+        })
+        .transpose()
+}
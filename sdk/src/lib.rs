@@ -8,6 +8,7 @@
 //! This SDK provides all the necessary components for building on top of the Diem Blockchain. Some of the important modules are:
 //!
 //! * `crypto` - Types used for signing and verifying
+//! * `move_resource` - Includes the derives mapping Rust structs to Move resources
 //! * `transaction_builder` - Includes helpers for constructing transactions
 //! * `types` - Includes types for Diem on-chain data structures
 //!
@@ -21,6 +22,8 @@ pub mod crypto {
     pub use aptos_crypto::*;
 }
 
+pub mod move_resource;
+
 pub mod transaction_builder;
 
 pub mod types;
@@ -0,0 +1,92 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed Move resources.
+//!
+//! `#[derive(MoveResource)]` maps a Rust struct to the Move struct named by its `move_module` and
+//! `move_struct` attributes, and `move_address` when it's not published at `0x1`, so the resource
+//! can be fetched as the struct with `aptos_rest_client::Client::get_move_resource`. The structs
+//! nested in the resource, and the type arguments of a generic resource, derive `MoveStructType`.
+//!
+//! The API renders the `u64`, `u128` and `vector<u8>` values of the resources as strings in JSON,
+//! the fields of these types use the serde helpers of this module to be deserialized from both
+//! the JSON and the BCS responses:
+//!
+//! ```ignore
+//! use aptos_sdk::move_resource::{move_u64, MoveResource, MoveStructType};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, MoveStructType)]
+//! #[move_module = "TestCoin"]
+//! #[move_struct = "Coin"]
+//! struct Coin {
+//!     #[serde(with = "move_u64")]
+//!     value: u64,
+//! }
+//!
+//! #[derive(Deserialize, MoveResource)]
+//! #[move_module = "TestCoin"]
+//! #[move_struct = "Balance"]
+//! struct Balance {
+//!     coin: Coin,
+//! }
+//!
+//! let balance = client.get_move_resource::<Balance>(address).await?.into_inner();
+//! ```
+
+pub use aptos_sdk_derive::{MoveResource, MoveStructType};
+
+macro_rules! move_integer {
+    ($module:ident, $ty:ty) => {
+        /// Serde helper for a Move integer, rendered as a string when human readable
+        pub mod $module {
+            use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+            pub fn serialize<S: Serializer>(value: &$ty, serializer: S) -> Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    value.to_string().serialize(serializer)
+                } else {
+                    value.serialize(serializer)
+                }
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<$ty, D::Error> {
+                if deserializer.is_human_readable() {
+                    String::deserialize(deserializer)?
+                        .parse()
+                        .map_err(D::Error::custom)
+                } else {
+                    <$ty>::deserialize(deserializer)
+                }
+            }
+        }
+    };
+}
+
+move_integer!(move_u64, u64);
+move_integer!(move_u128, u128);
+
+/// Serde helper for a Move `vector<u8>`, rendered as a `0x` prefixed hex string when human
+/// readable
+pub mod move_bytes {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            format!("0x{}", hex::encode(bytes)).serialize(serializer)
+        } else {
+            bytes.serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            let encoded = String::deserialize(deserializer)?;
+            hex::decode(encoded.strip_prefix("0x").unwrap_or(&encoded)).map_err(D::Error::custom)
+        } else {
+            Vec::<u8>::deserialize(deserializer)
+        }
+    }
+}
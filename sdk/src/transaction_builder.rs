@@ -16,8 +16,13 @@ use std::fmt;
 pub use aptos_transaction_builder::aptos_stdlib;
 use aptos_types::transaction::{
     authenticator::AuthenticationKeyPreimage, ChangeSet, ModuleBundle, Script, ScriptFunction,
-    WriteSetPayload,
+    ScriptFunctionBatch, WriteSetPayload,
 };
+pub use multi_agent::{MultiAgentError, MultiAgentTransaction};
+pub use script_function_abi::{ScriptFunctionAbi, ScriptFunctionAbiError};
+
+mod multi_agent;
+mod script_function_abi;
 
 pub struct TransactionBuilder {
     sender: Option<AccountAddress>,
@@ -141,6 +146,10 @@ impl TransactionFactory {
         self.payload(TransactionPayload::ScriptFunction(func))
     }
 
+    pub fn script_function_batch(&self, batch: ScriptFunctionBatch) -> TransactionBuilder {
+        self.payload(TransactionPayload::ScriptFunctionBatch(batch))
+    }
+
     pub fn create_user_account(&self, public_key: &Ed25519PublicKey) -> TransactionBuilder {
         let preimage = AuthenticationKeyPreimage::ed25519(public_key);
         self.payload(aptos_stdlib::encode_create_account_script_function(
@@ -0,0 +1,134 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Multi-agent transactions, signed by secondary signers in addition to their sender. The
+//! signatures can be collected in any order, e.g. by sending the `signing_message` to each
+//! secondary signer and adding the signatures as they come back, and each of them is verified
+//! when it's added, so the transaction is only built once it's signed by all of them. Whether the
+//! keys are the ones of the signers is only checked by the chain, see `add_signature`.
+
+use crate::types::{
+    account_address::AccountAddress,
+    transaction::{
+        authenticator::AccountAuthenticator, RawTransaction, RawTransactionWithData,
+        SignedTransaction,
+    },
+    LocalAccount,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum MultiAgentError {
+    #[error("{0} is not a secondary signer of the transaction")]
+    NotASecondarySigner(AccountAddress),
+    #[error("the signature of {0} doesn't match the transaction")]
+    InvalidSignature(AccountAddress),
+    #[error("the transaction isn't signed by {0:?}")]
+    MissingSignatures(Vec<AccountAddress>),
+}
+
+#[derive(Clone, Debug)]
+pub struct MultiAgentTransaction {
+    message: RawTransactionWithData,
+    raw_txn: RawTransaction,
+    secondary_signer_addresses: Vec<AccountAddress>,
+    sender: Option<AccountAuthenticator>,
+    secondary_signers: Vec<Option<AccountAuthenticator>>,
+}
+
+impl MultiAgentTransaction {
+    pub fn new(raw_txn: RawTransaction, secondary_signer_addresses: Vec<AccountAddress>) -> Self {
+        let message = RawTransactionWithData::new_multi_agent(
+            raw_txn.clone(),
+            secondary_signer_addresses.clone(),
+        );
+        Self {
+            message,
+            secondary_signers: vec![None; secondary_signer_addresses.len()],
+            raw_txn,
+            secondary_signer_addresses,
+            sender: None,
+        }
+    }
+
+    /// The message signed by the sender and each secondary signer
+    pub fn signing_message(&self) -> &RawTransactionWithData {
+        &self.message
+    }
+
+    pub fn raw_transaction(&self) -> &RawTransaction {
+        &self.raw_txn
+    }
+
+    pub fn secondary_signer_addresses(&self) -> &[AccountAddress] {
+        &self.secondary_signer_addresses
+    }
+
+    /// Adds the signature of the sender or of a secondary signer, once verified.
+    ///
+    /// Only the signature of the message is verified: whether the public key is the one of
+    /// `signer` is left to the chain, as the authentication key of an account can be rotated
+    /// (so it can't be derived from the address), and a transaction signed with the wrong key is
+    /// rejected when it's submitted.
+    pub fn add_signature(
+        &mut self,
+        signer: AccountAddress,
+        authenticator: AccountAuthenticator,
+    ) -> Result<(), MultiAgentError> {
+        authenticator
+            .verify(&self.message)
+            .map_err(|_| MultiAgentError::InvalidSignature(signer))?;
+        if signer == self.raw_txn.sender() {
+            self.sender = Some(authenticator);
+            return Ok(());
+        }
+        let index = self
+            .secondary_signer_addresses
+            .iter()
+            .position(|address| *address == signer)
+            .ok_or(MultiAgentError::NotASecondarySigner(signer))?;
+        self.secondary_signers[index] = Some(authenticator);
+        Ok(())
+    }
+
+    /// Signs the transaction as the sender or as one of the secondary signers
+    pub fn sign(&mut self, account: &LocalAccount) -> Result<(), MultiAgentError> {
+        let authenticator = account.sign_multi_agent_message(&self.message);
+        self.add_signature(account.address(), authenticator)
+    }
+
+    /// The signers whose signatures are still missing, starting with the sender
+    pub fn missing_signers(&self) -> Vec<AccountAddress> {
+        let sender = match self.sender {
+            Some(_) => None,
+            None => Some(self.raw_txn.sender()),
+        };
+        sender
+            .into_iter()
+            .chain(
+                self.secondary_signer_addresses
+                    .iter()
+                    .zip(&self.secondary_signers)
+                    .filter(|(_, authenticator)| authenticator.is_none())
+                    .map(|(address, _)| *address),
+            )
+            .collect()
+    }
+
+    pub fn into_signed_transaction(self) -> Result<SignedTransaction, MultiAgentError> {
+        let missing_signers = self.missing_signers();
+        let secondary_signers = self
+            .secondary_signers
+            .into_iter()
+            .collect::<Option<Vec<_>>>();
+        match (self.sender, secondary_signers) {
+            (Some(sender), Some(secondary_signers)) => Ok(SignedTransaction::new_multi_agent(
+                self.raw_txn,
+                sender,
+                self.secondary_signer_addresses,
+                secondary_signers,
+            )),
+            _ => Err(MultiAgentError::MissingSignatures(missing_signers)),
+        }
+    }
+}
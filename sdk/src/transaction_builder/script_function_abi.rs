@@ -0,0 +1,235 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Calls of arbitrary script functions, checked against the signature of the function in its
+//! module, e.g. as fetched from the API with `aptos_rest_client::Client::get_script_function_abi`.
+//! The arguments are checked and BCS encoded on the client, instead of the transaction being
+//! rejected on chain when they fail to deserialize as the parameters of the function.
+
+use crate::{
+    move_types::{
+        identifier::{IdentStr, Identifier},
+        language_storage::{ModuleId, TypeTag},
+        value::MoveValue,
+    },
+    types::transaction::ScriptFunction,
+};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{SignatureToken, Visibility},
+    CompiledModule,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ScriptFunctionAbiError {
+    #[error("failed to deserialize module: {0}")]
+    InvalidModule(String),
+    #[error("script function {0}::{1} not found")]
+    FunctionNotFound(ModuleId, Identifier),
+    #[error("{function} expects {expected} type arguments, got {actual}")]
+    TypeArgumentCount {
+        function: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("{function} expects {expected} arguments, got {actual}")]
+    ArgumentCount {
+        function: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("argument {index} of {function} must be a {expected}, got {actual}")]
+    ArgumentType {
+        function: String,
+        index: usize,
+        expected: String,
+        actual: String,
+    },
+    #[error(
+        "parameter {index} of {function} of type {param_type} can't be passed by a transaction"
+    )]
+    UnsupportedParameter {
+        function: String,
+        index: usize,
+        param_type: String,
+    },
+}
+
+/// The signature of a script function, without its leading signer parameters
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptFunctionAbi {
+    module: ModuleId,
+    name: Identifier,
+    type_params: usize,
+    params: Vec<SignatureToken>,
+}
+
+impl ScriptFunctionAbi {
+    pub fn from_module_bytes(
+        bytes: &[u8],
+        function: &IdentStr,
+    ) -> Result<Self, ScriptFunctionAbiError> {
+        let module = CompiledModule::deserialize(bytes)
+            .map_err(|e| ScriptFunctionAbiError::InvalidModule(e.to_string()))?;
+        Self::from_module(&module, function)
+    }
+
+    pub fn from_module(
+        module: &CompiledModule,
+        function: &IdentStr,
+    ) -> Result<Self, ScriptFunctionAbiError> {
+        let handle = module
+            .function_defs()
+            .iter()
+            .filter(|def| def.visibility == Visibility::Script)
+            .map(|def| module.function_handle_at(def.function))
+            .find(|handle| module.identifier_at(handle.name) == function)
+            .ok_or_else(|| {
+                ScriptFunctionAbiError::FunctionNotFound(module.self_id(), function.to_owned())
+            })?;
+        let params = module
+            .signature_at(handle.parameters)
+            .0
+            .iter()
+            .skip_while(|param| is_signer(param))
+            .cloned()
+            .collect();
+        Ok(Self {
+            module: module.self_id(),
+            name: function.to_owned(),
+            type_params: handle.type_parameters.len(),
+            params,
+        })
+    }
+
+    pub fn module(&self) -> &ModuleId {
+        &self.module
+    }
+
+    pub fn name(&self) -> &IdentStr {
+        &self.name
+    }
+
+    /// Builds the call of the function, once the number of type arguments and the number and
+    /// the types of the arguments are checked against its signature.
+    pub fn call(
+        &self,
+        ty_args: Vec<TypeTag>,
+        args: Vec<MoveValue>,
+    ) -> Result<ScriptFunction, ScriptFunctionAbiError> {
+        let function = format!(
+            "0x{}::{}::{}",
+            self.module.address().short_str_lossless(),
+            self.module.name(),
+            self.name
+        );
+        if ty_args.len() != self.type_params {
+            return Err(ScriptFunctionAbiError::TypeArgumentCount {
+                function,
+                expected: self.type_params,
+                actual: ty_args.len(),
+            });
+        }
+        if args.len() != self.params.len() {
+            return Err(ScriptFunctionAbiError::ArgumentCount {
+                function,
+                expected: self.params.len(),
+                actual: args.len(),
+            });
+        }
+
+        let mut encoded_args = vec![];
+        for (index, (param, arg)) in self.params.iter().zip(&args).enumerate() {
+            let (param, param_type) = instantiate(param, &ty_args)
+                .and_then(|ty| type_name(&ty).map(|name| (ty, name)))
+                .ok_or_else(|| ScriptFunctionAbiError::UnsupportedParameter {
+                    function: function.clone(),
+                    index,
+                    param_type: format!("{:?}", param),
+                })?;
+            if !is_instance_of(arg, &param) {
+                return Err(ScriptFunctionAbiError::ArgumentType {
+                    function,
+                    index,
+                    expected: param_type,
+                    actual: value_type_name(arg),
+                });
+            }
+            encoded_args.push(
+                arg.simple_serialize()
+                    .expect("transaction arguments serialize"),
+            );
+        }
+        Ok(ScriptFunction::new(
+            self.module.clone(),
+            self.name.clone(),
+            ty_args,
+            encoded_args,
+        ))
+    }
+}
+
+// Only the signers passed by value are given by the transaction, a `&signer` parameter is left
+// to the arguments and can't be passed.
+fn is_signer(token: &SignatureToken) -> bool {
+    matches!(token, SignatureToken::Signer)
+}
+
+/// Returns the type of the parameter once its type parameters are replaced by the type arguments
+fn instantiate(token: &SignatureToken, ty_args: &[TypeTag]) -> Option<TypeTag> {
+    Some(match token {
+        SignatureToken::Bool => TypeTag::Bool,
+        SignatureToken::U8 => TypeTag::U8,
+        SignatureToken::U64 => TypeTag::U64,
+        SignatureToken::U128 => TypeTag::U128,
+        SignatureToken::Address => TypeTag::Address,
+        SignatureToken::Signer => TypeTag::Signer,
+        SignatureToken::Vector(inner) => TypeTag::Vector(Box::new(instantiate(inner, ty_args)?)),
+        SignatureToken::TypeParameter(index) => ty_args.get(*index as usize)?.clone(),
+        _ => return None,
+    })
+}
+
+/// Returns the name of the type of the parameter, if a transaction can pass it
+fn type_name(ty: &TypeTag) -> Option<String> {
+    Some(match ty {
+        TypeTag::Bool => "bool".to_owned(),
+        TypeTag::U8 => "u8".to_owned(),
+        TypeTag::U64 => "u64".to_owned(),
+        TypeTag::U128 => "u128".to_owned(),
+        TypeTag::Address => "address".to_owned(),
+        TypeTag::Vector(inner) => format!("vector<{}>", type_name(inner)?),
+        _ => return None,
+    })
+}
+
+fn is_instance_of(value: &MoveValue, ty: &TypeTag) -> bool {
+    match (value, ty) {
+        (MoveValue::Bool(_), TypeTag::Bool)
+        | (MoveValue::U8(_), TypeTag::U8)
+        | (MoveValue::U64(_), TypeTag::U64)
+        | (MoveValue::U128(_), TypeTag::U128)
+        | (MoveValue::Address(_), TypeTag::Address) => true,
+        (MoveValue::Vector(values), TypeTag::Vector(inner)) => {
+            values.iter().all(|value| is_instance_of(value, inner))
+        }
+        _ => false,
+    }
+}
+
+fn value_type_name(value: &MoveValue) -> String {
+    match value {
+        MoveValue::Bool(_) => "bool".to_owned(),
+        MoveValue::U8(_) => "u8".to_owned(),
+        MoveValue::U64(_) => "u64".to_owned(),
+        MoveValue::U128(_) => "u128".to_owned(),
+        MoveValue::Address(_) => "address".to_owned(),
+        MoveValue::Signer(_) => "signer".to_owned(),
+        MoveValue::Vector(values) => match values.first() {
+            Some(value) => format!("vector<{}>", value_type_name(value)),
+            None => "vector".to_owned(),
+        },
+        MoveValue::Struct(_) => "struct".to_owned(),
+    }
+}
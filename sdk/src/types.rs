@@ -4,12 +4,15 @@
 use crate::{
     crypto::{
         ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-        traits::Uniform,
+        traits::{SigningKey, Uniform},
     },
-    transaction_builder::TransactionBuilder,
+    transaction_builder::{MultiAgentTransaction, TransactionBuilder},
     types::{
         account_address::AccountAddress,
-        transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+        transaction::{
+            authenticator::{AccountAuthenticator, AuthenticationKey},
+            RawTransaction, RawTransactionWithData, SignedTransaction,
+        },
     },
 };
 
@@ -90,6 +93,32 @@ impl LocalAccount {
             .into_inner()
     }
 
+    /// Builds a multi-agent transaction signed by the account as its sender, the signatures of
+    /// the secondary signers are added to it afterwards
+    pub fn multi_agent_with_transaction_builder(
+        &mut self,
+        secondary_signer_addresses: Vec<AccountAddress>,
+        builder: TransactionBuilder,
+    ) -> MultiAgentTransaction {
+        let raw_txn = builder
+            .sender(self.address())
+            .sequence_number(self.sequence_number())
+            .build();
+        *self.sequence_number_mut() += 1;
+        let mut txn = MultiAgentTransaction::new(raw_txn, secondary_signer_addresses);
+        txn.sign(self)
+            .expect("Signing a txn as its sender can't fail");
+        txn
+    }
+
+    /// Signs the message of a multi-agent transaction, as its sender or as a secondary signer
+    pub fn sign_multi_agent_message(
+        &self,
+        message: &RawTransactionWithData,
+    ) -> AccountAuthenticator {
+        AccountAuthenticator::ed25519(self.public_key().clone(), self.private_key().sign(message))
+    }
+
     pub fn address(&self) -> AccountAddress {
         self.address
     }
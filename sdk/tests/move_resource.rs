@@ -0,0 +1,79 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_sdk::{
+    move_resource::{move_bytes, move_u64, MoveResource, MoveStructType},
+    move_types::move_resource::MoveStructType as _,
+};
+use serde::Deserialize;
+use std::marker::PhantomData;
+
+#[derive(Debug, Deserialize, MoveStructType, PartialEq)]
+#[move_module = "TestCoin"]
+#[move_struct = "Coin"]
+struct Coin {
+    #[serde(with = "move_u64")]
+    value: u64,
+}
+
+#[derive(Debug, Deserialize, MoveResource, PartialEq)]
+#[move_module = "TestCoin"]
+#[move_struct = "Balance"]
+struct Balance {
+    coin: Coin,
+}
+
+#[derive(Debug, Deserialize, MoveStructType, PartialEq)]
+#[move_address = "0xcafe"]
+#[move_module = "Coins"]
+#[move_struct = "Token"]
+struct Token {
+    dummy_field: bool,
+}
+
+#[derive(Debug, Deserialize, MoveResource, PartialEq)]
+#[move_address = "0xcafe"]
+#[move_module = "Coins"]
+#[move_struct = "Treasury"]
+struct Treasury<CoinType> {
+    #[serde(with = "move_bytes")]
+    metadata: Vec<u8>,
+    #[serde(skip)]
+    coin_type: PhantomData<CoinType>,
+}
+
+#[test]
+fn test_struct_tags() {
+    assert_eq!(Balance::struct_tag().to_string(), "0x1::TestCoin::Balance");
+    assert_eq!(
+        Treasury::<Token>::struct_tag().to_string(),
+        "0xcafe::Coins::Treasury<0xcafe::Coins::Token>"
+    );
+}
+
+#[test]
+fn test_deserialize_json_and_bcs() {
+    let balance = Balance {
+        coin: Coin { value: 100 },
+    };
+    let json = serde_json::json!({"coin": {"value": "100"}});
+    assert_eq!(serde_json::from_value::<Balance>(json).unwrap(), balance);
+    assert_eq!(
+        bcs::from_bytes::<Balance>(&bcs::to_bytes(&100u64).unwrap()).unwrap(),
+        balance
+    );
+
+    let treasury = Treasury::<Token> {
+        metadata: vec![0xab, 0xcd],
+        coin_type: PhantomData,
+    };
+    let json = serde_json::json!({"metadata": "0xabcd"});
+    assert_eq!(
+        serde_json::from_value::<Treasury<Token>>(json).unwrap(),
+        treasury
+    );
+    assert_eq!(
+        bcs::from_bytes::<Treasury<Token>>(&bcs::to_bytes(&vec![0xabu8, 0xcd]).unwrap()).unwrap(),
+        treasury
+    );
+}
@@ -0,0 +1,70 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::{MultiAgentError, TransactionFactory},
+    types::{chain_id::ChainId, LocalAccount},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn accounts() -> (LocalAccount, LocalAccount, LocalAccount) {
+    let mut rng = StdRng::seed_from_u64(0);
+    (
+        LocalAccount::generate(&mut rng),
+        LocalAccount::generate(&mut rng),
+        LocalAccount::generate(&mut rng),
+    )
+}
+
+#[test]
+fn test_multi_agent_signatures_collected_in_any_order() {
+    let (mut sender, first_signer, second_signer) = accounts();
+    let builder = TransactionFactory::new(ChainId::test()).transfer(AccountAddress::random(), 1);
+    let mut txn = sender.multi_agent_with_transaction_builder(
+        vec![first_signer.address(), second_signer.address()],
+        builder,
+    );
+    assert_eq!(sender.sequence_number(), 1);
+    assert_eq!(
+        txn.missing_signers(),
+        vec![first_signer.address(), second_signer.address()]
+    );
+
+    // The signatures of the secondary signers may come back out of order.
+    let signature = second_signer.sign_multi_agent_message(txn.signing_message());
+    txn.add_signature(second_signer.address(), signature)
+        .unwrap();
+    assert_eq!(
+        txn.clone().into_signed_transaction().unwrap_err(),
+        MultiAgentError::MissingSignatures(vec![first_signer.address()])
+    );
+    txn.sign(&first_signer).unwrap();
+
+    let signed_txn = txn.into_signed_transaction().unwrap();
+    assert_eq!(signed_txn.sender(), sender.address());
+    signed_txn.check_signature().unwrap();
+}
+
+#[test]
+fn test_multi_agent_invalid_signatures() {
+    let (mut sender, signer, other) = accounts();
+    let builder = TransactionFactory::new(ChainId::test()).transfer(AccountAddress::random(), 1);
+    let mut txn = sender.multi_agent_with_transaction_builder(vec![signer.address()], builder);
+
+    assert_eq!(
+        txn.sign(&other),
+        Err(MultiAgentError::NotASecondarySigner(other.address()))
+    );
+
+    // The signature of another transaction of the same signers is rejected.
+    let other_txn = sender.multi_agent_with_transaction_builder(
+        vec![signer.address()],
+        TransactionFactory::new(ChainId::test()).transfer(AccountAddress::random(), 2),
+    );
+    let signature = signer.sign_multi_agent_message(other_txn.signing_message());
+    assert_eq!(
+        txn.add_signature(signer.address(), signature),
+        Err(MultiAgentError::InvalidSignature(signer.address()))
+    );
+}
@@ -0,0 +1,206 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_sdk::{
+    move_types::{
+        account_address::AccountAddress, ident_str, language_storage::TypeTag, value::MoveValue,
+    },
+    transaction_builder::{ScriptFunctionAbi, ScriptFunctionAbiError},
+};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{
+        AbilitySet, AddressIdentifierIndex, CompiledModule, FunctionDefinition, FunctionHandle,
+        FunctionHandleIndex, IdentifierIndex, ModuleHandle, ModuleHandleIndex, Signature,
+        SignatureIndex, SignatureToken, Visibility,
+    },
+    file_format_common::VERSION_MAX,
+};
+
+fn test_coin_transfer_abi() -> ScriptFunctionAbi {
+    let module = cached_framework_packages::modules()
+        .iter()
+        .find(|module| module.self_id().name().as_str() == "TestCoin")
+        .unwrap();
+    ScriptFunctionAbi::from_module(module, ident_str!("transfer")).unwrap()
+}
+
+#[test]
+fn test_call_checked_against_abi() {
+    let abi = test_coin_transfer_abi();
+    let to = AccountAddress::random();
+    let call = abi
+        .call(vec![], vec![MoveValue::Address(to), MoveValue::U64(100)])
+        .unwrap();
+    assert_eq!(call.function().as_str(), "transfer");
+    assert_eq!(
+        call.args().to_vec(),
+        vec![bcs::to_bytes(&to).unwrap(), bcs::to_bytes(&100u64).unwrap()]
+    );
+}
+
+#[test]
+fn test_call_with_invalid_arguments() {
+    let abi = test_coin_transfer_abi();
+    let function = "0x1::TestCoin::transfer".to_owned();
+    assert_eq!(
+        abi.call(vec![], vec![MoveValue::U64(100)]),
+        Err(ScriptFunctionAbiError::ArgumentCount {
+            function: function.clone(),
+            expected: 2,
+            actual: 1,
+        })
+    );
+    assert_eq!(
+        abi.call(vec![], vec![MoveValue::U64(1), MoveValue::U64(100)]),
+        Err(ScriptFunctionAbiError::ArgumentType {
+            function: function.clone(),
+            index: 0,
+            expected: "address".to_owned(),
+            actual: "u64".to_owned(),
+        })
+    );
+    assert_eq!(
+        abi.call(
+            vec![TypeTag::U64],
+            vec![MoveValue::Address(AccountAddress::ONE), MoveValue::U64(100)]
+        ),
+        Err(ScriptFunctionAbiError::TypeArgumentCount {
+            function,
+            expected: 0,
+            actual: 1,
+        })
+    );
+}
+
+#[test]
+fn test_function_not_found() {
+    let module = cached_framework_packages::modules()
+        .iter()
+        .find(|module| module.self_id().name().as_str() == "TestCoin")
+        .unwrap();
+    // `balance_of` is public but not a script function.
+    assert_eq!(
+        ScriptFunctionAbi::from_module(module, ident_str!("balance_of")),
+        Err(ScriptFunctionAbiError::FunctionNotFound(
+            module.self_id(),
+            ident_str!("balance_of").to_owned()
+        ))
+    );
+}
+
+/// A module `0x1::M` with the script functions `generic<T>(signer, T, vector<T>)` and
+/// `by_reference(&signer, u64)`
+fn module_with_script_functions() -> CompiledModule {
+    let function = |name: u16, parameters: u16, type_parameters| FunctionHandle {
+        module: ModuleHandleIndex(0),
+        name: IdentifierIndex(name),
+        parameters: SignatureIndex(parameters),
+        return_: SignatureIndex(0),
+        type_parameters,
+    };
+    let definition = |function: u16| FunctionDefinition {
+        function: FunctionHandleIndex(function),
+        visibility: Visibility::Script,
+        acquires_global_resources: vec![],
+        code: None,
+    };
+    CompiledModule {
+        version: VERSION_MAX,
+        self_module_handle_idx: ModuleHandleIndex(0),
+        module_handles: vec![ModuleHandle {
+            address: AddressIdentifierIndex(0),
+            name: IdentifierIndex(0),
+        }],
+        function_handles: vec![
+            function(1, 1, vec![AbilitySet::EMPTY]),
+            function(2, 2, vec![]),
+        ],
+        signatures: vec![
+            Signature(vec![]),
+            Signature(vec![
+                SignatureToken::Signer,
+                SignatureToken::TypeParameter(0),
+                SignatureToken::Vector(Box::new(SignatureToken::TypeParameter(0))),
+            ]),
+            Signature(vec![
+                SignatureToken::Reference(Box::new(SignatureToken::Signer)),
+                SignatureToken::U64,
+            ]),
+        ],
+        identifiers: vec![
+            ident_str!("M").to_owned(),
+            ident_str!("generic").to_owned(),
+            ident_str!("by_reference").to_owned(),
+        ],
+        address_identifiers: vec![AccountAddress::ONE],
+        function_defs: vec![definition(0), definition(1)],
+        ..CompiledModule::default()
+    }
+}
+
+#[test]
+fn test_call_with_type_parameters() {
+    let abi =
+        ScriptFunctionAbi::from_module(&module_with_script_functions(), ident_str!("generic"))
+            .unwrap();
+    let args = vec![
+        MoveValue::U64(1),
+        MoveValue::Vector(vec![MoveValue::U64(2)]),
+    ];
+    let call = abi.call(vec![TypeTag::U64], args.clone()).unwrap();
+    assert_eq!(
+        call.args().to_vec(),
+        vec![
+            bcs::to_bytes(&1u64).unwrap(),
+            bcs::to_bytes(&vec![2u64]).unwrap()
+        ]
+    );
+    // the arguments are checked against the instantiated parameters
+    assert_eq!(
+        abi.call(vec![TypeTag::Address], args),
+        Err(ScriptFunctionAbiError::ArgumentType {
+            function: "0x1::M::generic".to_owned(),
+            index: 0,
+            expected: "address".to_owned(),
+            actual: "u64".to_owned(),
+        })
+    );
+    assert_eq!(
+        abi.call(
+            vec![TypeTag::Signer],
+            vec![MoveValue::U64(1), MoveValue::Vector(vec![])]
+        ),
+        Err(ScriptFunctionAbiError::UnsupportedParameter {
+            function: "0x1::M::generic".to_owned(),
+            index: 0,
+            param_type: "TypeParameter(0)".to_owned(),
+        })
+    );
+}
+
+#[test]
+fn test_signer_reference_not_passed_by_transaction() {
+    let abi =
+        ScriptFunctionAbi::from_module(&module_with_script_functions(), ident_str!("by_reference"))
+            .unwrap();
+    assert_eq!(
+        abi.call(vec![], vec![MoveValue::U64(1)]),
+        Err(ScriptFunctionAbiError::ArgumentCount {
+            function: "0x1::M::by_reference".to_owned(),
+            expected: 2,
+            actual: 1,
+        })
+    );
+    assert_eq!(
+        abi.call(
+            vec![],
+            vec![MoveValue::Address(AccountAddress::ONE), MoveValue::U64(1)]
+        ),
+        Err(ScriptFunctionAbiError::UnsupportedParameter {
+            function: "0x1::M::by_reference".to_owned(),
+            index: 0,
+            param_type: "Reference(Signer)".to_owned(),
+        })
+    );
+}
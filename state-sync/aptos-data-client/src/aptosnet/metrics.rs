@@ -80,6 +80,16 @@ pub static OPTIMAL_CHUNK_SIZES: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Gauge for the scores and stats used to choose the peers for requests
+pub static PEER_SCORES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_data_client_peer_scores",
+        "Gauge related to the scores and stats of each peer",
+        &["peer", "score_type"]
+    )
+    .unwrap()
+});
+
 /// An enum representing the various types of data that can be
 /// fetched via the data client.
 pub enum DataType {
@@ -109,6 +119,38 @@ impl DataType {
     }
 }
 
+/// An enum representing the various scores and stats tracked for
+/// each peer.
+pub enum PeerScoreType {
+    ErrorRatePercent,
+    LatencyMs,
+    Score,
+    SelectionWeightPercent,
+    VersionLag,
+}
+
+impl PeerScoreType {
+    pub fn all() -> [PeerScoreType; 5] {
+        [
+            PeerScoreType::ErrorRatePercent,
+            PeerScoreType::LatencyMs,
+            PeerScoreType::Score,
+            PeerScoreType::SelectionWeightPercent,
+            PeerScoreType::VersionLag,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PeerScoreType::ErrorRatePercent => "error_rate_percent",
+            PeerScoreType::LatencyMs => "latency_ms",
+            PeerScoreType::Score => "score",
+            PeerScoreType::SelectionWeightPercent => "selection_weight_percent",
+            PeerScoreType::VersionLag => "version_lag",
+        }
+    }
+}
+
 /// Increments the given counter with the provided label values.
 pub fn increment_counter(counter: &Lazy<IntCounterVec>, label: String) {
     counter.with_label_values(&[&label]).inc();
@@ -120,6 +162,13 @@ pub fn set_gauge(counter: &Lazy<IntGaugeVec>, label: String, value: u64) {
     counter.with_label_values(&[&label]).set(value as i64);
 }
 
+/// Removes the scores and stats of the given peer (if any)
+pub fn remove_peer_scores(peer: &str) {
+    for score_type in PeerScoreType::all() {
+        let _ = PEER_SCORES.remove_label_values(&[peer, score_type.as_str()]);
+    }
+}
+
 /// Starts the timer for the provided histogram and label values.
 pub fn start_timer(histogram: &Lazy<HistogramVec>, label: String) -> HistogramTimer {
     histogram.with_label_values(&[&label]).start_timer()
@@ -30,7 +30,6 @@ use network::{
     application::interface::NetworkInterface,
     protocols::{rpc::error::RpcError, wire::handshake::v1::ProtocolId},
 };
-use rand::seq::SliceRandom;
 use std::{convert::TryFrom, fmt, sync::Arc, time::Duration};
 use storage_service_client::StorageServiceClient;
 use storage_service_types::{
@@ -79,6 +78,8 @@ pub struct AptosNetDataClient {
     global_summary_cache: Arc<RwLock<GlobalDataSummary>>,
     /// Used for generating the next request/response id.
     response_id_generator: Arc<U64IdGenerator>,
+    /// Used for measuring the response latencies of the peers.
+    time_service: TimeService,
 }
 
 impl AptosNetDataClient {
@@ -94,6 +95,7 @@ impl AptosNetDataClient {
             peer_states: Arc::new(RwLock::new(PeerStates::new(storage_service_config))),
             global_summary_cache: Arc::new(RwLock::new(GlobalDataSummary::empty())),
             response_id_generator: Arc::new(U64IdGenerator::new()),
+            time_service: time_service.clone(),
         };
         let poller = DataSummaryPoller::new(
             time_service,
@@ -119,6 +121,15 @@ impl AptosNetDataClient {
         *self.global_summary_cache.write() = aggregate;
     }
 
+    /// Update the peer score metrics of the connected peers, and remove the
+    /// metrics of the disconnected ones.
+    fn update_peer_score_metrics(&self) {
+        let connected_peers = self.get_all_connected_peers().unwrap_or_default();
+        self.peer_states
+            .read()
+            .update_peer_score_metrics(&connected_peers);
+    }
+
     /// Choose a connected peer that can service the given request. Returns an
     /// error if no such peer can be found.
     fn choose_peer_for_request(
@@ -134,10 +145,10 @@ impl AptosNetDataClient {
             .filter(|peer| internal_peer_states.can_service_request(peer, request))
             .collect::<Vec<_>>();
 
-        // Choose a random peer from those that can service the request
-        serviceable_peers
-            .choose(&mut rand::thread_rng())
-            .copied()
+        // Choose a peer from those that can service the request, biased
+        // towards the best performing peers
+        internal_peer_states
+            .choose_peer(&serviceable_peers)
             .ok_or_else(|| {
                 Error::DataIsUnavailable(
                     "No connected peers are advertising that they can serve this data!".to_owned(),
@@ -261,6 +272,7 @@ impl AptosNetDataClient {
 
         increment_counter(&metrics::SENT_REQUESTS, request.get_label().into());
 
+        let start_time = self.time_service.now();
         let result = self
             .network_client
             .send_request(
@@ -269,9 +281,13 @@ impl AptosNetDataClient {
                 Duration::from_millis(self.data_client_config.response_timeout_ms),
             )
             .await;
+        let latency = self
+            .time_service
+            .now()
+            .saturating_duration_since(start_time);
 
         match result {
-            Ok(response) => {
+            Ok((response, num_bytes)) => {
                 debug!(
                     (LogSchema::new(LogEntry::StorageServiceResponse)
                         .event(LogEvent::ResponseSuccess)
@@ -288,7 +304,9 @@ impl AptosNetDataClient {
                 // On the one hand, scoring dynamics are simpler when each request
                 // is successful or failed but not both; on the other hand, this
                 // feels simpler for the consumer.
-                self.peer_states.write().update_score_success(peer);
+                let mut peer_states = self.peer_states.write();
+                peer_states.update_latency(peer, latency);
+                peer_states.update_score_success(peer);
 
                 // Package up all of the context needed to fully report an error
                 // with this RPC.
@@ -300,6 +318,7 @@ impl AptosNetDataClient {
                 };
                 let context = ResponseContext {
                     id,
+                    num_bytes: num_bytes as u64,
                     response_callback: Box::new(response_callback),
                 };
                 Ok(Response::new(context, response))
@@ -311,7 +330,11 @@ impl AptosNetDataClient {
                 let client_err = match err {
                     storage_service_client::Error::RpcError(err) => match err {
                         RpcError::NotConnected(_) => Error::DataIsUnavailable(err.to_string()),
-                        RpcError::TimedOut => Error::TimeoutWaitingForResponse(err.to_string()),
+                        RpcError::TimedOut => {
+                            // Timeouts count towards the latency of slow peers
+                            self.peer_states.write().update_latency(peer, latency);
+                            Error::TimeoutWaitingForResponse(err.to_string())
+                        }
                         _ => Error::UnexpectedErrorEncountered(err.to_string()),
                     },
                     storage_service_client::Error::StorageServiceError(err) => {
@@ -484,6 +507,9 @@ impl DataSummaryPoller {
             // Wait for next round before polling
             ticker.next().await;
 
+            // Update the peer score metrics (removing those of disconnected peers)
+            self.data_client.update_peer_score_metrics();
+
             // Fetch the peers to poll
             let peers_to_poll = match self.data_client.fetch_peers_to_poll() {
                 Ok(peers_to_poll) => peers_to_poll,
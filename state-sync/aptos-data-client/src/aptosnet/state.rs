@@ -2,14 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    aptosnet::logging::{LogEntry, LogEvent, LogSchema},
+    aptosnet::{
+        logging::{LogEntry, LogEvent, LogSchema},
+        metrics,
+    },
     AdvertisedData, GlobalDataSummary, OptimalChunkSizes, ResponseError,
 };
 use aptos_config::{config::StorageServiceConfig, network_id::PeerNetworkId};
 use aptos_logger::debug;
+use aptos_types::transaction::Version;
+use rand::seq::SliceRandom;
 use std::{
     cmp::min,
     collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
 };
 use storage_service_types::{StorageServerSummary, StorageServiceRequest};
 
@@ -26,6 +32,18 @@ const MALICIOUS_MULTIPLIER: f64 = 0.8;
 /// Ignore a peer when their score dips below this threshold.
 const IGNORE_PEER_THRESHOLD: f64 = 25.0;
 
+/// The weight of a new sample in the moving averages of latency and errors.
+const MOVING_AVERAGE_WEIGHT: f64 = 0.1;
+/// Peers responding within this latency are chosen equally often. Slower
+/// peers are chosen proportionally less often.
+const PREFERRED_LATENCY_SECS: f64 = 0.1;
+/// Peers lagging this many versions behind the highest advertised version
+/// are chosen half as often.
+const HALF_WEIGHT_VERSION_LAG: f64 = 10_000.0;
+/// The minimum selection weight, so that even the worst peers are chosen
+/// once in a while (and get the chance to improve their stats).
+const MIN_SELECTION_WEIGHT: f64 = 0.01;
+
 pub(crate) enum ErrorType {
     /// A response or error that's not actively malicious but also doesn't help
     /// us make progress, e.g., timeouts, remote errors, invalid data, etc...
@@ -53,6 +71,12 @@ struct PeerState {
     storage_summary: Option<StorageServerSummary>,
     /// For now, a simplified port of the original state-sync v1 scoring system.
     score: f64,
+    /// The moving average of the peer's response latency (in seconds), or
+    /// `None` if we haven't received a response from them yet.
+    average_latency_secs: Option<f64>,
+    /// The moving average of the peer's errors, from 0 (no errors) to 1 (only
+    /// errors).
+    error_rate: f64,
 }
 
 impl Default for PeerState {
@@ -60,6 +84,8 @@ impl Default for PeerState {
         Self {
             storage_summary: None,
             score: STARTING_SCORE,
+            average_latency_secs: None,
+            error_rate: 0.0,
         }
     }
 }
@@ -79,9 +105,55 @@ impl PeerState {
         }
     }
 
+    /// Returns the version of the latest synced ledger info advertised by the peer
+    fn synced_version(&self) -> Option<Version> {
+        self.storage_summary
+            .as_ref()?
+            .data_summary
+            .synced_ledger_info
+            .as_ref()
+            .map(|ledger_info| ledger_info.ledger_info().version())
+    }
+
+    /// Returns the number of versions the peer lags behind the given highest
+    /// synced version (0 if unknown).
+    fn version_lag(&self, highest_synced_version: Option<Version>) -> u64 {
+        match (highest_synced_version, self.synced_version()) {
+            (Some(highest_synced_version), Some(synced_version)) => {
+                highest_synced_version.saturating_sub(synced_version)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Returns the relative weight with which the peer should be chosen for
+    /// a request, according to its latency, error rate and data freshness.
+    fn selection_weight(&self, highest_synced_version: Option<Version>) -> f64 {
+        let latency_weight = self.average_latency_secs.map_or(1.0, |latency_secs| {
+            PREFERRED_LATENCY_SECS / f64::max(latency_secs, PREFERRED_LATENCY_SECS)
+        });
+        let error_weight = 1.0 - self.error_rate;
+        let freshness_weight =
+            1.0 / (1.0 + self.version_lag(highest_synced_version) as f64 / HALF_WEIGHT_VERSION_LAG);
+        f64::max(
+            latency_weight * error_weight * freshness_weight,
+            MIN_SELECTION_WEIGHT,
+        )
+    }
+
+    /// Updates the average latency of the peer with a new response latency
+    fn update_latency(&mut self, latency: Duration) {
+        let latency_secs = latency.as_secs_f64();
+        self.average_latency_secs = Some(match self.average_latency_secs {
+            Some(average_latency_secs) => moving_average(average_latency_secs, latency_secs),
+            None => latency_secs,
+        });
+    }
+
     /// Updates the score of the peer according to a successful operation
     fn update_score_success(&mut self) {
         self.score = f64::min(self.score + SUCCESSFUL_RESPONSE_DELTA, MAX_SCORE);
+        self.error_rate = moving_average(self.error_rate, 0.0);
     }
 
     /// Updates the score of the peer according to an error
@@ -91,6 +163,7 @@ impl PeerState {
             ErrorType::Malicious => MALICIOUS_MULTIPLIER,
         };
         self.score = f64::max(self.score * multiplier, MIN_SCORE);
+        self.error_rate = moving_average(self.error_rate, 1.0);
     }
 }
 
@@ -136,6 +209,38 @@ impl PeerStates {
             .unwrap_or(false)
     }
 
+    /// Chooses one of the given peers at random, biased towards the peers with
+    /// low latency, few errors and fresh data. Returns `None` iff no peers are
+    /// given.
+    pub fn choose_peer(&self, peers: &[PeerNetworkId]) -> Option<PeerNetworkId> {
+        let highest_synced_version = self.highest_synced_version();
+        peers
+            .choose_weighted(&mut rand::thread_rng(), |peer| {
+                self.peer_to_state
+                    .get(peer)
+                    .map_or(1.0, |state| state.selection_weight(highest_synced_version))
+            })
+            .ok()
+            .copied()
+    }
+
+    /// Returns the highest synced version advertised by the unignored peers
+    fn highest_synced_version(&self) -> Option<Version> {
+        self.peer_to_state
+            .values()
+            .filter(|state| state.storage_summary_if_not_ignored().is_some())
+            .filter_map(PeerState::synced_version)
+            .max()
+    }
+
+    /// Updates the average latency of the peer with a new response latency
+    pub fn update_latency(&mut self, peer: PeerNetworkId, latency: Duration) {
+        self.peer_to_state
+            .entry(peer)
+            .or_default()
+            .update_latency(latency);
+    }
+
     /// Updates the score of the peer according to a successful operation
     pub fn update_score_success(&mut self, peer: PeerNetworkId) {
         let old_score = self.peer_to_state.entry(peer).or_default().score;
@@ -172,6 +277,42 @@ impl PeerStates {
         }
     }
 
+    /// Updates the peer score metrics of the connected peers, and removes
+    /// the metrics of the peers that have disconnected.
+    pub fn update_peer_score_metrics(&self, connected_peers: &[PeerNetworkId]) {
+        let highest_synced_version = self.highest_synced_version();
+        for (peer, state) in self.peer_to_state.iter() {
+            let peer_is_connected = connected_peers.contains(peer);
+            let peer = peer.to_string();
+            if !peer_is_connected {
+                metrics::remove_peer_scores(&peer);
+                continue;
+            }
+            let set_score = |score_type: metrics::PeerScoreType, value: f64| {
+                metrics::PEER_SCORES
+                    .with_label_values(&[&peer, score_type.as_str()])
+                    .set(value.round() as i64);
+            };
+            set_score(metrics::PeerScoreType::Score, state.score);
+            set_score(
+                metrics::PeerScoreType::LatencyMs,
+                state.average_latency_secs.unwrap_or_default() * 1000.0,
+            );
+            set_score(
+                metrics::PeerScoreType::ErrorRatePercent,
+                state.error_rate * 100.0,
+            );
+            set_score(
+                metrics::PeerScoreType::VersionLag,
+                state.version_lag(highest_synced_version) as f64,
+            );
+            set_score(
+                metrics::PeerScoreType::SelectionWeightPercent,
+                state.selection_weight(highest_synced_version) * 100.0,
+            );
+        }
+    }
+
     /// Marks the given peer as polled
     pub fn add_polled_peer(&mut self, peer: PeerNetworkId) {
         self.polled_peer_queue.push_front(peer);
@@ -292,6 +433,11 @@ pub(crate) fn calculate_optimal_chunk_sizes(
     }
 }
 
+/// Adds a new sample to the given moving average
+fn moving_average(average: f64, sample: f64) -> f64 {
+    average * (1.0 - MOVING_AVERAGE_WEIGHT) + sample * MOVING_AVERAGE_WEIGHT
+}
+
 /// Calculates the median of the given set of values (if it exists)
 /// and returns the median or the specified max value, whichever is
 /// lower.
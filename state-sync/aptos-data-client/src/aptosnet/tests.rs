@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{AptosDataClient, AptosNetDataClient, DataSummaryPoller, Error};
-use crate::aptosnet::state::calculate_optimal_chunk_sizes;
+use crate::aptosnet::state::{calculate_optimal_chunk_sizes, ErrorType};
 use aptos_config::{
     config::{AptosDataClientConfig, StorageServiceConfig},
     network_id::{NetworkId, PeerNetworkId},
@@ -25,7 +25,11 @@ use network::{
     protocols::{network::NewNetworkSender, wire::handshake::v1::ProtocolId},
     transport::ConnectionMetadata,
 };
-use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
 use storage_service_client::{StorageServiceClient, StorageServiceNetworkSender};
 use storage_service_server::network::{NetworkRequest, ResponseSender};
 use storage_service_types::{
@@ -112,6 +116,11 @@ impl MockNetwork {
         PeerNetworkId::new(network_id, peer_id)
     }
 
+    /// Remove the given peer from the network peer DB
+    fn disconnect_peer(&mut self, peer: PeerNetworkId) {
+        self.peer_infos.remove(&peer);
+    }
+
     /// Get the next request sent from the client.
     async fn next_request(&mut self) -> Option<NetworkRequest> {
         match self.peer_mgr_reqs_rx.next().await {
@@ -204,6 +213,15 @@ async fn request_works_only_when_data_available() {
         .await
         .unwrap();
     assert_eq!(response.payload, TransactionListWithProof::new_empty());
+
+    // The response is accounted for the bytes it was received in
+    let message = StorageServiceMessage::Response(Ok(
+        StorageServiceResponse::TransactionsWithProof(TransactionListWithProof::new_empty()),
+    ));
+    assert_eq!(
+        response.context.num_bytes,
+        bcs::to_bytes(&message).unwrap().len() as u64
+    );
 }
 
 #[tokio::test]
@@ -558,3 +576,90 @@ async fn optimal_chunk_size_calculations() {
     );
     assert_eq!(400, optimal_chunk_sizes.transaction_output_chunk_size);
 }
+
+#[tokio::test]
+async fn fast_and_fresh_peers_are_preferred() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new();
+
+    let fast_peer = mock_network.add_connected_peer();
+    let slow_peer = mock_network.add_connected_peer();
+    let lagging_peer = mock_network.add_connected_peer();
+    let failing_peer = mock_network.add_connected_peer();
+
+    // All peers advertise the requested data, but the lagging peer is far
+    // behind the others.
+    client.update_summary(fast_peer, mock_storage_summary(100_000));
+    client.update_summary(slow_peer, mock_storage_summary(100_000));
+    client.update_summary(lagging_peer, mock_storage_summary(50_000));
+    client.update_summary(failing_peer, mock_storage_summary(100_000));
+
+    // Record the behavior of each peer
+    {
+        let mut peer_states = client.peer_states.write();
+        for _ in 0..10 {
+            peer_states.update_latency(fast_peer, Duration::from_millis(10));
+            peer_states.update_latency(slow_peer, Duration::from_secs(1));
+            peer_states.update_latency(lagging_peer, Duration::from_millis(10));
+            peer_states.update_latency(failing_peer, Duration::from_millis(10));
+            peer_states.update_score_error(failing_peer, ErrorType::NotUseful);
+        }
+    }
+
+    // Choose the peers for many requests
+    let request = StorageServiceRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+        proof_version: 50_000,
+        start_version: 0,
+        end_version: 10,
+        include_events: false,
+    });
+    let mut num_choices = HashMap::new();
+    for _ in 0..1000 {
+        let peer = client.choose_peer_for_request(&request).unwrap();
+        *num_choices.entry(peer).or_insert(0u64) += 1;
+    }
+
+    // The fast peer with fresh data should be chosen most often, but the
+    // other peers should still be chosen once in a while.
+    let fast_peer_choices = num_choices[&fast_peer];
+    for peer in [slow_peer, lagging_peer, failing_peer] {
+        let peer_choices = num_choices.get(&peer).copied().unwrap_or_default();
+        assert!(fast_peer_choices > 2 * peer_choices);
+    }
+    assert_eq!(num_choices.values().sum::<u64>(), 1000);
+}
+
+#[tokio::test]
+async fn disconnected_peer_scores_are_removed() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new();
+
+    let peer_1 = mock_network.add_connected_peer();
+    let peer_2 = mock_network.add_connected_peer();
+    client.update_summary(peer_1, mock_storage_summary(100));
+    client.update_summary(peer_2, mock_storage_summary(100));
+
+    // The scores of both peers are reported
+    client.update_peer_score_metrics();
+    assert!(has_peer_scores(peer_1));
+    assert!(has_peer_scores(peer_2));
+
+    // Only the scores of the connected peer are reported
+    mock_network.disconnect_peer(peer_1);
+    client.update_peer_score_metrics();
+    assert!(!has_peer_scores(peer_1));
+    assert!(has_peer_scores(peer_2));
+
+    // The scores are removed even if no peers are connected
+    mock_network.disconnect_peer(peer_2);
+    client.update_peer_score_metrics();
+    assert!(!has_peer_scores(peer_2));
+}
+
+/// Returns true iff any peer score metric is reported for the given peer
+fn has_peer_scores(peer: PeerNetworkId) -> bool {
+    let peer = peer.to_string();
+    aptos_metrics::get_all_metrics()
+        .keys()
+        .any(|metric| metric.starts_with("aptos_data_client_peer_scores") && metric.contains(&peer))
+}
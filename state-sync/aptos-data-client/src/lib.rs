@@ -149,6 +149,8 @@ pub struct ResponseContext {
     /// A unique identifier for this request/response pair. Intended mostly for
     /// debugging.
     pub id: ResponseId,
+    /// The number of bytes the response was received in from the network.
+    pub num_bytes: u64,
     /// A callback for notifying the data-client source about an error with this
     /// response.
     pub response_callback: Box<dyn ResponseCallback>,
@@ -9,12 +9,14 @@ use aptos_types::{
 use async_trait::async_trait;
 use futures::{
     channel::{mpsc, oneshot},
+    lock::{Mutex, MutexGuard},
     stream::FusedStream,
     SinkExt, Stream,
 };
 use serde::{Deserialize, Serialize};
 use std::{
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
@@ -54,9 +56,15 @@ pub fn new_consensus_notifier_listener_pair(
     timeout_ms: u64,
 ) -> (ConsensusNotifier, ConsensusNotificationListener) {
     let (notification_sender, notification_receiver) = mpsc::unbounded();
+    let consensus_observer_gate = ConsensusObserverGate::default();
 
-    let consensus_notifier = ConsensusNotifier::new(notification_sender, timeout_ms);
-    let consensus_listener = ConsensusNotificationListener::new(notification_receiver);
+    let consensus_notifier = ConsensusNotifier::new(
+        notification_sender,
+        timeout_ms,
+        consensus_observer_gate.clone(),
+    );
+    let consensus_listener =
+        ConsensusNotificationListener::new(notification_receiver, consensus_observer_gate);
 
     (consensus_notifier, consensus_listener)
 }
@@ -73,18 +81,29 @@ pub struct ConsensusNotifier {
     /// Timeout for state sync to respond to consensus when handling a commit
     /// notification.
     timeout_ms: u64,
+
+    /// The gate shared with state sync (used by the consensus observer)
+    consensus_observer_gate: ConsensusObserverGate,
 }
 
 impl ConsensusNotifier {
     fn new(
         notification_sender: mpsc::UnboundedSender<ConsensusNotification>,
         timeout_ms: u64,
+        consensus_observer_gate: ConsensusObserverGate,
     ) -> Self {
         ConsensusNotifier {
             notification_sender,
             timeout_ms,
+            consensus_observer_gate,
         }
     }
+
+    /// Returns the gate through which the consensus observer of a fullnode
+    /// takes turns with state sync to write to storage
+    pub fn consensus_observer_gate(&self) -> ConsensusObserverGate {
+        self.consensus_observer_gate.clone()
+    }
 }
 
 #[async_trait]
@@ -166,15 +185,28 @@ impl ConsensusNotificationSender for ConsensusNotifier {
 #[derive(Debug)]
 pub struct ConsensusNotificationListener {
     notification_receiver: mpsc::UnboundedReceiver<ConsensusNotification>,
+
+    /// The gate shared with the consensus observer (if any)
+    consensus_observer_gate: ConsensusObserverGate,
 }
 
 impl ConsensusNotificationListener {
-    fn new(notification_receiver: mpsc::UnboundedReceiver<ConsensusNotification>) -> Self {
+    fn new(
+        notification_receiver: mpsc::UnboundedReceiver<ConsensusNotification>,
+        consensus_observer_gate: ConsensusObserverGate,
+    ) -> Self {
         ConsensusNotificationListener {
             notification_receiver,
+            consensus_observer_gate,
         }
     }
 
+    /// Returns the gate through which state sync takes turns with the
+    /// consensus observer of a fullnode to write to storage
+    pub fn consensus_observer_gate(&self) -> ConsensusObserverGate {
+        self.consensus_observer_gate.clone()
+    }
+
     /// Respond to the commit notification previously sent by consensus.
     pub async fn respond_to_commit_notification(
         &mut self,
@@ -268,9 +300,96 @@ impl ConsensusSyncNotification {
     }
 }
 
+/// Decides which of the consensus observer of a fullnode and state sync writes
+/// to storage, so that only one of them does at a time. The observer holds the
+/// gate while it executes and commits blocks. State sync only takes over when
+/// the gate is free, i.e., it pauses the observer (e.g., when the upstream nodes
+/// are unavailable). The observer then requests to resume once it receives a
+/// commit decision again, and state sync hands back once it has no pending
+/// writes.
+#[derive(Clone, Debug, Default)]
+pub struct ConsensusObserverGate {
+    state: Arc<Mutex<ConsensusObserverGateState>>,
+}
+
+#[derive(Debug, Default)]
+struct ConsensusObserverGateState {
+    // True iff the observer is paused and state sync writes to storage
+    observer_paused: bool,
+    // True iff the paused observer has requested to resume
+    resume_requested: bool,
+}
+
+/// Held by the consensus observer while it writes to storage
+pub struct ConsensusObserverGuard<'a> {
+    _state: MutexGuard<'a, ConsensusObserverGateState>,
+}
+
+impl ConsensusObserverGate {
+    /// Waits for the observer's turn to write to storage. Returns None if the
+    /// observer is paused (in which case it shouldn't write).
+    pub async fn lock_observer(&self) -> Option<ConsensusObserverGuard<'_>> {
+        let state = self.state.lock().await;
+        if state.observer_paused {
+            None
+        } else {
+            Some(ConsensusObserverGuard { _state: state })
+        }
+    }
+
+    /// Requests state sync to resume the paused observer
+    pub async fn request_resume(&self) {
+        let mut state = self.state.lock().await;
+        if state.observer_paused {
+            state.resume_requested = true;
+        }
+    }
+
+    /// Returns true iff the observer is paused (i.e., state sync writes to storage)
+    pub fn is_observer_paused(&self) -> bool {
+        self.state
+            .try_lock()
+            .map_or(false, |state| state.observer_paused)
+    }
+
+    /// Returns true iff the paused observer has requested to resume
+    pub fn is_resume_requested(&self) -> bool {
+        self.state.try_lock().map_or(false, |state| {
+            state.observer_paused && state.resume_requested
+        })
+    }
+
+    /// Pauses the observer so that state sync can write to storage. Returns
+    /// false if the observer is writing to storage (or already paused).
+    pub fn try_pause_observer(&self) -> bool {
+        match self.state.try_lock() {
+            Some(mut state) if !state.observer_paused => {
+                state.observer_paused = true;
+                state.resume_requested = false;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Resumes the paused observer, after which state sync must not write to
+    /// storage. Returns false if the gate is busy (e.g., the observer is
+    /// requesting to resume), in which case this should be retried.
+    pub fn try_resume_observer(&self) -> bool {
+        match self.state.try_lock() {
+            Some(mut state) => {
+                state.observer_paused = false;
+                state.resume_requested = false;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{ConsensusNotification, ConsensusNotificationSender, Error};
+    use crate::{ConsensusNotification, ConsensusNotificationSender, ConsensusObserverGate, Error};
     use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, SigningKey, Uniform};
     use aptos_types::{
         account_address::AccountAddress,
@@ -413,6 +532,36 @@ mod tests {
         assert_err!(notify_result);
     }
 
+    #[test]
+    fn test_consensus_observer_gate() {
+        let gate = ConsensusObserverGate::default();
+
+        // State sync can't pause the observer while it writes to storage
+        let guard = block_on(gate.lock_observer()).unwrap();
+        assert!(!gate.try_pause_observer());
+        assert!(!gate.is_observer_paused());
+        drop(guard);
+
+        // Pause the observer and verify it can't write to storage
+        assert!(gate.try_pause_observer());
+        assert!(gate.is_observer_paused());
+        assert!(block_on(gate.lock_observer()).is_none());
+        assert!(!gate.is_resume_requested());
+
+        // Request to resume and verify the observer can write again once resumed
+        block_on(gate.request_resume());
+        assert!(gate.is_resume_requested());
+        assert!(gate.try_resume_observer());
+        assert!(!gate.is_observer_paused());
+        assert!(!gate.is_resume_requested());
+        assert!(block_on(gate.lock_observer()).is_some());
+
+        // Requests to resume are ignored unless the observer is paused
+        block_on(gate.request_resume());
+        assert!(gate.try_pause_observer());
+        assert!(!gate.is_resume_requested());
+    }
+
     fn create_user_transaction() -> Transaction {
         let private_key = Ed25519PrivateKey::generate_for_testing();
         let public_key = private_key.public_key();
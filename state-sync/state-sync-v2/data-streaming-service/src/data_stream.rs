@@ -8,6 +8,7 @@ use crate::{
         EpochEndingLedgerInfosRequest, NotificationId, NumberOfAccountsRequest,
         TransactionOutputsWithProofRequest, TransactionsWithProofRequest,
     },
+    download_throttle::DownloadThrottle,
     error::Error,
     logging::{LogEntry, LogEvent, LogSchema},
     metrics,
@@ -62,6 +63,9 @@ pub struct DataStream<T> {
     // The data client through which to fetch data from the Aptos network
     aptos_data_client: T,
 
+    // The throttle (shared by all streams) limiting the download rate
+    download_throttle: Arc<DownloadThrottle>,
+
     // The engine for this data stream
     stream_engine: StreamEngine,
 
@@ -103,6 +107,7 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
         data_stream_id: DataStreamId,
         stream_request: &StreamRequest,
         aptos_data_client: T,
+        download_throttle: Arc<DownloadThrottle>,
         notification_id_generator: Arc<U64IdGenerator>,
         advertised_data: &AdvertisedData,
     ) -> Result<(Self, DataStreamListener), Error> {
@@ -122,6 +127,7 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
             config,
             data_stream_id,
             aptos_data_client,
+            download_throttle,
             stream_engine,
             sent_data_requests: None,
             spawned_tasks: vec![],
@@ -203,6 +209,12 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
         &mut self,
         global_data_summary: &GlobalDataSummary,
     ) -> Result<(), Error> {
+        // Hold back new requests if the download rate limit has been reached.
+        // These will be sent on a later progress check.
+        if !self.download_throttle.can_send_requests() {
+            return Ok(());
+        }
+
         // Determine how many requests (at most) can be sent to the network
         let num_sent_requests = self.get_sent_data_requests().len() as u64;
         let max_num_requests_to_send = self
@@ -260,6 +272,7 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
         let join_handle = spawn_request_task(
             data_client_request,
             self.aptos_data_client.clone(),
+            self.download_throttle.clone(),
             pending_client_response.clone(),
         );
         self.spawned_tasks.push(join_handle);
@@ -681,6 +694,7 @@ fn extract_response_error(notification_feedback: &NotificationFeedback) -> Respo
 fn spawn_request_task<T: AptosDataClient + Send + Clone + 'static>(
     data_client_request: DataClientRequest,
     aptos_data_client: T,
+    download_throttle: Arc<DownloadThrottle>,
     pending_response: PendingClientResponse,
 ) -> JoinHandle<()> {
     // Update the requests sent counter
@@ -723,6 +737,7 @@ fn spawn_request_task<T: AptosDataClient + Send + Clone + 'static>(
                     &metrics::RECEIVED_DATA_RESPONSE,
                     response.payload.get_label().into(),
                 );
+                download_throttle.record_downloaded_bytes(response.context.num_bytes);
             }
             Err(error) => {
                 increment_counter(&metrics::RECEIVED_RESPONSE_ERROR, error.get_label().into());
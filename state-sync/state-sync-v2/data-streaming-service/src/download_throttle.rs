@@ -0,0 +1,193 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics;
+use aptos_config::config::DataStreamingServiceConfig;
+use aptos_infallible::Mutex;
+use aptos_types::download_stats::{DownloadStats, DownloadStatsReporter};
+use std::{
+    cmp::min,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+// The window over which the download throughput is measured
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(5);
+
+const SECS_PER_HOUR: u64 = 60 * 60;
+const HOURS_PER_DAY: u64 = 24;
+
+/// Throttles the downloads of all data streams so that the bytes downloaded
+/// from the network don't exceed the configured rate (e.g., so that a syncing
+/// node doesn't saturate the network link of a shared host).
+///
+/// The throttle is a token bucket of bytes, filled at the configured rate
+/// and holding at most one second worth of bytes. The size of a response is
+/// only known once it has been received, so each response is deducted from
+/// the bucket when it arrives (possibly leaving the bucket in debt) and new
+/// data requests are held back until the bucket is refilled.
+#[derive(Debug)]
+pub struct DownloadThrottle {
+    config: DataStreamingServiceConfig,
+    bucket: Mutex<DownloadBucket>,
+}
+
+#[derive(Debug)]
+pub(crate) struct DownloadBucket {
+    // The number of bytes that can be downloaded before throttling (negative
+    // if the received responses have exceeded the rate).
+    available_bytes: i64,
+
+    // The last time the bucket was refilled
+    last_refill_time: Instant,
+
+    // The start of the current throughput window and the bytes downloaded in it
+    window_start_time: Instant,
+    window_downloaded_bytes: u64,
+
+    // The throughput (bytes per second) measured over the last window
+    throughput: u64,
+
+    // The bytes downloaded and the data requests held back since the start
+    downloaded_bytes: u64,
+    throttled_data_requests: u64,
+}
+
+impl DownloadThrottle {
+    pub fn new(config: DataStreamingServiceConfig) -> Self {
+        Self {
+            config,
+            bucket: Mutex::new(DownloadBucket::new(Instant::now())),
+        }
+    }
+
+    /// Returns true iff new data requests can be sent to the network now
+    pub fn can_send_requests(&self) -> bool {
+        let max_bytes_per_sec = self.max_download_bytes_per_sec();
+        let mut bucket = self.bucket.lock();
+        let can_send_requests = bucket.refill(Instant::now(), max_bytes_per_sec);
+        if !can_send_requests {
+            bucket.throttled_data_requests += 1;
+            metrics::THROTTLED_DATA_REQUESTS.inc();
+        }
+        can_send_requests
+    }
+
+    /// Accounts for a response of `num_bytes` downloaded from the network
+    pub fn record_downloaded_bytes(&self, num_bytes: u64) {
+        metrics::DOWNLOADED_BYTES.inc_by(num_bytes);
+        self.bucket.lock().record_downloaded_bytes(num_bytes);
+    }
+
+    /// Updates the throughput and rate limit metrics. This should be called
+    /// periodically (e.g., when checking the progress of the streams).
+    pub fn update_metrics(&self) {
+        let throughput = self.bucket.lock().update_throughput(Instant::now());
+        metrics::DOWNLOAD_THROUGHPUT.set(min(throughput, i64::MAX as u64) as i64);
+        metrics::DOWNLOAD_RATE_LIMIT.set(
+            self.max_download_bytes_per_sec()
+                .map_or(-1, |max_bytes| min(max_bytes, i64::MAX as u64) as i64),
+        );
+    }
+
+    /// Returns the downloads since the node started
+    pub fn stats(&self) -> DownloadStats {
+        let bucket = self.bucket.lock();
+        DownloadStats {
+            throughput_bytes_per_sec: bucket.throughput,
+            rate_limit_bytes_per_sec: self.max_download_bytes_per_sec(),
+            downloaded_bytes: bucket.downloaded_bytes,
+            throttled_data_requests: bucket.throttled_data_requests,
+        }
+    }
+
+    /// Returns the current download rate limit, or `None` if unlimited
+    pub fn max_download_bytes_per_sec(&self) -> Option<u64> {
+        self.config
+            .max_download_bytes_per_sec_at(current_hour_of_day())
+    }
+}
+
+impl DownloadStatsReporter for DownloadThrottle {
+    fn download_stats(&self) -> DownloadStats {
+        self.stats()
+    }
+}
+
+impl DownloadBucket {
+    /// Creates a full bucket (it's capped on the first refill)
+    pub(crate) fn new(now: Instant) -> Self {
+        Self {
+            available_bytes: i64::MAX,
+            last_refill_time: now,
+            window_start_time: now,
+            window_downloaded_bytes: 0,
+            throughput: 0,
+            downloaded_bytes: 0,
+            throttled_data_requests: 0,
+        }
+    }
+
+    /// Returns the number of bytes that can be downloaded before throttling
+    pub(crate) fn available_bytes(&self) -> i64 {
+        self.available_bytes
+    }
+
+    /// Refills the bucket at the given rate (if any) and returns true iff
+    /// there are bytes available to download.
+    pub(crate) fn refill(&mut self, now: Instant, max_bytes_per_sec: Option<u64>) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill_time);
+        self.last_refill_time = now;
+        match max_bytes_per_sec {
+            Some(max_bytes_per_sec) => {
+                let max_bytes_per_sec = min(max_bytes_per_sec, i64::MAX as u64) as i64;
+                let refill_bytes =
+                    (max_bytes_per_sec as f64 * elapsed.as_secs_f64()).min(i64::MAX as f64) as i64;
+                self.available_bytes = min(
+                    self.available_bytes.saturating_add(refill_bytes),
+                    max_bytes_per_sec,
+                );
+                self.available_bytes > 0
+            }
+            None => {
+                // Nothing is owed once downloads are unlimited
+                self.available_bytes = 0;
+                true
+            }
+        }
+    }
+
+    /// Deducts a response of `num_bytes` from the bucket
+    pub(crate) fn record_downloaded_bytes(&mut self, num_bytes: u64) {
+        self.available_bytes = self
+            .available_bytes
+            .saturating_sub(min(num_bytes, i64::MAX as u64) as i64);
+        self.window_downloaded_bytes = self.window_downloaded_bytes.saturating_add(num_bytes);
+        self.downloaded_bytes = self.downloaded_bytes.saturating_add(num_bytes);
+    }
+
+    /// Closes the throughput window if it has elapsed and returns the
+    /// throughput measured over the last window.
+    pub(crate) fn update_throughput(&mut self, now: Instant) -> u64 {
+        let elapsed = now.saturating_duration_since(self.window_start_time);
+        if elapsed >= THROUGHPUT_WINDOW {
+            self.throughput = (self.window_downloaded_bytes as f64 / elapsed.as_secs_f64()) as u64;
+            self.window_start_time = now;
+            self.window_downloaded_bytes = 0;
+        }
+        self.throughput
+    }
+}
+
+/// Returns the current hour of the day (UTC)
+fn current_hour_of_day() -> u8 {
+    let secs_since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    hour_of_day(secs_since_epoch)
+}
+
+/// Returns the hour of the day (UTC) at the given number of seconds since the
+/// unix epoch.
+pub(crate) fn hour_of_day(secs_since_epoch: u64) -> u8 {
+    ((secs_since_epoch / SECS_PER_HOUR) % HOURS_PER_DAY) as u8
+}
@@ -5,6 +5,7 @@
 
 pub mod data_notification;
 pub mod data_stream;
+pub mod download_throttle;
 pub mod error;
 mod logging;
 mod metrics;
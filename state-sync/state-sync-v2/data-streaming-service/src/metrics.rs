@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics::{
-    register_histogram_vec, register_int_counter_vec, HistogramTimer, HistogramVec, IntCounterVec,
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    HistogramTimer, HistogramVec, IntCounter, IntCounterVec, IntGauge,
 };
 use once_cell::sync::Lazy;
 
@@ -86,6 +87,42 @@ pub static DATA_REQUEST_PROCESSING_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for the number of bytes downloaded from the network
+pub static DOWNLOADED_BYTES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_data_streaming_service_downloaded_bytes",
+        "Counters related to the number of bytes downloaded from the network"
+    )
+    .unwrap()
+});
+
+/// Gauge for the download throughput (bytes per second)
+pub static DOWNLOAD_THROUGHPUT: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_data_streaming_service_download_throughput",
+        "Gauge related to the download throughput (bytes per second)"
+    )
+    .unwrap()
+});
+
+/// Gauge for the current download rate limit (bytes per second, -1 if unlimited)
+pub static DOWNLOAD_RATE_LIMIT: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_data_streaming_service_download_rate_limit",
+        "Gauge related to the current download rate limit (bytes per second, -1 if unlimited)"
+    )
+    .unwrap()
+});
+
+/// Counter for the times new data requests were held back by the download throttle
+pub static THROTTLED_DATA_REQUESTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_data_streaming_service_throttled_data_requests",
+        "Counters related to data requests held back by the download throttle"
+    )
+    .unwrap()
+});
+
 /// Increments the given counter with the provided label values.
 pub fn increment_counter(counter: &Lazy<IntCounterVec>, label: String) {
     counter.with_label_values(&[&label]).inc();
@@ -3,6 +3,7 @@
 
 use crate::{
     data_stream::{DataStream, DataStreamId, DataStreamListener},
+    download_throttle::DownloadThrottle,
     error::Error,
     logging::{LogEntry, LogEvent, LogSchema},
     metrics,
@@ -31,6 +32,9 @@ pub struct DataStreamingService<T> {
     // The data client through which to fetch data from the Aptos network
     aptos_data_client: T,
 
+    // The throttle limiting the download rate across all data streams
+    download_throttle: Arc<DownloadThrottle>,
+
     // Cached global data summary
     global_data_summary: GlobalDataSummary,
 
@@ -54,6 +58,7 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStreamingService<T> {
         Self {
             config,
             aptos_data_client,
+            download_throttle: Arc::new(DownloadThrottle::new(config)),
             global_data_summary: GlobalDataSummary::empty(),
             data_streams: HashMap::new(),
             stream_requests,
@@ -62,6 +67,11 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStreamingService<T> {
         }
     }
 
+    /// Returns the throttle of the downloads of all data streams
+    pub fn download_throttle(&self) -> Arc<DownloadThrottle> {
+        self.download_throttle.clone()
+    }
+
     /// Starts the dedicated streaming service
     pub async fn start_service(mut self) {
         let mut data_refresh_interval = IntervalStream::new(interval(Duration::from_millis(
@@ -178,6 +188,7 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStreamingService<T> {
             stream_id,
             &request_message.stream_request,
             self.aptos_data_client.clone(),
+            self.download_throttle.clone(),
             self.notification_id_generator.clone(),
             &self.global_data_summary.advertised_data,
         )?;
@@ -238,6 +249,8 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStreamingService<T> {
 
     /// Ensures that all existing data streams are making progress
     fn check_progress_of_all_data_streams(&mut self) {
+        self.download_throttle.update_metrics();
+
         let data_stream_ids = self.get_all_data_stream_ids();
         for data_stream_id in &data_stream_ids {
             if let Err(error) = self.update_progress_of_data_stream(data_stream_id) {
@@ -6,6 +6,7 @@ use crate::{
         DataClientRequest, DataPayload, EpochEndingLedgerInfosRequest, PendingClientResponse,
     },
     data_stream::{DataStream, DataStreamListener},
+    download_throttle::DownloadThrottle,
     streaming_client::{
         GetAllAccountsRequest, GetAllEpochEndingLedgerInfosRequest, GetAllTransactionsRequest,
         NotificationFeedback, StreamRequest,
@@ -55,6 +56,7 @@ async fn test_stream_blocked() {
             });
         let context = ResponseContext {
             id: 0,
+            num_bytes: 0,
             response_callback: Box::new(NoopResponseCallback),
         };
         let pending_response = PendingClientResponse {
@@ -168,6 +170,31 @@ async fn test_stream_initialization() {
     assert_ne!(sent_requests.as_ref().unwrap().len(), 0);
 }
 
+#[tokio::test]
+async fn test_stream_download_paused() {
+    // Create an epoch ending data stream with downloads paused
+    let streaming_service_config = DataStreamingServiceConfig {
+        max_download_bytes_per_sec: Some(0),
+        ..Default::default()
+    };
+    let (mut data_stream, _) =
+        create_epoch_ending_stream(streaming_service_config, MIN_ADVERTISED_EPOCH_END);
+
+    // Initialize the data stream
+    let global_data_summary = create_global_data_summary(100);
+    data_stream
+        .initialize_data_requests(global_data_summary.clone())
+        .unwrap();
+    assert!(data_stream.data_requests_initialized());
+
+    // Verify that no client requests have been made
+    data_stream
+        .process_data_responses(global_data_summary)
+        .unwrap();
+    let (sent_requests, _) = data_stream.get_sent_requests_and_notifications();
+    assert_eq!(sent_requests.as_ref().unwrap().len(), 0);
+}
+
 #[tokio::test]
 async fn test_stream_data_error() {
     // Create an epoch ending data stream
@@ -222,6 +249,7 @@ async fn test_stream_invalid_response() {
     });
     let context = ResponseContext {
         id: 0,
+        num_bytes: 0,
         response_callback: Box::new(NoopResponseCallback),
     };
     let client_response = Response::new(context, ResponsePayload::NumberOfAccountStates(10));
@@ -443,8 +471,9 @@ fn create_data_stream(
         .unwrap()],
     };
 
-    // Create a aptos data client mock and notification generator
+    // Create a aptos data client mock, download throttle and notification generator
     let aptos_data_client = MockAptosDataClient::new();
+    let download_throttle = Arc::new(DownloadThrottle::new(streaming_service_config));
     let notification_generator = Arc::new(U64IdGenerator::new());
 
     // Return the data stream and listener pair
@@ -453,6 +482,7 @@ fn create_data_stream(
         create_random_u64(10000),
         &stream_request,
         aptos_data_client,
+        download_throttle,
         notification_generator,
         &advertised_data,
     )
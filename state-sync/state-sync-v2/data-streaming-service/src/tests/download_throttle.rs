@@ -0,0 +1,120 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::download_throttle::{hour_of_day, DownloadBucket, DownloadThrottle};
+use aptos_config::config::{DataStreamingServiceConfig, OffPeakHours};
+use aptos_types::download_stats::DownloadStats;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_download_bucket_refill() {
+    let start = Instant::now();
+    let mut bucket = DownloadBucket::new(start);
+
+    // Verify the downloads aren't throttled at first
+    assert!(bucket.refill(start, Some(1000)));
+    assert_eq!(bucket.available_bytes(), 1000);
+
+    // Verify the bucket holds one second worth of bytes at most
+    assert!(bucket.refill(start + Duration::from_secs(2), Some(1000)));
+    assert_eq!(bucket.available_bytes(), 1000);
+
+    // Verify a large response leaves the bucket in debt until it's refilled
+    bucket.record_downloaded_bytes(2500);
+    assert!(!bucket.refill(start + Duration::from_secs(3), Some(1000)));
+    assert_eq!(bucket.available_bytes(), -500);
+    assert!(bucket.refill(start + Duration::from_millis(3600), Some(1000)));
+    assert_eq!(bucket.available_bytes(), 100);
+
+    // Verify a rate of 0 pauses the downloads
+    bucket.record_downloaded_bytes(100);
+    assert!(!bucket.refill(start + Duration::from_secs(10), Some(0)));
+
+    // Verify the debt is forgiven when the downloads are unlimited
+    bucket.record_downloaded_bytes(1000);
+    assert!(bucket.refill(start + Duration::from_secs(11), None));
+    assert_eq!(bucket.available_bytes(), 0);
+}
+
+#[test]
+fn test_download_throughput() {
+    let start = Instant::now();
+    let mut bucket = DownloadBucket::new(start);
+
+    // Verify the throughput is only updated once the window has elapsed
+    bucket.record_downloaded_bytes(5000);
+    assert_eq!(bucket.update_throughput(start + Duration::from_secs(1)), 0);
+    assert_eq!(
+        bucket.update_throughput(start + Duration::from_secs(5)),
+        1000
+    );
+
+    // Verify the throughput drops once nothing is downloaded
+    assert_eq!(bucket.update_throughput(start + Duration::from_secs(10)), 0);
+}
+
+#[test]
+fn test_download_stats() {
+    // Create a throttle with the downloads paused
+    let config = DataStreamingServiceConfig {
+        max_download_bytes_per_sec: Some(0),
+        ..Default::default()
+    };
+    let throttle = DownloadThrottle::new(config);
+
+    // Verify the downloaded bytes and the held back requests are counted
+    throttle.record_downloaded_bytes(1000);
+    assert!(!throttle.can_send_requests());
+    throttle.record_downloaded_bytes(500);
+    assert!(!throttle.can_send_requests());
+    assert_eq!(
+        throttle.stats(),
+        DownloadStats {
+            throughput_bytes_per_sec: 0,
+            rate_limit_bytes_per_sec: Some(0),
+            downloaded_bytes: 1500,
+            throttled_data_requests: 2,
+        }
+    );
+}
+
+#[test]
+fn test_off_peak_download_rate_limit() {
+    // Create a config with no rate limit from 22:00 to 06:00
+    let config = DataStreamingServiceConfig {
+        max_download_bytes_per_sec: Some(1000),
+        off_peak_hours: Some(OffPeakHours {
+            start_hour: 22,
+            end_hour: 6,
+        }),
+        off_peak_max_download_bytes_per_sec: None,
+        ..Default::default()
+    };
+
+    // Verify the rate limit of each hour
+    for hour in 0..24 {
+        let expected_limit = if (6..22).contains(&hour) {
+            Some(1000)
+        } else {
+            None
+        };
+        assert_eq!(config.max_download_bytes_per_sec_at(hour), expected_limit);
+    }
+
+    // Verify the off-peak hours don't wrap around midnight unless they have to
+    let off_peak_hours = OffPeakHours {
+        start_hour: 1,
+        end_hour: 5,
+    };
+    assert!(!off_peak_hours.contains(0));
+    assert!(off_peak_hours.contains(1));
+    assert!(off_peak_hours.contains(4));
+    assert!(!off_peak_hours.contains(5));
+}
+
+#[test]
+fn test_hour_of_day() {
+    assert_eq!(hour_of_day(0), 0);
+    assert_eq!(hour_of_day(3 * 60 * 60 + 59), 3);
+    assert_eq!(hour_of_day(25 * 60 * 60), 1);
+}
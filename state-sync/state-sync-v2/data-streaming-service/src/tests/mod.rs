@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod data_stream;
+mod download_throttle;
 mod stream_engine;
 mod streaming_client;
 mod streaming_service;
@@ -225,6 +225,7 @@ pub fn create_data_client_response<T>(payload: T) -> Response<T> {
     let response_callback = Box::new(NoopResponseCallback);
     let context = ResponseContext {
         id,
+        num_bytes: 0,
         response_callback,
     };
     Response::new(context, payload)
@@ -17,6 +17,7 @@ use aptos_logger::{
     sample::{SampleRate, Sampling},
 };
 use aptos_types::{
+    block_info::GENESIS_VERSION,
     epoch_change::Verifier,
     epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
@@ -227,15 +228,11 @@ impl VerifiedEpochStates {
     }
 }
 
-// TODO(joshlind): persist the index (e.g., in case we crash mid-download)?
 /// A simple container to manage state related to account state snapshot syncing
 struct AccountStateSyncer {
     // Whether or not a state snapshot receiver has been initialized
     initialized_state_snapshot_receiver: bool,
 
-    // Whether or not all states have been synced
-    is_sync_complete: bool,
-
     // The epoch ending ledger info for the version we're syncing
     ledger_info_to_sync: Option<LedgerInfoWithSignatures>,
 
@@ -255,7 +252,6 @@ impl AccountStateSyncer {
     pub fn new() -> Self {
         Self {
             initialized_state_snapshot_receiver: false,
-            is_sync_complete: false,
             ledger_info_to_sync: None,
             next_account_index_to_commit: 0,
             next_account_index_to_process: 0,
@@ -430,10 +426,11 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
         // Check if we've already fetched the required data for bootstrapping.
         // If not, bootstrap according to the mode.
         match self.driver_configuration.config.bootstrapping_mode {
-            BootstrappingMode::DownloadLatestAccountStates => {
-                if (self.account_state_syncer.ledger_info_to_sync.is_none()
-                    && highest_synced_version >= highest_known_ledger_version)
-                    || self.account_state_syncer.is_sync_complete
+            BootstrappingMode::DownloadLatestAccountStates
+                if self.should_download_account_states(highest_synced_version) =>
+            {
+                if self.account_state_syncer.ledger_info_to_sync.is_none()
+                    && highest_synced_version >= highest_known_ledger_version
                 {
                     return self.bootstrapping_complete();
                 }
@@ -453,6 +450,20 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
         }
     }
 
+    /// Returns true iff the node should bootstrap by downloading the account
+    /// states (assuming the bootstrapping mode is to download them), i.e., the
+    /// node is already downloading them or only holds genesis. Otherwise, the
+    /// node has already downloaded the account states (e.g., before a restart)
+    /// and it catches up by applying transaction outputs instead.
+    fn should_download_account_states(&self, highest_synced_version: Version) -> bool {
+        self.is_downloading_account_states() || highest_synced_version == GENESIS_VERSION
+    }
+
+    /// Returns true iff the node has started downloading the account states
+    fn is_downloading_account_states(&self) -> bool {
+        self.account_state_syncer.ledger_info_to_sync.is_some()
+    }
+
     /// Attempts to fetch a data notification from the active stream
     async fn fetch_next_data_notification(&mut self) -> Result<DataNotification, Error> {
         let max_stream_wait_time_ms = self.driver_configuration.config.max_stream_wait_time_ms;
@@ -522,20 +533,20 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
         &mut self,
         highest_known_ledger_info: LedgerInfoWithSignatures,
     ) -> Result<(), Error> {
-        // Verify we're trying to sync to an unchanging ledger info
-        if let Some(ledger_info_to_sync) = &self.account_state_syncer.ledger_info_to_sync {
-            if ledger_info_to_sync != &highest_known_ledger_info {
-                panic!(
-                    "Mismatch in ledger info to sync! Highest: {:?}, target: {:?}",
-                    highest_known_ledger_info, ledger_info_to_sync
-                );
-            }
-        } else {
-            self.account_state_syncer.ledger_info_to_sync = Some(highest_known_ledger_info.clone());
+        // Choose the ledger info to sync to (if not already done). This stays
+        // unchanged until all account states have been synced.
+        if self.account_state_syncer.ledger_info_to_sync.is_none() {
+            self.initialize_ledger_info_to_sync(highest_known_ledger_info)?;
         }
+        let ledger_info_to_sync_version = self
+            .account_state_syncer
+            .ledger_info_to_sync
+            .as_ref()
+            .expect("Ledger info to sync is missing!")
+            .ledger_info()
+            .version();
 
         // Fetch the transaction info first, before the account states
-        let highest_known_ledger_version = highest_known_ledger_info.ledger_info().version();
         let data_stream = if self
             .account_state_syncer
             .transaction_output_to_sync
@@ -543,15 +554,15 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
         {
             self.streaming_service_client
                 .get_all_transaction_outputs(
-                    highest_known_ledger_version,
-                    highest_known_ledger_version,
-                    highest_known_ledger_version,
+                    ledger_info_to_sync_version,
+                    ledger_info_to_sync_version,
+                    ledger_info_to_sync_version,
                 )
                 .await?
         } else {
             let start_account_index = Some(self.account_state_syncer.next_account_index_to_commit);
             self.streaming_service_client
-                .get_all_accounts(highest_known_ledger_version, start_account_index)
+                .get_all_accounts(ledger_info_to_sync_version, start_account_index)
                 .await?
         };
         self.active_data_stream = Some(data_stream);
@@ -559,6 +570,55 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
         Ok(())
     }
 
+    /// Initializes the ledger info to sync the account states to. If an
+    /// account states sync was started before but not completed (e.g., the
+    /// node restarted mid-download), the sync resumes at the same version,
+    /// from the first account that wasn't committed to storage. Otherwise,
+    /// the highest known ledger info is synced to.
+    fn initialize_ledger_info_to_sync(
+        &mut self,
+        highest_known_ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        let state_snapshot_progress =
+            self.storage
+                .get_state_snapshot_progress()
+                .map_err(|error| {
+                    Error::StorageError(format!(
+                        "Failed to get the state snapshot progress: {:?}",
+                        error
+                    ))
+                })?;
+        if let Some(state_snapshot_progress) = state_snapshot_progress {
+            let snapshot_version = state_snapshot_progress.version;
+            match self
+                .verified_epoch_states
+                .get_epoch_ending_ledger_info(snapshot_version)
+            {
+                Some(ledger_info_to_sync) => {
+                    info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                        "Resuming the account states sync at version: {:?}, account index: {:?}",
+                        snapshot_version, state_snapshot_progress.num_restored_keys
+                    )));
+                    let next_account_index = state_snapshot_progress.num_restored_keys;
+                    self.account_state_syncer.ledger_info_to_sync = Some(ledger_info_to_sync);
+                    self.account_state_syncer.next_account_index_to_commit = next_account_index;
+                    self.account_state_syncer.next_account_index_to_process = next_account_index;
+                    return Ok(());
+                }
+                None => {
+                    warn!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                        "No verified epoch ending ledger info found at the version of the \
+                        interrupted account states sync: {:?}. Starting a new sync!",
+                        snapshot_version
+                    )));
+                }
+            }
+        }
+
+        self.account_state_syncer.ledger_info_to_sync = Some(highest_known_ledger_info);
+        Ok(())
+    }
+
     /// Fetches all missing transaction data in order to bootstrap the node
     async fn fetch_missing_transaction_data(
         &mut self,
@@ -574,7 +634,8 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
             .next_epoch_ending_version(highest_synced_version)
             .expect("No higher epoch ending version known!");
         let data_stream = match self.driver_configuration.config.bootstrapping_mode {
-            BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis
+            | BootstrappingMode::DownloadLatestAccountStates => {
                 self.streaming_service_client
                     .get_all_transaction_outputs(
                         next_version,
@@ -593,9 +654,6 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
                     )
                     .await?
             }
-            bootstrapping_mode => {
-                unreachable!("Bootstrapping mode not supported: {:?}", bootstrapping_mode)
-            }
         };
         self.speculative_stream_state = Some(SpeculativeStreamState::new(
             utils::fetch_latest_epoch_state(self.storage.clone())?,
@@ -776,13 +834,7 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
         account_state_chunk_with_proof: StateValueChunkWithProof,
     ) -> Result<(), Error> {
         // Verify that we're expecting account payloads
-        let bootstrapping_mode = self.driver_configuration.config.bootstrapping_mode;
-        if self.should_fetch_epoch_ending_ledger_infos()
-            || !matches!(
-                bootstrapping_mode,
-                BootstrappingMode::DownloadLatestAccountStates
-            )
-        {
+        if self.should_fetch_epoch_ending_ledger_infos() || !self.is_downloading_account_states() {
             self.terminate_active_stream(notification_id, NotificationFeedback::InvalidPayloadData)
                 .await?;
             return Err(Error::InvalidPayload(
@@ -920,13 +972,10 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
         // Verify that we're expecting transaction or output payloads
         let bootstrapping_mode = self.driver_configuration.config.bootstrapping_mode;
         if self.should_fetch_epoch_ending_ledger_infos()
-            || (matches!(
-                bootstrapping_mode,
-                BootstrappingMode::DownloadLatestAccountStates
-            ) && self
+            || self
                 .account_state_syncer
                 .transaction_output_to_sync
-                .is_some())
+                .is_some()
         {
             self.terminate_active_stream(notification_id, NotificationFeedback::InvalidPayloadData)
                 .await?;
@@ -936,10 +985,7 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
         }
 
         // If we're account state syncing, we expect a single transaction info
-        if matches!(
-            bootstrapping_mode,
-            BootstrappingMode::DownloadLatestAccountStates
-        ) {
+        if self.is_downloading_account_states() {
             return self
                 .verify_transaction_info_to_sync(
                     notification_id,
@@ -976,7 +1022,8 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
 
         // Execute/apply and commit the transactions/outputs
         let num_transactions_or_outputs = match bootstrapping_mode {
-            BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis
+            | BootstrappingMode::DownloadLatestAccountStates => {
                 if let Some(transaction_outputs_with_proof) = transaction_outputs_with_proof {
                     let num_transaction_outputs = transaction_outputs_with_proof
                         .transactions_and_outputs
@@ -1020,9 +1067,6 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
                     ));
                 }
             }
-            bootstrapping_mode => {
-                unreachable!("Bootstrapping mode not supported: {:?}", bootstrapping_mode)
-            }
         };
         let synced_version = payload_start_version
             .checked_add(num_transactions_or_outputs as u64)
@@ -1147,7 +1191,8 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
     ) -> Result<Option<LedgerInfoWithSignatures>, Error> {
         // Calculate the payload end version
         let num_versions = match self.driver_configuration.config.bootstrapping_mode {
-            BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis
+            | BootstrappingMode::DownloadLatestAccountStates => {
                 if let Some(transaction_outputs_with_proof) = transaction_outputs_with_proof {
                     transaction_outputs_with_proof
                         .transactions_and_outputs
@@ -1177,9 +1222,6 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
                     ));
                 }
             }
-            bootstrapping_mode => {
-                unimplemented!("Bootstrapping mode not supported: {:?}", bootstrapping_mode)
-            }
         };
         let payload_end_version = payload_start_version
             .checked_add(num_versions as u64)
@@ -1259,7 +1301,9 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
                 Error::IntegerOverflow("The next account index to commit has overflown!".into())
             })?;
 
-        // Check if we've downloaded all account states
+        // Check if we've downloaded all account states. If so, the node
+        // catches up from the synced version (e.g., if the sync was resumed at
+        // a version lower than the highest known ledger info).
         if committed_accounts.all_accounts_synced {
             info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
                 "Successfully synced all account states at version: {:?}. \
@@ -1267,7 +1311,7 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> Bootstrapper<StorageSy
                 self.account_state_syncer.ledger_info_to_sync,
                 committed_accounts.last_committed_account_index
             )));
-            self.account_state_syncer.is_sync_complete = true;
+            self.account_state_syncer = AccountStateSyncer::new();
         }
 
         Ok(())
@@ -396,6 +396,20 @@ impl<StorageSyncer: StorageSynchronizerInterface + Clone> ContinuousSyncer<Stora
         .await
     }
 
+    /// Stops the active data stream (e.g., to hand the storage writes back to
+    /// the consensus observer). Returns true iff there's no more pending data
+    /// for the storage synchronizer, i.e., state sync has stopped writing.
+    pub fn stop_active_stream(&mut self) -> bool {
+        self.reset_active_stream();
+        !self.storage_synchronizer.pending_storage_data()
+    }
+
+    /// Resets the chunk executor before syncing again (e.g., after the
+    /// consensus observer has committed blocks to storage)
+    pub fn reset_chunk_executor(&self) -> Result<(), Error> {
+        self.storage_synchronizer.reset_chunk_executor()
+    }
+
     /// Returns the speculative stream state. Assumes that the state exists.
     fn get_speculative_stream_state(&mut self) -> &mut SpeculativeStreamState {
         self.speculative_stream_state
@@ -17,7 +17,7 @@ use crate::{
     utils,
 };
 use ::aptos_logger::*;
-use aptos_config::config::{RoleType, StateSyncDriverConfig};
+use aptos_config::config::{ConsensusObserverConfig, RoleType, StateSyncDriverConfig};
 use aptos_data_client::AptosDataClient;
 use aptos_infallible::Mutex;
 use aptos_types::waypoint::Waypoint;
@@ -39,6 +39,10 @@ pub struct DriverConfiguration {
     // The config file of the driver
     pub config: StateSyncDriverConfig,
 
+    // The config of the consensus observer, which executes and commits the
+    // blocks instead of state sync on fullnodes (if enabled)
+    pub consensus_observer_config: ConsensusObserverConfig,
+
     // The role of the node
     pub role: RoleType,
 
@@ -47,9 +51,15 @@ pub struct DriverConfiguration {
 }
 
 impl DriverConfiguration {
-    pub fn new(config: StateSyncDriverConfig, role: RoleType, waypoint: Waypoint) -> Self {
+    pub fn new(
+        config: StateSyncDriverConfig,
+        consensus_observer_config: ConsensusObserverConfig,
+        role: RoleType,
+        waypoint: Waypoint,
+    ) -> Self {
         Self {
             config,
+            consensus_observer_config,
             role,
             waypoint,
         }
@@ -91,6 +101,9 @@ pub struct StateSyncDriver<DataClient, MempoolNotifier, StorageSyncer> {
     // The timestamp at which the driver started executing
     start_time: Option<SystemTime>,
 
+    // The timestamp of the last commit notification sent by consensus
+    last_consensus_commit_time: Option<SystemTime>,
+
     // The interface to read from storage
     storage: Arc<dyn DbReader>,
 }
@@ -140,6 +153,7 @@ impl<
             event_subscription_service,
             mempool_notification_handler,
             start_time: None,
+            last_consensus_commit_time: None,
             storage,
         }
     }
@@ -178,8 +192,11 @@ impl<
     /// Handles a notification sent by consensus
     async fn handle_consensus_notification(&mut self, notification: ConsensusNotification) {
         // Verify the notification: full nodes shouldn't receive notifications
-        // and consensus should only send notifications after bootstrapping!
-        let result = if self.driver_configuration.role == RoleType::FullNode {
+        // (unless the consensus observer is enabled) and consensus should only
+        // send notifications after bootstrapping!
+        let result = if self.driver_configuration.role == RoleType::FullNode
+            && !self.is_consensus_observer_enabled()
+        {
             Err(Error::FullNodeConsensusNotification(format!(
                 "Received consensus notification: {:?}",
                 notification
@@ -269,6 +286,7 @@ impl<
         self.consensus_notification_handler
             .respond_to_commit_notification(consensus_commit_notification, Ok(()))
             .await?;
+        self.last_consensus_commit_time = Some(SystemTime::now());
 
         // Check the progress of any sync requests. We need this here because
         // consensus might issue a sync request and then commit (asynchronously).
@@ -499,9 +517,81 @@ impl<
         self.driver_configuration.role == RoleType::Validator
     }
 
-    /// Returns true iff consensus is currently executing
+    /// Returns true iff the consensus observer is enabled on this full node
+    fn is_consensus_observer_enabled(&self) -> bool {
+        self.driver_configuration.role == RoleType::FullNode
+            && self
+                .driver_configuration
+                .consensus_observer_config
+                .observer_enabled
+    }
+
+    /// Returns true iff the consensus observer has committed recently. Otherwise
+    /// (e.g., the upstream nodes are unavailable) state sync pauses it and falls
+    /// back to continuously syncing.
+    fn check_if_consensus_observer_committing(&self) -> bool {
+        let fallback_duration = Duration::from_millis(
+            self.driver_configuration
+                .consensus_observer_config
+                .observer_fallback_duration_ms,
+        );
+        self.last_consensus_commit_time
+            .and_then(|commit_time| SystemTime::now().duration_since(commit_time).ok())
+            .map_or(false, |elapsed| elapsed < fallback_duration)
+    }
+
+    /// Pauses the consensus observer if it has stopped committing, so that only
+    /// state sync writes to storage, or resumes it once it has requested to and
+    /// state sync has stopped writing. Returns true iff state sync is handing
+    /// back to the observer (and so shouldn't sync meanwhile).
+    fn update_consensus_observer_gate(&mut self) -> bool {
+        if !self.is_consensus_observer_enabled() || !self.bootstrapper.is_bootstrapped() {
+            return false;
+        }
+
+        let consensus_observer_gate = self
+            .consensus_notification_handler
+            .consensus_observer_gate();
+        if consensus_observer_gate.is_observer_paused() {
+            if !consensus_observer_gate.is_resume_requested() {
+                return false;
+            }
+            if self.continuous_syncer.stop_active_stream()
+                && consensus_observer_gate.try_resume_observer()
+            {
+                info!(LogSchema::new(LogEntry::Driver)
+                    .message("Handed the storage writes back to the consensus observer!"));
+                // Give the observer the full fallback duration to commit again
+                self.last_consensus_commit_time = Some(SystemTime::now());
+            }
+            return true;
+        }
+
+        if !self.check_if_consensus_observer_committing()
+            && !self.consensus_notification_handler.active_sync_request()
+            && consensus_observer_gate.try_pause_observer()
+        {
+            info!(LogSchema::new(LogEntry::Driver).message(
+                "The consensus observer isn't committing! Paused it to continuously sync."
+            ));
+            // The observer has committed blocks since state sync last synced
+            if let Err(error) = self.continuous_syncer.reset_chunk_executor() {
+                error!(LogSchema::new(LogEntry::Driver)
+                    .error(&error)
+                    .message("Failed to reset the chunk executor!"));
+            }
+        }
+        false
+    }
+
+    /// Returns true iff consensus (or the consensus observer) is currently executing
     fn check_if_consensus_executing(&self) -> bool {
-        self.is_validator()
+        let consensus_observer_executing = self.is_consensus_observer_enabled()
+            && !self
+                .consensus_notification_handler
+                .consensus_observer_gate()
+                .is_observer_paused();
+        (self.is_validator() || consensus_observer_executing)
             && self.bootstrapper.is_bootstrapped()
             && !self.consensus_notification_handler.active_sync_request()
     }
@@ -560,6 +650,13 @@ impl<
                 .message("Error found when checking the sync request progress!"));
         }
 
+        // Hand the storage writes over between the consensus observer and state sync
+        if self.update_consensus_observer_gate() {
+            trace!(LogSchema::new(LogEntry::Driver)
+                .message("Waiting to hand back to the consensus observer."));
+            return;
+        }
+
         // If consensus is executing, there's nothing to do
         if self.check_if_consensus_executing() {
             trace!(LogSchema::new(LogEntry::Driver)
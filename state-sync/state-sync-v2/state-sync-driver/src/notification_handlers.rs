@@ -14,7 +14,7 @@ use aptos_types::{
 };
 use consensus_notifications::{
     ConsensusCommitNotification, ConsensusNotification, ConsensusNotificationListener,
-    ConsensusSyncNotification,
+    ConsensusObserverGate, ConsensusSyncNotification,
 };
 use data_streaming_service::data_notification::NotificationId;
 use event_notifications::{EventNotificationSender, EventSubscriptionService};
@@ -191,6 +191,11 @@ impl ConsensusNotificationHandler {
         self.consensus_sync_request.lock().is_some()
     }
 
+    /// Returns the gate shared with the consensus observer (if any)
+    pub fn consensus_observer_gate(&self) -> ConsensusObserverGate {
+        self.consensus_listener.consensus_observer_gate()
+    }
+
     /// Returns the active sync request that consensus is waiting on
     pub fn get_consensus_sync_request(&self) -> Arc<Mutex<Option<ConsensusSyncRequest>>> {
         self.consensus_sync_request.clone()
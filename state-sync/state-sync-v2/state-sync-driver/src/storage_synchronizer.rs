@@ -75,6 +75,10 @@ pub trait StorageSynchronizerInterface {
     /// to be executed/applied or committed.
     fn pending_storage_data(&self) -> bool;
 
+    /// Resets the chunk executor to the latest state in storage (e.g., after
+    /// another component has written to storage).
+    fn reset_chunk_executor(&self) -> Result<(), Error>;
+
     /// Saves the given account states to storage.
     ///
     /// Note: this requires that `initialize_account_synchronizer` has been
@@ -162,6 +166,7 @@ impl<ChunkExecutor: ChunkExecutorTrait + 'static> StorageSynchronizer<ChunkExecu
             committer_notifier,
             pending_transaction_chunks.clone(),
             max_pending_data_chunks as u64,
+            driver_config.max_parallel_executed_chunks,
             runtime.clone(),
         );
 
@@ -274,6 +279,15 @@ impl<ChunkExecutor: ChunkExecutorTrait + 'static> StorageSynchronizerInterface
         load_pending_data_chunks(self.pending_data_chunks.clone()) > 0
     }
 
+    fn reset_chunk_executor(&self) -> Result<(), Error> {
+        self.chunk_executor.reset().map_err(|error| {
+            Error::UnexpectedError(format!(
+                "Failed to reset the chunk executor! Error: {:?}",
+                error
+            ))
+        })
+    }
+
     fn save_account_states(
         &mut self,
         notification_id: NotificationId,
@@ -325,6 +339,7 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
     mut committer_notifier: mpsc::Sender<NotificationId>,
     pending_transaction_chunks: Arc<AtomicU64>,
     max_pending_data_chunks: u64,
+    max_parallel_executed_chunks: u64,
     runtime: Option<Handle>,
 ) {
     // Create an executor
@@ -332,65 +347,48 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
         loop {
             ::futures::select! {
                 storage_data_chunk = executor_listener.select_next_some() => {
-                    // Execute/apply the storage data chunk
-                    let (notification_id, result) = match storage_data_chunk {
-                        StorageDataChunk::Transactions(notification_id, transactions_with_proof, target_ledger_info, end_of_epoch_ledger_info) => {
-                            let num_transactions = transactions_with_proof.transactions.len();
-                            let result = chunk_executor
-                               .execute_chunk(
-                                    transactions_with_proof,
-                                    &target_ledger_info,
-                                    end_of_epoch_ledger_info.as_ref(),
-                                );
-                            if result.is_ok() {
-                                metrics::increment_gauge(
-                                    &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
-                                    metrics::StorageSynchronizerOperations::ExecutedTransactions
-                                        .get_label(),
-                                    num_transactions as u64,
-                                );
-                            }
-                            (notification_id, result)
-                        },
-                        StorageDataChunk::TransactionOutputs(notification_id, outputs_with_proof, target_ledger_info, end_of_epoch_ledger_info) => {
-                            let num_outputs = outputs_with_proof.transactions_and_outputs.len();
-                            let result = chunk_executor
-                                .apply_chunk(
-                                    outputs_with_proof,
-                                    &target_ledger_info,
-                                    end_of_epoch_ledger_info.as_ref(),
-                                );
-                            if result.is_ok() {
-                                metrics::increment_gauge(
-                                    &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
-                                    metrics::StorageSynchronizerOperations::AppliedTransactionOutputs
-                                        .get_label(),
-                                    num_outputs as u64,
-                                );
-                            }
-                            (notification_id, result)
+                    // Take the storage data chunks that are also ready, so that
+                    // consecutive transaction chunks can be executed in parallel.
+                    let mut storage_data_chunks = vec![storage_data_chunk];
+                    while (storage_data_chunks.len() as u64) < max_parallel_executed_chunks {
+                        match executor_listener.try_next() {
+                            Ok(Some(storage_data_chunk)) => storage_data_chunks.push(storage_data_chunk),
+                            _ => break,
                         }
-                        storage_data_chunk => {
-                            panic!("Invalid storage data chunk sent to executor: {:?}", storage_data_chunk);
-                        }
-                    };
+                    }
+
+                    // Execute/apply the storage data chunks
+                    let num_storage_data_chunks = storage_data_chunks.len();
+                    let results = execute_storage_data_chunks(
+                        chunk_executor.as_ref(),
+                        storage_data_chunks,
+                        max_parallel_executed_chunks > 1,
+                    );
+                    let num_results = results.len();
 
                     // Notify the committer of new executed chunks
-                    match result {
-                        Ok(()) => {
-                            if let Err(error) = committer_notifier.try_send(notification_id) {
-                                let error = format!("Failed to notify the committer! Error: {:?}", error);
+                    for (notification_id, result) in results {
+                        match result {
+                            Ok(()) => {
+                                if let Err(error) = committer_notifier.try_send(notification_id) {
+                                    let error = format!("Failed to notify the committer! Error: {:?}", error);
+                                    send_storage_synchronizer_error(error_notification_sender.clone(), notification_id, error).await;
+                                    decrement_pending_data_chunks(pending_transaction_chunks.clone());
+                                }
+                            },
+                            Err(error) => {
+                                let error = format!("Failed to execute/apply the storage data chunk! Error: {:?}", error);
                                 send_storage_synchronizer_error(error_notification_sender.clone(), notification_id, error).await;
                                 decrement_pending_data_chunks(pending_transaction_chunks.clone());
                             }
-                        },
-                        Err(error) => {
-                            let error = format!("Failed to execute/apply the storage data chunk! Error: {:?}", error);
-                            send_storage_synchronizer_error(error_notification_sender.clone(), notification_id, error).await;
-                            decrement_pending_data_chunks(pending_transaction_chunks.clone());
                         }
                     }
 
+                    // The chunks after a failed chunk are dropped (the stream will be reset)
+                    for _ in num_results..num_storage_data_chunks {
+                        decrement_pending_data_chunks(pending_transaction_chunks.clone());
+                    }
+
                     // If the executor begins running too far ahead of the committer
                     // let's force more yields to avoid unnecessary back pressure.
                     if load_pending_data_chunks(pending_transaction_chunks.clone()) > max_pending_data_chunks / 2 {
@@ -405,6 +403,181 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
     spawn(runtime, executor);
 }
 
+/// Executes/applies the given storage data chunks in order and returns the
+/// result of each chunk, up to (and including) the first failure. If
+/// `execute_in_parallel` is true, consecutive transaction chunks are executed
+/// together in parallel.
+fn execute_storage_data_chunks<ChunkExecutor: ChunkExecutorTrait>(
+    chunk_executor: &ChunkExecutor,
+    storage_data_chunks: Vec<StorageDataChunk>,
+    execute_in_parallel: bool,
+) -> Vec<(NotificationId, anyhow::Result<()>)> {
+    let mut results = vec![];
+    let mut storage_data_chunks = storage_data_chunks.into_iter().peekable();
+    while let Some(storage_data_chunk) = storage_data_chunks.next() {
+        match storage_data_chunk {
+            StorageDataChunk::Transactions(
+                notification_id,
+                transactions_with_proof,
+                target_ledger_info,
+                end_of_epoch_ledger_info,
+            ) => {
+                // Take the consecutive transaction chunks
+                let mut transaction_chunks = vec![(
+                    notification_id,
+                    transactions_with_proof,
+                    target_ledger_info,
+                    end_of_epoch_ledger_info,
+                )];
+                while execute_in_parallel
+                    && matches!(
+                        storage_data_chunks.peek(),
+                        Some(StorageDataChunk::Transactions(..))
+                    )
+                {
+                    if let Some(StorageDataChunk::Transactions(
+                        notification_id,
+                        transactions_with_proof,
+                        target_ledger_info,
+                        end_of_epoch_ledger_info,
+                    )) = storage_data_chunks.next()
+                    {
+                        transaction_chunks.push((
+                            notification_id,
+                            transactions_with_proof,
+                            target_ledger_info,
+                            end_of_epoch_ledger_info,
+                        ));
+                    }
+                }
+                results.extend(execute_transaction_chunks(
+                    chunk_executor,
+                    transaction_chunks,
+                    execute_in_parallel,
+                ));
+            }
+            StorageDataChunk::TransactionOutputs(
+                notification_id,
+                outputs_with_proof,
+                target_ledger_info,
+                end_of_epoch_ledger_info,
+            ) => {
+                let num_outputs = outputs_with_proof.transactions_and_outputs.len();
+                let result = chunk_executor.apply_chunk(
+                    outputs_with_proof,
+                    &target_ledger_info,
+                    end_of_epoch_ledger_info.as_ref(),
+                );
+                if result.is_ok() {
+                    metrics::increment_gauge(
+                        &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
+                        metrics::StorageSynchronizerOperations::AppliedTransactionOutputs
+                            .get_label(),
+                        num_outputs as u64,
+                    );
+                }
+                results.push((notification_id, result));
+            }
+            storage_data_chunk => {
+                panic!(
+                    "Invalid storage data chunk sent to executor: {:?}",
+                    storage_data_chunk
+                );
+            }
+        }
+
+        // Stop at the first failure
+        if results.last().map_or(false, |(_, result)| result.is_err()) {
+            break;
+        }
+    }
+    results
+}
+
+/// Executes the given consecutive transaction chunks (in parallel if
+/// `execute_in_parallel` is true) and returns the result of each chunk, up to
+/// (and including) the first failure.
+fn execute_transaction_chunks<ChunkExecutor: ChunkExecutorTrait>(
+    chunk_executor: &ChunkExecutor,
+    transaction_chunks: Vec<(
+        NotificationId,
+        TransactionListWithProof,
+        LedgerInfoWithSignatures,
+        Option<LedgerInfoWithSignatures>,
+    )>,
+    execute_in_parallel: bool,
+) -> Vec<(NotificationId, anyhow::Result<()>)> {
+    let mut num_transactions = vec![];
+    let results = if execute_in_parallel {
+        let mut notification_ids = vec![];
+        let mut transaction_lists = vec![];
+        let mut ledger_infos = vec![];
+        for (
+            notification_id,
+            transactions_with_proof,
+            target_ledger_info,
+            end_of_epoch_ledger_info,
+        ) in transaction_chunks
+        {
+            notification_ids.push(notification_id);
+            num_transactions.push(transactions_with_proof.transactions.len());
+            transaction_lists.push(transactions_with_proof);
+            ledger_infos.push((target_ledger_info, end_of_epoch_ledger_info));
+        }
+        let chunks = transaction_lists
+            .into_iter()
+            .zip(&ledger_infos)
+            .map(
+                |(transactions_with_proof, (target_ledger_info, end_of_epoch_ledger_info))| {
+                    (
+                        transactions_with_proof,
+                        target_ledger_info,
+                        end_of_epoch_ledger_info.as_ref(),
+                    )
+                },
+            )
+            .collect();
+        notification_ids
+            .into_iter()
+            .zip(chunk_executor.execute_chunks(chunks))
+            .collect::<Vec<_>>()
+    } else {
+        let mut results = vec![];
+        for (
+            notification_id,
+            transactions_with_proof,
+            target_ledger_info,
+            end_of_epoch_ledger_info,
+        ) in transaction_chunks
+        {
+            num_transactions.push(transactions_with_proof.transactions.len());
+            let result = chunk_executor.execute_chunk(
+                transactions_with_proof,
+                &target_ledger_info,
+                end_of_epoch_ledger_info.as_ref(),
+            );
+            let is_err = result.is_err();
+            results.push((notification_id, result));
+            if is_err {
+                break;
+            }
+        }
+        results
+    };
+
+    // Update the metrics
+    for ((_, result), num_transactions) in results.iter().zip(num_transactions) {
+        if result.is_ok() {
+            metrics::increment_gauge(
+                &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
+                metrics::StorageSynchronizerOperations::ExecutedTransactions.get_label(),
+                num_transactions as u64,
+            );
+        }
+    }
+    results
+}
+
 /// Spawns a dedicated committer that commits executed (but pending) chunks
 fn spawn_committer<ChunkExecutor: ChunkExecutorTrait + 'static>(
     chunk_executor: Arc<ChunkExecutor>,
@@ -165,7 +165,7 @@ pub fn state_sync_v1_network_config() -> AppConfig {
 mod tests {
     use crate::StateSyncMultiplexer;
     use aptos_config::{
-        config::{RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG},
+        config::{RocksdbConfig, DEFAULT_STATE_CACHE_SIZE, NO_OP_STORAGE_PRUNER_CONFIG},
         utils::get_genesis_txn,
     };
     use aptos_crypto::HashValue;
@@ -201,6 +201,8 @@ mod tests {
             false,
             NO_OP_STORAGE_PRUNER_CONFIG,
             RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
         )
         .unwrap();
         let (_, db_rw) = DbReaderWriter::wrap(db);
@@ -54,18 +54,20 @@ impl StorageServiceClient {
         }
     }
 
+    /// Sends the request to the recipient, returning the response along with the number of bytes
+    /// it was received in.
     pub async fn send_request(
         &self,
         recipient: PeerNetworkId,
         request: StorageServiceRequest,
         timeout: Duration,
-    ) -> Result<StorageServiceResponse, Error> {
-        let message = self
+    ) -> Result<(StorageServiceResponse, usize), Error> {
+        let (message, num_bytes) = self
             .network_sender
-            .send_rpc(recipient, StorageServiceMessage::Request(request), timeout)
+            .send_rpc_with_num_bytes(recipient, StorageServiceMessage::Request(request), timeout)
             .await?;
         match message {
-            StorageServiceMessage::Response(Ok(response)) => Ok(response),
+            StorageServiceMessage::Response(Ok(response)) => Ok((response, num_bytes)),
             StorageServiceMessage::Response(Err(err)) => Err(Error::StorageServiceError(err)),
             StorageServiceMessage::Request(_) => Err(Error::RpcError(RpcError::InvalidRpcResponse)),
         }
@@ -144,4 +146,15 @@ impl ApplicationNetworkSender<StorageServiceMessage> for StorageServiceNetworkSe
             .send_rpc(recipient, ProtocolId::StorageServiceRpc, message, timeout)
             .await
     }
+
+    async fn send_rpc_with_num_bytes(
+        &self,
+        recipient: PeerId,
+        message: StorageServiceMessage,
+        timeout: Duration,
+    ) -> Result<(StorageServiceMessage, usize), RpcError> {
+        self.inner
+            .send_rpc_with_num_bytes(recipient, ProtocolId::StorageServiceRpc, message, timeout)
+            .await
+    }
 }
@@ -176,6 +176,233 @@ fn test_sync_transactions_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoW
     }
 }
 
+fn test_pipelined_commits_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_commit_pipelining(&tmp_dir);
+
+    let num_batches = input.len();
+    let mut cur_ver = 0;
+    for (batch_idx, (txns_to_commit, ledger_info_with_sigs)) in input.iter().enumerate() {
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+        )
+        .unwrap();
+
+        // the reads of the state wait for the state committer
+        verify_committed_transactions(
+            &db,
+            txns_to_commit,
+            cur_ver,
+            ledger_info_with_sigs,
+            batch_idx + 1 == num_batches, /* is_latest */
+        );
+
+        cur_ver += txns_to_commit.len() as u64;
+    }
+
+    // all the state committed, nothing left pending
+    drop(db);
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let mut iter = db
+        .db
+        .iter::<PendingStateUpdatesSchema>(ReadOptions::default())
+        .unwrap();
+    iter.seek_to_first();
+    assert!(iter.next().is_none());
+}
+
+fn test_replay_pending_state_updates_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let (txns_to_commit, ledger_info_with_sigs) = input.first().unwrap();
+    {
+        let db = AptosDB::new_for_test_with_commit_pipelining(&tmp_dir);
+        // Commits the ledger without queueing the state, as if crashed right after.
+        let mut cs = ChangeSet::new();
+        db.save_transactions_impl(txns_to_commit, 0, &mut cs)
+            .unwrap();
+        db.ledger_store
+            .put_ledger_info(ledger_info_with_sigs, &mut cs)
+            .unwrap();
+        let (sealed_cs, _counter_bumps) = cs.seal_without_counters();
+        db.commit(sealed_cs).unwrap();
+    }
+
+    let db = AptosDB::new_for_test(&tmp_dir);
+    verify_committed_transactions(
+        &db,
+        txns_to_commit,
+        0,
+        ledger_info_with_sigs,
+        true, /* is_latest */
+    );
+    let mut iter = db
+        .db
+        .iter::<PendingStateUpdatesSchema>(ReadOptions::default())
+        .unwrap();
+    iter.seek_to_first();
+    assert!(iter.next().is_none());
+}
+
+fn test_cold_ledger_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_cold_ledger(
+        &tmp_dir,
+        tmp_dir.path().join("cold"),
+        1, /* hot_window */
+    );
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+
+    // All but the latest version are moved in the background.
+    let cold_ledger = db.cold_ledger.as_ref().unwrap();
+    let timeout = Instant::now() + Duration::from_secs(10);
+    while cold_ledger.first_hot_version() + 1 < cur_ver {
+        assert!(
+            Instant::now() < timeout,
+            "Timed out moving to the cold ledger."
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let latest_ledger_info = input.last().unwrap().1.clone();
+    let mut cur_ver = 0;
+    for (txns_to_commit, _) in &input {
+        verify_committed_transactions(
+            &db,
+            txns_to_commit,
+            cur_ver,
+            &latest_ledger_info,
+            false, /* is_latest */
+        );
+        cur_ver += txns_to_commit.len() as u64;
+    }
+}
+
+fn test_db_checkpoint_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let checkpoint_dir = TempPath::new();
+    let shard_dirs: Vec<_> = (0..2)
+        .map(|i| tmp_dir.path().join(format!("shard{}", i)))
+        .collect();
+    let db = AptosDB::new_for_test_with_state_shards(&tmp_dir, &shard_dirs);
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let manifest = db
+        .create_db_checkpoint(checkpoint_dir.path().to_path_buf())
+        .unwrap();
+    assert!(db
+        .create_db_checkpoint(checkpoint_dir.path().to_path_buf())
+        .is_err());
+    drop(db);
+
+    let latest_ledger_info = input.last().unwrap().1.clone();
+    assert_eq!(
+        manifest.version,
+        Some(latest_ledger_info.ledger_info().version())
+    );
+    assert_eq!(manifest.state_shard_dirs.len(), 2);
+    let manifest_file =
+        std::fs::read(checkpoint_dir.path().join(DB_CHECKPOINT_MANIFEST_FILE)).unwrap();
+    assert_eq!(
+        serde_json::from_slice::<DbCheckpointManifest>(&manifest_file).unwrap(),
+        manifest
+    );
+
+    let state_shard_dirs: Vec<_> = manifest
+        .state_shard_dirs
+        .iter()
+        .map(|dir| checkpoint_dir.path().join(dir))
+        .collect();
+    let checkpoint = AptosDB::open_with_state_shards(
+        checkpoint_dir.path().join(&manifest.db_dir),
+        &state_shard_dirs,
+        false,                       /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+        RocksdbConfig::default(),
+        DEFAULT_STATE_CACHE_SIZE,
+        false, /* enable_event_type_index */
+        false, /* enable_commit_pipelining */
+        None,  /* cold_ledger_config */
+    )
+    .unwrap();
+    assert_eq!(
+        checkpoint.get_latest_ledger_info().unwrap(),
+        latest_ledger_info
+    );
+    let mut cur_ver = 0;
+    for (txns_to_commit, _) in &input {
+        verify_committed_transactions(
+            &checkpoint,
+            txns_to_commit,
+            cur_ver,
+            &latest_ledger_info,
+            false, /* is_latest */
+        );
+        cur_ver += txns_to_commit.len() as u64;
+    }
+}
+
+fn test_secondary_catch_up_impl(input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>) {
+    let tmp_dir = TempPath::new();
+    let secondary_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let secondary = AptosDB::open_as_secondary(
+        tmp_dir.path(),
+        secondary_dir.path(),
+        RocksdbConfig::default(),
+        DEFAULT_STATE_CACHE_SIZE,
+        false, /* enable_event_type_index */
+    )
+    .unwrap();
+    assert!(secondary.get_latest_ledger_info_option().unwrap().is_none());
+
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver, /* first_version */
+            Some(ledger_info_with_sigs),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+
+        secondary.try_catch_up_with_primary().unwrap();
+        assert_eq!(
+            secondary.get_latest_ledger_info().unwrap(),
+            *ledger_info_with_sigs
+        );
+        verify_committed_transactions(
+            &secondary,
+            txns_to_commit,
+            cur_ver - txns_to_commit.len() as u64,
+            ledger_info_with_sigs,
+            true, /* is_latest */
+        );
+    }
+}
+
 fn get_events_by_event_key(
     db: &AptosDB,
     ledger_info: &LedgerInfo,
@@ -577,6 +804,31 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit()) {
         test_sync_transactions_impl(input);
     }
+
+    #[test]
+    fn test_pipelined_commits(input in arb_blocks_to_commit()) {
+        test_pipelined_commits_impl(input);
+    }
+
+    #[test]
+    fn test_replay_pending_state_updates(input in arb_blocks_to_commit()) {
+        test_replay_pending_state_updates_impl(input);
+    }
+
+    #[test]
+    fn test_cold_ledger(input in arb_blocks_to_commit()) {
+        test_cold_ledger_impl(input);
+    }
+
+    #[test]
+    fn test_db_checkpoint(input in arb_blocks_to_commit()) {
+        test_db_checkpoint_impl(input);
+    }
+
+    #[test]
+    fn test_secondary_catch_up(input in arb_blocks_to_commit()) {
+        test_secondary_catch_up_impl(input);
+    }
 }
 
 #[test]
@@ -698,3 +950,33 @@ fn test_rocksdb_properties_reporter() {
     std::thread::sleep(Duration::from_secs(1));
     assert_eq!(get_metric(), 1);
 }
+
+#[test]
+fn test_get_storage_usage() {
+    let tmp_dir = TempPath::new();
+    let shard_dirs: Vec<_> = (0..2)
+        .map(|i| tmp_dir.path().join(format!("shard{}", i)))
+        .collect();
+    let db = AptosDB::new_for_test_with_state_shards(&tmp_dir, &shard_dirs);
+
+    let usage = db.get_storage_usage().unwrap();
+    assert_eq!(usage.latest_version, None);
+    // pruning is disabled in tests
+    assert!(usage.least_readable_versions.is_empty());
+    let cfs: Vec<_> = usage
+        .column_families
+        .iter()
+        .map(|cf| (cf.db.as_str(), cf.name.as_str()))
+        .collect();
+    let num_cfs = AptosDB::column_families().len();
+    assert_eq!(
+        cfs.len(),
+        num_cfs + 2 * AptosDB::state_shard_column_families().len()
+    );
+    assert!(cfs[..num_cfs].iter().all(|(db, _)| *db == "aptosdb"));
+    assert!(cfs.contains(&("aptosdb", TRANSACTION_INFO_CF_NAME)));
+    assert!(cfs.contains(&("state_shard_1", JELLYFISH_MERKLE_NODE_CF_NAME)));
+
+    // the properties of the shards are reported as well
+    db.update_rocksdb_properties().unwrap();
+}
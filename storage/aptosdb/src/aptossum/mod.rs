@@ -3,7 +3,7 @@
 
 use crate::{AptosDB, Order, MAX_LIMIT};
 use anyhow::{ensure, format_err, Result};
-use aptos_config::config::{RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_config::config::{RocksdbConfig, DEFAULT_STATE_CACHE_SIZE, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_types::{
     contract_event::ContractEvent,
     event::EventKey,
@@ -23,6 +23,8 @@ impl Aptossum {
             true,                        /* read only */
             NO_OP_STORAGE_PRUNER_CONFIG, /* no prune_window */
             RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
         )?;
         Ok(Aptossum { db })
     }
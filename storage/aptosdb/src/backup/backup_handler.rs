@@ -104,6 +104,7 @@ impl BackupHandler {
         &self,
         version: Version,
     ) -> Result<Box<dyn Iterator<Item = Result<(HashValue, StateKeyAndValue)>> + Send + Sync>> {
+        self.state_store.commit_progress().wait_for(version)?;
         let iterator = JellyfishMerkleIterator::new(
             Arc::clone(&self.state_store),
             version,
@@ -92,7 +92,11 @@ pub fn save_transactions(
 ) -> Result<()> {
     let mut cs = ChangeSet::new();
     for (idx, txn) in txns.iter().enumerate() {
-        transaction_store.put_transaction(first_version + idx as Version, txn, &mut cs)?;
+        let version = first_version + idx as Version;
+        transaction_store.put_transaction(version, txn, &mut cs)?;
+        if let Some(events) = events.get(idx) {
+            transaction_store.put_participants(version, txn, events, &mut cs)?;
+        }
     }
     ledger_store.put_transaction_infos(first_version, txn_infos, &mut cs)?;
     event_store.put_events_multiple_versions(first_version, events, &mut cs)?;
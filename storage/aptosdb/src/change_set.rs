@@ -4,7 +4,7 @@
 use crate::ledger_counters::LedgerCounterBumps;
 use aptos_types::transaction::Version;
 use schemadb::SchemaBatch;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Structure that collects changes to be made to the DB in one transaction.
 ///
@@ -14,6 +14,8 @@ use std::collections::HashMap;
 pub struct ChangeSet {
     /// A batch of db alternations.
     pub batch: SchemaBatch,
+    /// Alternations of the state Merkle tree shards, by shard id, if the tree is sharded.
+    pub state_shard_batches: BTreeMap<usize, SchemaBatch>,
     /// Counter bumps to be made on commit.
     counter_bumps: HashMap<Version, LedgerCounterBumps>,
 }
@@ -23,20 +25,39 @@ impl ChangeSet {
     pub fn new() -> Self {
         Self {
             batch: SchemaBatch::new(),
+            state_shard_batches: BTreeMap::new(),
             counter_bumps: HashMap::new(),
         }
     }
 
+    pub fn state_shard_batch(&mut self, shard_id: usize) -> &mut SchemaBatch {
+        self.state_shard_batches
+            .entry(shard_id)
+            .or_insert_with(SchemaBatch::new)
+    }
+
     pub fn counter_bumps(&mut self, version: Version) -> &mut LedgerCounterBumps {
         self.counter_bumps
             .entry(version)
             .or_insert_with(LedgerCounterBumps::new)
     }
 
-    #[cfg(test)]
+    /// Seals the alternations without the counter bumps, which are returned to be committed later,
+    /// along with the state of the versions, when the commits are pipelined.
+    pub fn seal_without_counters(self) -> (SealedChangeSet, HashMap<Version, LedgerCounterBumps>) {
+        (
+            SealedChangeSet {
+                batch: self.batch,
+                state_shard_batches: self.state_shard_batches,
+            },
+            self.counter_bumps,
+        )
+    }
+
     pub fn new_with_bumps(counter_bumps: HashMap<Version, LedgerCounterBumps>) -> Self {
         Self {
             batch: SchemaBatch::new(),
+            state_shard_batches: BTreeMap::new(),
             counter_bumps,
         }
     }
@@ -48,4 +69,6 @@ impl ChangeSet {
 pub(crate) struct SealedChangeSet {
     /// A batch of db alternations.
     pub batch: SchemaBatch,
+    /// Alternations of the state Merkle tree shards, committed before `batch`.
+    pub state_shard_batches: BTreeMap<usize, SchemaBatch>,
 }
@@ -0,0 +1,370 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides `ColdLedger`, the optional tier the transactions and events of the old
+//! versions are moved to from the main DB, e.g. a RocksDB instance on a cheaper and slower disk,
+//! and `ColdLedgerMover`, which manages a thread moving them in the background as new versions are
+//! committed.
+//!
+//! The records of a range of versions are written to the cold DB first, then the first hot version
+//! is advanced in memory, and last the records are deleted from the main DB in the same batch as
+//! the new first hot version is recorded. So the records of a version are always in at least one
+//! of the DBs, and a crash at most leaves a range in both, which is moved again.
+//!
+//! The point reads look up the main DB first and fall back to the cold DB. The iterators create
+//! their iterator of the main DB before reading the first hot version, so the versions from it on
+//! are in the snapshot of the main DB even if they are moved in the meantime.
+
+use crate::{
+    metrics::APTOS_STORAGE_OTHER_TIMERS_SECONDS,
+    schema::{
+        db_metadata::{DbMetadataKey, DbMetadataSchema},
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
+        transaction::TransactionSchema,
+    },
+};
+use anyhow::Result;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::{proof::position::Position, transaction::Version};
+use schemadb::{schema::Schema, ReadOptions, SchemaBatch, SchemaIterator, DB};
+use std::{
+    cmp::{max, min},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{channel, Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+
+/// The schemas moved to the cold DB, whose keys start with the version.
+pub(crate) trait LedgerSchema: Schema {
+    fn version_of(key: &Self::Key) -> Version;
+
+    /// The smallest key of `version`.
+    fn first_key_of(version: Version) -> Self::Key;
+}
+
+impl LedgerSchema for TransactionSchema {
+    fn version_of(key: &Version) -> Version {
+        *key
+    }
+
+    fn first_key_of(version: Version) -> Version {
+        version
+    }
+}
+
+impl LedgerSchema for EventSchema {
+    fn version_of(key: &(Version, u64)) -> Version {
+        key.0
+    }
+
+    fn first_key_of(version: Version) -> (Version, u64) {
+        (version, 0)
+    }
+}
+
+impl LedgerSchema for EventAccumulatorSchema {
+    fn version_of(key: &(Version, Position)) -> Version {
+        key.0
+    }
+
+    fn first_key_of(version: Version) -> (Version, Position) {
+        (version, Position::from_inorder_index(0))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ColdLedger {
+    db: Arc<DB>,
+    /// The first version whose records are in the main DB, those of the versions before it being
+    /// in the cold DB.
+    first_hot_version: AtomicU64,
+}
+
+impl ColdLedger {
+    pub fn new(db: Arc<DB>, hot_db: &DB) -> Result<Self> {
+        let first_hot_version = hot_db
+            .get::<DbMetadataSchema>(&DbMetadataKey::FirstHotLedgerVersion)?
+            .unwrap_or(0);
+        Ok(Self {
+            db,
+            first_hot_version: AtomicU64::new(first_hot_version),
+        })
+    }
+
+    pub fn db(&self) -> &Arc<DB> {
+        &self.db
+    }
+
+    pub fn first_hot_version(&self) -> Version {
+        self.first_hot_version.load(Ordering::SeqCst)
+    }
+
+    /// Moves the records of the versions from the first hot version to `end_version`, exclusive,
+    /// returning the number of records moved.
+    pub fn move_to(&self, hot_db: &DB, end_version: Version) -> Result<usize> {
+        let begin_version = self.first_hot_version();
+        if end_version <= begin_version {
+            return Ok(0);
+        }
+        let _timer = APTOS_STORAGE_OTHER_TIMERS_SECONDS
+            .with_label_values(&["move_to_cold_ledger"])
+            .start_timer();
+
+        let mut cold_batch = SchemaBatch::new();
+        let mut hot_batch = SchemaBatch::new();
+        let num_moved = Self::move_schema::<TransactionSchema>(
+            hot_db,
+            begin_version,
+            end_version,
+            &mut cold_batch,
+            &mut hot_batch,
+        )? + Self::move_schema::<EventSchema>(
+            hot_db,
+            begin_version,
+            end_version,
+            &mut cold_batch,
+            &mut hot_batch,
+        )? + Self::move_schema::<EventAccumulatorSchema>(
+            hot_db,
+            begin_version,
+            end_version,
+            &mut cold_batch,
+            &mut hot_batch,
+        )?;
+        hot_batch.put::<DbMetadataSchema>(&DbMetadataKey::FirstHotLedgerVersion, &end_version)?;
+
+        self.db.write_schemas(cold_batch)?;
+        self.first_hot_version.store(end_version, Ordering::SeqCst);
+        hot_db.write_schemas(hot_batch)?;
+        Ok(num_moved)
+    }
+
+    fn move_schema<S: LedgerSchema>(
+        hot_db: &DB,
+        begin_version: Version,
+        end_version: Version,
+        cold_batch: &mut SchemaBatch,
+        hot_batch: &mut SchemaBatch,
+    ) -> Result<usize> {
+        let mut num_moved = 0;
+        let mut iter = hot_db.iter::<S>(ReadOptions::default())?;
+        iter.seek(&S::first_key_of(begin_version))?;
+        for res in iter {
+            let (key, value) = res?;
+            if S::version_of(&key) >= end_version {
+                break;
+            }
+            cold_batch.put::<S>(&key, &value)?;
+            num_moved += 1;
+        }
+        hot_batch.delete_range::<S>(
+            &S::first_key_of(begin_version),
+            &S::first_key_of(end_version),
+        )?;
+        Ok(num_moved)
+    }
+}
+
+/// Reads the record from the main DB, or from the cold DB if it's been moved.
+pub(crate) fn get<S: LedgerSchema>(
+    hot_db: &DB,
+    cold_ledger: Option<&ColdLedger>,
+    key: &S::Key,
+) -> Result<Option<S::Value>> {
+    match (hot_db.get::<S>(key)?, cold_ledger) {
+        (None, Some(cold_ledger)) => cold_ledger.db.get::<S>(key),
+        (value, _) => Ok(value),
+    }
+}
+
+/// The main DB followed by the cold DB, if any.
+pub(crate) fn ledger_dbs<'a>(
+    hot_db: &'a DB,
+    cold_ledger: Option<&'a ColdLedger>,
+) -> impl Iterator<Item = &'a DB> {
+    std::iter::once(hot_db).chain(cold_ledger.map(|cold_ledger| cold_ledger.db.as_ref()))
+}
+
+/// Iterates the records of `S` from a version on, those of the versions before the first hot
+/// version from the cold DB and the rest from the main DB.
+pub(crate) struct TieredIter<'a, S> {
+    cold: Option<SchemaIterator<'a, S>>,
+    hot: SchemaIterator<'a, S>,
+    first_hot_version: Version,
+}
+
+impl<'a, S: LedgerSchema> TieredIter<'a, S> {
+    pub fn new(
+        hot_db: &'a DB,
+        cold_ledger: Option<&'a ColdLedger>,
+        start_version: Version,
+    ) -> Result<Self> {
+        let mut hot = hot_db.iter::<S>(ReadOptions::default())?;
+        let first_hot_version = cold_ledger.map_or(0, ColdLedger::first_hot_version);
+        let cold = match cold_ledger {
+            Some(cold_ledger) if start_version < first_hot_version => {
+                let mut cold = cold_ledger.db.iter::<S>(ReadOptions::default())?;
+                cold.seek(&S::first_key_of(start_version))?;
+                Some(cold)
+            }
+            _ => None,
+        };
+        hot.seek(&S::first_key_of(max(start_version, first_hot_version)))?;
+
+        Ok(Self {
+            cold,
+            hot,
+            first_hot_version,
+        })
+    }
+
+    fn next_impl(&mut self) -> Result<Option<(S::Key, S::Value)>> {
+        if let Some(cold) = &mut self.cold {
+            match cold.next().transpose()? {
+                Some((key, value)) if S::version_of(&key) < self.first_hot_version => {
+                    return Ok(Some((key, value)));
+                }
+                // Past the versions read from the cold DB.
+                _ => self.cold = None,
+            }
+        }
+        self.hot.next().transpose()
+    }
+}
+
+impl<'a, S: LedgerSchema> Iterator for TieredIter<'a, S> {
+    type Item = Result<(S::Key, S::Value)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_impl().transpose()
+    }
+}
+
+/// Runs in the background to move the records of the versions older than `hot_window` to the
+/// cold DB. When destructed, it quits the worker thread without waiting for the pending moves.
+#[derive(Debug)]
+pub(crate) struct ColdLedgerMover {
+    hot_window: Version,
+    /// The worker thread handle. It only becomes `None` after joined in `drop()`.
+    worker_thread: Option<JoinHandle<()>>,
+    command_sender: Mutex<Sender<Command>>,
+}
+
+enum Command {
+    Quit,
+    Move { target_version: Version },
+}
+
+impl ColdLedgerMover {
+    pub fn new(
+        hot_db: Arc<DB>,
+        cold_ledger: Arc<ColdLedger>,
+        hot_window: Version,
+        max_versions_to_move_per_batch: u64,
+    ) -> Self {
+        let (command_sender, command_receiver) = channel();
+        let worker_thread = std::thread::Builder::new()
+            .name("aptosdb_cold_ledger_mover".into())
+            .spawn(move || {
+                Self::work(
+                    &hot_db,
+                    &cold_ledger,
+                    command_receiver,
+                    max_versions_to_move_per_batch,
+                )
+            })
+            .expect("Creating cold ledger mover thread should succeed.");
+
+        Self {
+            hot_window,
+            worker_thread: Some(worker_thread),
+            command_sender: Mutex::new(command_sender),
+        }
+    }
+
+    /// Sends the new target to the worker thread as new versions are committed.
+    pub fn wake(&self, latest_version: Version) {
+        // The latest version, not the number of versions, is accounted in the window.
+        let target_version = (latest_version + 1).saturating_sub(self.hot_window);
+        self.command_sender
+            .lock()
+            .send(Command::Move { target_version })
+            .expect("Receiver should not destruct prematurely.");
+    }
+
+    fn work(
+        hot_db: &DB,
+        cold_ledger: &ColdLedger,
+        command_receiver: Receiver<Command>,
+        max_versions_to_move_per_batch: u64,
+    ) {
+        let mut target_version = 0;
+        loop {
+            let first_hot_version = cold_ledger.first_hot_version();
+            // Blocks for the next command only once caught up, otherwise moves the next batch
+            // after draining the channel, in case `Command::Quit` is received.
+            let command = if first_hot_version >= target_version {
+                command_receiver
+                    .recv()
+                    .expect("Sender should not destruct prematurely.")
+            } else {
+                match command_receiver.try_recv() {
+                    Ok(command) => command,
+                    Err(TryRecvError::Empty) => Command::Move { target_version },
+                    Err(TryRecvError::Disconnected) => {
+                        panic!("Sender should not destruct prematurely.")
+                    }
+                }
+            };
+            match command {
+                Command::Quit => return,
+                Command::Move {
+                    target_version: new_target_version,
+                } => target_version = max(target_version, new_target_version),
+            }
+
+            let end_version = min(
+                target_version,
+                first_hot_version.saturating_add(max_versions_to_move_per_batch),
+            );
+            match cold_ledger.move_to(hot_db, end_version) {
+                Ok(num_moved) => debug!(
+                    end_version = end_version,
+                    num_moved = num_moved,
+                    "Moved to the cold ledger.",
+                ),
+                Err(e) => {
+                    error!(
+                        error = ?e,
+                        end_version = end_version,
+                        "Failed to move to the cold ledger.",
+                    );
+                    // Retries on the next command.
+                    target_version = first_hot_version;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ColdLedgerMover {
+    fn drop(&mut self) {
+        self.command_sender
+            .lock()
+            .send(Command::Quit)
+            .expect("Receiver should not destruct.");
+        self.worker_thread
+            .take()
+            .expect("Cold ledger mover thread must exist.")
+            .join()
+            .expect("Cold ledger mover thread should join peacefully.");
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,90 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::AptosDB;
+use aptos_config::config::{RocksdbConfig, DEFAULT_STATE_CACHE_SIZE, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_proptest_helpers::Index;
+use aptos_temppath::TempPath;
+use aptos_types::transaction::Transaction;
+use proptest::{collection::vec, prelude::*};
+
+fn put_transactions(db: &DB, first_version: Version, txns: &[Transaction]) {
+    let mut batch = SchemaBatch::new();
+    for (version, txn) in (first_version..).zip(txns) {
+        batch.put::<TransactionSchema>(&version, txn).unwrap();
+    }
+    db.write_schemas(batch).unwrap();
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_tiered_reads(txns in vec(any::<Transaction>(), 1..10), split in any::<Index>()) {
+        let tmp_dir = TempPath::new();
+        let cold_ledger_dir = tmp_dir.path().join("cold");
+        let num_txns = txns.len() as Version;
+        let split = split.index(txns.len() + 1) as Version;
+        {
+            // Nothing moved by the mover.
+            let db = AptosDB::new_for_test_with_cold_ledger(
+                &tmp_dir,
+                cold_ledger_dir.clone(),
+                u64::MAX, /* hot_window */
+            );
+            let cold_ledger = db.cold_ledger.as_ref().unwrap();
+            put_transactions(&db.db, 0, &txns);
+            prop_assert_eq!(
+                cold_ledger.move_to(&db.db, split).unwrap(),
+                split as usize
+            );
+            prop_assert_eq!(cold_ledger.first_hot_version(), split);
+            let mut iter = db.db.iter::<TransactionSchema>(ReadOptions::default()).unwrap();
+            iter.seek_to_first();
+            let first_hot = iter.next().transpose().unwrap().map(|(version, _)| version);
+            prop_assert_eq!(first_hot, (split < num_txns).then(|| split));
+
+            let store = &db.transaction_store;
+            for (version, txn) in txns.iter().enumerate() {
+                prop_assert_eq!(&store.get_transaction(version as Version).unwrap(), txn);
+            }
+            prop_assert_eq!(store.get_first_txn_version().unwrap(), Some(0));
+            for start_version in 0..num_txns {
+                let iterated = store
+                    .get_transaction_iter(start_version, txns.len())
+                    .unwrap()
+                    .collect::<Result<Vec<_>>>()
+                    .unwrap();
+                prop_assert_eq!(&iterated[..], &txns[start_version as usize..]);
+            }
+
+            // A range left in both DBs by a crash is read once.
+            put_transactions(cold_ledger.db(), split, &txns[split as usize..]);
+            let iterated = store
+                .get_transaction_iter(0, txns.len())
+                .unwrap()
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+            prop_assert_eq!(&iterated, &txns);
+        }
+
+        // The first hot version is recovered, and the cold ledger is then required.
+        let db = AptosDB::new_for_test_with_cold_ledger(
+            &tmp_dir,
+            cold_ledger_dir,
+            u64::MAX, /* hot_window */
+        );
+        prop_assert_eq!(db.cold_ledger.as_ref().unwrap().first_hot_version(), split);
+        drop(db);
+        prop_assert!(AptosDB::open(
+            &tmp_dir,
+            false,                       /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
+        )
+        .is_err() == (split > 0));
+    }
+}
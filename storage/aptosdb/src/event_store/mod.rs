@@ -8,11 +8,13 @@
 use super::AptosDB;
 use crate::{
     change_set::ChangeSet,
+    cold_ledger::{self, ColdLedger, TieredIter},
     errors::AptosDbError,
     ledger_counters::{LedgerCounter, LedgerCounterBumps},
     schema::{
         event::EventSchema, event_accumulator::EventAccumulatorSchema,
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+        event_by_key::EventByKeySchema, event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema,
     },
 };
 use accumulator::{HashReader, MerkleAccumulator};
@@ -30,6 +32,7 @@ use aptos_types::{
     proof::{position::Position, EventAccumulatorProof, EventProof},
     transaction::Version,
 };
+use move_core_types::language_storage::TypeTag;
 use schemadb::{schema::ValueCodec, ReadOptions, SchemaBatch, SchemaIterator, DB};
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
@@ -41,11 +44,31 @@ use std::{
 #[derive(Debug)]
 pub struct EventStore {
     db: Arc<DB>,
+    /// Whether the events are indexed by type in `EventByTypeSchema` as they are committed.
+    enable_type_index: bool,
+    /// The tier the events of the old versions are moved to, if any.
+    cold_ledger: Option<Arc<ColdLedger>>,
 }
 
 impl EventStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, enable_type_index: bool) -> Self {
+        Self {
+            db,
+            enable_type_index,
+            cold_ledger: None,
+        }
+    }
+
+    pub fn new_with_cold_ledger(
+        db: Arc<DB>,
+        enable_type_index: bool,
+        cold_ledger: Arc<ColdLedger>,
+    ) -> Self {
+        Self {
+            db,
+            enable_type_index,
+            cold_ledger: Some(cold_ledger),
+        }
     }
 
     /// Get all of the events given a transaction version.
@@ -54,9 +77,9 @@ impl EventStore {
     pub fn get_events_by_version(&self, version: Version) -> Result<Vec<ContractEvent>> {
         let mut events = vec![];
 
-        let mut iter = self.db.iter::<EventSchema>(ReadOptions::default())?;
         // Grab the first event and then iterate until we get all events for this version.
-        iter.seek(&version)?;
+        let mut iter =
+            TieredIter::<EventSchema>::new(&self.db, self.cold_ledger.as_deref(), version)?;
         while let Some(((ver, index), event)) = iter.next().transpose()? {
             if ver != version {
                 break;
@@ -72,8 +95,7 @@ impl EventStore {
         start_version: Version,
         num_versions: usize,
     ) -> Result<EventsByVersionIter> {
-        let mut iter = self.db.iter::<EventSchema>(Default::default())?;
-        iter.seek(&start_version)?;
+        let iter = TieredIter::new(&self.db, self.cold_ledger.as_deref(), start_version)?;
 
         Ok(EventsByVersionIter {
             inner: iter.peekable(),
@@ -84,13 +106,24 @@ impl EventStore {
         })
     }
 
+    /// Get the version of the first event, if any.
+    pub fn get_first_event_version(&self) -> Result<Option<Version>> {
+        let mut iter = TieredIter::<EventSchema>::new(
+            &self.db,
+            self.cold_ledger.as_deref(),
+            0, /* start_version */
+        )?;
+        iter.next()
+            .map(|res| res.map(|((version, _index), _event)| version))
+            .transpose()
+    }
+
     fn get_event_by_version_and_index(
         &self,
         version: Version,
         index: u64,
     ) -> Result<ContractEvent> {
-        self.db
-            .get::<EventSchema>(&(version, index))?
+        cold_ledger::get::<EventSchema>(&self.db, self.cold_ledger.as_deref(), &(version, index))?
             .ok_or_else(|| {
                 AptosDbError::NotFound(format!("Event {} of Txn {}", index, version)).into()
             })
@@ -105,13 +138,21 @@ impl EventStore {
         // Get event content.
         let event = self.get_event_by_version_and_index(version, index)?;
 
-        // Get the number of events in total for the transaction at `version`.
-        let mut iter = self.db.iter::<EventSchema>(ReadOptions::default())?;
-        iter.seek_for_prev(&(version + 1))?;
-        let num_events = match iter.next().transpose()? {
-            Some(((ver, index), _)) if ver == version => (index + 1),
-            _ => unreachable!(), // since we've already got at least one event above
-        };
+        // Get the number of events in total for the transaction at `version`, from the DB which
+        // still has them.
+        let mut num_events = None;
+        for db in cold_ledger::ledger_dbs(&self.db, self.cold_ledger.as_deref()) {
+            let mut iter = db.iter::<EventSchema>(ReadOptions::default())?;
+            iter.seek_for_prev(&(version + 1))?;
+            if let Some(((ver, index), _)) = iter.next().transpose()? {
+                if ver == version {
+                    num_events = Some(index + 1);
+                    break;
+                }
+            }
+        }
+        // since we've already got at least one event above
+        let num_events = num_events.expect("Events of the version should exist.");
 
         // Get proof.
         let proof =
@@ -227,6 +268,39 @@ impl EventStore {
         Ok((version, index))
     }
 
+    /// Given `type_tag` and `start_version`, returns at most `limit` events of the type emitted by
+    /// the transactions of versions in [`start_version`, `ledger_version`], with the versions of
+    /// the transactions, in ascending order. Only the events committed while the type index is
+    /// enabled are found.
+    pub fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, ContractEvent)>> {
+        ensure!(
+            self.enable_type_index,
+            "The event type index is not enabled in the storage config."
+        );
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(type_tag.clone(), start_version, 0))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((typ, version, index), ()) = res?;
+            if &typ != type_tag || version > ledger_version {
+                break;
+            }
+            result.push((
+                version,
+                self.get_event_by_version_and_index(version, index)?,
+            ));
+        }
+
+        Ok(result)
+    }
+
     /// Save contract events yielded by the transaction at `version` and return root hash of the
     /// event accumulator formed by these events.
     pub fn put_events(
@@ -252,6 +326,12 @@ impl EventStore {
                     &(*event.key(), version, event.sequence_number()),
                     &(idx as u64),
                 )?;
+                if self.enable_type_index {
+                    cs.batch.put::<EventByTypeSchema>(
+                        &(event.type_tag().clone(), version, idx as u64),
+                        &(),
+                    )?;
+                }
                 Ok(())
             })?;
 
@@ -419,6 +499,25 @@ impl EventStore {
         Ok(())
     }
 
+    /// Prunes events by type store for a set of event types in version range [begin, end). The
+    /// records are deleted regardless of the type index being enabled, so none are left behind
+    /// by a node which disabled it.
+    pub fn prune_events_by_type(
+        &self,
+        type_tags: HashSet<TypeTag>,
+        begin: Version,
+        end: Version,
+        db_batch: &mut SchemaBatch,
+    ) -> anyhow::Result<()> {
+        for type_tag in type_tags {
+            db_batch.delete_range::<EventByTypeSchema>(
+                &(type_tag.clone(), begin, 0),
+                &(type_tag, end, 0),
+            )?;
+        }
+        Ok(())
+    }
+
     /// Prunes the event schema for a range of version in [begin, end)
     pub fn prune_event_schema(
         &self,
@@ -445,10 +544,12 @@ impl<'a> EventHashReader<'a> {
 
 impl<'a> HashReader for EventHashReader<'a> {
     fn get(&self, position: Position) -> Result<HashValue> {
-        self.store
-            .db
-            .get::<EventAccumulatorSchema>(&(self.version, position))?
-            .ok_or_else(|| format_err!("Hash at position {:?} not found.", position))
+        cold_ledger::get::<EventAccumulatorSchema>(
+            &self.store.db,
+            self.store.cold_ledger.as_deref(),
+            &(self.version, position),
+        )?
+        .ok_or_else(|| format_err!("Hash at position {:?} not found.", position))
     }
 }
 
@@ -464,7 +565,7 @@ impl HashReader for EmptyReader {
 }
 
 pub struct EventsByVersionIter<'a> {
-    inner: Peekable<SchemaIterator<'a, EventSchema>>,
+    inner: Peekable<TieredIter<'a, EventSchema>>,
     expected_next_version: Version,
     end_version: Version,
 }
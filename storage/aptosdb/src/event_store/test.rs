@@ -57,6 +57,68 @@ fn test_error_on_get_from_empty() {
         .is_err());
 }
 
+#[test]
+fn test_get_events_by_type() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = EventStore::new(Arc::clone(&db.db), true /* enable_type_index */);
+    let event = |seq_num: u64, type_tag: TypeTag| {
+        ContractEvent::new(EventKey::random(), seq_num, type_tag, vec![])
+    };
+    let bool_events = vec![
+        event(0, TypeTag::Bool),
+        event(1, TypeTag::Bool),
+        event(2, TypeTag::Bool),
+    ];
+    let u8_event = event(0, TypeTag::U8);
+    save(&store, 0, &[bool_events[0].clone(), u8_event.clone()]);
+    save(&store, 1, &[bool_events[1].clone()]);
+    save(&store, 2, &[bool_events[2].clone()]);
+
+    assert_eq!(
+        store.get_events_by_type(&TypeTag::Bool, 0, 10, 2).unwrap(),
+        (0..).zip(bool_events.clone()).collect::<Vec<_>>(),
+    );
+    assert_eq!(
+        store.get_events_by_type(&TypeTag::Bool, 1, 1, 2).unwrap(),
+        vec![(1, bool_events[1].clone())],
+    );
+    // events after the ledger version are ignored
+    assert_eq!(
+        store.get_events_by_type(&TypeTag::Bool, 0, 10, 0).unwrap(),
+        vec![(0, bool_events[0].clone())],
+    );
+    assert_eq!(
+        store.get_events_by_type(&TypeTag::U8, 0, 10, 2).unwrap(),
+        vec![(0, u8_event)],
+    );
+    assert!(store
+        .get_events_by_type(&TypeTag::U64, 0, 10, 2)
+        .unwrap()
+        .is_empty());
+
+    let mut db_batch = SchemaBatch::new();
+    store
+        .prune_events_by_type(
+            vec![TypeTag::Bool].into_iter().collect(),
+            0,
+            2,
+            &mut db_batch,
+        )
+        .unwrap();
+    store.db.write_schemas(db_batch).unwrap();
+    assert_eq!(
+        store.get_events_by_type(&TypeTag::Bool, 0, 10, 2).unwrap(),
+        vec![(2, bool_events[2].clone())],
+    );
+
+    // the type index of the DB is disabled
+    assert!(db
+        .event_store
+        .get_events_by_type(&TypeTag::Bool, 0, 10, 2)
+        .is_err());
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
 
@@ -286,6 +348,7 @@ prop_compose! {
                 seq, // round
                 address, // proposer
                 Vec::new(), // prev block voters
+                Vec::new(), // failed proposers
                 timestamp,
             );
             let event = ContractEvent::new(
@@ -33,6 +33,7 @@ use itertools::Itertools;
 use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
 use std::{ops::Deref, sync::Arc};
 use storage_interface::{StartupInfo, TreeState};
+use tokio::sync::watch;
 
 #[derive(Debug)]
 pub struct LedgerStore {
@@ -42,26 +43,43 @@ pub struct LedgerStore {
     /// cache it in memory in order to avoid reading DB and deserializing the object frequently. It
     /// should be updated every time new ledger info and signatures are persisted.
     latest_ledger_info: ArcSwap<Option<LedgerInfoWithSignatures>>,
+
+    /// Notified of the version of the latest ledger info every time it's updated. A receiver is
+    /// kept so the updates are sent without subscriber.
+    committed_version: (watch::Sender<Version>, watch::Receiver<Version>),
 }
 
 impl LedgerStore {
     pub fn new(db: Arc<DB>) -> Self {
         // Upon restart, read the latest ledger info and signatures and cache them in memory.
-        let ledger_info = {
-            let mut iter = db
-                .iter::<LedgerInfoSchema>(ReadOptions::default())
-                .expect("Constructing iterator should work.");
-            iter.seek_to_last();
-            iter.next()
-                .transpose()
-                .expect("Reading latest ledger info from DB should work.")
-                .map(|kv| kv.1)
-        };
+        let ledger_info = Self::read_latest_ledger_info(&db)
+            .expect("Reading latest ledger info from DB should work.");
 
+        let version = ledger_info
+            .as_ref()
+            .map_or(0, |ledger_info| ledger_info.ledger_info().version());
         Self {
             db,
             latest_ledger_info: ArcSwap::from(Arc::new(ledger_info)),
+            committed_version: watch::channel(version),
+        }
+    }
+
+    fn read_latest_ledger_info(db: &DB) -> Result<Option<LedgerInfoWithSignatures>> {
+        let mut iter = db.iter::<LedgerInfoSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        Ok(iter.next().transpose()?.map(|kv| kv.1))
+    }
+
+    /// Reloads the cached latest ledger info from the DB, for a secondary instance to see the
+    /// ledger infos the primary committed.
+    pub fn refresh_latest_ledger_info(&self) -> Result<()> {
+        let ledger_info = Self::read_latest_ledger_info(&self.db)?;
+        if let Some(ledger_info) = &ledger_info {
+            self.notify_committed_version(ledger_info.ledger_info().version());
         }
+        self.latest_ledger_info.store(Arc::new(ledger_info));
+        Ok(())
     }
 
     pub fn get_epoch(&self, version: Version) -> Result<u64> {
@@ -130,8 +148,22 @@ impl LedgerStore {
     }
 
     pub fn set_latest_ledger_info(&self, ledger_info_with_sigs: LedgerInfoWithSignatures) {
+        let version = ledger_info_with_sigs.ledger_info().version();
         self.latest_ledger_info
             .store(Arc::new(Some(ledger_info_with_sigs)));
+        self.notify_committed_version(version);
+    }
+
+    /// The receiver is notified every time the latest ledger info is updated, after it is.
+    pub fn subscribe_committed_version(&self) -> watch::Receiver<Version> {
+        self.committed_version.1.clone()
+    }
+
+    fn notify_committed_version(&self, version: Version) {
+        if *self.committed_version.1.borrow() != version {
+            // A receiver is kept, the send never fails.
+            let _ = self.committed_version.0.send(version);
+        }
     }
 
     pub fn get_latest_ledger_info_in_epoch(&self, epoch: u64) -> Result<LedgerInfoWithSignatures> {
@@ -21,10 +21,12 @@ pub mod metrics;
 pub mod schema;
 
 mod change_set;
+mod cold_ledger;
 mod event_store;
 mod ledger_counters;
 mod ledger_store;
 mod pruner;
+mod state_committer;
 mod state_store;
 mod system_store;
 mod transaction_store;
@@ -39,24 +41,39 @@ pub use aptosdb_test::test_save_blocks_impl;
 use crate::{
     backup::{backup_handler::BackupHandler, restore_handler::RestoreHandler, restore_utils},
     change_set::{ChangeSet, SealedChangeSet},
+    cold_ledger::{ColdLedger, ColdLedgerMover},
     errors::AptosDbError,
     event_store::EventStore,
     ledger_counters::LedgerCounters,
     ledger_store::LedgerStore,
     metrics::{
-        APTOS_STORAGE_API_LATENCY_SECONDS, APTOS_STORAGE_COMMITTED_TXNS,
+        APTOS_STORAGE_API_LATENCY_SECONDS, APTOS_STORAGE_COLUMN_FAMILY_NUM_KEYS,
+        APTOS_STORAGE_COLUMN_FAMILY_SIZE_BYTES, APTOS_STORAGE_COMMITTED_TXNS,
         APTOS_STORAGE_LATEST_ACCOUNT_COUNT, APTOS_STORAGE_LATEST_TXN_VERSION,
         APTOS_STORAGE_LEDGER_VERSION, APTOS_STORAGE_NEXT_BLOCK_EPOCH,
         APTOS_STORAGE_OTHER_TIMERS_SECONDS, APTOS_STORAGE_ROCKSDB_PROPERTIES,
     },
     pruner::{utils, Pruner},
-    schema::*,
-    state_store::StateStore,
+    schema::{
+        db_metadata::{DbMetadataKey, DbMetadataSchema},
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        pending_state_updates::PendingStateUpdatesSchema,
+        stale_node_index::StaleNodeIndexSchema,
+        state_value_index::StateValueIndexSchema,
+        *,
+    },
+    state_committer::{StateCommit, StateCommitter},
+    state_store::{state_shards::StateShards, StateStore},
     system_store::SystemStore,
     transaction_store::TransactionStore,
 };
 use anyhow::{ensure, format_err, Result};
-use aptos_config::config::{RocksdbConfig, StoragePrunerConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+#[cfg(any(test, feature = "fuzzing"))]
+use aptos_config::config::DEFAULT_STATE_CACHE_SIZE;
+use aptos_config::config::{
+    ColdLedgerConfig, RocksdbColumnFamilyConfig, RocksdbCompactionStyle, RocksdbCompressionType,
+    RocksdbConfig, StoragePrunerConfig, NO_OP_STORAGE_PRUNER_CONFIG,
+};
 use aptos_crypto::hash::{HashValue, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
@@ -84,21 +101,32 @@ use aptos_types::{
     },
 };
 use itertools::zip_eq;
+use move_core_types::language_storage::TypeTag;
 use once_cell::sync::Lazy;
-use schemadb::{ColumnFamilyName, Options, SchemaBatch, DB, DEFAULT_CF_NAME};
+use schemadb::{
+    BlockBasedOptions, Cache, ColumnFamilyName, DBCompactionStyle, DBCompressionType, Options,
+    ReadOptions, SchemaBatch, DB, DEFAULT_CF_NAME,
+};
 use std::{
     collections::HashMap,
     iter::Iterator,
-    path::Path,
+    path::{Path, PathBuf},
     sync::{mpsc, Arc},
     thread,
     thread::JoinHandle,
     time::{Duration, Instant},
 };
-use storage_interface::{DbReader, DbWriter, Order, StartupInfo, StateSnapshotReceiver, TreeState};
+use storage_interface::{
+    AccountHistory, ColumnFamilyUsage, DbCheckpointManifest, DbCheckpointer, DbReader, DbWriter,
+    Order, StartupInfo, StateSnapshotProgress, StateSnapshotReceiver, StorageUsage, TreeState,
+};
+use tokio::sync::watch;
 
 const MAX_LIMIT: u64 = 5000;
 
+/// The file describing a checkpoint made by `create_db_checkpoint`, in the checkpoint dir.
+pub const DB_CHECKPOINT_MANIFEST_FILE: &str = "checkpoint_manifest.json";
+
 // TODO: Either implement an iteration API to allow a very old client to loop through a long history
 // or guarantee that there is always a recent enough waypoint and client knows to boot from there.
 const MAX_NUM_EPOCH_ENDING_LEDGER_INFO: usize = 100;
@@ -157,7 +185,26 @@ fn gen_rocksdb_options(config: &RocksdbConfig) -> Options {
     db_opts
 }
 
-fn update_rocksdb_properties(db: &DB) -> Result<()> {
+fn gen_cf_options(config: &RocksdbColumnFamilyConfig) -> Result<Options> {
+    let mut table_opts = BlockBasedOptions::default();
+    table_opts.set_block_cache(&Cache::new_lru_cache(config.block_cache_size)?);
+
+    let mut cf_opts = Options::default();
+    cf_opts.set_block_based_table_factory(&table_opts);
+    cf_opts.set_compression_type(match config.compression {
+        RocksdbCompressionType::None => DBCompressionType::None,
+        RocksdbCompressionType::Lz4 => DBCompressionType::Lz4,
+        RocksdbCompressionType::Lz4hc => DBCompressionType::Lz4hc,
+    });
+    cf_opts.set_write_buffer_size(config.write_buffer_size);
+    cf_opts.set_compaction_style(match config.compaction_style {
+        RocksdbCompactionStyle::Level => DBCompactionStyle::Level,
+        RocksdbCompactionStyle::Universal => DBCompactionStyle::Universal,
+    });
+    Ok(cf_opts)
+}
+
+fn update_rocksdb_properties(db: &DB, state_shard_dbs: &[Arc<DB>]) -> Result<()> {
     let _timer = APTOS_STORAGE_OTHER_TIMERS_SECONDS
         .with_label_values(&["update_rocksdb_properties"])
         .start_timer();
@@ -168,9 +215,53 @@ fn update_rocksdb_properties(db: &DB) -> Result<()> {
                 .set(db.get_property(cf_name, rockdb_property_name)? as i64);
         }
     }
+    for usage in get_column_family_usages(db, state_shard_dbs)? {
+        APTOS_STORAGE_COLUMN_FAMILY_SIZE_BYTES
+            .with_label_values(&[&usage.db, &usage.name])
+            .set(usage.total_sst_files_size as i64);
+        APTOS_STORAGE_COLUMN_FAMILY_NUM_KEYS
+            .with_label_values(&[&usage.db, &usage.name])
+            .set(usage.estimated_num_keys as i64);
+    }
     Ok(())
 }
 
+/// The usage of the column families of the main DB, followed by the ones of the state shards.
+fn get_column_family_usages(
+    db: &DB,
+    state_shard_dbs: &[Arc<DB>],
+) -> Result<Vec<ColumnFamilyUsage>> {
+    let mut usages = get_db_column_family_usages("aptosdb", db, AptosDB::column_families())?;
+    for (shard_id, shard_db) in state_shard_dbs.iter().enumerate() {
+        usages.extend(get_db_column_family_usages(
+            &format!("state_shard_{}", shard_id),
+            shard_db,
+            AptosDB::state_shard_column_families(),
+        )?);
+    }
+    Ok(usages)
+}
+
+fn get_db_column_family_usages(
+    db_name: &str,
+    db: &DB,
+    cf_names: Vec<ColumnFamilyName>,
+) -> Result<Vec<ColumnFamilyUsage>> {
+    cf_names
+        .into_iter()
+        .map(|cf_name| {
+            Ok(ColumnFamilyUsage {
+                db: db_name.to_string(),
+                name: cf_name.to_string(),
+                total_sst_files_size: db.get_property(cf_name, "rocksdb.total-sst-files-size")?,
+                estimated_live_data_size: db
+                    .get_property(cf_name, "rocksdb.estimate-live-data-size")?,
+                estimated_num_keys: db.get_property(cf_name, "rocksdb.estimate-num-keys")?,
+            })
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 struct RocksdbPropertyReporter {
     sender: Mutex<mpsc::Sender<()>>,
@@ -178,10 +269,10 @@ struct RocksdbPropertyReporter {
 }
 
 impl RocksdbPropertyReporter {
-    fn new(db: Arc<DB>) -> Self {
+    fn new(db: Arc<DB>, state_shard_dbs: Vec<Arc<DB>>) -> Self {
         let (send, recv) = mpsc::channel();
         let join_handle = Some(thread::spawn(move || loop {
-            if let Err(e) = update_rocksdb_properties(&db) {
+            if let Err(e) = update_rocksdb_properties(&db, &state_shard_dbs) {
                 warn!(
                     error = ?e,
                     "Updating rocksdb property failed."
@@ -229,6 +320,14 @@ pub struct AptosDB {
     event_store: Arc<EventStore>,
     system_store: Arc<SystemStore>,
     pruner: Option<Pruner>,
+    /// Commits the state of the versions after their ledger when the commits are pipelined.
+    state_committer: Option<StateCommitter>,
+    /// The tier the transactions and events of the old versions are moved to, if any, and the
+    /// mover moving them unless opened readonly.
+    cold_ledger: Option<Arc<ColdLedger>>,
+    cold_ledger_mover: Option<ColdLedgerMover>,
+    /// Held by each commit, and while creating a checkpoint so it's at no version in between.
+    commit_lock: Mutex<()>,
     _rocksdb_property_reporter: RocksdbPropertyReporter,
 }
 
@@ -236,50 +335,146 @@ impl AptosDB {
     fn column_families() -> Vec<ColumnFamilyName> {
         vec![
             /* LedgerInfo CF = */ DEFAULT_CF_NAME,
+            AUTHENTICATION_KEY_HISTORY_CF_NAME,
+            DB_METADATA_CF_NAME,
             EPOCH_BY_VERSION_CF_NAME,
             EVENT_ACCUMULATOR_CF_NAME,
             EVENT_BY_KEY_CF_NAME,
+            EVENT_BY_TYPE_CF_NAME,
             EVENT_BY_VERSION_CF_NAME,
             EVENT_CF_NAME,
             JELLYFISH_MERKLE_NODE_CF_NAME,
             LEDGER_COUNTERS_CF_NAME,
+            PENDING_STATE_UPDATES_CF_NAME,
             STALE_NODE_INDEX_CF_NAME,
             STATE_VALUE_INDEX_CF_NAME,
             TRANSACTION_CF_NAME,
             TRANSACTION_ACCUMULATOR_CF_NAME,
             TRANSACTION_BY_ACCOUNT_CF_NAME,
             TRANSACTION_BY_HASH_CF_NAME,
+            TRANSACTION_BY_PARTICIPANT_CF_NAME,
             TRANSACTION_INFO_CF_NAME,
             WRITE_SET_CF_NAME,
         ]
     }
 
-    fn new_with_db(db: DB, storage_pruner_config: StoragePrunerConfig) -> Self {
+    /// The column families of each of the DBs the state Merkle tree is sharded across.
+    fn state_shard_column_families() -> Vec<ColumnFamilyName> {
+        vec![
+            DEFAULT_CF_NAME,
+            DB_METADATA_CF_NAME,
+            JELLYFISH_MERKLE_NODE_CF_NAME,
+            STALE_NODE_INDEX_CF_NAME,
+            STATE_VALUE_INDEX_CF_NAME,
+        ]
+    }
+
+    /// The column families of the DB the old transactions and events are moved to.
+    fn cold_ledger_column_families() -> Vec<ColumnFamilyName> {
+        vec![
+            DEFAULT_CF_NAME,
+            EVENT_ACCUMULATOR_CF_NAME,
+            EVENT_CF_NAME,
+            TRANSACTION_CF_NAME,
+        ]
+    }
+
+    fn gen_cfs(
+        column_families: Vec<ColumnFamilyName>,
+        rocksdb_config: &RocksdbConfig,
+    ) -> Result<Vec<(ColumnFamilyName, Options)>> {
+        for cf_name in rocksdb_config.column_families.keys() {
+            ensure!(
+                Self::column_families().contains(&cf_name.as_str()),
+                "Unknown column family {} in the rocksdb config.",
+                cf_name,
+            );
+        }
+        column_families
+            .into_iter()
+            .map(|cf_name| {
+                let cf_opts = gen_cf_options(rocksdb_config.column_family(cf_name))?;
+                Ok((cf_name, cf_opts))
+            })
+            .collect()
+    }
+
+    fn new_with_db(
+        db: DB,
+        state_shard_dbs: Vec<DB>,
+        cold_ledger_db: Option<DB>,
+        storage_pruner_config: StoragePrunerConfig,
+        state_cache_size: usize,
+        enable_event_type_index: bool,
+        enable_commit_pipelining: bool,
+    ) -> Result<Self> {
         let db = Arc::new(db);
-        let transaction_store = Arc::new(TransactionStore::new(Arc::clone(&db)));
-        let event_store = Arc::new(EventStore::new(Arc::clone(&db)));
+        let state_store = Arc::new(if state_shard_dbs.is_empty() {
+            StateStore::new(Arc::clone(&db), state_cache_size)
+        } else {
+            let state_shards =
+                StateShards::new(state_shard_dbs.into_iter().map(Arc::new).collect())?;
+            StateStore::new_with_shards(state_shards, state_cache_size)
+        });
+        let state_shard_dbs = state_store.state_shard_dbs().to_vec();
+        let cold_ledger = cold_ledger_db
+            .map(|cold_ledger_db| ColdLedger::new(Arc::new(cold_ledger_db), &db))
+            .transpose()?
+            .map(Arc::new);
+        let (transaction_store, event_store) = match &cold_ledger {
+            Some(cold_ledger) => (
+                TransactionStore::new_with_cold_ledger(Arc::clone(&db), Arc::clone(cold_ledger)),
+                EventStore::new_with_cold_ledger(
+                    Arc::clone(&db),
+                    enable_event_type_index,
+                    Arc::clone(cold_ledger),
+                ),
+            ),
+            None => (
+                TransactionStore::new(Arc::clone(&db)),
+                EventStore::new(Arc::clone(&db), enable_event_type_index),
+            ),
+        };
+        let transaction_store = Arc::new(transaction_store);
+        let event_store = Arc::new(event_store);
         let ledger_store = Arc::new(LedgerStore::new(Arc::clone(&db)));
         let system_store = Arc::new(SystemStore::new(Arc::clone(&db)));
 
-        AptosDB {
+        Ok(AptosDB {
             db: Arc::clone(&db),
             event_store: Arc::clone(&event_store),
             ledger_store: Arc::clone(&ledger_store),
-            state_store: Arc::new(StateStore::new(Arc::clone(&db))),
+            state_store: Arc::clone(&state_store),
             transaction_store: Arc::clone(&transaction_store),
             system_store: Arc::clone(&system_store),
+            state_committer: enable_commit_pipelining
+                .then(|| {
+                    StateCommitter::new(
+                        Arc::clone(&db),
+                        Arc::clone(&state_store),
+                        Arc::clone(&system_store),
+                    )
+                })
+                .transpose()?,
+            cold_ledger,
+            cold_ledger_mover: None,
+            commit_lock: Mutex::new(()),
             pruner: match storage_pruner_config {
                 NO_OP_STORAGE_PRUNER_CONFIG => None,
                 _ => Some(Pruner::new(
                     Arc::clone(&db),
                     storage_pruner_config,
+                    state_store,
                     transaction_store,
                     ledger_store,
                     event_store,
                 )),
             },
-            _rocksdb_property_reporter: RocksdbPropertyReporter::new(Arc::clone(&db)),
-        }
+            _rocksdb_property_reporter: RocksdbPropertyReporter::new(
+                Arc::clone(&db),
+                state_shard_dbs,
+            ),
+        })
     }
 
     pub fn open<P: AsRef<Path> + Clone>(
@@ -287,6 +482,46 @@ impl AptosDB {
         readonly: bool,
         storage_pruner_config: StoragePrunerConfig,
         rocksdb_config: RocksdbConfig,
+        state_cache_size: usize,
+        enable_event_type_index: bool,
+    ) -> Result<Self> {
+        Self::open_with_state_shards(
+            db_root_path,
+            &[],
+            readonly,
+            storage_pruner_config,
+            rocksdb_config,
+            state_cache_size,
+            enable_event_type_index,
+            false, /* enable_commit_pipelining */
+            None,  /* cold_ledger_config */
+        )
+    }
+
+    /// Same as `open`, but with the state Merkle tree sharded across a RocksDB instance in each of
+    /// `state_shard_dirs`, e.g. one on each NVMe device, or kept in the main DB if it's empty.
+    ///
+    /// The number of shards must divide 16, and can't change once the DB has state; the state of
+    /// an existing unsharded DB is moved to the shards by `migrate_state_to_shards`.
+    ///
+    /// With `enable_commit_pipelining`, `save_transactions` returns once the ledger of the versions
+    /// is committed, their state being committed to the state Merkle tree in the background. The
+    /// state left uncommitted by a crash is committed when the DB is opened, whether pipelining
+    /// is enabled or not; it's ignored when opening readonly.
+    ///
+    /// With `cold_ledger_config`, the transactions and events of the old versions are moved to
+    /// the RocksDB instance in its dir in the background, unless opened readonly.
+    #[allow(clippy::too_many_arguments)]
+    pub fn open_with_state_shards<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        state_shard_dirs: &[PathBuf],
+        readonly: bool,
+        storage_pruner_config: StoragePrunerConfig,
+        rocksdb_config: RocksdbConfig,
+        state_cache_size: usize,
+        enable_event_type_index: bool,
+        enable_commit_pipelining: bool,
+        cold_ledger_config: Option<ColdLedgerConfig>,
     ) -> Result<Self> {
         ensure!(
             storage_pruner_config.eq(&NO_OP_STORAGE_PRUNER_CONFIG) || !readonly,
@@ -308,17 +543,65 @@ impl AptosDB {
         } else {
             rocksdb_opts.create_if_missing(true);
             rocksdb_opts.create_missing_column_families(true);
-            DB::open(
+            DB::open_with_cf_opts(
                 path.clone(),
                 "aptosdb",
-                Self::column_families(),
+                Self::gen_cfs(Self::column_families(), &rocksdb_config)?,
                 &rocksdb_opts,
             )?
         };
+        let state_shard_dbs =
+            Self::open_state_shard_dbs(state_shard_dirs, readonly, &rocksdb_config)?;
+        Self::check_state_shards(&db, state_shard_dirs, &state_shard_dbs, readonly)?;
+        let cold_ledger_db = match &cold_ledger_config {
+            Some(config) => Some(Self::open_cold_ledger_db(
+                &config.dir,
+                readonly,
+                &rocksdb_config,
+            )?),
+            None => {
+                Self::ensure_no_cold_ledger(&db)?;
+                None
+            }
+        };
 
-        let ret = Self::new_with_db(db, storage_pruner_config);
+        let mut ret = Self::new_with_db(
+            db,
+            state_shard_dbs,
+            cold_ledger_db,
+            storage_pruner_config,
+            state_cache_size,
+            enable_event_type_index,
+            enable_commit_pipelining && !readonly,
+        )?;
+        if !readonly {
+            let num_replayed = state_committer::replay_pending_state_updates(
+                &ret.db,
+                &ret.state_store,
+                &ret.system_store,
+                &ret.event_store,
+            )?;
+            if num_replayed > 0 {
+                info!(
+                    num_versions = num_replayed,
+                    "Committed the state left uncommitted by the last run.",
+                );
+            }
+            if let (Some(cold_ledger), Some(config)) = (&ret.cold_ledger, &cold_ledger_config) {
+                ret.cold_ledger_mover = Some(ColdLedgerMover::new(
+                    Arc::clone(&ret.db),
+                    Arc::clone(cold_ledger),
+                    config.hot_window,
+                    config.max_versions_to_move_per_batch,
+                ));
+                if let Some(ledger_info) = ret.ledger_store.get_latest_ledger_info_option() {
+                    ret.wake_cold_ledger_mover(ledger_info.ledger_info().version());
+                }
+            }
+        }
         info!(
             path = path,
+            num_state_shards = state_shard_dirs.len(),
             time_ms = %instant.elapsed().as_millis(),
             "Opened AptosDB.",
         );
@@ -329,6 +612,8 @@ impl AptosDB {
         db_root_path: P,
         secondary_path: P,
         mut rocksdb_config: RocksdbConfig,
+        state_cache_size: usize,
+        enable_event_type_index: bool,
     ) -> Result<Self> {
         let primary_path = db_root_path.as_ref().join("aptosdb");
         let secondary_path = secondary_path.as_ref().to_path_buf();
@@ -336,16 +621,257 @@ impl AptosDB {
         rocksdb_config.max_open_files = -1;
         let rocksdb_opts = gen_rocksdb_options(&rocksdb_config);
 
-        Ok(Self::new_with_db(
-            DB::open_as_secondary(
-                primary_path,
-                secondary_path,
-                "aptosdb_sec",
-                Self::column_families(),
-                &rocksdb_opts,
-            )?,
+        let db = DB::open_as_secondary(
+            primary_path,
+            secondary_path,
+            "aptosdb_sec",
+            Self::column_families(),
+            &rocksdb_opts,
+        )?;
+        ensure!(
+            db.get::<DbMetadataSchema>(&DbMetadataKey::NumStateShards)?
+                .is_none(),
+            "A DB with the state Merkle tree sharded can't be opened as secondary.",
+        );
+        Self::ensure_no_cold_ledger(&db)?;
+        Self::new_with_db(
+            db,
+            vec![],
+            None, /* cold_ledger_db */
             NO_OP_STORAGE_PRUNER_CONFIG,
-        ))
+            state_cache_size,
+            enable_event_type_index,
+            false, /* enable_commit_pipelining */
+        )
+    }
+
+    fn open_state_shard_dbs(
+        state_shard_dirs: &[PathBuf],
+        readonly: bool,
+        rocksdb_config: &RocksdbConfig,
+    ) -> Result<Vec<DB>> {
+        if !state_shard_dirs.is_empty() {
+            StateShards::ensure_num_shards(state_shard_dirs.len())?;
+        }
+        let mut rocksdb_opts = gen_rocksdb_options(rocksdb_config);
+        if !readonly {
+            rocksdb_opts.create_if_missing(true);
+            rocksdb_opts.create_missing_column_families(true);
+        }
+        state_shard_dirs
+            .iter()
+            .map(|dir| {
+                let path = dir.join("aptosdb_state_shard");
+                if readonly {
+                    DB::open_readonly(
+                        path,
+                        "aptosdb_state_shard_ro",
+                        Self::state_shard_column_families(),
+                        &rocksdb_opts,
+                    )
+                } else {
+                    DB::open_with_cf_opts(
+                        path,
+                        "aptosdb_state_shard",
+                        Self::gen_cfs(Self::state_shard_column_families(), rocksdb_config)?,
+                        &rocksdb_opts,
+                    )
+                }
+            })
+            .collect()
+    }
+
+    fn open_cold_ledger_db(
+        dir: &Path,
+        readonly: bool,
+        rocksdb_config: &RocksdbConfig,
+    ) -> Result<DB> {
+        let path = dir.join("aptosdb_cold_ledger");
+        let mut rocksdb_opts = gen_rocksdb_options(rocksdb_config);
+        if readonly {
+            DB::open_readonly(
+                path,
+                "aptosdb_cold_ledger_ro",
+                Self::cold_ledger_column_families(),
+                &rocksdb_opts,
+            )
+        } else {
+            rocksdb_opts.create_if_missing(true);
+            rocksdb_opts.create_missing_column_families(true);
+            DB::open_with_cf_opts(
+                path,
+                "aptosdb_cold_ledger",
+                Self::gen_cfs(Self::cold_ledger_column_families(), rocksdb_config)?,
+                &rocksdb_opts,
+            )
+        }
+    }
+
+    /// Ensures that none of the ledger of the DB has been moved to the cold ledger, which is not
+    /// configured.
+    fn ensure_no_cold_ledger(db: &DB) -> Result<()> {
+        ensure!(
+            db.get::<DbMetadataSchema>(&DbMetadataKey::FirstHotLedgerVersion)?
+                .is_none(),
+            "The old transactions and events of the DB are moved to the cold ledger, which must \
+             be configured.",
+        );
+        Ok(())
+    }
+
+    /// Checks that the state Merkle tree is sharded across the DBs as the main DB records,
+    /// recording the sharding if the DB has no state yet.
+    fn check_state_shards(
+        db: &DB,
+        state_shard_dirs: &[PathBuf],
+        state_shard_dbs: &[DB],
+        readonly: bool,
+    ) -> Result<()> {
+        let num_shards = state_shard_dbs.len() as u64;
+        match db.get::<DbMetadataSchema>(&DbMetadataKey::NumStateShards)? {
+            Some(recorded) => {
+                ensure!(
+                    recorded == num_shards,
+                    "The state Merkle tree is sharded across {} DBs, but {} state shard dirs are \
+                     configured.",
+                    recorded,
+                    num_shards,
+                );
+                Self::check_state_shard_ids(
+                    state_shard_dirs,
+                    state_shard_dbs,
+                    false, /* init */
+                )
+            }
+            None if num_shards == 0 => Ok(()),
+            None => {
+                let mut iter = db.iter::<JellyfishMerkleNodeSchema>(ReadOptions::default())?;
+                iter.seek_to_first();
+                ensure!(
+                    iter.next().is_none(),
+                    "The state Merkle tree is in the main DB, migrate it to the shards first.",
+                );
+                ensure!(
+                    !readonly,
+                    "Can't set up the state shards when opening readonly."
+                );
+                Self::check_state_shard_ids(
+                    state_shard_dirs,
+                    state_shard_dbs,
+                    true, /* init */
+                )?;
+                db.put::<DbMetadataSchema>(&DbMetadataKey::NumStateShards, &num_shards)
+            }
+        }
+    }
+
+    /// Checks that each of the state shards is the shard it is configured as, recording it first
+    /// in the shards that don't know yet if `init` is set.
+    fn check_state_shard_ids(
+        state_shard_dirs: &[PathBuf],
+        state_shard_dbs: &[DB],
+        init: bool,
+    ) -> Result<()> {
+        let num_shards = state_shard_dbs.len() as u64;
+        zip_eq(state_shard_dirs, state_shard_dbs)
+            .enumerate()
+            .try_for_each(|(shard_id, (dir, shard_db))| {
+                let mut recorded = (
+                    shard_db.get::<DbMetadataSchema>(&DbMetadataKey::StateShardId)?,
+                    shard_db.get::<DbMetadataSchema>(&DbMetadataKey::NumStateShards)?,
+                );
+                if init && recorded == (None, None) {
+                    let mut batch = SchemaBatch::new();
+                    batch.put::<DbMetadataSchema>(
+                        &DbMetadataKey::StateShardId,
+                        &(shard_id as u64),
+                    )?;
+                    batch.put::<DbMetadataSchema>(&DbMetadataKey::NumStateShards, &num_shards)?;
+                    shard_db.write_schemas(batch)?;
+                    info!(dir = dir, shard_id = shard_id, "Set up state shard.");
+                    recorded = (Some(shard_id as u64), Some(num_shards));
+                }
+                ensure!(
+                    recorded == (Some(shard_id as u64), Some(num_shards)),
+                    "State shard dir {:?} holds shard {:?} of {:?}, expected shard {} of {}.",
+                    dir,
+                    recorded.0,
+                    recorded.1,
+                    shard_id,
+                    num_shards,
+                );
+                Ok(())
+            })
+    }
+
+    /// Moves the state Merkle tree of the unsharded DB at `db_root_path` to a RocksDB instance in
+    /// each of `state_shard_dirs`, after which the DB is to be opened by `open_with_state_shards`
+    /// with the same dirs in the same order. The DB must not be open elsewhere. An interrupted
+    /// migration is resumed by running it again.
+    pub fn migrate_state_to_shards<P: AsRef<Path>>(
+        db_root_path: P,
+        state_shard_dirs: &[PathBuf],
+        rocksdb_config: RocksdbConfig,
+    ) -> Result<()> {
+        let path = db_root_path.as_ref().join("aptosdb");
+        let instant = Instant::now();
+        let mut rocksdb_opts = gen_rocksdb_options(&rocksdb_config);
+        rocksdb_opts.create_missing_column_families(true);
+        let db = DB::open_with_cf_opts(
+            path.clone(),
+            "aptosdb",
+            Self::gen_cfs(Self::column_families(), &rocksdb_config)?,
+            &rocksdb_opts,
+        )?;
+        ensure!(
+            db.get::<DbMetadataSchema>(&DbMetadataKey::NumStateShards)?
+                .is_none(),
+            "The state Merkle tree is already sharded.",
+        );
+        StateShards::ensure_num_shards(state_shard_dirs.len())?;
+        let state_shard_dbs = Self::open_state_shard_dbs(state_shard_dirs, false, &rocksdb_config)?;
+        Self::check_state_shard_ids(state_shard_dirs, &state_shard_dbs, true /* init */)?;
+        let state_shards = StateShards::new(state_shard_dbs.into_iter().map(Arc::new).collect())?;
+
+        let num_stale_node_indices = state_shards
+            .move_from::<StaleNodeIndexSchema, _>(&db, |index| {
+                state_shards.shard_id_by_nibble_path(index.node_key.nibble_path())
+            })?;
+        let num_value_indices = state_shards
+            .move_from::<StateValueIndexSchema, _>(&db, |(state_key, _version)| {
+                state_shards.shard_id_by_state_key(state_key)
+            })?;
+        // The nodes go last, so a DB without nodes in the main DB has nothing left to move.
+        let num_nodes = state_shards
+            .move_from::<JellyfishMerkleNodeSchema, _>(&db, |node_key| {
+                state_shards.shard_id_by_nibble_path(node_key.nibble_path())
+            })?;
+        // Recorded last, so the DB is only opened with the shards once they hold the whole tree.
+        db.put::<DbMetadataSchema>(
+            &DbMetadataKey::NumStateShards,
+            &(state_shards.num_shards() as u64),
+        )?;
+
+        info!(
+            path = path,
+            num_state_shards = state_shards.num_shards(),
+            num_nodes = num_nodes,
+            num_stale_node_indices = num_stale_node_indices,
+            num_value_indices = num_value_indices,
+            time_ms = %instant.elapsed().as_millis(),
+            "Migrated the state Merkle tree to the shards.",
+        );
+        Ok(())
+    }
+
+    /// Catches up a db opened by `open_as_secondary` with the primary, so that the data committed
+    /// by the primary so far is readable.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        let _timer = APTOS_STORAGE_OTHER_TIMERS_SECONDS
+            .with_label_values(&["try_catch_up_with_primary"])
+            .start_timer();
+        self.db.try_catch_up_with_primary()?;
+        self.ledger_store.refresh_latest_ledger_info()
     }
 
     /// This opens db in non-readonly mode, without the pruner.
@@ -356,13 +882,78 @@ impl AptosDB {
             false,                       /* readonly */
             NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
             RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
+        )
+        .expect("Unable to open AptosDB")
+    }
+
+    /// Same as `new_for_test`, with the state Merkle tree sharded across `state_shard_dirs`.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn new_for_test_with_state_shards<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        state_shard_dirs: &[PathBuf],
+    ) -> Self {
+        Self::open_with_state_shards(
+            db_root_path,
+            state_shard_dirs,
+            false,                       /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
+            false, /* enable_commit_pipelining */
+            None,  /* cold_ledger_config */
+        )
+        .expect("Unable to open AptosDB")
+    }
+
+    /// Same as `new_for_test`, with the commits pipelined.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn new_for_test_with_commit_pipelining<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
+        Self::open_with_state_shards(
+            db_root_path,
+            &[],
+            false,                       /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
+            true,  /* enable_commit_pipelining */
+            None,  /* cold_ledger_config */
+        )
+        .expect("Unable to open AptosDB")
+    }
+
+    /// Same as `new_for_test`, with the transactions and events older than `hot_window` versions
+    /// moved to the cold ledger in `cold_ledger_dir`.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn new_for_test_with_cold_ledger<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        cold_ledger_dir: PathBuf,
+        hot_window: u64,
+    ) -> Self {
+        Self::open_with_state_shards(
+            db_root_path,
+            &[],
+            false,                       /* readonly */
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            RocksdbConfig::default(),
+            DEFAULT_STATE_CACHE_SIZE,
+            false, /* enable_event_type_index */
+            false, /* enable_commit_pipelining */
+            Some(ColdLedgerConfig {
+                dir: cold_ledger_dir,
+                hot_window,
+                max_versions_to_move_per_batch: 100,
+            }),
         )
         .expect("Unable to open AptosDB")
     }
 
     /// This force the db to update rocksdb properties immediately.
     pub fn update_rocksdb_properties(&self) -> Result<()> {
-        update_rocksdb_properties(&self.db)
+        update_rocksdb_properties(&self.db, self.state_store.state_shard_dbs())
     }
 
     /// Returns ledger infos reflecting epoch bumps starting with the given epoch. If there are no
@@ -453,6 +1044,14 @@ impl AptosDB {
         })
     }
 
+    /// Gets the state root hash at `version`, recomputed from the hashes of the children of the
+    /// root node, or `None` if the root node doesn't exist, e.g. pruned.
+    pub fn get_state_root_hash_option(&self, version: Version) -> Result<Option<HashValue>> {
+        gauged_api("get_state_root_hash_option", || {
+            self.state_store.get_root_hash_option(version)
+        })
+    }
+
     // ================================== Backup APIs ===================================
 
     /// Gets an instance of `BackupHandler` for data backup purpose.
@@ -467,6 +1066,10 @@ impl AptosDB {
 
     /// Creates new physical DB checkpoint in directory specified by `path`.
     pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        ensure!(
+            !self.state_store.is_sharded(),
+            "Checkpointing a DB with the state Merkle tree sharded is not supported.",
+        );
         let start = Instant::now();
         self.db.create_checkpoint(&path).map(|_| {
             info!(
@@ -571,7 +1174,13 @@ impl AptosDB {
             None
         };
 
-        Ok((SealedChangeSet { batch: cs.batch }, counters))
+        Ok((
+            SealedChangeSet {
+                batch: cs.batch,
+                state_shard_batches: cs.state_shard_batches,
+            },
+            counters,
+        ))
     }
 
     fn save_transactions_impl(
@@ -588,17 +1197,31 @@ impl AptosDB {
                 .with_label_values(&["save_transactions_state"])
                 .start_timer();
 
-            let account_state_sets = txns_to_commit
-                .iter()
-                .map(|txn_to_commit| txn_to_commit.state_updates())
-                .collect::<Vec<_>>();
-
-            let node_hashes = txns_to_commit
-                .iter()
-                .map(|txn_to_commit| txn_to_commit.jf_node_hashes())
-                .collect::<Option<Vec<_>>>();
-            self.state_store
-                .put_value_sets(account_state_sets, node_hashes, first_version, cs)?;
+            if self.state_committer.is_some() {
+                // The state Merkle tree is updated by the state committer, after the commit.
+                zip_eq(first_version..=last_version, txns_to_commit).try_for_each(
+                    |(ver, txn_to_commit)| {
+                        cs.batch
+                            .put::<PendingStateUpdatesSchema>(&ver, txn_to_commit.state_updates())
+                    },
+                )?;
+            } else {
+                let account_state_sets = txns_to_commit
+                    .iter()
+                    .map(|txn_to_commit| txn_to_commit.state_updates())
+                    .collect::<Vec<_>>();
+
+                let node_hashes = txns_to_commit
+                    .iter()
+                    .map(|txn_to_commit| txn_to_commit.jf_node_hashes())
+                    .collect::<Option<Vec<_>>>();
+                self.state_store.put_value_sets(
+                    account_state_sets,
+                    node_hashes,
+                    first_version,
+                    cs,
+                )?;
+            }
         }
 
         // Event updates. Gather event accumulator root hashes.
@@ -617,11 +1240,24 @@ impl AptosDB {
             let _timer = APTOS_STORAGE_OTHER_TIMERS_SECONDS
                 .with_label_values(&["save_transactions_txn_infos"])
                 .start_timer();
+            let mut latest_authentication_keys = HashMap::new();
             zip_eq(first_version..=last_version, txns_to_commit).try_for_each(
                 |(ver, txn_to_commit)| {
                     // Transaction updates. Gather transaction hashes.
                     self.transaction_store
                         .put_transaction(ver, txn_to_commit.transaction(), cs)?;
+                    self.transaction_store.put_authentication_keys(
+                        ver,
+                        txn_to_commit.write_set(),
+                        &mut latest_authentication_keys,
+                        cs,
+                    )?;
+                    self.transaction_store.put_participants(
+                        ver,
+                        txn_to_commit.transaction(),
+                        txn_to_commit.events(),
+                        cs,
+                    )?;
                     self.transaction_store
                         .put_write_set(ver, txn_to_commit.write_set(), cs)
                 },
@@ -643,11 +1279,20 @@ impl AptosDB {
     /// state of some transaction by leveraging rocksdb atomicity support. Also committed are the
     /// LedgerCounters.
     fn commit(&self, sealed_cs: SealedChangeSet) -> Result<()> {
+        // The state shards are written first, as the main DB batch is what commits the versions.
+        self.state_store
+            .commit_state_shard_batches(sealed_cs.state_shard_batches)?;
         self.db.write_schemas(sealed_cs.batch)?;
 
         Ok(())
     }
 
+    fn wake_cold_ledger_mover(&self, latest_version: Version) {
+        if let Some(cold_ledger_mover) = self.cold_ledger_mover.as_ref() {
+            cold_ledger_mover.wake(latest_version)
+        }
+    }
+
     fn wake_pruner(&self, latest_version: Version) {
         if let Some(pruner) = self.pruner.as_ref() {
             pruner.wake(latest_version)
@@ -668,14 +1313,17 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn subscribe_committed_version(&self) -> Result<watch::Receiver<Version>> {
+        gauged_api("subscribe_committed_version", || {
+            Ok(self.ledger_store.subscribe_committed_version())
+        })
+    }
+
     fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>> {
         gauged_api("get_latest_state_value", || {
             let ledger_info_with_sigs = self.ledger_store.get_latest_ledger_info()?;
             let version = ledger_info_with_sigs.ledger_info().version();
-            let (blob, _proof) = self
-                .state_store
-                .get_value_with_proof_by_version(&state_key, version)?;
-            Ok(blob)
+            self.state_store.get_value_by_version(&state_key, version)
         })
     }
 
@@ -702,6 +1350,25 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_account_history(
+        &self,
+        address: AccountAddress,
+        ledger_version: Version,
+    ) -> Result<AccountHistory> {
+        gauged_api("get_account_history", || {
+            let authentication_keys = self
+                .transaction_store
+                .get_authentication_key_history(address, ledger_version)?;
+            Ok(AccountHistory {
+                creation_version: authentication_keys.first().map(|(version, _)| *version),
+                authentication_key_rotations: authentication_keys.len().saturating_sub(1) as u64,
+                last_transaction_version: self
+                    .transaction_store
+                    .get_account_last_transaction_version(address, ledger_version)?,
+            })
+        })
+    }
+
     fn get_account_transactions(
         &self,
         address: AccountAddress,
@@ -731,6 +1398,40 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_account_participated_transactions(
+        &self,
+        address: AccountAddress,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<AccountTransactionsWithProof> {
+        gauged_api("get_account_participated_transactions", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+            // The pruner can leave index entries of pruned transactions behind, skip them.
+            let start_version = std::cmp::max(
+                start_version,
+                self.get_least_readable_transaction_version()?,
+            );
+            let txns_with_proofs = self
+                .transaction_store
+                .get_participated_transaction_versions(
+                    address,
+                    start_version,
+                    limit,
+                    ledger_version,
+                )?
+                .into_iter()
+                .map(|txn_version| {
+                    self.get_transaction_with_proof(txn_version, ledger_version, include_events)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(AccountTransactionsWithProof::new(txns_with_proofs))
+        })
+    }
+
     /// This API is best-effort in that it CANNOT provide absense proof.
     fn get_transaction_by_hash(
         &self,
@@ -887,6 +1588,31 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_latest_event_sequence_number(
+        &self,
+        event_key: &EventKey,
+        ledger_version: Version,
+    ) -> Result<Option<u64>> {
+        gauged_api("get_latest_event_sequence_number", || {
+            self.event_store
+                .get_latest_sequence_number(ledger_version, event_key)
+        })
+    }
+
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, ContractEvent)>> {
+        gauged_api("get_events_by_type", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+            self.event_store
+                .get_events_by_type(type_tag, start_version, limit, ledger_version)
+        })
+    }
+
     fn get_events_with_proofs(
         &self,
         event_key: &EventKey,
@@ -1024,6 +1750,17 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_value_by_version(
+        &self,
+        state_store_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        gauged_api("get_state_value_by_version", || {
+            self.state_store
+                .get_value_by_version(state_store_key, version)
+        })
+    }
+
     fn get_latest_tree_state(&self) -> Result<TreeState> {
         gauged_api("get_latest_tree_state", || {
             let tree_state = match self.ledger_store.get_latest_transaction_info_option()? {
@@ -1192,11 +1929,126 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_state_snapshot_progress(&self) -> Result<Option<StateSnapshotProgress>> {
+        gauged_api("get_state_snapshot_progress", || {
+            self.db
+                .get::<DbMetadataSchema>(&DbMetadataKey::StateSnapshotRestoreVersion)?
+                .map(|version| {
+                    Ok(StateSnapshotProgress {
+                        version,
+                        num_restored_keys: self
+                            .state_store
+                            .get_num_restored_snapshot_keys(version)?,
+                    })
+                })
+                .transpose()
+        })
+    }
+
     fn get_state_prune_window(&self) -> Option<usize> {
         self.pruner
             .as_ref()
             .map(|x| x.get_state_store_pruner_window() as usize)
     }
+
+    fn get_least_readable_state_version(&self) -> Result<Version> {
+        Ok(self
+            .pruner
+            .as_ref()
+            .map_or(0, |x| x.get_least_readable_state_store_version()))
+    }
+
+    fn get_least_readable_transaction_version(&self) -> Result<Version> {
+        Ok(self
+            .pruner
+            .as_ref()
+            .map_or(0, |x| x.get_least_readable_transaction_version()))
+    }
+
+    fn get_storage_usage(&self) -> Result<StorageUsage> {
+        gauged_api("get_storage_usage", || {
+            Ok(StorageUsage {
+                latest_version: self.get_latest_version_option()?,
+                least_readable_versions: self
+                    .pruner
+                    .as_ref()
+                    .map(|pruner| pruner.get_least_readable_versions())
+                    .unwrap_or_default(),
+                column_families: get_column_family_usages(
+                    &self.db,
+                    self.state_store.state_shard_dbs(),
+                )?,
+            })
+        })
+    }
+}
+
+impl DbCheckpointer for AptosDB {
+    /// The checkpoint of each RocksDB instance hard links the SST files of the instance if on the
+    /// same file system, so it's cheap. The commits are blocked meanwhile, after the state of the
+    /// versions committed so far is committed when the commits are pipelined.
+    fn create_db_checkpoint(&self, checkpoint_dir: PathBuf) -> Result<DbCheckpointManifest> {
+        gauged_api("create_db_checkpoint", || {
+            ensure!(
+                !checkpoint_dir.exists(),
+                "Checkpoint dir {:?} already exists.",
+                checkpoint_dir,
+            );
+            let instant = Instant::now();
+            let _commit_lock = self.commit_lock.lock();
+            self.state_store.commit_progress().wait_for_all()?;
+
+            let ledger_info = self.ledger_store.get_latest_ledger_info_option();
+            let ledger_info = ledger_info.as_ref().map(|li| li.ledger_info());
+            let manifest = DbCheckpointManifest {
+                version: ledger_info.map(|li| li.version()),
+                epoch: ledger_info.map(|li| li.epoch()),
+                accumulator_root_hash: ledger_info.map(|li| li.transaction_accumulator_hash()),
+                db_dir: PathBuf::from("."),
+                state_shard_dirs: (0..self.state_store.state_shard_dbs().len())
+                    .map(|shard_id| PathBuf::from(format!("state_shard_{}", shard_id)))
+                    .collect(),
+                cold_ledger_dir: self
+                    .cold_ledger
+                    .as_ref()
+                    .map(|_| PathBuf::from("cold_ledger")),
+            };
+
+            std::fs::create_dir_all(&checkpoint_dir)?;
+            // The main DB goes first: the cold ledger mover writes to the cold DB before deleting
+            // from the main DB, so the versions it moves in the meantime are in either checkpoint.
+            self.db.create_checkpoint(checkpoint_dir.join("aptosdb"))?;
+            for (shard_dir, shard_db) in zip_eq(
+                &manifest.state_shard_dirs,
+                self.state_store.state_shard_dbs(),
+            ) {
+                let shard_dir = checkpoint_dir.join(shard_dir);
+                std::fs::create_dir_all(&shard_dir)?;
+                shard_db.create_checkpoint(shard_dir.join("aptosdb_state_shard"))?;
+            }
+            if let (Some(cold_ledger), Some(cold_ledger_dir)) =
+                (&self.cold_ledger, &manifest.cold_ledger_dir)
+            {
+                let cold_ledger_dir = checkpoint_dir.join(cold_ledger_dir);
+                std::fs::create_dir_all(&cold_ledger_dir)?;
+                cold_ledger
+                    .db()
+                    .create_checkpoint(cold_ledger_dir.join("aptosdb_cold_ledger"))?;
+            }
+            std::fs::write(
+                checkpoint_dir.join(DB_CHECKPOINT_MANIFEST_FILE),
+                serde_json::to_vec_pretty(&manifest)?,
+            )?;
+
+            info!(
+                path = checkpoint_dir,
+                version = manifest.version,
+                time_ms = %instant.elapsed().as_millis(),
+                "Created DB checkpoint.",
+            );
+            Ok(manifest)
+        })
+    }
 }
 
 impl DbWriter for AptosDB {
@@ -1222,6 +2074,11 @@ impl DbWriter for AptosDB {
         ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
     ) -> Result<()> {
         gauged_api("save_transactions", || {
+            let _commit_lock = self.commit_lock.lock();
+            // The ledger isn't committed further once committing the state failed.
+            if let Some(state_committer) = &self.state_committer {
+                state_committer.check()?;
+            }
             let num_txns = txns_to_commit.len() as u64;
             // ledger_info_with_sigs could be None if we are doing state synchronization. In this case
             // txns_to_commit should not be empty. Otherwise it is okay to commit empty blocks.
@@ -1260,8 +2117,15 @@ impl DbWriter for AptosDB {
                 self.ledger_store.put_ledger_info(x, &mut cs)?;
             }
 
-            // Persist.
-            let (sealed_cs, counters) = self.seal_change_set(first_version, num_txns, cs)?;
+            // Persist. When the commits are pipelined, the ledger counters are committed along with
+            // the state, by the state committer.
+            let (sealed_cs, counters, counter_bumps) = if self.state_committer.is_some() {
+                let (sealed_cs, counter_bumps) = cs.seal_without_counters();
+                (sealed_cs, None, counter_bumps)
+            } else {
+                let (sealed_cs, counters) = self.seal_change_set(first_version, num_txns, cs)?;
+                (sealed_cs, counters, HashMap::new())
+            };
             {
                 let _timer = APTOS_STORAGE_OTHER_TIMERS_SECONDS
                     .with_label_values(&["save_transactions_commit"])
@@ -1269,6 +2133,25 @@ impl DbWriter for AptosDB {
                 self.commit(sealed_cs)?;
             }
 
+            // Queued before the ledger info is updated, so the reads of the state of the versions
+            // wait for it to be committed.
+            if let Some(state_committer) = &self.state_committer {
+                if num_txns > 0 {
+                    state_committer.queue(StateCommit {
+                        first_version,
+                        value_sets: txns_to_commit
+                            .iter()
+                            .map(|txn_to_commit| txn_to_commit.state_updates().clone())
+                            .collect(),
+                        node_hashes: txns_to_commit
+                            .iter()
+                            .map(|txn_to_commit| txn_to_commit.jf_node_hashes().cloned())
+                            .collect(),
+                        counter_bumps,
+                    })?;
+                }
+            }
+
             // Once everything is successfully persisted, update the latest in-memory ledger info.
             if let Some(x) = ledger_info_with_sigs {
                 self.ledger_store.set_latest_ledger_info(x.clone());
@@ -1283,17 +2166,26 @@ impl DbWriter for AptosDB {
                 let last_version = first_version + num_txns - 1;
                 APTOS_STORAGE_COMMITTED_TXNS.inc_by(num_txns);
                 APTOS_STORAGE_LATEST_TXN_VERSION.set(last_version as i64);
-                counters
-                    .expect("Counters should be bumped with transactions being saved.")
-                    .bump_op_counters();
-                // -1 for "not fully migrated", -2 for "error on get_account_count()"
-                APTOS_STORAGE_LATEST_ACCOUNT_COUNT.set(
-                    self.state_store
-                        .get_value_count(last_version)
-                        .map_or(-1, |c| c as i64),
-                );
+                if self.state_committer.is_some() {
+                    // The state committer bumps the counters. The state of the queued versions is
+                    // not to be pruned before it's committed.
+                    if let Some(version) = self.state_store.commit_progress().committed_version() {
+                        self.wake_pruner(version);
+                    }
+                } else {
+                    counters
+                        .expect("Counters should be bumped with transactions being saved.")
+                        .bump_op_counters();
+                    // -1 for "not fully migrated", -2 for "error on get_account_count()"
+                    APTOS_STORAGE_LATEST_ACCOUNT_COUNT.set(
+                        self.state_store
+                            .get_value_count(last_version)
+                            .map_or(-1, |c| c as i64),
+                    );
 
-                self.wake_pruner(last_version);
+                    self.wake_pruner(last_version);
+                }
+                self.wake_cold_ledger_mover(last_version);
             }
 
             Ok(())
@@ -1306,6 +2198,9 @@ impl DbWriter for AptosDB {
         expected_root_hash: HashValue,
     ) -> Result<Box<dyn StateSnapshotReceiver<StateKeyAndValue>>> {
         gauged_api("get_state_snapshot_receiver", || {
+            // Recorded so that the restore can be resumed after a restart.
+            self.db
+                .put::<DbMetadataSchema>(&DbMetadataKey::StateSnapshotRestoreVersion, &version)?;
             self.state_store
                 .get_snapshot_receiver(version, expected_root_hash)
         })
@@ -1369,15 +2264,22 @@ impl DbWriter for AptosDB {
                 self.transaction_store.clone(),
                 version,
                 outputs,
-            )
+            )?;
+
+            // The restore is complete, there's nothing to resume anymore.
+            let mut batch = SchemaBatch::new();
+            batch.delete::<DbMetadataSchema>(&DbMetadataKey::StateSnapshotRestoreVersion)?;
+            self.db.write_schemas(batch)
         })
     }
 
     fn delete_genesis(&self) -> Result<()> {
         gauged_api("delete_genesis", || {
+            self.state_store.commit_progress().wait_for_all()?;
             // Create all the db pruners
             let db_pruners = utils::create_db_pruners(
                 self.db.clone(),
+                self.state_store.clone(),
                 self.transaction_store.clone(),
                 self.ledger_store.clone(),
                 self.event_store.clone(),
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics::{
-    register_histogram_vec, register_int_counter, register_int_gauge, register_int_gauge_vec,
-    HistogramVec, IntCounter, IntGauge, IntGaugeVec,
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -76,6 +76,19 @@ pub static APTOS_PRUNER_LEAST_READABLE_VERSION: Lazy<IntGaugeVec> = Lazy::new(||
     .unwrap()
 });
 
+/// DB pruner target versions, the versions before which the pruners are to prune
+pub static APTOS_PRUNER_TARGET_VERSION: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "aptos_pruner_target_version",
+        // metric description
+        "Aptos pruner target version",
+        // metric labels (dimensions)
+        &["pruner_name",]
+    )
+    .unwrap()
+});
+
 pub static APTOS_STORAGE_API_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         // metric name
@@ -113,6 +126,31 @@ pub static APTOS_STORAGE_ROCKSDB_PROPERTIES: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// The disk usage of the column families of all the DBs, including the state shards
+pub static APTOS_STORAGE_COLUMN_FAMILY_SIZE_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "aptos_storage_column_family_size_bytes",
+        // metric description
+        "Total size of the SST files of the column family",
+        // metric labels (dimensions)
+        &["db", "cf_name"]
+    )
+    .unwrap()
+});
+
+pub static APTOS_STORAGE_COLUMN_FAMILY_NUM_KEYS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "aptos_storage_column_family_num_keys",
+        // metric description
+        "Estimated number of keys in the column family",
+        // metric labels (dimensions)
+        &["db", "cf_name"]
+    )
+    .unwrap()
+});
+
 // Backup progress gauges:
 
 pub(crate) static BACKUP_EPOCH_ENDING_EPOCH: Lazy<IntGauge> = Lazy::new(|| {
@@ -146,3 +184,15 @@ pub(crate) static BACKUP_STATE_SNAPSHOT_LEAF_IDX: Lazy<IntGauge> = Lazy::new(||
     )
     .unwrap()
 });
+
+pub static APTOS_STORAGE_STATE_CACHE: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "aptos_storage_state_cache",
+        // metric description
+        "Aptos storage state cache lookups, by whether they hit or missed",
+        // metric labels (dimensions)
+        &["result"]
+    )
+    .unwrap()
+});
@@ -1,7 +1,8 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    event::EventSchema, metrics::APTOS_PRUNER_LEAST_READABLE_VERSION, pruner::db_pruner::DBPruner,
+    metrics::{APTOS_PRUNER_LEAST_READABLE_VERSION, APTOS_PRUNER_TARGET_VERSION},
+    pruner::db_pruner::DBPruner,
     EventStore,
 };
 use aptos_types::{
@@ -10,7 +11,7 @@ use aptos_types::{
     transaction::{AtomicVersion, Version},
 };
 use itertools::Itertools;
-use schemadb::{ReadOptions, SchemaBatch, DB};
+use schemadb::SchemaBatch;
 use std::{
     collections::HashSet,
     sync::{atomic::Ordering, Arc},
@@ -19,7 +20,6 @@ use std::{
 pub const EVENT_STORE_PRUNER_NAME: &str = "event store pruner";
 
 pub struct EventStorePruner {
-    db: Arc<DB>,
     event_store: Arc<EventStore>,
     /// Keeps track of the target version that the pruner needs to achieve.
     target_version: AtomicVersion,
@@ -51,6 +51,16 @@ impl DBPruner for EventStorePruner {
         self.event_store
             .prune_events_by_key(&candidate_events, db_batch)?;
 
+        self.event_store.prune_events_by_type(
+            candidate_events
+                .iter()
+                .map(|event| event.type_tag().clone())
+                .collect(),
+            self.least_readable_version(),
+            current_target_version,
+            db_batch,
+        )?;
+
         self.event_store.prune_event_accumulator(
             self.least_readable_version(),
             current_target_version,
@@ -68,9 +78,8 @@ impl DBPruner for EventStorePruner {
     }
 
     fn initialize_least_readable_version(&self) -> anyhow::Result<Version> {
-        let mut iter = self.db.iter::<EventSchema>(ReadOptions::default())?;
-        iter.seek_to_first();
-        let version = iter.next().transpose()?.map_or(0, |(key, _)| key.0);
+        // Including the events moved to the cold ledger.
+        let version = self.event_store.get_first_event_version()?.unwrap_or(0);
         Ok(version)
     }
 
@@ -79,7 +88,10 @@ impl DBPruner for EventStorePruner {
     }
 
     fn set_target_version(&self, target_version: Version) {
-        self.target_version.store(target_version, Ordering::Relaxed)
+        self.target_version.store(target_version, Ordering::Relaxed);
+        APTOS_PRUNER_TARGET_VERSION
+            .with_label_values(&["event_store"])
+            .set(target_version as i64);
     }
 
     fn target_version(&self) -> Version {
@@ -96,9 +108,8 @@ impl DBPruner for EventStorePruner {
 }
 
 impl EventStorePruner {
-    pub(in crate::pruner) fn new(db: Arc<DB>, event_store: Arc<EventStore>) -> Self {
+    pub(in crate::pruner) fn new(event_store: Arc<EventStore>) -> Self {
         EventStorePruner {
-            db,
             event_store,
             target_version: AtomicVersion::new(0),
             least_readable_version: AtomicVersion::new(0),
@@ -43,8 +43,12 @@ fn verify_event_store_pruner(events: Vec<Vec<ContractEvent>>) {
         StoragePrunerConfig {
             state_store_prune_window: Some(0),
             default_prune_window: Some(0),
+            transaction_store_prune_window: None,
+            event_store_prune_window: None,
+            write_set_prune_window: None,
             max_version_to_prune_per_batch: Some(100),
         },
+        Arc::clone(&aptos_db.state_store),
         Arc::clone(&aptos_db.transaction_store),
         Arc::clone(&aptos_db.ledger_store),
         Arc::clone(&aptos_db.event_store),
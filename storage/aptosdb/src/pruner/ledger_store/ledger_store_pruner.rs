@@ -1,8 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    metrics::APTOS_PRUNER_LEAST_READABLE_VERSION, pruner::db_pruner::DBPruner,
-    schema::ledger_counters::LedgerCountersSchema, LedgerStore,
+    metrics::{APTOS_PRUNER_LEAST_READABLE_VERSION, APTOS_PRUNER_TARGET_VERSION},
+    pruner::db_pruner::DBPruner,
+    schema::ledger_counters::LedgerCountersSchema,
+    LedgerStore,
 };
 use aptos_types::transaction::{AtomicVersion, Version};
 use schemadb::{ReadOptions, SchemaBatch, DB};
@@ -48,7 +50,10 @@ impl DBPruner for LedgerStorePruner {
     }
 
     fn set_target_version(&self, target_version: Version) {
-        self.target_version.store(target_version, Ordering::Relaxed)
+        self.target_version.store(target_version, Ordering::Relaxed);
+        APTOS_PRUNER_TARGET_VERSION
+            .with_label_values(&["ledger_store"])
+            .set(target_version as i64);
     }
 
     fn target_version(&self) -> Version {
@@ -17,10 +17,11 @@ use crate::metrics::APTOS_STORAGE_PRUNE_WINDOW;
 use aptos_config::config::StoragePrunerConfig;
 use aptos_infallible::Mutex;
 
-use crate::{EventStore, LedgerStore, TransactionStore};
+use crate::{EventStore, LedgerStore, StateStore, TransactionStore};
 use aptos_types::transaction::Version;
 use schemadb::DB;
 use std::{
+    collections::BTreeMap,
     sync::{
         mpsc::{channel, Sender},
         Arc,
@@ -36,24 +37,32 @@ use worker::{Command, Worker};
 /// quits the worker thread eagerly without waiting for all pending work to be done.
 #[derive(Debug)]
 pub(crate) struct Pruner {
-    /// DB version window, which dictates how many versions of state store
-    /// to keep.
-    state_store_prune_window: Version,
-    /// DB version window, which dictates how many version of other stores like transaction, ledger
-    /// info, events etc to keep.
-    default_prune_window: Version,
+    /// DB version windows, which dictate how many versions of each store to keep, indexed like
+    /// `least_readable_version`.
+    prune_windows: Vec<Version>,
     /// The worker thread handle, created upon Pruner instance construction and joined upon its
     /// destruction. It only becomes `None` after joined in `drop()`.
     worker_thread: Option<JoinHandle<()>>,
     /// The sender side of the channel talking to the worker thread.
     command_sender: Mutex<Sender<Command>>,
-    /// (For tests) A way for the worker thread to inform the `Pruner` the pruning progress. If it
+    /// A way for the worker thread to inform the `Pruner` the pruning progress. If it
     /// sets value to `V`, all versions before `V` can no longer be accessed. This is protected by Mutex
     /// as this is accessed both by the Pruner thread and the worker thread.
-    #[allow(dead_code)]
     least_readable_version: Arc<Mutex<Vec<Version>>>,
 }
 
+// The indices of the pruners in `least_readable_version`, see `PrunerIndex`.
+const STATE_STORE_PRUNER_INDEX: usize = 0;
+const TRANSACTION_STORE_PRUNER_INDEX: usize = 1;
+// The names of the pruners in the order of `PrunerIndex`, the same as in the metrics.
+const PRUNER_NAMES: [&str; 5] = [
+    "state_store",
+    "transaction_store",
+    "ledger_store",
+    "event_store",
+    "write_set",
+];
+
 #[cfg(test)]
 pub enum PrunerIndex {
     StateStorePrunerIndex,
@@ -68,22 +77,41 @@ impl Pruner {
     pub fn new(
         db: Arc<DB>,
         storage_pruner_config: StoragePrunerConfig,
+        state_store: Arc<StateStore>,
         transaction_store: Arc<TransactionStore>,
         ledger_store: Arc<LedgerStore>,
         event_store: Arc<EventStore>,
     ) -> Self {
+        let state_store_prune_window = storage_pruner_config
+            .state_store_prune_window
+            .expect("State store prune window must be specified");
+        let default_prune_window = storage_pruner_config
+            .default_prune_window
+            .expect("Default prune window must be specified");
+        // In the order of the pruners, see `PrunerIndex`.
+        let prune_windows = vec![
+            state_store_prune_window,
+            storage_pruner_config
+                .transaction_store_prune_window
+                .unwrap_or(default_prune_window),
+            default_prune_window,
+            storage_pruner_config
+                .event_store_prune_window
+                .unwrap_or(default_prune_window),
+            storage_pruner_config
+                .write_set_prune_window
+                .unwrap_or(default_prune_window),
+        ];
+
         let (command_sender, command_receiver) = channel();
 
         let least_readable_version = Arc::new(Mutex::new(vec![0, 0, 0, 0, 0]));
         let worker_progress_clone = Arc::clone(&least_readable_version);
 
-        APTOS_STORAGE_PRUNE_WINDOW.set(
-            storage_pruner_config
-                .state_store_prune_window
-                .expect("State store pruner window is required") as i64,
-        );
+        APTOS_STORAGE_PRUNE_WINDOW.set(state_store_prune_window as i64);
         let worker = Worker::new(
             db,
+            state_store,
             transaction_store,
             ledger_store,
             event_store,
@@ -99,12 +127,7 @@ impl Pruner {
             .expect("Creating pruner thread should succeed.");
 
         Self {
-            state_store_prune_window: storage_pruner_config
-                .state_store_prune_window
-                .expect("State store prune window must be specified"),
-            default_prune_window: storage_pruner_config
-                .default_prune_window
-                .expect("Default prune window must be specified"),
+            prune_windows,
             worker_thread: Some(worker_thread),
             command_sender: Mutex::new(command_sender),
             least_readable_version: worker_progress_clone,
@@ -112,27 +135,50 @@ impl Pruner {
     }
 
     pub fn get_state_store_pruner_window(&self) -> Version {
-        self.state_store_prune_window
+        self.prune_windows[STATE_STORE_PRUNER_INDEX]
+    }
+
+    /// Returns the version before which the state store has been pruned.
+    pub fn get_least_readable_state_store_version(&self) -> Version {
+        self.least_readable_version.lock()[STATE_STORE_PRUNER_INDEX]
+    }
+
+    /// Returns the version before which the transaction store has been pruned.
+    pub fn get_least_readable_transaction_store_version(&self) -> Version {
+        self.least_readable_version.lock()[TRANSACTION_STORE_PRUNER_INDEX]
+    }
+
+    /// Returns the version before which any of the stores of the transactions, i.e. all but the
+    /// state store, has been pruned, so the transactions from it are readable with all their data
+    /// whatever the prune windows of the stores.
+    pub fn get_least_readable_transaction_version(&self) -> Version {
+        self.least_readable_version.lock()[TRANSACTION_STORE_PRUNER_INDEX..]
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the version before which each store has been pruned, by the pruner name.
+    pub fn get_least_readable_versions(&self) -> BTreeMap<String, Version> {
+        PRUNER_NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .zip(self.least_readable_version.lock().iter().copied())
+            .collect()
     }
 
     /// Sends pruning command to the worker thread when necessary.
     pub fn wake(&self, latest_version: Version) {
-        let least_readable_state_store_version =
-            latest_version.saturating_sub(self.state_store_prune_window);
-        let least_readable_default_store_version =
-            latest_version.saturating_sub(self.default_prune_window);
+        let target_db_versions = self
+            .prune_windows
+            .iter()
+            .map(|prune_window| latest_version.saturating_sub(*prune_window))
+            .collect();
 
         self.command_sender
             .lock()
-            .send(Command::Prune {
-                target_db_versions: vec![
-                    least_readable_state_store_version,
-                    least_readable_default_store_version,
-                    least_readable_default_store_version,
-                    least_readable_default_store_version,
-                    least_readable_default_store_version,
-                ],
-            })
+            .send(Command::Prune { target_db_versions })
             .expect("Receiver should not destruct prematurely.");
     }
 
@@ -151,10 +197,9 @@ impl Pruner {
 
         self.wake(latest_version);
 
-        if latest_version > self.state_store_prune_window
-            || latest_version > self.default_prune_window
-        {
-            let least_readable_state_store_version = latest_version - self.state_store_prune_window;
+        let prune_window = self.prune_windows[pruner_index];
+        if latest_version > prune_window {
+            let least_readable_version = latest_version - prune_window;
             // Assuming no big pruning chunks will be issued by a test.
             const TIMEOUT: Duration = Duration::from_secs(10);
             let end = Instant::now() + TIMEOUT;
@@ -165,7 +210,7 @@ impl Pruner {
                     .lock()
                     .get(pruner_index)
                     .unwrap()
-                    >= least_readable_state_store_version
+                    >= least_readable_version
                 {
                     return Ok(());
                 }
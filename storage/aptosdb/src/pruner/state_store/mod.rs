@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    jellyfish_merkle_node::JellyfishMerkleNodeSchema, metrics::APTOS_PRUNER_LEAST_READABLE_VERSION,
-    pruner::db_pruner::DBPruner, stale_node_index::StaleNodeIndexSchema,
+    jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+    metrics::{APTOS_PRUNER_LEAST_READABLE_VERSION, APTOS_PRUNER_TARGET_VERSION},
+    pruner::db_pruner::DBPruner,
+    stale_node_index::StaleNodeIndexSchema,
     APTOS_STORAGE_OTHER_TIMERS_SECONDS,
 };
 use aptos_infallible::Mutex;
@@ -23,7 +25,8 @@ mod test;
 pub const STATE_STORE_PRUNER_NAME: &str = "state store pruner";
 
 pub struct StateStorePruner {
-    db: Arc<DB>,
+    /// The DBs the state Merkle tree is spread across, only the main DB if it's not sharded.
+    state_dbs: Vec<Arc<DB>>,
     index_min_nonpurged_version: AtomicVersion,
     index_purged_at: Mutex<Instant>,
     /// Keeps track of the target version that the pruner needs to achieve.
@@ -40,7 +43,7 @@ impl DBPruner for StateStorePruner {
         let least_readable_version = self.least_readable_version.load(Ordering::Relaxed);
         let target_version = self.target_version();
         return match prune_state_store(
-            self.db.clone(),
+            &self.state_dbs,
             least_readable_version,
             target_version,
             max_versions as usize,
@@ -68,16 +71,21 @@ impl DBPruner for StateStorePruner {
     }
 
     fn initialize_least_readable_version(&self) -> anyhow::Result<Version> {
-        let mut iter = self
-            .db
-            .iter::<StaleNodeIndexSchema>(ReadOptions::default())?;
-        iter.seek_to_first();
-        Ok(iter.next().transpose()?.map_or(0, |(index, _)| {
-            index
-                .stale_since_version
-                .checked_sub(1)
-                .expect("Nothing is stale since version 0.")
-        }))
+        let mut least_readable_version = None;
+        for db in &self.state_dbs {
+            let mut iter = db.iter::<StaleNodeIndexSchema>(ReadOptions::default())?;
+            iter.seek_to_first();
+            if let Some((index, _)) = iter.next().transpose()? {
+                let version = index
+                    .stale_since_version
+                    .checked_sub(1)
+                    .expect("Nothing is stale since version 0.");
+                least_readable_version = Some(
+                    least_readable_version.map_or(version, |other: Version| other.min(version)),
+                );
+            }
+        }
+        Ok(least_readable_version.unwrap_or(0))
     }
 
     fn least_readable_version(&self) -> Version {
@@ -86,6 +94,9 @@ impl DBPruner for StateStorePruner {
 
     fn set_target_version(&self, target_version: Version) {
         self.target_version.store(target_version, Ordering::Relaxed);
+        APTOS_PRUNER_TARGET_VERSION
+            .with_label_values(&["state_store"])
+            .set(target_version as i64);
     }
 
     fn target_version(&self) -> Version {
@@ -103,12 +114,12 @@ impl DBPruner for StateStorePruner {
 
 impl StateStorePruner {
     pub fn new(
-        db: Arc<DB>,
+        state_dbs: Vec<Arc<DB>>,
         index_min_nonpurged_version: Version,
         index_purged_at: Instant,
     ) -> Self {
         StateStorePruner {
-            db,
+            state_dbs,
             index_min_nonpurged_version: AtomicVersion::new(index_min_nonpurged_version),
             index_purged_at: Mutex::new(index_purged_at),
             target_version: AtomicVersion::new(0),
@@ -137,10 +148,12 @@ impl StateStorePruner {
         {
             let new_min_non_purged_version =
                 self.least_readable_version.load(Ordering::Relaxed) + 1;
-            self.db.range_delete::<StaleNodeIndexSchema, Version>(
-                &self.index_min_nonpurged_version(),
-                &new_min_non_purged_version, // end is exclusive
-            )?;
+            for db in &self.state_dbs {
+                db.range_delete::<StaleNodeIndexSchema, Version>(
+                    &self.index_min_nonpurged_version(),
+                    &new_min_non_purged_version, // end is exclusive
+                )?;
+            }
             self.index_min_nonpurged_version
                 .store(new_min_non_purged_version, Ordering::Relaxed);
             *self.index_purged_at.lock() = now;
@@ -157,16 +170,19 @@ impl StateStorePruner {
     }
 }
 
+/// Deletes the nodes that became stale in the first `max_versions` versions after
+/// `least_readable_version`, up to `target_version`, from all the `state_dbs`, each of which holds
+/// the stale node index of its own nodes.
 pub fn prune_state_store(
-    db: Arc<DB>,
+    state_dbs: &[Arc<DB>],
     least_readable_version: Version,
     target_version: Version,
     max_versions: usize,
 ) -> anyhow::Result<Version> {
     let indices =
-        StaleNodeIndicesByVersionIterator::new(&db, least_readable_version, target_version)?
-            .take(max_versions) // Iterator<Item = Result<Vec<StaleNodeIndex>>>
-            .collect::<anyhow::Result<Vec<_>>>()? // now Vec<Vec<StaleNodeIndex>>
+        StaleNodeIndicesByVersionIterator::new(state_dbs, least_readable_version, target_version)?
+            .take(max_versions) // Iterator<Item = Result<Vec<(usize, StaleNodeIndex)>>>
+            .collect::<anyhow::Result<Vec<_>>>()? // now Vec<Vec<(usize, StaleNodeIndex)>>
             .into_iter()
             .flatten()
             .collect::<Vec<_>>();
@@ -177,65 +193,90 @@ pub fn prune_state_store(
         let _timer = APTOS_STORAGE_OTHER_TIMERS_SECONDS
             .with_label_values(&["pruner_commit"])
             .start_timer();
-        let new_least_readable_version = indices.last().expect("Should exist.").stale_since_version;
-        let mut batch = SchemaBatch::new();
-        indices
-            .into_iter()
-            .try_for_each(|index| batch.delete::<JellyfishMerkleNodeSchema>(&index.node_key))?;
-        db.write_schemas(batch)?;
+        let new_least_readable_version =
+            indices.last().expect("Should exist.").1.stale_since_version;
+        let mut batches: Vec<_> = state_dbs.iter().map(|_| SchemaBatch::new()).collect();
+        indices.into_iter().try_for_each(|(db_idx, index)| {
+            batches[db_idx].delete::<JellyfishMerkleNodeSchema>(&index.node_key)
+        })?;
+        for (db, batch) in state_dbs.iter().zip(batches) {
+            db.write_schemas(batch)?;
+        }
         Ok(new_least_readable_version)
     }
 }
 
+/// Yields the stale node indices of all the DBs, version by version, each tagged with the index of
+/// the DB it's from.
 struct StaleNodeIndicesByVersionIterator<'a> {
-    inner: Peekable<SchemaIterator<'a, StaleNodeIndexSchema>>,
+    inners: Vec<Peekable<SchemaIterator<'a, StaleNodeIndexSchema>>>,
     target_least_readable_version: Version,
 }
 
 impl<'a> StaleNodeIndicesByVersionIterator<'a> {
     fn new(
-        db: &'a DB,
+        dbs: &'a [Arc<DB>],
         least_readable_version: Version,
         target_least_readable_version: Version,
     ) -> anyhow::Result<Self> {
-        let mut iter = db.iter::<StaleNodeIndexSchema>(ReadOptions::default())?;
-        iter.seek(&least_readable_version)?;
+        let inners = dbs
+            .iter()
+            .map(|db| {
+                let mut iter = db.iter::<StaleNodeIndexSchema>(ReadOptions::default())?;
+                iter.seek(&least_readable_version)?;
+                Ok(iter.peekable())
+            })
+            .collect::<anyhow::Result<_>>()?;
 
         Ok(Self {
-            inner: iter.peekable(),
+            inners,
             target_least_readable_version,
         })
     }
 
-    fn next_result(&mut self) -> anyhow::Result<Option<Vec<StaleNodeIndex>>> {
-        match self.inner.next().transpose()? {
-            None => Ok(None),
-            Some((index, _)) => {
-                let version = index.stale_since_version;
-                if version > self.target_least_readable_version {
-                    return Ok(None);
+    fn next_result(&mut self) -> anyhow::Result<Option<Vec<(usize, StaleNodeIndex)>>> {
+        // The next version is the smallest one any of the DBs is at.
+        let mut next_version = None;
+        for inner in &mut self.inners {
+            match inner.peek() {
+                Some(Ok((index, _))) => {
+                    next_version = Some(
+                        next_version.map_or(index.stale_since_version, |version: Version| {
+                            version.min(index.stale_since_version)
+                        }),
+                    )
                 }
+                Some(Err(_)) => {
+                    inner.next().transpose()?;
+                }
+                None => (),
+            }
+        }
+        let version = match next_version {
+            Some(version) if version <= self.target_least_readable_version => version,
+            _ => return Ok(None),
+        };
 
-                let mut indices = vec![index];
-                while let Some(res) = self.inner.peek() {
-                    if let Ok((index_ref, _)) = res {
-                        if index_ref.stale_since_version != version {
-                            break;
-                        }
+        let mut indices = Vec::new();
+        for (db_idx, inner) in self.inners.iter_mut().enumerate() {
+            while let Some(res) = inner.peek() {
+                if let Ok((index_ref, _)) = res {
+                    if index_ref.stale_since_version != version {
+                        break;
                     }
-
-                    let (index, _) = self.inner.next().transpose()?.expect("Should be Some.");
-                    indices.push(index);
                 }
 
-                Ok(Some(indices))
+                let (index, _) = inner.next().transpose()?.expect("Should be Some.");
+                indices.push((db_idx, index));
             }
         }
+
+        Ok(Some(indices))
     }
 }
 
 impl<'a> Iterator for StaleNodeIndicesByVersionIterator<'a> {
-    type Item = anyhow::Result<Vec<StaleNodeIndex>>;
+    type Item = anyhow::Result<Vec<(usize, StaleNodeIndex)>>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next_result().transpose()
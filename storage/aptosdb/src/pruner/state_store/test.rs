@@ -61,15 +61,22 @@ fn test_state_store_pruner() {
     let tmp_dir = TempPath::new();
     let aptos_db = AptosDB::new_for_test(&tmp_dir);
     let db = aptos_db.db;
-    let state_store = &StateStore::new(Arc::clone(&db));
+    let state_store = &Arc::new(StateStore::new(
+        Arc::clone(&db),
+        0, /* state_cache_size */
+    ));
     let transaction_store = &aptos_db.transaction_store;
     let pruner = Pruner::new(
         Arc::clone(&db),
         StoragePrunerConfig {
             state_store_prune_window: Some(0),
             default_prune_window: Some(0),
+            transaction_store_prune_window: None,
+            event_store_prune_window: None,
+            write_set_prune_window: None,
             max_version_to_prune_per_batch: Some(100),
         },
+        Arc::clone(state_store),
         Arc::clone(transaction_store),
         Arc::clone(&aptos_db.ledger_store),
         Arc::clone(&aptos_db.event_store),
@@ -121,6 +128,7 @@ fn test_state_store_pruner() {
         // root1 is still there.
         verify_state_in_store(state_store, address, Some(&value1), 1);
         verify_state_in_store(state_store, address, Some(&value2), 2);
+        assert_eq!(pruner.get_least_readable_state_store_version(), 1);
     }
     // Prune till version=2.
     {
@@ -149,7 +157,7 @@ fn test_worker_quit_eagerly() {
     let tmp_dir = TempPath::new();
     let aptos_db = AptosDB::new_for_test(&tmp_dir);
     let db = aptos_db.db;
-    let state_store = &StateStore::new(Arc::clone(&db));
+    let state_store = &StateStore::new(Arc::clone(&db), 0 /* state_cache_size */);
 
     let _root0 = put_account_state_set(
         &db,
@@ -174,6 +182,7 @@ fn test_worker_quit_eagerly() {
         let (command_sender, command_receiver) = channel();
         let worker = Worker::new(
             Arc::clone(&db),
+            Arc::clone(&aptos_db.state_store),
             Arc::clone(&aptos_db.transaction_store),
             Arc::clone(&aptos_db.ledger_store),
             Arc::clone(&aptos_db.event_store),
@@ -38,6 +38,18 @@ proptest! {
         ) {
             verify_write_set_pruner(write_set);
         }
+
+    #[test]
+    fn test_separate_prune_windows(
+        txns in vec(
+            any::<SignedTransaction>().prop_map(Transaction::UserTransaction),
+            10,
+        ),
+        txn_infos in vec(any::<TransactionInfo>(), 10),
+        write_sets in vec(any::<WriteSet>(), 10),
+    ) {
+        verify_separate_prune_windows(txns, txn_infos, write_sets)
+    }
 }
 
 fn verify_write_set_pruner(write_sets: Vec<WriteSet>) {
@@ -51,8 +63,12 @@ fn verify_write_set_pruner(write_sets: Vec<WriteSet>) {
         StoragePrunerConfig {
             state_store_prune_window: Some(0),
             default_prune_window: Some(0),
+            transaction_store_prune_window: None,
+            event_store_prune_window: None,
+            write_set_prune_window: None,
             max_version_to_prune_per_batch: Some(100),
         },
+        Arc::clone(&aptos_db.state_store),
         Arc::clone(transaction_store),
         Arc::clone(&aptos_db.ledger_store),
         Arc::clone(&aptos_db.event_store),
@@ -102,8 +118,12 @@ fn verify_txn_store_pruner(
         StoragePrunerConfig {
             state_store_prune_window: Some(0),
             default_prune_window: Some(0),
+            transaction_store_prune_window: None,
+            event_store_prune_window: None,
+            write_set_prune_window: None,
             max_version_to_prune_per_batch: Some(100),
         },
+        Arc::clone(&aptos_db.state_store),
         Arc::clone(transaction_store),
         Arc::clone(&aptos_db.ledger_store),
         Arc::clone(&aptos_db.event_store),
@@ -143,6 +163,71 @@ fn verify_txn_store_pruner(
     }
 }
 
+// Prunes the write sets while keeping the transactions, which have a longer prune window.
+fn verify_separate_prune_windows(
+    txns: Vec<Transaction>,
+    txn_infos: Vec<TransactionInfo>,
+    write_sets: Vec<WriteSet>,
+) {
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test(&tmp_dir);
+    let transaction_store = &aptos_db.transaction_store;
+    let ledger_store = LedgerStore::new(Arc::clone(&aptos_db.db));
+    let num_transaction = txns.len();
+
+    let pruner = Pruner::new(
+        Arc::clone(&aptos_db.db),
+        StoragePrunerConfig {
+            state_store_prune_window: Some(0),
+            default_prune_window: Some(0),
+            transaction_store_prune_window: Some(1_000),
+            event_store_prune_window: None,
+            write_set_prune_window: None,
+            max_version_to_prune_per_batch: Some(100),
+        },
+        Arc::clone(&aptos_db.state_store),
+        Arc::clone(transaction_store),
+        Arc::clone(&aptos_db.ledger_store),
+        Arc::clone(&aptos_db.event_store),
+    );
+
+    let ledger_version = num_transaction as Version - 1;
+    put_txn_in_store(
+        &aptos_db,
+        transaction_store,
+        &ledger_store,
+        &txn_infos,
+        &txns,
+    );
+    let mut cs = ChangeSet::new();
+    for (ver, ws) in write_sets.iter().enumerate() {
+        transaction_store
+            .put_write_set(ver as Version, ws, &mut cs)
+            .unwrap();
+    }
+    aptos_db.db.write_schemas(cs.batch).unwrap();
+
+    pruner
+        .wake_and_wait(
+            num_transaction as u64, /* latest_version */
+            PrunerIndex::WriteSetPrunerIndex as usize,
+        )
+        .unwrap();
+    for i in 0..num_transaction {
+        assert!(transaction_store.get_write_set(i as u64).is_err());
+        verify_txn_in_store(
+            transaction_store,
+            &ledger_store,
+            &txns,
+            i as u64,
+            ledger_version,
+        );
+    }
+    assert_eq!(pruner.get_least_readable_transaction_store_version(), 0);
+    // The transactions without their write sets aren't reported readable.
+    assert!(pruner.get_least_readable_transaction_version() >= num_transaction as Version);
+}
+
 fn verify_txn_not_in_store(
     transaction_store: &TransactionStore,
     txns: &[Transaction],
@@ -1,18 +1,19 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    metrics::APTOS_PRUNER_LEAST_READABLE_VERSION, pruner::db_pruner::DBPruner,
-    transaction::TransactionSchema, TransactionStore,
+    metrics::{APTOS_PRUNER_LEAST_READABLE_VERSION, APTOS_PRUNER_TARGET_VERSION},
+    pruner::db_pruner::DBPruner,
+    EventStore, TransactionStore,
 };
 use aptos_types::transaction::{AtomicVersion, Transaction, Version};
-use schemadb::{ReadOptions, SchemaBatch, DB};
+use schemadb::SchemaBatch;
 use std::sync::{atomic::Ordering, Arc};
 
 pub const TRANSACTION_STORE_PRUNER_NAME: &str = "transaction store pruner";
 
 pub struct TransactionStorePruner {
-    db: Arc<DB>,
     transaction_store: Arc<TransactionStore>,
+    event_store: Arc<EventStore>,
     /// Keeps track of the target version that the pruner needs to achieve.
     target_version: AtomicVersion,
     least_readable_version: AtomicVersion,
@@ -34,6 +35,20 @@ impl DBPruner for TransactionStorePruner {
             .prune_transaction_by_hash(&candidate_transactions, db_batch)?;
         self.transaction_store
             .prune_transaction_by_account(&candidate_transactions, db_batch)?;
+        // The event store pruner runs after this one, so the events of the candidates are still
+        // there unless the event store prune window is shorter.
+        let candidate_participations = (least_readable_version..)
+            .zip(candidate_transactions)
+            .map(|(version, txn)| {
+                Ok((
+                    version,
+                    txn,
+                    self.event_store.get_events_by_version(version)?,
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.transaction_store
+            .prune_transaction_by_participant(&candidate_participations, db_batch)?;
         self.transaction_store.prune_transaction_schema(
             self.least_readable_version(),
             current_target_version,
@@ -50,9 +65,8 @@ impl DBPruner for TransactionStorePruner {
     }
 
     fn initialize_least_readable_version(&self) -> anyhow::Result<Version> {
-        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
-        iter.seek_to_first();
-        let version = iter.next().transpose()?.map_or(0, |(version, _)| version);
+        // Including the transactions moved to the cold ledger.
+        let version = self.transaction_store.get_first_txn_version()?.unwrap_or(0);
         Ok(version)
     }
 
@@ -61,7 +75,10 @@ impl DBPruner for TransactionStorePruner {
     }
 
     fn set_target_version(&self, target_version: Version) {
-        self.target_version.store(target_version, Ordering::Relaxed)
+        self.target_version.store(target_version, Ordering::Relaxed);
+        APTOS_PRUNER_TARGET_VERSION
+            .with_label_values(&["transaction_store"])
+            .set(target_version as i64);
     }
 
     fn target_version(&self) -> Version {
@@ -78,10 +95,13 @@ impl DBPruner for TransactionStorePruner {
 }
 
 impl TransactionStorePruner {
-    pub(in crate::pruner) fn new(db: Arc<DB>, transaction_store: Arc<TransactionStore>) -> Self {
+    pub(in crate::pruner) fn new(
+        transaction_store: Arc<TransactionStore>,
+        event_store: Arc<EventStore>,
+    ) -> Self {
         TransactionStorePruner {
-            db,
             transaction_store,
+            event_store,
             target_version: AtomicVersion::new(0),
             least_readable_version: AtomicVersion::new(0),
         }
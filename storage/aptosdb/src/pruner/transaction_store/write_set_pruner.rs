@@ -1,8 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    metrics::APTOS_PRUNER_LEAST_READABLE_VERSION, pruner::db_pruner::DBPruner,
-    write_set::WriteSetSchema, TransactionStore,
+    metrics::{APTOS_PRUNER_LEAST_READABLE_VERSION, APTOS_PRUNER_TARGET_VERSION},
+    pruner::db_pruner::DBPruner,
+    write_set::WriteSetSchema,
+    TransactionStore,
 };
 use aptos_types::transaction::{AtomicVersion, Version};
 use schemadb::{ReadOptions, SchemaBatch, DB};
@@ -49,7 +51,10 @@ impl DBPruner for WriteSetPruner {
     }
 
     fn set_target_version(&self, target_version: Version) {
-        self.target_version.store(target_version, Ordering::Relaxed)
+        self.target_version.store(target_version, Ordering::Relaxed);
+        APTOS_PRUNER_TARGET_VERSION
+            .with_label_values(&["write_set"])
+            .set(target_version as i64);
     }
 
     fn target_version(&self) -> Version {
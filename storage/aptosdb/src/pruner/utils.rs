@@ -13,7 +13,7 @@ use crate::{
             transaction_store_pruner::TransactionStorePruner, write_set_pruner::WriteSetPruner,
         },
     },
-    EventStore, LedgerStore, TransactionStore,
+    EventStore, LedgerStore, StateStore, TransactionStore,
 };
 use aptos_infallible::Mutex;
 use schemadb::DB;
@@ -22,28 +22,26 @@ use std::{sync::Arc, time::Instant};
 /// A useful utility function to instantiate all db pruners.
 pub fn create_db_pruners(
     db: Arc<DB>,
+    state_store: Arc<StateStore>,
     transaction_store: Arc<TransactionStore>,
     ledger_store: Arc<LedgerStore>,
     event_store: Arc<EventStore>,
 ) -> Vec<Mutex<Arc<dyn DBPruner + Send + Sync>>> {
     vec![
         Mutex::new(Arc::new(StateStorePruner::new(
-            Arc::clone(&db),
+            state_store.state_dbs(),
             0,
             Instant::now(),
         ))),
         Mutex::new(Arc::new(TransactionStorePruner::new(
-            Arc::clone(&db),
             Arc::clone(&transaction_store),
+            Arc::clone(&event_store),
         ))),
         Mutex::new(Arc::new(LedgerStorePruner::new(
             Arc::clone(&db),
             Arc::clone(&ledger_store),
         ))),
-        Mutex::new(Arc::new(EventStorePruner::new(
-            Arc::clone(&db),
-            Arc::clone(&event_store),
-        ))),
+        Mutex::new(Arc::new(EventStorePruner::new(Arc::clone(&event_store)))),
         Mutex::new(Arc::new(WriteSetPruner::new(
             Arc::clone(&db),
             Arc::clone(&transaction_store),
@@ -5,7 +5,7 @@ use schemadb::{SchemaBatch, DB};
 
 use crate::{
     pruner::{db_pruner::DBPruner, utils},
-    EventStore, LedgerStore, TransactionStore,
+    EventStore, LedgerStore, StateStore, TransactionStore,
 };
 use aptos_infallible::Mutex;
 use itertools::zip_eq;
@@ -31,6 +31,7 @@ pub struct Worker {
 impl Worker {
     pub(crate) fn new(
         db: Arc<DB>,
+        state_store: Arc<StateStore>,
         transaction_store: Arc<TransactionStore>,
         ledger_store: Arc<LedgerStore>,
         event_store: Arc<EventStore>,
@@ -38,8 +39,13 @@ impl Worker {
         least_readable_versions: Arc<Mutex<Vec<Version>>>,
         max_version_to_prune_per_batch: u64,
     ) -> Self {
-        let db_pruners =
-            utils::create_db_pruners(db.clone(), transaction_store, ledger_store, event_store);
+        let db_pruners = utils::create_db_pruners(
+            db.clone(),
+            state_store,
+            transaction_store,
+            ledger_store,
+            event_store,
+        );
         Self {
             db: Arc::clone(&db),
             db_pruners,
@@ -0,0 +1,66 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an account index via which the versions at
+//! which the authentication key of `account_address` was set can be found, the first one being
+//! the version at which the account was created.
+//!
+//! ```text
+//! |<-------key------->|<------value------->|
+//! | address | txn_ver | authentication key |
+//! ```
+//!
+//! `Version` is serialized in big endian so that records of an account in RocksDB will be in
+//! order of its numeric value.
+
+use crate::schema::{ensure_slice_len_eq, AUTHENTICATION_KEY_HISTORY_CF_NAME};
+use anyhow::Result;
+use aptos_types::{account_address::AccountAddress, transaction::Version};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::{convert::TryFrom, mem::size_of};
+
+define_schema!(
+    AuthenticationKeyHistorySchema,
+    Key,
+    Vec<u8>,
+    AUTHENTICATION_KEY_HISTORY_CF_NAME
+);
+
+type Key = (AccountAddress, Version);
+
+impl KeyCodec<AuthenticationKeyHistorySchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref account_address, version) = *self;
+
+        let mut encoded = account_address.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let version = (&data[AccountAddress::LENGTH..]).read_u64::<BigEndian>()?;
+
+        Ok((address, version))
+    }
+}
+
+impl ValueCodec<AuthenticationKeyHistorySchema> for Vec<u8> {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.clone())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(data.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod test;
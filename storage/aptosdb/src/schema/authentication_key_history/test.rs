@@ -0,0 +1,22 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        address in any::<AccountAddress>(),
+        version in any::<Version>(),
+        authentication_key in any::<Vec<u8>>(),
+    ) {
+        assert_encode_decode::<AuthenticationKeyHistorySchema>(
+            &(address, version),
+            &authentication_key,
+        );
+    }
+}
+
+test_no_panic_decoding!(AuthenticationKeyHistorySchema);
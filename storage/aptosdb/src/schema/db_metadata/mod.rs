@@ -0,0 +1,65 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the metadata describing the layout of the DB
+//! itself, e.g. the number of shards the state Merkle tree is spread across.
+//!
+//! ```text
+//! |<-------key------->|<-value->|
+//! | metadata key enum |   u64   |
+//! ```
+
+use super::DB_METADATA_CF_NAME;
+use crate::schema::ensure_slice_len_eq;
+use anyhow::Result;
+use byteorder::{BigEndian, ReadBytesExt};
+#[cfg(test)]
+use proptest_derive::Arbitrary;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(test, derive(Arbitrary))]
+pub enum DbMetadataKey {
+    /// Number of shards the state Merkle tree is spread across, recorded in the main DB and in
+    /// each of the shards. Absent in the main DB if the tree is not sharded.
+    NumStateShards,
+    /// Index of a shard, recorded in each of the shards.
+    StateShardId,
+    /// The first version whose transactions and events are in the main DB, those of the versions
+    /// before it having been moved to the cold ledger DB. Absent if nothing was ever moved.
+    FirstHotLedgerVersion,
+    /// The version of the state snapshot being restored. Absent if no restore was started, or
+    /// once the restored snapshot is finalized.
+    StateSnapshotRestoreVersion,
+}
+
+define_schema!(DbMetadataSchema, DbMetadataKey, u64, DB_METADATA_CF_NAME);
+
+impl KeyCodec<DbMetadataSchema> for DbMetadataKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+impl ValueCodec<DbMetadataSchema> for u64 {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<u64>())?;
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod test;
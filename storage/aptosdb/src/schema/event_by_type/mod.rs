@@ -0,0 +1,70 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an optional event index via which the events
+//! of a Move type, emitted to any event stream, can be found in the order of the versions of the
+//! transactions emitting them. The event itself is fetched from `EventSchema` by the
+//! <txn_version, event_idx> tuple of the key.
+//!
+//! ```text
+//! |<------------key------------>|<-value->|
+//! | type_tag | txn_version | idx |  empty  |
+//! ```
+//!
+//! The type tag is BCS serialized, which is prefix free, so the records of a type are contiguous
+//! in RocksDB. `Version` and index are serialized in big endian so that the records of a type are
+//! in order of their numeric values.
+
+use crate::schema::{ensure_slice_len_eq, ensure_slice_len_gt, EVENT_BY_TYPE_CF_NAME};
+use anyhow::Result;
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use move_core_types::language_storage::TypeTag;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (TypeTag, Version, Index);
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref type_tag, version, index) = *self;
+
+        let mut encoded = bcs::to_bytes(type_tag)?;
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        const SUFFIX_SIZE: usize = size_of::<Version>() + size_of::<Index>();
+
+        ensure_slice_len_gt(data, SUFFIX_SIZE)?;
+        let type_tag_len = data.len() - SUFFIX_SIZE;
+        let type_tag = bcs::from_bytes(&data[..type_tag_len])?;
+        let version = (&data[type_tag_len..]).read_u64::<BigEndian>()?;
+        let index = (&data[type_tag_len + size_of::<Version>()..]).read_u64::<BigEndian>()?;
+
+        Ok((type_tag, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
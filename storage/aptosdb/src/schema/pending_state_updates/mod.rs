@@ -0,0 +1,61 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the state updates of the versions committed
+//! to the ledger whose state is yet to be committed to the state Merkle tree, when the commits are
+//! pipelined. They are replayed to the tree when the DB is opened after a crash, and deleted once
+//! the state of their version is committed.
+//!
+//! ```text
+//! |<--key-->|<-----value---->|
+//! | version | state updates  |
+//! ```
+//!
+//! `Version` is serialized in big endian so that records in RocksDB will be in order of it's
+//! numeric value.
+
+use crate::schema::{ensure_slice_len_eq, PENDING_STATE_UPDATES_CF_NAME};
+use anyhow::Result;
+use aptos_types::{
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use byteorder::{BigEndian, ReadBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::{collections::HashMap, mem::size_of};
+
+pub(crate) type StateUpdates = HashMap<StateKey, StateValue>;
+
+define_schema!(
+    PendingStateUpdatesSchema,
+    Version,
+    StateUpdates,
+    PENDING_STATE_UPDATES_CF_NAME
+);
+
+impl KeyCodec<PendingStateUpdatesSchema> for Version {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_key(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Version>())?;
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+impl ValueCodec<PendingStateUpdatesSchema> for StateUpdates {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        bcs::to_bytes(self).map_err(Into::into)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        bcs::from_bytes(data).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test;
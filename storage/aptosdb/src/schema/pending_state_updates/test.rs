@@ -0,0 +1,18 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::{collection::hash_map, prelude::*};
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        version in any::<Version>(),
+        state_updates in hash_map(any::<StateKey>(), any::<StateValue>(), 0..10),
+    ) {
+        assert_encode_decode::<PendingStateUpdatesSchema>(&version, &state_updates);
+    }
+}
+
+test_no_panic_decoding!(PendingStateUpdatesSchema);
@@ -0,0 +1,68 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for a transaction index via which the versions of
+//! the transactions `account_address` participated in can be found: the ones it sent or
+//! co-signed, and the ones emitting events to its event streams, e.g. the deposits of a transfer
+//! to it.
+//!
+//! ```text
+//! |<-------key------->|<-value->|
+//! | address | txn_ver |  empty  |
+//! ```
+//!
+//! `Version` is serialized in big endian so that records of an account in RocksDB will be in
+//! order of its numeric value.
+
+use crate::schema::{ensure_slice_len_eq, TRANSACTION_BY_PARTICIPANT_CF_NAME};
+use anyhow::Result;
+use aptos_types::{account_address::AccountAddress, transaction::Version};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::{convert::TryFrom, mem::size_of};
+
+define_schema!(
+    TransactionByParticipantSchema,
+    Key,
+    (),
+    TRANSACTION_BY_PARTICIPANT_CF_NAME
+);
+
+type Key = (AccountAddress, Version);
+
+impl KeyCodec<TransactionByParticipantSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref account_address, version) = *self;
+
+        let mut encoded = account_address.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let version = (&data[AccountAddress::LENGTH..]).read_u64::<BigEndian>()?;
+
+        Ok((address, version))
+    }
+}
+
+impl ValueCodec<TransactionByParticipantSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,239 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides `StateCommitter` which pipelines the commits: `save_transactions` commits
+//! the ledger of the versions, with their state updates recorded as pending, and hands the update
+//! of the state Merkle tree over to the committer thread, so the next commit doesn't wait for it.
+//!
+//! The ledger of the versions and their pending state updates are written to the main DB in one
+//! batch. The committer writes the new tree nodes to the state shards, if any, then, in one batch
+//! of the main DB, the tree nodes if not sharded, the ledger counters of the versions, and the
+//! deletion of their pending state updates. So after a crash, the pending state updates left in
+//! the DB are exactly the ones to be committed again, which `replay_pending_state_updates` does
+//! when the DB is opened.
+//!
+//! If committing the state fails, the committer stops, and the reads waiting for the state as
+//! well as the next `save_transactions` fail with the error.
+
+use crate::{
+    change_set::ChangeSet,
+    event_store::EventStore,
+    ledger_counters::{LedgerCounter, LedgerCounterBumps},
+    metrics::{APTOS_STORAGE_LATEST_ACCOUNT_COUNT, APTOS_STORAGE_OTHER_TIMERS_SECONDS},
+    schema::pending_state_updates::{PendingStateUpdatesSchema, StateUpdates},
+    system_store::SystemStore,
+    StateStore,
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::{nibble::nibble_path::NibblePath, transaction::Version};
+use schemadb::{ReadOptions, DB};
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{sync_channel, SyncSender},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+
+/// Number of commits waiting for the committer before `save_transactions` blocks, so the ledger
+/// doesn't get too far ahead of the state.
+const MAX_QUEUED_COMMITS: usize = 8;
+/// Number of versions of pending state updates replayed at once.
+const REPLAY_BATCH_SIZE: usize = 1000;
+
+/// The state updates of a range of versions committed to the ledger.
+pub(crate) struct StateCommit {
+    pub first_version: Version,
+    pub value_sets: Vec<StateUpdates>,
+    pub node_hashes: Option<Vec<HashMap<NibblePath, HashValue>>>,
+    /// The bumps of the ledger counters of the versions made by the ledger, the ledger counters
+    /// being committed with the state.
+    pub counter_bumps: HashMap<Version, LedgerCounterBumps>,
+}
+
+impl StateCommit {
+    fn last_version(&self) -> Version {
+        self.first_version + self.value_sets.len() as Version - 1
+    }
+}
+
+/// Commits the state of the versions in order in a background thread. When dropped, commits the
+/// queued versions before joining the thread.
+#[derive(Debug)]
+pub(crate) struct StateCommitter {
+    state_store: Arc<StateStore>,
+    /// `None` once dropped, which lets the thread quit.
+    sender: Mutex<Option<SyncSender<StateCommit>>>,
+    worker_thread: Option<JoinHandle<()>>,
+}
+
+impl StateCommitter {
+    pub fn new(
+        db: Arc<DB>,
+        state_store: Arc<StateStore>,
+        system_store: Arc<SystemStore>,
+    ) -> Result<Self> {
+        let (sender, receiver) = sync_channel::<StateCommit>(MAX_QUEUED_COMMITS);
+        let worker_state_store = Arc::clone(&state_store);
+        let worker_thread = std::thread::Builder::new()
+            .name("aptosdb_state_committer".into())
+            .spawn(move || {
+                for commit in receiver {
+                    let first_version = commit.first_version;
+                    if let Err(err) = commit_state(&db, &worker_state_store, &system_store, commit)
+                    {
+                        error!(
+                            first_version = first_version,
+                            error = ?err,
+                            "Failed to commit the state, stopping the state committer.",
+                        );
+                        worker_state_store.commit_progress().fail(&err);
+                        return;
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            state_store,
+            sender: Mutex::new(Some(sender)),
+            worker_thread: Some(worker_thread),
+        })
+    }
+
+    /// Fails if committing the state of the queued versions failed.
+    pub fn check(&self) -> Result<()> {
+        self.state_store.commit_progress().check()
+    }
+
+    /// Queues the state of the versions, whose ledger is committed, to be committed. Blocks if
+    /// too many commits are queued. Fails if the committer failed.
+    pub fn queue(&self, commit: StateCommit) -> Result<()> {
+        self.check()?;
+        self.state_store
+            .commit_progress()
+            .queue(commit.first_version, commit.last_version());
+        self.sender
+            .lock()
+            .as_ref()
+            .expect("Sender only dropped on drop.")
+            .send(commit)
+            .map_err(|_| {
+                // the committer quits on failure, with its error recorded
+                self.check()
+                    .err()
+                    .unwrap_or_else(|| anyhow!("The state committer quit."))
+            })
+    }
+}
+
+impl Drop for StateCommitter {
+    fn drop(&mut self) {
+        self.sender.lock().take();
+        self.worker_thread
+            .take()
+            .expect("State committer thread must exist.")
+            .join()
+            .expect("State committer thread should join peacefully.");
+    }
+}
+
+/// Commits the state Merkle tree update of the versions, along with their ledger counters, and
+/// deletes their pending state updates.
+pub(crate) fn commit_state(
+    db: &DB,
+    state_store: &StateStore,
+    system_store: &SystemStore,
+    commit: StateCommit,
+) -> Result<()> {
+    let _timer = APTOS_STORAGE_OTHER_TIMERS_SECONDS
+        .with_label_values(&["commit_state"])
+        .start_timer();
+    let first_version = commit.first_version;
+    let last_version = commit.last_version();
+
+    let mut cs = ChangeSet::new_with_bumps(commit.counter_bumps);
+    state_store.put_value_sets(
+        commit.value_sets.iter().collect(),
+        commit
+            .node_hashes
+            .as_ref()
+            .map(|node_hashes| node_hashes.iter().collect()),
+        first_version,
+        &mut cs,
+    )?;
+    cs.batch
+        .delete_range::<PendingStateUpdatesSchema>(&first_version, &(last_version + 1))?;
+    let counters = system_store.bump_ledger_counters(first_version, last_version, &mut cs)?;
+    state_store.commit_state_shard_batches(cs.state_shard_batches)?;
+    db.write_schemas(cs.batch)?;
+    state_store.commit_progress().finish(last_version);
+
+    counters.bump_op_counters();
+    // -1 for "not fully migrated", -2 for "error on get_account_count()"
+    APTOS_STORAGE_LATEST_ACCOUNT_COUNT.set(
+        state_store
+            .get_value_count(last_version)
+            .map_or(-1, |c| c as i64),
+    );
+    Ok(())
+}
+
+/// Commits the state of the versions left pending by a crash, returning the number of versions
+/// replayed. The ledger counter bumps made by the ledger are recovered from the events.
+pub(crate) fn replay_pending_state_updates(
+    db: &DB,
+    state_store: &StateStore,
+    system_store: &SystemStore,
+    event_store: &EventStore,
+) -> Result<usize> {
+    let mut num_replayed = 0;
+    let mut iter = db.iter::<PendingStateUpdatesSchema>(ReadOptions::default())?;
+    iter.seek_to_first();
+    loop {
+        let pending = iter
+            .by_ref()
+            .take(REPLAY_BATCH_SIZE)
+            .collect::<Result<Vec<_>>>()?;
+        let first_version = match pending.first() {
+            Some((version, _)) => *version,
+            None => return Ok(num_replayed),
+        };
+        info!(
+            first_version = first_version,
+            num_versions = pending.len(),
+            "Replaying pending state updates.",
+        );
+        let mut counter_bumps = HashMap::new();
+        let mut value_sets = Vec::with_capacity(pending.len());
+        for (expected_version, (version, state_updates)) in (first_version..).zip(pending) {
+            ensure!(
+                version == expected_version,
+                "Pending state updates of version {} missing.",
+                expected_version,
+            );
+            let mut bumps = LedgerCounterBumps::new();
+            bumps.bump(
+                LedgerCounter::EventsCreated,
+                event_store.get_events_by_version(version)?.len(),
+            );
+            counter_bumps.insert(version, bumps);
+            value_sets.push(state_updates);
+        }
+        num_replayed += value_sets.len();
+        commit_state(
+            db,
+            state_store,
+            system_store,
+            StateCommit {
+                first_version,
+                value_sets,
+                node_hashes: None,
+                counter_bumps,
+            },
+        )?;
+    }
+}
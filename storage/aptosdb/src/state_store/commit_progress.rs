@@ -0,0 +1,171 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! When the commits are pipelined, the state of the versions committed to the ledger is committed
+//! to the state Merkle tree a bit later. The reads of the state of those versions wait for it,
+//! instead of failing on the missing tree. If the committer fails, the waiting reads and the
+//! next commits fail with its error.
+
+use anyhow::{bail, Result};
+use aptos_infallible::Mutex;
+use aptos_types::transaction::Version;
+use std::sync::Condvar;
+
+#[derive(Debug, Default)]
+pub(crate) struct StateCommitProgress {
+    inner: Mutex<Inner>,
+    committed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    // The last version whose state is queued to be committed, and the last one committed.
+    queued_version: Option<Version>,
+    committed_version: Option<Version>,
+    // The error of the committer, which stops committing once it fails.
+    error: Option<String>,
+}
+
+impl Inner {
+    fn is_pending(&self, version: Version) -> bool {
+        self.queued_version
+            .map_or(false, |queued| version <= queued)
+            && self
+                .committed_version
+                .map_or(true, |committed| version > committed)
+    }
+}
+
+impl StateCommitProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Versions are queued in order, after all the versions before `first_version` are committed.
+    pub fn queue(&self, first_version: Version, last_version: Version) {
+        let mut inner = self.inner.lock();
+        if inner.committed_version.is_none() {
+            inner.committed_version = first_version.checked_sub(1);
+        }
+        inner.queued_version = Some(last_version);
+    }
+
+    pub fn finish(&self, last_version: Version) {
+        self.inner.lock().committed_version = Some(last_version);
+        self.committed.notify_all();
+    }
+
+    /// Records that the committer failed, waking the waiters up with the error.
+    pub fn fail(&self, error: &anyhow::Error) {
+        self.inner.lock().error = Some(format!("{:?}", error));
+        self.committed.notify_all();
+    }
+
+    /// Fails with the error of the committer, if it failed.
+    pub fn check(&self) -> Result<()> {
+        match &self.inner.lock().error {
+            Some(error) => bail!("Committing the state failed: {}", error),
+            None => Ok(()),
+        }
+    }
+
+    /// The last version whose state is committed, `None` if nothing was queued since the DB was
+    /// opened.
+    pub fn committed_version(&self) -> Option<Version> {
+        self.inner.lock().committed_version
+    }
+
+    /// Blocks till the state of `version` is committed, if it's queued to be. Otherwise returns
+    /// right away, the state being either committed or not known to the DB. Fails if the
+    /// committer failed before committing it.
+    ///
+    /// On a worker thread of a tokio runtime, e.g. of the API, the other tasks of the worker are
+    /// handed over to another thread while blocking, which requires the multi-threaded runtime.
+    pub fn wait_for(&self, version: Version) -> Result<()> {
+        if !self.inner.lock().is_pending(version) {
+            return Ok(());
+        }
+        if tokio::runtime::Handle::try_current().is_ok() {
+            tokio::task::block_in_place(|| self.wait_for_blocking(version))
+        } else {
+            self.wait_for_blocking(version)
+        }
+    }
+
+    fn wait_for_blocking(&self, version: Version) -> Result<()> {
+        let mut inner = self.inner.lock();
+        while inner.is_pending(version) {
+            if let Some(error) = &inner.error {
+                bail!(
+                    "Committing the state of version {} failed: {}",
+                    version,
+                    error
+                );
+            }
+            inner = self
+                .committed
+                .wait(inner)
+                .expect("Cannot currently handle a poisoned lock");
+        }
+        Ok(())
+    }
+
+    /// Blocks till the state of all the queued versions is committed.
+    pub fn wait_for_all(&self) -> Result<()> {
+        let queued_version = self.inner.lock().queued_version;
+        match queued_version {
+            Some(version) => self.wait_for(version),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StateCommitProgress;
+    use anyhow::anyhow;
+    use std::{sync::Arc, thread, time::Duration};
+
+    #[test]
+    fn test_wait_for() {
+        let progress = Arc::new(StateCommitProgress::new());
+        // nothing queued
+        progress.wait_for(10).unwrap();
+
+        progress.queue(3, 10);
+        // committed before
+        progress.wait_for(2).unwrap();
+        progress.finish(5);
+        progress.wait_for(5).unwrap();
+        // beyond the queued versions
+        progress.wait_for(11).unwrap();
+
+        let progress_clone = Arc::clone(&progress);
+        let committer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            progress_clone.finish(10);
+        });
+        progress.wait_for(8).unwrap();
+        assert_eq!(progress.committed_version(), Some(10));
+        committer.join().unwrap();
+    }
+
+    #[test]
+    fn test_wait_for_failed_commit() {
+        let progress = Arc::new(StateCommitProgress::new());
+        progress.queue(0, 10);
+        progress.finish(5);
+
+        let progress_clone = Arc::clone(&progress);
+        let committer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            progress_clone.fail(&anyhow!("disk full"));
+        });
+        assert!(progress.wait_for(8).is_err());
+        committer.join().unwrap();
+        // the committed versions are still readable
+        progress.wait_for(5).unwrap();
+        assert!(progress.check().is_err());
+        assert!(progress.wait_for_all().is_err());
+    }
+}
@@ -3,6 +3,9 @@
 
 //! This file defines state store APIs that are related account state Merkle tree.
 
+pub(crate) mod commit_progress;
+mod state_cache;
+pub(crate) mod state_shards;
 #[cfg(test)]
 mod state_store_test;
 
@@ -12,6 +15,9 @@ use crate::{
     schema::{
         jellyfish_merkle_node::JellyfishMerkleNodeSchema, stale_node_index::StaleNodeIndexSchema,
     },
+    state_store::{
+        commit_progress::StateCommitProgress, state_cache::StateCache, state_shards::StateShards,
+    },
     state_value_index::StateValueIndexSchema,
     AptosDbError,
 };
@@ -38,7 +44,10 @@ use itertools::process_results;
 use schemadb::{SchemaBatch, DB};
 #[cfg(test)]
 use std::cmp::Ordering;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 use storage_interface::StateSnapshotReceiver;
 
 type LeafNode = aptos_jellyfish_merkle::node_type::LeafNode<StateKeyAndValue>;
@@ -50,12 +59,66 @@ pub const MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX: usize = 10_000;
 
 #[derive(Debug)]
 pub(crate) struct StateStore {
-    db: Arc<DB>,
+    shards: StateShards,
+    cache: StateCache,
+    commit_progress: StateCommitProgress,
 }
 
 impl StateStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    /// `state_cache_size` is the number of state values kept in memory.
+    pub fn new(db: Arc<DB>, state_cache_size: usize) -> Self {
+        Self::new_with_shards(StateShards::unsharded(db), state_cache_size)
+    }
+
+    pub fn new_with_shards(shards: StateShards, state_cache_size: usize) -> Self {
+        Self {
+            shards,
+            cache: StateCache::new(state_cache_size),
+            commit_progress: StateCommitProgress::new(),
+        }
+    }
+
+    /// The progress of the pipelined commits of the state, which the reads of the state of a
+    /// version wait for.
+    pub fn commit_progress(&self) -> &StateCommitProgress {
+        &self.commit_progress
+    }
+
+    pub fn is_sharded(&self) -> bool {
+        self.shards.is_sharded()
+    }
+
+    /// The DBs the state Merkle tree is spread across, only the main DB if it's not sharded.
+    pub fn state_dbs(&self) -> Vec<Arc<DB>> {
+        self.shards.dbs().to_vec()
+    }
+
+    /// The DBs the state Merkle tree is sharded across, none if it's not sharded.
+    pub fn state_shard_dbs(&self) -> &[Arc<DB>] {
+        if self.is_sharded() {
+            self.shards.dbs()
+        } else {
+            &[]
+        }
+    }
+
+    /// Writes the alternations of the state Merkle tree shards. They are written before the main
+    /// DB commits the versions they belong to, so a crash in between leaves only nodes of
+    /// uncommitted versions behind, which are overwritten when the versions are committed again.
+    pub fn commit_state_shard_batches(&self, batches: BTreeMap<usize, SchemaBatch>) -> Result<()> {
+        batches
+            .into_iter()
+            .try_for_each(|(shard_id, batch)| self.shards.dbs()[shard_id].write_schemas(batch))
+    }
+
+    /// The batch the alternations of the shard go to, the batch of the main DB if the tree is not
+    /// sharded.
+    fn batch_of<'a>(&self, cs: &'a mut ChangeSet, shard_id: usize) -> &'a mut SchemaBatch {
+        if self.shards.is_sharded() {
+            cs.state_shard_batch(shard_id)
+        } else {
+            &mut cs.batch
+        }
     }
 
     /// Get the state value with proof given the state key and root hash of state Merkle tree
@@ -64,6 +127,7 @@ impl StateStore {
         state_key: &StateKey,
         version: Version,
     ) -> Result<(Option<StateValue>, SparseMerkleProof<StateValue>)> {
+        self.commit_progress.wait_for(version)?;
         let (state_key_value_option, proof) =
             JellyfishMerkleTree::new(self).get_with_proof(state_key.hash(), version)?;
         Ok((
@@ -78,8 +142,22 @@ impl StateStore {
         key_prefix: &StateKeyPrefix,
         desired_version: Version,
     ) -> Result<HashMap<StateKey, NodeKey>> {
-        let mut iter = self.db.iter::<StateValueIndexSchema>(Default::default())?;
         let mut result = HashMap::new();
+        // The keys of the prefix are spread across all the shards by their hashes.
+        for db in self.shards.dbs() {
+            Self::get_node_keys_by_key_prefix_in_db(db, key_prefix, desired_version, &mut result)?;
+        }
+        Ok(result)
+    }
+
+    #[cfg(test)]
+    fn get_node_keys_by_key_prefix_in_db(
+        db: &DB,
+        key_prefix: &StateKeyPrefix,
+        desired_version: Version,
+        result: &mut HashMap<StateKey, NodeKey>,
+    ) -> Result<()> {
+        let mut iter = db.iter::<StateValueIndexSchema>(Default::default())?;
         iter.seek(&(key_prefix))?;
         while let Some(((state_key, first_version), num_nibbles)) = iter.next().transpose()? {
             // Cursor is currently at the first available version of the state key.
@@ -87,7 +165,7 @@ impl StateStore {
 
             if !key_prefix.is_prefix(&state_key)? {
                 // No more keys matching the key_prefix, we can return the result.
-                return Ok(result);
+                return Ok(());
             }
             match first_version.cmp(&desired_version) {
                 Ordering::Less => {
@@ -132,7 +210,7 @@ impl StateStore {
             // Seek to the next key - this can be done by seeking to the current key with max version
             iter.seek(&(state_key, u64::MAX))?;
         }
-        Ok(result)
+        Ok(())
     }
 
     /// Returns the key, value pairs for a particular state key prefix at at desired version. This
@@ -154,24 +232,38 @@ impl StateStore {
         Ok(result)
     }
 
-    /// Get the state value given the state key and root hash of state Merkle tree by using the
-    /// state value index. Only used for testing for now but should replace the
-    /// `get_value_with_proof_by_version` call for VM execution to fetch the value without proof.
-    #[cfg(test)]
+    /// Get the state value given the state key and version by using the state value index, without
+    /// proof. The values are cached over the versions since the leaf of the key was last written,
+    /// so a cached value is validated by a seek in the index rather than a walk of the tree.
     pub fn get_value_by_version(
         &self,
         state_key: &StateKey,
         version: Version,
     ) -> Result<Option<StateValue>> {
-        match self.get_jmt_leaf_node_key(state_key, version)? {
-            Some(node_key) => self.get_value_by_node_key(&node_key),
-            None => Ok(None),
+        self.commit_progress.wait_for(version)?;
+        if let Some(value) = self.cache.get(state_key, version) {
+            return Ok(value);
+        }
+        let node_key = self.get_jmt_leaf_node_key(state_key, version)?;
+        let written_at = node_key.as_ref().map(|node_key| node_key.version());
+        if let Some(value) = self.cache.get_unchanged(state_key, version, written_at) {
+            return Ok(value);
         }
+        let value = match &node_key {
+            Some(node_key) => self.get_value_by_node_key(node_key)?,
+            None => None,
+        };
+        self.cache
+            .insert(state_key, version, written_at, value.clone());
+        Ok(value)
     }
 
-    #[cfg(test)]
     fn get_value_by_node_key(&self, node_key: &NodeKey) -> Result<Option<StateValue>> {
-        if let Some(Node::Leaf(leaf)) = self.db.get::<JellyfishMerkleNodeSchema>(node_key)? {
+        if let Some(Node::Leaf(leaf)) = self
+            .shards
+            .db_by_nibble_path(node_key.nibble_path())
+            .get::<JellyfishMerkleNodeSchema>(node_key)?
+        {
             Ok(Some(leaf.value().value.clone()))
         } else {
             Err(anyhow::anyhow!(
@@ -183,13 +275,15 @@ impl StateStore {
 
     /// Returns the value index in the form of number of nibbles for given pair of state key and version
     /// which can be used to index into the JMT leaf.
-    #[cfg(test)]
     fn get_jmt_leaf_node_key(
         &self,
         state_key: &StateKey,
         version: Version,
     ) -> Result<Option<NodeKey>> {
-        let mut iter = self.db.iter::<StateValueIndexSchema>(Default::default())?;
+        let mut iter = self
+            .shards
+            .db_by_state_key(state_key)
+            .iter::<StateValueIndexSchema>(Default::default())?;
         iter.seek_for_prev(&(state_key.clone(), version))?;
         Ok(iter
             .next()
@@ -214,6 +308,7 @@ impl StateStore {
         rightmost_key: HashValue,
         version: Version,
     ) -> Result<SparseMerkleRangeProof> {
+        self.commit_progress.wait_for(version)?;
         JellyfishMerkleTree::new(self).get_range_proof(rightmost_key, version)
     }
 
@@ -263,22 +358,30 @@ impl StateStore {
                 counter_bumps.bump(LedgerCounter::StaleStateNodes, stats.stale_nodes);
                 counter_bumps.bump(LedgerCounter::StaleStateLeaves, stats.stale_leaves);
             });
-        add_node_batch_and_index(&mut cs.batch, &tree_update_batch.node_batch)?;
+        self.add_node_batch_and_index(cs, &tree_update_batch.node_batch)?;
 
         tree_update_batch
             .stale_node_index_batch
             .iter()
-            .map(|row| cs.batch.put::<StaleNodeIndexSchema>(row, &()))
+            .map(|row| {
+                let shard_id = self
+                    .shards
+                    .shard_id_by_nibble_path(row.node_key.nibble_path());
+                self.batch_of(cs, shard_id)
+                    .put::<StaleNodeIndexSchema>(row, &())
+            })
             .collect::<Result<Vec<()>>>()?;
 
         Ok(new_root_hash_vec)
     }
 
     pub fn get_root_hash(&self, version: Version) -> Result<HashValue> {
+        self.commit_progress.wait_for(version)?;
         JellyfishMerkleTree::new(self).get_root_hash(version)
     }
 
     pub fn get_root_hash_option(&self, version: Version) -> Result<Option<HashValue>> {
+        self.commit_progress.wait_for(version)?;
         JellyfishMerkleTree::new(self).get_root_hash_option(version)
     }
 
@@ -287,18 +390,18 @@ impl StateStore {
     pub fn get_rightmost_leaf_naive(&self) -> Result<Option<(NodeKey, LeafNode)>> {
         let mut ret = None;
 
-        let mut iter = self
-            .db
-            .iter::<JellyfishMerkleNodeSchema>(Default::default())?;
-        iter.seek_to_first();
+        for db in self.shards.dbs() {
+            let mut iter = db.iter::<JellyfishMerkleNodeSchema>(Default::default())?;
+            iter.seek_to_first();
 
-        while let Some((node_key, node)) = iter.next().transpose()? {
-            if let Node::Leaf(leaf_node) = node {
-                match ret {
-                    None => ret = Some((node_key, leaf_node)),
-                    Some(ref other) => {
-                        if leaf_node.account_key() > other.1.account_key() {
-                            ret = Some((node_key, leaf_node));
+            while let Some((node_key, node)) = iter.next().transpose()? {
+                if let Node::Leaf(leaf_node) = node {
+                    match ret {
+                        None => ret = Some((node_key, leaf_node)),
+                        Some(ref other) => {
+                            if leaf_node.account_key() > other.1.account_key() {
+                                ret = Some((node_key, leaf_node));
+                            }
                         }
                     }
                 }
@@ -309,6 +412,7 @@ impl StateStore {
     }
 
     pub fn get_value_count(&self, version: Version) -> Result<usize> {
+        self.commit_progress.wait_for(version)?;
         JellyfishMerkleTree::new(self).get_leaf_count(version)
     }
 
@@ -318,6 +422,7 @@ impl StateStore {
         first_index: usize,
         chunk_size: usize,
     ) -> Result<StateValueChunkWithProof> {
+        self.commit_progress.wait_for(version)?;
         let result_iter =
             JellyfishMerkleIterator::new_by_index(Arc::clone(self), version, first_index)?
                 .take(chunk_size);
@@ -344,109 +449,139 @@ impl StateStore {
         })
     }
 
+    /// Returns a receiver of the snapshot at `version`, which resumes the restore of the snapshot
+    /// at the same version interrupted before, if any.
     pub fn get_snapshot_receiver(
         self: &Arc<Self>,
         version: Version,
         expected_root_hash: HashValue,
     ) -> Result<Box<dyn StateSnapshotReceiver<StateKeyAndValue>>> {
-        Ok(Box::new(JellyfishMerkleRestore::new_overwrite(
+        Ok(Box::new(JellyfishMerkleRestore::new(
             Arc::clone(self),
             version,
             expected_root_hash,
         )?))
     }
+
+    /// Returns the number of keys of the snapshot at `version` restored before the restore was
+    /// interrupted.
+    pub fn get_num_restored_snapshot_keys(&self, version: Version) -> Result<u64> {
+        JellyfishMerkleRestore::<StateKeyAndValue>::get_num_restored_keys(self, version)
+    }
 }
 
 impl TreeReader<StateKeyAndValue> for StateStore {
     fn get_node_option(&self, node_key: &NodeKey) -> Result<Option<Node>> {
-        self.db.get::<JellyfishMerkleNodeSchema>(node_key)
+        self.shards
+            .db_by_nibble_path(node_key.nibble_path())
+            .get::<JellyfishMerkleNodeSchema>(node_key)
     }
 
     fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode)>> {
-        // Since everything has the same version during restore, we seek to the first node and get
-        // its version.
-        let mut iter = self
-            .db
-            .iter::<JellyfishMerkleNodeSchema>(Default::default())?;
-        iter.seek_to_first();
-        let version = match iter.next().transpose()? {
-            Some((node_key, _node)) => node_key.version(),
-            None => return Ok(None),
-        };
-
-        // The encoding of key and value in DB looks like:
-        //
-        // | <-------------- key --------------> | <- value -> |
-        // | version | num_nibbles | nibble_path |    node     |
-        //
-        // Here version is fixed. For each num_nibbles, there could be a range of nibble paths
-        // of the same length. If one of them is the rightmost leaf R, it must be at the end of this
-        // range. Otherwise let's assume the R is in the middle of the range, so we
-        // call the node at the end of this range X:
-        //   1. If X is leaf, then X.account_key() > R.account_key(), because the nibble path is a
-        //      prefix of the account key. So R is not the rightmost leaf.
-        //   2. If X is internal node, then X must be on the right side of R, so all its children's
-        //      account keys are larger than R.account_key(). So R is not the rightmost leaf.
-        //
-        // Given that num_nibbles ranges from 0 to ROOT_NIBBLE_HEIGHT, there are only
-        // ROOT_NIBBLE_HEIGHT+1 ranges, so we can just find the node at the end of each range and
-        // then pick the one with the largest account key.
-        let mut ret = None;
+        // The shards hold the nodes in the order of the tree, so the rightmost leaf is in the last
+        // shard that has a leaf.
+        for db in self.shards.dbs().iter().rev() {
+            if let Some(ret) = get_rightmost_leaf_in_db(db)? {
+                return Ok(Some(ret));
+            }
+        }
+        Ok(None)
+    }
+}
 
-        for num_nibbles in 1..=ROOT_NIBBLE_HEIGHT + 1 {
-            let mut iter = self
-                .db
-                .iter::<JellyfishMerkleNodeSchema>(Default::default())?;
-            // nibble_path is always non-empty except for the root, so if we use an empty nibble
-            // path as the seek key, the iterator will end up pointing to the end of the previous
-            // range.
-            let seek_key = (version, num_nibbles as u8);
-            iter.seek_for_prev(&seek_key)?;
+impl TreeWriter<StateKeyAndValue> for StateStore {
+    fn write_node_batch(&self, node_batch: &NodeBatch) -> Result<()> {
+        let mut cs = ChangeSet::new();
+        self.add_node_batch_and_index(&mut cs, node_batch)?;
+        self.commit_state_shard_batches(cs.state_shard_batches)?;
+        if self.shards.is_sharded() {
+            Ok(())
+        } else {
+            self.shards.dbs()[0].write_schemas(cs.batch)
+        }
+    }
+}
 
-            if let Some((node_key, node)) = iter.next().transpose()? {
-                debug_assert_eq!(node_key.version(), version);
-                debug_assert!(node_key.nibble_path().num_nibbles() < num_nibbles);
+fn get_rightmost_leaf_in_db(db: &DB) -> Result<Option<(NodeKey, LeafNode)>> {
+    // Since the nodes being restored have the latest version in the DB (e.g. above the genesis
+    // tree of a node syncing a state snapshot), we seek to the last node and get its version.
+    let mut iter = db.iter::<JellyfishMerkleNodeSchema>(Default::default())?;
+    iter.seek_to_last();
+    let version = match iter.next().transpose()? {
+        Some((node_key, _node)) => node_key.version(),
+        None => return Ok(None),
+    };
+
+    // The encoding of key and value in DB looks like:
+    //
+    // | <-------------- key --------------> | <- value -> |
+    // | version | num_nibbles | nibble_path |    node     |
+    //
+    // Here version is fixed. For each num_nibbles, there could be a range of nibble paths
+    // of the same length. If one of them is the rightmost leaf R, it must be at the end of this
+    // range. Otherwise let's assume the R is in the middle of the range, so we
+    // call the node at the end of this range X:
+    //   1. If X is leaf, then X.account_key() > R.account_key(), because the nibble path is a
+    //      prefix of the account key. So R is not the rightmost leaf.
+    //   2. If X is internal node, then X must be on the right side of R, so all its children's
+    //      account keys are larger than R.account_key(). So R is not the rightmost leaf.
+    //
+    // Given that num_nibbles ranges from 0 to ROOT_NIBBLE_HEIGHT, there are only
+    // ROOT_NIBBLE_HEIGHT+1 ranges, so we can just find the node at the end of each range and
+    // then pick the one with the largest account key.
+    let mut ret = None;
+
+    for num_nibbles in 1..=ROOT_NIBBLE_HEIGHT + 1 {
+        let mut iter = db.iter::<JellyfishMerkleNodeSchema>(Default::default())?;
+        // nibble_path is always non-empty except for the root, so if we use an empty nibble
+        // path as the seek key, the iterator will end up pointing to the end of the previous
+        // range.
+        let seek_key = (version, num_nibbles as u8);
+        iter.seek_for_prev(&seek_key)?;
+
+        if let Some((node_key, node)) = iter.next().transpose()? {
+            debug_assert_eq!(node_key.version(), version);
+            debug_assert!(node_key.nibble_path().num_nibbles() < num_nibbles);
 
-                if let Node::Leaf(leaf_node) = node {
-                    match ret {
-                        None => ret = Some((node_key, leaf_node)),
-                        Some(ref other) => {
-                            if leaf_node.account_key() > other.1.account_key() {
-                                ret = Some((node_key, leaf_node));
-                            }
+            if let Node::Leaf(leaf_node) = node {
+                match ret {
+                    None => ret = Some((node_key, leaf_node)),
+                    Some(ref other) => {
+                        if leaf_node.account_key() > other.1.account_key() {
+                            ret = Some((node_key, leaf_node));
                         }
                     }
                 }
             }
         }
-
-        Ok(ret)
     }
-}
 
-impl TreeWriter<StateKeyAndValue> for StateStore {
-    fn write_node_batch(&self, node_batch: &NodeBatch) -> Result<()> {
-        let mut batch = SchemaBatch::new();
-        add_node_batch_and_index(&mut batch, node_batch)?;
-        self.db.write_schemas(batch)
-    }
+    Ok(ret)
 }
 
-fn add_node_batch_and_index(batch: &mut SchemaBatch, node_batch: &NodeBatch) -> Result<()> {
-    node_batch
-        .iter()
-        .map(|(node_key, node)| {
-            batch.put::<JellyfishMerkleNodeSchema>(node_key, node)?;
-            // Add the value index for leaf nodes.
-            match node {
-                Node::Leaf(leaf) => batch.put::<StateValueIndexSchema>(
-                    &(leaf.value().key.clone(), node_key.version()),
-                    &(node_key.nibble_path().num_nibbles() as u8),
-                ),
-
-                _ => Ok(()),
-            }
-        })
-        .collect::<Result<Vec<_>>>()?;
-    Ok(())
+impl StateStore {
+    fn add_node_batch_and_index(&self, cs: &mut ChangeSet, node_batch: &NodeBatch) -> Result<()> {
+        node_batch
+            .iter()
+            .map(|(node_key, node)| {
+                let shard_id = self.shards.shard_id_by_nibble_path(node_key.nibble_path());
+                self.batch_of(cs, shard_id)
+                    .put::<JellyfishMerkleNodeSchema>(node_key, node)?;
+                // Add the value index for leaf nodes.
+                match node {
+                    Node::Leaf(leaf) => {
+                        let state_key = &leaf.value().key;
+                        let shard_id = self.shards.shard_id_by_state_key(state_key);
+                        self.batch_of(cs, shard_id).put::<StateValueIndexSchema>(
+                            &(state_key.clone(), node_key.version()),
+                            &(node_key.nibble_path().num_nibbles() as u8),
+                        )
+                    }
+
+                    _ => Ok(()),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(())
+    }
 }
@@ -0,0 +1,234 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! LRU cache of the state values, serving the repeated reads of hot state, e.g. the modules under
+//! 0x1 or popular coin resources, without walking the Jellyfish Merkle tree.
+
+use crate::metrics::APTOS_STORAGE_STATE_CACHE;
+use aptos_infallible::Mutex;
+use aptos_types::{
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Each entry holds the value of a key over a range of versions, from the version the leaf of the
+/// key was last written at, `None` if it never was, to the latest version the entry was validated
+/// at. A read in the range is served from the entry. A read at a later version is served from it
+/// once validated against the version the leaf was last written at, extending the range. The
+/// proofs of the values change with every version, so they're not cached.
+///
+/// The entries are evicted once the cache is full, least recently read first.
+#[derive(Debug)]
+pub(crate) struct StateCache {
+    capacity: usize,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: Option<StateValue>,
+    written_at: Option<Version>,
+    validated_at: Version,
+    last_read: u64,
+}
+
+impl Entry {
+    fn contains(&self, version: Version) -> bool {
+        self.written_at
+            .map_or(true, |written_at| written_at <= version)
+            && version <= self.validated_at
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    entries: HashMap<StateKey, Entry>,
+    // The key of each entry, by the tick it was last read at.
+    recency: BTreeMap<u64, StateKey>,
+    next_tick: u64,
+}
+
+impl Inner {
+    // Serves the read from the entry of the key if `is_valid`, marking it as the last read.
+    fn read(
+        &mut self,
+        state_key: &StateKey,
+        is_valid: impl FnOnce(&mut Entry) -> bool,
+    ) -> Option<Option<StateValue>> {
+        self.next_tick += 1;
+        let tick = self.next_tick;
+        let entry = self.entries.get_mut(state_key)?;
+        if !is_valid(entry) {
+            return None;
+        }
+        let old_tick = std::mem::replace(&mut entry.last_read, tick);
+        let value = entry.value.clone();
+        let key = self
+            .recency
+            .remove(&old_tick)
+            .expect("Recency tracked for every entry.");
+        self.recency.insert(tick, key);
+        Some(value)
+    }
+}
+
+impl StateCache {
+    /// A cache with a `capacity` of 0 caches nothing.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// The value of the key at the version, if in the range of versions of its entry.
+    pub fn get(&self, state_key: &StateKey, version: Version) -> Option<Option<StateValue>> {
+        if self.capacity == 0 {
+            return None;
+        }
+        let ret = self
+            .inner
+            .lock()
+            .read(state_key, |entry| entry.contains(version));
+        APTOS_STORAGE_STATE_CACHE
+            .with_label_values(&[if ret.is_some() { "hit" } else { "miss" }])
+            .inc();
+        ret
+    }
+
+    /// The value of the key at the version, given the version its leaf was last written at before
+    /// it, if the entry of the key holds the value of that write. The range of versions of the
+    /// entry is extended to the version.
+    pub fn get_unchanged(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+        written_at: Option<Version>,
+    ) -> Option<Option<StateValue>> {
+        if self.capacity == 0 {
+            return None;
+        }
+        let ret = self.inner.lock().read(state_key, |entry| {
+            if entry.written_at != written_at {
+                return false;
+            }
+            entry.validated_at = entry.validated_at.max(version);
+            true
+        });
+        APTOS_STORAGE_STATE_CACHE
+            .with_label_values(&[if ret.is_some() { "hit" } else { "miss" }])
+            .inc();
+        ret
+    }
+
+    /// Caches the value of the key at the version, whose leaf was last written at `written_at`.
+    pub fn insert(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+        written_at: Option<Version>,
+        value: Option<StateValue>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock();
+        inner.next_tick += 1;
+        let entry = Entry {
+            value,
+            written_at,
+            validated_at: version,
+            last_read: inner.next_tick,
+        };
+        // Only the key of a new entry is cloned.
+        let key = match inner.entries.get_mut(state_key) {
+            Some(old_entry) => {
+                let old_tick = old_entry.last_read;
+                *old_entry = entry;
+                inner
+                    .recency
+                    .remove(&old_tick)
+                    .expect("Recency tracked for every entry.")
+            }
+            None => {
+                inner.entries.insert(state_key.clone(), entry);
+                state_key.clone()
+            }
+        };
+        let tick = inner.next_tick;
+        inner.recency.insert(tick, key);
+        while inner.entries.len() > self.capacity {
+            let oldest_tick = *inner
+                .recency
+                .keys()
+                .next()
+                .expect("Recency tracked for every entry.");
+            let oldest = inner.recency.remove(&oldest_tick).expect("Tick just read.");
+            inner.entries.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StateCache;
+    use aptos_types::{
+        account_address::AccountAddress,
+        state_store::{state_key::StateKey, state_value::StateValue},
+    };
+
+    fn key(i: u8) -> StateKey {
+        StateKey::AccountAddressKey(AccountAddress::new([i; AccountAddress::LENGTH]))
+    }
+
+    fn value(i: u8) -> Option<StateValue> {
+        Some(StateValue::from(vec![i]))
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = StateCache::new(2);
+        cache.insert(&key(1), 0, Some(0), value(1));
+        cache.insert(&key(2), 0, Some(0), value(2));
+        // reading key 1 makes key 2 the least recently read
+        assert_eq!(cache.get(&key(1), 0), Some(value(1)));
+        cache.insert(&key(3), 0, Some(0), value(3));
+
+        assert_eq!(cache.get(&key(2), 0), None);
+        assert_eq!(cache.get(&key(1), 0), Some(value(1)));
+        assert_eq!(cache.get(&key(3), 0), Some(value(3)));
+    }
+
+    #[test]
+    fn test_range_of_versions() {
+        let cache = StateCache::new(2);
+        cache.insert(&key(1), 5, Some(3), value(1));
+        assert_eq!(cache.get(&key(1), 3), Some(value(1)));
+        assert_eq!(cache.get(&key(1), 5), Some(value(1)));
+        // before the write, and past the validated versions
+        assert_eq!(cache.get(&key(1), 2), None);
+        assert_eq!(cache.get(&key(1), 8), None);
+
+        // unchanged since the write, extending the range
+        assert_eq!(cache.get_unchanged(&key(1), 8, Some(3)), Some(value(1)));
+        assert_eq!(cache.get(&key(1), 7), Some(value(1)));
+        // written again
+        assert_eq!(cache.get_unchanged(&key(1), 10, Some(9)), None);
+        cache.insert(&key(1), 10, Some(9), value(2));
+        assert_eq!(cache.get(&key(1), 10), Some(value(2)));
+        assert_eq!(cache.get(&key(1), 5), None);
+
+        // never written
+        cache.insert(&key(2), 4, None, None);
+        assert_eq!(cache.get(&key(2), 0), Some(None));
+        assert_eq!(cache.get_unchanged(&key(2), 6, None), Some(None));
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let cache = StateCache::new(0);
+        cache.insert(&key(1), 0, Some(0), value(1));
+        assert_eq!(cache.get(&key(1), 0), None);
+    }
+}
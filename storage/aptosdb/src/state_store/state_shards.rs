@@ -0,0 +1,126 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The state Merkle tree can be spread across multiple RocksDB instances, e.g. one on each NVMe
+//! device of a large validator, by the leading nibble of the hash of the state key.
+//!
+//! Shard `i` of `N` holds the nodes whose nibble paths start with a nibble in
+//! `[i * 16 / N, (i + 1) * 16 / N)`, in the same order as the nodes are in the tree, so the
+//! rightmost leaf is always in the last shard that has a leaf. The root, whose nibble path is
+//! empty, is held by the first shard. The stale node index of a node is put in the same shard as
+//! the node, and the value index of a state key in the shard of the hash of the key.
+
+use anyhow::{ensure, Result};
+use aptos_crypto::hash::CryptoHash;
+use aptos_types::{nibble::nibble_path::NibblePath, state_store::state_key::StateKey};
+use schemadb::{schema::Schema, ReadOptions, SchemaBatch, DB};
+use std::{collections::BTreeMap, sync::Arc};
+
+const NUM_NIBBLE_VALUES: usize = 16;
+/// Number of records moved to the shards at once by `move_from`.
+const MOVE_BATCH_SIZE: usize = 10_000;
+
+#[derive(Debug)]
+pub(crate) struct StateShards {
+    dbs: Vec<Arc<DB>>,
+    sharded: bool,
+}
+
+impl StateShards {
+    /// The whole tree in the main DB, written in the same batch as the rest of the ledger.
+    pub fn unsharded(db: Arc<DB>) -> Self {
+        Self {
+            dbs: vec![db],
+            sharded: false,
+        }
+    }
+
+    /// The tree spread across `dbs`, by the order of the nibbles they hold.
+    pub fn new(dbs: Vec<Arc<DB>>) -> Result<Self> {
+        Self::ensure_num_shards(dbs.len())?;
+        Ok(Self { dbs, sharded: true })
+    }
+
+    pub fn ensure_num_shards(num_shards: usize) -> Result<()> {
+        ensure!(
+            num_shards > 0 && NUM_NIBBLE_VALUES % num_shards == 0,
+            "The number of state shards must divide {}, got {}.",
+            NUM_NIBBLE_VALUES,
+            num_shards,
+        );
+        Ok(())
+    }
+
+    pub fn is_sharded(&self) -> bool {
+        self.sharded
+    }
+
+    pub fn num_shards(&self) -> usize {
+        self.dbs.len()
+    }
+
+    pub fn dbs(&self) -> &[Arc<DB>] {
+        &self.dbs
+    }
+
+    pub fn shard_id_by_nibble_path(&self, nibble_path: &NibblePath) -> usize {
+        if nibble_path.is_empty() {
+            0
+        } else {
+            self.shard_id_by_nibble(u8::from(nibble_path.get_nibble(0)))
+        }
+    }
+
+    pub fn shard_id_by_state_key(&self, state_key: &StateKey) -> usize {
+        self.shard_id_by_nibble(state_key.hash().nibble(0))
+    }
+
+    pub fn db_by_nibble_path(&self, nibble_path: &NibblePath) -> &DB {
+        &self.dbs[self.shard_id_by_nibble_path(nibble_path)]
+    }
+
+    #[cfg(test)]
+    pub fn db_by_state_key(&self, state_key: &StateKey) -> &DB {
+        &self.dbs[self.shard_id_by_state_key(state_key)]
+    }
+
+    /// Moves all the records of the schema from `db` to the shards, returning the number of
+    /// records moved. Each batch is written to the shards before it's deleted from `db`, so nothing
+    /// is lost if interrupted, and calling it again moves the rest.
+    pub fn move_from<S, F>(&self, db: &DB, shard_id_by_key: F) -> Result<usize>
+    where
+        S: Schema,
+        F: Fn(&S::Key) -> usize,
+    {
+        let mut num_moved = 0;
+        let mut iter = db.iter::<S>(ReadOptions::default())?;
+        iter.seek_to_first();
+        loop {
+            let rows = iter
+                .by_ref()
+                .take(MOVE_BATCH_SIZE)
+                .collect::<Result<Vec<_>>>()?;
+            if rows.is_empty() {
+                return Ok(num_moved);
+            }
+            let mut shard_batches = BTreeMap::new();
+            let mut batch = SchemaBatch::new();
+            for (key, value) in &rows {
+                shard_batches
+                    .entry(shard_id_by_key(key))
+                    .or_insert_with(SchemaBatch::new)
+                    .put::<S>(key, value)?;
+                batch.delete::<S>(key)?;
+            }
+            for (shard_id, shard_batch) in shard_batches {
+                self.dbs[shard_id].write_schemas(shard_batch)?;
+            }
+            db.write_schemas(batch)?;
+            num_moved += rows.len();
+        }
+    }
+
+    fn shard_id_by_nibble(&self, nibble: u8) -> usize {
+        nibble as usize / (NUM_NIBBLE_VALUES / self.dbs.len())
+    }
+}
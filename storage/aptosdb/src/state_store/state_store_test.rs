@@ -1,20 +1,21 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::HashSet, convert::TryFrom};
+use std::{collections::HashSet, convert::TryFrom, path::PathBuf};
 
 use proptest::{
     collection::{hash_map, vec},
     prelude::*,
 };
 
+use aptos_config::config::{RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_jellyfish_merkle::restore::JellyfishMerkleRestore;
 use aptos_temppath::TempPath;
 use aptos_types::{
     access_path::AccessPath, account_address::AccountAddress, account_state_blob::AccountStateBlob,
     state_store::state_key::StateKeyTag,
 };
-use storage_interface::StateSnapshotReceiver;
+use storage_interface::{DbReader, DbWriter, StateSnapshotReceiver};
 
 use crate::{pruner, AptosDB};
 
@@ -57,7 +58,7 @@ fn put_account_state_set(
         expected_stale_leaves
     );
 
-    store.db.write_schemas(cs.batch).unwrap();
+    commit(store, cs);
     root
 }
 
@@ -75,10 +76,25 @@ fn put_value_set(
     let root = state_store
         .put_value_sets(vec![&value_set], None, version, &mut cs)
         .unwrap()[0];
-    state_store.db.write_schemas(cs.batch).unwrap();
+    commit(state_store, cs);
     root
 }
 
+fn commit(store: &StateStore, cs: ChangeSet) {
+    store
+        .commit_state_shard_batches(cs.state_shard_batches)
+        .unwrap();
+    if !store.is_sharded() {
+        store.state_dbs()[0].write_schemas(cs.batch).unwrap();
+    }
+}
+
+fn state_shard_dirs(tmp_dir: &TempPath, num_shards: usize) -> Vec<PathBuf> {
+    (0..num_shards)
+        .map(|shard_id| tmp_dir.path().join(format!("shard_{}", shard_id)))
+        .collect()
+}
+
 fn prune_stale_indices(
     store: &StateStore,
     least_readable_version: Version,
@@ -86,7 +102,7 @@ fn prune_stale_indices(
     limit: usize,
 ) {
     pruner::state_store::prune_state_store(
-        Arc::clone(&store.db),
+        &store.state_dbs(),
         least_readable_version,
         target_least_readable_version,
         limit,
@@ -298,6 +314,20 @@ fn test_get_values_by_key_prefix() {
 
 #[test]
 fn test_retired_records() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    check_retired_records(&db.state_store);
+}
+
+#[test]
+fn test_sharded_retired_records() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_state_shards(&tmp_dir, &state_shard_dirs(&tmp_dir, 4));
+    assert!(db.state_store.is_sharded());
+    check_retired_records(&db.state_store);
+}
+
+fn check_retired_records(store: &StateStore) {
     let address1 = AccountAddress::new([1u8; AccountAddress::LENGTH]);
     let address2 = AccountAddress::new([2u8; AccountAddress::LENGTH]);
     let address3 = AccountAddress::new([3u8; AccountAddress::LENGTH]);
@@ -307,10 +337,6 @@ fn test_retired_records() {
     let value3 = AccountStateBlob::from(vec![0x03]);
     let value3_update = AccountStateBlob::from(vec![0x13]);
 
-    let tmp_dir = TempPath::new();
-    let db = AptosDB::new_for_test(&tmp_dir);
-    let store = &db.state_store;
-
     // Update.
     // ```text
     // | batch    | 0      | 1             | 2             |
@@ -390,6 +416,91 @@ fn test_retired_records() {
     }
 }
 
+#[test]
+fn test_migrate_state_to_shards() {
+    let address1 = AccountAddress::new([1u8; AccountAddress::LENGTH]);
+    let address2 = AccountAddress::new([2u8; AccountAddress::LENGTH]);
+    let value1 = AccountStateBlob::from(vec![0x01]);
+    let value2 = AccountStateBlob::from(vec![0x02]);
+    let value2_update = AccountStateBlob::from(vec![0x12]);
+
+    let tmp_dir = TempPath::new();
+    let shard_dirs = state_shard_dirs(&tmp_dir, 2);
+    let (root0, root1) = {
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.state_store;
+        let root0 = put_account_state_set(
+            store,
+            vec![(address1, value1.clone()), (address2, value2.clone())],
+            0, /* version */
+            3, /* expected_nodes_created */
+            0, /* expected_nodes_retired */
+            0, /* expected_blobs_retired */
+        );
+        let root1 = put_account_state_set(
+            store,
+            vec![(address2, value2_update.clone())],
+            1, /* version */
+            2, /* expected_nodes_created */
+            2, /* expected_nodes_retired */
+            1, /* expected_blobs_retired */
+        );
+        (root0, root1)
+    };
+    // The state needs to be migrated before the DB is opened with the shards.
+    assert!(AptosDB::open_with_state_shards(
+        &tmp_dir,
+        &shard_dirs,
+        false, /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfig::default(),
+        0,     /* state_cache_size */
+        false, /* enable_event_type_index */
+        false, /* enable_commit_pipelining */
+        None,  /* cold_ledger_config */
+    )
+    .is_err());
+
+    AptosDB::migrate_state_to_shards(&tmp_dir, &shard_dirs, RocksdbConfig::default()).unwrap();
+    // Neither opens without the shards nor with them in another order any more.
+    assert!(AptosDB::open(
+        &tmp_dir,
+        false, /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfig::default(),
+        0,     /* state_cache_size */
+        false, /* enable_event_type_index */
+    )
+    .is_err());
+    let reversed_dirs: Vec<_> = shard_dirs.iter().rev().cloned().collect();
+    assert!(AptosDB::open_with_state_shards(
+        &tmp_dir,
+        &reversed_dirs,
+        false, /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        RocksdbConfig::default(),
+        0,     /* state_cache_size */
+        false, /* enable_event_type_index */
+        false, /* enable_commit_pipelining */
+        None,  /* cold_ledger_config */
+    )
+    .is_err());
+
+    let db = AptosDB::new_for_test_with_state_shards(&tmp_dir, &shard_dirs);
+    let store = &db.state_store;
+    verify_value_and_proof(store, address1, Some(&value1), 0, root0);
+    verify_value_and_proof(store, address2, Some(&value2), 0, root0);
+    verify_value_and_proof(store, address1, Some(&value1), 1, root1);
+    verify_value_and_proof(store, address2, Some(&value2_update), 1, root1);
+    // Nothing of the tree is left in the main DB.
+    let mut iter = db
+        .db
+        .iter::<JellyfishMerkleNodeSchema>(Default::default())
+        .unwrap();
+    iter.seek_to_first();
+    assert!(iter.next().is_none());
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
 
@@ -523,6 +634,53 @@ proptest! {
         );
     }
 
+    #[test]
+    fn test_resume_restore(
+        (input, batch_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
+            .prop_flat_map(|input| {
+                let len = input.len();
+                (Just(input), 1..len)
+            }),
+        genesis in (any::<StateKey>(), any::<StateValue>()),
+    ) {
+        let tmp_dir1 = TempPath::new();
+        let db1 = AptosDB::new_for_test(&tmp_dir1);
+        let store1 = &db1.state_store;
+        init_store(store1, input.clone().into_iter());
+
+        let version = (input.len() - 1) as Version;
+        let expected_root_hash = store1.get_root_hash(version).unwrap();
+
+        // The restore is interrupted after the first chunk, on top of the tree of the genesis.
+        let tmp_dir2 = TempPath::new();
+        let db2 = AptosDB::new_for_test(&tmp_dir2);
+        init_store(&db2.state_store, std::iter::once(genesis));
+        prop_assert_eq!(db2.get_state_snapshot_progress().unwrap(), None);
+        {
+            let mut restore = db2.get_state_snapshot_receiver(version, expected_root_hash).unwrap();
+            let chunk = store1.get_value_chunk_with_proof(version, 0, batch_size).unwrap();
+            restore.add_chunk(chunk.raw_values, chunk.proof).unwrap();
+        }
+
+        // It's resumed from the first key not in the DB.
+        let progress = db2.get_state_snapshot_progress().unwrap().unwrap();
+        prop_assert_eq!(progress.version, version);
+        prop_assert!(progress.num_restored_keys < batch_size as u64);
+        let mut restore = db2.get_state_snapshot_receiver(version, expected_root_hash).unwrap();
+        let chunk = store1
+            .get_value_chunk_with_proof(version, progress.num_restored_keys as usize, input.len())
+            .unwrap();
+        restore.add_chunk(chunk.raw_values, chunk.proof).unwrap();
+        restore.finish_box().unwrap();
+
+        let store2 = &db2.state_store;
+        prop_assert_eq!(store2.get_root_hash(version).unwrap(), expected_root_hash);
+        prop_assert_eq!(
+            store2.get_value_count(version).unwrap(),
+            input.len()
+        );
+    }
+
     #[test]
     fn test_get_rightmost_leaf(
         (input, batch1_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
@@ -569,6 +727,61 @@ proptest! {
         prop_assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_sharded_get_rightmost_leaf(
+        (input, batch1_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
+            .prop_flat_map(|input| {
+                let len = input.len();
+                (Just(input), 1..len)
+            })
+    ) {
+        let tmp_dir1 = TempPath::new();
+        let db1 = AptosDB::new_for_test(&tmp_dir1);
+        let store1 = &db1.state_store;
+        init_store(store1, input.clone().into_iter());
+
+        let version = (input.len() - 1) as Version;
+        let expected_root_hash = store1.get_root_hash(version).unwrap();
+
+        let tmp_dir2 = TempPath::new();
+        let db2 =
+            AptosDB::new_for_test_with_state_shards(&tmp_dir2, &state_shard_dirs(&tmp_dir2, 8));
+        let store2 = &db2.state_store;
+        // The same tree whether sharded or not.
+        init_store(store2, input.clone().into_iter());
+        prop_assert_eq!(store2.get_root_hash(version).unwrap(), expected_root_hash);
+
+        let tmp_dir3 = TempPath::new();
+        let db3 =
+            AptosDB::new_for_test_with_state_shards(&tmp_dir3, &state_shard_dirs(&tmp_dir3, 8));
+        let store3 = &db3.state_store;
+
+        let mut restore =
+            JellyfishMerkleRestore::new(Arc::clone(store3), version, expected_root_hash).unwrap();
+
+        let mut ordered_input: Vec<_> = input
+            .into_iter()
+            .map(|(addr, value)| (addr.hash(), value))
+            .collect();
+        ordered_input.sort_unstable_by_key(|(key, _value)| *key);
+
+        let batch1: Vec<_> = ordered_input
+            .into_iter()
+            .take(batch1_size)
+            .map(|(key, value)| (key, StateKeyAndValue::new(StateKey::Raw(vec![]), value)))
+            .collect();
+        let rightmost_of_batch1 = batch1.last().map(|(key, _value)| *key).unwrap();
+        let proof_of_batch1 = store1
+            .get_value_range_proof(rightmost_of_batch1, version)
+            .unwrap();
+
+        restore.add_chunk(batch1, proof_of_batch1).unwrap();
+
+        let expected = store3.get_rightmost_leaf_naive().unwrap();
+        let actual = store3.get_rightmost_leaf().unwrap();
+        prop_assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_get_account_count(
         input in vec((any::<StateKey>(), any::<StateValue>()), 1..200)
@@ -605,6 +818,6 @@ fn update_store(
                 &mut cs,
             )
             .unwrap();
-        store.db.write_schemas(cs.batch).unwrap();
+        commit(store, cs);
     }
 }
@@ -5,10 +5,13 @@
 
 use crate::{
     change_set::ChangeSet,
+    cold_ledger::{self, ColdLedger, TieredIter},
     errors::AptosDbError,
     schema::{
-        transaction::TransactionSchema, transaction_by_account::TransactionByAccountSchema,
-        transaction_by_hash::TransactionByHashSchema, write_set::WriteSetSchema,
+        authentication_key_history::AuthenticationKeyHistorySchema, transaction::TransactionSchema,
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema,
+        transaction_by_participant::TransactionByParticipantSchema, write_set::WriteSetSchema,
     },
     transaction_info::TransactionInfoSchema,
 };
@@ -16,22 +19,41 @@ use anyhow::{ensure, format_err, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_types::{
     account_address::AccountAddress,
+    account_config::AccountResource,
     block_metadata::BlockMetadata,
+    contract_event::ContractEvent,
     proof::position::Position,
+    state_store::state_key::StateKey,
     transaction::{Transaction, Version},
-    write_set::WriteSet,
+    write_set::{WriteOp, WriteSet},
 };
+use move_core_types::move_resource::MoveStructType;
 use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
-use std::sync::Arc;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
 
 #[derive(Debug)]
 pub struct TransactionStore {
     db: Arc<DB>,
+    /// The tier the transactions of the old versions are moved to, if any.
+    cold_ledger: Option<Arc<ColdLedger>>,
 }
 
 impl TransactionStore {
     pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+        Self {
+            db,
+            cold_ledger: None,
+        }
+    }
+
+    pub fn new_with_cold_ledger(db: Arc<DB>, cold_ledger: Arc<ColdLedger>) -> Self {
+        Self {
+            db,
+            cold_ledger: Some(cold_ledger),
+        }
     }
 
     /// Gets the version of a transaction by the sender `address` and `sequence_number`.
@@ -94,10 +116,100 @@ impl TransactionStore {
         })
     }
 
+    /// Gets the version of the latest transaction sent by `address` with
+    /// `version <= ledger_version`.
+    pub fn get_account_last_transaction_version(
+        &self,
+        address: AccountAddress,
+        ledger_version: Version,
+    ) -> Result<Option<Version>> {
+        let mut iter = self
+            .db
+            .rev_iter::<TransactionByAccountSchema>(ReadOptions::default())?;
+        iter.seek_for_prev(&(address, u64::MAX))?;
+        // Only the transactions committed after `ledger_version` are skipped.
+        for res in iter {
+            let ((txn_address, _seq_num), version) = res?;
+            if txn_address != address {
+                break;
+            }
+            if version <= ledger_version {
+                return Ok(Some(version));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Gets the versions of at most `limit` transactions `address` participated in, see
+    /// `put_participants`, starting from `start_version`, with `version <= ledger_version`.
+    pub fn get_participated_transaction_versions(
+        &self,
+        address: AccountAddress,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<Version>> {
+        let mut iter = self
+            .db
+            .iter::<TransactionByParticipantSchema>(ReadOptions::default())?;
+        iter.seek(&(address, start_version))?;
+
+        let mut versions = vec![];
+        for res in iter.take(limit as usize) {
+            let ((participant, version), ()) = res?;
+            if participant != address || version > ledger_version {
+                break;
+            }
+            versions.push(version);
+        }
+
+        Ok(versions)
+    }
+
+    /// Gets the `(version, authentication_key)` pairs of the versions at which the
+    /// authentication key of `address` was set with `version <= ledger_version`, in the order of
+    /// version. The first one is set by the transaction creating the account, each of the others
+    /// is a rotation of the key.
+    pub fn get_authentication_key_history(
+        &self,
+        address: AccountAddress,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, Vec<u8>)>> {
+        let mut iter = self
+            .db
+            .iter::<AuthenticationKeyHistorySchema>(ReadOptions::default())?;
+        iter.seek(&(address, 0))?;
+
+        let mut history = vec![];
+        for res in iter {
+            let ((key_address, version), authentication_key) = res?;
+            if key_address != address || version > ledger_version {
+                break;
+            }
+            history.push((version, authentication_key));
+        }
+
+        Ok(history)
+    }
+
+    /// Gets the latest authentication key of `address` saved via `put_authentication_keys`.
+    fn get_latest_authentication_key(&self, address: AccountAddress) -> Result<Option<Vec<u8>>> {
+        let mut iter = self
+            .db
+            .rev_iter::<AuthenticationKeyHistorySchema>(ReadOptions::default())?;
+        iter.seek_for_prev(&(address, Version::MAX))?;
+        Ok(match iter.next().transpose()? {
+            Some(((key_address, _version), authentication_key)) if key_address == address => {
+                Some(authentication_key)
+            }
+            _ => None,
+        })
+    }
+
     /// Get signed transaction given `version`
     pub fn get_transaction(&self, version: Version) -> Result<Transaction> {
-        self.db
-            .get::<TransactionSchema>(&version)?
+        cold_ledger::get::<TransactionSchema>(&self.db, self.cold_ledger.as_deref(), &version)?
             .ok_or_else(|| AptosDbError::NotFound(format!("Txn {}", version)).into())
     }
 
@@ -107,10 +219,8 @@ impl TransactionStore {
         start_version: Version,
         num_transactions: usize,
     ) -> Result<TransactionIter> {
-        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
-        iter.seek(&start_version)?;
         Ok(TransactionIter {
-            inner: iter,
+            inner: TieredIter::new(&self.db, self.cold_ledger.as_deref(), start_version)?,
             expected_next_version: start_version,
             end_version: start_version
                 .checked_add(num_transactions as u64)
@@ -120,8 +230,11 @@ impl TransactionStore {
 
     /// Get the first version that txn starts existent.
     pub fn get_first_txn_version(&self) -> Result<Option<Version>> {
-        let mut iter = self.db.iter::<TransactionSchema>(Default::default())?;
-        iter.seek_to_first();
+        let mut iter = TieredIter::<TransactionSchema>::new(
+            &self.db,
+            self.cold_ledger.as_deref(),
+            0, /* start_version */
+        )?;
         iter.next().map(|res| res.map(|(v, _)| v)).transpose()
     }
 
@@ -135,15 +248,22 @@ impl TransactionStore {
 
         // Linear search via `DB::rev_iter()` here, NOT expecting performance hit, due to the fact
         // that the iterator caches data block and that there are limited number of transactions in
-        // each block.
-        let mut iter = self.db.rev_iter::<TransactionSchema>(Default::default())?;
-        iter.seek(&version)?;
-        for res in iter.take(MAX_VERSIONS_TO_SEARCH) {
-            let (v, txn) = res?;
-            if let Transaction::BlockMetadata(block_meta) = txn {
-                return Ok(Some((v, block_meta)));
-            } else if v == 0 {
-                return Ok(None);
+        // each block. The search goes on in the cold DB if it reaches the versions moved there.
+        let mut num_searched = 0;
+        for db in cold_ledger::ledger_dbs(&self.db, self.cold_ledger.as_deref()) {
+            let mut iter = db.rev_iter::<TransactionSchema>(Default::default())?;
+            iter.seek(&version)?;
+            for res in iter.take(MAX_VERSIONS_TO_SEARCH - num_searched) {
+                let (v, txn) = res?;
+                match txn {
+                    Transaction::BlockMetadata(block_meta) => return Ok(Some((v, block_meta))),
+                    Transaction::BlockMetadataExt(block_meta_ext) => {
+                        return Ok(Some((v, block_meta_ext.into_inner().0)))
+                    }
+                    _ if v == 0 => return Ok(None),
+                    _ => (),
+                }
+                num_searched += 1;
             }
         }
 
@@ -194,6 +314,65 @@ impl TransactionStore {
         cs.batch.put::<WriteSetSchema>(&version, write_set)
     }
 
+    /// Save the authentication keys of the accounts created or whose key is rotated at `version`,
+    /// according to the account resources in `write_set`. `latest_keys` holds the latest keys of
+    /// the accounts written by the earlier versions of the same batch, which are not readable from
+    /// the DB yet.
+    pub fn put_authentication_keys(
+        &self,
+        version: Version,
+        write_set: &WriteSet,
+        latest_keys: &mut HashMap<AccountAddress, Vec<u8>>,
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        let account_resource_path = AccountResource::struct_tag().access_vector();
+        for (state_key, write_op) in write_set {
+            let (address, bytes) = match (state_key, write_op) {
+                (StateKey::AccessPath(access_path), WriteOp::Value(bytes))
+                    if access_path.path == account_resource_path =>
+                {
+                    (access_path.address, bytes)
+                }
+                _ => continue,
+            };
+            let authentication_key = bcs::from_bytes::<AccountResource>(bytes)?
+                .authentication_key()
+                .to_vec();
+
+            if !latest_keys.contains_key(&address) {
+                if let Some(latest_key) = self.get_latest_authentication_key(address)? {
+                    latest_keys.insert(address, latest_key);
+                }
+            }
+            if latest_keys.get(&address) != Some(&authentication_key) {
+                cs.batch.put::<AuthenticationKeyHistorySchema>(
+                    &(address, version),
+                    &authentication_key,
+                )?;
+                latest_keys.insert(address, authentication_key);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Save the index of the accounts participating in the transaction at `version`: the sender
+    /// and the secondary signers of a user transaction, and the accounts whose event streams
+    /// `events` are emitted to.
+    pub fn put_participants(
+        &self,
+        version: Version,
+        transaction: &Transaction,
+        events: &[ContractEvent],
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        for address in participants(transaction, events) {
+            cs.batch
+                .put::<TransactionByParticipantSchema>(&(address, version), &())?;
+        }
+        Ok(())
+    }
+
     /// Prune the transaction by hash store given a list of transaction
     pub fn prune_transaction_by_hash(
         &self,
@@ -221,6 +400,22 @@ impl TransactionStore {
         Ok(())
     }
 
+    /// Prune the transaction by participant store given a list of `(version, transaction,
+    /// events)`. The events of a version already pruned by the event store pruner aren't known,
+    /// so the accounts participating only through them are left in the index.
+    pub fn prune_transaction_by_participant(
+        &self,
+        transactions: &[(Version, Transaction, Vec<ContractEvent>)],
+        db_batch: &mut SchemaBatch,
+    ) -> anyhow::Result<()> {
+        for (version, transaction, events) in transactions {
+            for address in participants(transaction, events) {
+                db_batch.delete::<TransactionByParticipantSchema>(&(address, *version))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Prune the transaction schema store between a range of version in [begin, end)
     pub fn prune_transaction_schema(
         &self,
@@ -266,8 +461,20 @@ impl TransactionStore {
     }
 }
 
+fn participants(transaction: &Transaction, events: &[ContractEvent]) -> BTreeSet<AccountAddress> {
+    let mut participants: BTreeSet<_> = events
+        .iter()
+        .map(|event| event.key().get_creator_address())
+        .collect();
+    if let Transaction::UserTransaction(txn) = transaction {
+        participants.insert(txn.sender());
+        participants.extend(txn.authenticator().secondary_signer_addreses());
+    }
+    participants
+}
+
 pub struct TransactionIter<'a> {
-    inner: SchemaIterator<'a, TransactionSchema>,
+    inner: TieredIter<'a, TransactionSchema>,
     expected_next_version: Version,
     end_version: Version,
 }
@@ -6,10 +6,14 @@ use crate::AptosDB;
 use aptos_proptest_helpers::Index;
 use aptos_temppath::TempPath;
 use aptos_types::{
+    access_path::AccessPath,
     block_metadata::BlockMetadata,
+    event::EventKey,
     proptest_types::{AccountInfoUniverse, SignatureCheckedTransactionGen},
     transaction::{SignedTransaction, Transaction},
+    write_set::WriteSetMut,
 };
+use move_core_types::language_storage::TypeTag;
 use proptest::{collection::vec, prelude::*};
 use std::collections::BTreeMap;
 
@@ -246,9 +250,146 @@ proptest! {
             .collect::<BTreeMap<_, _>>();
 
         prop_assert_eq!(&actual_scan, &expected_scan);
+
+        // the latest transaction of each account
+        for address in expected_scan.keys() {
+            let expected_version = txns
+                .iter()
+                .filter(|(version, txn)| txn.sender() == *address && *version <= ledger_version)
+                .map(|(version, _)| *version)
+                .max();
+            prop_assert_eq!(
+                store
+                    .get_account_last_transaction_version(*address, ledger_version)
+                    .unwrap(),
+                expected_version
+            );
+        }
     }
 }
 
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_put_get_prune_participants(
+        txns in vec(any::<SignedTransaction>(), 1..10),
+        recipient in any::<AccountAddress>(),
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.transaction_store;
+        let num_txns = txns.len() as Version;
+        let event = ContractEvent::new(
+            EventKey::new_from_address(&recipient, 0),
+            0,
+            TypeTag::Bool,
+            vec![],
+        );
+        let participations = txns
+            .into_iter()
+            .enumerate()
+            .map(|(ver, txn)| {
+                (ver as Version, Transaction::UserTransaction(txn), vec![event.clone()])
+            })
+            .collect::<Vec<_>>();
+
+        let mut cs = ChangeSet::new();
+        for (ver, txn, events) in &participations {
+            store.put_participants(*ver, txn, events, &mut cs).unwrap();
+        }
+        store.db.write_schemas(cs.batch).unwrap();
+
+        for (ver, txn, _) in &participations {
+            let sender = match txn {
+                Transaction::UserTransaction(txn) => txn.sender(),
+                _ => unreachable!(),
+            };
+            let versions = store
+                .get_participated_transaction_versions(sender, *ver, 1, num_txns - 1)
+                .unwrap();
+            prop_assert_eq!(versions, vec![*ver]);
+        }
+        prop_assert_eq!(
+            store
+                .get_participated_transaction_versions(recipient, 0, 100, num_txns - 1)
+                .unwrap(),
+            (0..num_txns).collect::<Vec<_>>()
+        );
+        // versions after the ledger version are ignored
+        prop_assert_eq!(
+            store
+                .get_participated_transaction_versions(recipient, 0, 100, 0)
+                .unwrap(),
+            vec![0]
+        );
+
+        let mut db_batch = SchemaBatch::new();
+        store
+            .prune_transaction_by_participant(&participations, &mut db_batch)
+            .unwrap();
+        store.db.write_schemas(db_batch).unwrap();
+        prop_assert!(store
+            .get_participated_transaction_versions(recipient, 0, 100, num_txns - 1)
+            .unwrap()
+            .is_empty());
+    }
+}
+
+#[test]
+fn test_put_get_authentication_key_history() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.transaction_store;
+    let address = AccountAddress::random();
+    let account_write_set = |sequence_number: u64, authentication_key: &[u8]| {
+        let resource = AccountResource::new(sequence_number, authentication_key.to_vec(), address);
+        let access_path = AccessPath::new(address, AccountResource::struct_tag().access_vector());
+        WriteSetMut::new(vec![(
+            StateKey::AccessPath(access_path),
+            WriteOp::Value(bcs::to_bytes(&resource).unwrap()),
+        )])
+        .freeze()
+        .unwrap()
+    };
+
+    // creates the account and bumps its sequence number in the same batch
+    let mut cs = ChangeSet::new();
+    let mut latest_keys = HashMap::new();
+    for (version, write_set) in [account_write_set(0, b"a"), account_write_set(1, b"a")]
+        .iter()
+        .enumerate()
+    {
+        store
+            .put_authentication_keys(version as Version, write_set, &mut latest_keys, &mut cs)
+            .unwrap();
+    }
+    store.db.write_schemas(cs.batch).unwrap();
+
+    // rotates the key in another batch
+    let mut cs = ChangeSet::new();
+    store
+        .put_authentication_keys(2, &account_write_set(2, b"b"), &mut HashMap::new(), &mut cs)
+        .unwrap();
+    store
+        .put_authentication_keys(3, &WriteSet::default(), &mut HashMap::new(), &mut cs)
+        .unwrap();
+    store.db.write_schemas(cs.batch).unwrap();
+
+    assert_eq!(
+        store.get_authentication_key_history(address, 3).unwrap(),
+        vec![(0, b"a".to_vec()), (2, b"b".to_vec())]
+    );
+    assert_eq!(
+        store.get_authentication_key_history(address, 1).unwrap(),
+        vec![(0, b"a".to_vec())]
+    );
+    assert!(store
+        .get_authentication_key_history(AccountAddress::random(), 3)
+        .unwrap()
+        .is_empty());
+}
+
 fn init_store(
     mut universe: AccountInfoUniverse,
     gens: Vec<(Index, SignatureCheckedTransactionGen)>,
@@ -0,0 +1,243 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
+        incremental::manifest::{IncrementalBackup, INCREMENTAL_BACKUP_FORMAT_VERSION},
+        state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        transaction::backup::{TransactionBackupController, TransactionBackupOpt},
+    },
+    metadata,
+    metadata::{cache::MetadataCacheOpt, Metadata},
+    storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
+    utils::{
+        backup_service_client::BackupServiceClient, storage_ext::BackupStorageExt,
+        ConcurrentDownloadsOpt, GlobalBackupOpt,
+    },
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_logger::prelude::*;
+use aptos_types::transaction::Version;
+use once_cell::sync::Lazy;
+use std::{str::FromStr, sync::Arc};
+use structopt::StructOpt;
+use tokio::io::AsyncWriteExt;
+
+#[derive(StructOpt)]
+pub struct IncrementalBackupOpt {
+    #[structopt(flatten)]
+    pub metadata_cache_opt: MetadataCacheOpt,
+
+    #[structopt(
+        long = "target-version",
+        help = "[Defaults to the latest committed version] Last transaction to backup."
+    )]
+    pub target_version: Option<Version>,
+
+    // Like for the backup coordinator, a state snapshot every 10 Mil versions keeps the
+    // transactions replayed by a restore under two hours.
+    #[structopt(
+        long,
+        default_value = "10000000",
+        help = "A state snapshot is taken at the last version of the backup when the latest one \
+        in the storage is at least this many versions behind, or there is none."
+    )]
+    pub state_snapshot_interval: usize,
+
+    #[structopt(flatten)]
+    pub concurrent_downloads: ConcurrentDownloadsOpt,
+}
+
+/// Backs up the transactions, and the epoch endings, committed since the last backup in the
+/// storage, and a state snapshot every `state_snapshot_interval` versions, and chains the backup
+/// to the last incremental backup.
+pub struct IncrementalBackupController {
+    metadata_cache_opt: MetadataCacheOpt,
+    target_version: Option<Version>,
+    state_snapshot_interval: usize,
+    concurrent_downloads: usize,
+    global_opt: GlobalBackupOpt,
+    client: Arc<BackupServiceClient>,
+    storage: Arc<dyn BackupStorage>,
+}
+
+impl IncrementalBackupController {
+    pub fn new(
+        opt: IncrementalBackupOpt,
+        global_opt: GlobalBackupOpt,
+        client: Arc<BackupServiceClient>,
+        storage: Arc<dyn BackupStorage>,
+    ) -> Self {
+        Self {
+            metadata_cache_opt: opt.metadata_cache_opt,
+            target_version: opt.target_version,
+            state_snapshot_interval: opt.state_snapshot_interval,
+            concurrent_downloads: opt.concurrent_downloads.get(),
+            global_opt,
+            client,
+            storage,
+        }
+    }
+
+    /// Returns the manifest of the new link of the chain, or `None` if there are no new
+    /// transactions to backup.
+    pub async fn run(self) -> Result<Option<FileHandle>> {
+        info!("Incremental backup started.");
+        let ret = self
+            .run_impl()
+            .await
+            .map_err(|e| anyhow!("Incremental backup failed: {}", e))?;
+        match &ret {
+            Some(manifest) => info!("Incremental backup succeeded. Manifest: {}", manifest),
+            None => info!("Incremental backup skipped, no new transactions to backup."),
+        }
+        Ok(ret)
+    }
+}
+
+impl IncrementalBackupController {
+    async fn run_impl(self) -> Result<Option<FileHandle>> {
+        let metadata_view = metadata::cache::sync_and_load(
+            &self.metadata_cache_opt,
+            Arc::clone(&self.storage),
+            self.concurrent_downloads,
+        )
+        .await?;
+        let storage_state = metadata_view.get_storage_state();
+        let db_state = self
+            .client
+            .get_db_state()
+            .await?
+            .ok_or_else(|| anyhow!("DB not bootstrapped."))?;
+
+        let first_version = storage_state
+            .latest_transaction_version
+            .map_or(0, |version| version + 1);
+        let last_version = self
+            .target_version
+            .map_or(db_state.committed_version, |version| {
+                std::cmp::min(version, db_state.committed_version)
+            });
+        if first_version > last_version {
+            return Ok(None);
+        }
+
+        let previous = metadata_view.select_incremental_backups().pop();
+        if let Some(previous) = &previous {
+            ensure!(
+                previous.last_version + 1 == first_version,
+                "Transactions after version {} were backed up outside of the incremental backups, \
+                 can't chain to manifest {}.",
+                previous.last_version,
+                previous.manifest,
+            );
+        }
+
+        // Epoch endings are backed up till the current epoch of the node, which covers the ledger
+        // infos the transaction backup proves against.
+        let start_epoch = storage_state
+            .latest_epoch_ending_epoch
+            .map_or(0, |epoch| epoch + 1);
+        let epoch_endings = if start_epoch < db_state.epoch {
+            Some(
+                EpochEndingBackupController::new(
+                    EpochEndingBackupOpt {
+                        start_epoch,
+                        end_epoch: db_state.epoch,
+                    },
+                    self.global_opt.clone(),
+                    Arc::clone(&self.client),
+                    Arc::clone(&self.storage),
+                )
+                .run()
+                .await?,
+            )
+        } else {
+            None
+        };
+
+        let transactions = TransactionBackupController::new(
+            TransactionBackupOpt {
+                start_version: first_version,
+                num_transactions: (last_version - first_version + 1) as usize,
+            },
+            self.global_opt.clone(),
+            Arc::clone(&self.client),
+            Arc::clone(&self.storage),
+        )
+        .without_metadata()
+        .run()
+        .await?;
+
+        let state_snapshot = match storage_state.latest_state_snapshot_version {
+            Some(version) if last_version < version + self.state_snapshot_interval as Version => {
+                None
+            }
+            _ => Some(
+                StateSnapshotBackupController::new(
+                    StateSnapshotBackupOpt {
+                        version: last_version,
+                    },
+                    self.global_opt.clone(),
+                    Arc::clone(&self.client),
+                    Arc::clone(&self.storage),
+                )
+                .run()
+                .await?,
+            ),
+        };
+
+        let manifest = IncrementalBackup {
+            format_version: INCREMENTAL_BACKUP_FORMAT_VERSION,
+            previous: previous.map(|previous| previous.manifest),
+            first_version,
+            last_version,
+            transactions,
+            epoch_endings,
+            state_snapshot,
+        };
+        let backup_handle = self
+            .storage
+            .create_backup_with_random_suffix(&format!("incremental_{}-", first_version))
+            .await?;
+        self.write_manifest(&backup_handle, manifest)
+            .await
+            .map(Some)
+    }
+
+    fn manifest_name() -> &'static ShellSafeName {
+        static NAME: Lazy<ShellSafeName> =
+            Lazy::new(|| ShellSafeName::from_str("incremental.manifest").unwrap());
+        &NAME
+    }
+
+    async fn write_manifest(
+        &self,
+        backup_handle: &BackupHandleRef,
+        manifest: IncrementalBackup,
+    ) -> Result<FileHandle> {
+        let (manifest_handle, mut manifest_file) = self
+            .storage
+            .create_for_write(backup_handle, Self::manifest_name())
+            .await?;
+        manifest_file
+            .write_all(&serde_json::to_vec(&manifest)?)
+            .await?;
+        manifest_file.shutdown().await?;
+
+        // The link is recorded by the metadata of its transactions, written last so that the
+        // link isn't seen before its manifest exists.
+        let metadata = Metadata::new_incremental_transaction_backup(
+            manifest.first_version,
+            manifest.last_version,
+            manifest.transactions.clone(),
+            manifest_handle.clone(),
+        );
+        self.storage
+            .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
+            .await?;
+
+        Ok(manifest_handle)
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::FileHandle;
+use anyhow::{ensure, Result};
+use aptos_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+
+/// The version of the format of the `IncrementalBackup` manifests written by this binary, bumped
+/// on incompatible changes so that a reader refuses the manifests it doesn't understand.
+pub const INCREMENTAL_BACKUP_FORMAT_VERSION: u64 = 1;
+
+/// Incremental backup manifest, a link of a chain of backups each carrying the transactions
+/// in the [`first_version`, `last_version`] range (right side inclusive) that follows the range
+/// of the previous link.
+#[derive(Deserialize, Serialize)]
+pub struct IncrementalBackup {
+    /// See `INCREMENTAL_BACKUP_FORMAT_VERSION`.
+    pub format_version: u64,
+    /// Manifest of the previous link, `None` for the first link of the chain.
+    pub previous: Option<FileHandle>,
+    pub first_version: Version,
+    pub last_version: Version,
+    /// Manifest of the `TransactionBackup` of the range.
+    pub transactions: FileHandle,
+    /// Manifest of the `EpochEndingBackup` of the epochs ended since the previous link, if any,
+    /// so that the transactions can be verified with the backups in the chain.
+    pub epoch_endings: Option<FileHandle>,
+    /// Manifest of the `StateSnapshotBackup` at `last_version`, if the link takes one: the
+    /// state is restored from the latest snapshot, and the transactions after it are replayed.
+    pub state_snapshot: Option<FileHandle>,
+}
+
+impl IncrementalBackup {
+    pub fn verify(&self) -> Result<()> {
+        ensure!(
+            self.format_version == INCREMENTAL_BACKUP_FORMAT_VERSION,
+            "Unsupported incremental backup format version {}, expecting {}.",
+            self.format_version,
+            INCREMENTAL_BACKUP_FORMAT_VERSION,
+        );
+        ensure!(
+            self.first_version <= self.last_version,
+            "Bad version range: [{}, {}]",
+            self.first_version,
+            self.last_version,
+        );
+        Ok(())
+    }
+
+    /// Checks that this link directly follows `previous`.
+    pub fn verify_follows(&self, previous: &IncrementalBackup) -> Result<()> {
+        ensure!(
+            previous.last_version + 1 == self.first_version,
+            "Link not continuous. Previous link ends at version {}, this one starts at {}.",
+            previous.last_version,
+            self.first_version,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod backup;
+pub mod manifest;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,191 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        incremental::{
+            backup::{IncrementalBackupController, IncrementalBackupOpt},
+            manifest::{IncrementalBackup, INCREMENTAL_BACKUP_FORMAT_VERSION},
+        },
+        transaction::backup::{TransactionBackupController, TransactionBackupOpt},
+    },
+    coordinators::verify_chain::VerifyChainCoordinator,
+    metadata,
+    metadata::cache::MetadataCacheOpt,
+    storage::{local_fs::LocalFs, BackupStorage},
+    utils::{
+        backup_service_client::BackupServiceClient, storage_ext::BackupStorageExt,
+        test_utils::start_local_backup_service, ConcurrentDownloadsOpt, GlobalBackupOpt,
+        TrustedWaypointOpt,
+    },
+};
+use aptos_temppath::TempPath;
+use aptos_types::transaction::Version;
+use executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
+use std::sync::Arc;
+use storage_interface::DbReader;
+use tokio::{runtime::Runtime, time::Duration};
+
+fn incremental_backup(
+    rt: &Runtime,
+    client: &Arc<BackupServiceClient>,
+    store: &Arc<dyn BackupStorage>,
+    metadata_cache_dir: &TempPath,
+    target_version: Option<Version>,
+) -> anyhow::Result<Option<String>> {
+    rt.block_on(
+        IncrementalBackupController::new(
+            IncrementalBackupOpt {
+                metadata_cache_opt: MetadataCacheOpt::new(Some(
+                    metadata_cache_dir.path().to_path_buf(),
+                )),
+                target_version,
+                state_snapshot_interval: 10_000_000,
+                concurrent_downloads: ConcurrentDownloadsOpt::default(),
+            },
+            GlobalBackupOpt {
+                max_chunk_size: 2048,
+            },
+            Arc::clone(client),
+            Arc::clone(store),
+        )
+        .run(),
+    )
+}
+
+fn verify_chain(
+    rt: &Runtime,
+    store: &Arc<dyn BackupStorage>,
+    metadata_cache_dir: &TempPath,
+) -> anyhow::Result<()> {
+    rt.block_on(
+        VerifyChainCoordinator::new(
+            Arc::clone(store),
+            MetadataCacheOpt::new(Some(metadata_cache_dir.path().to_path_buf())),
+            TrustedWaypointOpt::default(),
+            ConcurrentDownloadsOpt::default().get(),
+        )
+        .unwrap()
+        .run(),
+    )
+}
+
+#[test]
+fn test_incremental_backup_chain() {
+    let db = test_execution_with_storage_impl();
+    let latest_version = db.get_latest_version().unwrap();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let metadata_cache_dir = TempPath::new();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+    let (rt, port) = start_local_backup_service(db);
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+
+    let first = incremental_backup(
+        &rt,
+        &client,
+        &store,
+        &metadata_cache_dir,
+        Some(latest_version / 2),
+    )
+    .unwrap()
+    .unwrap();
+    let second = incremental_backup(&rt, &client, &store, &metadata_cache_dir, None)
+        .unwrap()
+        .unwrap();
+    // nothing new to backup
+    assert!(
+        incremental_backup(&rt, &client, &store, &metadata_cache_dir, None)
+            .unwrap()
+            .is_none()
+    );
+
+    let first_link: IncrementalBackup = rt.block_on(store.load_json_file(&first)).unwrap();
+    assert_eq!(first_link.previous, None);
+    assert_eq!(
+        (first_link.first_version, first_link.last_version),
+        (0, latest_version / 2)
+    );
+    assert_eq!(first_link.format_version, INCREMENTAL_BACKUP_FORMAT_VERSION);
+    assert!(first_link.epoch_endings.is_some());
+    // the storage has no state snapshot before the first link
+    assert!(first_link.state_snapshot.is_some());
+    let second_link: IncrementalBackup = rt.block_on(store.load_json_file(&second)).unwrap();
+    assert_eq!(second_link.previous, Some(first));
+    assert_eq!(
+        (second_link.first_version, second_link.last_version),
+        (latest_version / 2 + 1, latest_version)
+    );
+    assert!(second_link.state_snapshot.is_none());
+
+    // the links are recorded as plain transaction backups, which readers unaware of the chain
+    // restore as usual
+    let metadata_view = rt
+        .block_on(metadata::cache::sync_and_load(
+            &MetadataCacheOpt::new(Some(metadata_cache_dir.path().to_path_buf())),
+            Arc::clone(&store),
+            ConcurrentDownloadsOpt::default().get(),
+        ))
+        .unwrap();
+    let transaction_backups = metadata_view
+        .select_transaction_backups(0, latest_version)
+        .unwrap();
+    assert_eq!(transaction_backups.len(), 2);
+    assert_eq!(
+        metadata_view
+            .get_storage_state()
+            .latest_state_snapshot_version,
+        Some(latest_version / 2)
+    );
+
+    verify_chain(&rt, &store, &metadata_cache_dir).unwrap();
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn test_incremental_backup_refuses_broken_chain() {
+    let db = test_execution_with_storage_impl();
+    let latest_version = db.get_latest_version().unwrap();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let metadata_cache_dir = TempPath::new();
+    let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+    let (rt, port) = start_local_backup_service(db);
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+
+    incremental_backup(
+        &rt,
+        &client,
+        &store,
+        &metadata_cache_dir,
+        Some(latest_version / 2),
+    )
+    .unwrap()
+    .unwrap();
+    // transactions backed up outside of the chain
+    rt.block_on(
+        TransactionBackupController::new(
+            TransactionBackupOpt {
+                start_version: latest_version / 2 + 1,
+                num_transactions: 1,
+            },
+            GlobalBackupOpt {
+                max_chunk_size: 2048,
+            },
+            Arc::clone(&client),
+            Arc::clone(&store),
+        )
+        .run(),
+    )
+    .unwrap();
+    assert!(incremental_backup(&rt, &client, &store, &metadata_cache_dir, None).is_err());
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
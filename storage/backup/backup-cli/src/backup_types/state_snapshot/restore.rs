@@ -3,7 +3,8 @@
 
 use crate::{
     backup_types::{
-        epoch_ending::restore::EpochHistory, state_snapshot::manifest::StateSnapshotBackup,
+        epoch_ending::restore::EpochHistory,
+        state_snapshot::manifest::{StateSnapshotBackup, StateSnapshotChunk},
     },
     metrics::{
         restore::{
@@ -16,17 +17,20 @@ use crate::{
     },
     storage::{BackupStorage, FileHandle},
     utils::{
-        read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt, GlobalRestoreOptions,
-        RestoreRunMode,
+        error_notes::ErrorNotes, read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt,
+        stream::StreamX, GlobalRestoreOptions, RestoreRunMode,
     },
 };
 use anyhow::{anyhow, ensure, Result};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_types::{
-    ledger_info::LedgerInfoWithSignatures, proof::TransactionInfoWithProof,
-    state_store::state_value::StateKeyAndValue, transaction::Version,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{SparseMerkleRangeProof, TransactionInfoWithProof},
+    state_store::state_value::StateKeyAndValue,
+    transaction::Version,
 };
+use futures::{stream, TryStreamExt};
 use std::sync::Arc;
 use storage_interface::StateSnapshotReceiver;
 use structopt::StructOpt;
@@ -49,6 +53,8 @@ pub struct StateSnapshotRestoreController {
     /// nothing will be done, otherwise, this has no effect.
     target_version: Version,
     epoch_history: Option<Arc<EpochHistory>>,
+    /// Number of chunks loaded from the storage at the same time.
+    concurrent_downloads: usize,
 }
 
 impl StateSnapshotRestoreController {
@@ -65,6 +71,7 @@ impl StateSnapshotRestoreController {
             manifest_handle: opt.manifest_handle,
             target_version: global_opt.target_version,
             epoch_history,
+            concurrent_downloads: global_opt.concurrent_downloads,
         }
     }
 
@@ -130,23 +137,76 @@ impl StateSnapshotRestoreController {
         // FIXME update counters
         ver_gauge.set(self.version as i64);
         tgt_leaf_idx.set(manifest.chunks.last().map_or(0, |c| c.last_idx as i64));
-        for chunk in manifest.chunks {
-            let blobs = self.read_state_value(chunk.blobs).await?;
-            let proof = self.storage.load_bcs_file(&chunk.proof).await?;
-
-            receiver.add_chunk(blobs, proof)?;
+        // Chunks are loaded and deserialized in parallel while the loaded ones are added to the
+        // tree in order, which is what the restore requires. At most `2 * concurrent_downloads`
+        // chunks are held in memory at once.
+        let con = self.concurrent_downloads;
+        let storage = self.storage.clone();
+        let mut loaded_chunk_stream = stream::iter(manifest.chunks.into_iter().map(move |chunk| {
+            let storage = storage.clone();
+            async move {
+                tokio::task::spawn(async move { LoadedChunk::load(chunk, &storage).await }).await?
+            }
+        }))
+        .buffered_x(con * 2 /* buffer size */, con /* concurrency */);
+
+        while let Some(LoadedChunk {
+            manifest: chunk,
+            blobs,
+            proof,
+        }) = loaded_chunk_stream.try_next().await?
+        {
+            // Verifying the chunk against the partial tree and writing the frozen nodes block
+            // the thread, so the receiver is moved to a blocking task and back.
+            receiver = tokio::task::spawn_blocking(move || {
+                receiver.add_chunk(blobs, proof)?;
+                Result::<_>::Ok(receiver)
+            })
+            .await??;
             leaf_idx.set(chunk.last_idx as i64);
         }
 
-        receiver.finish()?;
+        tokio::task::spawn_blocking(move || receiver.finish()).await??;
         Ok(())
     }
+}
+
+struct LoadedChunk {
+    manifest: StateSnapshotChunk,
+    blobs: Vec<(HashValue, StateKeyAndValue)>,
+    proof: SparseMerkleRangeProof,
+}
+
+impl LoadedChunk {
+    async fn load(manifest: StateSnapshotChunk, storage: &Arc<dyn BackupStorage>) -> Result<Self> {
+        let blobs = Self::read_state_value(storage, &manifest.blobs)
+            .await
+            .err_notes(&manifest.blobs)?;
+        ensure!(
+            blobs.len() == manifest.last_idx - manifest.first_idx + 1,
+            "Number of items in chunk doesn't match that in manifest. first_idx: {}, last_idx: {}, \
+             items in chunk: {}",
+            manifest.first_idx,
+            manifest.last_idx,
+            blobs.len(),
+        );
+        let proof = storage
+            .load_bcs_file(&manifest.proof)
+            .await
+            .err_notes(&manifest.proof)?;
+
+        Ok(Self {
+            manifest,
+            blobs,
+            proof,
+        })
+    }
 
     async fn read_state_value(
-        &self,
-        file_handle: FileHandle,
+        storage: &Arc<dyn BackupStorage>,
+        file_handle: &FileHandle,
     ) -> Result<Vec<(HashValue, StateKeyAndValue)>> {
-        let mut file = self.storage.open_for_read(&file_handle).await?;
+        let mut file = storage.open_for_read(file_handle).await?;
 
         let mut chunk = vec![];
 
@@ -33,6 +33,7 @@ pub struct TransactionBackupController {
     max_chunk_size: usize,
     client: Arc<BackupServiceClient>,
     storage: Arc<dyn BackupStorage>,
+    save_metadata: bool,
 }
 
 impl TransactionBackupController {
@@ -48,9 +49,17 @@ impl TransactionBackupController {
             max_chunk_size: global_opt.max_chunk_size,
             client,
             storage,
+            save_metadata: true,
         }
     }
 
+    /// Doesn't record the backup in the metadata, the caller records it once the backup is
+    /// part of a larger one, e.g. a link of the incremental backup chain.
+    pub(crate) fn without_metadata(mut self) -> Self {
+        self.save_metadata = false;
+        self
+    }
+
     pub async fn run(self) -> Result<FileHandle> {
         info!(
             "Transaction backup started, starting from version {}, for {} transactions in total.",
@@ -201,11 +210,16 @@ impl TransactionBackupController {
             .await?;
         manifest_file.shutdown().await?;
 
-        let metadata =
-            Metadata::new_transaction_backup(first_version, last_version, manifest_handle.clone());
-        self.storage
-            .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
-            .await?;
+        if self.save_metadata {
+            let metadata = Metadata::new_transaction_backup(
+                first_version,
+                last_version,
+                manifest_handle.clone(),
+            );
+            self.storage
+                .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
+                .await?;
+        }
 
         Ok(manifest_handle)
     }
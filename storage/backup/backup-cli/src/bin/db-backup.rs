@@ -11,15 +11,19 @@ use aptos_secure_push_metrics::MetricsPusher;
 use backup_cli::{
     backup_types::{
         epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
+        incremental::backup::{IncrementalBackupController, IncrementalBackupOpt},
         state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
         transaction::backup::{TransactionBackupController, TransactionBackupOpt},
     },
-    coordinators::backup::{BackupCoordinator, BackupCoordinatorOpt},
+    coordinators::{
+        backup::{BackupCoordinator, BackupCoordinatorOpt},
+        verify_chain::VerifyChainCoordinator,
+    },
     metadata::{cache, cache::MetadataCacheOpt},
     storage::StorageOpt,
     utils::{
         backup_service_client::{BackupServiceClient, BackupServiceClientOpt},
-        ConcurrentDownloadsOpt, GlobalBackupOpt,
+        ConcurrentDownloadsOpt, GlobalBackupOpt, TrustedWaypointOpt,
     },
 };
 
@@ -30,6 +34,11 @@ enum Command {
     OneShot(OneShotCommand),
     #[structopt(about = "Long running process backing up the chain continuously.")]
     Coordinator(CoordinatorCommand),
+    #[structopt(
+        about = "Verify the chain of incremental backups in the storage, from the latest one back \
+        to the first one."
+    )]
+    VerifyChain(VerifyChainOpt),
 }
 
 #[derive(StructOpt)]
@@ -101,6 +110,16 @@ enum BackupType {
         #[structopt(subcommand)]
         storage: StorageOpt,
     },
+    #[structopt(
+        about = "Backup the transactions and epoch endings committed since the last backup in \
+        the storage, chained to the last incremental backup."
+    )]
+    Incremental {
+        #[structopt(flatten)]
+        opt: IncrementalBackupOpt,
+        #[structopt(subcommand)]
+        storage: StorageOpt,
+    },
 }
 
 #[derive(StructOpt)]
@@ -124,6 +143,18 @@ struct CoordinatorRunOpt {
     storage: StorageOpt,
 }
 
+#[derive(StructOpt)]
+struct VerifyChainOpt {
+    #[structopt(flatten)]
+    metadata_cache_opt: MetadataCacheOpt,
+    #[structopt(flatten)]
+    trusted_waypoints_opt: TrustedWaypointOpt,
+    #[structopt(flatten)]
+    concurrent_downloads: ConcurrentDownloadsOpt,
+    #[structopt(subcommand)]
+    storage: StorageOpt,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     main_impl().await.map_err(|e| {
@@ -193,6 +224,16 @@ async fn main_impl() -> Result<()> {
                         .run()
                         .await?;
                     }
+                    BackupType::Incremental { opt, storage } => {
+                        IncrementalBackupController::new(
+                            opt,
+                            global_opt,
+                            client,
+                            storage.init_storage().await?,
+                        )
+                        .run()
+                        .await?;
+                    }
                 }
             }
         },
@@ -208,6 +249,16 @@ async fn main_impl() -> Result<()> {
                 .await?;
             }
         },
+        Command::VerifyChain(opt) => {
+            VerifyChainCoordinator::new(
+                opt.storage.init_storage().await?,
+                opt.metadata_cache_opt,
+                opt.trusted_waypoints_opt,
+                opt.concurrent_downloads.get(),
+            )?
+            .run()
+            .await?;
+        }
     }
     Ok(())
 }
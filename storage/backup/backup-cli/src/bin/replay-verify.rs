@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
-use aptos_config::config::NO_OP_STORAGE_PRUNER_CONFIG;
+use aptos_config::config::{DEFAULT_STATE_CACHE_SIZE, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_logger::{prelude::*, Level, Logger};
 use aptos_types::transaction::Version;
 use aptosdb::{AptosDB, GetRestoreHandler};
@@ -59,6 +59,8 @@ async fn main_impl() -> Result<()> {
         false,                       /* read_only */
         NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
         opt.rocksdb_opt.into(),
+        DEFAULT_STATE_CACHE_SIZE,
+        false, /* enable_event_type_index */
     )?)
     .get_restore_handler();
     ReplayVerifyCoordinator::new(
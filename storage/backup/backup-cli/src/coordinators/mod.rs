@@ -5,3 +5,4 @@ pub mod backup;
 pub mod replay_verify;
 pub mod restore;
 pub mod verify;
+pub mod verify_chain;
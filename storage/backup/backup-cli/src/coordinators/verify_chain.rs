@@ -0,0 +1,181 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::restore::EpochHistoryRestoreController,
+        incremental::manifest::IncrementalBackup,
+        state_snapshot::manifest::StateSnapshotBackup,
+        transaction::{manifest::TransactionBackup, restore::TransactionRestoreBatchController},
+    },
+    metadata,
+    metadata::cache::MetadataCacheOpt,
+    storage::{BackupStorage, FileHandle},
+    utils::{
+        storage_ext::BackupStorageExt, GlobalRestoreOptions, RestoreRunMode, TrustedWaypointOpt,
+    },
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_logger::prelude::*;
+use aptos_types::transaction::Version;
+use std::{collections::HashMap, sync::Arc};
+
+/// Verifies the chain of incremental backups: that the links, from the latest one back to the
+/// first one, cover a continuous range of versions, that every incremental backup in the
+/// storage is part of the chain, and that the transactions in the chain are properly signed.
+pub struct VerifyChainCoordinator {
+    storage: Arc<dyn BackupStorage>,
+    metadata_cache_opt: MetadataCacheOpt,
+    trusted_waypoints_opt: TrustedWaypointOpt,
+    concurrent_downloads: usize,
+}
+
+impl VerifyChainCoordinator {
+    pub fn new(
+        storage: Arc<dyn BackupStorage>,
+        metadata_cache_opt: MetadataCacheOpt,
+        trusted_waypoints_opt: TrustedWaypointOpt,
+        concurrent_downloads: usize,
+    ) -> Result<Self> {
+        Ok(Self {
+            storage,
+            metadata_cache_opt,
+            trusted_waypoints_opt,
+            concurrent_downloads,
+        })
+    }
+
+    pub async fn run(self) -> Result<()> {
+        info!("Verify chain coordinator started.");
+
+        let ret = self.run_impl().await;
+
+        if let Err(e) = &ret {
+            error!(
+                error = ?e,
+                "Verify chain coordinator failed."
+            );
+        } else {
+            info!("Verify chain coordinator exiting with success.");
+        }
+
+        ret
+    }
+
+    async fn run_impl(self) -> Result<()> {
+        let metadata_view = metadata::cache::sync_and_load(
+            &self.metadata_cache_opt,
+            Arc::clone(&self.storage),
+            self.concurrent_downloads,
+        )
+        .await?;
+        let links = metadata_view.select_incremental_backups();
+        let tip = links
+            .last()
+            .ok_or_else(|| anyhow!("No incremental backup found."))?;
+
+        // Walk the chain from the tip back to the first link.
+        let mut manifests = HashMap::new();
+        let mut chain: Vec<IncrementalBackup> = Vec::new();
+        let mut next_handle = Some(tip.manifest.clone());
+        while let Some(handle) = next_handle {
+            let link: IncrementalBackup = self.storage.load_json_file(&handle).await?;
+            link.verify()?;
+            if let Some(later) = chain.last() {
+                later.verify_follows(&link)?;
+            }
+            self.verify_transactions_manifest(&link).await?;
+            self.verify_state_snapshot_manifest(&link).await?;
+            next_handle = link.previous.clone();
+            ensure!(
+                manifests
+                    .insert(handle.clone(), link.first_version)
+                    .is_none(),
+                "Chain loops back to manifest {}.",
+                handle,
+            );
+            chain.push(link);
+        }
+        chain.reverse();
+        for meta in &links {
+            ensure!(
+                manifests.get(&meta.manifest) == Some(&meta.first_version),
+                "Incremental backup [{}, {}] with manifest {} is not part of the chain.",
+                meta.first_version,
+                meta.last_version,
+                meta.manifest,
+            );
+        }
+        info!(
+            "Incremental backup chain is continuous from version {} to {}, in {} links.",
+            chain.first().expect("Chain not empty.").first_version,
+            chain.last().expect("Chain not empty.").last_version,
+            chain.len(),
+        );
+
+        // The first link relies on the epoch endings backed up before it, so all of them are used.
+        let ver_max = Version::max_value();
+        let epoch_endings = metadata_view.select_epoch_ending_backups(ver_max)?;
+        let global_opt = GlobalRestoreOptions {
+            target_version: ver_max,
+            trusted_waypoints: Arc::new(self.trusted_waypoints_opt.verify()?),
+            run_mode: Arc::new(RestoreRunMode::Verify),
+            concurrent_downloads: self.concurrent_downloads,
+        };
+        let epoch_history = Arc::new(
+            EpochHistoryRestoreController::new(
+                epoch_endings
+                    .into_iter()
+                    .map(|backup| backup.manifest)
+                    .collect(),
+                global_opt.clone(),
+                self.storage.clone(),
+            )
+            .run()
+            .await?,
+        );
+
+        let txn_manifests: Vec<FileHandle> =
+            chain.into_iter().map(|link| link.transactions).collect();
+        TransactionRestoreBatchController::new(
+            global_opt,
+            self.storage,
+            txn_manifests,
+            None, /* replay_from_version */
+            Some(epoch_history),
+        )
+        .run()
+        .await?;
+
+        Ok(())
+    }
+
+    async fn verify_transactions_manifest(&self, link: &IncrementalBackup) -> Result<()> {
+        let manifest: TransactionBackup = self.storage.load_json_file(&link.transactions).await?;
+        manifest.verify()?;
+        ensure!(
+            manifest.first_version == link.first_version
+                && manifest.last_version == link.last_version,
+            "Transaction backup range [{}, {}] doesn't match the link [{}, {}].",
+            manifest.first_version,
+            manifest.last_version,
+            link.first_version,
+            link.last_version,
+        );
+        Ok(())
+    }
+
+    async fn verify_state_snapshot_manifest(&self, link: &IncrementalBackup) -> Result<()> {
+        if let Some(handle) = &link.state_snapshot {
+            let manifest: StateSnapshotBackup = self.storage.load_json_file(handle).await?;
+            ensure!(
+                manifest.version == link.last_version,
+                "State snapshot at version {} doesn't match the link [{}, {}].",
+                manifest.version,
+                link.first_version,
+                link.last_version,
+            );
+        }
+        Ok(())
+    }
+}
@@ -44,6 +44,10 @@ pub struct MetadataCacheOpt {
 }
 
 impl MetadataCacheOpt {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+
     // in cache we save things other than the cached files.
     const SUB_DIR: &'static str = "cache";
 
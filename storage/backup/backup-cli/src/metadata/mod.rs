@@ -48,6 +48,24 @@ impl Metadata {
             first_version,
             last_version,
             manifest,
+            incremental_backup: None,
+        })
+    }
+
+    /// The transaction backup of a link of the incremental backup chain. It's recorded as a
+    /// plain transaction backup, so that the readers unaware of the incremental backups still
+    /// load the metadata, and restore the transactions.
+    pub fn new_incremental_transaction_backup(
+        first_version: Version,
+        last_version: Version,
+        manifest: FileHandle,
+        incremental_backup: FileHandle,
+    ) -> Self {
+        Self::TransactionBackup(TransactionBackupMeta {
+            first_version,
+            last_version,
+            manifest,
+            incremental_backup: Some(incremental_backup),
         })
     }
 
@@ -90,4 +108,15 @@ pub struct TransactionBackupMeta {
     pub first_version: Version,
     pub last_version: Version,
     pub manifest: FileHandle,
+    /// Manifest of the `IncrementalBackup` link the transactions are backed up by, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incremental_backup: Option<FileHandle>,
+}
+
+/// A link of the incremental backup chain, as recorded by the metadata of its transactions.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct IncrementalBackupMeta {
+    pub first_version: Version,
+    pub last_version: Version,
+    pub manifest: FileHandle,
 }
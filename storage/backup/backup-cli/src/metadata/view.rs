@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::metadata::{
-    EpochEndingBackupMeta, Metadata, StateSnapshotBackupMeta, TransactionBackupMeta,
+    EpochEndingBackupMeta, IncrementalBackupMeta, Metadata, StateSnapshotBackupMeta,
+    TransactionBackupMeta,
 };
 use anyhow::{anyhow, ensure, Result};
 use aptos_types::transaction::Version;
@@ -77,6 +78,23 @@ impl MetadataView {
         Ok(res)
     }
 
+    /// Returns the links of the incremental backup chain, in order of their versions.
+    pub fn select_incremental_backups(&self) -> Vec<IncrementalBackupMeta> {
+        self.transaction_backups
+            .iter()
+            .filter_map(|t| {
+                t.incremental_backup
+                    .clone()
+                    .map(|manifest| IncrementalBackupMeta {
+                        first_version: t.first_version,
+                        last_version: t.last_version,
+                        manifest,
+                    })
+            })
+            .sorted()
+            .collect()
+    }
+
     pub fn select_epoch_ending_backups(
         &self,
         target_version: Version,
@@ -11,7 +11,7 @@ pub(crate) mod stream;
 pub mod test_utils;
 
 use anyhow::{anyhow, Result};
-use aptos_config::config::{RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_config::config::{RocksdbConfig, DEFAULT_STATE_CACHE_SIZE, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_crypto::HashValue;
 use aptos_infallible::duration_since_epoch;
 use aptos_jellyfish_merkle::{restore::JellyfishMerkleRestore, NodeBatch, TreeWriter};
@@ -56,6 +56,7 @@ impl From<RocksdbOpt> for RocksdbConfig {
         Self {
             max_open_files: opt.max_open_files,
             max_total_wal_size: opt.max_total_wal_size,
+            ..Default::default()
         }
     }
 }
@@ -162,6 +163,8 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
                 false,                       /* read_only */
                 NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
                 opt.rocksdb_opt.into(),
+                DEFAULT_STATE_CACHE_SIZE,
+                false, /* enable_event_type_index */
             )?)
             .get_restore_handler();
             RestoreRunMode::Restore { restore_handler }
@@ -0,0 +1,64 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares the execution traces dumped by two nodes, i.e. the write sets and events of the
+//! transactions of the blocks they executed, to find where their states diverge. Either two trace
+//! files of the same block, or two trace dirs whose traces of the same blocks are compared.
+
+use anyhow::{ensure, Result};
+use executor_types::BlockExecutionTrace;
+use std::{fs, path::Path, time::SystemTime};
+
+/// Prints the differences between the traces, returns whether there are any.
+pub fn diff_trace(left: &Path, right: &Path) -> Result<bool> {
+    if !left.is_dir() {
+        return diff_trace_files(left, right);
+    }
+    ensure!(right.is_dir(), "{:?} is a dir but {:?} isn't.", left, right);
+
+    // The blocks are compared in the order the left node executed them, the first one differing
+    // being where the states diverge.
+    let mut traces = vec![];
+    for entry in fs::read_dir(left)? {
+        let entry = entry?;
+        let right_path = right.join(entry.file_name());
+        if right_path.exists() {
+            let modified = entry
+                .metadata()?
+                .modified()
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            let left_trace = BlockExecutionTrace::read(&entry.path())?;
+            traces.push((modified, left_trace.version, left_trace, right_path));
+        }
+    }
+    traces.sort_by_key(|(modified, version, _, _)| (*modified, *version));
+    println!("Comparing the traces of {} blocks.", traces.len());
+
+    let mut differ = false;
+    for (_, _, left_trace, right_path) in traces {
+        differ |= diff_traces(&left_trace, &BlockExecutionTrace::read(&right_path)?);
+    }
+    Ok(differ)
+}
+
+fn diff_trace_files(left: &Path, right: &Path) -> Result<bool> {
+    Ok(diff_traces(
+        &BlockExecutionTrace::read(left)?,
+        &BlockExecutionTrace::read(right)?,
+    ))
+}
+
+fn diff_traces(left_trace: &BlockExecutionTrace, right_trace: &BlockExecutionTrace) -> bool {
+    let diffs = left_trace.diff(right_trace);
+    if diffs.is_empty() {
+        return false;
+    }
+    println!(
+        "The traces of block {} (version {:?}) differ:",
+        left_trace.block_id, left_trace.version
+    );
+    for diff in diffs {
+        println!("  {}", diff);
+    }
+    true
+}
@@ -3,12 +3,17 @@
 
 #![forbid(unsafe_code)]
 
+mod diff_trace;
+mod verify;
+
 use anyhow::Result;
-use aptos_config::config::{RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_config::config::{
+    ColdLedgerConfig, RocksdbConfig, DEFAULT_STATE_CACHE_SIZE, NO_OP_STORAGE_PRUNER_CONFIG,
+};
 use aptos_logger::info;
 use aptosdb::AptosDB;
 use std::path::PathBuf;
-use storage_interface::DbReader;
+use storage_interface::{DbCheckpointer, DbReader};
 
 use aptos_types::{
     account_address::AccountAddress, account_config::AccountResource, account_state::AccountState,
@@ -19,8 +24,17 @@ use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
 struct Opt {
+    /// Required by all the commands but `diff-trace`.
+    #[structopt(long, parse(from_os_str))]
+    db: Option<PathBuf>,
+
+    /// Dirs of the shards of the state Merkle tree, in the order of the shards, if it's sharded.
+    #[structopt(long = "state-shard-dir", parse(from_os_str))]
+    state_shard_dirs: Vec<PathBuf>,
+
+    /// Dir of the cold ledger the old transactions and events are moved to, if any.
     #[structopt(long, parse(from_os_str))]
-    db: PathBuf,
+    cold_ledger_dir: Option<PathBuf>,
 
     #[structopt(subcommand)] // Note that we mark a field as a subcommand
     cmd: Option<Command>,
@@ -39,6 +53,40 @@ enum Command {
     },
     #[structopt(name = "list-accounts")]
     ListAccounts,
+    /// Verifies the transactions, events and state roots of a range of versions, reporting the
+    /// corrupted ones.
+    #[structopt(name = "verify")]
+    Verify {
+        #[structopt(long, default_value = "0")]
+        start_version: u64,
+        /// Defaults to the version of the latest ledger info.
+        #[structopt(long)]
+        end_version: Option<u64>,
+    },
+    /// Moves the state Merkle tree of an unsharded DB to the shards given by `--state-shard-dir`,
+    /// after which the node is to be configured with the same `state_shard_dirs`, in the same
+    /// order. The node must be stopped. An interrupted migration is resumed by running it again.
+    #[structopt(name = "migrate-state-to-shards")]
+    MigrateStateToShards,
+    /// Creates a checkpoint of the DB in `dir`, which must not exist, with the manifest of the
+    /// checkpoint. The node must be stopped, the checkpoint of a running node is created with the
+    /// admin API.
+    #[structopt(name = "create-checkpoint")]
+    CreateCheckpoint {
+        #[structopt(parse(from_os_str))]
+        dir: PathBuf,
+    },
+    /// Compares the execution traces dumped by two nodes to the `trace_dir` of their execution
+    /// config, reporting the first transaction whose output differs in each block. Either two
+    /// trace files of the same block or two trace dirs, whose blocks are compared in the order the
+    /// left node executed them.
+    #[structopt(name = "diff-trace")]
+    DiffTrace {
+        #[structopt(parse(from_os_str))]
+        left: PathBuf,
+        #[structopt(parse(from_os_str))]
+        right: PathBuf,
+    },
 }
 
 /// Print out latest information stored in the DB.
@@ -160,7 +208,25 @@ fn main() {
 
     let opt = Opt::from_args();
 
-    let p = opt.db.as_path();
+    if let Some(Command::DiffTrace { left, right }) = &opt.cmd {
+        match diff_trace::diff_trace(left, right) {
+            Ok(false) => println!("The traces are the same."),
+            Ok(true) => std::process::exit(1),
+            Err(e) => {
+                println!("Comparing the traces failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let p = match &opt.db {
+        Some(db) => db.as_path(),
+        None => {
+            info!("--db is required.");
+            std::process::exit(-1);
+        }
+    };
 
     if !p.is_dir() {
         info!("Invalid Directory {:?}!", p);
@@ -170,11 +236,34 @@ fn main() {
     let log_dir = tempfile::tempdir().expect("Unable to get temp dir");
     info!("Opening DB at: {:?}, log at {:?}", p, log_dir.path());
 
-    let db = AptosDB::open(
+    if let Some(Command::MigrateStateToShards) = opt.cmd {
+        if let Err(e) =
+            AptosDB::migrate_state_to_shards(p, &opt.state_shard_dirs, RocksdbConfig::default())
+        {
+            println!("Migration failed: {}", e);
+            std::process::exit(1);
+        }
+        println!("State migrated to {} shards.", opt.state_shard_dirs.len());
+        return;
+    }
+
+    // RocksDB only creates checkpoints of the DBs opened for writes.
+    let readonly = !matches!(opt.cmd, Some(Command::CreateCheckpoint { .. }));
+    let db = AptosDB::open_with_state_shards(
         p,
-        true,                        /* readonly */
+        &opt.state_shard_dirs,
+        readonly,
         NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
         RocksdbConfig::default(),
+        DEFAULT_STATE_CACHE_SIZE,
+        false, /* enable_event_type_index */
+        false, /* enable_commit_pipelining */
+        // Nothing is moved when opened readonly, nor out of a hot window of all the versions.
+        opt.cold_ledger_dir.map(|dir| ColdLedgerConfig {
+            dir,
+            hot_window: u64::MAX,
+            max_versions_to_move_per_batch: 0,
+        }),
     )
     .expect("Unable to open AptosDB");
     info!("DB opened successfully.");
@@ -193,6 +282,24 @@ fn main() {
             Command::ListAccounts => {
                 list_accounts(&db);
             }
+            Command::Verify {
+                start_version,
+                end_version,
+            } => {
+                if let Err(e) = verify::verify(&db, start_version, end_version) {
+                    println!("Verification failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            Command::CreateCheckpoint { dir } => match db.create_db_checkpoint(dir) {
+                Ok(manifest) => println!("Checkpoint created: {:?}", manifest),
+                Err(e) => {
+                    println!("Creating the checkpoint failed: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            Command::MigrateStateToShards => unreachable!("Migrated before opening the DB."),
+            Command::DiffTrace { .. } => unreachable!("Compared before opening the DB."),
         }
     } else {
         print_head(&db).expect("Unable to read information from DB");
@@ -0,0 +1,139 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks the integrity of the data of a range of versions: the transactions, transaction infos
+//! and events are verified against the accumulator root hash of the latest ledger info in the
+//! DB, and the state root hash of each version is recomputed and compared to the one in its
+//! transaction info.
+
+use anyhow::{ensure, Result};
+use aptos_logger::info;
+use aptos_types::{ledger_info::LedgerInfo, transaction::Version};
+use aptosdb::AptosDB;
+use std::cmp::min;
+use storage_interface::DbReader;
+
+/// Number of versions read and verified at once.
+const BATCH_SIZE: u64 = 1000;
+
+pub fn verify(db: &AptosDB, start_version: Version, end_version: Option<Version>) -> Result<()> {
+    let ledger_info = db.get_latest_ledger_info()?.ledger_info().clone();
+    let end_version = end_version.map_or(ledger_info.version(), |version| {
+        min(version, ledger_info.version())
+    });
+    ensure!(
+        start_version <= end_version,
+        "Nothing to verify, start version {} is after end version {}.",
+        start_version,
+        end_version,
+    );
+    info!(
+        "Verifying versions [{}, {}] against the ledger info at version {}.",
+        start_version,
+        end_version,
+        ledger_info.version(),
+    );
+
+    let mut verifier = Verifier {
+        db,
+        ledger_info,
+        first_state_version: None,
+    };
+    let mut num_corruptions = 0;
+    let mut version = start_version;
+    while version <= end_version {
+        let limit = min(BATCH_SIZE, end_version - version + 1);
+        if let Err(batch_err) = verifier.verify_batch(version, limit) {
+            // Narrow down to the corrupted versions.
+            let num_corrupted_versions = (version..version + limit)
+                .filter(|v| match verifier.verify_batch(*v, 1) {
+                    Ok(()) => false,
+                    Err(e) => {
+                        println!("Version {} is corrupted: {}", v, e);
+                        true
+                    }
+                })
+                .count();
+            if num_corrupted_versions == 0 {
+                println!(
+                    "Versions [{}, {}] are corrupted: {}",
+                    version,
+                    version + limit - 1,
+                    batch_err,
+                );
+                num_corruptions += 1;
+            }
+            num_corruptions += num_corrupted_versions;
+        }
+        version += limit;
+    }
+
+    match verifier.first_state_version {
+        Some(version) if version > start_version => println!(
+            "States of versions [{}, {}] are pruned, not verified.",
+            start_version,
+            version - 1,
+        ),
+        Some(_) => (),
+        None => println!("States of all versions are pruned, not verified."),
+    }
+    ensure!(
+        num_corruptions == 0,
+        "Found {} corruptions in versions [{}, {}].",
+        num_corruptions,
+        start_version,
+        end_version,
+    );
+    println!(
+        "Versions [{}, {}] verified, no corruption found.",
+        start_version, end_version,
+    );
+    Ok(())
+}
+
+struct Verifier<'a> {
+    db: &'a AptosDB,
+    ledger_info: LedgerInfo,
+    // Pruning removes the states of the oldest versions, so the states of the versions after the
+    // first one with a state must all exist.
+    first_state_version: Option<Version>,
+}
+
+impl Verifier<'_> {
+    fn verify_batch(&mut self, first_version: Version, limit: u64) -> Result<()> {
+        let outputs =
+            self.db
+                .get_transaction_outputs(first_version, limit, self.ledger_info.version())?;
+        ensure!(
+            outputs.transactions_and_outputs.len() as u64 == limit,
+            "Expected {} transactions, got {}.",
+            limit,
+            outputs.transactions_and_outputs.len(),
+        );
+        // Verifies the accumulator proof of the transaction infos, and the transactions and
+        // events against the transaction infos.
+        outputs.verify(&self.ledger_info, Some(first_version))?;
+
+        for (version, txn_info) in (first_version..).zip(&outputs.proof.transaction_infos) {
+            match self.db.get_state_root_hash_option(version)? {
+                Some(state_root_hash) => {
+                    ensure!(
+                        state_root_hash == txn_info.state_change_hash(),
+                        "State root hash {} of version {} doesn't match the one in the \
+                         transaction info, {}.",
+                        state_root_hash,
+                        version,
+                        txn_info.state_change_hash(),
+                    );
+                    self.first_state_version.get_or_insert(version);
+                }
+                None => ensure!(
+                    self.first_state_version.is_none(),
+                    "State of version {} is missing.",
+                    version,
+                ),
+            }
+        }
+        Ok(())
+    }
+}
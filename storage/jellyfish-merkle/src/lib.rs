@@ -124,7 +124,7 @@ pub trait TreeReader<V> {
     fn get_node_option(&self, node_key: &NodeKey) -> Result<Option<Node<V>>>;
 
     /// Gets the rightmost leaf. Note that this assumes we are in the process of restoring the tree
-    /// and all nodes are at the same version.
+    /// and the nodes being restored are at the latest version in storage.
     fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode<V>)>>;
 }
 
@@ -14,7 +14,7 @@ use crate::{
     },
     NibbleExt, NodeBatch, TreeReader, TreeWriter, ROOT_NIBBLE_HEIGHT,
 };
-use anyhow::{bail, ensure, Result};
+use anyhow::{bail, ensure, format_err, Result};
 use aptos_crypto::{
     hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue,
@@ -28,7 +28,7 @@ use aptos_types::{
     transaction::Version,
 };
 use mirai_annotations::*;
-use std::sync::Arc;
+use std::{mem, sync::Arc, thread::JoinHandle};
 use storage_interface::StateSnapshotReceiver;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -150,6 +150,11 @@ pub struct JellyfishMerkleRestore<V> {
     /// The nodes that have been fully restored and are ready to be written to storage.
     frozen_nodes: NodeBatch<V>,
 
+    /// The write of the nodes frozen by the previous chunk, which runs while the next chunk is
+    /// added and verified. It's waited for before the next write, so the nodes are written in
+    /// order and a failed write fails the restore.
+    pending_write: Option<JoinHandle<Result<()>>>,
+
     /// The most recently added leaf. This is used to ensure the keys come in increasing order and
     /// do proof verification.
     previous_leaf: Option<LeafNode<V>>,
@@ -163,7 +168,7 @@ pub struct JellyfishMerkleRestore<V> {
 
 impl<V> JellyfishMerkleRestore<V>
 where
-    V: crate::Value,
+    V: 'static + crate::Value,
 {
     pub fn new<D: 'static + TreeReader<V> + TreeWriter<V>>(
         store: Arc<D>,
@@ -171,27 +176,28 @@ where
         expected_root_hash: HashValue,
     ) -> Result<Self> {
         let tree_reader = Arc::clone(&store);
-        let (partial_nodes, previous_leaf) =
-            if let Some((node_key, leaf_node)) = tree_reader.get_rightmost_leaf()? {
-                // TODO: confirm rightmost leaf is at the desired version
-                // If the system crashed in the middle of the previous restoration attempt, we need
-                // to recover the partial nodes to the state right before the crash.
-                (
-                    Self::recover_partial_nodes(tree_reader.as_ref(), version, node_key)?,
-                    Some(leaf_node),
-                )
-            } else {
-                (
-                    vec![InternalInfo::new_empty(NodeKey::new_empty_path(version))],
-                    None,
-                )
-            };
+        let (partial_nodes, previous_leaf) = if let Some((node_key, leaf_node)) =
+            Self::get_rightmost_leaf_at(tree_reader.as_ref(), version)?
+        {
+            // If the system crashed in the middle of the previous restoration attempt, we need
+            // to recover the partial nodes to the state right before the crash.
+            (
+                Self::recover_partial_nodes(tree_reader.as_ref(), version, node_key)?,
+                Some(leaf_node),
+            )
+        } else {
+            (
+                vec![InternalInfo::new_empty(NodeKey::new_empty_path(version))],
+                None,
+            )
+        };
 
         Ok(Self {
             store,
             version,
             partial_nodes,
             frozen_nodes: NodeBatch::new(),
+            pending_write: None,
             previous_leaf,
             num_keys_received: 0,
             expected_root_hash,
@@ -208,12 +214,43 @@ where
             version,
             partial_nodes: vec![InternalInfo::new_empty(NodeKey::new_empty_path(version))],
             frozen_nodes: NodeBatch::new(),
+            pending_write: None,
             previous_leaf: None,
             num_keys_received: 0,
             expected_root_hash,
         })
     }
 
+    /// Returns the number of keys restored at `version` before the previous restoration attempt was
+    /// interrupted, i.e. the keys in storage, which are skipped when the restoration is resumed.
+    pub fn get_num_restored_keys(store: &dyn TreeReader<V>, version: Version) -> Result<u64> {
+        let node_key = match Self::get_rightmost_leaf_at(store, version)? {
+            Some((node_key, _leaf_node)) => node_key,
+            None => return Ok(0),
+        };
+        Self::recover_partial_nodes(store, version, node_key)?
+            .iter()
+            .flat_map(|internal_info| internal_info.children.iter().flatten())
+            .map(|child_info| match child_info {
+                ChildInfo::Internal { leaf_count, .. } => leaf_count
+                    .map(|leaf_count| leaf_count as u64)
+                    .ok_or_else(|| format_err!("Leaf count of a restored node is unknown.")),
+                ChildInfo::Leaf { .. } => Ok(1),
+            })
+            .sum()
+    }
+
+    /// Gets the rightmost leaf in storage if it's at `version`, i.e. restored by a previous attempt
+    /// to restore the tree at `version` rather than belonging to another tree.
+    fn get_rightmost_leaf_at(
+        store: &dyn TreeReader<V>,
+        version: Version,
+    ) -> Result<Option<(NodeKey, LeafNode<V>)>> {
+        Ok(store
+            .get_rightmost_leaf()?
+            .filter(|(node_key, _leaf_node)| node_key.version() == version))
+    }
+
     /// Recovers partial nodes from storage. We do this by looking at all the ancestors of the
     /// rightmost leaf. The ones do not exist in storage are the partial nodes.
     fn recover_partial_nodes(
@@ -313,13 +350,29 @@ where
         // Verify what we have added so far is all correct.
         self.verify(proof)?;
 
-        // Write the frozen nodes to storage.
-        self.store.write_node_batch(&self.frozen_nodes)?;
-        self.frozen_nodes.clear();
+        // Write the frozen nodes to storage in the background, the next chunk being verified
+        // meanwhile.
+        self.wait_for_pending_write()?;
+        let store = Arc::clone(&self.store);
+        let frozen_nodes = mem::take(&mut self.frozen_nodes);
+        self.pending_write = Some(std::thread::spawn(move || {
+            store.write_node_batch(&frozen_nodes)
+        }));
 
         Ok(())
     }
 
+    /// Waits for the nodes frozen by the previous chunk to be written, returning the error of the
+    /// write if it failed.
+    fn wait_for_pending_write(&mut self) -> Result<()> {
+        match self.pending_write.take() {
+            Some(write) => write
+                .join()
+                .map_err(|_| format_err!("Writing the frozen nodes panicked."))?,
+            None => Ok(()),
+        }
+    }
+
     /// Restores one account.
     fn add_one(&mut self, new_key: HashValue, new_value: V) {
         let nibble_path = NibblePath::new_even(new_key.to_vec());
@@ -646,6 +699,8 @@ where
     /// Finishes the restoration process. This tells the code that there is no more account,
     /// otherwise we can not freeze the rightmost leaf and its ancestors.
     fn finish_impl(mut self) -> Result<()> {
+        self.wait_for_pending_write()?;
+
         // Deal with the special case when the entire tree has a single leaf.
         if self.partial_nodes.len() == 1 {
             let mut num_children = 0;
@@ -675,7 +730,16 @@ where
     }
 }
 
-impl<V: crate::Value> StateSnapshotReceiver<V> for JellyfishMerkleRestore<V> {
+impl<V> Drop for JellyfishMerkleRestore<V> {
+    fn drop(&mut self) {
+        // An interrupted restore still leaves the nodes of the chunks it added in storage.
+        if let Some(write) = self.pending_write.take() {
+            let _ = write.join();
+        }
+    }
+}
+
+impl<V: 'static + crate::Value> StateSnapshotReceiver<V> for JellyfishMerkleRestore<V> {
     fn add_chunk(
         &mut self,
         chunk: Vec<(HashValue, V)>,
@@ -5,12 +5,19 @@ use crate::{
     mock_tree_store::MockTreeStore,
     restore::JellyfishMerkleRestore,
     test_helper::{init_mock_db, ValueBlob},
-    JellyfishMerkleTree, TreeReader,
+    JellyfishMerkleTree, NodeBatch, TreeReader, TreeWriter,
 };
+use anyhow::{bail, Result};
 use aptos_crypto::HashValue;
 use aptos_types::transaction::Version;
 use proptest::{collection::btree_map, prelude::*};
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 use storage_interface::StateSnapshotReceiver;
 
 proptest! {
@@ -58,6 +65,12 @@ proptest! {
                 }
                 Some((_, node)) => node.account_key(),
             };
+            let num_restored_keys = JellyfishMerkleRestore::<ValueBlob>::get_num_restored_keys(
+                restore_db.as_ref(),
+                version,
+            )
+            .unwrap();
+            prop_assert_eq!(num_restored_keys, all.range(..=rightmost_key).count() as u64);
             let remaining_accounts: Vec<_> = all
                 .clone()
                 .into_iter()
@@ -79,6 +92,32 @@ proptest! {
         assert_success(&restore_db, expected_root_hash, &all, version);
     }
 
+    #[test]
+    fn test_restore_in_chunks(
+        (btree, chunk_size) in btree_map(any::<HashValue>(), any::<ValueBlob>(), 1..1000)
+            .prop_flat_map(|btree| {
+                let len = btree.len();
+                (Just(btree), 1..=len)
+            })
+    ) {
+        let (db, version) = init_mock_db(&btree.clone().into_iter().collect());
+        let tree = JellyfishMerkleTree::new(&db);
+        let expected_root_hash = tree.get_root_hash(version).unwrap();
+
+        let restore_db = Arc::new(MockTreeStore::default());
+        let mut restore =
+            JellyfishMerkleRestore::new(Arc::clone(&restore_db), version, expected_root_hash)
+                .unwrap();
+        let kvs: Vec<_> = btree.clone().into_iter().collect();
+        for chunk in kvs.chunks(chunk_size) {
+            let proof = tree.get_range_proof(chunk.last().unwrap().0, version).unwrap();
+            restore.add_chunk(chunk.to_vec(), proof).unwrap();
+        }
+        restore.finish().unwrap();
+
+        assert_success(&restore_db, expected_root_hash, &btree, version);
+    }
+
     #[test]
     fn test_overwrite(
         btree1 in btree_map(any::<HashValue>(), any::<ValueBlob>(), 1..1000),
@@ -92,6 +131,65 @@ proptest! {
     }
 }
 
+/// A store failing the write of the `n`-th node batch
+struct FailingStore {
+    store: MockTreeStore<ValueBlob>,
+    failing_write: usize,
+    num_writes: AtomicUsize,
+}
+
+impl TreeWriter<ValueBlob> for FailingStore {
+    fn write_node_batch(&self, node_batch: &NodeBatch<ValueBlob>) -> Result<()> {
+        if self.num_writes.fetch_add(1, Ordering::SeqCst) == self.failing_write {
+            bail!("Failed to write.");
+        }
+        self.store.write_node_batch(node_batch)
+    }
+}
+
+#[test]
+fn test_restore_fails_on_failed_write() {
+    let btree: BTreeMap<_, _> = (0..10)
+        .map(|i| (HashValue::random(), ValueBlob::from(vec![i])))
+        .collect();
+    let (db, version) = init_mock_db(&btree.clone().into_iter().collect());
+    let tree = JellyfishMerkleTree::new(&db);
+    let expected_root_hash = tree.get_root_hash(version).unwrap();
+    let add_chunks = |restore: &mut JellyfishMerkleRestore<ValueBlob>| -> Result<()> {
+        for (key, value) in &btree {
+            let proof = tree.get_range_proof(*key, version).unwrap();
+            restore.add_chunk(vec![(*key, value.clone())], proof)?;
+        }
+        Ok(())
+    };
+
+    // The nodes of a chunk are written while the next chunk is added, which fails once the
+    // write failed.
+    let store = Arc::new(FailingStore {
+        store: MockTreeStore::default(),
+        failing_write: 2,
+        num_writes: AtomicUsize::new(0),
+    });
+    let mut restore =
+        JellyfishMerkleRestore::new_overwrite(Arc::clone(&store), version, expected_root_hash)
+            .unwrap();
+    assert!(add_chunks(&mut restore).is_err());
+    assert_eq!(store.num_writes.load(Ordering::SeqCst), 3);
+
+    // The write of the last chunk fails the finish.
+    let store = Arc::new(FailingStore {
+        store: MockTreeStore::default(),
+        failing_write: btree.len() - 1,
+        num_writes: AtomicUsize::new(0),
+    });
+    let mut restore =
+        JellyfishMerkleRestore::new_overwrite(Arc::clone(&store), version, expected_root_hash)
+            .unwrap();
+    add_chunks(&mut restore).unwrap();
+    assert!(restore.finish().is_err());
+    assert_eq!(store.num_writes.load(Ordering::SeqCst), btree.len());
+}
+
 fn assert_success<V>(
     db: &MockTreeStore<V>,
     expected_root_hash: HashValue,
@@ -42,6 +42,18 @@ pub type ReadOptions = rocksdb::ReadOptions;
 /// Type alias to `rocksdb::Options`.
 pub type Options = rocksdb::Options;
 
+/// Type alias to `rocksdb::BlockBasedOptions`.
+pub type BlockBasedOptions = rocksdb::BlockBasedOptions;
+
+/// Type alias to `rocksdb::Cache`.
+pub type Cache = rocksdb::Cache;
+
+/// Type alias to `rocksdb::DBCompressionType`.
+pub type DBCompressionType = rocksdb::DBCompressionType;
+
+/// Type alias to `rocksdb::DBCompactionStyle`.
+pub type DBCompactionStyle = rocksdb::DBCompactionStyle;
+
 /// Type alias to improve readability.
 pub type ColumnFamilyName = &'static str;
 
@@ -238,20 +250,38 @@ impl DB {
         name: &'static str,
         column_families: Vec<ColumnFamilyName>,
         db_opts: &rocksdb::Options,
+    ) -> Result<Self> {
+        let cfs = column_families
+            .into_iter()
+            .map(|cf_name| {
+                let mut cf_opts = rocksdb::Options::default();
+                cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
+                (cf_name, cf_opts)
+            })
+            .collect();
+        DB::open_with_cf_opts(path, name, cfs, db_opts)
+    }
+
+    /// Same as `open`, but with the options of each column family provided.
+    pub fn open_with_cf_opts(
+        path: impl AsRef<Path>,
+        name: &'static str,
+        cfs: Vec<(ColumnFamilyName, rocksdb::Options)>,
+        db_opts: &rocksdb::Options,
     ) -> Result<Self> {
         {
-            let cfs_set: HashSet<_> = column_families.iter().collect();
+            let cfs_set: HashSet<_> = cfs.iter().map(|(cf_name, _)| cf_name).collect();
             ensure!(
                 cfs_set.contains(&DEFAULT_CF_NAME),
                 "No \"default\" column family name is provided.",
             );
             ensure!(
-                cfs_set.len() == column_families.len(),
+                cfs_set.len() == cfs.len(),
                 "Duplicate column family name found.",
             );
         }
 
-        let db = DB::open_cf(db_opts, path, name, column_families)?;
+        let db = DB::open_cf(db_opts, path, name, cfs)?;
         Ok(db)
     }
 
@@ -285,15 +315,14 @@ impl DB {
         db_opts: &rocksdb::Options,
         path: impl AsRef<Path>,
         name: &'static str,
-        column_families: Vec<ColumnFamilyName>,
+        cfs: Vec<(ColumnFamilyName, rocksdb::Options)>,
     ) -> Result<DB> {
+        let column_families = cfs.iter().map(|(cf_name, _)| *cf_name).collect();
         let inner = rocksdb::DB::open_cf_descriptors(
             db_opts,
             path,
-            column_families.iter().map(|cf_name| {
-                let mut cf_opts = rocksdb::Options::default();
-                cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
-                rocksdb::ColumnFamilyDescriptor::new((*cf_name).to_string(), cf_opts)
+            cfs.into_iter().map(|(cf_name, cf_opts)| {
+                rocksdb::ColumnFamilyDescriptor::new(cf_name.to_string(), cf_opts)
             }),
         )?;
         Ok(Self::log_construct(name, column_families, inner))
@@ -504,6 +533,13 @@ impl DB {
             })
     }
 
+    /// Replays the updates the primary made since the last catch-up, so they are visible to the
+    /// reads of a db opened by `open_as_secondary`.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        self.inner.try_catch_up_with_primary()?;
+        Ok(())
+    }
+
     /// Creates new physical DB checkpoint in directory specified by `path`.
     pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         rocksdb::checkpoint::Checkpoint::new(&self.inner)?.create_checkpoint(path)?;
@@ -14,7 +14,8 @@ use std::ops::Deref;
 /// the VM for transaction execution, during which the VM is guaranteed to read anything at the
 /// given state.
 pub trait StateView: Sync {
-    /// For logging and debugging purpose, identifies what this view is for.
+    /// For logging and debugging purpose, identifies what this view is for. The VM also shares its
+    /// Move VM between the blocks, and the chunks, executed on top of each other by the id.
     fn id(&self) -> StateViewId {
         StateViewId::Miscellaneous
     }
@@ -31,8 +32,11 @@ pub trait StateView: Sync {
 pub enum StateViewId {
     /// State-sync applying a chunk of transactions.
     ChunkExecution { first_version: Version },
-    /// LEC applying a block.
-    BlockExecution { block_id: HashValue },
+    /// LEC applying a block on top of its parent.
+    BlockExecution {
+        block_id: HashValue,
+        parent_block_id: HashValue,
+    },
     /// VmValidator verifying incoming transaction.
     TransactionValidation { base_version: Version },
     /// For test, db-bootstrapper, etc. Usually not aimed to pass to VM.
@@ -31,9 +31,11 @@ use aptos_types::{
         TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
     },
 };
+use move_core_types::language_storage::TypeTag;
 use serde::{Deserialize, Serialize};
-use std::{convert::TryFrom, sync::Arc};
+use std::{collections::BTreeMap, convert::TryFrom, path::PathBuf, sync::Arc};
 use thiserror::Error;
+use tokio::sync::watch;
 
 #[cfg(any(feature = "testing", feature = "fuzzing"))]
 pub mod mock;
@@ -135,6 +137,80 @@ impl TreeState {
     }
 }
 
+/// The history of an account gathered from the account indices of the DB.
+///
+/// The indices are kept since the DB commits the genesis transaction; for the accounts created
+/// before, e.g. in a DB restored from a state snapshot, the first change of the authentication
+/// key seen by the DB is regarded as the creation.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AccountHistory {
+    /// The version of the transaction creating the account, `None` if the account doesn't exist.
+    pub creation_version: Option<Version>,
+    /// The number of times the authentication key was rotated since the account creation.
+    pub authentication_key_rotations: u64,
+    /// The version of the latest transaction sent by the account, `None` if it sent none or the
+    /// transaction is pruned.
+    pub last_transaction_version: Option<Version>,
+}
+
+/// The disk usage of a DB and how far it's pruned, for capacity planning.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StorageUsage {
+    /// The version of the latest committed transaction, `None` if the DB is empty.
+    pub latest_version: Option<Version>,
+    /// The least readable version of each pruner, by the pruner name. Empty if pruning is
+    /// disabled.
+    pub least_readable_versions: BTreeMap<String, Version>,
+    pub column_families: Vec<ColumnFamilyUsage>,
+}
+
+/// The usage of a column family, from the estimates of RocksDB.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ColumnFamilyUsage {
+    /// The RocksDB instance holding the column family, e.g. `aptosdb`, or `state_shard_1` when the
+    /// state Merkle tree is sharded.
+    pub db: String,
+    pub name: String,
+    /// The total size of the SST files, including the ones not compacted away yet.
+    pub total_sst_files_size: u64,
+    pub estimated_live_data_size: u64,
+    pub estimated_num_keys: u64,
+}
+
+/// Describes a checkpoint of the DB, as written to `checkpoint_manifest.json` in its dir. The dir
+/// is laid out like the data dir of a node, so a node is cloned by copying it over.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DbCheckpointManifest {
+    /// The version of the latest ledger info in the checkpoint, `None` if the DB is empty.
+    pub version: Option<Version>,
+    pub epoch: Option<u64>,
+    /// The root hash of the transaction accumulator at `version`.
+    pub accumulator_root_hash: Option<HashValue>,
+    /// The dirs to configure the storage of the clone with, relative to the checkpoint dir: the
+    /// `dir` of the DB, the `state_shard_dirs`, empty if the state Merkle tree is not sharded, and
+    /// the `dir` of the `cold_ledger_config`, if any.
+    pub db_dir: PathBuf,
+    pub state_shard_dirs: Vec<PathBuf>,
+    pub cold_ledger_dir: Option<PathBuf>,
+}
+
+/// Creates checkpoints of a running DB, e.g. for the admin API of the node.
+pub trait DbCheckpointer: Send + Sync {
+    /// Creates a checkpoint of the DB in `checkpoint_dir`, which must not exist, at the latest
+    /// committed version, without stopping the node.
+    fn create_db_checkpoint(&self, checkpoint_dir: PathBuf) -> Result<DbCheckpointManifest>;
+}
+
+/// The progress of a state snapshot restore that was started but not finalized yet, e.g. because
+/// the node restarted in the middle of it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshotProgress {
+    /// The version of the snapshot being restored.
+    pub version: Version,
+    /// The number of keys restored so far, i.e. the index of the next key to restore.
+    pub num_restored_keys: u64,
+}
+
 pub trait StateSnapshotReceiver<V>: Send {
     fn add_chunk(
         &mut self,
@@ -274,6 +350,29 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the sequence number of the latest event of the event stream committed at the
+    /// `ledger_version`, `None` if none is.
+    fn get_latest_event_sequence_number(
+        &self,
+        event_key: &EventKey,
+        ledger_version: Version,
+    ) -> Result<Option<u64>> {
+        unimplemented!()
+    }
+
+    /// Returns at most `limit` events of the type `type_tag`, emitted to any event stream by the
+    /// transactions with versions in [`start_version`, `ledger_version`], with the versions of the
+    /// transactions. Requires the event type index to be enabled in the storage config.
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, ContractEvent)>> {
+        unimplemented!()
+    }
+
     /// Returns events by given event key
     fn get_events_with_proofs(
         &self,
@@ -347,6 +446,12 @@ pub trait DbReader: Send + Sync {
         Ok(self.get_latest_ledger_info()?.ledger_info().version())
     }
 
+    /// Subscribes to the version of the latest ledger info, the receiver is notified every time
+    /// transactions are committed along with a new ledger info.
+    fn subscribe_committed_version(&self) -> Result<watch::Receiver<Version>> {
+        unimplemented!()
+    }
+
     /// Returns the latest version and committed block timestamp
     fn get_latest_commit_metadata(&self) -> Result<(Version, u64)> {
         let ledger_info_with_sig = self.get_latest_ledger_info()?;
@@ -375,6 +480,17 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the history of the account with `address` as of `ledger_version`: the version
+    /// of its creation, the number of rotations of its authentication key and the version of the
+    /// latest transaction it sent.
+    fn get_account_history(
+        &self,
+        address: AccountAddress,
+        ledger_version: Version,
+    ) -> Result<AccountHistory> {
+        unimplemented!()
+    }
+
     /// Returns the list of transactions sent by an account with `address` starting
     /// at sequence number `seq_num`. Will return no more than `limit` transactions.
     /// Will ignore transactions with `txn.version > ledger_version`. Optionally
@@ -390,6 +506,23 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the list of transactions an account with `address` participated in, as the sender,
+    /// a secondary signer or the owner of an event stream the transaction emitted to, starting at
+    /// version `start_version`. Will return no more than `limit` transactions. Will ignore
+    /// transactions with `txn.version > ledger_version`. Optionally fetch events for each
+    /// transaction when `include_events` is `true`. The transactions committed before the index
+    /// was added to the DB aren't indexed, the index isn't backfilled.
+    fn get_account_participated_transactions(
+        &self,
+        address: AccountAddress,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<AccountTransactionsWithProof> {
+        unimplemented!()
+    }
+
     /// Returns proof of new state for a given ledger info with signatures relative to version known
     /// to client
     fn get_state_proof_with_ledger_info(
@@ -432,6 +565,17 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Gets the state value by state key at the version, without proof. Used for reading the
+    /// state during execution, where the proofs aren't needed.
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        self.get_state_value_with_proof_by_version(state_key, version)
+            .map(|(value, _proof)| value)
+    }
+
     /// Gets the latest TreeState no matter if db has been bootstrapped.
     /// Used by the Db-bootstrapper.
     fn get_latest_tree_state(&self) -> Result<TreeState> {
@@ -509,10 +653,33 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the progress of the state snapshot restore started with
+    /// `get_state_snapshot_receiver()` but not finalized yet, `None` if there's none. The restore
+    /// is resumed from there by getting a receiver for the same version again.
+    fn get_state_snapshot_progress(&self) -> Result<Option<StateSnapshotProgress>> {
+        unimplemented!()
+    }
+
     /// Get the state prune window config value.
     fn get_state_prune_window(&self) -> Option<usize> {
         unimplemented!()
     }
+
+    /// Returns the least readable version of the state store, the states before it are pruned.
+    fn get_least_readable_state_version(&self) -> Result<Version> {
+        unimplemented!()
+    }
+
+    /// Returns the least readable version of the transactions, the transactions before it
+    /// are pruned, or their events, write sets or ledger data are.
+    fn get_least_readable_transaction_version(&self) -> Result<Version> {
+        unimplemented!()
+    }
+
+    /// Returns the disk usage of the DB by column family, and the progress of the pruners.
+    fn get_storage_usage(&self) -> Result<StorageUsage> {
+        unimplemented!()
+    }
 }
 
 impl MoveStorage for &dyn DbReader {
@@ -525,7 +692,7 @@ impl MoveStorage for &dyn DbReader {
         access_path: AccessPath,
         version: Version,
     ) -> Result<Vec<u8>> {
-        let (state_value, _) = self.get_state_value_with_proof_by_version(
+        let state_value = self.get_state_value_by_version(
             &StateKey::AccountAddressKey(access_path.address),
             version,
         )?;
@@ -543,11 +710,10 @@ impl MoveStorage for &dyn DbReader {
     fn fetch_config_by_version(&self, config_id: ConfigID, version: Version) -> Result<Vec<u8>> {
         let aptos_root_state = AccountState::try_from(
             &self
-                .get_state_value_with_proof_by_version(
+                .get_state_value_by_version(
                     &StateKey::AccountAddressKey(aptos_root_address()),
                     version,
                 )?
-                .0
                 .ok_or_else(|| {
                     format_err!("missing blob in account state/account does not exist")
                 })?,
@@ -590,6 +756,9 @@ pub trait DbWriter: Send + Sync {
     /// Get a (stateful) state snapshot receiver.
     ///
     /// Chunk of accounts need to be added via `add_chunk()` before finishing up with `finish_box()`
+    ///
+    /// If a restore of the same version was interrupted, the receiver resumes it, expecting the
+    /// chunks from `DbReader::get_state_snapshot_progress()` on.
     fn get_state_snapshot_receiver(
         &self,
         version: Version,
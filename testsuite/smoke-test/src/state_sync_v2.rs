@@ -50,6 +50,46 @@ async fn test_full_node_bootstrap_accounts() {
     test_full_node_sync(vfn_peer_id, swarm, true).await;
 }
 
+#[tokio::test]
+async fn test_full_node_catch_up_after_bootstrap_accounts() {
+    // Create a validator swarm of 1 validator node
+    let mut swarm = new_local_swarm_with_aptos(1).await;
+
+    // Create a fullnode config that uses account state syncing
+    let mut vfn_config = NodeConfig::default_for_validator_full_node();
+    vfn_config.state_sync.state_sync_driver.enable_state_sync_v2 = true;
+    vfn_config.state_sync.state_sync_driver.bootstrapping_mode =
+        BootstrappingMode::DownloadLatestAccountStates;
+
+    // Create the fullnode and wait for it to bootstrap
+    let vfn_peer_id = create_full_node(vfn_config, &mut swarm).await;
+    let validator_peer_id = swarm.validators().next().unwrap().peer_id();
+    let validator_client = swarm.validator(validator_peer_id).unwrap().rest_client();
+    let (mut account_0, account_1) = create_test_accounts(&mut swarm).await;
+    wait_for_all_nodes(&mut swarm).await;
+
+    // Stop the fullnode and execute more transactions on the validator
+    swarm.fullnode_mut(vfn_peer_id).unwrap().stop();
+    execute_transactions(
+        &mut swarm,
+        &validator_client,
+        &mut account_0,
+        &account_1,
+        true,
+    )
+    .await;
+
+    // Restart the fullnode and verify it catches up (without downloading the
+    // account states again)
+    swarm
+        .fullnode_mut(vfn_peer_id)
+        .unwrap()
+        .restart()
+        .await
+        .unwrap();
+    wait_for_all_nodes(&mut swarm).await;
+}
+
 #[tokio::test]
 async fn test_full_node_bootstrap_outputs() {
     // Create a validator swarm of 1 validator node
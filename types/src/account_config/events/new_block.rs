@@ -13,6 +13,17 @@ pub struct NewBlockEvent {
     proposer: AccountAddress,
     previous_block_votes: Vec<AccountAddress>,
     time_micro_seconds: u64,
+    // Last, so that the events emitted before it was added decode with no failed proposers
+    failed_proposers: Vec<AccountAddress>,
+}
+
+/// The layout of the NewBlockEvents emitted before they had failed proposers
+#[derive(Deserialize)]
+struct LegacyNewBlockEvent {
+    round: u64,
+    proposer: AccountAddress,
+    previous_block_votes: Vec<AccountAddress>,
+    time_micro_seconds: u64,
 }
 
 impl NewBlockEvent {
@@ -24,12 +35,25 @@ impl NewBlockEvent {
         self.proposer
     }
 
+    pub fn failed_proposers(&self) -> &[AccountAddress] {
+        &self.failed_proposers
+    }
+
     pub fn proposed_time(&self) -> u64 {
         self.time_micro_seconds
     }
 
     pub fn try_from_bytes(bytes: &[u8]) -> Result<Self> {
-        bcs::from_bytes(bytes).map_err(Into::into)
+        bcs::from_bytes(bytes).or_else(|_| {
+            let event: LegacyNewBlockEvent = bcs::from_bytes(bytes)?;
+            Ok(Self {
+                round: event.round,
+                proposer: event.proposer,
+                previous_block_votes: event.previous_block_votes,
+                time_micro_seconds: event.time_micro_seconds,
+                failed_proposers: vec![],
+            })
+        })
     }
 
     #[cfg(any(test, feature = "fuzzing"))]
@@ -37,6 +61,7 @@ impl NewBlockEvent {
         round: u64,
         proposer: AccountAddress,
         previous_block_votes: Vec<AccountAddress>,
+        failed_proposers: Vec<AccountAddress>,
         time_micro_seconds: u64,
     ) -> Self {
         Self {
@@ -44,6 +69,7 @@ impl NewBlockEvent {
             proposer,
             previous_block_votes,
             time_micro_seconds,
+            failed_proposers,
         }
     }
 }
@@ -83,6 +83,39 @@ impl BlockMetadata {
     }
 }
 
+/// A BlockMetadata along with the proposers of the rounds after the parent block that failed.
+///
+/// It's a separate transaction type so that the BlockMetadata transactions keep their layout:
+/// consensus only proposes the blocks leading to it once the on-chain version is at least
+/// APTOS_VERSION_5, and the VM then runs the `block_prologue_ext` of the framework for it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockMetadataExt {
+    block_metadata: BlockMetadata,
+    // In the order of the rounds
+    failed_proposers: Vec<AccountAddress>,
+}
+
+impl BlockMetadataExt {
+    pub fn new(block_metadata: BlockMetadata, failed_proposers: Vec<AccountAddress>) -> Self {
+        Self {
+            block_metadata,
+            failed_proposers,
+        }
+    }
+
+    pub fn block_metadata(&self) -> &BlockMetadata {
+        &self.block_metadata
+    }
+
+    pub fn failed_proposers(&self) -> &Vec<AccountAddress> {
+        &self.failed_proposers
+    }
+
+    pub fn into_inner(self) -> (BlockMetadata, Vec<AccountAddress>) {
+        (self.block_metadata, self.failed_proposers)
+    }
+}
+
 pub fn new_block_event_key() -> EventKey {
     EventKey::new_from_address(&aptos_root_address(), 5)
 }
@@ -124,6 +157,17 @@ pub struct NewBlockEvent {
     proposer: AccountAddress,
     votes: Vec<AccountAddress>,
     timestamp: u64,
+    // Last, so that the events emitted before it was added decode with no failed proposers
+    failed_proposers: Vec<AccountAddress>,
+}
+
+/// The layout of the NewBlockEvents emitted before they had failed proposers
+#[derive(Deserialize)]
+struct LegacyNewBlockEvent {
+    round: u64,
+    proposer: AccountAddress,
+    votes: Vec<AccountAddress>,
+    timestamp: u64,
 }
 
 impl NewBlockEvent {
@@ -131,6 +175,7 @@ impl NewBlockEvent {
         round: u64,
         proposer: AccountAddress,
         votes: Vec<AccountAddress>,
+        failed_proposers: Vec<AccountAddress>,
         timestamp: u64,
     ) -> Self {
         Self {
@@ -138,8 +183,24 @@ impl NewBlockEvent {
             proposer,
             votes,
             timestamp,
+            failed_proposers,
         }
     }
+
+    /// Decodes the event data of a NewBlockEvent, in either layout
+    pub fn try_from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        bcs::from_bytes(bytes).or_else(|_| {
+            let event: LegacyNewBlockEvent = bcs::from_bytes(bytes)?;
+            Ok(Self {
+                round: event.round,
+                proposer: event.proposer,
+                votes: event.votes,
+                timestamp: event.timestamp,
+                failed_proposers: vec![],
+            })
+        })
+    }
+
     pub fn round(&self) -> u64 {
         self.round
     }
@@ -151,4 +212,8 @@ impl NewBlockEvent {
     pub fn votes(&self) -> Vec<AccountAddress> {
         self.votes.clone()
     }
+
+    pub fn failed_proposers(&self) -> &[AccountAddress] {
+        &self.failed_proposers
+    }
 }
@@ -0,0 +1,21 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+/// The downloads of all the data streams of state sync since the node started
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DownloadStats {
+    /// The throughput (bytes per second) measured over the last window
+    pub throughput_bytes_per_sec: u64,
+    /// The current download rate limit, or `None` if unlimited
+    pub rate_limit_bytes_per_sec: Option<u64>,
+    /// The number of bytes downloaded from the network
+    pub downloaded_bytes: u64,
+    /// The number of times new data requests were held back
+    pub throttled_data_requests: u64,
+}
+
+/// Reports the downloads of state sync, e.g. to the admin API of the node.
+pub trait DownloadStatsReporter: Send + Sync {
+    /// Returns the downloads since the node started
+    fn download_stats(&self) -> DownloadStats;
+}
@@ -12,10 +12,12 @@ pub mod block_info;
 pub mod block_metadata;
 pub mod chain_id;
 pub mod contract_event;
+pub mod download_stats;
 pub mod epoch_change;
 pub mod epoch_state;
 pub mod event;
 pub mod ledger_info;
+pub mod liveness;
 pub mod mempool_status;
 pub mod move_resource;
 pub mod network_address;
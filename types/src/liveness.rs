@@ -0,0 +1,26 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::account_address::AccountAddress;
+use std::collections::BTreeMap;
+
+/// The liveness of consensus at some point in time
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LivenessSnapshot {
+    /// The current round, 0 till consensus has started its first round
+    pub current_round: u64,
+    /// The round of the last committed block
+    pub last_committed_round: u64,
+    /// The count of the validators whose votes for the current round are pending a QC or a TC
+    pub pending_votes: usize,
+    /// The count of the timeout votes received from each validator since the node started
+    pub timeout_votes: BTreeMap<AccountAddress, u64>,
+    /// The count of the ordered blocks waiting to be executed, signed or committed
+    pub pending_blocks: usize,
+}
+
+/// Reports the liveness of consensus, e.g. to the admin API of the node.
+pub trait LivenessReporter: Send + Sync {
+    /// Returns the current liveness of consensus
+    fn liveness_snapshot(&self) -> LivenessSnapshot;
+}
@@ -56,13 +56,19 @@ pub enum MempoolStatusCode {
     InvalidSeqNumber = 1,
     // Mempool is full (reached max global capacity)
     MempoolIsFull = 2,
-    // Account reached max capacity per account
+    // Account reached max number of transactions per account
     TooManyTransactions = 3,
     // Invalid update. Only gas price increase is allowed
     InvalidUpdate = 4,
     // transaction didn't pass vm_validation
     VmError = 5,
     UnknownStatus = 6,
+    // Gas unit price is below the min gas unit price of mempool
+    GasPriceTooLow = 7,
+    // Transaction was denied by a transaction filter of mempool
+    Filtered = 8,
+    // Account reached max total size of transactions per account
+    TooManyBytes = 9,
 }
 
 impl TryFrom<u64> for MempoolStatusCode {
@@ -77,6 +83,9 @@ impl TryFrom<u64> for MempoolStatusCode {
             4 => Ok(MempoolStatusCode::InvalidUpdate),
             5 => Ok(MempoolStatusCode::VmError),
             6 => Ok(MempoolStatusCode::UnknownStatus),
+            7 => Ok(MempoolStatusCode::GasPriceTooLow),
+            8 => Ok(MempoolStatusCode::Filtered),
+            9 => Ok(MempoolStatusCode::TooManyBytes),
             _ => Err("invalid StatusCode"),
         }
     }
@@ -29,5 +29,11 @@ pub const APTOS_VERSION_3: Version = Version { major: 3 };
 //  - Conflict-Resistant Sequence Numbers
 pub const APTOS_VERSION_4: Version = Version { major: 4 };
 
+// NOTE: version number for the release reporting the failed proposers of the blocks
+// Items gated by this version number include:
+//  - the ProposalExt blocks and the BlockMetadataExt transaction
+//  - the Block::block_prologue_ext function, run for all the blocks, seeding the randomness
+pub const APTOS_VERSION_5: Version = Version { major: 5 };
+
 // Maximum current known version
-pub const APTOS_MAX_KNOWN_VERSION: Version = APTOS_VERSION_4;
+pub const APTOS_MAX_KNOWN_VERSION: Version = APTOS_VERSION_5;
@@ -28,6 +28,7 @@ mod vm_publishing_option;
 pub use self::{
     aptos_version::{
         Version, APTOS_MAX_KNOWN_VERSION, APTOS_VERSION_2, APTOS_VERSION_3, APTOS_VERSION_4,
+        APTOS_VERSION_5,
     },
     consensus_config::{ConsensusConfigV1, ConsensusConfigV2, OnChainConsensusConfig},
     parallel_execution_config::{ParallelExecutionConfig, ReadWriteSetAnalysis},
@@ -10,7 +10,7 @@ use crate::{
     },
     account_state_blob::AccountStateBlob,
     block_info::{BlockInfo, Round},
-    block_metadata::BlockMetadata,
+    block_metadata::{BlockMetadata, BlockMetadataExt},
     chain_id::ChainId,
     contract_event::ContractEvent,
     epoch_state::EpochState,
@@ -383,6 +383,16 @@ fn new_raw_transaction(
             expiration_time_secs,
             chain_id,
         ),
+        payload @ TransactionPayload::ScriptFunctionBatch(_) => RawTransaction::new(
+            sender,
+            sequence_number,
+            payload,
+            max_gas_amount,
+            gas_unit_price,
+            gas_currency_code,
+            expiration_time_secs,
+            chain_id,
+        ),
         TransactionPayload::WriteSet(WriteSetPayload::Direct(write_set)) => {
             // It's a bit unfortunate that max_gas_amount etc is generated but
             // not used, but it isn't a huge deal.
@@ -1008,6 +1018,19 @@ impl Arbitrary for BlockMetadata {
     type Strategy = BoxedStrategy<Self>;
 }
 
+impl Arbitrary for BlockMetadataExt {
+    type Parameters = ();
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (any::<BlockMetadata>(), vec(any::<AccountAddress>(), 0..3))
+            .prop_map(|(block_metadata, failed_proposers)| {
+                BlockMetadataExt::new(block_metadata, failed_proposers)
+            })
+            .boxed()
+    }
+
+    type Strategy = BoxedStrategy<Self>;
+}
+
 #[derive(Debug)]
 struct ValidatorSetGen {
     validators: Vec<Index>,
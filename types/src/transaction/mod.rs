@@ -4,7 +4,7 @@
 use crate::{
     account_address::AccountAddress,
     account_config::XUS_NAME,
-    block_metadata::BlockMetadata,
+    block_metadata::{BlockMetadata, BlockMetadataExt},
     chain_id::ChainId,
     contract_event::ContractEvent,
     ledger_info::LedgerInfo,
@@ -46,8 +46,8 @@ mod transaction_argument;
 pub use change_set::ChangeSet;
 pub use module::{Module, ModuleBundle};
 pub use script::{
-    ArgumentABI, Script, ScriptABI, ScriptFunction, ScriptFunctionABI, TransactionScriptABI,
-    TypeArgumentABI,
+    ArgumentABI, BatchedCall, BatchedCallArgument, Script, ScriptABI, ScriptFunction,
+    ScriptFunctionABI, ScriptFunctionBatch, TransactionScriptABI, TypeArgumentABI,
 };
 
 use crate::state_store::{state_key::StateKey, state_value::StateValue};
@@ -369,6 +369,15 @@ impl RawTransaction {
                 format!("{}::{}", script_fn.module(), script_fn.function()),
                 script_fn.args().to_vec(),
             ),
+            TransactionPayload::ScriptFunctionBatch(batch) => (
+                batch
+                    .calls()
+                    .iter()
+                    .map(|call| format!("{}::{}", call.module(), call.function()))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                vec![],
+            ),
             TransactionPayload::ModuleBundle(_) => ("module publishing".to_string(), vec![]),
         };
         let mut f_args: String = "".to_string();
@@ -445,13 +454,19 @@ pub enum TransactionPayload {
     ModuleBundle(ModuleBundle),
     /// A transaction that executes an existing script function published on-chain.
     ScriptFunction(ScriptFunction),
+    /// A transaction that executes a sequence of existing functions published on-chain
+    /// atomically.
+    ScriptFunctionBatch(ScriptFunctionBatch),
 }
 
 impl TransactionPayload {
     pub fn should_trigger_reconfiguration_by_default(&self) -> bool {
         match self {
             Self::WriteSet(ws) => ws.should_trigger_reconfiguration_by_default(),
-            Self::Script(_) | Self::ScriptFunction(_) | Self::ModuleBundle(_) => false,
+            Self::Script(_)
+            | Self::ScriptFunction(_)
+            | Self::ScriptFunctionBatch(_)
+            | Self::ModuleBundle(_) => false,
         }
     }
 
@@ -639,6 +654,13 @@ impl SignedTransaction {
             .len()
     }
 
+    /// The size of the transaction along with its authenticator, as it's sent in the blocks
+    pub fn txn_bytes_len(&self) -> usize {
+        bcs::to_bytes(&self)
+            .expect("Unable to serialize SignedTransaction")
+            .len()
+    }
+
     /// Checks that the signature of given transaction. Returns `Ok(SignatureCheckedTransaction)` if
     /// the signature is valid.
     pub fn check_signature(self) -> Result<SignatureCheckedTransaction> {
@@ -1455,6 +1477,10 @@ pub enum Transaction {
     /// Transaction to let the executor update the global state tree and record the root hash
     /// in the TransactionInfo
     StateCheckpoint,
+
+    /// Transaction to update the block metadata resource at the beginning of a block, that also
+    /// reports the proposers of the failed rounds since the parent block.
+    BlockMetadataExt(BlockMetadataExt),
 }
 
 impl Transaction {
@@ -1476,6 +1502,8 @@ impl Transaction {
             Transaction::BlockMetadata(_block_metadata) => String::from("block_metadata"),
             // TODO: display proper information for client
             Transaction::StateCheckpoint => String::from("state_checkpoint"),
+            // TODO: display proper information for client
+            Transaction::BlockMetadataExt(_block_metadata) => String::from("block_metadata"),
         }
     }
 }
@@ -103,3 +103,80 @@ impl ScriptFunction {
         (self.module, self.function, self.ty_args, self.args)
     }
 }
+
+/// Call a sequence of Move script and public functions atomically: either all the calls are
+/// executed, or the transaction aborts without the changes made by any of them. The arguments of
+/// a call may be the return values of the calls before it.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ScriptFunctionBatch {
+    calls: Vec<BatchedCall>,
+}
+
+impl ScriptFunctionBatch {
+    pub fn new(calls: Vec<BatchedCall>) -> Self {
+        ScriptFunctionBatch { calls }
+    }
+
+    pub fn calls(&self) -> &[BatchedCall] {
+        &self.calls
+    }
+
+    pub fn into_inner(self) -> Vec<BatchedCall> {
+        self.calls
+    }
+}
+
+/// A call of a `ScriptFunctionBatch`, the signers are passed before `args` like for a
+/// `ScriptFunction`.
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BatchedCall {
+    module: ModuleId,
+    function: Identifier,
+    ty_args: Vec<TypeTag>,
+    args: Vec<BatchedCallArgument>,
+}
+
+impl BatchedCall {
+    pub fn new(
+        module: ModuleId,
+        function: Identifier,
+        ty_args: Vec<TypeTag>,
+        args: Vec<BatchedCallArgument>,
+    ) -> Self {
+        BatchedCall {
+            module,
+            function,
+            ty_args,
+            args,
+        }
+    }
+
+    pub fn module(&self) -> &ModuleId {
+        &self.module
+    }
+
+    pub fn function(&self) -> &IdentStr {
+        &self.function
+    }
+
+    pub fn ty_args(&self) -> &[TypeTag] {
+        &self.ty_args
+    }
+
+    pub fn args(&self) -> &[BatchedCallArgument] {
+        &self.args
+    }
+
+    pub fn into_inner(self) -> (ModuleId, Identifier, Vec<TypeTag>, Vec<BatchedCallArgument>) {
+        (self.module, self.function, self.ty_args, self.args)
+    }
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BatchedCallArgument {
+    /// A BCS serialized value
+    Value(#[serde(with = "serde_bytes")] Vec<u8>),
+    /// The return value at index `output` of the call at index `call` of the batch, which must
+    /// be before the call it's passed to.
+    Output { call: u16, output: u16 },
+}
@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::block_metadata::BlockMetadata;
+use crate::{
+    account_address::AccountAddress,
+    block_metadata::{BlockMetadata, BlockMetadataExt, NewBlockEvent},
+};
 use bcs::test_helpers::assert_canonical_encode_decode;
 use proptest::prelude::*;
 
@@ -12,4 +15,35 @@ proptest! {
     fn test_block_metadata_canonical_serialization(data in any::<BlockMetadata>()) {
         assert_canonical_encode_decode(data);
     }
+
+    #[test]
+    fn test_block_metadata_ext_canonical_serialization(data in any::<BlockMetadataExt>()) {
+        assert_canonical_encode_decode(data);
+    }
+}
+
+#[test]
+fn test_new_block_event_legacy_layout() {
+    let proposer = AccountAddress::random();
+    let votes = vec![AccountAddress::random(), AccountAddress::random()];
+
+    // The events emitted before the failed proposers were added have no failed proposers.
+    let legacy_bytes = bcs::to_bytes(&(3u64, proposer, votes.clone(), 42u64)).unwrap();
+    let event = NewBlockEvent::try_from_bytes(&legacy_bytes).unwrap();
+    assert_eq!(event.round(), 3);
+    assert_eq!(event.proposer(), proposer);
+    assert_eq!(event.votes(), votes);
+    assert!(event.failed_proposers().is_empty());
+
+    let failed_proposers = vec![AccountAddress::random()];
+    let bytes = bcs::to_bytes(&NewBlockEvent::new(
+        3,
+        proposer,
+        votes,
+        failed_proposers.clone(),
+        42,
+    ))
+    .unwrap();
+    let event = NewBlockEvent::try_from_bytes(&bytes).unwrap();
+    assert_eq!(event.failed_proposers(), failed_proposers.as_slice());
 }